@@ -25,6 +25,7 @@ use crate::{
     helper::CliHelper,
     object_storage::get_object_store,
     print_options::{MaxRows, PrintOptions},
+    progress::ProgressBar,
 };
 use datafusion::common::instant::Instant;
 use datafusion::common::{plan_datafusion_err, plan_err};
@@ -285,6 +286,9 @@ impl StatementExecutor {
         } else {
             // Bounded stream; collected results size is limited by the maxrows option
             let schema = physical_plan.schema();
+            let progress = print_options
+                .progress
+                .then(|| ProgressBar::start(&physical_plan));
             let mut stream = execute_stream(physical_plan, task_ctx.clone())?;
             let mut results = vec![];
             let mut row_count = 0_usize;
@@ -304,6 +308,7 @@ impl StatementExecutor {
                 }
                 row_count += curr_num_rows;
             }
+            drop(progress);
             adjusted.into_inner().print_batches(
                 schema,
                 &results,
@@ -138,6 +138,9 @@ struct Args {
     #[clap(long, help = "Enables console syntax highlighting")]
     color: bool,
 
+    #[clap(long, help = "Display a live progress bar on stderr while queries run")]
+    progress: bool,
+
     #[clap(
         short = 'd',
         long,
@@ -233,6 +236,7 @@ async fn main_inner() -> Result<()> {
         quiet: args.quiet,
         maxrows: args.maxrows,
         color: args.color,
+        progress: args.progress,
     };
 
     let commands = args.command;
@@ -73,6 +73,8 @@ pub struct PrintOptions {
     pub quiet: bool,
     pub maxrows: MaxRows,
     pub color: bool,
+    /// Display a live progress bar on stderr while queries run
+    pub progress: bool,
 }
 
 // Returns the query execution details formatted
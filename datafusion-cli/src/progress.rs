@@ -0,0 +1,128 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Live progress bar for running queries
+
+use std::io::{IsTerminal, Write};
+use std::sync::Arc;
+use std::time::Duration;
+
+use datafusion::common::runtime::SpawnedTask;
+use datafusion::physical_plan::progress::{ProgressTracker, QueryProgress};
+use datafusion::physical_plan::ExecutionPlan;
+
+/// How often the progress bar is redrawn. Queries that complete faster never
+/// show a progress bar.
+const REFRESH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Width of the bar, in characters
+const BAR_WIDTH: usize = 30;
+
+/// Renders the progress of a running query on stderr until it is dropped
+pub struct ProgressBar {
+    task: Option<SpawnedTask<()>>,
+}
+
+impl ProgressBar {
+    /// Starts rendering the progress of `plan`, unless stderr is not a terminal
+    pub fn start(plan: &Arc<dyn ExecutionPlan>) -> Self {
+        if !std::io::stderr().is_terminal() {
+            return Self { task: None };
+        }
+
+        let tracker = ProgressTracker::new(Arc::clone(plan));
+        let task = SpawnedTask::spawn(async move {
+            let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+            // The first tick completes immediately
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                let mut stderr = std::io::stderr().lock();
+                let _ = write!(stderr, "\r{}", render(&tracker.snapshot()));
+                let _ = stderr.flush();
+            }
+        });
+        Self { task: Some(task) }
+    }
+}
+
+impl Drop for ProgressBar {
+    fn drop(&mut self) {
+        if let Some(task) = self.task.take() {
+            // Stop redrawing, then clear the line
+            drop(task);
+            let mut stderr = std::io::stderr().lock();
+            let _ = write!(stderr, "\r\x1b[2K");
+            let _ = stderr.flush();
+        }
+    }
+}
+
+/// Formats a single line progress bar, for example
+///
+/// ```text
+/// [=============>                ]  45.2% | 1234567 rows read (2469134 rows/s) | 0.5s
+/// ```
+fn render(progress: &QueryProgress) -> String {
+    let (rows, rows_per_second) = progress
+        .operators()
+        .iter()
+        .filter(|operator| operator.is_leaf())
+        .fold((0, 0.0), |(rows, rate), operator| {
+            (
+                rows + operator.output_rows(),
+                rate + operator.rows_per_second(),
+            )
+        });
+
+    let bar = match progress.percent_complete() {
+        Some(percent) => {
+            let filled = ((percent / 100.0) * BAR_WIDTH as f64) as usize;
+            let filled = filled.min(BAR_WIDTH);
+            let head = if filled < BAR_WIDTH { ">" } else { "" };
+            let bar = format!("{}{head}", "=".repeat(filled));
+            format!("[{bar:<BAR_WIDTH$}] {percent:5.1}%")
+        }
+        None => format!("[{:^BAR_WIDTH$}]     ?", "?"),
+    };
+
+    format!(
+        "{bar} | {rows} rows read ({rows_per_second:.0} rows/s) | {:.1}s",
+        progress.elapsed().as_secs_f64()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use datafusion::arrow::datatypes::Schema;
+    use datafusion::physical_plan::placeholder_row::PlaceholderRowExec;
+
+    #[test]
+    fn render_progress_bar() {
+        let plan = Arc::new(PlaceholderRowExec::new(Arc::new(Schema::empty())));
+        let tracker = ProgressTracker::new(plan);
+        let line = render(&tracker.snapshot());
+        assert!(
+            line.starts_with(
+                "[>                             ]   0.0% | 0 rows read (0 rows/s) | "
+            ),
+            "{line}"
+        );
+    }
+}
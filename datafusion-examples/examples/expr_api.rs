@@ -302,6 +302,7 @@ fn boundary_analysis_and_selectivity_demo() -> Result<()> {
         min_value: Precision::Exact(ScalarValue::Int64(Some(1))),
         sum_value: Precision::Absent,
         distinct_count: Precision::Absent,
+        histogram: None,
     };
 
     // We can then build our expression boundaries from the column statistics
@@ -369,6 +370,7 @@ fn boundary_analysis_in_conjunctions_demo() -> Result<()> {
         min_value: Precision::Exact(ScalarValue::Int64(Some(14))),
         sum_value: Precision::Absent,
         distinct_count: Precision::Absent,
+        histogram: None,
     };
 
     let initial_boundaries =
@@ -0,0 +1,137 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! MaterializedCteTable implementation used for CTEs that are computed once
+//! for all their references
+
+use std::any::Any;
+use std::borrow::Cow;
+use std::sync::{Arc, Weak};
+
+use crate::Session;
+use crate::TableProvider;
+
+use arrow::datatypes::SchemaRef;
+use async_trait::async_trait;
+use datafusion_common::error::Result;
+use datafusion_expr::{Expr, LogicalPlan, TableProviderFilterPushDown, TableType};
+use datafusion_physical_plan::materialized_cte::{MaterializedCte, MaterializedCteExec};
+use datafusion_physical_plan::ExecutionPlan;
+use parking_lot::Mutex;
+
+/// A non-recursive common table expression (CTE) that is computed once and
+/// shared by all references to it in a query, instead of being inlined and
+/// computed once per reference.
+///
+/// Each scan of the table returns a [`MaterializedCteExec`]. All scans that
+/// are planned while the physical plan of a previous scan is alive read from
+/// the same [`MaterializedCte`], so the CTE is computed once per query.
+#[derive(Debug)]
+pub struct MaterializedCteTable {
+    /// The name of the CTE
+    name: String,
+    /// The logical plan of the CTE
+    plan: Arc<LogicalPlan>,
+    /// The schema of the CTE
+    table_schema: SchemaRef,
+    /// The results of the CTE, shared by the scans of the current query
+    cte: Mutex<Weak<MaterializedCte>>,
+}
+
+impl MaterializedCteTable {
+    /// Create a new table computing the CTE `name` with the logical plan
+    /// `plan`
+    pub fn new(name: &str, plan: Arc<LogicalPlan>) -> Self {
+        let table_schema = Arc::clone(plan.schema().inner());
+        Self {
+            name: name.to_owned(),
+            plan,
+            table_schema,
+            cte: Mutex::new(Weak::new()),
+        }
+    }
+
+    /// The user-provided name of the CTE
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The logical plan of the CTE
+    pub fn plan(&self) -> &Arc<LogicalPlan> {
+        &self.plan
+    }
+}
+
+#[async_trait]
+impl TableProvider for MaterializedCteTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    // The plan is not returned, as it would be inlined in place of the scan
+    fn get_logical_plan(&'_ self) -> Option<Cow<'_, LogicalPlan>> {
+        None
+    }
+
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.table_schema)
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Temporary
+    }
+
+    async fn scan(
+        &self,
+        state: &dyn Session,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let existing = self.cte.lock().upgrade();
+        let cte = match existing {
+            Some(cte) => cte,
+            None => {
+                let input = state.create_physical_plan(&self.plan).await?;
+                let cte = Arc::new(MaterializedCte::new(self.name.clone(), input));
+                // Another scan may have planned the CTE in the meantime
+                let mut shared = self.cte.lock();
+                match shared.upgrade() {
+                    Some(existing) => existing,
+                    None => {
+                        *shared = Arc::downgrade(&cte);
+                        cte
+                    }
+                }
+            }
+        };
+        Ok(Arc::new(MaterializedCteExec::try_new(
+            cte,
+            projection.cloned(),
+        )?))
+    }
+
+    fn supports_filters_pushdown(
+        &self,
+        filters: &[&Expr],
+    ) -> Result<Vec<TableProviderFilterPushDown>> {
+        Ok(vec![
+            TableProviderFilterPushDown::Unsupported;
+            filters.len()
+        ])
+    }
+}
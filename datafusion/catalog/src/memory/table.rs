@@ -19,25 +19,40 @@
 
 use std::any::Any;
 use std::collections::HashMap;
-use std::fmt::Debug;
+use std::fmt::{self, Debug};
 use std::sync::Arc;
 
 use crate::TableProvider;
 
-use arrow::datatypes::SchemaRef;
+use arrow::array::{Array, ArrayRef, AsArray, BooleanArray, BooleanBuilder, UInt64Array};
+use arrow::compute::kernels::zip::zip;
+use arrow::compute::{filter_record_batch, not, prep_null_mask_filter};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef, UInt8Type};
 use arrow::record_batch::RecordBatch;
 use datafusion_common::error::Result;
-use datafusion_common::{not_impl_err, plan_err, Constraints, DFSchema, SchemaExt};
+use datafusion_common::{
+    internal_datafusion_err, internal_err, not_impl_err, plan_err, Constraints, DFSchema,
+    SchemaExt,
+};
 use datafusion_common_runtime::JoinSet;
 use datafusion_datasource::memory::{MemSink, MemorySourceConfig};
-use datafusion_datasource::sink::DataSinkExec;
+use datafusion_datasource::sink::{DataSink, DataSinkExec};
 use datafusion_datasource::source::DataSourceExec;
-use datafusion_expr::dml::InsertOp;
+use datafusion_execution::TaskContext;
+use datafusion_expr::dml::{InsertOp, MergeRowAction, MERGE_ACTION_COLUMN};
+use datafusion_expr::utils::conjunction;
 use datafusion_expr::{Expr, SortExpr, TableType};
-use datafusion_physical_expr::{create_physical_sort_exprs, LexOrdering};
+use datafusion_physical_expr::{
+    create_physical_sort_exprs, EquivalenceProperties, LexOrdering, PhysicalExpr,
+};
+use datafusion_physical_plan::execution_plan::{
+    Boundedness, EmissionType, SchedulingType,
+};
 use datafusion_physical_plan::repartition::RepartitionExec;
+use datafusion_physical_plan::stream::RecordBatchStreamAdapter;
 use datafusion_physical_plan::{
-    common, ExecutionPlan, ExecutionPlanProperties, Partitioning,
+    common, DisplayAs, DisplayFormatType, ExecutionPlan, ExecutionPlanProperties,
+    Partitioning, PlanProperties, SendableRecordBatchStream,
 };
 use datafusion_session::Session;
 
@@ -200,6 +215,20 @@ impl MemTable {
         }
         MemTable::try_new(Arc::clone(&schema), data)
     }
+
+    /// Combines the filters of a `DELETE` or `UPDATE` statement into a single
+    /// predicate, or `None` if the statement affects every row.
+    fn create_dml_predicate(
+        &self,
+        state: &dyn Session,
+        filters: Vec<Expr>,
+    ) -> Result<Option<Arc<dyn PhysicalExpr>>> {
+        let Some(filter) = conjunction(filters) else {
+            return Ok(None);
+        };
+        let df_schema = DFSchema::try_from(Arc::clone(&self.schema))?;
+        state.create_physical_expr(filter, &df_schema).map(Some)
+    }
 }
 
 #[async_trait]
@@ -292,7 +321,395 @@ impl TableProvider for MemTable {
         Ok(Arc::new(DataSinkExec::new(input, Arc::new(sink), None)))
     }
 
+    /// Returns an ExecutionPlan that deletes the rows matching `filters` from this [`MemTable`].
+    ///
+    /// The rows are removed when the returned plan is executed, which reports
+    /// the number of deleted rows.
+    async fn delete_from(
+        &self,
+        state: &dyn Session,
+        filters: Vec<Expr>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let predicate = self.create_dml_predicate(state, filters)?;
+        Ok(Arc::new(MemoryDmlExec::new(
+            self.batches.clone(),
+            Arc::clone(&self.sort_order),
+            MemoryDmlOp::Delete,
+            predicate,
+        )))
+    }
+
+    /// Returns an ExecutionPlan that applies `assignments` to the rows matching `filters`
+    /// in this [`MemTable`].
+    ///
+    /// The rows are modified when the returned plan is executed, which reports
+    /// the number of updated rows.
+    async fn update(
+        &self,
+        state: &dyn Session,
+        assignments: Vec<(String, Expr)>,
+        filters: Vec<Expr>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let df_schema = DFSchema::try_from(Arc::clone(&self.schema))?;
+        let assignments = assignments
+            .into_iter()
+            .map(|(name, expr)| {
+                let index = self.schema.index_of(&name)?;
+                let expr = state.create_physical_expr(expr, &df_schema)?;
+                let data_type = expr.data_type(&self.schema)?;
+                let expected = self.schema.field(index).data_type();
+                if &data_type != expected {
+                    return plan_err!(
+                        "Cannot assign a value of type {data_type} to column {name} of type {expected}"
+                    );
+                }
+                Ok((index, expr))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let predicate = self.create_dml_predicate(state, filters)?;
+        Ok(Arc::new(MemoryDmlExec::new(
+            self.batches.clone(),
+            Arc::clone(&self.sort_order),
+            MemoryDmlOp::Update(assignments),
+            predicate,
+        )))
+    }
+
+    /// Returns an ExecutionPlan that replaces the contents of this [`MemTable`]
+    /// with the result of a `MERGE` statement.
+    ///
+    /// See [`TableProvider::merge_into`] for the expected schema of `input`.
+    async fn merge_into(
+        &self,
+        _state: &dyn Session,
+        input: Arc<dyn ExecutionPlan>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let input_schema = input.schema();
+        let num_columns = self.schema.fields().len();
+        let action_field = input_schema.fields().get(num_columns);
+        if input_schema.fields().len() != num_columns + 1
+            || action_field.map(|field| field.name().as_str())
+                != Some(MERGE_ACTION_COLUMN)
+        {
+            return plan_err!(
+                "Expected the input of MERGE to have the columns of the table followed by {MERGE_ACTION_COLUMN}"
+            );
+        }
+        self.schema().logically_equivalent_names_and_types(
+            &input_schema.project(&(0..num_columns).collect::<Vec<_>>())?,
+        )?;
+
+        let sink = MemMergeSink {
+            batches: self.batches.clone(),
+            sort_order: Arc::clone(&self.sort_order),
+            schema: input_schema,
+        };
+        Ok(Arc::new(DataSinkExec::new(input, Arc::new(sink), None)))
+    }
+
     fn get_column_default(&self, column: &str) -> Option<&Expr> {
         self.column_defaults.get(column)
     }
 }
+
+/// The modification applied by a [`MemoryDmlExec`]
+#[derive(Debug, Clone)]
+enum MemoryDmlOp {
+    /// Remove the matching rows
+    Delete,
+    /// Replace the columns at the given indices of the matching rows with the
+    /// result of evaluating the expressions against the original rows
+    Update(Vec<(usize, Arc<dyn PhysicalExpr>)>),
+}
+
+/// Execution plan that deletes or updates rows of a [`MemTable`] in place.
+///
+/// Produces a single row with the number of affected rows in a UInt64 column
+/// called "count".
+#[derive(Debug)]
+struct MemoryDmlExec {
+    /// The partitions of the table to modify
+    batches: Vec<PartitionData>,
+    /// The sort order of the table, reset once rows have been updated
+    sort_order: Arc<Mutex<Vec<Vec<SortExpr>>>>,
+    op: MemoryDmlOp,
+    /// Rows for which the predicate is not `true` are left unchanged. `None`
+    /// means that every row is affected.
+    predicate: Option<Arc<dyn PhysicalExpr>>,
+    cache: PlanProperties,
+}
+
+impl MemoryDmlExec {
+    fn new(
+        batches: Vec<PartitionData>,
+        sort_order: Arc<Mutex<Vec<Vec<SortExpr>>>>,
+        op: MemoryDmlOp,
+        predicate: Option<Arc<dyn PhysicalExpr>>,
+    ) -> Self {
+        let count_schema = Arc::new(Schema::new(vec![Field::new(
+            "count",
+            DataType::UInt64,
+            false,
+        )]));
+        let cache = PlanProperties::new(
+            EquivalenceProperties::new(count_schema),
+            Partitioning::UnknownPartitioning(1),
+            EmissionType::Final,
+            Boundedness::Bounded,
+        )
+        .with_scheduling_type(SchedulingType::Cooperative);
+        Self {
+            batches,
+            sort_order,
+            op,
+            predicate,
+            cache,
+        }
+    }
+
+    /// Applies the modification to a single batch, returning the new batch
+    /// (if any rows remain) and the number of affected rows
+    fn apply(
+        op: &MemoryDmlOp,
+        predicate: Option<&Arc<dyn PhysicalExpr>>,
+        batch: RecordBatch,
+    ) -> Result<(Option<RecordBatch>, usize)> {
+        let selection = match predicate {
+            Some(predicate) => {
+                let selection =
+                    predicate.evaluate(&batch)?.into_array(batch.num_rows())?;
+                let Some(selection) = selection.as_any().downcast_ref::<BooleanArray>()
+                else {
+                    return internal_err!(
+                        "Cannot create filter_array from non-boolean predicates"
+                    );
+                };
+                // Rows for which the predicate is NULL are not affected
+                if selection.null_count() > 0 {
+                    prep_null_mask_filter(selection)
+                } else {
+                    selection.clone()
+                }
+            }
+            None => BooleanArray::from(vec![true; batch.num_rows()]),
+        };
+        let affected = selection.true_count();
+        if affected == 0 {
+            return Ok((Some(batch), 0));
+        }
+
+        match op {
+            MemoryDmlOp::Delete => {
+                if affected == batch.num_rows() {
+                    return Ok((None, affected));
+                }
+                let remaining = filter_record_batch(&batch, &not(&selection)?)?;
+                Ok((Some(remaining), affected))
+            }
+            MemoryDmlOp::Update(assignments) => {
+                let mut columns = batch.columns().to_vec();
+                for (index, expr) in assignments {
+                    let new_values = expr
+                        .evaluate_selection(&batch, &selection)?
+                        .into_array(batch.num_rows())?;
+                    columns[*index] = zip(&selection, &new_values, batch.column(*index))?;
+                }
+                let updated = RecordBatch::try_new(batch.schema(), columns)?;
+                Ok((Some(updated), affected))
+            }
+        }
+    }
+}
+
+impl DisplayAs for MemoryDmlExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        let op = match self.op {
+            MemoryDmlOp::Delete => "Delete",
+            MemoryDmlOp::Update(_) => "Update",
+        };
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                let partition_count = self.batches.len();
+                write!(f, "MemoryDmlExec: op={op}, partitions={partition_count}")?;
+                if let Some(predicate) = &self.predicate {
+                    write!(f, ", predicate={predicate}")?;
+                }
+                Ok(())
+            }
+            DisplayFormatType::TreeRender => {
+                writeln!(f, "op={op}")?;
+                if let Some(predicate) = &self.predicate {
+                    writeln!(f, "predicate={predicate}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for MemoryDmlExec {
+    fn name(&self) -> &'static str {
+        "MemoryDmlExec"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        &self.cache
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if children.is_empty() {
+            Ok(self)
+        } else {
+            internal_err!("Children cannot be replaced in {self:?}")
+        }
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        _context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        if partition != 0 {
+            return internal_err!("MemoryDmlExec can only be called on partition 0!");
+        }
+        let batches = self.batches.clone();
+        let sort_order = Arc::clone(&self.sort_order);
+        let op = self.op.clone();
+        let predicate = self.predicate.clone();
+        let count_schema = self.schema();
+
+        let stream = futures::stream::once(async move {
+            let mut partitions = Vec::with_capacity(batches.len());
+            for partition in batches.iter() {
+                partitions.push(partition.write().await);
+            }
+
+            // Apply the modification to every batch before replacing the
+            // contents of any partition, so that an error leaves the table unchanged
+            let mut count = 0;
+            let mut new_partitions = Vec::with_capacity(partitions.len());
+            for partition in partitions.iter() {
+                let mut new_batches = Vec::with_capacity(partition.len());
+                for batch in partition.iter() {
+                    let (batch, affected) =
+                        Self::apply(&op, predicate.as_ref(), batch.clone())?;
+                    new_batches.extend(batch);
+                    count += affected;
+                }
+                new_partitions.push(new_batches);
+            }
+            for (partition, new_batches) in partitions.iter_mut().zip(new_partitions) {
+                **partition = new_batches;
+            }
+            // Updated values may not respect the pre-known sort order anymore
+            if count > 0 && matches!(op, MemoryDmlOp::Update(_)) {
+                *sort_order.lock() = vec![];
+            }
+            let array = Arc::new(UInt64Array::from(vec![count as u64])) as ArrayRef;
+            Ok(RecordBatch::try_from_iter_with_nullable(vec![(
+                "count", array, false,
+            )])?)
+        });
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            count_schema,
+            stream,
+        )))
+    }
+}
+
+/// Replaces the contents of a [`MemTable`] with the result of a `MERGE`
+/// statement, see [`TableProvider::merge_into`]
+#[derive(Debug)]
+struct MemMergeSink {
+    /// The partitions of the table to replace
+    batches: Vec<PartitionData>,
+    /// The sort order of the table, reset once the rows have been replaced
+    sort_order: Arc<Mutex<Vec<Vec<SortExpr>>>>,
+    /// The schema of the table followed by the [`MERGE_ACTION_COLUMN`]
+    schema: SchemaRef,
+}
+
+impl DisplayAs for MemMergeSink {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                let partition_count = self.batches.len();
+                write!(f, "MemoryTable merge (partitions={partition_count})")
+            }
+            DisplayFormatType::TreeRender => {
+                writeln!(f, "partitions={}", self.batches.len())
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl DataSink for MemMergeSink {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    async fn write_all(
+        &self,
+        mut data: SendableRecordBatchStream,
+        _context: &Arc<TaskContext>,
+    ) -> Result<u64> {
+        let num_partitions = self.batches.len();
+        let action_index = self.schema.fields().len() - 1;
+        let table_columns = (0..action_index).collect::<Vec<_>>();
+
+        // buffer up the rows that are not deleted round robin style into num_partitions
+        let mut new_batches = vec![vec![]; num_partitions];
+        let mut i = 0;
+        let mut count = 0;
+        while let Some(batch) = data.next().await.transpose()? {
+            let actions = batch.column(action_index).as_primitive::<UInt8Type>();
+            let mut keep = BooleanBuilder::with_capacity(batch.num_rows());
+            for action in actions.iter() {
+                let action =
+                    action.and_then(MergeRowAction::from_u8).ok_or_else(|| {
+                        internal_datafusion_err!(
+                            "Invalid {MERGE_ACTION_COLUMN}: {action:?}"
+                        )
+                    })?;
+                if action.is_modification() {
+                    count += 1;
+                }
+                keep.append_value(action != MergeRowAction::Delete);
+            }
+            let batch = filter_record_batch(&batch, &keep.finish())?;
+            new_batches[i].push(batch.project(&table_columns)?);
+            i = (i + 1) % num_partitions;
+        }
+
+        // replace the contents of all the partitions at once
+        let mut partitions = Vec::with_capacity(num_partitions);
+        for partition in self.batches.iter() {
+            partitions.push(partition.write().await);
+        }
+        for (partition, batches) in partitions.iter_mut().zip(new_batches) {
+            **partition = batches;
+        }
+        // Updated and inserted rows may not respect the pre-known sort order
+        *self.sort_order.lock() = vec![];
+
+        Ok(count)
+    }
+}
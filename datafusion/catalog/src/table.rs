@@ -328,6 +328,77 @@ pub trait TableProvider: Debug + Sync + Send {
     ) -> Result<Arc<dyn ExecutionPlan>> {
         not_impl_err!("Insert into not implemented for this table")
     }
+
+    /// Return an [`ExecutionPlan`] that deletes rows from this table, if
+    /// supported.
+    ///
+    /// `filters` are the conjuncts of the `WHERE` clause of the `DELETE`
+    /// statement, with column references unqualified and resolved against
+    /// [`Self::schema`]. A row is deleted if all of the filters evaluate to
+    /// `true` for it. An empty list means that all rows are deleted.
+    ///
+    /// The returned plan should return a single row in a UInt64 column called
+    /// "count" with the number of deleted rows, in the same format as
+    /// [`Self::insert_into`]. The deletion should only take effect when the
+    /// returned plan is executed.
+    async fn delete_from(
+        &self,
+        _state: &dyn Session,
+        _filters: Vec<Expr>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        not_impl_err!("Delete not implemented for this table")
+    }
+
+    /// Return an [`ExecutionPlan`] that updates rows of this table, if
+    /// supported.
+    ///
+    /// `assignments` is a list of `(column name, new value)` pairs, one for
+    /// each column modified by the `SET` clause of the `UPDATE` statement.
+    /// The new values are already cast to the type of the target column and
+    /// are evaluated against the current values of the row being updated.
+    /// `filters` select the rows to update in the same way as for
+    /// [`Self::delete_from`].
+    ///
+    /// The returned plan should return a single row in a UInt64 column called
+    /// "count" with the number of updated rows, in the same format as
+    /// [`Self::insert_into`].
+    async fn update(
+        &self,
+        _state: &dyn Session,
+        _assignments: Vec<(String, Expr)>,
+        _filters: Vec<Expr>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        not_impl_err!("Update not implemented for this table")
+    }
+
+    /// Return an [`ExecutionPlan`] that applies a `MERGE` statement to this
+    /// table, if supported.
+    ///
+    /// `input` produces the columns of this table followed by a UInt8 column
+    /// called [`MERGE_ACTION_COLUMN`]. It contains one row for each existing
+    /// row of the table, with its values after the merge and a
+    /// [`MergeRowAction`] of `Keep`, `Update` or `Delete`, plus one row for
+    /// each inserted row, with an action of `Insert`. A table that is
+    /// rewritten as a whole can therefore be replaced with the rows of `input`
+    /// whose action is not `Delete`.
+    ///
+    /// Each existing row is returned exactly once, and the execution of
+    /// `input` fails if several rows of the source of the statement apply a
+    /// clause to the same target row.
+    ///
+    /// The returned plan should return a single row in a UInt64 column called
+    /// "count" with the number of inserted, updated and deleted rows, in the
+    /// same format as [`Self::insert_into`].
+    ///
+    /// [`MERGE_ACTION_COLUMN`]: datafusion_expr::dml::MERGE_ACTION_COLUMN
+    /// [`MergeRowAction`]: datafusion_expr::dml::MergeRowAction
+    async fn merge_into(
+        &self,
+        _state: &dyn Session,
+        _input: Arc<dyn ExecutionPlan>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        not_impl_err!("Merge not implemented for this table")
+    }
 }
 
 /// Arguments for scanning a table with [`TableProvider::scan_with_args`].
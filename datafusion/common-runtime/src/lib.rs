@@ -30,4 +30,7 @@ mod trace_utils;
 
 pub use common::SpawnedTask;
 pub use join_set::JoinSet;
-pub use trace_utils::{set_join_set_tracer, JoinSetTracer};
+pub use trace_utils::{
+    is_join_set_tracer_set, record_operator_span, set_join_set_tracer, JoinSetTracer,
+    OperatorSpan,
+};
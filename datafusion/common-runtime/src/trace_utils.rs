@@ -21,6 +21,7 @@ use std::any::Any;
 use std::error::Error;
 use std::fmt::{Display, Formatter, Result as FmtResult};
 use std::future::Future;
+use std::time::SystemTime;
 use tokio::sync::OnceCell;
 
 /// A trait for injecting instrumentation into either asynchronous futures or
@@ -45,6 +46,34 @@ pub trait JoinSetTracer: Send + Sync + 'static {
         &self,
         f: Box<dyn FnOnce() -> Box<dyn Any + Send> + Send>,
     ) -> Box<dyn FnOnce() -> Box<dyn Any + Send> + Send>;
+
+    /// Records the execution of one partition of an operator.
+    ///
+    /// Spans are recorded once the query completes, from the start and end
+    /// timestamps reported in the operator's metrics. Does nothing by default.
+    fn record_operator_span(&self, _span: &OperatorSpan) {}
+}
+
+/// The execution of one partition of an operator, passed to
+/// [`JoinSetTracer::record_operator_span`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperatorSpan {
+    /// Id of the session that ran the query
+    pub session_id: String,
+    /// Name of the operator, such as `FilterExec`
+    pub operator: String,
+    /// Position of the operator in a pre-order traversal of the plan
+    pub operator_id: usize,
+    /// `operator_id` of the parent operator, or `None` for the root
+    pub parent_operator_id: Option<usize>,
+    /// The partition that was executed
+    pub partition: usize,
+    /// When the partition started executing
+    pub start: SystemTime,
+    /// When the partition finished executing
+    pub end: SystemTime,
+    /// Other metrics of the partition, such as `output_rows`
+    pub attributes: Vec<(String, String)>,
 }
 
 /// A no-op tracer that does not modify or instrument any futures or closures.
@@ -115,6 +144,18 @@ pub fn set_join_set_tracer(
         .map_err(|_set_err| JoinSetTracerError::AlreadySet)
 }
 
+/// Returns true if a custom tracer has been registered with
+/// [`set_join_set_tracer`], so that callers can skip building
+/// [`OperatorSpan`]s that would be discarded.
+pub fn is_join_set_tracer_set() -> bool {
+    GLOBAL_TRACER.initialized()
+}
+
+/// Passes `span` to the registered tracer, if any
+pub fn record_operator_span(span: &OperatorSpan) {
+    get_tracer().record_operator_span(span)
+}
+
 /// Optionally instruments a future with custom tracing.
 ///
 /// If a tracer has been injected via `set_tracer`, the future's output is
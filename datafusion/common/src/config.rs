@@ -464,6 +464,14 @@ config_namespace! {
         /// Should DataFusion support recursive CTEs
         pub enable_recursive_ctes: bool, default = true
 
+        /// Should DataFusion compute a non-recursive CTE once and share its
+        /// results between its references, when it is referenced more than
+        /// once and computing it involves aggregations, joins, sorts or window
+        /// functions. CTEs declared `AS MATERIALIZED` are always materialized
+        /// and CTEs declared `AS NOT MATERIALIZED` are never materialized,
+        /// regardless of this setting
+        pub enable_cte_materialization: bool, default = true
+
         /// Attempt to eliminate sorts by packing & sorting files with non-overlapping
         /// statistics into the same file groups.
         /// Currently experimental
@@ -495,12 +503,49 @@ config_namespace! {
         /// tables with a highly-selective join filter, but is also slightly slower.
         pub enforce_batch_size_in_joins: bool, default = false
 
+        /// Should hash joins spill their build side to disk when it does not
+        /// fit in memory. When enabled, and a `DiskManager` is configured, both
+        /// inputs of a partitioned hash join are split by their join keys and
+        /// written to disk, then joined one partition at a time.
+        pub enable_hash_join_spill: bool, default = false
+
         /// Size (bytes) of data buffer DataFusion uses when writing output files.
         /// This affects the size of the data chunks that are uploaded to remote
         /// object stores (e.g. AWS S3). If very large (>= 100 GiB) output files are being
         /// written, it may be necessary to increase this size to avoid errors from
         /// the remote end point.
         pub objectstore_writer_buffer_size: usize, default = 10 * 1024 * 1024
+
+        /// Number of equi-depth histogram buckets that `ANALYZE TABLE` computes
+        /// for each column. More buckets give better selectivity estimates for
+        /// skewed data, at the cost of planning time.
+        pub analyze_histogram_buckets: usize, default = 64
+
+        /// Number of most common values, with their frequencies, that
+        /// `ANALYZE TABLE` tracks for each column
+        pub analyze_most_common_values: usize, default = 16
+
+        /// Number of rows that each input partition of a range partitioning
+        /// `RepartitionExec` buffers and samples to determine the boundaries
+        /// between output partitions. Larger samples give more evenly sized
+        /// partitions, at the cost of memory and latency before the first output.
+        pub range_partitioning_sample_size: usize, default = 8192
+
+        /// Priority of the queries of this session when they are queued by
+        /// the admission controller of the `RuntimeEnv`. Queries with a higher
+        /// priority are admitted first.
+        pub query_priority: i64, default = 0
+
+        /// Estimated peak memory, in bytes, of the queries of this session,
+        /// used by the admission controller of the `RuntimeEnv` to decide how
+        /// many queries can run at the same time. If not set, the default
+        /// estimate of the admission controller is used.
+        pub query_memory_estimate: Option<usize>, default = None
+
+        /// Maximum wall-clock time, in milliseconds, that a query may run
+        /// before it is cancelled with a timeout error. The deadline starts
+        /// when the query begins executing. If not set, queries never time out.
+        pub query_timeout: Option<u64>, default = None
     }
 }
 
@@ -752,12 +797,31 @@ config_namespace! {
         /// any files that only have timestamps in the year 2024 can be skipped / pruned at various stages in the scan.
         pub enable_dynamic_filter_pushdown: bool, default = true
 
+        /// Maximum number of rows of the build side of a hash join for which the
+        /// dynamic filter pushed down to the probe side also contains `IN` lists
+        /// of the join keys, which can be used to prune row groups. Set to 0 to
+        /// disable `IN` lists.
+        pub hash_join_inlist_pushdown_max_rows: usize, default = 128
+
+        /// When set to true, the dynamic filter pushed down to the probe side of a
+        /// hash join with more build-side rows than `hash_join_inlist_pushdown_max_rows`
+        /// also contains a bloom filter of the join keys, which is more selective than
+        /// their bounds for high-cardinality keys.
+        pub enable_hash_join_bloom_filter_pushdown: bool, default = true
+
         /// When set to true, the optimizer will insert filters before a join between
         /// a nullable and non-nullable column to filter out nulls on the nullable side. This
         /// filter can add additional overhead when the file format does not fully support
         /// predicate push down.
         pub filter_null_join_keys: bool, default = false
 
+        /// When set to true, the optimizer will compute aggregations over inner joins
+        /// partially below the join, grouped by the join keys, when the aggregate
+        /// functions are decomposable and the join keys of the other input are unique
+        /// (or all aggregate functions are insensitive to duplicates, like `min` and
+        /// `max`). This reduces the number of rows that are joined.
+        pub enable_eager_aggregation: bool, default = true
+
         /// Should DataFusion repartition data using the aggregate keys to execute aggregates
         /// in parallel using the provided `target_partitions` level
         pub repartition_aggregations: bool, default = true
@@ -848,6 +912,22 @@ config_namespace! {
         /// will be collected into a single partition
         pub hash_join_single_partition_threshold_rows: usize, default = 1024 * 128
 
+        /// When set to true, joins without equijoin keys whose filter compares
+        /// the two inputs with `<`, `<=`, `>` or `>=` (e.g. band joins) are
+        /// executed with the sort-based IEJoinExec instead of NestedLoopJoinExec
+        pub enable_ie_join: bool, default = false
+
+        /// When set to true, the physical optimizer reorders trees of inner
+        /// joins using the statistics of their inputs (row counts, distinct
+        /// counts, min/max values), so that the estimated sizes of the
+        /// intermediate results are as small as possible
+        pub enable_join_reordering: bool, default = true
+
+        /// The maximum number of inputs of a tree of inner joins for which
+        /// all join orders are considered when reordering joins. Larger trees
+        /// are reordered greedily
+        pub join_reordering_dp_threshold: usize, default = 8
+
         /// The default filter selectivity used by Filter Statistics
         /// when an exact selectivity cannot be determined. Valid values are
         /// between 0 (no selectivity) and 100 (all rows are selected).
@@ -1399,6 +1479,7 @@ config_field!(bool, value => default_config_transform(value.to_lowercase().as_st
 config_field!(usize);
 config_field!(f64);
 config_field!(u64);
+config_field!(i64);
 
 impl ConfigField for u8 {
     fn visit<V: Visit>(&self, v: &mut V, key: &str, description: &'static str) {
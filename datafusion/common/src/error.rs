@@ -126,6 +126,12 @@ pub enum DataFusionError {
     /// This error is thrown when a consumer cannot acquire additional memory
     /// or other resources needed to execute the query from the Memory Manager.
     ResourcesExhausted(String),
+    /// Error when a query runs past its deadline.
+    ///
+    /// This error is returned by the streams of a query once the deadline of
+    /// its `TaskContext`, for example one derived from the
+    /// `datafusion.execution.query_timeout` setting, has passed.
+    Timeout(String),
     /// Errors originating from outside DataFusion's core codebase.
     ///
     /// For example, a custom S3Error from the crate datafusion-objectstore-s3
@@ -382,6 +388,7 @@ impl Error for DataFusionError {
             DataFusionError::Execution(_) => None,
             DataFusionError::ExecutionJoin(e) => Some(e.as_ref()),
             DataFusionError::ResourcesExhausted(_) => None,
+            DataFusionError::Timeout(_) => None,
             DataFusionError::External(e) => Some(e.as_ref()),
             DataFusionError::Context(_, e) => Some(e.as_ref()),
             DataFusionError::Substrait(_) => None,
@@ -518,6 +525,7 @@ impl DataFusionError {
             DataFusionError::ResourcesExhausted(_) => {
                 "Resources exhausted: "
             }
+            DataFusionError::Timeout(_) => "Query timed out: ",
             DataFusionError::External(_) => "External error: ",
             DataFusionError::Context(_, _) => "",
             DataFusionError::Substrait(_) => "Substrait error: ",
@@ -562,6 +570,7 @@ impl DataFusionError {
             DataFusionError::Execution(ref desc) => Cow::Owned(desc.to_string()),
             DataFusionError::ExecutionJoin(ref desc) => Cow::Owned(desc.to_string()),
             DataFusionError::ResourcesExhausted(ref desc) => Cow::Owned(desc.to_string()),
+            DataFusionError::Timeout(ref desc) => Cow::Owned(desc.to_string()),
             DataFusionError::External(ref desc) => Cow::Owned(desc.to_string()),
             #[cfg(feature = "object_store")]
             DataFusionError::ObjectStore(ref desc) => Cow::Owned(desc.to_string()),
@@ -832,6 +841,9 @@ make_error!(substrait_err, substrait_datafusion_err, Substrait);
 // Exposes a macro to create `DataFusionError::ResourcesExhausted` with optional backtrace
 make_error!(resources_err, resources_datafusion_err, ResourcesExhausted);
 
+// Exposes a macro to create `DataFusionError::Timeout` with optional backtrace
+make_error!(timeout_err, timeout_datafusion_err, Timeout);
+
 // Exposes a macro to create `DataFusionError::SQL` with optional backtrace
 #[macro_export]
 macro_rules! sql_datafusion_err {
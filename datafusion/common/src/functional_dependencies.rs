@@ -364,6 +364,12 @@ impl FunctionalDependencies {
                 // These joins preserve functional dependencies of the left side:
                 left_func_dependencies
             }
+            JoinType::AsOf => {
+                // Every left row appears exactly once, but a right row may be
+                // matched several times, so only the left side dependencies
+                // are preserved:
+                left_func_dependencies
+            }
             JoinType::RightSemi | JoinType::RightAnti | JoinType::RightMark => {
                 // These joins preserve functional dependencies of the right side:
                 right_func_dependencies
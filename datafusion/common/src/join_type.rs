@@ -72,6 +72,13 @@ pub enum JoinType {
     /// Same logic as the LeftMark Join above, however it returns a record for each record from the
     /// right input.
     RightMark,
+    /// As-of Join
+    ///
+    /// Returns one record for each record from the left input, joined with the single closest
+    /// record from the right input that has equal join keys and satisfies the inequality in the
+    /// join filter (e.g. the latest quote at or before the time of a trade). If there is no such
+    /// record, NULL values are returned for the columns from the right table.
+    AsOf,
 }
 
 impl JoinType {
@@ -81,9 +88,9 @@ impl JoinType {
 
     /// Returns the `JoinType` if the (2) inputs were swapped
     ///
-    /// Panics if [`Self::supports_swap`] returns false
-    pub fn swap(&self) -> JoinType {
-        match self {
+    /// Returns an error for [`JoinType::AsOf`], which has no mirrored join type
+    pub fn swap(&self) -> Result<JoinType> {
+        Ok(match self {
             JoinType::Inner => JoinType::Inner,
             JoinType::Full => JoinType::Full,
             JoinType::Left => JoinType::Right,
@@ -94,7 +101,10 @@ impl JoinType {
             JoinType::RightAnti => JoinType::LeftAnti,
             JoinType::LeftMark => JoinType::RightMark,
             JoinType::RightMark => JoinType::LeftMark,
-        }
+            JoinType::AsOf => {
+                return _not_impl_err!("AsOf join does not support swapping inputs")
+            }
+        })
     }
 
     /// Does the join type support swapping inputs?
@@ -126,6 +136,7 @@ impl Display for JoinType {
             JoinType::RightAnti => "RightAnti",
             JoinType::LeftMark => "LeftMark",
             JoinType::RightMark => "RightMark",
+            JoinType::AsOf => "AsOf",
         };
         write!(f, "{join_type}")
     }
@@ -147,6 +158,7 @@ impl FromStr for JoinType {
             "RIGHTANTI" => Ok(JoinType::RightAnti),
             "LEFTMARK" => Ok(JoinType::LeftMark),
             "RIGHTMARK" => Ok(JoinType::RightMark),
+            "ASOF" => Ok(JoinType::AsOf),
             _ => _not_impl_err!("The join type {s} does not exist or is not implemented"),
         }
     }
@@ -88,7 +88,7 @@ pub use param_value::ParamValues;
 pub use scalar::{ScalarType, ScalarValue};
 pub use schema_reference::SchemaReference;
 pub use spans::{Location, Span, Spans};
-pub use stats::{ColumnStatistics, Statistics};
+pub use stats::{ColumnHistogram, ColumnStatistics, HistogramBucket, Statistics};
 pub use table_reference::{ResolvedTableReference, TableReference};
 pub use unnest::{RecursionUnnestOption, UnnestOptions};
 pub use utils::project_schema;
@@ -18,6 +18,7 @@
 //! This module provides data structures to represent statistics
 
 use std::fmt::{self, Debug, Display};
+use std::sync::Arc;
 
 use crate::{Result, ScalarValue};
 
@@ -564,6 +565,7 @@ impl Statistics {
             col_stats.min_value = col_stats.min_value.min(&item_col_stats.min_value);
             col_stats.sum_value = col_stats.sum_value.add(&item_col_stats.sum_value);
             col_stats.distinct_count = Precision::Absent;
+            col_stats.histogram = None;
         }
 
         Ok(Statistics {
@@ -625,6 +627,11 @@ impl Display for Statistics {
                 } else {
                     s
                 };
+                let s = if let Some(histogram) = &cs.histogram {
+                    format!("{} Histogram={}", s, histogram.buckets.len())
+                } else {
+                    s
+                };
 
                 s + ")"
             })
@@ -654,6 +661,9 @@ pub struct ColumnStatistics {
     pub sum_value: Precision<ScalarValue>,
     /// Number of distinct values
     pub distinct_count: Precision<usize>,
+    /// Distribution of the values, if the column was analyzed with
+    /// `ANALYZE TABLE`
+    pub histogram: Option<Arc<ColumnHistogram>>,
 }
 
 impl ColumnStatistics {
@@ -676,6 +686,7 @@ impl ColumnStatistics {
             min_value: Precision::Absent,
             sum_value: Precision::Absent,
             distinct_count: Precision::Absent,
+            histogram: None,
         }
     }
 
@@ -709,6 +720,12 @@ impl ColumnStatistics {
         self
     }
 
+    /// Set the histogram
+    pub fn with_histogram(mut self, histogram: Option<Arc<ColumnHistogram>>) -> Self {
+        self.histogram = histogram;
+        self
+    }
+
     /// If the exactness of a [`ColumnStatistics`] instance is lost, this
     /// function relaxes the exactness of all information by converting them
     /// [`Precision::Inexact`].
@@ -722,6 +739,188 @@ impl ColumnStatistics {
     }
 }
 
+/// A bucket of a [`ColumnHistogram`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HistogramBucket {
+    /// Smallest value in the bucket
+    pub lower: ScalarValue,
+    /// Largest value in the bucket
+    pub upper: ScalarValue,
+    /// Number of non-null values in the bucket
+    pub num_values: usize,
+    /// Number of distinct values in the bucket
+    pub distinct_count: usize,
+}
+
+/// Distribution of the values of a column, computed by `ANALYZE TABLE`.
+///
+/// The non-null values are split into equi-depth buckets, which hold about
+/// the same number of values each, so that the bucket bounds are closer
+/// together where the values are dense. The most common values are tracked
+/// separately with their frequencies, since a bucket does not tell how its
+/// values are distributed.
+///
+/// All estimates are fractions of the [`Self::num_rows`] rows the histogram
+/// was computed from, so that they still apply after a filter or a limit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColumnHistogram {
+    /// Number of rows, including nulls
+    pub num_rows: usize,
+    /// Buckets of the non-null values, ordered by their bounds
+    pub buckets: Vec<HistogramBucket>,
+    /// The most common values with their number of rows, by decreasing
+    /// number of rows
+    pub most_common_values: Vec<(ScalarValue, usize)>,
+}
+
+impl ColumnHistogram {
+    /// Estimates the fraction of rows equal to `value`. Returns `None` if
+    /// `value` cannot be compared with the histogram bounds.
+    pub fn estimate_eq(&self, value: &ScalarValue) -> Option<f64> {
+        if value.is_null() || self.num_rows == 0 {
+            return Some(0.0);
+        }
+        if let Some((_, count)) = self.most_common_values.iter().find(|(v, _)| v == value)
+        {
+            return Some(*count as f64 / self.num_rows as f64);
+        }
+        for bucket in &self.buckets {
+            if value.partial_cmp(&bucket.lower)?.is_lt() {
+                return Some(0.0);
+            }
+            if value.partial_cmp(&bucket.upper)?.is_le() {
+                // Values that are not among the most common values are
+                // assumed to be uniformly distributed in their bucket
+                let (values, distinct) = self
+                    .most_common_values
+                    .iter()
+                    .filter(|(v, _)| v >= &bucket.lower && v <= &bucket.upper)
+                    .fold(
+                        (bucket.num_values, bucket.distinct_count),
+                        |(values, distinct), (_, count)| {
+                            (values.saturating_sub(*count), distinct.saturating_sub(1))
+                        },
+                    );
+                let rows = values as f64 / distinct.max(1) as f64;
+                return Some(rows / self.num_rows as f64);
+            }
+        }
+        Some(0.0)
+    }
+
+    /// Estimates the fraction of rows with values between `lower` and `upper`,
+    /// inclusive. A null bound means that the range is unbounded on that
+    /// side. Returns `None` if the bounds cannot be compared with the
+    /// histogram bounds.
+    pub fn estimate_range(
+        &self,
+        lower: &ScalarValue,
+        upper: &ScalarValue,
+    ) -> Option<f64> {
+        if !lower.is_null() && lower == upper {
+            return self.estimate_eq(lower);
+        }
+        if self.num_rows == 0 {
+            return Some(0.0);
+        }
+        let mut rows = 0.0;
+        for bucket in &self.buckets {
+            let fraction = bucket_overlap(bucket, lower, upper)?;
+            rows += bucket.num_values as f64 * fraction;
+        }
+        Some((rows / self.num_rows as f64).min(1.0))
+    }
+
+    /// Estimates the number of rows of an equijoin of the analyzed column
+    /// with the analyzed column of `other`, assuming that the values of
+    /// matching buckets are uniformly distributed. Returns `None` if the
+    /// histogram bounds cannot be compared.
+    pub fn estimate_join(&self, other: &ColumnHistogram) -> Option<f64> {
+        let mut rows = 0.0;
+        for left in &self.buckets {
+            for right in &other.buckets {
+                let lower = if left.lower.partial_cmp(&right.lower)?.is_ge() {
+                    &left.lower
+                } else {
+                    &right.lower
+                };
+                let upper = if left.upper.partial_cmp(&right.upper)?.is_le() {
+                    &left.upper
+                } else {
+                    &right.upper
+                };
+                if lower.partial_cmp(upper)?.is_gt() {
+                    continue;
+                }
+                let left_fraction = bucket_overlap(left, lower, upper)?;
+                let right_fraction = bucket_overlap(right, lower, upper)?;
+                let left_distinct = (left.distinct_count as f64 * left_fraction).max(1.0);
+                let right_distinct =
+                    (right.distinct_count as f64 * right_fraction).max(1.0);
+                rows += left.num_values as f64
+                    * left_fraction
+                    * right.num_values as f64
+                    * right_fraction
+                    / left_distinct.max(right_distinct);
+            }
+        }
+        Some(rows)
+    }
+}
+
+/// Returns the fraction of the values of `bucket` between `lower` and
+/// `upper`, where null bounds are unbounded. Values are assumed to be
+/// uniformly distributed in the bucket if they can be converted to numbers,
+/// and half of a partially overlapping bucket is assumed to match otherwise.
+fn bucket_overlap(
+    bucket: &HistogramBucket,
+    lower: &ScalarValue,
+    upper: &ScalarValue,
+) -> Option<f64> {
+    let lower = if lower.is_null() || lower.partial_cmp(&bucket.lower)?.is_lt() {
+        &bucket.lower
+    } else {
+        lower
+    };
+    let upper = if upper.is_null() || upper.partial_cmp(&bucket.upper)?.is_gt() {
+        &bucket.upper
+    } else {
+        upper
+    };
+    if lower.partial_cmp(upper)?.is_gt() {
+        return Some(0.0);
+    }
+    if lower == &bucket.lower && upper == &bucket.upper {
+        return Some(1.0);
+    }
+    let min_fraction = 1.0 / bucket.distinct_count.max(1) as f64;
+    let fraction = match (
+        scalar_to_f64(&bucket.lower),
+        scalar_to_f64(&bucket.upper),
+        scalar_to_f64(lower),
+        scalar_to_f64(upper),
+    ) {
+        (Some(bucket_lower), Some(bucket_upper), Some(lower), Some(upper))
+            if bucket_upper > bucket_lower =>
+        {
+            (upper - lower) / (bucket_upper - bucket_lower)
+        }
+        _ => 0.5,
+    };
+    Some(fraction.clamp(min_fraction, 1.0))
+}
+
+/// Converts a numeric or temporal value to a number, for interpolation
+fn scalar_to_f64(value: &ScalarValue) -> Option<f64> {
+    [DataType::Float64, DataType::Int64]
+        .iter()
+        .find_map(|data_type| match value.cast_to(data_type).ok()? {
+            ScalarValue::Float64(Some(value)) => Some(value),
+            ScalarValue::Int64(Some(value)) => Some(value as f64),
+            _ => None,
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -987,6 +1186,7 @@ mod tests {
             min_value: Precision::Exact(ScalarValue::Int64(Some(64))),
             sum_value: Precision::Exact(ScalarValue::Int64(Some(4600))),
             distinct_count: Precision::Exact(100),
+            histogram: None,
         }
     }
 
@@ -1009,6 +1209,7 @@ mod tests {
                     min_value: Precision::Exact(ScalarValue::Int32(Some(1))),
                     sum_value: Precision::Exact(ScalarValue::Int32(Some(500))),
                     distinct_count: Precision::Absent,
+                    histogram: None,
                 },
                 ColumnStatistics {
                     null_count: Precision::Exact(2),
@@ -1016,6 +1217,7 @@ mod tests {
                     min_value: Precision::Exact(ScalarValue::Int32(Some(10))),
                     sum_value: Precision::Exact(ScalarValue::Int32(Some(1000))),
                     distinct_count: Precision::Absent,
+                    histogram: None,
                 },
             ],
         };
@@ -1030,6 +1232,7 @@ mod tests {
                     min_value: Precision::Exact(ScalarValue::Int32(Some(-10))),
                     sum_value: Precision::Exact(ScalarValue::Int32(Some(600))),
                     distinct_count: Precision::Absent,
+                    histogram: None,
                 },
                 ColumnStatistics {
                     null_count: Precision::Exact(3),
@@ -1037,6 +1240,7 @@ mod tests {
                     min_value: Precision::Exact(ScalarValue::Int32(Some(5))),
                     sum_value: Precision::Exact(ScalarValue::Int32(Some(1200))),
                     distinct_count: Precision::Absent,
+                    histogram: None,
                 },
             ],
         };
@@ -1100,6 +1304,7 @@ mod tests {
                 min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
                 sum_value: Precision::Exact(ScalarValue::Int32(Some(500))),
                 distinct_count: Precision::Absent,
+                histogram: None,
             }],
         };
 
@@ -1112,6 +1317,7 @@ mod tests {
                 min_value: Precision::Exact(ScalarValue::Int32(Some(-10))),
                 sum_value: Precision::Absent,
                 distinct_count: Precision::Absent,
+                histogram: None,
             }],
         };
 
@@ -1238,6 +1444,7 @@ mod tests {
                     min_value: Precision::Exact(ScalarValue::Int32(Some(0))),
                     sum_value: Precision::Exact(ScalarValue::Int32(Some(5050))),
                     distinct_count: Precision::Exact(50),
+                    histogram: None,
                 },
                 ColumnStatistics {
                     null_count: Precision::Exact(20),
@@ -1245,6 +1452,7 @@ mod tests {
                     min_value: Precision::Exact(ScalarValue::Int64(Some(10))),
                     sum_value: Precision::Exact(ScalarValue::Int64(Some(10100))),
                     distinct_count: Precision::Exact(75),
+                    histogram: None,
                 },
             ],
         };
@@ -1319,6 +1527,7 @@ mod tests {
                 min_value: Precision::Inexact(ScalarValue::Int32(Some(0))),
                 sum_value: Precision::Inexact(ScalarValue::Int32(Some(5050))),
                 distinct_count: Precision::Inexact(50),
+                histogram: None,
             }],
         };
 
@@ -1419,6 +1628,7 @@ mod tests {
                 min_value: Precision::Absent,
                 sum_value: Precision::Absent,
                 distinct_count: Precision::Absent,
+                histogram: None,
             }],
         };
 
@@ -1457,6 +1667,7 @@ mod tests {
             min_value: Precision::Exact(ScalarValue::Int32(Some(-100))),
             sum_value: Precision::Exact(ScalarValue::Int32(Some(123456))),
             distinct_count: Precision::Exact(789),
+            histogram: None,
         };
 
         let original_stats = Statistics {
@@ -1485,4 +1696,76 @@ mod tests {
         );
         assert_eq!(result_col_stats.distinct_count, Precision::Inexact(789));
     }
+
+    fn test_histogram() -> ColumnHistogram {
+        let bucket = |lower, upper, num_values, distinct_count| HistogramBucket {
+            lower: ScalarValue::Int32(Some(lower)),
+            upper: ScalarValue::Int32(Some(upper)),
+            num_values,
+            distinct_count,
+        };
+        // 110 rows: 10 nulls, 50 values in 1..=10 and 50 values in 11..=20,
+        // 30 of which are 15
+        ColumnHistogram {
+            num_rows: 110,
+            buckets: vec![bucket(1, 10, 50, 10), bucket(11, 20, 50, 5)],
+            most_common_values: vec![(ScalarValue::Int32(Some(15)), 30)],
+        }
+    }
+
+    #[test]
+    fn test_histogram_estimate_eq() {
+        let histogram = test_histogram();
+        let estimate = |value| histogram.estimate_eq(&ScalarValue::Int32(value));
+        // Most common value
+        assert_eq!(estimate(Some(15)), Some(30.0 / 110.0));
+        // Uniformly distributed in its bucket
+        assert_eq!(estimate(Some(3)), Some(5.0 / 110.0));
+        // The most common value is excluded from its bucket
+        assert_eq!(estimate(Some(12)), Some(5.0 / 110.0));
+        // Out of range
+        assert_eq!(estimate(Some(0)), Some(0.0));
+        assert_eq!(estimate(Some(21)), Some(0.0));
+        assert_eq!(estimate(None), Some(0.0));
+    }
+
+    #[test]
+    fn test_histogram_estimate_range() {
+        let histogram = test_histogram();
+        let estimate = |lower, upper| {
+            histogram
+                .estimate_range(&ScalarValue::Int32(lower), &ScalarValue::Int32(upper))
+        };
+        assert_eq!(estimate(None, None), Some(100.0 / 110.0));
+        assert_eq!(estimate(Some(11), None), Some(50.0 / 110.0));
+        assert_eq!(estimate(None, Some(10)), Some(50.0 / 110.0));
+        assert_eq!(estimate(Some(15), Some(15)), Some(30.0 / 110.0));
+        assert_eq!(estimate(Some(30), None), Some(0.0));
+        assert!(estimate(Some(1), Some(5))
+            .is_some_and(|estimate| { estimate > 0.0 && estimate < 50.0 / 110.0 }));
+        // Bounds that cannot be compared with the histogram bounds
+        assert_eq!(
+            histogram.estimate_range(&ScalarValue::from("a"), &ScalarValue::Int32(None)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_histogram_estimate_join() {
+        let histogram = test_histogram();
+        // Matching buckets: 50 * 50 / 10 + 50 * 50 / 5
+        assert_eq!(histogram.estimate_join(&histogram), Some(750.0));
+
+        let disjoint = ColumnHistogram {
+            num_rows: 10,
+            buckets: vec![HistogramBucket {
+                lower: ScalarValue::Int32(Some(100)),
+                upper: ScalarValue::Int32(Some(200)),
+                num_values: 10,
+                distinct_count: 10,
+            }],
+            most_common_values: vec![],
+        };
+        assert_eq!(histogram.estimate_join(&disjoint), Some(0.0));
+    }
 }
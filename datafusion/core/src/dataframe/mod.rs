@@ -19,6 +19,7 @@
 
 #[cfg(feature = "parquet")]
 mod parquet;
+mod result_cache;
 
 use crate::arrow::record_batch::RecordBatch;
 use crate::arrow::util::pretty;
@@ -37,7 +38,7 @@ use crate::logical_expr::{
     LogicalPlanBuilderOptions, Partitioning, TableType,
 };
 use crate::physical_plan::{
-    collect, collect_partitioned, execute_stream, execute_stream_partitioned,
+    collect_partitioned, common, execute_stream, execute_stream_partitioned,
     ExecutionPlan, SendableRecordBatchStream,
 };
 use crate::prelude::SessionContext;
@@ -62,7 +63,7 @@ use datafusion_expr::{
     expr::{Alias, ScalarFunction},
     is_null, lit,
     utils::COUNT_STAR_EXPANSION,
-    ExplainOption, SortExpr, TableProviderFilterPushDown, UNNAMED_TABLE,
+    ExplainOption, SampleMethod, SortExpr, TableProviderFilterPushDown, UNNAMED_TABLE,
 };
 use datafusion_functions::core::coalesce;
 use datafusion_functions_aggregate::expr_fn::{
@@ -71,6 +72,8 @@ use datafusion_functions_aggregate::expr_fn::{
 
 use async_trait::async_trait;
 use datafusion_catalog::Session;
+use datafusion_execution::admission::{AdmissionPermit, AdmissionRequest};
+use result_cache::{invalidate_written_table, CacheableQuery};
 
 /// Contains options that control how data is
 /// written out from a DataFrame
@@ -619,6 +622,118 @@ impl DataFrame {
         })
     }
 
+    /// Return a new `DataFrame` that rotates the values of `pivot_column`
+    /// into columns, like `PIVOT` in SQL.
+    ///
+    /// Every value in `pivot_values` becomes a column that holds the
+    /// `aggr_expr` aggregates of the rows where `pivot_column` equals the
+    /// value, grouped by the columns that are neither the pivot column nor
+    /// referenced by the aggregates. See [`LogicalPlanBuilder::pivot`] for
+    /// how the columns are named.
+    ///
+    /// # Example
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # use datafusion::functions_aggregate::expr_fn::sum;
+    /// # use datafusion_common::assert_batches_sorted_eq;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let df = dataframe!(
+    ///     "region" => ["east", "east", "west", "west"],
+    ///     "quarter" => ["q1", "q2", "q1", "q1"],
+    ///     "amount" => [10, 20, 30, 40]
+    /// )?;
+    /// // The following use is the equivalent of
+    /// // "SELECT * FROM df PIVOT (sum(amount) FOR quarter IN ('q1', 'q2'))"
+    /// let df = df.pivot(
+    ///     vec![sum(col("amount"))],
+    ///     "quarter",
+    ///     vec![lit("q1"), lit("q2")],
+    ///     None,
+    /// )?;
+    /// let expected = vec![
+    ///     "+--------+----+----+",
+    ///     "| region | q1 | q2 |",
+    ///     "+--------+----+----+",
+    ///     "| east   | 10 | 20 |",
+    ///     "| west   | 70 |    |",
+    ///     "+--------+----+----+",
+    /// ];
+    /// assert_batches_sorted_eq!(expected, &df.collect().await?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pivot(
+        self,
+        aggr_expr: Vec<Expr>,
+        pivot_column: impl Into<Column>,
+        pivot_values: Vec<Expr>,
+        default_on_null: Option<Expr>,
+    ) -> Result<DataFrame> {
+        let plan = LogicalPlanBuilder::from(self.plan)
+            .pivot(aggr_expr, pivot_column, pivot_values, default_on_null)?
+            .build()?;
+        Ok(DataFrame {
+            session_state: self.session_state,
+            plan,
+            projection_requires_validation: true,
+        })
+    }
+
+    /// Return a new `DataFrame` that rotates `columns` into rows, like
+    /// `UNPIVOT` in SQL.
+    ///
+    /// Every row is turned into one row per column in `columns`, holding the
+    /// other columns, the name of the column in `name_column` and its value
+    /// in `value_column`. Rows with a null value are skipped unless
+    /// `include_nulls` is true.
+    ///
+    /// # Example
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # use datafusion_common::assert_batches_sorted_eq;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let df = dataframe!(
+    ///     "region" => ["east", "west"],
+    ///     "q1" => [Some(10), Some(70)],
+    ///     "q2" => [Some(20), None]
+    /// )?;
+    /// // The following use is the equivalent of
+    /// // "SELECT * FROM df UNPIVOT (amount FOR quarter IN (q1, q2))"
+    /// let df = df.unpivot("amount", "quarter", vec!["q1".into(), "q2".into()], false)?;
+    /// let expected = vec![
+    ///     "+--------+---------+--------+",
+    ///     "| region | quarter | amount |",
+    ///     "+--------+---------+--------+",
+    ///     "| east   | q1      | 10     |",
+    ///     "| east   | q2      | 20     |",
+    ///     "| west   | q1      | 70     |",
+    ///     "+--------+---------+--------+",
+    /// ];
+    /// assert_batches_sorted_eq!(expected, &df.collect().await?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn unpivot(
+        self,
+        value_column: &str,
+        name_column: &str,
+        columns: Vec<Column>,
+        include_nulls: bool,
+    ) -> Result<DataFrame> {
+        let plan = LogicalPlanBuilder::from(self.plan)
+            .unpivot(value_column, name_column, columns, include_nulls)?
+            .build()?;
+        Ok(DataFrame {
+            session_state: self.session_state,
+            plan,
+            projection_requires_validation: true,
+        })
+    }
+
     /// Return a new DataFrame that adds the result of evaluating one or more
     /// window functions ([`Expr::WindowFunction`]) to the existing columns
     pub fn window(self, window_exprs: Vec<Expr>) -> Result<DataFrame> {
@@ -671,6 +786,46 @@ impl DataFrame {
         })
     }
 
+    /// Returns a new `DataFrame` with a random sample of the rows, like
+    /// SQL `TABLESAMPLE`.
+    ///
+    /// # Arguments
+    /// `method` - [`SampleMethod::Bernoulli`] samples individual rows,
+    /// [`SampleMethod::System`] samples whole blocks of rows, which is cheaper
+    /// and can skip entire Parquet row groups
+    /// `fraction` - Probability of keeping a row or block, between 0 and 1
+    /// `seed` - Optional seed that makes the sample repeatable
+    ///
+    /// # Example
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # use datafusion::logical_expr::SampleMethod;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// let df = ctx.read_csv("tests/data/example_long.csv", CsvReadOptions::new()).await?;
+    /// let df = df.sample(SampleMethod::Bernoulli, 0.5, Some(42))?;
+    /// assert!(df.count().await? <= 3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn sample(
+        self,
+        method: SampleMethod,
+        fraction: f64,
+        seed: Option<u64>,
+    ) -> Result<DataFrame> {
+        let plan = LogicalPlanBuilder::from(self.plan)
+            .sample(method, fraction, seed)?
+            .build()?;
+        Ok(DataFrame {
+            session_state: self.session_state,
+            plan,
+            projection_requires_validation: self.projection_requires_validation,
+        })
+    }
+
     /// Calculate the union of two [`DataFrame`]s, preserving duplicate rows.
     ///
     /// The two [`DataFrame`]s must have exactly the same schema
@@ -1373,9 +1528,8 @@ impl DataFrame {
     /// # }
     /// ```
     pub async fn collect(self) -> Result<Vec<RecordBatch>> {
-        let task_ctx = Arc::new(self.task_ctx());
-        let plan = self.create_physical_plan().await?;
-        collect(plan, task_ctx).await
+        let stream = self.execute_stream().await?;
+        common::collect(stream).await
     }
 
     /// Execute the `DataFrame` and print the results to the console.
@@ -1482,10 +1636,46 @@ impl DataFrame {
     ///
     /// Dropping the stream will abort the execution of the query, and free up
     /// any allocated resources
+    ///
+    /// # Result Cache
+    ///
+    /// If the runtime has a [`ResultCache`], the result of a query that was
+    /// already executed against the same files is returned from the cache.
+    ///
+    /// [`ResultCache`]: datafusion_execution::cache::cache_manager::ResultCache
     pub async fn execute_stream(self) -> Result<SendableRecordBatchStream> {
         let task_ctx = Arc::new(self.task_ctx());
+        let result_cache = task_ctx.runtime_env().cache_manager.get_result_cache();
+        let logical_plan = result_cache.is_some().then(|| self.plan.clone());
         let plan = self.create_physical_plan().await?;
-        execute_stream(plan, task_ctx)
+
+        let query = match (&result_cache, logical_plan) {
+            (Some(cache), Some(logical_plan)) => {
+                invalidate_written_table(cache.as_ref(), &logical_plan);
+                CacheableQuery::try_new(
+                    cache,
+                    logical_plan,
+                    &plan,
+                    task_ctx.session_config().options(),
+                )
+            }
+            _ => None,
+        };
+        if let Some(query) = &query {
+            if let Some(stream) = query.get().await? {
+                return Ok(stream);
+            }
+        }
+
+        let permit = admit(&task_ctx).await?;
+        let mut stream = execute_stream(plan, task_ctx)?;
+        if let Some(query) = query {
+            stream = query.attach(stream);
+        }
+        Ok(match permit {
+            Some(permit) => permit.attach(stream),
+            None => stream,
+        })
     }
 
     /// Executes this DataFrame and collects all results into a vector of vector of RecordBatch
@@ -1506,6 +1696,7 @@ impl DataFrame {
     pub async fn collect_partitioned(self) -> Result<Vec<Vec<RecordBatch>>> {
         let task_ctx = Arc::new(self.task_ctx());
         let plan = self.create_physical_plan().await?;
+        let _permit = admit(&task_ctx).await?;
         collect_partitioned(plan, task_ctx).await
     }
 
@@ -1532,7 +1723,15 @@ impl DataFrame {
     ) -> Result<Vec<SendableRecordBatchStream>> {
         let task_ctx = Arc::new(self.task_ctx());
         let plan = self.create_physical_plan().await?;
-        execute_stream_partitioned(plan, task_ctx)
+        let permit = admit(&task_ctx).await?;
+        let streams = execute_stream_partitioned(plan, task_ctx)?;
+        Ok(match permit {
+            Some(permit) => streams
+                .into_iter()
+                .map(|stream| Arc::clone(&permit).attach(stream))
+                .collect(),
+            None => streams,
+        })
     }
 
     /// Returns the `DFSchema` describing the output of this DataFrame.
@@ -2423,6 +2622,18 @@ macro_rules! dataframe {
     }};
 }
 
+/// Waits for the admission controller of the runtime, if any, to admit a
+/// query executed with `task_ctx`.
+///
+/// See [`datafusion_execution::admission::AdmissionController`]
+async fn admit(task_ctx: &TaskContext) -> Result<Option<Arc<AdmissionPermit>>> {
+    let Some(controller) = &task_ctx.runtime_env().admission_controller else {
+        return Ok(None);
+    };
+    let request = AdmissionRequest::from_config(task_ctx.session_config().options());
+    Ok(Some(Arc::new(controller.admit(request).await?)))
+}
+
 #[derive(Debug)]
 struct DataFrameTableProvider {
     plan: LogicalPlan,
@@ -0,0 +1,301 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Caching of [`DataFrame`](super::DataFrame) results in the [`ResultCache`]
+//! of the runtime
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use crate::error::Result;
+use crate::physical_plan::empty::EmptyExec;
+use crate::physical_plan::memory::MemoryStream;
+use crate::physical_plan::placeholder_row::PlaceholderRowExec;
+use crate::physical_plan::{ExecutionPlan, RecordBatchStream, SendableRecordBatchStream};
+
+use arrow::array::RecordBatch;
+use arrow::datatypes::SchemaRef;
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::tree_node::{TreeNode, TreeNodeRecursion};
+use datafusion_common::DataFusionError;
+use datafusion_common_runtime::SpawnedTask;
+use datafusion_datasource::file_scan_config::FileScanConfig;
+use datafusion_datasource::source::DataSourceExec;
+use datafusion_execution::cache::cache_manager::{
+    CachedResult, ResultCache, ResultCacheKey,
+};
+use datafusion_expr::{Expr, LogicalPlan, Volatility};
+use datafusion_optimizer::plan_signature::LogicalPlanSignature;
+use futures::{ready, FutureExt, Stream};
+use object_store::ObjectMeta;
+
+/// A query whose result can be stored in a [`ResultCache`]
+pub(super) struct CacheableQuery {
+    cache: Arc<dyn ResultCache>,
+    key: ResultCacheKey,
+    plan: Arc<LogicalPlan>,
+}
+
+impl CacheableQuery {
+    /// Returns `None` if the result of the query cannot be cached, because it
+    /// has side effects, calls non immutable functions, or reads data from
+    /// anything other than files.
+    pub(super) fn try_new(
+        cache: &Arc<dyn ResultCache>,
+        logical_plan: LogicalPlan,
+        physical_plan: &Arc<dyn ExecutionPlan>,
+        options: &ConfigOptions,
+    ) -> Option<Self> {
+        if !is_deterministic(&logical_plan) {
+            return None;
+        }
+        let files = scanned_files(physical_plan)?;
+
+        let mut hasher = DefaultHasher::new();
+        LogicalPlanSignature::new(&logical_plan).hash(&mut hasher);
+        for entry in options.entries() {
+            entry.key.hash(&mut hasher);
+            entry.value.hash(&mut hasher);
+        }
+
+        Some(Self {
+            cache: Arc::clone(cache),
+            key: ResultCacheKey {
+                plan_fingerprint: hasher.finish(),
+                files,
+            },
+            plan: Arc::new(logical_plan),
+        })
+    }
+
+    /// Returns a stream of the cached result, if any
+    ///
+    /// The lookup runs on a blocking thread, as results spilled to disk are
+    /// read back synchronously.
+    pub(super) async fn get(&self) -> Result<Option<SendableRecordBatchStream>> {
+        let cache = Arc::clone(&self.cache);
+        let key = self.key.clone();
+        let plan = Arc::clone(&self.plan);
+        let result =
+            SpawnedTask::spawn_blocking(move || cache.get_with_extra(&key, &plan))
+                .join_unwind()
+                .await
+                .map_err(|e| DataFusionError::ExecutionJoin(Box::new(e)))?;
+        let Some(result) = result else {
+            return Ok(None);
+        };
+        let stream = MemoryStream::try_new(
+            result.batches.clone(),
+            Arc::clone(&result.schema),
+            None,
+        )?;
+        Ok(Some(Box::pin(stream)))
+    }
+
+    /// Wraps `stream` so that its result is cached once it completes
+    /// successfully, unless it is larger than the cache
+    pub(super) fn attach(
+        self,
+        stream: SendableRecordBatchStream,
+    ) -> SendableRecordBatchStream {
+        let max_size = self.cache.memory_limit().max(self.cache.disk_limit());
+        Box::pin(CachingStream {
+            inner: stream,
+            query: Some(self),
+            batches: vec![],
+            size: 0,
+            max_size,
+            put_task: None,
+        })
+    }
+}
+
+/// Removes the cached results of the queries reading the table written by
+/// `plan`, if it is an `INSERT`, `UPDATE` or `DELETE` statement
+pub(super) fn invalidate_written_table(cache: &dyn ResultCache, plan: &LogicalPlan) {
+    if let LogicalPlan::Dml(dml) = plan {
+        cache.invalidate_table(&dml.table_name);
+    }
+}
+
+/// Returns true if `plan` has no side effects, and only calls immutable
+/// functions, so that executing it again against the same files returns the
+/// same result
+fn is_deterministic(plan: &LogicalPlan) -> bool {
+    let mut deterministic = true;
+    plan.apply_with_subqueries(|plan| {
+        deterministic = !matches!(
+            plan,
+            LogicalPlan::Dml(_)
+                | LogicalPlan::Ddl(_)
+                | LogicalPlan::Copy(_)
+                | LogicalPlan::Statement(_)
+                | LogicalPlan::Explain(_)
+                | LogicalPlan::Analyze(_)
+                | LogicalPlan::DescribeTable(_)
+                | LogicalPlan::Extension(_)
+        ) && !calls_non_immutable_function(plan);
+        Ok(if deterministic {
+            TreeNodeRecursion::Continue
+        } else {
+            TreeNodeRecursion::Stop
+        })
+    })
+    // Closure always return Ok
+    .unwrap();
+    deterministic
+}
+
+/// Returns true if the expressions of `plan` call a volatile or stable
+/// function, such as `random()` or `now()`
+fn calls_non_immutable_function(plan: &LogicalPlan) -> bool {
+    let mut found = false;
+    plan.apply_expressions(|expr| {
+        found = expr.exists(|expr| {
+            Ok(matches!(
+                expr,
+                Expr::ScalarFunction(function)
+                    if function.func.signature().volatility != Volatility::Immutable
+            ))
+        })?;
+        Ok(if found {
+            TreeNodeRecursion::Stop
+        } else {
+            TreeNodeRecursion::Continue
+        })
+    })
+    // Closure always return Ok
+    .unwrap();
+    found
+}
+
+/// Returns the files scanned by `plan`, or `None` if it reads data from
+/// anything other than files
+fn scanned_files(plan: &Arc<dyn ExecutionPlan>) -> Option<Vec<ObjectMeta>> {
+    let mut files = vec![];
+    let mut only_files = true;
+    plan.apply(|node| {
+        if !node.children().is_empty() {
+            return Ok(TreeNodeRecursion::Continue);
+        }
+        let any = node.as_any();
+        if any.is::<EmptyExec>() || any.is::<PlaceholderRowExec>() {
+            return Ok(TreeNodeRecursion::Continue);
+        }
+        let config = any.downcast_ref::<DataSourceExec>().and_then(|exec| {
+            exec.data_source().as_any().downcast_ref::<FileScanConfig>()
+        });
+        match config {
+            Some(config) => {
+                files.extend(
+                    config
+                        .file_groups
+                        .iter()
+                        .flat_map(|group| group.files())
+                        .map(|file| file.object_meta.clone()),
+                );
+                Ok(TreeNodeRecursion::Continue)
+            }
+            None => {
+                only_files = false;
+                Ok(TreeNodeRecursion::Stop)
+            }
+        }
+    })
+    // Closure always return Ok
+    .unwrap();
+    only_files.then_some(files)
+}
+
+/// A stream that stores the result of its query in the [`ResultCache`] once
+/// it completes successfully
+///
+/// The result is stored on a blocking thread, as the cache may spill results
+/// to disk, and the stream ends once it was stored.
+struct CachingStream {
+    inner: SendableRecordBatchStream,
+    /// `None` once the result is being cached, or is known not to be cacheable
+    query: Option<CacheableQuery>,
+    batches: Vec<RecordBatch>,
+    size: usize,
+    max_size: usize,
+    /// Task storing the result in the cache, once the input is exhausted
+    put_task: Option<SpawnedTask<()>>,
+}
+
+impl Stream for CachingStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        if let Some(put_task) = &mut self.put_task {
+            // a failure to cache the result does not fail the query
+            let _ = ready!(put_task.poll_unpin(cx));
+            self.put_task = None;
+            return Poll::Ready(None);
+        }
+
+        let poll = self.inner.as_mut().poll_next(cx);
+        let this = &mut *self;
+        if this.query.is_none() {
+            return poll;
+        }
+        match &poll {
+            Poll::Ready(Some(Ok(batch))) => {
+                this.size += batch.get_array_memory_size();
+                if this.size > this.max_size {
+                    this.query = None;
+                    this.batches = vec![];
+                } else {
+                    this.batches.push(batch.clone());
+                }
+            }
+            Poll::Ready(Some(Err(_))) => {
+                this.query = None;
+                this.batches = vec![];
+            }
+            Poll::Ready(None) => {
+                let query = this.query.take().expect("checked above");
+                let result = Arc::new(CachedResult {
+                    schema: this.inner.schema(),
+                    batches: std::mem::take(&mut this.batches),
+                });
+                this.put_task = Some(SpawnedTask::spawn_blocking(move || {
+                    query.cache.put_with_extra(&query.key, result, &query.plan);
+                }));
+                return self.as_mut().poll_next(cx);
+            }
+            Poll::Pending => {}
+        }
+        poll
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl RecordBatchStream for CachingStream {
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+}
@@ -23,14 +23,14 @@ mod tests {
     use crate::physical_plan::collect;
     use crate::prelude::SessionContext;
     use arrow::array::{AsArray, Int32Array};
-    use arrow::datatypes::{DataType, Field, Schema, UInt64Type};
+    use arrow::datatypes::{DataType, Field, Int32Type, Schema, UInt64Type};
     use arrow::error::ArrowError;
     use arrow::record_batch::RecordBatch;
     use arrow_schema::SchemaRef;
     use datafusion_catalog::TableProvider;
     use datafusion_common::{DataFusionError, Result};
     use datafusion_expr::dml::InsertOp;
-    use datafusion_expr::LogicalPlanBuilder;
+    use datafusion_expr::{col, lit, LogicalPlanBuilder};
     use futures::StreamExt;
     use std::collections::HashMap;
     use std::sync::Arc;
@@ -451,4 +451,56 @@ mod tests {
         );
         Ok(())
     }
+
+    // Test deleting and updating rows spread over multiple partitions
+    #[tokio::test]
+    async fn test_delete_and_update_multi_partition() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let batch = |values: Vec<i32>| {
+            RecordBatch::try_new(schema.clone(), vec![Arc::new(Int32Array::from(values))])
+        };
+        let table = Arc::new(
+            MemTable::try_new(
+                schema.clone(),
+                vec![
+                    vec![batch(vec![1, 2, 3])?, batch(vec![4])?],
+                    vec![batch(vec![5, 6])?],
+                ],
+            )?
+            .with_sort_order(vec![vec![col("a").sort(true, false)]]),
+        );
+        let session_ctx = SessionContext::new();
+        let state = session_ctx.state();
+
+        // The table is only modified when the plan is executed
+        let plan = table.delete_from(&state, vec![col("a").gt(lit(3))]).await?;
+        assert_eq!(table.batches[1].read().await.len(), 1);
+        let res = collect(plan, session_ctx.task_ctx()).await?;
+        assert_eq!(extract_count(res), 3);
+        // Batches without remaining rows are removed
+        assert_eq!(table.batches[0].read().await.len(), 1);
+        assert!(table.batches[1].read().await.is_empty());
+        // Deleting rows keeps the sort order
+        assert_eq!(table.sort_order.lock().len(), 1);
+
+        let plan = table
+            .update(
+                &state,
+                vec![("a".to_string(), col("a") * lit(10))],
+                vec![col("a").not_eq(lit(2))],
+            )
+            .await?;
+        // Planning the update does not modify the sort order, executing it does
+        assert_eq!(table.sort_order.lock().len(), 1);
+        let res = collect(plan, session_ctx.task_ctx()).await?;
+        assert!(table.sort_order.lock().is_empty());
+        assert_eq!(extract_count(res), 2);
+        let values = table.batches[0].read().await[0]
+            .column(0)
+            .as_primitive::<Int32Type>()
+            .values()
+            .to_vec();
+        assert_eq!(values, vec![10, 2, 30]);
+        Ok(())
+    }
 }
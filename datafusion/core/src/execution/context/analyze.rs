@@ -0,0 +1,314 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef};
+use arrow::compute::kernels::cmp::distinct;
+use arrow::datatypes::{DataType, TimeUnit};
+use datafusion_common::stats::Precision;
+use datafusion_common::{
+    plan_err, ColumnHistogram, HistogramBucket, ScalarValue, Statistics, TableReference,
+};
+use datafusion_expr::{ident, lit, Expr};
+use datafusion_functions_aggregate::expr_fn::{approx_distinct, count, max, min};
+use futures::StreamExt;
+
+use super::{DataFrame, Result, SessionContext};
+
+impl SessionContext {
+    /// Computes the statistics of the given columns of a table, or of all
+    /// columns if `columns` is empty, and stores them in the table statistics
+    /// cache of the [`CacheManager`]. This is the same as the SQL statement
+    /// `ANALYZE TABLE t [COLUMNS (a, b)]`.
+    ///
+    /// For each column, this computes the number of nulls, the minimum and
+    /// maximum values, the number of distinct values (with a HyperLogLog
+    /// sketch, where supported), an equi-depth histogram and the most common
+    /// values. These statistics are attached to the scans of the table, and
+    /// used to estimate the cardinality of filters and joins. They are not
+    /// updated when the table changes, so the table should be analyzed again
+    /// after large changes.
+    ///
+    /// [`CacheManager`]: crate::execution::cache::cache_manager::CacheManager
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// ctx.sql("CREATE TABLE t AS VALUES (1, 'a'), (2, 'b'), (2, 'c')").await?;
+    /// ctx.analyze_table("t", &["column1"]).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn analyze_table(
+        &self,
+        table_ref: impl Into<TableReference>,
+        columns: &[&str],
+    ) -> Result<()> {
+        let table_ref = table_ref.into();
+        let provider = self.table_provider(table_ref.clone()).await?;
+        let schema = provider.schema();
+        let columns = if columns.is_empty() {
+            (0..schema.fields().len())
+                .filter(|index| supports_histogram(schema.field(*index).data_type()))
+                .collect::<Vec<_>>()
+        } else {
+            columns
+                .iter()
+                .map(|name| {
+                    let index = schema.index_of(name)?;
+                    let data_type = schema.field(index).data_type();
+                    if !supports_histogram(data_type) {
+                        return plan_err!(
+                            "Cannot analyze column {name} of type {data_type}"
+                        );
+                    }
+                    Ok(index)
+                })
+                .collect::<Result<Vec<_>>>()?
+        };
+
+        let state = self.state();
+        let options = &state.config_options().execution;
+        let num_buckets = options.analyze_histogram_buckets.max(1);
+        let num_most_common_values = options.analyze_most_common_values;
+        let df = self.read_table(Arc::clone(&provider))?;
+
+        // Compute the row count, null counts, bounds and distinct counts in a
+        // single aggregation
+        let mut aggr_exprs = vec![count(lit(1)).alias("__rows")];
+        for index in &columns {
+            let column = ident(schema.field(*index).name());
+            aggr_exprs.push(count(column.clone()).alias(format!("__values_{index}")));
+            aggr_exprs.push(min(column.clone()).alias(format!("__min_{index}")));
+            aggr_exprs.push(max(column.clone()).alias(format!("__max_{index}")));
+            if supports_approx_distinct(schema.field(*index).data_type()) {
+                aggr_exprs.push(approx_distinct(column).alias(format!("__ndv_{index}")));
+            }
+        }
+        let summary = df.clone().aggregate(vec![], aggr_exprs)?.collect().await?;
+        let summary = &summary[0];
+        let summary_value = |name: &str| match summary.schema().index_of(name) {
+            Ok(column) => ScalarValue::try_from_array(summary.column(column), 0),
+            Err(_) => Ok(ScalarValue::Null),
+        };
+        let num_rows = scalar_to_usize(&summary_value("__rows")?);
+
+        let cache = state
+            .runtime_env()
+            .cache_manager
+            .get_table_statistics_cache();
+        let key = TableReference::from(state.resolve_table_ref(table_ref));
+        let mut statistics = cache
+            .get_with_extra(&key, &schema)
+            .map(|statistics| statistics.as_ref().clone())
+            .unwrap_or_else(|| Statistics::new_unknown(&schema));
+        statistics.num_rows = Precision::Inexact(num_rows);
+
+        for index in columns {
+            let column = ident(schema.field(index).name());
+            let num_values =
+                scalar_to_usize(&summary_value(&format!("__values_{index}"))?);
+            let buckets =
+                histogram_buckets(&df, column.clone(), num_values, num_buckets).await?;
+            let most_common_values =
+                most_common_values(&df, column, num_most_common_values).await?;
+
+            let distinct_count = match summary_value(&format!("__ndv_{index}"))? {
+                ScalarValue::Null => buckets.iter().map(|b| b.distinct_count).sum(),
+                ndv => scalar_to_usize(&ndv),
+            };
+            let bound = |value: ScalarValue| {
+                if value.is_null() {
+                    Precision::Absent
+                } else {
+                    Precision::Inexact(value)
+                }
+            };
+            let column_statistics = &mut statistics.column_statistics[index];
+            column_statistics.null_count = Precision::Inexact(num_rows - num_values);
+            column_statistics.min_value =
+                bound(summary_value(&format!("__min_{index}"))?);
+            column_statistics.max_value =
+                bound(summary_value(&format!("__max_{index}"))?);
+            column_statistics.distinct_count = Precision::Inexact(distinct_count);
+            column_statistics.histogram = Some(Arc::new(ColumnHistogram {
+                num_rows,
+                buckets,
+                most_common_values,
+            }));
+        }
+
+        cache.put_with_extra(&key, Arc::new(statistics), &schema);
+        Ok(())
+    }
+}
+
+/// Computes the buckets of an equi-depth histogram from the sorted non-null
+/// values of `column`. Equal values are never split across buckets.
+async fn histogram_buckets(
+    df: &DataFrame,
+    column: Expr,
+    num_values: usize,
+    num_buckets: usize,
+) -> Result<Vec<HistogramBucket>> {
+    let bucket_size = num_values.div_ceil(num_buckets).max(1);
+    let mut stream = df
+        .clone()
+        .select(vec![column.clone()])?
+        .filter(column.clone().is_not_null())?
+        .sort(vec![column.sort(true, false)])?
+        .execute_stream()
+        .await?;
+
+    let mut buckets = vec![];
+    let mut current: Option<HistogramBucket> = None;
+    let mut last_value: Option<ScalarValue> = None;
+    while let Some(batch) = stream.next().await {
+        let values: ArrayRef = Arc::clone(batch?.column(0));
+        if values.is_empty() {
+            continue;
+        }
+        // `changed[i]` is whether row `i + 1` differs from row `i`
+        let changed = distinct(
+            &values.slice(0, values.len() - 1),
+            &values.slice(1, values.len() - 1),
+        )?;
+        for row in 0..values.len() {
+            let is_new_value = if row == 0 {
+                let value = ScalarValue::try_from_array(&values, 0)?;
+                last_value.as_ref() != Some(&value)
+            } else {
+                changed.value(row - 1)
+            };
+            if is_new_value {
+                let full = current
+                    .as_ref()
+                    .is_some_and(|bucket| bucket.num_values >= bucket_size);
+                if full {
+                    let mut bucket = current.take().unwrap();
+                    bucket.upper = if row == 0 {
+                        last_value.clone().unwrap()
+                    } else {
+                        ScalarValue::try_from_array(&values, row - 1)?
+                    };
+                    buckets.push(bucket);
+                }
+                let bucket = match current.as_mut() {
+                    Some(bucket) => bucket,
+                    None => {
+                        let lower = ScalarValue::try_from_array(&values, row)?;
+                        current.insert(HistogramBucket {
+                            upper: lower.clone(),
+                            lower,
+                            num_values: 0,
+                            distinct_count: 0,
+                        })
+                    }
+                };
+                bucket.distinct_count += 1;
+            }
+            current.as_mut().unwrap().num_values += 1;
+        }
+        last_value = Some(ScalarValue::try_from_array(&values, values.len() - 1)?);
+    }
+    if let Some(mut bucket) = current {
+        bucket.upper = last_value.unwrap();
+        buckets.push(bucket);
+    }
+    Ok(buckets)
+}
+
+/// Returns the most common non-null values of `column` that occur more than
+/// once, with their number of rows
+async fn most_common_values(
+    df: &DataFrame,
+    column: Expr,
+    limit: usize,
+) -> Result<Vec<(ScalarValue, usize)>> {
+    if limit == 0 {
+        return Ok(vec![]);
+    }
+    let batches = df
+        .clone()
+        .filter(column.clone().is_not_null())?
+        .aggregate(vec![column.clone()], vec![count(lit(1)).alias("__count")])?
+        .filter(ident("__count").gt(lit(1_i64)))?
+        .sort(vec![
+            ident("__count").sort(false, true),
+            column.sort(true, false),
+        ])?
+        .limit(0, Some(limit))?
+        .collect()
+        .await?;
+
+    let mut values = vec![];
+    for batch in batches {
+        for row in 0..batch.num_rows() {
+            let value = ScalarValue::try_from_array(batch.column(0), row)?;
+            let count = ScalarValue::try_from_array(batch.column(1), row)?;
+            values.push((value, scalar_to_usize(&count)));
+        }
+    }
+    Ok(values)
+}
+
+/// Returns whether histograms can be computed for values of `data_type`
+fn supports_histogram(data_type: &DataType) -> bool {
+    data_type.is_primitive()
+        || matches!(
+            data_type,
+            DataType::Boolean
+                | DataType::Utf8
+                | DataType::LargeUtf8
+                | DataType::Utf8View
+                | DataType::Binary
+                | DataType::LargeBinary
+                | DataType::BinaryView
+        )
+}
+
+/// Returns whether the `approx_distinct` sketch supports `data_type`
+fn supports_approx_distinct(data_type: &DataType) -> bool {
+    data_type.is_integer()
+        || matches!(
+            data_type,
+            DataType::Date32
+                | DataType::Date64
+                | DataType::Time32(TimeUnit::Second | TimeUnit::Millisecond)
+                | DataType::Time64(TimeUnit::Microsecond | TimeUnit::Nanosecond)
+                | DataType::Timestamp(_, _)
+                | DataType::Utf8
+                | DataType::LargeUtf8
+                | DataType::Utf8View
+                | DataType::Binary
+                | DataType::LargeBinary
+        )
+}
+
+fn scalar_to_usize(value: &ScalarValue) -> usize {
+    match value {
+        ScalarValue::Int64(Some(value)) => *value as usize,
+        ScalarValue::UInt64(Some(value)) => *value as usize,
+        _ => 0,
+    }
+}
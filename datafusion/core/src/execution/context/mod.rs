@@ -76,7 +76,7 @@ pub use datafusion_execution::TaskContext;
 pub use datafusion_expr::execution_props::ExecutionProps;
 use datafusion_expr::{
     expr_rewriter::FunctionRewrite,
-    logical_plan::{DdlStatement, Statement},
+    logical_plan::{AnalyzeTable, DdlStatement, Statement},
     planner::ExprPlanner,
     Expr, UserDefinedLogicalNode, WindowUDF,
 };
@@ -91,6 +91,7 @@ use object_store::ObjectStore;
 use parking_lot::RwLock;
 use url::Url;
 
+mod analyze;
 mod csv;
 mod json;
 #[cfg(feature = "parquet")]
@@ -737,6 +738,14 @@ impl SessionContext {
                     .remove_prepared(deallocate.name.as_str())?;
                 self.return_empty_dataframe()
             }
+            LogicalPlan::Statement(Statement::AnalyzeTable(AnalyzeTable {
+                table_name,
+                columns,
+            })) => {
+                let columns = columns.iter().map(String::as_str).collect::<Vec<_>>();
+                Box::pin(self.analyze_table(table_name, &columns)).await?;
+                self.return_empty_dataframe()
+            }
             plan => Ok(DataFrame::new(self.state(), plan)),
         }
     }
@@ -1099,6 +1108,10 @@ impl SessionContext {
                 let limit = Self::parse_memory_limit(value)?;
                 builder.with_metadata_cache_limit(limit)
             }
+            "file_range_cache_limit" => {
+                let limit = Self::parse_memory_limit(value)?;
+                builder.with_file_range_cache_limit(limit)
+            }
             _ => {
                 return Err(DataFusionError::Plan(format!(
                     "Unknown runtime configuration: {variable}"
@@ -74,6 +74,8 @@ use datafusion_session::Session;
 use datafusion_sql::{
     parser::{DFParserBuilder, Statement},
     planner::{ContextProvider, ParserOptions, PlannerContext, SqlToRel},
+    resolve::{dynamic_pivot_values_query, resolve_pivot_values},
+    unparser::Unparser,
 };
 
 use async_trait::async_trait;
@@ -83,7 +85,7 @@ use log::{debug, info};
 use object_store::ObjectStore;
 #[cfg(feature = "sql")]
 use sqlparser::{
-    ast::{Expr as SQLExpr, ExprWithAlias as SQLExprWithAlias},
+    ast::{Expr as SQLExpr, ExprWithAlias as SQLExprWithAlias, Ident},
     dialect::dialect_from_str,
 };
 use url::Url;
@@ -466,8 +468,9 @@ impl SessionState {
     #[cfg(feature = "sql")]
     pub async fn statement_to_plan(
         &self,
-        statement: Statement,
+        mut statement: Statement,
     ) -> datafusion_common::Result<LogicalPlan> {
+        self.resolve_dynamic_pivots(&mut statement).await?;
         let references = self.resolve_table_references(&statement)?;
 
         let mut provider = SessionContextProvider {
@@ -491,6 +494,53 @@ impl SessionState {
         query.statement_to_plan(statement)
     }
 
+    /// Replaces the dynamic value lists of `PIVOT`s (`IN (ANY)` or
+    /// `IN (<subquery>)`) in the statement with the values returned by running
+    /// their value queries, as the SQL planner only supports static value lists.
+    #[cfg(feature = "sql")]
+    async fn resolve_dynamic_pivots(
+        &self,
+        statement: &mut Statement,
+    ) -> datafusion_common::Result<()> {
+        let dialect = self.config.options().sql_parser.dialect.as_str();
+        while let Some(sql) = dynamic_pivot_values_query(statement) {
+            let values_statement = self.sql_to_statement(&sql, dialect)?;
+            let plan = Box::pin(self.statement_to_plan(values_statement)).await?;
+            if plan.schema().fields().len() != 1 {
+                return datafusion_common::plan_err!(
+                    "PIVOT value query must return a single column, got {} columns",
+                    plan.schema().fields().len()
+                );
+            }
+            let plan = self.create_physical_plan(&plan).await?;
+            let batches =
+                datafusion_physical_plan::collect(plan, self.task_ctx()).await?;
+            let unparser = Unparser::default();
+            let mut values = vec![];
+            for batch in batches {
+                for row in 0..batch.num_rows() {
+                    let value = datafusion_common::ScalarValue::try_from_array(
+                        batch.column(0),
+                        row,
+                    )?;
+                    if value.is_null() {
+                        continue;
+                    }
+                    let name = match value.try_as_str() {
+                        Some(Some(value)) => value.to_string(),
+                        _ => value.to_string(),
+                    };
+                    values.push(SQLExprWithAlias {
+                        expr: unparser.expr_to_sql(&datafusion_expr::lit(value))?,
+                        alias: Some(Ident::with_quote('"', name)),
+                    });
+                }
+            }
+            resolve_pivot_values(statement, values);
+        }
+        Ok(())
+    }
+
     #[cfg(feature = "sql")]
     fn get_parser_options(&self) -> ParserOptions {
         let sql_parser_options = &self.config.options().sql_parser;
@@ -1731,6 +1781,17 @@ impl ContextProvider for SessionContextProvider<'_> {
         Ok(provider_as_source(table))
     }
 
+    fn create_materialized_cte(
+        &self,
+        name: &str,
+        plan: Arc<LogicalPlan>,
+    ) -> datafusion_common::Result<Option<Arc<dyn TableSource>>> {
+        let table = Arc::new(
+            crate::datasource::materialized_cte::MaterializedCteTable::new(name, plan),
+        );
+        Ok(Some(provider_as_source(table)))
+    }
+
     fn get_function_meta(&self, name: &str) -> Option<Arc<ScalarUDF>> {
         self.state.scalar_functions().get(name).cloned()
     }
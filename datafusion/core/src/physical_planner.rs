@@ -42,11 +42,16 @@ use crate::physical_plan::explain::ExplainExec;
 use crate::physical_plan::filter::FilterExec;
 use crate::physical_plan::joins::utils as join_utils;
 use crate::physical_plan::joins::{
-    CrossJoinExec, HashJoinExec, NestedLoopJoinExec, PartitionMode, SortMergeJoinExec,
+    AsOfJoinExec, CrossJoinExec, HashJoinExec, IEJoinExec, NestedLoopJoinExec,
+    PartitionMode, SortMergeJoinExec,
 };
 use crate::physical_plan::limit::{GlobalLimitExec, LocalLimitExec};
+use crate::physical_plan::match_recognize::{
+    MatchMeasureExpr, MatchMeasureFunction, MatchRecognizeExec, MatchSymbolExpr,
+};
 use crate::physical_plan::projection::{ProjectionExec, ProjectionExpr};
 use crate::physical_plan::repartition::RepartitionExec;
+use crate::physical_plan::sample::SampleExec;
 use crate::physical_plan::sorts::sort::SortExec;
 use crate::physical_plan::union::UnionExec;
 use crate::physical_plan::unnest::UnnestExec;
@@ -59,7 +64,7 @@ use crate::schema_equivalence::schema_satisfied_by;
 
 use arrow::array::{builder::StringBuilder, RecordBatch};
 use arrow::compute::SortOptions;
-use arrow::datatypes::Schema;
+use arrow::datatypes::{Schema, SchemaRef};
 use datafusion_catalog::ScanArgs;
 use datafusion_common::display::ToStringifiedPlan;
 use datafusion_common::tree_node::{TreeNode, TreeNodeRecursion, TreeNodeVisitor};
@@ -70,17 +75,20 @@ use datafusion_common::{
 };
 use datafusion_datasource::file_groups::FileGroup;
 use datafusion_datasource::memory::MemorySourceConfig;
+use datafusion_datasource::source::DataSourceExec;
 use datafusion_expr::dml::{CopyTo, InsertOp};
 use datafusion_expr::expr::{
     physical_name, AggregateFunction, AggregateFunctionParams, Alias, GroupingSet,
     NullTreatment, WindowFunction, WindowFunctionParams,
 };
-use datafusion_expr::expr_rewriter::unnormalize_cols;
+use datafusion_expr::expr_rewriter::{unnormalize_col, unnormalize_cols};
 use datafusion_expr::logical_plan::builder::wrap_projection_for_join_if_necessary;
+use datafusion_expr::utils::split_conjunction;
 use datafusion_expr::{
-    Analyze, DescribeTable, DmlStatement, Explain, ExplainFormat, Extension, FetchType,
-    Filter, JoinType, RecursiveQuery, SkipType, StringifiedPlan, WindowFrame,
-    WindowFrameBound, WriteOp,
+    lit, Analyze, DescribeTable, DmlStatement, Explain, ExplainFormat, Extension,
+    FetchType, Filter, JoinType, MatchMeasureKind, MatchRecognize, RecursiveQuery,
+    Sample, SampleMethod, SkipType, StringifiedPlan, WindowFrame, WindowFrameBound,
+    WriteOp,
 };
 use datafusion_physical_expr::aggregate::{AggregateExprBuilder, AggregateFunctionExpr};
 use datafusion_physical_expr::expressions::Literal;
@@ -446,6 +454,7 @@ impl DefaultPhysicalPlanner {
         let exec_node: Arc<dyn ExecutionPlan> = match node {
             // Leaves (no children)
             LogicalPlan::TableScan(TableScan {
+                table_name,
                 source,
                 projection,
                 filters,
@@ -463,7 +472,13 @@ impl DefaultPhysicalPlanner {
                     .with_filters(Some(&filters_vec))
                     .with_limit(*fetch);
                 let res = source.scan_with_args(session_state, opts).await?;
-                Arc::clone(res.plan())
+                attach_analyzed_statistics(
+                    Arc::clone(res.plan()),
+                    table_name,
+                    source.schema(),
+                    projection.as_ref(),
+                    session_state,
+                )
             }
             LogicalPlan::Values(Values { values, schema }) => {
                 let exprs = values
@@ -583,6 +598,72 @@ impl DefaultPhysicalPlanner {
                     );
                 }
             }
+            LogicalPlan::Dml(DmlStatement {
+                target,
+                op: WriteOp::Delete,
+                input,
+                ..
+            }) => {
+                if let Some(provider) =
+                    target.as_any().downcast_ref::<DefaultTableSource>()
+                {
+                    let filters = extract_dml_filters(input)?;
+                    provider
+                        .table_provider
+                        .delete_from(session_state, filters)
+                        .await?
+                } else {
+                    return exec_err!(
+                        "Table source can't be downcasted to DefaultTableSource"
+                    );
+                }
+            }
+            LogicalPlan::Dml(DmlStatement {
+                target,
+                op: WriteOp::Update,
+                input,
+                ..
+            }) => {
+                if let Some(provider) =
+                    target.as_any().downcast_ref::<DefaultTableSource>()
+                {
+                    let LogicalPlan::Projection(projection) = input.as_ref() else {
+                        return internal_err!(
+                            "Expected the input of an UPDATE to be a projection, found: {}",
+                            input.display()
+                        );
+                    };
+                    let assignments = extract_update_assignments(projection);
+                    let filters = extract_dml_filters(&projection.input)?;
+                    provider
+                        .table_provider
+                        .update(session_state, assignments, filters)
+                        .await?
+                } else {
+                    return exec_err!(
+                        "Table source can't be downcasted to DefaultTableSource"
+                    );
+                }
+            }
+            LogicalPlan::Dml(DmlStatement {
+                target,
+                op: WriteOp::Merge,
+                ..
+            }) => {
+                if let Some(provider) =
+                    target.as_any().downcast_ref::<DefaultTableSource>()
+                {
+                    let input_exec = children.one()?;
+                    provider
+                        .table_provider
+                        .merge_into(session_state, input_exec)
+                        .await?
+                } else {
+                    return exec_err!(
+                        "Table source can't be downcasted to DefaultTableSource"
+                    );
+                }
+            }
             LogicalPlan::Window(Window { window_expr, .. }) => {
                 if window_expr.is_empty() {
                     return internal_err!("Impossibly got empty window expression");
@@ -931,7 +1012,11 @@ impl DefaultPhysicalPlanner {
                 let new_sort = SortExec::new(ordering, physical_input).with_fetch(*fetch);
                 Arc::new(new_sort)
             }
-            LogicalPlan::Subquery(_) => todo!(),
+            LogicalPlan::Subquery(_) => {
+                return internal_err!(
+                    "Unsupported logical plan: Subquery should be decorrelated to a join"
+                )
+            }
             LogicalPlan::SubqueryAlias(_) => children.one()?,
             LogicalPlan::Limit(limit) => {
                 let input = children.one()?;
@@ -963,6 +1048,108 @@ impl DefaultPhysicalPlanner {
 
                 Arc::new(GlobalLimitExec::new(input, skip, fetch))
             }
+            LogicalPlan::Sample(Sample {
+                method,
+                fraction,
+                seed,
+                ..
+            }) => {
+                let input = children.one()?;
+                // Block level samples are pushed into the scan if it supports them,
+                // so that unsampled blocks are never read
+                let pushed_down = match input.as_any().downcast_ref::<DataSourceExec>() {
+                    Some(scan) if *method == SampleMethod::System => scan
+                        .data_source()
+                        .try_pushdown_sample(*fraction, *seed)?
+                        .map(|source| scan.clone().with_data_source(source)),
+                    _ => None,
+                };
+                match pushed_down {
+                    Some(scan) => Arc::new(scan),
+                    None => {
+                        Arc::new(SampleExec::try_new(input, *method, *fraction, *seed)?)
+                    }
+                }
+            }
+            LogicalPlan::MatchRecognize(MatchRecognize {
+                input,
+                partition_by,
+                order_by,
+                symbols,
+                pattern,
+                measures,
+                rows_per_match,
+                after_match_skip,
+                ..
+            }) => {
+                let physical_input = children.one()?;
+                let input_dfschema = input.schema();
+                let execution_props = session_state.execution_props();
+                let physical_expr =
+                    |expr| create_physical_expr(expr, input_dfschema, execution_props);
+                let partition_by = partition_by
+                    .iter()
+                    .map(physical_expr)
+                    .collect::<Result<_>>()?;
+                let order_by = create_physical_sort_exprs(
+                    order_by,
+                    input_dfschema,
+                    execution_props,
+                )?;
+                let symbols = symbols
+                    .iter()
+                    .map(|symbol| {
+                        Ok(MatchSymbolExpr {
+                            name: symbol.name.clone(),
+                            predicate: physical_expr(&symbol.predicate)?,
+                        })
+                    })
+                    .collect::<Result<_>>()?;
+                let measures = measures
+                    .iter()
+                    .map(|measure| {
+                        let function = match &measure.kind {
+                            MatchMeasureKind::MatchNumber => {
+                                MatchMeasureFunction::MatchNumber
+                            }
+                            MatchMeasureKind::Classifier => {
+                                MatchMeasureFunction::Classifier
+                            }
+                            MatchMeasureKind::Count { symbol } => {
+                                MatchMeasureFunction::Count {
+                                    symbol: symbol.clone(),
+                                }
+                            }
+                            MatchMeasureKind::First { expr, symbol } => {
+                                MatchMeasureFunction::First {
+                                    expr: physical_expr(expr)?,
+                                    symbol: symbol.clone(),
+                                }
+                            }
+                            MatchMeasureKind::Last { expr, symbol } => {
+                                MatchMeasureFunction::Last {
+                                    expr: physical_expr(expr)?,
+                                    symbol: symbol.clone(),
+                                }
+                            }
+                        };
+                        Ok(MatchMeasureExpr {
+                            function,
+                            name: measure.name.clone(),
+                        })
+                    })
+                    .collect::<Result<_>>()?;
+                Arc::new(MatchRecognizeExec::try_new(
+                    physical_input,
+                    partition_by,
+                    order_by,
+                    symbols,
+                    pattern.clone(),
+                    measures,
+                    *rows_per_match,
+                    after_match_skip.clone(),
+                )?)
+            }
             LogicalPlan::Unnest(Unnest {
                 list_type_columns,
                 struct_type_columns,
@@ -1210,10 +1397,56 @@ impl DefaultPhysicalPlanner {
                 let prefer_hash_join =
                     session_state.config_options().optimizer.prefer_hash_join;
 
-                let join: Arc<dyn ExecutionPlan> = if join_on.is_empty() {
+                let join: Arc<dyn ExecutionPlan> = if *join_type == JoinType::AsOf {
+                    let join = match new_logical.as_ref() {
+                        LogicalPlan::Projection(Projection { input, .. }) => {
+                            input.as_ref()
+                        }
+                        plan => plan,
+                    };
+                    let LogicalPlan::Join(join) = join else {
+                        return internal_err!("Expected a Join for an AsOf join");
+                    };
+                    let condition = join.asof_match_condition()?;
+                    Arc::new(AsOfJoinExec::try_new(
+                        physical_left,
+                        physical_right,
+                        join_on,
+                        create_physical_expr(
+                            &condition.left,
+                            left_df_schema,
+                            execution_props,
+                        )?,
+                        condition.op,
+                        create_physical_expr(
+                            &condition.right,
+                            right_df_schema,
+                            execution_props,
+                        )?,
+                        condition.tolerance,
+                    )?)
+                } else if join_on.is_empty() {
                     if join_filter.is_none() && matches!(join_type, JoinType::Inner) {
                         // cross join if there is no join conditions and no join filter set
                         Arc::new(CrossJoinExec::new(physical_left, physical_right))
+                    } else if let Some(filter) = join_filter.as_ref().filter(|filter| {
+                        session_state.config_options().optimizer.enable_ie_join
+                            && IEJoinExec::range_predicates(
+                                filter,
+                                &physical_left.schema(),
+                                &physical_right.schema(),
+                            )
+                            .is_ok_and(|predicates| !predicates.is_empty())
+                    }) {
+                        let filter = filter.clone();
+                        // range predicates between the inputs, use the sort-based
+                        // inequality join
+                        Arc::new(IEJoinExec::try_new(
+                            physical_left,
+                            physical_right,
+                            filter,
+                            join_type,
+                        )?)
                     } else {
                         // there is no equal join condition, use the nested loop join
                         Arc::new(NestedLoopJoinExec::try_new(
@@ -1640,6 +1873,117 @@ fn qualify_join_schema_sides(
     join_schema.with_field_specific_qualified_schema(qualifiers)
 }
 
+/// Attaches the statistics computed by `ANALYZE TABLE` for the table
+/// `table_name` to a [`DataSourceExec`] scanning it, so that they can be used
+/// to estimate the cardinality of filters and joins. Statistics of tables that
+/// have changed their schema since they were analyzed are ignored.
+fn attach_analyzed_statistics(
+    plan: Arc<dyn ExecutionPlan>,
+    table_name: &TableReference,
+    table_schema: SchemaRef,
+    projection: Option<&Vec<usize>>,
+    session_state: &SessionState,
+) -> Arc<dyn ExecutionPlan> {
+    let cache = session_state
+        .runtime_env()
+        .cache_manager
+        .get_table_statistics_cache();
+    if cache.is_empty() {
+        return plan;
+    }
+    let Some(exec) = plan.as_any().downcast_ref::<DataSourceExec>() else {
+        return plan;
+    };
+    let key = TableReference::from(session_state.resolve_table_ref(table_name.clone()));
+    let Some(statistics) = cache.get_with_extra(&key, &table_schema) else {
+        return plan;
+    };
+    let statistics = statistics.as_ref().clone().project(projection);
+    if statistics.column_statistics.len() != exec.schema().fields().len() {
+        return plan;
+    }
+    Arc::new(
+        exec.clone()
+            .with_analyzed_statistics(Some(Arc::new(statistics))),
+    )
+}
+
+/// Extracts the filters of a `DELETE` or `UPDATE` statement from the input
+/// plan of its [`DmlStatement`], to be passed to [`TableProvider::delete_from`]
+/// or [`TableProvider::update`].
+///
+/// The input is expected to be a scan of the target table with optional
+/// filters on top. Column references in the returned filters are unqualified,
+/// as the target table provider resolves them against its own schema.
+///
+/// [`TableProvider::delete_from`]: crate::datasource::TableProvider::delete_from
+/// [`TableProvider::update`]: crate::datasource::TableProvider::update
+fn extract_dml_filters(input: &LogicalPlan) -> Result<Vec<Expr>> {
+    let mut filters = vec![];
+    let mut plan = input;
+    loop {
+        match plan {
+            LogicalPlan::Filter(filter) => {
+                filters.extend(split_conjunction(&filter.predicate).into_iter().cloned());
+                plan = filter.input.as_ref();
+            }
+            LogicalPlan::TableScan(scan) => {
+                filters.extend(scan.filters.iter().cloned());
+                break;
+            }
+            LogicalPlan::SubqueryAlias(alias) => plan = alias.input.as_ref(),
+            // Projections that only select columns do not change how the
+            // filters above them refer to the rows of the target table
+            LogicalPlan::Projection(projection)
+                if projection
+                    .expr
+                    .iter()
+                    .all(|expr| matches!(expr, Expr::Column(_))) =>
+            {
+                plan = projection.input.as_ref();
+            }
+            // The optimizer replaces statements that cannot match any row
+            // with an empty relation
+            LogicalPlan::EmptyRelation(EmptyRelation {
+                produce_one_row: false,
+                ..
+            }) => {
+                filters.push(lit(false));
+                break;
+            }
+            other => {
+                return not_impl_err!(
+                    "DML filters can only be extracted from scans of the target table, found: {}",
+                    other.display()
+                );
+            }
+        }
+    }
+
+    Ok(unnormalize_cols(filters))
+}
+
+/// Extracts the `SET` assignments of an `UPDATE` statement from the projection
+/// at the top of the input plan of its [`DmlStatement`].
+///
+/// The projection computes the new value of every column of the target table.
+/// Columns whose new value is the column itself are not modified and are
+/// omitted from the returned assignments.
+fn extract_update_assignments(projection: &Projection) -> Vec<(String, Expr)> {
+    projection
+        .expr
+        .iter()
+        .zip(projection.schema.fields())
+        .filter_map(|(expr, field)| {
+            let expr = expr.clone().unalias_nested().data;
+            match &expr {
+                Expr::Column(column) if column.name == *field.name() => None,
+                _ => Some((field.name().clone(), unnormalize_col(expr))),
+            }
+        })
+        .collect()
+}
+
 fn get_physical_expr_pair(
     expr: &Expr,
     input_dfschema: &DFSchema,
@@ -69,8 +69,12 @@ use datafusion_common::{
 };
 use datafusion_common_runtime::SpawnedTask;
 use datafusion_datasource::file_format::format_as_file_type;
+use datafusion_execution::admission::AdmissionController;
+use datafusion_execution::cache::cache_manager::{CacheManagerConfig, ResultCache};
+use datafusion_execution::cache::cache_unit::DefaultResultCache;
+use datafusion_execution::cache::CacheAccessor;
 use datafusion_execution::config::SessionConfig;
-use datafusion_execution::runtime_env::RuntimeEnv;
+use datafusion_execution::runtime_env::{RuntimeEnv, RuntimeEnvBuilder};
 use datafusion_expr::expr::{
     FieldMetadata, GroupingSet, NullTreatment, Sort, WindowFunction,
 };
@@ -2358,6 +2362,7 @@ async fn verify_join_output_partitioning() -> Result<()> {
                         out_partitioning,
                     &Partitioning::UnknownPartitioning(partition_count) if partition_count == default_partition_count));
             }
+            JoinType::AsOf => unreachable!(),
         }
     }
 
@@ -6479,3 +6484,138 @@ async fn test_duplicate_state_fields_for_dfschema_construct() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_admission_controller_queues_queries() -> Result<()> {
+    let controller = Arc::new(
+        AdmissionController::new()
+            .with_max_concurrent_queries(1)
+            .with_queue_timeout(std::time::Duration::from_millis(10)),
+    );
+    let runtime = RuntimeEnvBuilder::new()
+        .with_admission_controller(Arc::clone(&controller))
+        .build_arc()?;
+    let ctx = SessionContext::new_with_config_rt(SessionConfig::new(), runtime);
+
+    // The stream holds its permit until it is dropped
+    let stream = ctx.sql("SELECT 1").await?.execute_stream().await?;
+    assert_eq!(controller.running_queries(), 1);
+
+    let err = ctx.sql("SELECT 2").await?.collect().await.unwrap_err();
+    assert_contains!(err.to_string(), "was not admitted within 10ms");
+
+    drop(stream);
+    assert_eq!(controller.running_queries(), 0);
+    let batches = ctx.sql("SELECT 3").await?.collect().await?;
+    assert_eq!(batches[0].num_rows(), 1);
+    assert_eq!(controller.running_queries(), 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_query_timeout() -> Result<()> {
+    let ctx = SessionContext::new();
+    ctx.sql("SET datafusion.execution.query_timeout = 10")
+        .await?
+        .collect()
+        .await?;
+
+    // Would take hours to complete without the timeout
+    let err = ctx
+        .sql("SELECT count(*) FROM generate_series(1, 1000000000000)")
+        .await?
+        .collect()
+        .await
+        .unwrap_err();
+    assert!(matches!(err, DataFusionError::Timeout(_)), "{err}");
+    assert_contains!(err.to_string(), "Query timed out");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_result_cache() -> Result<()> {
+    let cache = Arc::new(DefaultResultCache::new(1024 * 1024));
+    let runtime = RuntimeEnvBuilder::new()
+        .with_cache_manager(
+            CacheManagerConfig::default()
+                .with_result_cache(Some(Arc::clone(&cache) as Arc<dyn ResultCache>)),
+        )
+        .build_arc()?;
+    let ctx = SessionContext::new_with_config_rt(SessionConfig::new(), runtime);
+
+    let tmp_dir = TempDir::new()?;
+    let path = tmp_dir.path().join("data.csv");
+    fs::write(&path, "a\n1\n2\n")?;
+    let modified = fs::metadata(&path)?.modified()?;
+    ctx.register_csv("t", tmp_dir.path().to_str().unwrap(), CsvReadOptions::new())
+        .await?;
+
+    let query = "SELECT sum(a) AS s FROM t";
+    let batches = ctx.sql(query).await?.collect().await?;
+    assert_snapshot!(batches_to_string(&batches), @r"
+    +---+
+    | s |
+    +---+
+    | 3 |
+    +---+
+    ");
+    assert_eq!(cache.len(), 1);
+
+    // Queries calling volatile functions are not cached
+    ctx.sql("SELECT sum(a) + random() FROM t")
+        .await?
+        .collect()
+        .await?;
+    assert_eq!(cache.len(), 1);
+
+    // Rewrite the file without changing its size or modification time, so
+    // that the stale result is returned from the cache
+    fs::write(&path, "a\n5\n6\n")?;
+    fs::File::options()
+        .write(true)
+        .open(&path)?
+        .set_modified(modified)?;
+    let batches = ctx.sql(query).await?.collect().await?;
+    assert_snapshot!(batches_to_string(&batches), @r"
+    +---+
+    | s |
+    +---+
+    | 3 |
+    +---+
+    ");
+
+    // Once the file changes version, the query is executed again
+    fs::File::options()
+        .write(true)
+        .open(&path)?
+        .set_modified(modified + std::time::Duration::from_secs(1))?;
+    let stream = ctx.sql(query).await?.execute_stream().await?;
+    let batches = datafusion::physical_plan::common::collect(stream).await?;
+    assert_snapshot!(batches_to_string(&batches), @r"
+    +----+
+    | s  |
+    +----+
+    | 11 |
+    +----+
+    ");
+    assert_eq!(cache.len(), 1);
+
+    // Writing to the table invalidates the results of the queries reading it
+    ctx.sql("INSERT INTO t VALUES (10)")
+        .await?
+        .collect()
+        .await?;
+    assert_eq!(cache.len(), 0);
+    let batches = ctx.sql(query).await?.collect().await?;
+    assert_snapshot!(batches_to_string(&batches), @r"
+    +----+
+    | s  |
+    +----+
+    | 21 |
+    +----+
+    ");
+
+    Ok(())
+}
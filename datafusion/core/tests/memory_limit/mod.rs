@@ -211,6 +211,33 @@ async fn sort_merge_join_spill() {
         .await
 }
 
+#[tokio::test]
+async fn hash_join_spill() {
+    // Keep both sides partitioned so that the build side is not collected
+    let config = SessionConfig::new()
+        .with_target_partitions(2)
+        .set_bool("datafusion.execution.enable_hash_join_spill", true)
+        .set_usize(
+            "datafusion.optimizer.hash_join_single_partition_threshold",
+            0,
+        )
+        .set_usize(
+            "datafusion.optimizer.hash_join_single_partition_threshold_rows",
+            0,
+        );
+
+    TestCase::new()
+        .with_query(
+            "select t1.* from t t1 JOIN t t2 ON t1.pod = t2.pod AND t1.time = t2.time",
+        )
+        .with_memory_limit(20_000)
+        .with_config(config)
+        .with_disk_manager_builder(DiskManagerBuilder::default())
+        .with_expected_success()
+        .run()
+        .await
+}
+
 #[tokio::test]
 async fn symmetric_hash_join() {
     TestCase::new()
@@ -571,6 +598,8 @@ async fn setup_context(
         parquet_encryption_factory_registry: runtime
             .parquet_encryption_factory_registry
             .clone(),
+        admission_controller: runtime.admission_controller.clone(),
+        metrics_exporter: runtime.metrics_exporter.clone(),
     });
 
     let config = SessionConfig::new()
@@ -907,6 +907,44 @@ async fn without_pushdown_filter() {
     assert_eq!(bytes_scanned_with_filter, bytes_scanned_without_filter);
 }
 
+#[tokio::test]
+async fn with_file_range_cache() {
+    let mut context = ContextWithParquet::new(Scenario::Timestamps, Page(5)).await;
+    let sum = |output: &crate::parquet::TestOutput, name: &str| {
+        cast_count_metric(output.parquet_metrics.sum_by_name(name).unwrap()).unwrap()
+    };
+
+    // The cache is disabled by default
+    let output = context.query("SELECT * FROM t").await;
+    assert_eq!(sum(&output, "column_chunk_cache_hits"), 0);
+    assert_eq!(sum(&output, "column_chunk_cache_misses"), 0);
+    let bytes_scanned_without_cache = sum(&output, "bytes_scanned");
+
+    context
+        .ctx
+        .sql("SET datafusion.runtime.file_range_cache_limit = '10M'")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+
+    // `query` reads the whole table before running the query, which fills the cache
+    let output = context.query("SELECT * FROM t").await;
+    assert!(sum(&output, "column_chunk_cache_hits") > 0);
+    assert_eq!(sum(&output, "column_chunk_cache_misses"), 0);
+    assert_eq!(sum(&output, "bytes_scanned"), bytes_scanned_without_cache);
+    assert!(
+        context
+            .ctx
+            .runtime_env()
+            .cache_manager
+            .get_file_range_cache()
+            .len()
+            > 0
+    );
+}
+
 #[tokio::test]
 // Data layout like this:
 // row_group1: page1(1~5), page2(All Null)
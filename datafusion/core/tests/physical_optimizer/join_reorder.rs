@@ -0,0 +1,247 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::datatypes::{DataType, Field, Schema};
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::stats::Precision;
+use datafusion_common::{
+    ColumnStatistics, JoinType, NullEquality, Result, ScalarValue, Statistics,
+};
+use datafusion_physical_expr::expressions::{col, Column};
+use datafusion_physical_optimizer::join_reorder::JoinReorder;
+use datafusion_physical_optimizer::PhysicalOptimizerRule;
+use datafusion_physical_plan::joins::{HashJoinExec, PartitionMode};
+use datafusion_physical_plan::projection::ProjectionExec;
+use datafusion_physical_plan::test::exec::StatisticsExec;
+use datafusion_physical_plan::{displayable, ExecutionPlan};
+use insta::assert_snapshot;
+
+/// Column statistics of a `UInt64` column with values in `0..=max`
+fn column_stats(max: u64, distinct_count: usize) -> ColumnStatistics {
+    ColumnStatistics {
+        distinct_count: Precision::Inexact(distinct_count),
+        min_value: Precision::Inexact(ScalarValue::UInt64(Some(0))),
+        max_value: Precision::Inexact(ScalarValue::UInt64(Some(max))),
+        ..Default::default()
+    }
+}
+
+/// Creates a table with `UInt64` columns, with the given column statistics
+fn table(
+    num_rows: Option<usize>,
+    columns: Vec<(&str, ColumnStatistics)>,
+) -> Arc<dyn ExecutionPlan> {
+    let schema = Schema::new(
+        columns
+            .iter()
+            .map(|(name, _)| Field::new(*name, DataType::UInt64, true))
+            .collect::<Vec<_>>(),
+    );
+    let statistics = Statistics {
+        num_rows: num_rows
+            .map(Precision::Inexact)
+            .unwrap_or(Precision::Absent),
+        total_byte_size: Precision::Absent,
+        column_statistics: columns.into_iter().map(|(_, stats)| stats).collect(),
+    };
+    Arc::new(StatisticsExec::new(statistics, schema))
+}
+
+fn hash_join(
+    left: Arc<dyn ExecutionPlan>,
+    right: Arc<dyn ExecutionPlan>,
+    on: &[(&str, &str)],
+) -> Result<Arc<dyn ExecutionPlan>> {
+    hash_join_with_mode(left, right, on, PartitionMode::CollectLeft)
+}
+
+fn hash_join_with_mode(
+    left: Arc<dyn ExecutionPlan>,
+    right: Arc<dyn ExecutionPlan>,
+    on: &[(&str, &str)],
+    mode: PartitionMode,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let on = on
+        .iter()
+        .map(|(l, r)| Ok((col(l, &left.schema())?, col(r, &right.schema())?)))
+        .collect::<Result<_>>()?;
+    Ok(Arc::new(HashJoinExec::try_new(
+        left,
+        right,
+        on,
+        None,
+        &JoinType::Inner,
+        None,
+        mode,
+        NullEquality::NullEqualsNothing,
+    )?))
+}
+
+/// A fact table with one million rows, joined with three dimension tables in
+/// the written order `((fact ⋈ d1) ⋈ d2) ⋈ d3`, where only a few rows of `d2`
+/// and `d3` match
+fn star_join(fact_rows: Option<usize>) -> Result<Arc<dyn ExecutionPlan>> {
+    let fact = table(
+        fact_rows,
+        vec![
+            ("f_d1", column_stats(999, 1000)),
+            ("f_d2", column_stats(999, 1000)),
+            ("f_d3", column_stats(999, 1000)),
+        ],
+    );
+    let d1 = table(Some(1000), vec![("d1_id", column_stats(999, 1000))]);
+    let d2 = table(Some(10), vec![("d2_id", column_stats(999, 10))]);
+    let d3 = table(Some(100), vec![("d3_id", column_stats(999, 100))]);
+
+    let join = hash_join(fact, d1, &[("f_d1", "d1_id")])?;
+    let join = hash_join(join, d2, &[("f_d2", "d2_id")])?;
+    hash_join(join, d3, &[("f_d3", "d3_id")])
+}
+
+fn optimize(plan: Arc<dyn ExecutionPlan>, config: &ConfigOptions) -> Result<String> {
+    let optimized = JoinReorder::new().optimize(Arc::clone(&plan), config)?;
+    assert_eq!(optimized.schema(), plan.schema());
+    let formatted = displayable(optimized.as_ref()).indent(true).to_string();
+    Ok(formatted)
+}
+
+#[test]
+fn test_star_join_selective_dimensions_first() -> Result<()> {
+    let plan = optimize(star_join(Some(1_000_000))?, &ConfigOptions::new())?;
+    assert_snapshot!(plan, @r"
+    ProjectionExec: expr=[f_d1@0 as f_d1, f_d2@1 as f_d2, f_d3@2 as f_d3, d1_id@5 as d1_id, d2_id@3 as d2_id, d3_id@4 as d3_id]
+      HashJoinExec: mode=CollectLeft, join_type=Inner, on=[(f_d1@0, d1_id@0)]
+        HashJoinExec: mode=CollectLeft, join_type=Inner, on=[(f_d3@2, d3_id@0)]
+          HashJoinExec: mode=CollectLeft, join_type=Inner, on=[(f_d2@1, d2_id@0)]
+            StatisticsExec: col_count=3, row_count=Inexact(1000000)
+            StatisticsExec: col_count=1, row_count=Inexact(10)
+          StatisticsExec: col_count=1, row_count=Inexact(100)
+        StatisticsExec: col_count=1, row_count=Inexact(1000)
+    ");
+    Ok(())
+}
+
+#[test]
+fn test_dynamic_programming_and_greedy_agree() -> Result<()> {
+    let mut config = ConfigOptions::new();
+    config.optimizer.join_reordering_dp_threshold = 8;
+    let dynamic_programming = optimize(star_join(Some(1_000_000))?, &config)?;
+    config.optimizer.join_reordering_dp_threshold = 0;
+    let greedy = optimize(star_join(Some(1_000_000))?, &config)?;
+    assert_eq!(dynamic_programming, greedy);
+    Ok(())
+}
+
+#[test]
+fn test_no_reordering_without_statistics() -> Result<()> {
+    let plan = star_join(None)?;
+    let expected = displayable(plan.as_ref()).indent(true).to_string();
+    assert_eq!(optimize(plan, &ConfigOptions::new())?, expected);
+    Ok(())
+}
+
+#[test]
+fn test_no_reordering_when_disabled() -> Result<()> {
+    let plan = star_join(Some(1_000_000))?;
+    let expected = displayable(plan.as_ref()).indent(true).to_string();
+    let mut config = ConfigOptions::new();
+    config.optimizer.enable_join_reordering = false;
+    assert_eq!(optimize(plan, &config)?, expected);
+    Ok(())
+}
+
+#[test]
+fn test_keep_written_order_with_lowest_cost() -> Result<()> {
+    let a = table(Some(10), vec![("a", column_stats(999, 10))]);
+    let b = table(Some(1000), vec![("b", column_stats(999, 1000))]);
+    let c = table(Some(100_000), vec![("c", column_stats(999, 1000))]);
+    let join = hash_join(a, b, &[("a", "b")])?;
+    let plan = hash_join(join, c, &[("b", "c")])?;
+    let expected = displayable(plan.as_ref()).indent(true).to_string();
+    assert_eq!(optimize(plan, &ConfigOptions::new())?, expected);
+    Ok(())
+}
+
+#[test]
+fn test_reorder_inputs_of_kept_join_tree() -> Result<()> {
+    // The outer join tree is kept as written as `e` has no statistics, but the
+    // join tree below the projection is still reordered
+    let star = star_join(Some(1_000_000))?;
+    let exprs = star
+        .schema()
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            (
+                Arc::new(Column::new(field.name(), index)) as _,
+                field.name().to_string(),
+            )
+        })
+        .collect::<Vec<_>>();
+    let star = Arc::new(ProjectionExec::try_new(exprs, star)?);
+    let e = table(None, vec![("e", column_stats(999, 1000))]);
+    let f = table(Some(10), vec![("f", column_stats(999, 10))]);
+    let join = hash_join(star, e, &[("f_d1", "e")])?;
+    let plan = optimize(hash_join(join, f, &[("e", "f")])?, &ConfigOptions::new())?;
+    assert_snapshot!(plan, @r"
+    HashJoinExec: mode=CollectLeft, join_type=Inner, on=[(e@6, f@0)]
+      HashJoinExec: mode=CollectLeft, join_type=Inner, on=[(f_d1@0, e@0)]
+        ProjectionExec: expr=[f_d1@0 as f_d1, f_d2@1 as f_d2, f_d3@2 as f_d3, d1_id@3 as d1_id, d2_id@4 as d2_id, d3_id@5 as d3_id]
+          ProjectionExec: expr=[f_d1@0 as f_d1, f_d2@1 as f_d2, f_d3@2 as f_d3, d1_id@5 as d1_id, d2_id@3 as d2_id, d3_id@4 as d3_id]
+            HashJoinExec: mode=CollectLeft, join_type=Inner, on=[(f_d1@0, d1_id@0)]
+              HashJoinExec: mode=CollectLeft, join_type=Inner, on=[(f_d3@2, d3_id@0)]
+                HashJoinExec: mode=CollectLeft, join_type=Inner, on=[(f_d2@1, d2_id@0)]
+                  StatisticsExec: col_count=3, row_count=Inexact(1000000)
+                  StatisticsExec: col_count=1, row_count=Inexact(10)
+                StatisticsExec: col_count=1, row_count=Inexact(100)
+              StatisticsExec: col_count=1, row_count=Inexact(1000)
+        StatisticsExec: col_count=1, row_count=Absent
+      StatisticsExec: col_count=1, row_count=Inexact(10)
+    ");
+    Ok(())
+}
+
+#[test]
+fn test_mixed_partition_modes_use_auto() -> Result<()> {
+    let fact = table(
+        Some(1_000_000),
+        vec![
+            ("f_d1", column_stats(999, 1000)),
+            ("f_d2", column_stats(999, 1000)),
+        ],
+    );
+    let d1 = table(Some(1000), vec![("d1_id", column_stats(999, 1000))]);
+    let d2 = table(Some(10), vec![("d2_id", column_stats(999, 10))]);
+    let join =
+        hash_join_with_mode(fact, d1, &[("f_d1", "d1_id")], PartitionMode::Partitioned)?;
+    let plan = optimize(
+        hash_join(join, d2, &[("f_d2", "d2_id")])?,
+        &ConfigOptions::new(),
+    )?;
+    assert_snapshot!(plan, @r"
+    ProjectionExec: expr=[f_d1@0 as f_d1, f_d2@1 as f_d2, d1_id@3 as d1_id, d2_id@2 as d2_id]
+      HashJoinExec: mode=Auto, join_type=Inner, on=[(f_d1@0, d1_id@0)]
+        HashJoinExec: mode=Auto, join_type=Inner, on=[(f_d2@1, d2_id@0)]
+          StatisticsExec: col_count=2, row_count=Inexact(1000000)
+          StatisticsExec: col_count=1, row_count=Inexact(10)
+        StatisticsExec: col_count=1, row_count=Inexact(1000)
+    ");
+    Ok(())
+}
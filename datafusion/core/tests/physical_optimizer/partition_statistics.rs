@@ -124,6 +124,7 @@ mod test {
             min_value: Precision::Exact(ScalarValue::Int32(Some(min_value))),
             sum_value: Precision::Absent,
             distinct_count: Precision::Absent,
+            histogram: None,
         }];
 
         if include_date_column {
@@ -133,6 +134,7 @@ mod test {
                 min_value: Precision::Absent,
                 sum_value: Precision::Absent,
                 distinct_count: Precision::Absent,
+                histogram: None,
             });
         }
 
@@ -332,6 +334,7 @@ mod test {
                     min_value: Precision::Exact(ScalarValue::Null),
                     sum_value: Precision::Exact(ScalarValue::Null),
                     distinct_count: Precision::Exact(0),
+                    histogram: None,
                 },
                 ColumnStatistics {
                     null_count: Precision::Exact(0),
@@ -339,6 +342,7 @@ mod test {
                     min_value: Precision::Exact(ScalarValue::Null),
                     sum_value: Precision::Exact(ScalarValue::Null),
                     distinct_count: Precision::Exact(0),
+                    histogram: None,
                 },
             ],
         };
@@ -412,6 +416,7 @@ mod test {
                 min_value: Precision::Exact(ScalarValue::Int32(Some(3))),
                 sum_value: Precision::Absent,
                 distinct_count: Precision::Absent,
+                histogram: None,
             });
         let mut expected_statistic_partition_2 =
             create_partition_statistics(8, 48400, 1, 4, true);
@@ -423,6 +428,7 @@ mod test {
                 min_value: Precision::Exact(ScalarValue::Int32(Some(1))),
                 sum_value: Precision::Absent,
                 distinct_count: Precision::Absent,
+                histogram: None,
             });
         assert_eq!(statistics[0], expected_statistic_partition_1);
         assert_eq!(statistics[1], expected_statistic_partition_2);
@@ -578,6 +584,7 @@ mod test {
                     min_value: Precision::Exact(ScalarValue::Int32(Some(3))),
                     sum_value: Precision::Absent,
                     distinct_count: Precision::Absent,
+                    histogram: None,
                 },
                 ColumnStatistics::new_unknown(),
                 ColumnStatistics::new_unknown(),
@@ -596,6 +603,7 @@ mod test {
                     min_value: Precision::Exact(ScalarValue::Int32(Some(1))),
                     sum_value: Precision::Absent,
                     distinct_count: Precision::Absent,
+                    histogram: None,
                 },
                 ColumnStatistics::new_unknown(),
                 ColumnStatistics::new_unknown(),
@@ -233,6 +233,30 @@ async fn test_test_metadata_cache_limit() {
     assert_eq!(get_limit(&ctx), 123 * 1024);
 }
 
+#[tokio::test]
+async fn test_file_range_cache_limit() {
+    let ctx = SessionContext::new();
+
+    let get_limit = |ctx: &SessionContext| -> usize {
+        ctx.task_ctx()
+            .runtime_env()
+            .cache_manager
+            .get_file_range_cache()
+            .cache_limit()
+    };
+
+    // disabled by default
+    assert_eq!(get_limit(&ctx), 0);
+
+    ctx.sql("SET datafusion.runtime.file_range_cache_limit = '256M'")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    assert_eq!(get_limit(&ctx), 256 * 1024 * 1024);
+}
+
 #[tokio::test]
 async fn test_unknown_runtime_config() {
     let ctx = SessionContext::new();
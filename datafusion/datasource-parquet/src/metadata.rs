@@ -400,6 +400,7 @@ fn get_col_stats(
                 min_value: min_value.unwrap_or(Precision::Absent),
                 sum_value: Precision::Absent,
                 distinct_count: Precision::Absent,
+                histogram: None,
             }
         })
         .collect()
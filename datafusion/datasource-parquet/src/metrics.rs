@@ -79,6 +79,16 @@ pub struct ParquetFileMetrics {
     /// number of rows that were stored in the cache after evaluating predicates
     /// reused for the output.
     pub predicate_cache_records: Count,
+    /// Number of byte ranges, such as column chunks, read from the
+    /// [`FileRangeCache`] instead of the file
+    ///
+    /// [`FileRangeCache`]: datafusion_execution::cache::cache_manager::FileRangeCache
+    pub column_chunk_cache_hits: Count,
+    /// Number of byte ranges, such as column chunks, that were not in the
+    /// [`FileRangeCache`] and had to be read from the file
+    ///
+    /// [`FileRangeCache`]: datafusion_execution::cache::cache_manager::FileRangeCache
+    pub column_chunk_cache_misses: Count,
 }
 
 impl ParquetFileMetrics {
@@ -155,6 +165,14 @@ impl ParquetFileMetrics {
             .with_new_label("filename", filename.to_string())
             .counter("predicate_cache_records", partition);
 
+        let column_chunk_cache_hits = MetricBuilder::new(metrics)
+            .with_new_label("filename", filename.to_string())
+            .counter("column_chunk_cache_hits", partition);
+
+        let column_chunk_cache_misses = MetricBuilder::new(metrics)
+            .with_new_label("filename", filename.to_string())
+            .counter("column_chunk_cache_misses", partition);
+
         Self {
             files_ranges_pruned_statistics,
             predicate_evaluation_errors,
@@ -174,6 +192,8 @@ impl ParquetFileMetrics {
             metadata_load_time,
             predicate_cache_inner_records,
             predicate_cache_records,
+            column_chunk_cache_hits,
+            column_chunk_cache_misses,
         }
     }
 }
@@ -38,5 +38,5 @@ pub use page_filter::PagePruningAccessPlanFilter;
 pub use reader::*; // Expose so downstream crates can use it
 pub use row_filter::build_row_filter;
 pub use row_filter::can_expr_be_pushed_down_with_schemas;
-pub use row_group_filter::RowGroupAccessPlanFilter;
+pub use row_group_filter::{RowGroupAccessPlanFilter, RowGroupSample};
 pub use writer::plan_to_parquet;
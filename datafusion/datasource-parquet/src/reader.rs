@@ -24,6 +24,7 @@ use bytes::Bytes;
 use datafusion_datasource::file_meta::FileMeta;
 use datafusion_execution::cache::cache_manager::FileMetadata;
 use datafusion_execution::cache::cache_manager::FileMetadataCache;
+use datafusion_execution::cache::cache_manager::{FileRangeCache, FileRangeKey};
 use datafusion_physical_plan::metrics::ExecutionPlanMetricsSet;
 use futures::future::BoxFuture;
 use futures::FutureExt;
@@ -162,10 +163,14 @@ impl ParquetFileReaderFactory for DefaultParquetFileReaderFactory {
 /// This reader always loads the entire metadata (including page index, unless the file is
 /// encrypted), even if not required by the current query, to ensure it is always available for
 /// those that need it.
+///
+/// If a [`FileRangeCache`] with a non zero limit is provided, the column chunks and pages read
+/// from the files are cached as well, so that repeated scans of the same data skip the I/O.
 #[derive(Debug)]
 pub struct CachedParquetFileReaderFactory {
     store: Arc<dyn ObjectStore>,
     metadata_cache: Arc<dyn FileMetadataCache>,
+    file_range_cache: Option<Arc<dyn FileRangeCache>>,
 }
 
 impl CachedParquetFileReaderFactory {
@@ -176,8 +181,18 @@ impl CachedParquetFileReaderFactory {
         Self {
             store,
             metadata_cache,
+            file_range_cache: None,
         }
     }
+
+    /// Set the [`FileRangeCache`] used to cache the byte ranges read from the files
+    pub fn with_file_range_cache(
+        mut self,
+        file_range_cache: Arc<dyn FileRangeCache>,
+    ) -> Self {
+        self.file_range_cache = Some(file_range_cache);
+        self
+    }
 }
 
 impl ParquetFileReaderFactory for CachedParquetFileReaderFactory {
@@ -210,6 +225,7 @@ impl ParquetFileReaderFactory for CachedParquetFileReaderFactory {
             file_meta,
             metadata_cache: Arc::clone(&self.metadata_cache),
             metadata_size_hint,
+            file_range_cache: self.file_range_cache.clone(),
         }))
     }
 }
@@ -217,6 +233,8 @@ impl ParquetFileReaderFactory for CachedParquetFileReaderFactory {
 /// Implements [`AsyncFileReader`] for a Parquet file in object storage. Reads the file metadata
 /// from the [`FileMetadataCache`], if available, otherwise reads it directly from the file and then
 /// updates the cache.
+///
+/// Byte ranges are similarly read from the [`FileRangeCache`], if it is enabled.
 pub struct CachedParquetFileReader {
     pub file_metrics: ParquetFileMetrics,
     store: Arc<dyn ObjectStore>,
@@ -224,6 +242,69 @@ pub struct CachedParquetFileReader {
     file_meta: FileMeta,
     metadata_cache: Arc<dyn FileMetadataCache>,
     metadata_size_hint: Option<usize>,
+    file_range_cache: Option<Arc<dyn FileRangeCache>>,
+}
+
+impl CachedParquetFileReader {
+    /// Returns the [`FileRangeCache`], unless it is not set or disabled
+    fn enabled_file_range_cache(&self) -> Option<Arc<dyn FileRangeCache>> {
+        self.file_range_cache
+            .as_ref()
+            .filter(|cache| cache.cache_limit() > 0)
+            .map(Arc::clone)
+    }
+
+    /// Reads `ranges` from the `file_range_cache`, and the missing ones from the file,
+    /// adding them to the cache.
+    async fn get_cached_byte_ranges(
+        &mut self,
+        file_range_cache: Arc<dyn FileRangeCache>,
+        ranges: Vec<Range<u64>>,
+    ) -> parquet::errors::Result<Vec<Bytes>> {
+        let object_meta = &self.file_meta.object_meta;
+        let key = |range: Range<u64>| FileRangeKey {
+            location: object_meta.location.clone(),
+            range,
+        };
+
+        let mut result: Vec<Option<Bytes>> = ranges
+            .iter()
+            .map(|range| {
+                file_range_cache.get_with_extra(&key(range.clone()), object_meta)
+            })
+            .collect();
+        let missing: Vec<Range<u64>> = ranges
+            .into_iter()
+            .zip(&result)
+            .filter(|(_, cached)| cached.is_none())
+            .map(|(range, _)| range)
+            .collect();
+        self.file_metrics
+            .column_chunk_cache_hits
+            .add(result.len() - missing.len());
+        self.file_metrics
+            .column_chunk_cache_misses
+            .add(missing.len());
+
+        if !missing.is_empty() {
+            let fetched = self.inner.get_byte_ranges(missing.clone()).await?;
+            let mut fetched = missing.into_iter().zip(fetched);
+            for slot in result.iter_mut().filter(|slot| slot.is_none()) {
+                let (range, bytes) = fetched.next().ok_or_else(|| {
+                    parquet::errors::ParquetError::General(
+                        "Fewer byte ranges returned than requested".to_string(),
+                    )
+                })?;
+                // The fetched ranges may be slices of a larger coalesced read,
+                // copy them so the cache does not keep the whole read alive
+                let bytes = Bytes::copy_from_slice(&bytes);
+                file_range_cache.put_with_extra(&key(range), bytes.clone(), object_meta);
+                *slot = Some(bytes);
+            }
+        }
+
+        Ok(result.into_iter().flatten().collect())
+    }
 }
 
 impl AsyncFileReader for CachedParquetFileReader {
@@ -233,7 +314,16 @@ impl AsyncFileReader for CachedParquetFileReader {
     ) -> BoxFuture<'_, parquet::errors::Result<Bytes>> {
         let bytes_scanned = range.end - range.start;
         self.file_metrics.bytes_scanned.add(bytes_scanned as usize);
-        self.inner.get_bytes(range)
+        let Some(file_range_cache) = self.enabled_file_range_cache() else {
+            return self.inner.get_bytes(range);
+        };
+        async move {
+            let mut bytes = self
+                .get_cached_byte_ranges(file_range_cache, vec![range])
+                .await?;
+            Ok(bytes.remove(0))
+        }
+        .boxed()
     }
 
     fn get_byte_ranges(
@@ -245,7 +335,11 @@ impl AsyncFileReader for CachedParquetFileReader {
     {
         let total: u64 = ranges.iter().map(|r| r.end - r.start).sum();
         self.file_metrics.bytes_scanned.add(total as usize);
-        self.inner.get_byte_ranges(ranges)
+        let Some(file_range_cache) = self.enabled_file_range_cache() else {
+            return self.inner.get_byte_ranges(ranges);
+        };
+        self.get_cached_byte_ranges(file_range_cache, ranges)
+            .boxed()
     }
 
     fn get_metadata<'a>(
@@ -16,6 +16,7 @@
 // under the License.
 
 use std::collections::{HashMap, HashSet};
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::sync::Arc;
 
 use super::{ParquetAccessPlan, ParquetFileMetrics};
@@ -35,6 +36,8 @@ use parquet::{
     bloom_filter::Sbbf,
     file::metadata::RowGroupMetaData,
 };
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
 /// Reduces the [`ParquetAccessPlan`] based on row group level metadata.
 ///
@@ -92,6 +95,29 @@ impl RowGroupAccessPlanFilter {
             }
         }
     }
+
+    /// Prune remaining row groups to a random [`RowGroupSample`]
+    ///
+    /// Every row group of the file is drawn, including already pruned ones,
+    /// so a seeded sample selects the same row groups no matter how the file
+    /// is split into ranges.
+    pub fn prune_by_sample(&mut self, sample: &RowGroupSample, file_name: &str) {
+        let mut rng = match sample.seed {
+            Some(seed) => {
+                let mut hasher = DefaultHasher::new();
+                seed.hash(&mut hasher);
+                file_name.hash(&mut hasher);
+                StdRng::seed_from_u64(hasher.finish())
+            }
+            None => StdRng::from_os_rng(),
+        };
+        for idx in 0..self.access_plan.len() {
+            if !rng.random_bool(sample.fraction) {
+                self.access_plan.skip(idx);
+            }
+        }
+    }
+
     /// Prune remaining row groups using min/max/null_count statistics and
     /// the [`PruningPredicate`] to determine if the predicate can not be true.
     ///
@@ -223,6 +249,17 @@ impl RowGroupAccessPlanFilter {
         }
     }
 }
+
+/// A random sample of the row groups of every file, pushed down from
+/// `TABLESAMPLE SYSTEM`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RowGroupSample {
+    /// Probability of reading a row group
+    pub fraction: f64,
+    /// Seed for the random number generator, combined with the file name
+    pub seed: Option<u64>,
+}
+
 /// Implements [`PruningStatistics`] for Parquet Split Block Bloom Filters (SBBF)
 struct BloomFilterStatistics {
     /// Maps column name to the parquet bloom filter and parquet physical type
@@ -539,6 +576,25 @@ mod tests {
         assert_pruned(row_groups, ExpectedPruning::Some(vec![1]))
     }
 
+    #[test]
+    fn row_group_pruning_sample() {
+        let sampled = |fraction: f64, seed: Option<u64>, file_name: &str| {
+            let mut row_groups =
+                RowGroupAccessPlanFilter::new(ParquetAccessPlan::new_all(100));
+            row_groups.prune_by_sample(&RowGroupSample { fraction, seed }, file_name);
+            row_groups.build().row_group_indexes()
+        };
+        assert!(sampled(0.0, None, "a.parquet").is_empty());
+        assert_eq!(sampled(1.0, None, "a.parquet").len(), 100);
+
+        // a seeded sample is repeatable per file
+        let first = sampled(0.5, Some(42), "a.parquet");
+        assert!(!first.is_empty() && first.len() < 100);
+        assert_eq!(first, sampled(0.5, Some(42), "a.parquet"));
+        assert_ne!(first, sampled(0.5, Some(42), "b.parquet"));
+        assert_ne!(first, sampled(0.5, Some(43), "a.parquet"));
+    }
+
     #[test]
     fn row_group_pruning_predicate_missing_stats() {
         use datafusion_expr::{col, lit};
@@ -24,6 +24,7 @@ use std::sync::Arc;
 use crate::opener::build_pruning_predicates;
 use crate::opener::ParquetOpener;
 use crate::row_filter::can_expr_be_pushed_down_with_schemas;
+use crate::row_group_filter::RowGroupSample;
 use crate::DefaultParquetFileReaderFactory;
 use crate::ParquetFileReaderFactory;
 use datafusion_common::config::ConfigOptions;
@@ -289,6 +290,8 @@ pub struct ParquetSource {
     pub(crate) projected_statistics: Option<Statistics>,
     #[cfg(feature = "parquet_encryption")]
     pub(crate) encryption_factory: Option<Arc<dyn EncryptionFactory>>,
+    /// Optional random sample of the row groups to read
+    pub(crate) sample: Option<RowGroupSample>,
 }
 
 impl ParquetSource {
@@ -337,6 +340,17 @@ impl ParquetSource {
         self
     }
 
+    /// Only read a random sample of the row groups of every file
+    pub fn with_sample(mut self, sample: RowGroupSample) -> Self {
+        self.sample = Some(sample);
+        self
+    }
+
+    /// Optional random sample of the row groups to read
+    pub fn sample(&self) -> Option<&RowGroupSample> {
+        self.sample.as_ref()
+    }
+
     /// Options passed to the parquet reader for this scan
     pub fn table_parquet_options(&self) -> &TableParquetOptions {
         &self.table_parquet_options
@@ -590,6 +604,7 @@ impl FileSource for ParquetSource {
             #[cfg(feature = "parquet_encryption")]
             encryption_factory: self.get_encryption_factory_with_config(),
             max_predicate_cache_size: self.max_predicate_cache_size(),
+            sample: self.sample,
         })
     }
 
@@ -638,10 +653,23 @@ impl FileSource for ParquetSource {
         // (bloom filters use `pruning_predicate` too).
         // Because filter pushdown may happen dynamically as long as there is a predicate
         // if we have *any* predicate applied, we can't guarantee the statistics are exact.
-        if self.predicate().is_some() {
-            Ok(statistics.to_inexact())
+        let statistics = if self.predicate().is_some() {
+            statistics.to_inexact()
         } else {
-            Ok(statistics)
+            statistics
+        };
+        match &self.sample {
+            Some(sample) => {
+                let mut statistics = statistics.to_inexact();
+                statistics.num_rows = statistics
+                    .num_rows
+                    .with_estimated_selectivity(sample.fraction);
+                statistics.total_byte_size = statistics
+                    .total_byte_size
+                    .with_estimated_selectivity(sample.fraction);
+                Ok(statistics)
+            }
+            None => Ok(statistics),
         }
     }
 
@@ -659,6 +687,13 @@ impl FileSource for ParquetSource {
 
                 write!(f, "{predicate_string}")?;
 
+                if let Some(sample) = &self.sample {
+                    write!(f, ", sample_fraction={}", sample.fraction)?;
+                    if let Some(seed) = sample.seed {
+                        write!(f, ", sample_seed={seed}")?;
+                    }
+                }
+
                 // Try to build a the pruning predicates.
                 // These are only generated here because it's useful to have *some*
                 // idea of what pushdown is happening when viewing plans.
@@ -696,6 +731,9 @@ impl FileSource for ParquetSource {
                 if let Some(predicate) = self.predicate() {
                     writeln!(f, "predicate={}", fmt_sql(predicate.as_ref()))?;
                 }
+                if let Some(sample) = &self.sample {
+                    writeln!(f, "sample_fraction={}", sample.fraction)?;
+                }
                 Ok(())
             }
         }
@@ -786,4 +824,13 @@ impl FileSource for ParquetSource {
     fn schema_adapter_factory(&self) -> Option<Arc<dyn SchemaAdapterFactory>> {
         self.schema_adapter_factory.clone()
     }
+
+    fn try_pushdown_sample(
+        &self,
+        fraction: f64,
+        seed: Option<u64>,
+    ) -> datafusion_common::Result<Option<Arc<dyn FileSource>>> {
+        let sample = RowGroupSample { fraction, seed };
+        Ok(Some(Arc::new(self.clone().with_sample(sample))))
+    }
 }
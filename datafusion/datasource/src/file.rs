@@ -129,6 +129,19 @@ pub trait FileSource: Send + Sync {
         ))
     }
 
+    /// Try to push a block level sample (`TABLESAMPLE SYSTEM`) down into this
+    /// FileSource, so that unsampled blocks are never read. Each block should
+    /// be kept with probability `fraction`, deterministically if `seed` is set.
+    ///
+    /// Returns `None` if sampling is not supported, which is the default.
+    fn try_pushdown_sample(
+        &self,
+        _fraction: f64,
+        _seed: Option<u64>,
+    ) -> Result<Option<Arc<dyn FileSource>>> {
+        Ok(None)
+    }
+
     /// Set optional schema adapter factory.
     ///
     /// [`SchemaAdapterFactory`] allows user to specify how fields from the
@@ -517,7 +517,9 @@ impl DataSource for FileScanConfig {
         let opener = source.create_file_opener(object_store, self, partition);
 
         let stream = FileStream::new(self, partition, opener, source.metrics())?;
-        Ok(Box::pin(cooperative(stream)))
+        Ok(Box::pin(
+            cooperative(stream).with_deadline(context.deadline()),
+        ))
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -688,6 +690,21 @@ impl DataSource for FileScanConfig {
             }
         }
     }
+
+    fn try_pushdown_sample(
+        &self,
+        fraction: f64,
+        seed: Option<u64>,
+    ) -> Result<Option<Arc<dyn DataSource>>> {
+        let Some(file_source) = self.file_source.try_pushdown_sample(fraction, seed)?
+        else {
+            return Ok(None);
+        };
+        let file_scan_config = FileScanConfigBuilder::from(self.clone())
+            .with_source(file_source)
+            .build();
+        Ok(Some(Arc::new(file_scan_config)))
+    }
 }
 
 impl FileScanConfig {
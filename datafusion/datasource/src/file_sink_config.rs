@@ -19,11 +19,14 @@ use std::sync::Arc;
 
 use crate::file_groups::FileGroup;
 use crate::sink::DataSink;
-use crate::write::demux::{start_demuxer_task, DemuxedStreamReceiver};
+use crate::write::demux::{
+    start_demuxer_task, track_demuxed_file_paths, DemuxedStreamReceiver,
+};
+use crate::write::orchestration::{abort_written_files, replace_existing_files};
 use crate::ListingTableUrl;
 
 use arrow::datatypes::{DataType, SchemaRef};
-use datafusion_common::Result;
+use datafusion_common::{DataFusionError, Result};
 use datafusion_common_runtime::SpawnedTask;
 use datafusion_execution::object_store::ObjectStoreUrl;
 use datafusion_execution::{SendableRecordBatchStream, TaskContext};
@@ -67,6 +70,12 @@ pub trait FileSink: DataSink {
     ) -> Result<u64>;
 
     /// File sink implementation of the [`DataSink::write_all`] method.
+    ///
+    /// For [`InsertOp::Overwrite`] and [`InsertOp::Replace`], the files listed in
+    /// [`FileSinkConfig::file_group`] are removed only after all new files have
+    /// been written successfully, so a failed write leaves the previous contents
+    /// of the table in place. When the table has partition columns, only the
+    /// existing files of partitions that received new rows are removed.
     async fn write_all(
         &self,
         data: SendableRecordBatchStream,
@@ -77,13 +86,41 @@ pub trait FileSink: DataSink {
             .runtime_env()
             .object_store(&config.object_store_url)?;
         let (demux_task, file_stream_rx) = start_demuxer_task(config, data, context);
-        self.spawn_writer_tasks_and_join(
-            context,
-            demux_task,
-            file_stream_rx,
-            object_store,
-        )
-        .await
+        if config.insert_op == InsertOp::Append {
+            return self
+                .spawn_writer_tasks_and_join(
+                    context,
+                    demux_task,
+                    file_stream_rx,
+                    object_store,
+                )
+                .await;
+        }
+
+        let (file_stream_rx, paths_task) = track_demuxed_file_paths(file_stream_rx);
+        let result = self
+            .spawn_writer_tasks_and_join(
+                context,
+                demux_task,
+                file_stream_rx,
+                Arc::clone(&object_store),
+            )
+            .await;
+        let written_files = paths_task
+            .join_unwind()
+            .await
+            .map_err(|e| DataFusionError::ExecutionJoin(Box::new(e)))?;
+
+        match result {
+            Ok(row_count) => {
+                replace_existing_files(config, &object_store, &written_files).await?;
+                Ok(row_count)
+            }
+            Err(e) => {
+                abort_written_files(config, &object_store, &written_files).await;
+                Err(e)
+            }
+        }
     }
 }
 
@@ -107,6 +144,9 @@ pub struct FileSinkConfig {
     pub table_partition_cols: Vec<(String, DataType)>,
     /// Controls how new data should be written to the file, determining whether
     /// to append to, overwrite, or replace records in existing files.
+    ///
+    /// Overwrites remove the files in [`Self::file_group`] once the new files
+    /// are written, see [`FileSink::write_all`].
     pub insert_op: InsertOp,
     /// Controls whether partition columns are kept for the file
     pub keep_partition_by_columns: bool,
@@ -78,16 +78,19 @@ impl DataSource for MemorySourceConfig {
     fn open(
         &self,
         partition: usize,
-        _context: Arc<TaskContext>,
+        context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
-        Ok(Box::pin(cooperative(
-            MemoryStream::try_new(
-                self.partitions[partition].clone(),
-                Arc::clone(&self.projected_schema),
-                self.projection.clone(),
-            )?
-            .with_fetch(self.fetch),
-        )))
+        Ok(Box::pin(
+            cooperative(
+                MemoryStream::try_new(
+                    self.partitions[partition].clone(),
+                    Arc::clone(&self.projected_schema),
+                    self.projection.clone(),
+                )?
+                .with_fetch(self.fetch),
+            )
+            .with_deadline(context.deadline()),
+        ))
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -959,6 +962,7 @@ mod tests {
                     max_value: Precision::Absent,
                     min_value: Precision::Absent,
                     sum_value: Precision::Absent,
+                    histogram: None,
                 },],
             }
         );
@@ -36,8 +36,10 @@ use itertools::Itertools;
 
 use crate::file_scan_config::FileScanConfig;
 use datafusion_common::config::ConfigOptions;
+use datafusion_common::stats::Precision;
 use datafusion_common::{Constraints, Result, Statistics};
 use datafusion_execution::{SendableRecordBatchStream, TaskContext};
+use datafusion_physical_expr::expressions::Column;
 use datafusion_physical_expr::{EquivalenceProperties, Partitioning, PhysicalExpr};
 use datafusion_physical_expr_common::sort_expr::LexOrdering;
 use datafusion_physical_plan::filter_pushdown::{
@@ -175,6 +177,17 @@ pub trait DataSource: Send + Sync + Debug {
             vec![PushedDown::No; filters.len()],
         ))
     }
+    /// Try to push a block level sample down into this DataSource, returning
+    /// `None` if not supported. See [`FileSource::try_pushdown_sample`].
+    ///
+    /// [`FileSource::try_pushdown_sample`]: crate::file::FileSource::try_pushdown_sample
+    fn try_pushdown_sample(
+        &self,
+        _fraction: f64,
+        _seed: Option<u64>,
+    ) -> Result<Option<Arc<dyn DataSource>>> {
+        Ok(None)
+    }
 }
 
 /// [`ExecutionPlan`] that reads one or more files
@@ -195,6 +208,9 @@ pub struct DataSourceExec {
     data_source: Arc<dyn DataSource>,
     /// Cached plan properties such as sort order
     cache: PlanProperties,
+    /// Statistics of the output columns computed by `ANALYZE TABLE`, which
+    /// complete the statistics of the data source
+    analyzed_statistics: Option<Arc<Statistics>>,
 }
 
 impl DisplayAs for DataSourceExec {
@@ -296,17 +312,37 @@ impl ExecutionPlan for DataSourceExec {
                     }
                 }
             }
-            Ok(statistics)
+            Ok(self.complete_statistics(statistics, true))
         } else {
-            Ok(self.data_source.statistics()?)
+            Ok(self.complete_statistics(self.data_source.statistics()?, false))
         }
     }
 
+    fn estimated_input_bytes(&self) -> Option<usize> {
+        let file_config = self.data_source.as_any().downcast_ref::<FileScanConfig>()?;
+        Some(
+            file_config
+                .file_groups
+                .iter()
+                .flat_map(|group| group.iter())
+                .map(|file| match &file.range {
+                    // Large files may be split into ranges scanned by different partitions
+                    Some(range) => (range.end - range.start) as usize,
+                    None => file.object_meta.size as usize,
+                })
+                .sum(),
+        )
+    }
+
     fn with_fetch(&self, limit: Option<usize>) -> Option<Arc<dyn ExecutionPlan>> {
         let data_source = self.data_source.with_fetch(limit)?;
         let cache = self.cache.clone();
 
-        Some(Arc::new(Self { data_source, cache }))
+        Some(Arc::new(Self {
+            data_source,
+            cache,
+            analyzed_statistics: self.analyzed_statistics.clone(),
+        }))
     }
 
     fn fetch(&self) -> Option<usize> {
@@ -322,7 +358,27 @@ impl ExecutionPlan for DataSourceExec {
             .try_swapping_with_projection(projection.expr())?
         {
             Some(new_data_source) => {
-                Ok(Some(Arc::new(DataSourceExec::new(new_data_source))))
+                // Keep the analyzed statistics if the projection only selects columns
+                let analyzed_statistics =
+                    self.analyzed_statistics.as_ref().and_then(|statistics| {
+                        let indices = projection
+                            .expr()
+                            .iter()
+                            .map(|expr| {
+                                expr.expr
+                                    .as_any()
+                                    .downcast_ref::<Column>()
+                                    .map(Column::index)
+                            })
+                            .collect::<Option<Vec<_>>>()?;
+                        Some(Arc::new(
+                            statistics.as_ref().clone().project(Some(&indices)),
+                        ))
+                    });
+                Ok(Some(Arc::new(
+                    DataSourceExec::new(new_data_source)
+                        .with_analyzed_statistics(analyzed_statistics),
+                )))
             }
             None => Ok(None),
         }
@@ -372,7 +428,11 @@ impl DataSourceExec {
     // Default constructor for `DataSourceExec`, setting the `cooperative` flag to `true`.
     pub fn new(data_source: Arc<dyn DataSource>) -> Self {
         let cache = Self::compute_properties(Arc::clone(&data_source));
-        Self { data_source, cache }
+        Self {
+            data_source,
+            cache,
+            analyzed_statistics: None,
+        }
     }
 
     /// Return the source object
@@ -386,6 +446,59 @@ impl DataSourceExec {
         self
     }
 
+    /// Assign the statistics of the output columns computed by `ANALYZE TABLE`.
+    ///
+    /// These complete the statistics of the data source: histograms are
+    /// added, and statistics the data source does not know are filled in.
+    pub fn with_analyzed_statistics(
+        mut self,
+        analyzed_statistics: Option<Arc<Statistics>>,
+    ) -> Self {
+        self.analyzed_statistics = analyzed_statistics;
+        self
+    }
+
+    /// Return the statistics of the output columns computed by `ANALYZE TABLE`
+    pub fn analyzed_statistics(&self) -> Option<&Arc<Statistics>> {
+        self.analyzed_statistics.as_ref()
+    }
+
+    /// Completes the statistics of the data source, or of one of its
+    /// partitions, with the analyzed statistics. Row and null counts only
+    /// apply to the whole data source.
+    fn complete_statistics(
+        &self,
+        mut statistics: Statistics,
+        is_partition: bool,
+    ) -> Statistics {
+        let Some(analyzed) = &self.analyzed_statistics else {
+            return statistics;
+        };
+        if !is_partition && statistics.num_rows == Precision::Absent {
+            statistics.num_rows = analyzed.num_rows.to_inexact();
+        }
+        for (column, analyzed) in statistics
+            .column_statistics
+            .iter_mut()
+            .zip(&analyzed.column_statistics)
+        {
+            if !is_partition && column.null_count == Precision::Absent {
+                column.null_count = analyzed.null_count.to_inexact();
+            }
+            if column.min_value == Precision::Absent {
+                column.min_value = analyzed.min_value.clone().to_inexact();
+            }
+            if column.max_value == Precision::Absent {
+                column.max_value = analyzed.max_value.clone().to_inexact();
+            }
+            if column.distinct_count == Precision::Absent {
+                column.distinct_count = analyzed.distinct_count.to_inexact();
+            }
+            column.histogram = analyzed.histogram.clone();
+        }
+        statistics
+    }
+
     /// Assign constraints
     pub fn with_constraints(mut self, constraints: Constraints) -> Self {
         self.cache = self.cache.with_constraints(constraints);
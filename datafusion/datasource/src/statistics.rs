@@ -370,6 +370,7 @@ pub async fn get_statistics_with_limit(
                         min_value: file_min,
                         sum_value: file_sum,
                         distinct_count: _,
+                        histogram: _,
                     } = file_col_stats;
 
                     col_stats.null_count = col_stats.null_count.add(file_nc);
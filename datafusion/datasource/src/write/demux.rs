@@ -136,6 +136,29 @@ pub(crate) fn start_demuxer_task(
     (task, rx)
 }
 
+/// Forwards the file streams produced by a demuxer task, recording the path of
+/// every output file as it is opened.
+///
+/// The returned task completes once the demuxer has closed its channel and
+/// yields the paths of all files the demuxer created. This is used to determine
+/// which hive style partitions were touched when overwriting a table.
+pub(crate) fn track_demuxed_file_paths(
+    mut file_stream_rx: DemuxedStreamReceiver,
+) -> (DemuxedStreamReceiver, SpawnedTask<Vec<Path>>) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let task = SpawnedTask::spawn(async move {
+        let mut paths = vec![];
+        while let Some((path, rb_stream)) = file_stream_rx.recv().await {
+            paths.push(path.clone());
+            if tx.send((path, rb_stream)).is_err() {
+                break;
+            }
+        }
+        paths
+    });
+    (rx, task)
+}
+
 /// Dynamically partitions input stream to achieve desired maximum rows per file
 async fn row_count_demuxer(
     mut tx: UnboundedSender<(Path, Receiver<RecordBatch>)>,
@@ -19,11 +19,13 @@
 //! orchestrating file serialization, streaming to object store,
 //! parallelization, and abort handling
 
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use super::demux::DemuxedStreamReceiver;
 use super::{BatchSerializer, ObjectWriterBuilder};
 use crate::file_compression_type::FileCompressionType;
+use crate::file_sink_config::FileSinkConfig;
 use datafusion_common::error::Result;
 
 use arrow::array::RecordBatch;
@@ -33,6 +35,7 @@ use datafusion_execution::TaskContext;
 
 use bytes::Bytes;
 use futures::join;
+use object_store::path::Path;
 use object_store::ObjectStore;
 use tokio::io::{AsyncWrite, AsyncWriteExt};
 use tokio::sync::mpsc::{self, Receiver};
@@ -293,3 +296,135 @@ pub async fn spawn_writer_tasks_and_join(
         internal_datafusion_err!("Did not receive row count from write coordinator")
     })
 }
+
+/// Completes an overwrite of the table described by `config` by removing the
+/// files that existed before the write, once all `written_files` have been
+/// successfully finalized.
+///
+/// For tables without partition columns every previously existing file is
+/// removed. For hive style partitioned tables only files located in a partition
+/// that received new data are removed, leaving all other partitions untouched
+/// (dynamic partition overwrite).
+pub(crate) async fn replace_existing_files(
+    config: &FileSinkConfig,
+    object_store: &Arc<dyn ObjectStore>,
+    written_files: &[Path],
+) -> Result<()> {
+    let written: HashSet<&Path> = written_files.iter().collect();
+    let written_partitions = if config.table_partition_cols.is_empty() {
+        None
+    } else {
+        Some(
+            written_files
+                .iter()
+                .map(|path| {
+                    let parts = path.parts().collect::<Vec<_>>();
+                    Path::from_iter(parts[..parts.len().saturating_sub(1)].to_vec())
+                })
+                .collect::<HashSet<_>>(),
+        )
+    };
+
+    for file in config.file_group.iter() {
+        let location = &file.object_meta.location;
+        if written.contains(location) {
+            // a single file table was rewritten in place
+            continue;
+        }
+        let replaced = match &written_partitions {
+            None => true,
+            Some(partitions) => partitions
+                .iter()
+                .any(|partition| location.prefix_match(partition).is_some()),
+        };
+        if !replaced {
+            continue;
+        }
+        match object_store.delete(location).await {
+            Ok(()) | Err(object_store::Error::NotFound { .. }) => {}
+            Err(e) => {
+                return Err(DataFusionError::Execution(format!(
+                    "Error removing replaced file {location} from object store: {e}"
+                )))
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Removes files written by a failed overwrite of the table described by
+/// `config` so that the previous contents of the table remain the only
+/// visible data.
+///
+/// Paths that were part of the table before the write, such as a single file
+/// table being rewritten in place, are never removed: a file is only replaced
+/// once its upload completes, so such a path still holds either the previous
+/// or the complete new contents.
+///
+/// Cleanup is best effort: files that were never finalized do not exist in the
+/// object store, and any error encountered while deleting is ignored.
+pub(crate) async fn abort_written_files(
+    config: &FileSinkConfig,
+    object_store: &Arc<dyn ObjectStore>,
+    written_files: &[Path],
+) {
+    let existing: HashSet<&Path> = config
+        .file_group
+        .iter()
+        .map(|file| &file.object_meta.location)
+        .collect();
+    for location in written_files {
+        if existing.contains(location) {
+            continue;
+        }
+        let _ = object_store.delete(location).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_groups::FileGroup;
+    use crate::{ListingTableUrl, PartitionedFile};
+
+    use arrow::datatypes::Schema;
+    use datafusion_execution::object_store::ObjectStoreUrl;
+    use datafusion_expr::dml::InsertOp;
+    use object_store::memory::InMemory;
+
+    #[tokio::test]
+    async fn abort_keeps_existing_files() -> Result<()> {
+        let object_store: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let existing = Path::from("table/data.parquet");
+        let written = Path::from("table/abc_0.parquet");
+        for location in [&existing, &written] {
+            object_store
+                .put(location, Bytes::from("data").into())
+                .await?;
+        }
+
+        let config = FileSinkConfig {
+            original_url: String::default(),
+            object_store_url: ObjectStoreUrl::parse("memory://")?,
+            file_group: FileGroup::new(vec![PartitionedFile::new(
+                existing.to_string(),
+                4,
+            )]),
+            table_paths: vec![ListingTableUrl::parse("memory:///table/")?],
+            output_schema: Arc::new(Schema::empty()),
+            table_partition_cols: vec![],
+            insert_op: InsertOp::Overwrite,
+            keep_partition_by_columns: false,
+            file_extension: "parquet".into(),
+        };
+        abort_written_files(&config, &object_store, &[existing.clone(), written.clone()])
+            .await;
+
+        assert!(object_store.head(&existing).await.is_ok());
+        assert!(matches!(
+            object_store.head(&written).await,
+            Err(object_store::Error::NotFound { .. })
+        ));
+        Ok(())
+    }
+}
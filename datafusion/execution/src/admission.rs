@@ -0,0 +1,526 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`AdmissionController`] for queueing queries before they start executing
+
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use crate::memory_pool::human_readable_size;
+use crate::{RecordBatchStream, SendableRecordBatchStream};
+
+use arrow::array::RecordBatch;
+use arrow::datatypes::SchemaRef;
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::{resources_datafusion_err, Result};
+use futures::Stream;
+use log::debug;
+use parking_lot::Mutex;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+/// Limits the number of concurrently executing queries, and their total
+/// estimated memory, queueing the queries that exceed the limits until
+/// enough running queries complete.
+///
+/// Without admission control, all queries start as soon as they arrive and
+/// compete for the same [`MemoryPool`], so that under load many of them fail
+/// with resources exhausted errors instead of waiting for their turn.
+///
+/// Queries are admitted in order of their [`AdmissionRequest::priority`]
+/// (highest first) and then of arrival. A query is only admitted once all
+/// queries ahead of it in the queue have been admitted, so that large
+/// queries are not starved by a stream of smaller ones. A query estimated to
+/// need more memory than [`Self::with_memory_limit`] is admitted once no
+/// other query is running.
+///
+/// Queries that wait longer than [`Self::with_queue_timeout`] are removed from
+/// the queue with a [`DataFusionError::ResourcesExhausted`] error.
+///
+/// # Example
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use std::time::Duration;
+/// # use datafusion_execution::admission::{AdmissionController, AdmissionRequest};
+/// # use datafusion_execution::runtime_env::RuntimeEnvBuilder;
+/// # use datafusion_common::Result;
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> Result<()> {
+/// let controller = Arc::new(
+///     AdmissionController::new()
+///         .with_max_concurrent_queries(8)
+///         .with_memory_limit(4 * 1024 * 1024 * 1024)
+///         .with_queue_timeout(Duration::from_secs(30)),
+/// );
+///
+/// // Queries executed through a `SessionContext` using this runtime are
+/// // admitted by the controller
+/// let runtime = RuntimeEnvBuilder::new()
+///     .with_admission_controller(Arc::clone(&controller))
+///     .build_arc()?;
+///
+/// // Queries can also be admitted explicitly
+/// let permit = controller
+///     .admit(AdmissionRequest::new().with_estimated_memory(1024 * 1024))
+///     .await?;
+/// assert_eq!(controller.running_queries(), 1);
+/// drop(permit);
+/// assert_eq!(controller.running_queries(), 0);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`MemoryPool`]: crate::memory_pool::MemoryPool
+/// [`DataFusionError::ResourcesExhausted`]: datafusion_common::DataFusionError::ResourcesExhausted
+#[derive(Debug)]
+pub struct AdmissionController {
+    /// The maximum number of queries running at the same time
+    max_concurrent_queries: usize,
+    /// The maximum total estimated memory of the queries running at the same time
+    memory_limit: usize,
+    /// The estimated memory of queries that do not specify one
+    default_memory_estimate: usize,
+    /// The maximum time a query waits in the queue
+    queue_timeout: Option<Duration>,
+    state: Mutex<AdmissionState>,
+    /// Notified whenever the running queries or the queue change
+    notify: Notify,
+}
+
+#[derive(Debug, Default)]
+struct AdmissionState {
+    /// The number of running queries
+    running: usize,
+    /// The total estimated memory of the running queries
+    reserved_memory: usize,
+    /// The waiting queries, ordered by descending priority and arrival
+    queue: BTreeSet<(Reverse<i64>, u64)>,
+    /// The arrival sequence number of the next query
+    next_sequence: u64,
+}
+
+impl Default for AdmissionController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AdmissionController {
+    /// Create a new [`AdmissionController`] that admits all queries immediately
+    pub fn new() -> Self {
+        Self {
+            max_concurrent_queries: usize::MAX,
+            memory_limit: usize::MAX,
+            default_memory_estimate: 0,
+            queue_timeout: None,
+            state: Default::default(),
+            notify: Notify::new(),
+        }
+    }
+
+    /// Limit the number of queries running at the same time
+    pub fn with_max_concurrent_queries(mut self, max_concurrent_queries: usize) -> Self {
+        self.max_concurrent_queries = max_concurrent_queries.max(1);
+        self
+    }
+
+    /// Limit the total estimated memory, in bytes, of the queries running at
+    /// the same time
+    pub fn with_memory_limit(mut self, memory_limit: usize) -> Self {
+        self.memory_limit = memory_limit;
+        self
+    }
+
+    /// Set the estimated memory, in bytes, of queries that do not specify
+    /// [`AdmissionRequest::with_estimated_memory`]. Defaults to 0.
+    pub fn with_default_memory_estimate(mut self, estimate: usize) -> Self {
+        self.default_memory_estimate = estimate;
+        self
+    }
+
+    /// Fail queries that have not been admitted after waiting for `timeout`
+    pub fn with_queue_timeout(mut self, timeout: Duration) -> Self {
+        self.queue_timeout = Some(timeout);
+        self
+    }
+
+    /// Returns the number of running queries
+    pub fn running_queries(&self) -> usize {
+        self.state.lock().running
+    }
+
+    /// Returns the number of queries waiting to be admitted
+    pub fn queued_queries(&self) -> usize {
+        self.state.lock().queue.len()
+    }
+
+    /// Returns the total estimated memory of the running queries
+    pub fn reserved_memory(&self) -> usize {
+        self.state.lock().reserved_memory
+    }
+
+    /// Waits until the query described by `request` can run, returning an
+    /// [`AdmissionPermit`] that must be held while the query runs.
+    ///
+    /// Returns an error if the query is not admitted within the queue timeout.
+    /// Dropping the returned future removes the query from the queue.
+    pub async fn admit(
+        self: &Arc<Self>,
+        request: AdmissionRequest,
+    ) -> Result<AdmissionPermit> {
+        let memory = request
+            .estimated_memory
+            .unwrap_or(self.default_memory_estimate)
+            .min(self.memory_limit);
+        let deadline = self.queue_timeout.map(|timeout| Instant::now() + timeout);
+
+        let key = {
+            let mut state = self.state.lock();
+            let key = (Reverse(request.priority), state.next_sequence);
+            state.next_sequence += 1;
+            state.queue.insert(key);
+            key
+        };
+        // Removes the query from the queue if it is not admitted
+        let mut entry = QueueEntry {
+            controller: Arc::clone(self),
+            key: Some(key),
+        };
+
+        loop {
+            // Register for notifications before checking, so that none are missed
+            let notified = self.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            if self.try_admit(key, memory) {
+                entry.key = None;
+                // The next query in the queue may fit as well
+                self.notify.notify_waiters();
+                debug!(
+                    "Admitted query with priority {} and estimated memory {}",
+                    request.priority,
+                    human_readable_size(memory)
+                );
+                return Ok(AdmissionPermit {
+                    controller: Arc::clone(self),
+                    memory,
+                });
+            }
+
+            match deadline {
+                Some(deadline) => {
+                    if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                        drop(entry);
+                        return Err(self.timeout_error(memory));
+                    }
+                }
+                None => notified.await,
+            }
+        }
+    }
+
+    /// Admits the query `key` if it is at the head of the queue and fits
+    /// within the limits
+    fn try_admit(&self, key: (Reverse<i64>, u64), memory: usize) -> bool {
+        let mut state = self.state.lock();
+        let is_next = state.queue.first() == Some(&key);
+        let fits = state.running == 0
+            || (state.running < self.max_concurrent_queries
+                && state.reserved_memory.saturating_add(memory) <= self.memory_limit);
+        if !is_next || !fits {
+            return false;
+        }
+        state.queue.remove(&key);
+        state.running += 1;
+        state.reserved_memory += memory;
+        true
+    }
+
+    fn timeout_error(&self, memory: usize) -> datafusion_common::DataFusionError {
+        let state = self.state.lock();
+        let timeout = self.queue_timeout.unwrap_or_default();
+        resources_datafusion_err!(
+            "Query with estimated memory {} was not admitted within {timeout:?}: {} queries running with {} of estimated memory, {} queries queued",
+            human_readable_size(memory),
+            state.running,
+            human_readable_size(state.reserved_memory),
+            state.queue.len()
+        )
+    }
+
+    fn release(&self, memory: usize) {
+        {
+            let mut state = self.state.lock();
+            state.running -= 1;
+            state.reserved_memory -= memory;
+        }
+        self.notify.notify_waiters();
+    }
+}
+
+/// The priority and estimated memory of a query waiting for admission by an
+/// [`AdmissionController`]
+#[derive(Debug, Clone, Default)]
+pub struct AdmissionRequest {
+    priority: i64,
+    estimated_memory: Option<usize>,
+}
+
+impl AdmissionRequest {
+    /// Create a new request with priority 0 and the default memory estimate
+    /// of the [`AdmissionController`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a request from the `datafusion.execution.query_priority` and
+    /// `datafusion.execution.query_memory_estimate` settings
+    pub fn from_config(options: &ConfigOptions) -> Self {
+        Self {
+            priority: options.execution.query_priority,
+            estimated_memory: options.execution.query_memory_estimate,
+        }
+    }
+
+    /// Set the priority of the query. Queries with a higher priority are
+    /// admitted first.
+    pub fn with_priority(mut self, priority: i64) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Set the estimated peak memory of the query, in bytes
+    pub fn with_estimated_memory(mut self, estimated_memory: usize) -> Self {
+        self.estimated_memory = Some(estimated_memory);
+        self
+    }
+
+    /// Returns the priority of the query
+    pub fn priority(&self) -> i64 {
+        self.priority
+    }
+
+    /// Returns the estimated peak memory of the query, if specified
+    pub fn estimated_memory(&self) -> Option<usize> {
+        self.estimated_memory
+    }
+}
+
+/// Removes a query from the queue of an [`AdmissionController`] when it
+/// stops waiting without being admitted
+struct QueueEntry {
+    controller: Arc<AdmissionController>,
+    key: Option<(Reverse<i64>, u64)>,
+}
+
+impl Drop for QueueEntry {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            self.controller.state.lock().queue.remove(&key);
+            // The queries behind this one may be admitted now
+            self.controller.notify.notify_waiters();
+        }
+    }
+}
+
+/// Permission for a query to run, returned by [`AdmissionController::admit`].
+///
+/// The resources of the query are released back to the controller on drop.
+#[derive(Debug)]
+pub struct AdmissionPermit {
+    controller: Arc<AdmissionController>,
+    memory: usize,
+}
+
+impl AdmissionPermit {
+    /// Returns the estimated memory reserved for the query
+    pub fn estimated_memory(&self) -> usize {
+        self.memory
+    }
+
+    /// Wraps `stream` so that the permit is held until the stream is dropped
+    pub fn attach(
+        self: Arc<Self>,
+        stream: SendableRecordBatchStream,
+    ) -> SendableRecordBatchStream {
+        Box::pin(AdmittedStream {
+            inner: stream,
+            _permit: self,
+        })
+    }
+}
+
+impl Drop for AdmissionPermit {
+    fn drop(&mut self) {
+        self.controller.release(self.memory);
+    }
+}
+
+/// A stream that holds the [`AdmissionPermit`] of the query it belongs to
+struct AdmittedStream {
+    inner: SendableRecordBatchStream,
+    _permit: Arc<AdmissionPermit>,
+}
+
+impl Stream for AdmittedStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl RecordBatchStream for AdmittedStream {
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion_common::DataFusionError;
+    use futures::FutureExt;
+
+    #[tokio::test]
+    async fn test_concurrency_limit() -> Result<()> {
+        let controller =
+            Arc::new(AdmissionController::new().with_max_concurrent_queries(2));
+
+        let p1 = controller.admit(AdmissionRequest::new()).await?;
+        let _p2 = controller.admit(AdmissionRequest::new()).await?;
+        assert_eq!(controller.running_queries(), 2);
+
+        // The third query waits until one of the running queries completes
+        let mut p3 = Box::pin(controller.admit(AdmissionRequest::new()));
+        assert!((&mut p3).now_or_never().is_none());
+        assert_eq!(controller.queued_queries(), 1);
+
+        drop(p1);
+        let _p3 = p3.await?;
+        assert_eq!(controller.running_queries(), 2);
+        assert_eq!(controller.queued_queries(), 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_memory_limit() -> Result<()> {
+        let controller = Arc::new(AdmissionController::new().with_memory_limit(100));
+        let request = |memory| AdmissionRequest::new().with_estimated_memory(memory);
+
+        let p1 = controller.admit(request(60)).await?;
+        let mut p2 = Box::pin(controller.admit(request(50)));
+        assert!((&mut p2).now_or_never().is_none());
+        let p3 = controller.admit(request(40)).now_or_never();
+        // Queued behind the second query, even though it would fit
+        assert!(p3.is_none());
+
+        drop(p1);
+        let p2 = p2.await?;
+        assert_eq!(controller.reserved_memory(), 50);
+
+        // A query larger than the limit runs on its own
+        let mut p4 = Box::pin(controller.admit(request(1000)));
+        assert!((&mut p4).now_or_never().is_none());
+        drop(p2);
+        let p4 = p4.await?;
+        assert_eq!(p4.estimated_memory(), 100);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_priority() -> Result<()> {
+        let controller =
+            Arc::new(AdmissionController::new().with_max_concurrent_queries(1));
+        let p1 = controller.admit(AdmissionRequest::new()).await?;
+
+        let mut low =
+            Box::pin(controller.admit(AdmissionRequest::new().with_priority(-1)));
+        let mut high =
+            Box::pin(controller.admit(AdmissionRequest::new().with_priority(1)));
+        assert!((&mut low).now_or_never().is_none());
+        assert!((&mut high).now_or_never().is_none());
+        assert_eq!(controller.queued_queries(), 2);
+
+        drop(p1);
+        // The query with the higher priority is admitted first
+        assert!((&mut low).now_or_never().is_none());
+        let high = high.await?;
+        assert!((&mut low).now_or_never().is_none());
+        drop(high);
+        low.await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cancel_waiting_query() -> Result<()> {
+        let controller =
+            Arc::new(AdmissionController::new().with_max_concurrent_queries(1));
+        let p1 = controller.admit(AdmissionRequest::new()).await?;
+
+        let mut waiting =
+            Box::pin(controller.admit(AdmissionRequest::new().with_priority(1)));
+        assert!((&mut waiting).now_or_never().is_none());
+        let mut next = Box::pin(controller.admit(AdmissionRequest::new()));
+        assert!((&mut next).now_or_never().is_none());
+        assert_eq!(controller.queued_queries(), 2);
+
+        // Dropping a waiting query removes it from the queue
+        drop(waiting);
+        assert_eq!(controller.queued_queries(), 1);
+        drop(p1);
+        next.await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_queue_timeout() -> Result<()> {
+        let controller = Arc::new(
+            AdmissionController::new()
+                .with_max_concurrent_queries(1)
+                .with_queue_timeout(Duration::from_millis(10)),
+        );
+        let _p1 = controller
+            .admit(AdmissionRequest::new().with_estimated_memory(2048))
+            .await?;
+
+        let err = controller
+            .admit(AdmissionRequest::new().with_estimated_memory(1024))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DataFusionError::ResourcesExhausted(_)));
+        assert_eq!(
+            err.strip_backtrace(),
+            "Resources exhausted: Query with estimated memory 1024.0 B was not admitted within 10ms: 1 queries running with 2.0 KB of estimated memory, 0 queries queued"
+        );
+        assert_eq!(controller.queued_queries(), 0);
+        Ok(())
+    }
+}
@@ -15,14 +15,22 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use crate::cache::cache_unit::DefaultFilesMetadataCache;
+use crate::cache::cache_unit::{
+    DefaultFileRangeCache, DefaultFilesMetadataCache, DefaultTableStatisticsCache,
+};
 use crate::cache::CacheAccessor;
-use datafusion_common::{Result, Statistics};
+use arrow::array::RecordBatch;
+use arrow::datatypes::SchemaRef;
+use bytes::Bytes;
+use datafusion_common::{Result, Statistics, TableReference};
+use datafusion_expr::LogicalPlan;
 use object_store::path::Path;
 use object_store::ObjectMeta;
 use std::any::Any;
 use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
 use std::sync::Arc;
 
 /// A cache for [`Statistics`].
@@ -35,6 +43,16 @@ use std::sync::Arc;
 pub type FileStatisticsCache =
     Arc<dyn CacheAccessor<Path, Arc<Statistics>, Extra = ObjectMeta>>;
 
+/// A cache for the [`Statistics`] of tables, computed by `ANALYZE TABLE`.
+///
+/// The key is the fully qualified table name, and the [`SchemaRef`] of the
+/// table is passed as extra information, so that statistics are not used
+/// after the table was replaced by a table with a different schema.
+///
+/// See [`crate::runtime_env::RuntimeEnv`] for more details
+pub type TableStatisticsCache =
+    Arc<dyn CacheAccessor<TableReference, Arc<Statistics>, Extra = SchemaRef>>;
+
 /// Cache for storing the [`ObjectMeta`]s that result from listing a path
 ///
 /// Listing a path means doing an object store "list" operation or `ls`
@@ -103,6 +121,114 @@ pub struct FileMetadataCacheEntry {
     pub extra: HashMap<String, String>,
 }
 
+/// Identifies a byte range of a file in a [`FileRangeCache`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FileRangeKey {
+    /// The location of the file
+    pub location: Path,
+    /// The byte range within the file
+    pub range: Range<u64>,
+}
+
+/// Cache for byte ranges read from files.
+///
+/// For example, the built in Parquet reader uses this cache to avoid fetching
+/// the same column chunks from object storage for repeated queries.
+///
+/// The [`ObjectMeta`] of the file is passed as extra information, so that
+/// cached ranges are not used after the file has changed.
+///
+/// DataFusion provides a default implementation, [`DefaultFileRangeCache`],
+/// which is disabled (has a limit of 0 bytes) unless configured with
+/// [`CacheManagerConfig::with_file_range_cache_limit`].
+///
+/// See [`crate::runtime_env::RuntimeEnv`] for more details.
+pub trait FileRangeCache: CacheAccessor<FileRangeKey, Bytes, Extra = ObjectMeta> {
+    /// Returns the cache's memory limit in bytes.
+    fn cache_limit(&self) -> usize;
+
+    /// Updates the cache with a new memory limit in bytes.
+    fn update_cache_limit(&self, limit: usize);
+}
+
+/// Identifies the result of a query in a [`ResultCache`]
+///
+/// Two executions of a query have the same key if they have the same plan and
+/// session options, and scan the same versions of the same files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResultCacheKey {
+    /// Fingerprint of the plan of the query and of the session options that
+    /// can affect its result
+    pub plan_fingerprint: u64,
+    /// The files scanned by the query. A file changes version when its
+    /// `size`, `last_modified`, `e_tag` or `version` change.
+    pub files: Vec<ObjectMeta>,
+}
+
+impl Hash for ResultCacheKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.plan_fingerprint.hash(state);
+        for file in &self.files {
+            file.location.hash(state);
+            file.size.hash(state);
+            file.e_tag.hash(state);
+            file.version.hash(state);
+        }
+    }
+}
+
+/// The result of a query, stored in a [`ResultCache`]
+#[derive(Debug, Clone)]
+pub struct CachedResult {
+    /// The schema of the result
+    pub schema: SchemaRef,
+    /// The result, in the order it was produced
+    pub batches: Vec<RecordBatch>,
+}
+
+impl CachedResult {
+    /// Returns the memory used by the result, in bytes
+    pub fn memory_size(&self) -> usize {
+        self.batches
+            .iter()
+            .map(|batch| batch.get_array_memory_size())
+            .sum()
+    }
+}
+
+/// Cache for the results of queries.
+///
+/// If enabled via [`CacheManagerConfig::with_result_cache`], `DataFrame::collect`
+/// and `DataFrame::execute_stream` return the cached result of a query that
+/// was already executed, as long as none of the files it scans have changed.
+/// Only queries that read files and call no volatile or stable functions (such
+/// as `random()` or `now()`) are cached.
+///
+/// The logical plan of the query is passed as extra information, so that
+/// results are never returned for a different query with the same
+/// [`ResultCacheKey::plan_fingerprint`], and so that the results of the queries
+/// that scan a table can be [invalidated](Self::invalidate_table) when it is
+/// written to.
+///
+/// DataFusion provides a default implementation, [`DefaultResultCache`].
+///
+/// See [`crate::runtime_env::RuntimeEnv`] for more details.
+///
+/// [`DefaultResultCache`]: crate::cache::cache_unit::DefaultResultCache
+pub trait ResultCache:
+    CacheAccessor<ResultCacheKey, Arc<CachedResult>, Extra = Arc<LogicalPlan>>
+{
+    /// Returns the maximum size of the results kept in memory, in bytes.
+    fn memory_limit(&self) -> usize;
+
+    /// Returns the maximum size of the results spilled to disk, in bytes.
+    fn disk_limit(&self) -> usize;
+
+    /// Removes the results of all queries that scan `table`, returning the
+    /// number of removed results.
+    fn invalidate_table(&self, table: &TableReference) -> usize;
+}
+
 impl Debug for dyn CacheAccessor<Path, Arc<Statistics>, Extra = ObjectMeta> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "Cache name: {} with length: {}", self.name(), self.len())
@@ -115,12 +241,30 @@ impl Debug for dyn CacheAccessor<Path, Arc<Vec<ObjectMeta>>, Extra = ObjectMeta>
     }
 }
 
+impl Debug for dyn CacheAccessor<TableReference, Arc<Statistics>, Extra = SchemaRef> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Cache name: {} with length: {}", self.name(), self.len())
+    }
+}
+
 impl Debug for dyn FileMetadataCache {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "Cache name: {} with length: {}", self.name(), self.len())
     }
 }
 
+impl Debug for dyn FileRangeCache {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Cache name: {} with length: {}", self.name(), self.len())
+    }
+}
+
+impl Debug for dyn ResultCache {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Cache name: {} with length: {}", self.name(), self.len())
+    }
+}
+
 /// Manages various caches used in DataFusion.
 ///
 /// Following DataFusion design principles, DataFusion provides default cache
@@ -133,6 +277,9 @@ pub struct CacheManager {
     file_statistic_cache: Option<FileStatisticsCache>,
     list_files_cache: Option<ListFilesCache>,
     file_metadata_cache: Arc<dyn FileMetadataCache>,
+    table_statistics_cache: TableStatisticsCache,
+    file_range_cache: Arc<dyn FileRangeCache>,
+    result_cache: Option<Arc<dyn ResultCache>>,
 }
 
 impl CacheManager {
@@ -153,10 +300,32 @@ impl CacheManager {
         // the cache memory limit might have changed, ensure the limit is updated
         file_metadata_cache.update_cache_limit(config.metadata_cache_limit);
 
+        let table_statistics_cache = config
+            .table_statistics_cache
+            .as_ref()
+            .map(Arc::clone)
+            .unwrap_or_else(|| Arc::new(DefaultTableStatisticsCache::default()));
+
+        let file_range_cache = config
+            .file_range_cache
+            .as_ref()
+            .map(Arc::clone)
+            .unwrap_or_else(|| {
+                Arc::new(DefaultFileRangeCache::new(config.file_range_cache_limit))
+            });
+
+        // the cache memory limit might have changed, ensure the limit is updated
+        file_range_cache.update_cache_limit(config.file_range_cache_limit);
+
+        let result_cache = config.result_cache.as_ref().map(Arc::clone);
+
         Ok(Arc::new(CacheManager {
             file_statistic_cache,
             list_files_cache,
             file_metadata_cache,
+            table_statistics_cache,
+            file_range_cache,
+            result_cache,
         }))
     }
 
@@ -175,6 +344,26 @@ impl CacheManager {
         Arc::clone(&self.file_metadata_cache)
     }
 
+    /// Get the cache of the table statistics computed by `ANALYZE TABLE`.
+    pub fn get_table_statistics_cache(&self) -> TableStatisticsCache {
+        Arc::clone(&self.table_statistics_cache)
+    }
+
+    /// Get the cache of byte ranges read from files.
+    pub fn get_file_range_cache(&self) -> Arc<dyn FileRangeCache> {
+        Arc::clone(&self.file_range_cache)
+    }
+
+    /// Get the limit of the cache of byte ranges read from files.
+    pub fn get_file_range_cache_limit(&self) -> usize {
+        self.file_range_cache.cache_limit()
+    }
+
+    /// Get the cache of query results.
+    pub fn get_result_cache(&self) -> Option<Arc<dyn ResultCache>> {
+        self.result_cache.clone()
+    }
+
     /// Get the limit of the file embedded metadata cache.
     pub fn get_metadata_cache_limit(&self) -> usize {
         self.file_metadata_cache.cache_limit()
@@ -202,6 +391,20 @@ pub struct CacheManagerConfig {
     pub file_metadata_cache: Option<Arc<dyn FileMetadataCache>>,
     /// Limit of the file-embedded metadata cache, in bytes.
     pub metadata_cache_limit: usize,
+    /// Cache of the table statistics computed by `ANALYZE TABLE`.
+    /// If not provided, the [`CacheManager`] will create a [`DefaultTableStatisticsCache`].
+    pub table_statistics_cache: Option<TableStatisticsCache>,
+    /// Cache of byte ranges read from files, used to avoid fetching the same
+    /// data (e.g., Parquet column chunks) repeatedly.
+    /// If not provided, the [`CacheManager`] will create a [`DefaultFileRangeCache`].
+    pub file_range_cache: Option<Arc<dyn FileRangeCache>>,
+    /// Limit of the cache of byte ranges read from files, in bytes.
+    /// Default is 0 (disabled).
+    pub file_range_cache_limit: usize,
+    /// Cache of query results, to avoid executing the same query repeatedly
+    /// against unchanged files.
+    /// Default is disable.
+    pub result_cache: Option<Arc<dyn ResultCache>>,
 }
 
 impl Default for CacheManagerConfig {
@@ -211,6 +414,10 @@ impl Default for CacheManagerConfig {
             list_files_cache: Default::default(),
             file_metadata_cache: Default::default(),
             metadata_cache_limit: DEFAULT_METADATA_CACHE_LIMIT,
+            table_statistics_cache: Default::default(),
+            file_range_cache: Default::default(),
+            file_range_cache_limit: 0,
+            result_cache: Default::default(),
         }
     }
 }
@@ -246,6 +453,42 @@ impl CacheManagerConfig {
         self
     }
 
+    /// Sets the cache for the table statistics computed by `ANALYZE TABLE`.
+    ///
+    /// Default is a [`DefaultTableStatisticsCache`].
+    pub fn with_table_statistics_cache(
+        mut self,
+        cache: Option<TableStatisticsCache>,
+    ) -> Self {
+        self.table_statistics_cache = cache;
+        self
+    }
+
+    /// Sets the cache for byte ranges read from files.
+    ///
+    /// Default is a [`DefaultFileRangeCache`].
+    pub fn with_file_range_cache(
+        mut self,
+        cache: Option<Arc<dyn FileRangeCache>>,
+    ) -> Self {
+        self.file_range_cache = cache;
+        self
+    }
+
+    /// Sets the limit of the cache of byte ranges read from files, in bytes.
+    pub fn with_file_range_cache_limit(mut self, limit: usize) -> Self {
+        self.file_range_cache_limit = limit;
+        self
+    }
+
+    /// Sets the cache for query results.
+    ///
+    /// Default is `None` (disabled).
+    pub fn with_result_cache(mut self, cache: Option<Arc<dyn ResultCache>>) -> Self {
+        self.result_cache = cache;
+        self
+    }
+
     /// Sets the limit of the file-embedded metadata cache, in bytes.
     pub fn with_metadata_cache_limit(mut self, limit: usize) -> Self {
         self.metadata_cache_limit = limit;
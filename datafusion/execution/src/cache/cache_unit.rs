@@ -16,19 +16,30 @@
 // under the License.
 
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+use std::fs::File;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard};
 
 use crate::cache::cache_manager::{
-    FileMetadata, FileMetadataCache, FileMetadataCacheEntry,
+    CachedResult, FileMetadata, FileMetadataCache, FileMetadataCacheEntry,
+    FileRangeCache, FileRangeKey, ResultCache, ResultCacheKey,
 };
 use crate::cache::lru_queue::LruQueue;
 use crate::cache::CacheAccessor;
 
-use datafusion_common::Statistics;
+use arrow::datatypes::SchemaRef;
+use arrow::ipc::reader::FileReader;
+use arrow::ipc::writer::FileWriter;
+use bytes::Bytes;
+use datafusion_common::tree_node::TreeNodeRecursion;
+use datafusion_common::{Result, Statistics, TableReference};
+use datafusion_expr::LogicalPlan;
 
 use dashmap::DashMap;
+use log::debug;
 use object_store::path::Path;
 use object_store::ObjectMeta;
+use tempfile::NamedTempFile;
 
 /// Default implementation of [`FileStatisticsCache`]
 ///
@@ -172,6 +183,80 @@ impl CacheAccessor<Path, Arc<Vec<ObjectMeta>>> for DefaultListFilesCache {
     }
 }
 
+/// Default implementation of [`TableStatisticsCache`]
+///
+/// Stores the statistics computed by `ANALYZE TABLE`
+///
+/// Cache is invalidated when the schema of the table has changed
+///
+/// [`TableStatisticsCache`]: crate::cache::cache_manager::TableStatisticsCache
+#[derive(Default)]
+pub struct DefaultTableStatisticsCache {
+    statistics: DashMap<TableReference, (SchemaRef, Arc<Statistics>)>,
+}
+
+impl CacheAccessor<TableReference, Arc<Statistics>> for DefaultTableStatisticsCache {
+    type Extra = SchemaRef;
+
+    /// Get `Statistics` for a table.
+    fn get(&self, k: &TableReference) -> Option<Arc<Statistics>> {
+        self.statistics.get(k).map(|s| Arc::clone(&s.value().1))
+    }
+
+    /// Get `Statistics` for a table. Returns None if the table schema has
+    /// changed or the table was not analyzed.
+    fn get_with_extra(
+        &self,
+        k: &TableReference,
+        e: &Self::Extra,
+    ) -> Option<Arc<Statistics>> {
+        self.statistics.get(k).and_then(|s| {
+            let (saved_schema, statistics) = s.value();
+            (saved_schema == e).then(|| Arc::clone(statistics))
+        })
+    }
+
+    /// Save table statistics
+    fn put(
+        &self,
+        _key: &TableReference,
+        _value: Arc<Statistics>,
+    ) -> Option<Arc<Statistics>> {
+        panic!("Put cache in DefaultTableStatisticsCache without Extra not supported.")
+    }
+
+    fn put_with_extra(
+        &self,
+        key: &TableReference,
+        value: Arc<Statistics>,
+        e: &Self::Extra,
+    ) -> Option<Arc<Statistics>> {
+        self.statistics
+            .insert(key.clone(), (Arc::clone(e), value))
+            .map(|x| x.1)
+    }
+
+    fn remove(&mut self, k: &TableReference) -> Option<Arc<Statistics>> {
+        self.statistics.remove(k).map(|x| x.1 .1)
+    }
+
+    fn contains_key(&self, k: &TableReference) -> bool {
+        self.statistics.contains_key(k)
+    }
+
+    fn len(&self) -> usize {
+        self.statistics.len()
+    }
+
+    fn clear(&self) {
+        self.statistics.clear()
+    }
+
+    fn name(&self) -> String {
+        "DefaultTableStatisticsCache".to_string()
+    }
+}
+
 /// Handles the inner state of the [`DefaultFilesMetadataCache`] struct.
 struct DefaultFilesMetadataCacheState {
     lru_queue: LruQueue<Path, (ObjectMeta, Arc<dyn FileMetadata>)>,
@@ -424,21 +509,729 @@ impl CacheAccessor<ObjectMeta, Arc<dyn FileMetadata>> for DefaultFilesMetadataCa
     }
 }
 
+/// Handles the inner state of the [`DefaultFileRangeCache`] struct.
+struct DefaultFileRangeCacheState {
+    lru_queue: LruQueue<FileRangeKey, (ObjectMeta, Bytes)>,
+    memory_limit: usize,
+    memory_used: usize,
+}
+
+impl DefaultFileRangeCacheState {
+    fn new(memory_limit: usize) -> Self {
+        Self {
+            lru_queue: LruQueue::new(),
+            memory_limit,
+            memory_used: 0,
+        }
+    }
+
+    /// Returns the respective range from the cache, if it exists and the `size` and
+    /// `last_modified` properties of the file (when provided) match.
+    /// If the entry exists, it becomes the most recently used.
+    fn get(
+        &mut self,
+        k: &FileRangeKey,
+        object_meta: Option<&ObjectMeta>,
+    ) -> Option<Bytes> {
+        let (cached_meta, bytes) = self.lru_queue.get(k)?;
+        match object_meta {
+            Some(object_meta)
+                if cached_meta.size != object_meta.size
+                    || cached_meta.last_modified != object_meta.last_modified =>
+            {
+                None
+            }
+            _ => Some(bytes.clone()),
+        }
+    }
+
+    /// Adds a new range to the cache, meaning LRU entries might be evicted if required.
+    /// If the range is larger than the `memory_limit`, it is not inserted.
+    fn put(
+        &mut self,
+        key: FileRangeKey,
+        value: Bytes,
+        object_meta: ObjectMeta,
+    ) -> Option<Bytes> {
+        let value_size = value.len();
+
+        // no point in trying to add this value to the cache if it cannot fit entirely
+        if value_size > self.memory_limit {
+            return None;
+        }
+
+        let old_value = self.lru_queue.put(key, (object_meta, value));
+        self.memory_used += value_size;
+        if let Some((_, ref old_bytes)) = old_value {
+            self.memory_used -= old_bytes.len();
+        }
+
+        self.evict_entries();
+
+        old_value.map(|v| v.1)
+    }
+
+    /// Evicts entries from the LRU cache until `memory_used` is lower than `memory_limit`.
+    fn evict_entries(&mut self) {
+        while self.memory_used > self.memory_limit {
+            if let Some((_, (_, bytes))) = self.lru_queue.pop() {
+                self.memory_used -= bytes.len();
+            } else {
+                // cache is empty while memory_used > memory_limit, cannot happen
+                debug_assert!(
+                    false,
+                    "cache is empty while memory_used > memory_limit, cannot happen"
+                );
+                return;
+            }
+        }
+    }
+
+    /// Removes an entry from the cache and returns it, if it exists.
+    fn remove(&mut self, k: &FileRangeKey) -> Option<Bytes> {
+        let (_, bytes) = self.lru_queue.remove(k)?;
+        self.memory_used -= bytes.len();
+        Some(bytes)
+    }
+
+    /// Removes all entries from the cache.
+    fn clear(&mut self) {
+        self.lru_queue.clear();
+        self.memory_used = 0;
+    }
+}
+
+/// Default implementation of [`FileRangeCache`]
+///
+/// Caches byte ranges read from files, such as Parquet column chunks.
+///
+/// The ranges of each file are invalidated when the file size or last
+/// modification time have been changed.
+///
+/// # Internal details
+///
+/// The `memory_limit` controls the maximum size of the cache, which uses a
+/// Least Recently Used eviction algorithm. When adding a new entry, if the total
+/// size of the cached entries exceeds `memory_limit`, the least recently used entries
+/// are evicted until the total size is lower than `memory_limit`. A limit of 0
+/// disables the cache.
+///
+/// Cached [`Bytes`] are not copied, so callers should put ranges that do not
+/// share their buffer with other data, for the memory accounting to be
+/// accurate.
+///
+/// # `Extra` Handling
+///
+/// Users should use the [`Self::get_with_extra`] and [`Self::put_with_extra`]
+/// methods, passing the [`ObjectMeta`] of the file. [`Self::get`] does not check
+/// whether the file has changed, and [`Self::put`] is not supported.
+pub struct DefaultFileRangeCache {
+    // the state is wrapped in a Mutex to ensure the operations are atomic
+    state: Mutex<DefaultFileRangeCacheState>,
+}
+
+impl DefaultFileRangeCache {
+    /// Create a new instance of [`DefaultFileRangeCache`].
+    ///
+    /// # Arguments
+    /// `memory_limit`:  the maximum size of the cache, in bytes
+    pub fn new(memory_limit: usize) -> Self {
+        Self {
+            state: Mutex::new(DefaultFileRangeCacheState::new(memory_limit)),
+        }
+    }
+
+    /// Returns the size of the cached ranges, in bytes.
+    pub fn memory_used(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        state.memory_used
+    }
+}
+
+impl FileRangeCache for DefaultFileRangeCache {
+    fn cache_limit(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        state.memory_limit
+    }
+
+    fn update_cache_limit(&self, limit: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.memory_limit = limit;
+        state.evict_entries();
+    }
+}
+
+impl CacheAccessor<FileRangeKey, Bytes> for DefaultFileRangeCache {
+    type Extra = ObjectMeta;
+
+    fn get(&self, k: &FileRangeKey) -> Option<Bytes> {
+        let mut state = self.state.lock().unwrap();
+        state.get(k, None)
+    }
+
+    /// Returns the cached range, if the file has not changed since it was read
+    fn get_with_extra(&self, k: &FileRangeKey, e: &Self::Extra) -> Option<Bytes> {
+        let mut state = self.state.lock().unwrap();
+        state.get(k, Some(e))
+    }
+
+    fn put(&self, _key: &FileRangeKey, _value: Bytes) -> Option<Bytes> {
+        panic!("Put cache in DefaultFileRangeCache without Extra not supported.")
+    }
+
+    fn put_with_extra(
+        &self,
+        key: &FileRangeKey,
+        value: Bytes,
+        e: &Self::Extra,
+    ) -> Option<Bytes> {
+        let mut state = self.state.lock().unwrap();
+        state.put(key.clone(), value, e.clone())
+    }
+
+    fn remove(&mut self, k: &FileRangeKey) -> Option<Bytes> {
+        let mut state = self.state.lock().unwrap();
+        state.remove(k)
+    }
+
+    fn contains_key(&self, k: &FileRangeKey) -> bool {
+        let state = self.state.lock().unwrap();
+        state.lru_queue.contains_key(k)
+    }
+
+    fn len(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        state.lru_queue.len()
+    }
+
+    fn clear(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.clear();
+    }
+
+    fn name(&self) -> String {
+        "DefaultFileRangeCache".to_string()
+    }
+}
+
+/// A result spilled to disk by the [`DefaultResultCache`], as an Arrow IPC file
+struct SpilledResult {
+    file: NamedTempFile,
+    size: usize,
+}
+
+/// A result evicted from memory, to be spilled to disk by the
+/// [`DefaultResultCache`] once the lock on its state is released
+type EvictedResult = (ResultCacheKey, Arc<LogicalPlan>, Arc<CachedResult>);
+
+/// Where [`DefaultResultCacheState::get`] found a result
+enum ResultLookup {
+    /// The result is in memory
+    Memory(Arc<CachedResult>),
+    /// The result was spilled to disk. It is removed from the cache until it
+    /// was read back, so that the file is not read while holding the lock.
+    Disk(Arc<LogicalPlan>, SpilledResult),
+}
+
+/// Handles the inner state of the [`DefaultResultCache`] struct.
+///
+/// The state does not perform any I/O: results evicted from memory are
+/// collected in `evicted`, and written to disk by the [`DefaultResultCache`]
+/// without holding the lock.
+struct DefaultResultCacheState {
+    memory: LruQueue<ResultCacheKey, (Arc<LogicalPlan>, Arc<CachedResult>)>,
+    disk: LruQueue<ResultCacheKey, (Arc<LogicalPlan>, SpilledResult)>,
+    memory_limit: usize,
+    memory_used: usize,
+    disk_limit: usize,
+    disk_used: usize,
+    disk_path: Option<PathBuf>,
+    /// Results evicted from memory that still need to be spilled to disk
+    evicted: Vec<EvictedResult>,
+    /// Incremented whenever results are removed from the cache, so that the
+    /// results spilled or read back concurrently are not added again
+    generation: u64,
+}
+
+impl DefaultResultCacheState {
+    fn new(memory_limit: usize) -> Self {
+        Self {
+            memory: LruQueue::new(),
+            disk: LruQueue::new(),
+            memory_limit,
+            memory_used: 0,
+            disk_limit: 0,
+            disk_used: 0,
+            disk_path: None,
+            evicted: vec![],
+            generation: 0,
+        }
+    }
+
+    /// Returns the respective result from the cache, if it exists and was
+    /// produced by `plan` (when provided).
+    /// Results found in memory become the most recently used, and results
+    /// found on disk are removed from the cache, see [`ResultLookup::Disk`].
+    fn get(
+        &mut self,
+        k: &ResultCacheKey,
+        plan: Option<&Arc<LogicalPlan>>,
+    ) -> Option<ResultLookup> {
+        let plan_matches = |cached_plan: &Arc<LogicalPlan>| {
+            plan.is_none_or(|plan| Arc::ptr_eq(plan, cached_plan) || plan == cached_plan)
+        };
+
+        if let Some((cached_plan, result)) = self.memory.get(k) {
+            return plan_matches(cached_plan)
+                .then(|| ResultLookup::Memory(Arc::clone(result)));
+        }
+
+        let (cached_plan, _) = self.disk.peek(k)?;
+        if !plan_matches(cached_plan) {
+            return None;
+        }
+        let (cached_plan, spilled) = self.disk.remove(k)?;
+        self.disk_used -= spilled.size;
+        Some(ResultLookup::Disk(cached_plan, spilled))
+    }
+
+    /// Checks if a result is currently cached, in memory or on disk.
+    /// The LRU queues are not updated.
+    fn contains_key(&self, k: &ResultCacheKey) -> bool {
+        self.memory.contains_key(k) || self.disk.contains_key(k)
+    }
+
+    /// Adds a new result to the cache, removing the results of the same query
+    /// over other versions of its files.
+    /// Least recently used results are spilled to disk until `memory_used` is
+    /// lower than `memory_limit`, and results that do not fit in memory at all
+    /// are spilled directly.
+    fn put(
+        &mut self,
+        key: ResultCacheKey,
+        value: Arc<CachedResult>,
+        plan: Arc<LogicalPlan>,
+    ) -> Option<Arc<CachedResult>> {
+        self.remove_where(|k, _| k.plan_fingerprint == key.plan_fingerprint && *k != key);
+        if let Some((_, spilled)) = self.disk.remove(&key) {
+            self.disk_used -= spilled.size;
+        }
+
+        let value_size = value.memory_size();
+        if value_size > self.memory_limit {
+            let old_value = self.remove(&key);
+            self.spill(key, value, plan);
+            return old_value;
+        }
+
+        let old_value = self.memory.put(key, (plan, value));
+        self.memory_used += value_size;
+        if let Some((_, ref old_result)) = old_value {
+            self.memory_used -= old_result.memory_size();
+        }
+
+        self.evict_entries();
+
+        old_value.map(|v| v.1)
+    }
+
+    /// Spills least recently used results to disk until `memory_used` is lower
+    /// than `memory_limit`.
+    fn evict_entries(&mut self) {
+        while self.memory_used > self.memory_limit {
+            if let Some((key, (plan, result))) = self.memory.pop() {
+                self.memory_used -= result.memory_size();
+                self.spill(key, result, plan);
+            } else {
+                // cache is empty while memory_used > memory_limit, cannot happen
+                debug_assert!(
+                    false,
+                    "cache is empty while memory_used > memory_limit, cannot happen"
+                );
+                return;
+            }
+        }
+    }
+
+    /// Queues a result evicted from memory to be written to disk, if spilling
+    /// is enabled.
+    fn spill(
+        &mut self,
+        key: ResultCacheKey,
+        result: Arc<CachedResult>,
+        plan: Arc<LogicalPlan>,
+    ) {
+        if self.disk_limit > 0 {
+            self.evicted.push((key, plan, result));
+        }
+    }
+
+    /// Adds a result written to disk, if it fits in `disk_limit` and was not
+    /// cached again meanwhile, deleting least recently used files until
+    /// `disk_used` is lower than `disk_limit`.
+    fn add_spilled(
+        &mut self,
+        key: ResultCacheKey,
+        plan: Arc<LogicalPlan>,
+        spilled: SpilledResult,
+    ) {
+        if spilled.size > self.disk_limit || self.contains_key(&key) {
+            return;
+        }
+
+        self.disk_used += spilled.size;
+        if let Some((_, old_spilled)) = self.disk.put(key, (plan, spilled)) {
+            self.disk_used -= old_spilled.size;
+        }
+        while self.disk_used > self.disk_limit {
+            match self.disk.pop() {
+                Some((_, (_, spilled))) => self.disk_used -= spilled.size,
+                None => return,
+            }
+        }
+    }
+
+    /// Removes all the results whose key and plan match `predicate`, returning
+    /// how many were removed.
+    fn remove_where(
+        &mut self,
+        predicate: impl Fn(&ResultCacheKey, &LogicalPlan) -> bool,
+    ) -> usize {
+        let in_memory: Vec<_> = self
+            .memory
+            .list_entries()
+            .into_iter()
+            .filter(|(k, (plan, _))| predicate(k, plan))
+            .map(|(k, _)| k.clone())
+            .collect();
+        let on_disk: Vec<_> = self
+            .disk
+            .list_entries()
+            .into_iter()
+            .filter(|(k, (plan, _))| predicate(k, plan))
+            .map(|(k, _)| k.clone())
+            .collect();
+
+        let removed = in_memory.len() + on_disk.len();
+        for k in in_memory {
+            self.remove(&k);
+        }
+        for k in on_disk {
+            self.remove(&k);
+        }
+        removed
+    }
+
+    /// Removes a result from the cache, returning it if it was in memory.
+    fn remove(&mut self, k: &ResultCacheKey) -> Option<Arc<CachedResult>> {
+        if let Some((_, spilled)) = self.disk.remove(k) {
+            self.disk_used -= spilled.size;
+            self.generation += 1;
+        }
+        let (_, result) = self.memory.remove(k)?;
+        self.generation += 1;
+        self.memory_used -= result.memory_size();
+        Some(result)
+    }
+
+    /// Returns the number of results currently cached.
+    fn len(&self) -> usize {
+        self.memory.len() + self.disk.len()
+    }
+
+    /// Removes all results from the cache.
+    fn clear(&mut self) {
+        self.memory.clear();
+        self.disk.clear();
+        self.memory_used = 0;
+        self.disk_used = 0;
+        self.evicted.clear();
+        self.generation += 1;
+    }
+}
+
+fn write_spilled_result(
+    disk_path: Option<&PathBuf>,
+    result: &CachedResult,
+) -> Result<SpilledResult> {
+    let mut builder = tempfile::Builder::new();
+    builder.prefix("datafusion-result-").suffix(".arrow");
+    let file = match disk_path {
+        Some(path) => builder.tempfile_in(path)?,
+        None => builder.tempfile()?,
+    };
+
+    let mut writer = FileWriter::try_new(file.as_file(), &result.schema)?;
+    for batch in &result.batches {
+        writer.write(batch)?;
+    }
+    writer.finish()?;
+    drop(writer);
+
+    let size = file.as_file().metadata()?.len() as usize;
+    Ok(SpilledResult { file, size })
+}
+
+fn read_spilled_result(spilled: &SpilledResult) -> Result<CachedResult> {
+    let reader = FileReader::try_new(File::open(spilled.file.path())?, None)?;
+    let schema = reader.schema();
+    let batches = reader.collect::<Result<Vec<_>, _>>()?;
+    Ok(CachedResult { schema, batches })
+}
+
+/// Returns true if `plan`, or one of its subqueries, scans `table`
+fn scans_table(plan: &LogicalPlan, table: &TableReference) -> bool {
+    let mut found = false;
+    plan.apply_with_subqueries(|plan| {
+        if let LogicalPlan::TableScan(scan) = plan {
+            if scan.table_name.resolved_eq(table) || table.resolved_eq(&scan.table_name) {
+                found = true;
+                return Ok(TreeNodeRecursion::Stop);
+            }
+        }
+        Ok(TreeNodeRecursion::Continue)
+    })
+    // Closure always return Ok
+    .unwrap();
+    found
+}
+
+/// Default implementation of [`ResultCache`]
+///
+/// Caches the results of queries in memory, spilling the least recently used
+/// results to Arrow IPC files on disk when the memory limit is exceeded (if a
+/// disk limit is set with [`Self::with_disk_limit`]).
+///
+/// When a result is added, the results of the same query over other versions
+/// of its files are removed, as they can no longer be returned.
+///
+/// # Internal details
+///
+/// The `memory_limit` and `disk_limit` control the maximum size of the results
+/// kept in memory and on disk. Both use a Least Recently Used eviction
+/// algorithm: results evicted from memory are written to disk, and results
+/// evicted from disk are deleted. A result read back from disk is moved to
+/// memory.
+///
+/// # `Extra` Handling
+///
+/// Users should use the [`Self::get_with_extra`] and [`Self::put_with_extra`]
+/// methods, passing the logical plan of the query. [`Self::get`] returns the
+/// result without checking the plan, and [`Self::put`] is not supported.
+pub struct DefaultResultCache {
+    // the state is wrapped in a Mutex to ensure the operations are atomic,
+    // which is released while results are written to or read from disk
+    state: Mutex<DefaultResultCacheState>,
+}
+
+impl DefaultResultCache {
+    /// Create a new instance of [`DefaultResultCache`], that does not spill
+    /// results to disk.
+    ///
+    /// # Arguments
+    /// `memory_limit`:  the maximum size of the results kept in memory, in bytes
+    pub fn new(memory_limit: usize) -> Self {
+        Self {
+            state: Mutex::new(DefaultResultCacheState::new(memory_limit)),
+        }
+    }
+
+    /// Spill the results evicted from memory to disk, keeping at most
+    /// `disk_limit` bytes of results on disk.
+    pub fn with_disk_limit(self, disk_limit: usize) -> Self {
+        self.state.lock().unwrap().disk_limit = disk_limit;
+        self
+    }
+
+    /// Write the results spilled to disk in `path`, instead of the system's
+    /// temporary directory.
+    pub fn with_disk_path(self, path: impl Into<PathBuf>) -> Self {
+        self.state.lock().unwrap().disk_path = Some(path.into());
+        self
+    }
+
+    /// Returns the size of the results kept in memory, in bytes.
+    pub fn memory_used(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        state.memory_used
+    }
+
+    /// Returns the size of the results spilled to disk, in bytes.
+    pub fn disk_used(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        state.disk_used
+    }
+
+    /// Returns the respective result from the cache, reading it back from disk
+    /// without holding the lock if it was spilled.
+    fn lookup(
+        &self,
+        k: &ResultCacheKey,
+        plan: Option<&Arc<LogicalPlan>>,
+    ) -> Option<Arc<CachedResult>> {
+        let mut state = self.state.lock().unwrap();
+        let (cached_plan, spilled) = match state.get(k, plan)? {
+            ResultLookup::Memory(result) => return Some(result),
+            ResultLookup::Disk(cached_plan, spilled) => (cached_plan, spilled),
+        };
+        let generation = state.generation;
+        drop(state);
+
+        let result = match read_spilled_result(&spilled) {
+            Ok(result) => Arc::new(result),
+            Err(e) => {
+                debug!("Failed to read cached result from disk: {e}");
+                return None;
+            }
+        };
+
+        let mut state = self.state.lock().unwrap();
+        if state.generation == generation {
+            if result.memory_size() > state.memory_limit {
+                // keep the file rather than writing the result again
+                state.add_spilled(k.clone(), cached_plan, spilled);
+            } else if !state.contains_key(k) {
+                state.put(k.clone(), Arc::clone(&result), cached_plan);
+            }
+        }
+        self.spill_evicted(state);
+        Some(result)
+    }
+
+    /// Writes the results evicted from memory while `state` was locked to
+    /// disk, releasing the lock during the I/O.
+    fn spill_evicted(&self, mut state: MutexGuard<'_, DefaultResultCacheState>) {
+        if state.evicted.is_empty() {
+            return;
+        }
+        let evicted = std::mem::take(&mut state.evicted);
+        let disk_path = state.disk_path.clone();
+        let generation = state.generation;
+        drop(state);
+
+        let spilled: Vec<_> = evicted
+            .into_iter()
+            .filter_map(|(key, plan, result)| {
+                match write_spilled_result(disk_path.as_ref(), &result) {
+                    Ok(spilled) => Some((key, plan, spilled)),
+                    Err(e) => {
+                        debug!("Failed to write cached result to disk: {e}");
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        let mut state = self.state.lock().unwrap();
+        // results removed meanwhile must not be added back
+        if state.generation != generation {
+            return;
+        }
+        for (key, plan, spilled) in spilled {
+            state.add_spilled(key, plan, spilled);
+        }
+    }
+}
+
+impl ResultCache for DefaultResultCache {
+    fn memory_limit(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        state.memory_limit
+    }
+
+    fn disk_limit(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        state.disk_limit
+    }
+
+    fn invalidate_table(&self, table: &TableReference) -> usize {
+        let mut state = self.state.lock().unwrap();
+        state.remove_where(|_, plan| scans_table(plan, table))
+    }
+}
+
+impl CacheAccessor<ResultCacheKey, Arc<CachedResult>> for DefaultResultCache {
+    type Extra = Arc<LogicalPlan>;
+
+    fn get(&self, k: &ResultCacheKey) -> Option<Arc<CachedResult>> {
+        self.lookup(k, None)
+    }
+
+    /// Returns the cached result, if it was produced by the plan `e`
+    fn get_with_extra(
+        &self,
+        k: &ResultCacheKey,
+        e: &Self::Extra,
+    ) -> Option<Arc<CachedResult>> {
+        self.lookup(k, Some(e))
+    }
+
+    fn put(
+        &self,
+        _key: &ResultCacheKey,
+        _value: Arc<CachedResult>,
+    ) -> Option<Arc<CachedResult>> {
+        panic!("Put cache in DefaultResultCache without Extra not supported.")
+    }
+
+    fn put_with_extra(
+        &self,
+        key: &ResultCacheKey,
+        value: Arc<CachedResult>,
+        e: &Self::Extra,
+    ) -> Option<Arc<CachedResult>> {
+        let mut state = self.state.lock().unwrap();
+        let old_value = state.put(key.clone(), value, Arc::clone(e));
+        self.spill_evicted(state);
+        old_value
+    }
+
+    fn remove(&mut self, k: &ResultCacheKey) -> Option<Arc<CachedResult>> {
+        let mut state = self.state.lock().unwrap();
+        state.remove(k)
+    }
+
+    fn contains_key(&self, k: &ResultCacheKey) -> bool {
+        let state = self.state.lock().unwrap();
+        state.contains_key(k)
+    }
+
+    fn len(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        state.len()
+    }
+
+    fn clear(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.clear();
+    }
+
+    fn name(&self) -> String {
+        "DefaultResultCache".to_string()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
     use std::sync::Arc;
 
     use crate::cache::cache_manager::{
-        FileMetadata, FileMetadataCache, FileMetadataCacheEntry,
+        CachedResult, FileMetadata, FileMetadataCache, FileMetadataCacheEntry,
+        FileRangeCache, FileRangeKey, ResultCache, ResultCacheKey,
     };
     use crate::cache::cache_unit::{
-        DefaultFileStatisticsCache, DefaultFilesMetadataCache, DefaultListFilesCache,
+        DefaultFileRangeCache, DefaultFileStatisticsCache, DefaultFilesMetadataCache,
+        DefaultListFilesCache, DefaultResultCache,
     };
     use crate::cache::CacheAccessor;
+    use arrow::array::{Int64Array, RecordBatch};
     use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+    use bytes::Bytes;
     use chrono::DateTime;
-    use datafusion_common::Statistics;
+    use datafusion_common::{Statistics, TableReference};
+    use datafusion_expr::logical_plan::builder::table_scan;
+    use datafusion_expr::LogicalPlan;
     use object_store::path::Path;
     use object_store::ObjectMeta;
 
@@ -947,4 +1740,193 @@ mod tests {
         cache.clear();
         assert_eq!(cache.list_entries(), HashMap::from([]));
     }
+
+    #[test]
+    fn test_default_file_range_cache() {
+        let (object_meta, _) = generate_test_metadata_with_size("1", 1000);
+        let key = |start: u64, end: u64| FileRangeKey {
+            location: object_meta.location.clone(),
+            range: start..end,
+        };
+        let bytes = |len: usize| Bytes::from(vec![0u8; len]);
+
+        // disabled by default
+        let cache = DefaultFileRangeCache::new(0);
+        cache.put_with_extra(&key(0, 10), bytes(10), &object_meta);
+        assert!(cache.is_empty());
+
+        cache.update_cache_limit(250);
+        cache.put_with_extra(&key(0, 100), bytes(100), &object_meta);
+        cache.put_with_extra(&key(100, 200), bytes(100), &object_meta);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.memory_used(), 200);
+        assert_eq!(
+            cache.get_with_extra(&key(0, 100), &object_meta),
+            Some(bytes(100))
+        );
+        // only exact ranges are cached
+        assert!(cache.get_with_extra(&key(0, 50), &object_meta).is_none());
+
+        // the least recently used range is evicted
+        cache.put_with_extra(&key(200, 300), bytes(100), &object_meta);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.memory_used(), 200);
+        assert!(cache.contains_key(&key(0, 100)));
+        assert!(!cache.contains_key(&key(100, 200)));
+
+        // ranges larger than the limit are not cached
+        cache.put_with_extra(&key(300, 600), bytes(300), &object_meta);
+        assert!(!cache.contains_key(&key(300, 600)));
+
+        // ranges of a file that changed are not returned
+        let changed_meta = ObjectMeta {
+            size: 2000,
+            ..object_meta.clone()
+        };
+        assert!(cache.get_with_extra(&key(0, 100), &changed_meta).is_none());
+
+        // reducing the limit evicts entries
+        cache.update_cache_limit(100);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.memory_used(), 100);
+
+        cache.clear();
+        assert!(cache.is_empty());
+        assert_eq!(cache.memory_used(), 0);
+    }
+
+    fn result_cache_entry(
+        table: &str,
+        fingerprint: u64,
+        e_tag: &str,
+        rows: usize,
+    ) -> (ResultCacheKey, Arc<CachedResult>, Arc<LogicalPlan>) {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, false)]));
+        let plan = table_scan(Some(table), &schema, None)
+            .unwrap()
+            .build()
+            .unwrap();
+        let key = ResultCacheKey {
+            plan_fingerprint: fingerprint,
+            files: vec![ObjectMeta {
+                location: Path::from(format!("{table}/1.parquet")),
+                last_modified: DateTime::parse_from_rfc3339("2022-09-27T22:36:00+02:00")
+                    .unwrap()
+                    .into(),
+                size: 1024,
+                e_tag: Some(e_tag.to_string()),
+                version: None,
+            }],
+        };
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![Arc::new(Int64Array::from_iter_values(0..rows as i64))],
+        )
+        .unwrap();
+        let result = Arc::new(CachedResult {
+            schema,
+            batches: vec![batch],
+        });
+        (key, result, Arc::new(plan))
+    }
+
+    #[test]
+    fn test_default_result_cache() {
+        let cache = DefaultResultCache::new(1024 * 1024);
+        let (key, result, plan) = result_cache_entry("t", 1, "v1", 10);
+        let (_, _, other_plan) = result_cache_entry("u", 1, "v1", 10);
+
+        assert!(cache.get_with_extra(&key, &plan).is_none());
+        cache.put_with_extra(&key, Arc::clone(&result), &plan);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.memory_used(), result.memory_size());
+
+        let cached = cache.get_with_extra(&key, &plan).unwrap();
+        assert_eq!(cached.batches, result.batches);
+        // a different query with the same fingerprint does not get the result
+        assert!(cache.get_with_extra(&key, &other_plan).is_none());
+
+        // the same query over a new version of the file replaces the result
+        let (new_key, new_result, _) = result_cache_entry("t", 1, "v2", 20);
+        cache.put_with_extra(&new_key, new_result, &plan);
+        assert_eq!(cache.len(), 1);
+        assert!(!cache.contains_key(&key));
+        assert!(cache.contains_key(&new_key));
+
+        // results are removed when the table they scan is written to
+        let (key2, result2, plan2) = result_cache_entry("u", 2, "v1", 10);
+        cache.put_with_extra(&key2, result2, &plan2);
+        assert_eq!(cache.invalidate_table(&TableReference::from("public.t")), 1);
+        assert_eq!(cache.len(), 1);
+        assert!(cache.contains_key(&key2));
+
+        cache.clear();
+        assert_eq!(cache.len(), 0);
+        assert_eq!(cache.memory_used(), 0);
+    }
+
+    #[test]
+    fn test_default_result_cache_spills_to_disk() {
+        let (key1, result1, plan1) = result_cache_entry("t1", 1, "v1", 100);
+        let (key2, result2, plan2) = result_cache_entry("t2", 2, "v1", 100);
+        let (key3, result3, plan3) = result_cache_entry("t3", 3, "v1", 100);
+        let size = result1.memory_size();
+
+        // only one result fits in memory
+        let cache = DefaultResultCache::new(size).with_disk_limit(1024 * 1024);
+        cache.put_with_extra(&key1, Arc::clone(&result1), &plan1);
+        cache.put_with_extra(&key2, Arc::clone(&result2), &plan2);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.memory_used(), size);
+        assert!(cache.disk_used() > 0);
+
+        // reading the spilled result moves it back to memory
+        let cached = cache.get_with_extra(&key1, &plan1).unwrap();
+        assert_eq!(cached.batches, result1.batches);
+        assert_eq!(cache.memory_used(), size);
+        assert_eq!(cache.len(), 2);
+        let cached = cache.get_with_extra(&key2, &plan2).unwrap();
+        assert_eq!(cached.batches, result2.batches);
+
+        // without disk limit, evicted results are dropped
+        let cache = DefaultResultCache::new(size);
+        cache.put_with_extra(&key1, result1, &plan1);
+        cache.put_with_extra(&key2, result2, &plan2);
+        assert_eq!(cache.len(), 1);
+        assert!(cache.get_with_extra(&key1, &plan1).is_none());
+        assert_eq!(cache.disk_used(), 0);
+
+        // results larger than the memory limit go to disk directly
+        let cache = DefaultResultCache::new(0).with_disk_limit(1024 * 1024);
+        cache.put_with_extra(&key3, Arc::clone(&result3), &plan3);
+        assert_eq!(cache.memory_used(), 0);
+        let disk_used = cache.disk_used();
+        assert!(disk_used > 0);
+        let cached = cache.get_with_extra(&key3, &plan3).unwrap();
+        assert_eq!(cached.batches, result3.batches);
+        // ... and stay there
+        assert_eq!(cache.memory_used(), 0);
+        assert_eq!(cache.disk_used(), disk_used);
+    }
+
+    #[test]
+    fn test_default_result_cache_spills_outside_lock() {
+        let (key1, result1, plan1) = result_cache_entry("t1", 1, "v1", 100);
+        let (key2, result2, plan2) = result_cache_entry("t2", 2, "v1", 100);
+        let size = result1.memory_size();
+        let cache = DefaultResultCache::new(size).with_disk_limit(1024 * 1024);
+
+        // the state only queues the evicted results ...
+        let mut state = cache.state.lock().unwrap();
+        state.put(key1.clone(), result1, Arc::clone(&plan1));
+        state.put(key2, result2, plan2);
+        assert_eq!(state.evicted.len(), 1);
+        assert_eq!(state.disk_used, 0);
+        assert!(!state.contains_key(&key1));
+        // ... which are written to disk once the lock is released
+        cache.spill_evicted(state);
+        assert!(cache.disk_used() > 0);
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get_with_extra(&key1, &plan1).is_some());
+    }
 }
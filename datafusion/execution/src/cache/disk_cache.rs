@@ -0,0 +1,864 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`DiskCacheObjectStore`], an [`ObjectStore`] wrapper caching the ranges
+//! read from another store in a local directory
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::cache::lru_queue::LruQueue;
+
+use async_trait::async_trait;
+use bytes::{Buf, Bytes};
+use datafusion_common::instant::Instant;
+use datafusion_common::{DataFusionError, Result};
+use futures::stream::BoxStream;
+use log::debug;
+use object_store::local::LocalFileSystem;
+use object_store::path::Path;
+use object_store::{
+    coalesce_ranges, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta,
+    ObjectStore, PutMultipartOptions, PutOptions, PutPayload, PutResult,
+    OBJECT_STORE_COALESCE_DEFAULT,
+};
+
+/// Default for [`DiskCacheObjectStore::with_metadata_ttl`]
+const DEFAULT_METADATA_TTL: Duration = Duration::from_secs(60);
+
+/// Handles the inner state of the [`DiskCacheObjectStore`] struct.
+struct DiskCacheState {
+    /// Size of each cached file, by file name
+    lru_queue: LruQueue<String, usize>,
+    size_limit: usize,
+    disk_used: usize,
+    /// Names of the files being written
+    writing: HashSet<String>,
+    /// ETag of each object read, and when it was read from the inner store
+    e_tags: HashMap<Path, (String, Instant)>,
+    metadata_ttl: Duration,
+}
+
+impl DiskCacheState {
+    /// Evicts entries from the LRU cache until `disk_used` is lower than
+    /// `size_limit`, returning the names of the files to delete.
+    fn evict_entries(&mut self) -> Vec<String> {
+        let mut evicted = vec![];
+        while self.disk_used > self.size_limit {
+            let Some((name, size)) = self.lru_queue.pop() else {
+                break;
+            };
+            self.disk_used -= size;
+            evicted.push(name);
+        }
+        evicted
+    }
+}
+
+impl std::fmt::Debug for DiskCacheState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DiskCacheState")
+            .field("entries", &self.lru_queue.len())
+            .field("size_limit", &self.size_limit)
+            .field("disk_used", &self.disk_used)
+            .field("metadata_ttl", &self.metadata_ttl)
+            .finish()
+    }
+}
+
+/// An [`ObjectStore`] that caches the byte ranges read from an inner store,
+/// such as S3, in files in a local directory, so that repeated queries over
+/// the same data do not download it again.
+///
+/// Only [`ObjectStore::get_range`] and [`ObjectStore::get_ranges`] are
+/// cached, which is how DataFusion reads Parquet files. All other
+/// operations are forwarded to the inner store.
+///
+/// # Validation
+///
+/// Cached ranges are keyed by the ETag of the object, so ranges of objects
+/// that changed since they were cached are not returned. Objects without an
+/// ETag are not cached.
+///
+/// The ETag of an object is found with a `HEAD` request to the inner store,
+/// and reused for the reads within the metadata TTL (see
+/// [`Self::with_metadata_ttl`]), so that cached ranges are returned without
+/// any request to the inner store. Ranges missing from the cache are only
+/// read if the object still has that ETag, otherwise its ETag is read again.
+///
+/// # Size limit
+///
+/// The files of the cache use at most `size_limit` bytes, and the least
+/// recently used ranges are deleted when adding a new range would exceed it.
+/// Files left in the directory by a previous instance are reused, and the
+/// temporary files of its interrupted writes are deleted, so the directory
+/// should be dedicated to the cache.
+///
+/// # Example
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use datafusion_common::Result;
+/// # use datafusion_execution::cache::disk_cache::DiskCacheObjectStore;
+/// # use datafusion_execution::runtime_env::RuntimeEnvBuilder;
+/// # use object_store::memory::InMemory;
+/// # use url::Url;
+/// # fn main() -> Result<()> {
+/// # let cache_dir = tempfile::tempdir()?;
+/// // A stand-in for a remote object store, such as `AmazonS3`
+/// let remote = Arc::new(InMemory::new());
+/// let cached = DiskCacheObjectStore::try_new(
+///     remote,
+///     cache_dir.path(),
+///     10 * 1024 * 1024 * 1024, // 10 GB
+/// )?;
+///
+/// let runtime = RuntimeEnvBuilder::new().build_arc()?;
+/// runtime.register_object_store(&Url::parse("s3://bucket").unwrap(), Arc::new(cached));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct DiskCacheObjectStore {
+    inner: Arc<dyn ObjectStore>,
+    directory: PathBuf,
+    /// The cached files, stored in `directory`
+    files: LocalFileSystem,
+    // the state is wrapped in a Mutex to ensure the operations are atomic
+    state: Mutex<DiskCacheState>,
+}
+
+impl DiskCacheObjectStore {
+    /// Create a new [`DiskCacheObjectStore`] caching the ranges read from
+    /// `inner` in `directory`, which is created if it does not exist.
+    ///
+    /// # Arguments
+    /// `size_limit`:  the maximum size of the cached files, in bytes
+    pub fn try_new(
+        inner: Arc<dyn ObjectStore>,
+        directory: impl Into<PathBuf>,
+        size_limit: usize,
+    ) -> Result<Self> {
+        let directory = directory.into();
+        std::fs::create_dir_all(&directory)?;
+        let files = LocalFileSystem::new_with_prefix(&directory)
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+
+        // Reuse the files cached by a previous instance, the most recently
+        // modified ones being the most recently used
+        let mut existing = vec![];
+        for entry in std::fs::read_dir(&directory)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if !metadata.is_file() {
+                continue;
+            }
+            if is_cache_file_name(&name) {
+                existing.push((metadata.modified()?, name, metadata.len() as usize));
+            } else if is_staging_file_name(&name) {
+                std::fs::remove_file(entry.path())?;
+            }
+        }
+        existing.sort();
+
+        let mut state = DiskCacheState {
+            lru_queue: LruQueue::new(),
+            size_limit,
+            disk_used: 0,
+            writing: HashSet::new(),
+            e_tags: HashMap::new(),
+            metadata_ttl: DEFAULT_METADATA_TTL,
+        };
+        for (_, name, size) in existing {
+            state.lru_queue.put(name, size);
+            state.disk_used += size;
+        }
+        for name in state.evict_entries() {
+            std::fs::remove_file(directory.join(name))?;
+        }
+
+        Ok(Self {
+            inner,
+            directory,
+            files,
+            state: Mutex::new(state),
+        })
+    }
+
+    /// Reuse the ETag of an object read from the inner store for `ttl`,
+    /// instead of reading it again for every read. Defaults to 60 seconds.
+    ///
+    /// Within the TTL, ranges cached before the object changed may still be
+    /// returned. A TTL of zero reads the ETag for every read.
+    pub fn with_metadata_ttl(self, ttl: Duration) -> Self {
+        self.state.lock().unwrap().metadata_ttl = ttl;
+        self
+    }
+
+    /// Returns the directory the ranges are cached in.
+    pub fn directory(&self) -> &std::path::Path {
+        &self.directory
+    }
+
+    /// Returns the maximum size of the cached files, in bytes.
+    pub fn size_limit(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        state.size_limit
+    }
+
+    /// Returns the size of the cached files, in bytes.
+    pub fn disk_used(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        state.disk_used
+    }
+
+    /// Returns the number of cached ranges.
+    pub fn len(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        state.lru_queue.len()
+    }
+
+    /// Returns true if no range is cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the ETag of `location`, read from the inner store unless it was
+    /// read within the metadata TTL.
+    async fn e_tag(&self, location: &Path) -> object_store::Result<Option<String>> {
+        {
+            let mut state = self.state.lock().unwrap();
+            let ttl = state.metadata_ttl;
+            match state.e_tags.get(location) {
+                Some((e_tag, read_at)) if read_at.elapsed() < ttl => {
+                    return Ok(Some(e_tag.clone()));
+                }
+                Some(_) => {
+                    state.e_tags.remove(location);
+                }
+                None => {}
+            }
+        }
+
+        let meta = self.inner.head(location).await?;
+        if let Some(e_tag) = &meta.e_tag {
+            let mut state = self.state.lock().unwrap();
+            if !state.metadata_ttl.is_zero() {
+                state
+                    .e_tags
+                    .insert(location.clone(), (e_tag.clone(), Instant::now()));
+            }
+        }
+        Ok(meta.e_tag)
+    }
+
+    /// Forgets the ETag of `location`, which is being written to through this
+    /// store, so that it is read again on the next read.
+    fn forget_e_tag(&self, location: &Path) {
+        self.state.lock().unwrap().e_tags.remove(location);
+    }
+
+    /// Reads `ranges` of the version `e_tag` of `location` from the cache,
+    /// and the missing ones from the inner store, adding them to the cache.
+    async fn get_cached_ranges(
+        &self,
+        location: &Path,
+        e_tag: &str,
+        ranges: &[Range<u64>],
+    ) -> object_store::Result<Vec<Bytes>> {
+        let keys: Vec<CacheKey> = ranges
+            .iter()
+            .map(|range| CacheKey::new(location, e_tag, range.clone()))
+            .collect();
+
+        let mut result = Vec::with_capacity(keys.len());
+        for key in &keys {
+            result.push(self.read_entry(key).await);
+        }
+
+        let missing: Vec<Range<u64>> = keys
+            .iter()
+            .zip(&result)
+            .filter(|(_, cached)| cached.is_none())
+            .map(|(key, _)| key.range.clone())
+            .collect();
+        if missing.is_empty() {
+            return Ok(result.into_iter().flatten().collect());
+        }
+
+        let fetched = coalesce_ranges(
+            &missing,
+            |range| self.fetch_range(location, e_tag, range),
+            OBJECT_STORE_COALESCE_DEFAULT,
+        )
+        .await;
+        let fetched = match fetched {
+            Ok(fetched) => fetched,
+            // The object changed since its ETag was read, do not cache it, and
+            // read its ETag again on the next read
+            Err(object_store::Error::Precondition { .. }) => {
+                self.forget_e_tag(location);
+                return self.inner.get_ranges(location, ranges).await;
+            }
+            Err(e) => return Err(e),
+        };
+
+        let mut fetched = fetched.into_iter();
+        for (key, slot) in keys.iter().zip(result.iter_mut()) {
+            if slot.is_some() {
+                continue;
+            }
+            let bytes = fetched.next().ok_or_else(|| object_store::Error::Generic {
+                store: STORE,
+                source: "Fewer byte ranges returned than requested".into(),
+            })?;
+            self.write_entry(key, bytes.clone()).await;
+            *slot = Some(bytes);
+        }
+
+        Ok(result.into_iter().flatten().collect())
+    }
+
+    /// Reads `range` of `location` from the inner store, failing with
+    /// [`object_store::Error::Precondition`] if its ETag is not `e_tag`.
+    async fn fetch_range(
+        &self,
+        location: &Path,
+        e_tag: &str,
+        range: Range<u64>,
+    ) -> object_store::Result<Bytes> {
+        let options = GetOptions {
+            range: Some(range.into()),
+            if_match: Some(e_tag.to_string()),
+            ..Default::default()
+        };
+        self.inner.get_opts(location, options).await?.bytes().await
+    }
+
+    /// Returns the cached range, if any. Failing to read the cache is not an
+    /// error, the range is fetched from the inner store instead.
+    async fn read_entry(&self, key: &CacheKey) -> Option<Bytes> {
+        let name = key.file_name();
+        {
+            let mut state = self.state.lock().unwrap();
+            state.lru_queue.get(&name)?;
+        }
+
+        let contents = match self.files.get(&Path::from(name.as_str())).await {
+            Ok(result) => result.bytes().await,
+            Err(e) => Err(e),
+        };
+        match contents.map(|contents| key.decode(contents)) {
+            Ok(Some(bytes)) => Some(bytes),
+            Ok(None) => {
+                // Another range has the same file name, keep the existing one
+                None
+            }
+            Err(e) => {
+                debug!("Failed to read cached range from disk: {e}");
+                let mut state = self.state.lock().unwrap();
+                if let Some(size) = state.lru_queue.remove(&name) {
+                    state.disk_used -= size;
+                }
+                None
+            }
+        }
+    }
+
+    /// Adds a range to the cache, evicting the least recently used ranges
+    /// if required. Failing to write the cache is not an error.
+    async fn write_entry(&self, key: &CacheKey, bytes: Bytes) {
+        let name = key.file_name();
+        let header = key.header();
+        let size = header.len() + bytes.len();
+        {
+            let mut state = self.state.lock().unwrap();
+            // no point in caching a range that cannot fit entirely, or that
+            // is cached or being cached concurrently
+            if size > state.size_limit
+                || state.lru_queue.contains_key(&name)
+                || !state.writing.insert(name.clone())
+            {
+                return;
+            }
+        }
+
+        let payload = PutPayload::from_iter([header, bytes]);
+        let written = self.files.put(&Path::from(name.as_str()), payload).await;
+        if let Err(e) = written {
+            debug!("Failed to write cached range to disk: {e}");
+            self.remove_staging_files(&name);
+            self.state.lock().unwrap().writing.remove(&name);
+            return;
+        }
+
+        let evicted = {
+            let mut state = self.state.lock().unwrap();
+            state.writing.remove(&name);
+            if let Some(old_size) = state.lru_queue.put(name, size) {
+                state.disk_used -= old_size;
+            }
+            state.disk_used += size;
+            state.evict_entries()
+        };
+        for name in evicted {
+            if let Err(e) = self.files.delete(&Path::from(name.as_str())).await {
+                debug!("Failed to delete evicted range from disk: {e}");
+            }
+        }
+    }
+
+    /// Deletes the temporary files left by a failed write of the file `name`.
+    ///
+    /// [`LocalFileSystem`] writes to `<name>#<n>` files, numbered from 1,
+    /// before renaming them.
+    fn remove_staging_files(&self, name: &str) {
+        for n in 1.. {
+            let path = self.directory.join(format!("{name}#{n}"));
+            match std::fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => return,
+                Err(e) => {
+                    debug!("Failed to delete {}: {e}", path.display());
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Name of the store in the errors returned by [`DiskCacheObjectStore`]
+const STORE: &str = "DiskCacheObjectStore";
+
+/// Returns true if `name` is the name of a file written by the cache, as
+/// opposed to a temporary file of an interrupted write
+fn is_cache_file_name(name: &str) -> bool {
+    name.len() == 16 && name.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Returns true if `name` is the name of a temporary file written by
+/// [`LocalFileSystem`] before it is renamed to a cache file name
+fn is_staging_file_name(name: &str) -> bool {
+    name.split_once('#').is_some_and(|(name, n)| {
+        is_cache_file_name(name) && !n.is_empty() && n.chars().all(|c| c.is_ascii_digit())
+    })
+}
+
+/// Identifies a range of a version of an object
+struct CacheKey {
+    location: Path,
+    e_tag: String,
+    range: Range<u64>,
+}
+
+impl CacheKey {
+    fn new(location: &Path, e_tag: &str, range: Range<u64>) -> Self {
+        Self {
+            location: location.clone(),
+            e_tag: e_tag.to_string(),
+            range,
+        }
+    }
+
+    /// Full description of the key, stored at the start of the cached file
+    /// to detect file name collisions
+    fn description(&self) -> String {
+        format!(
+            "{}\n{}\n{}..{}",
+            self.location, self.e_tag, self.range.start, self.range.end
+        )
+    }
+
+    /// The name of the file the range is cached in
+    fn file_name(&self) -> String {
+        let mut hasher = DefaultHasher::new();
+        self.description().hash(&mut hasher);
+        format!("{:016x}", hasher.finish())
+    }
+
+    /// The header of the cached file: the length of the description, as a
+    /// little endian u32, followed by the description
+    fn header(&self) -> Bytes {
+        let description = self.description();
+        let mut header = Vec::with_capacity(4 + description.len());
+        header.extend_from_slice(&(description.len() as u32).to_le_bytes());
+        header.extend_from_slice(description.as_bytes());
+        header.into()
+    }
+
+    /// Returns the cached range from the contents of its file, or `None` if
+    /// the file caches another range
+    fn decode(&self, mut contents: Bytes) -> Option<Bytes> {
+        let header = self.header();
+        if !contents.starts_with(&header) {
+            return None;
+        }
+        contents.advance(header.len());
+        let expected_len = self.range.end.saturating_sub(self.range.start);
+        (contents.len() as u64 == expected_len).then_some(contents)
+    }
+}
+
+impl Display for DiskCacheObjectStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "DiskCacheObjectStore({}, {})",
+            self.inner,
+            self.directory.display()
+        )
+    }
+}
+
+/// Reads of ranges are cached, all other methods are forwarded to the inner
+/// object store.
+#[async_trait]
+impl ObjectStore for DiskCacheObjectStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> object_store::Result<PutResult> {
+        self.forget_e_tag(location);
+        self.inner.put_opts(location, payload, opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> object_store::Result<Box<dyn MultipartUpload>> {
+        self.forget_e_tag(location);
+        self.inner.put_multipart_opts(location, opts).await
+    }
+
+    async fn get_opts(
+        &self,
+        location: &Path,
+        options: GetOptions,
+    ) -> object_store::Result<GetResult> {
+        self.inner.get_opts(location, options).await
+    }
+
+    async fn get_range(
+        &self,
+        location: &Path,
+        range: Range<u64>,
+    ) -> object_store::Result<Bytes> {
+        let mut bytes = self.get_ranges(location, &[range]).await?;
+        Ok(bytes.remove(0))
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[Range<u64>],
+    ) -> object_store::Result<Vec<Bytes>> {
+        match self.e_tag(location).await? {
+            Some(e_tag) => self.get_cached_ranges(location, &e_tag, ranges).await,
+            None => self.inner.get_ranges(location, ranges).await,
+        }
+    }
+
+    async fn head(&self, location: &Path) -> object_store::Result<ObjectMeta> {
+        self.inner.head(location).await
+    }
+
+    async fn delete(&self, location: &Path) -> object_store::Result<()> {
+        self.forget_e_tag(location);
+        self.inner.delete(location).await
+    }
+
+    fn list(
+        &self,
+        prefix: Option<&Path>,
+    ) -> BoxStream<'static, object_store::Result<ObjectMeta>> {
+        self.inner.list(prefix)
+    }
+
+    async fn list_with_delimiter(
+        &self,
+        prefix: Option<&Path>,
+    ) -> object_store::Result<ListResult> {
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> object_store::Result<()> {
+        self.forget_e_tag(to);
+        self.inner.copy(from, to).await
+    }
+
+    async fn copy_if_not_exists(
+        &self,
+        from: &Path,
+        to: &Path,
+    ) -> object_store::Result<()> {
+        self.forget_e_tag(to);
+        self.inner.copy_if_not_exists(from, to).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use object_store::memory::InMemory;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// An in-memory stand-in for a remote object store, counting the ranges
+    /// and metadata read from it
+    #[derive(Debug, Default)]
+    struct CountingStore {
+        inner: InMemory,
+        ranges_read: AtomicUsize,
+        heads: AtomicUsize,
+    }
+
+    impl Display for CountingStore {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write!(f, "CountingStore")
+        }
+    }
+
+    #[async_trait]
+    impl ObjectStore for CountingStore {
+        async fn put_opts(
+            &self,
+            location: &Path,
+            payload: PutPayload,
+            opts: PutOptions,
+        ) -> object_store::Result<PutResult> {
+            self.inner.put_opts(location, payload, opts).await
+        }
+
+        async fn put_multipart_opts(
+            &self,
+            location: &Path,
+            opts: PutMultipartOptions,
+        ) -> object_store::Result<Box<dyn MultipartUpload>> {
+            self.inner.put_multipart_opts(location, opts).await
+        }
+
+        async fn get_opts(
+            &self,
+            location: &Path,
+            options: GetOptions,
+        ) -> object_store::Result<GetResult> {
+            if options.head {
+                self.heads.fetch_add(1, Ordering::SeqCst);
+            } else if options.range.is_some() {
+                self.ranges_read.fetch_add(1, Ordering::SeqCst);
+            }
+            self.inner.get_opts(location, options).await
+        }
+
+        async fn delete(&self, location: &Path) -> object_store::Result<()> {
+            self.inner.delete(location).await
+        }
+
+        fn list(
+            &self,
+            prefix: Option<&Path>,
+        ) -> BoxStream<'static, object_store::Result<ObjectMeta>> {
+            self.inner.list(prefix)
+        }
+
+        async fn list_with_delimiter(
+            &self,
+            prefix: Option<&Path>,
+        ) -> object_store::Result<ListResult> {
+            self.inner.list_with_delimiter(prefix).await
+        }
+
+        async fn copy(&self, from: &Path, to: &Path) -> object_store::Result<()> {
+            self.inner.copy(from, to).await
+        }
+
+        async fn copy_if_not_exists(
+            &self,
+            from: &Path,
+            to: &Path,
+        ) -> object_store::Result<()> {
+            self.inner.copy_if_not_exists(from, to).await
+        }
+    }
+
+    impl CountingStore {
+        fn ranges_read(&self) -> usize {
+            self.ranges_read.load(Ordering::SeqCst)
+        }
+
+        fn heads(&self) -> usize {
+            self.heads.load(Ordering::SeqCst)
+        }
+    }
+
+    async fn put(store: &dyn ObjectStore, location: &Path, data: &'static [u8]) {
+        store
+            .put(location, PutPayload::from_static(data))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn cache_ranges() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let remote = Arc::new(CountingStore::default());
+        let location = Path::from("data/file.parquet");
+        put(remote.as_ref(), &location, b"0123456789").await;
+
+        let store =
+            DiskCacheObjectStore::try_new(Arc::clone(&remote) as _, dir.path(), 1024)?;
+
+        assert_eq!(store.get_range(&location, 2..5).await.unwrap(), "234");
+        assert_eq!(remote.ranges_read(), 1);
+        assert_eq!(store.len(), 1);
+
+        // read from the cache
+        assert_eq!(store.get_range(&location, 2..5).await.unwrap(), "234");
+        assert_eq!(remote.ranges_read(), 1);
+
+        // only the missing ranges are read
+        let ranges = store.get_ranges(&location, &[2..5, 6..8]).await.unwrap();
+        assert_eq!(ranges, vec!["234", "67"]);
+        assert_eq!(remote.ranges_read(), 2);
+        assert_eq!(store.len(), 2);
+
+        // the ETag of the object is only read once within the metadata TTL
+        assert_eq!(remote.heads(), 1);
+
+        // missing ranges of an object that changed are read from its new
+        // version, and its ETag is read again
+        put(remote.as_ref(), &location, b"abcdefghij").await;
+        assert_eq!(store.get_range(&location, 0..2).await.unwrap(), "ab");
+        assert_eq!(store.get_range(&location, 2..5).await.unwrap(), "cde");
+        assert_eq!(remote.heads(), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn metadata_ttl() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let remote = Arc::new(CountingStore::default());
+        let location = Path::from("file");
+        put(remote.as_ref(), &location, b"0123456789").await;
+
+        let store =
+            DiskCacheObjectStore::try_new(Arc::clone(&remote) as _, dir.path(), 1024)?
+                .with_metadata_ttl(Duration::ZERO);
+        assert_eq!(store.get_range(&location, 2..5).await.unwrap(), "234");
+        assert_eq!(store.get_range(&location, 2..5).await.unwrap(), "234");
+        assert_eq!(remote.heads(), 2);
+        assert_eq!(remote.ranges_read(), 1);
+
+        // ranges of an object that changed are read again
+        put(remote.as_ref(), &location, b"abcdefghij").await;
+        assert_eq!(store.get_range(&location, 2..5).await.unwrap(), "cde");
+        assert_eq!(remote.ranges_read(), 2);
+
+        // writes through the store are seen within the metadata TTL
+        let store = store.with_metadata_ttl(Duration::from_secs(3600));
+        assert_eq!(store.get_range(&location, 2..5).await.unwrap(), "cde");
+        put(&store, &location, b"ABCDEFGHIJ").await;
+        assert_eq!(store.get_range(&location, 2..5).await.unwrap(), "CDE");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn size_limit() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let remote = Arc::new(CountingStore::default());
+        let location = Path::from("file");
+        put(remote.as_ref(), &location, &[0; 1000]).await;
+
+        let e_tag = remote.head(&location).await.unwrap().e_tag.unwrap();
+        let key_size = |range: Range<u64>| {
+            let key = CacheKey::new(&location, &e_tag, range.clone());
+            key.header().len() + (range.end - range.start) as usize
+        };
+        let limit = key_size(0..100) + key_size(100..200);
+        let store =
+            DiskCacheObjectStore::try_new(Arc::clone(&remote) as _, dir.path(), limit)?;
+
+        store.get_range(&location, 0..100).await.unwrap();
+        store.get_range(&location, 100..200).await.unwrap();
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.disk_used(), limit);
+
+        // the least recently used range is evicted
+        store.get_range(&location, 0..100).await.unwrap();
+        store.get_range(&location, 200..300).await.unwrap();
+        assert_eq!(store.len(), 2);
+        assert!(store.disk_used() <= limit);
+        assert_eq!(std::fs::read_dir(dir.path())?.count(), 2);
+        let ranges_read = remote.ranges_read();
+        store.get_range(&location, 0..100).await.unwrap();
+        assert_eq!(remote.ranges_read(), ranges_read);
+
+        // ranges larger than the limit are not cached
+        store.get_range(&location, 0..1000).await.unwrap();
+        assert_eq!(store.len(), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reuse_cached_files() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let remote = Arc::new(CountingStore::default());
+        let location = Path::from("file");
+        put(remote.as_ref(), &location, b"0123456789").await;
+
+        let store =
+            DiskCacheObjectStore::try_new(Arc::clone(&remote) as _, dir.path(), 1024)?;
+        store.get_range(&location, 0..4).await.unwrap();
+        let disk_used = store.disk_used();
+        drop(store);
+
+        let store =
+            DiskCacheObjectStore::try_new(Arc::clone(&remote) as _, dir.path(), 1024)?;
+        assert_eq!(store.len(), 1);
+        assert_eq!(store.disk_used(), disk_used);
+        assert_eq!(store.get_range(&location, 0..4).await.unwrap(), "0123");
+        assert_eq!(remote.ranges_read(), 1);
+
+        // files exceeding a lower limit are deleted
+        drop(store);
+        let store =
+            DiskCacheObjectStore::try_new(Arc::clone(&remote) as _, dir.path(), 1)?;
+        assert!(store.is_empty());
+        assert_eq!(std::fs::read_dir(dir.path())?.count(), 0);
+
+        // as well as the temporary files of interrupted writes
+        drop(store);
+        std::fs::write(dir.path().join("0123456789abcdef#1"), b"partial")?;
+        std::fs::write(dir.path().join("other#1"), b"not from the cache")?;
+        DiskCacheObjectStore::try_new(Arc::clone(&remote) as _, dir.path(), 1024)?;
+        let names: Vec<_> = std::fs::read_dir(dir.path())?
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(names, vec!["other#1"]);
+        Ok(())
+    }
+}
@@ -26,10 +26,12 @@
 
 //! DataFusion execution configuration and runtime structures
 
+pub mod admission;
 pub mod cache;
 pub mod config;
 pub mod disk_manager;
 pub mod memory_pool;
+pub mod metrics_exporter;
 pub mod object_store;
 #[cfg(feature = "parquet_encryption")]
 pub mod parquet_encryption;
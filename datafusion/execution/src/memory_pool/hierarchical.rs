@@ -0,0 +1,389 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::memory_pool::{MemoryConsumer, MemoryLimit, MemoryPool, MemoryReservation};
+use datafusion_common::Result;
+use log::debug;
+use parking_lot::Mutex;
+use std::sync::Arc;
+
+/// A [`MemoryPool`] that is a child of another, shared, [`MemoryPool`].
+///
+/// Memory is first reserved in the `inner` pool of the child, which enforces
+/// the limit and fairness policy among the consumers of the child (for
+/// example a [`GreedyMemoryPool`] or [`FairSpillPool`]). It is then also
+/// reserved in the `parent` pool, which enforces the limit and fairness among
+/// all of its children. An allocation succeeds only if both pools allow it.
+///
+/// This makes it possible to give each query (or session) of a multi-tenant
+/// service its own memory limit, while bounding the total memory used by all
+/// queries. Child pools can be nested, for example a child pool per query
+/// whose parent is a child pool per session.
+///
+/// The parent pool sees each child pool as (at most) two [`MemoryConsumer`]s
+/// named after the child: one accounting for the memory of the spillable
+/// consumers of the child, and one for the unspillable consumers. A
+/// [`FairSpillPool`] parent therefore shares the memory available for
+/// spilling evenly among the children with spillable consumers, rather than
+/// among all the spillable consumers of all children.
+///
+/// ```text
+///                  ┌──────────────────────────────┐
+///                  │   Parent (e.g. FairSpill)    │
+///                  └──────────────────────────────┘
+///                    ▲                          ▲
+///                    │ query_1                  │ query_2
+///       ┌────────────┴────────────┐┌────────────┴────────────┐
+///       │ ChildMemoryPool(inner)  ││ ChildMemoryPool(inner)  │
+///       └─────────────────────────┘└─────────────────────────┘
+///            ▲           ▲                 ▲           ▲
+///        Consumer    Consumer          Consumer    Consumer
+/// ```
+///
+/// # Example
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use datafusion_execution::memory_pool::{
+///     ChildMemoryPool, FairSpillPool, GreedyMemoryPool, MemoryConsumer, MemoryPool,
+/// };
+///
+/// // 100 bytes shared by all queries
+/// let parent: Arc<dyn MemoryPool> = Arc::new(FairSpillPool::new(100));
+///
+/// // Each query may use up to 60 bytes
+/// let query_1: Arc<dyn MemoryPool> =
+///     Arc::new(ChildMemoryPool::new("query_1", &parent, GreedyMemoryPool::new(60)));
+/// let query_2: Arc<dyn MemoryPool> =
+///     Arc::new(ChildMemoryPool::new("query_2", &parent, GreedyMemoryPool::new(60)));
+///
+/// let mut r1 = MemoryConsumer::new("r1").register(&query_1);
+/// r1.try_grow(50).unwrap();
+/// // Exceeds the limit of query_1
+/// assert!(r1.try_grow(20).is_err());
+///
+/// let mut r2 = MemoryConsumer::new("r2").register(&query_2);
+/// // Within the limit of query_2, but exceeds the limit of the parent
+/// assert!(r2.try_grow(60).is_err());
+/// r2.try_grow(50).unwrap();
+/// assert_eq!(parent.reserved(), 100);
+/// ```
+///
+/// Use [`RuntimeEnvBuilder::with_memory_pool`] to execute a query using a
+/// child pool.
+///
+/// [`GreedyMemoryPool`]: crate::memory_pool::GreedyMemoryPool
+/// [`FairSpillPool`]: crate::memory_pool::FairSpillPool
+/// [`RuntimeEnvBuilder::with_memory_pool`]: crate::runtime_env::RuntimeEnvBuilder::with_memory_pool
+#[derive(Debug)]
+pub struct ChildMemoryPool<I> {
+    /// The name of this pool, used to name its reservations in the parent
+    name: String,
+    /// The pool this pool reserves its memory from
+    parent: Arc<dyn MemoryPool>,
+    /// The pool enforcing the limit and policy among the consumers of this pool
+    inner: I,
+    state: Mutex<ChildMemoryPoolState>,
+}
+
+#[derive(Debug)]
+struct ChildMemoryPoolState {
+    /// The number of registered consumers that can spill
+    num_spill: usize,
+
+    /// The reservation in the parent for consumers that can spill, registered
+    /// while this pool has at least one spillable consumer
+    spillable: Option<MemoryReservation>,
+
+    /// The reservation in the parent for consumers that cannot spill
+    unspillable: MemoryReservation,
+}
+
+impl ChildMemoryPoolState {
+    fn parent_reservation(
+        &mut self,
+        consumer: &MemoryConsumer,
+    ) -> &mut MemoryReservation {
+        match (consumer.can_spill(), &mut self.spillable) {
+            (true, Some(spillable)) => spillable,
+            _ => &mut self.unspillable,
+        }
+    }
+}
+
+impl<I: MemoryPool> ChildMemoryPool<I> {
+    /// Create a new pool named `name` that enforces the limits of `inner` and
+    /// reserves its memory from `parent`
+    pub fn new(name: impl Into<String>, parent: &Arc<dyn MemoryPool>, inner: I) -> Self {
+        let name = name.into();
+        debug!("Created new ChildMemoryPool(name={name})");
+        let unspillable = MemoryConsumer::new(name.clone()).register(parent);
+        Self {
+            name,
+            parent: Arc::clone(parent),
+            inner,
+            state: Mutex::new(ChildMemoryPoolState {
+                num_spill: 0,
+                spillable: None,
+                unspillable,
+            }),
+        }
+    }
+
+    /// Returns the name of this pool
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the pool this pool reserves its memory from
+    pub fn parent(&self) -> &Arc<dyn MemoryPool> {
+        &self.parent
+    }
+
+    /// Returns the pool enforcing the limits among the consumers of this pool
+    pub fn inner(&self) -> &I {
+        &self.inner
+    }
+}
+
+impl<I: MemoryPool> MemoryPool for ChildMemoryPool<I> {
+    fn register(&self, consumer: &MemoryConsumer) {
+        self.inner.register(consumer);
+        if consumer.can_spill() {
+            let mut state = self.state.lock();
+            state.num_spill += 1;
+            if state.spillable.is_none() {
+                state.spillable = Some(
+                    MemoryConsumer::new(self.name.clone())
+                        .with_can_spill(true)
+                        .register(&self.parent),
+                );
+            }
+        }
+    }
+
+    fn unregister(&self, consumer: &MemoryConsumer) {
+        self.inner.unregister(consumer);
+        if consumer.can_spill() {
+            let mut state = self.state.lock();
+            state.num_spill = state.num_spill.checked_sub(1).unwrap();
+            if state.num_spill == 0 {
+                // Unregisters this pool as a spilling consumer of the parent
+                state.spillable = None;
+            }
+        }
+    }
+
+    fn grow(&self, reservation: &MemoryReservation, additional: usize) {
+        self.inner.grow(reservation, additional);
+        self.state
+            .lock()
+            .parent_reservation(reservation.consumer())
+            .grow(additional);
+    }
+
+    fn shrink(&self, reservation: &MemoryReservation, shrink: usize) {
+        self.inner.shrink(reservation, shrink);
+        self.state
+            .lock()
+            .parent_reservation(reservation.consumer())
+            .shrink(shrink);
+    }
+
+    fn try_grow(&self, reservation: &MemoryReservation, additional: usize) -> Result<()> {
+        self.inner.try_grow(reservation, additional)?;
+        let result = self
+            .state
+            .lock()
+            .parent_reservation(reservation.consumer())
+            .try_grow(additional);
+        if result.is_err() {
+            // Undo the reservation in the inner pool
+            self.inner.shrink(reservation, additional);
+        }
+        result
+    }
+
+    fn reserved(&self) -> usize {
+        self.inner.reserved()
+    }
+
+    fn memory_limit(&self) -> MemoryLimit {
+        match (self.inner.memory_limit(), self.parent.memory_limit()) {
+            (MemoryLimit::Finite(inner), MemoryLimit::Finite(parent)) => {
+                MemoryLimit::Finite(inner.min(parent))
+            }
+            (MemoryLimit::Infinite, parent) => parent,
+            (inner, _) => inner,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory_pool::{
+        FairSpillPool, GreedyMemoryPool, TrackConsumersPool, UnboundedMemoryPool,
+    };
+    use insta::assert_snapshot;
+    use std::num::NonZeroUsize;
+
+    #[test]
+    fn test_child_limits() {
+        let parent = Arc::new(GreedyMemoryPool::new(100)) as _;
+        let child1 = Arc::new(ChildMemoryPool::new(
+            "child1",
+            &parent,
+            GreedyMemoryPool::new(60),
+        )) as _;
+        let child2 = Arc::new(ChildMemoryPool::new(
+            "child2",
+            &parent,
+            GreedyMemoryPool::new(60),
+        )) as _;
+
+        let mut r1 = MemoryConsumer::new("r1").register(&child1);
+        r1.try_grow(50).unwrap();
+        assert_eq!(child1.reserved(), 50);
+        assert_eq!(parent.reserved(), 50);
+
+        // Exceeds the limit of child1
+        let err = r1.try_grow(20).unwrap_err().strip_backtrace();
+        assert_snapshot!(err, @"Resources exhausted: Failed to allocate additional 20.0 B for r1 with 50.0 B already allocated for this reservation - 10.0 B remain available for the total pool");
+        assert_eq!(child1.reserved(), 50);
+        assert_eq!(parent.reserved(), 50);
+
+        // Within the limit of child2, but exceeds the limit of the parent
+        let mut r2 = MemoryConsumer::new("r2").register(&child2);
+        let err = r2.try_grow(60).unwrap_err().strip_backtrace();
+        assert_snapshot!(err, @"Resources exhausted: Failed to allocate additional 60.0 B for child2 with 0.0 B already allocated for this reservation - 50.0 B remain available for the total pool");
+        // The failed allocation is not accounted in the child either
+        assert_eq!(child2.reserved(), 0);
+        assert_eq!(parent.reserved(), 50);
+
+        r2.try_grow(50).unwrap();
+        assert_eq!(parent.reserved(), 100);
+
+        // Infallible growth is accounted in both pools
+        r2.grow(30);
+        assert_eq!(child2.reserved(), 80);
+        assert_eq!(parent.reserved(), 130);
+
+        drop(r1);
+        assert_eq!(child1.reserved(), 0);
+        assert_eq!(parent.reserved(), 80);
+
+        r2.shrink(80);
+        assert_eq!(child2.reserved(), 0);
+        assert_eq!(parent.reserved(), 0);
+    }
+
+    #[test]
+    fn test_fair_spill_between_children() {
+        let parent = Arc::new(FairSpillPool::new(100)) as _;
+        let child1 = Arc::new(ChildMemoryPool::new(
+            "child1",
+            &parent,
+            FairSpillPool::new(100),
+        )) as _;
+        let child2 = Arc::new(ChildMemoryPool::new(
+            "child2",
+            &parent,
+            FairSpillPool::new(100),
+        )) as _;
+
+        let mut r1 = MemoryConsumer::new("r1")
+            .with_can_spill(true)
+            .register(&child1);
+        let mut r2 = MemoryConsumer::new("r2")
+            .with_can_spill(true)
+            .register(&child1);
+        let mut r3 = MemoryConsumer::new("r3")
+            .with_can_spill(true)
+            .register(&child2);
+
+        // Spillable consumers of the same child share the memory of the child
+        let err = r1.try_grow(60).unwrap_err().strip_backtrace();
+        assert_snapshot!(err, @"Resources exhausted: Failed to allocate additional 60.0 B for r1 with 0.0 B already allocated for this reservation - 50.0 B remain available for the total pool");
+        r1.try_grow(50).unwrap();
+
+        // Children with spillable consumers share the memory of the parent
+        let err = r2.try_grow(50).unwrap_err().strip_backtrace();
+        assert_snapshot!(err, @"Resources exhausted: Failed to allocate additional 50.0 B for child1 with 50.0 B already allocated for this reservation - 50.0 B remain available for the total pool");
+        assert_eq!(child1.reserved(), 50);
+        r3.try_grow(50).unwrap();
+        assert_eq!(parent.reserved(), 100);
+
+        // Once child2 has no spillable consumers, child1 may use all the memory
+        drop(r3);
+        r2.try_grow(50).unwrap();
+        assert_eq!(child1.reserved(), 100);
+        assert_eq!(parent.reserved(), 100);
+    }
+
+    #[test]
+    fn test_nested_children() {
+        let root = Arc::new(GreedyMemoryPool::new(100)) as _;
+        let session = Arc::new(ChildMemoryPool::new(
+            "session",
+            &root,
+            GreedyMemoryPool::new(80),
+        )) as _;
+        let query = Arc::new(ChildMemoryPool::new(
+            "query",
+            &session,
+            UnboundedMemoryPool::default(),
+        )) as Arc<dyn MemoryPool>;
+        assert!(matches!(query.memory_limit(), MemoryLimit::Finite(80)));
+
+        let mut r1 = MemoryConsumer::new("r1").register(&query);
+        r1.try_grow(80).unwrap();
+        assert_eq!(query.reserved(), 80);
+        assert_eq!(session.reserved(), 80);
+        assert_eq!(root.reserved(), 80);
+
+        let err = r1.try_grow(1).unwrap_err().strip_backtrace();
+        assert_snapshot!(err, @"Resources exhausted: Failed to allocate additional 1.0 B for query with 80.0 B already allocated for this reservation - 0.0 B remain available for the total pool");
+        assert_eq!(query.reserved(), 80);
+    }
+
+    #[test]
+    fn test_track_children() {
+        let parent = Arc::new(TrackConsumersPool::new(
+            GreedyMemoryPool::new(100),
+            NonZeroUsize::new(2).unwrap(),
+        ));
+        let dyn_parent = Arc::clone(&parent) as _;
+        let child = Arc::new(ChildMemoryPool::new(
+            "query_1",
+            &dyn_parent,
+            GreedyMemoryPool::new(100),
+        )) as _;
+
+        let mut r1 = MemoryConsumer::new("r1").register(&child);
+        r1.try_grow(30).unwrap();
+        let mut r2 = MemoryConsumer::new("r2").register(&child);
+        r2.try_grow(20).unwrap();
+
+        // The parent tracks the child as a whole
+        let report = parent.report_top(2);
+        assert!(
+            report.contains("query_1#") && report.contains("consumed 50.0 B"),
+            "{report}"
+        );
+    }
+}
@@ -22,6 +22,7 @@ use datafusion_common::{internal_err, Result};
 use std::hash::{Hash, Hasher};
 use std::{cmp::Ordering, sync::atomic, sync::Arc};
 
+mod hierarchical;
 mod pool;
 pub mod proxy {
     pub use datafusion_common::utils::proxy::{
@@ -29,6 +30,7 @@ pub mod proxy {
     };
 }
 
+pub use hierarchical::*;
 pub use pool::*;
 
 /// Tracks and potentially limits memory use across operators during execution.
@@ -176,6 +178,10 @@ pub use pool::*;
 ///
 /// * [`TrackConsumersPool`]: Wraps another [`MemoryPool`] and tracks consumers,
 ///   providing better error messages on the largest memory users.
+///
+/// * [`ChildMemoryPool`]: Enforces its own limits, for example for a single
+///   query or session, while also reserving all memory from a shared parent
+///   [`MemoryPool`]
 pub trait MemoryPool: Send + Sync + std::fmt::Debug {
     /// Registers a new [`MemoryConsumer`]
     ///
@@ -0,0 +1,303 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`MetricsExporter`] for sending the metrics of completed queries to an
+//! external observability system
+
+use std::collections::BTreeMap;
+use std::fmt::{Debug, Write};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Receives the finalized metrics of each query executed with a
+/// [`RuntimeEnv`], for example to forward them to OpenTelemetry or to
+/// expose them in the Prometheus text format (see
+/// [`QueryMetrics::to_prometheus_text`]).
+///
+/// [`MetricsExporter::export`] is called once per query, after all of its
+/// output streams have completed or been dropped, from the thread that drops
+/// the last stream. Implementations should therefore be cheap, and hand the
+/// metrics off to a background task if exporting them involves I/O.
+///
+/// # Example
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use datafusion_execution::metrics_exporter::{MetricsExporter, QueryMetrics};
+/// # use datafusion_execution::runtime_env::RuntimeEnvBuilder;
+/// # use datafusion_common::Result;
+/// # fn main() -> Result<()> {
+/// #[derive(Debug)]
+/// struct LogExporter;
+///
+/// impl MetricsExporter for LogExporter {
+///     fn export(&self, metrics: &QueryMetrics) {
+///         println!("{}", metrics.to_prometheus_text());
+///     }
+/// }
+///
+/// let runtime = RuntimeEnvBuilder::new()
+///     .with_metrics_exporter(Arc::new(LogExporter))
+///     .build_arc()?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`RuntimeEnv`]: crate::runtime_env::RuntimeEnv
+pub trait MetricsExporter: Debug + Send + Sync {
+    /// Export the metrics of a completed query
+    fn export(&self, metrics: &QueryMetrics);
+}
+
+/// The metrics of all operators of a completed query
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryMetrics {
+    /// Id of the session that ran the query
+    pub session_id: String,
+    /// Id of the task that ran the query, if any
+    pub task_id: Option<String>,
+    /// The operators of the plan, in pre-order (the root of the plan first)
+    pub operators: Vec<OperatorMetrics>,
+}
+
+/// The metrics of a single operator of a query plan
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperatorMetrics {
+    /// Position of the operator in a pre-order traversal of the plan, which
+    /// uniquely identifies it within the query
+    pub id: usize,
+    /// [`Self::id`] of the parent operator, or `None` for the root
+    pub parent_id: Option<usize>,
+    /// Name of the operator, such as `FilterExec`
+    pub name: String,
+    /// The metrics reported by the operator, typically one per partition
+    pub metrics: Vec<MetricSample>,
+}
+
+/// A single metric value reported by an operator
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricSample {
+    /// Name of the metric, such as `output_rows` or `spilled_bytes`
+    pub name: String,
+    /// The partition the value was reported for, if any
+    pub partition: Option<usize>,
+    /// Additional labels describing the value
+    pub labels: Vec<(String, String)>,
+    /// The value
+    pub value: MetricSampleValue,
+}
+
+/// The value of a [`MetricSample`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MetricSampleValue {
+    /// A monotonically increasing count, such as the number of output rows
+    Counter(u64),
+    /// A value that can go up and down, such as the peak memory used
+    Gauge(u64),
+    /// An amount of time, such as the elapsed compute time
+    Time(Duration),
+    /// A point in time, such as when the partition started executing
+    Timestamp(SystemTime),
+}
+
+impl QueryMetrics {
+    /// Formats the metrics in the [Prometheus text exposition format].
+    ///
+    /// Each sample becomes a `datafusion_<metric>` line labeled with the
+    /// session id, the operator name and id, and the partition. Counters
+    /// get a `_total` suffix, and times and timestamps are in seconds.
+    ///
+    /// [Prometheus text exposition format]: https://prometheus.io/docs/instrumenting/exposition_formats/
+    pub fn to_prometheus_text(&self) -> String {
+        // Samples grouped by metric family, so that each family is preceded
+        // by a single `# TYPE` line
+        let mut families: BTreeMap<String, (&str, Vec<String>)> = BTreeMap::new();
+
+        for operator in &self.operators {
+            for sample in &operator.metrics {
+                let name = sanitize_metric_name(&sample.name);
+                let (family, kind, value) = match sample.value {
+                    MetricSampleValue::Counter(count) => {
+                        (format!("datafusion_{name}_total"), "counter", count as f64)
+                    }
+                    MetricSampleValue::Gauge(gauge) => {
+                        (format!("datafusion_{name}"), "gauge", gauge as f64)
+                    }
+                    MetricSampleValue::Time(time) => (
+                        format!("datafusion_{name}_seconds_total"),
+                        "counter",
+                        time.as_secs_f64(),
+                    ),
+                    MetricSampleValue::Timestamp(timestamp) => (
+                        format!("datafusion_{name}_seconds"),
+                        "gauge",
+                        timestamp
+                            .duration_since(UNIX_EPOCH)
+                            .unwrap_or_default()
+                            .as_secs_f64(),
+                    ),
+                };
+
+                let mut labels = vec![
+                    ("session_id", self.session_id.as_str()),
+                    ("operator", operator.name.as_str()),
+                ];
+                let operator_id = operator.id.to_string();
+                labels.push(("operator_id", &operator_id));
+                let partition = sample.partition.map(|p| p.to_string());
+                if let Some(partition) = &partition {
+                    labels.push(("partition", partition));
+                }
+                labels.extend(
+                    sample
+                        .labels
+                        .iter()
+                        .map(|(name, value)| (name.as_str(), value.as_str())),
+                );
+
+                let labels = labels
+                    .into_iter()
+                    .map(|(name, value)| {
+                        format!(
+                            "{}=\"{}\"",
+                            sanitize_metric_name(name),
+                            escape_label_value(value)
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",");
+
+                families
+                    .entry(family.clone())
+                    .or_insert_with(|| (kind, vec![]))
+                    .1
+                    .push(format!("{family}{{{labels}}} {value}"));
+            }
+        }
+
+        let mut text = String::new();
+        for (family, (kind, lines)) in families {
+            let _ = writeln!(text, "# TYPE {family} {kind}");
+            for line in lines {
+                let _ = writeln!(text, "{line}");
+            }
+        }
+        text
+    }
+}
+
+/// Replaces the characters that are not valid in Prometheus metric and label
+/// names with underscores
+fn sanitize_metric_name(name: &str) -> String {
+    let mut sanitized: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if sanitized.starts_with(|c: char| c.is_ascii_digit()) {
+        sanitized.insert(0, '_');
+    }
+    sanitized
+}
+
+/// Escapes backslashes, double quotes and newlines in label values
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(
+        name: &str,
+        partition: Option<usize>,
+        value: MetricSampleValue,
+    ) -> MetricSample {
+        MetricSample {
+            name: name.to_string(),
+            partition,
+            labels: vec![],
+            value,
+        }
+    }
+
+    #[test]
+    fn prometheus_text() {
+        let metrics = QueryMetrics {
+            session_id: "session".to_string(),
+            task_id: None,
+            operators: vec![
+                OperatorMetrics {
+                    id: 0,
+                    parent_id: None,
+                    name: "SortExec".to_string(),
+                    metrics: vec![
+                        sample("output_rows", Some(0), MetricSampleValue::Counter(10)),
+                        sample(
+                            "elapsed_compute",
+                            Some(0),
+                            MetricSampleValue::Time(Duration::from_millis(1500)),
+                        ),
+                        sample("peak_mem_used", Some(0), MetricSampleValue::Gauge(1024)),
+                        sample(
+                            "end_timestamp",
+                            Some(0),
+                            MetricSampleValue::Timestamp(
+                                UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+                            ),
+                        ),
+                    ],
+                },
+                OperatorMetrics {
+                    id: 1,
+                    parent_id: Some(0),
+                    name: "DataSourceExec".to_string(),
+                    metrics: vec![
+                        sample("output_rows", Some(0), MetricSampleValue::Counter(4)),
+                        MetricSample {
+                            labels: vec![(
+                                "file".to_string(),
+                                "C:\\data \"1\".parquet".to_string(),
+                            )],
+                            ..sample(
+                                "bytes-scanned",
+                                None,
+                                MetricSampleValue::Counter(100),
+                            )
+                        },
+                    ],
+                },
+            ],
+        };
+
+        insta::assert_snapshot!(metrics.to_prometheus_text(), @r#"
+        # TYPE datafusion_bytes_scanned_total counter
+        datafusion_bytes_scanned_total{session_id="session",operator="DataSourceExec",operator_id="1",file="C:\\data \"1\".parquet"} 100
+        # TYPE datafusion_elapsed_compute_seconds_total counter
+        datafusion_elapsed_compute_seconds_total{session_id="session",operator="SortExec",operator_id="0",partition="0"} 1.5
+        # TYPE datafusion_end_timestamp_seconds gauge
+        datafusion_end_timestamp_seconds{session_id="session",operator="SortExec",operator_id="0",partition="0"} 1700000000
+        # TYPE datafusion_output_rows_total counter
+        datafusion_output_rows_total{session_id="session",operator="SortExec",operator_id="0",partition="0"} 10
+        datafusion_output_rows_total{session_id="session",operator="DataSourceExec",operator_id="1",partition="0"} 4
+        # TYPE datafusion_peak_mem_used gauge
+        datafusion_peak_mem_used{session_id="session",operator="SortExec",operator_id="0",partition="0"} 1024
+        "#);
+    }
+}
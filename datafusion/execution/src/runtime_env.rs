@@ -21,10 +21,12 @@
 #[allow(deprecated)]
 use crate::disk_manager::DiskManagerConfig;
 use crate::{
+    admission::AdmissionController,
     disk_manager::{DiskManager, DiskManagerBuilder, DiskManagerMode},
     memory_pool::{
         GreedyMemoryPool, MemoryPool, TrackConsumersPool, UnboundedMemoryPool,
     },
+    metrics_exporter::MetricsExporter,
     object_store::{DefaultObjectStoreRegistry, ObjectStoreRegistry},
 };
 
@@ -83,6 +85,10 @@ pub struct RuntimeEnv {
     /// Parquet encryption factory registry
     #[cfg(feature = "parquet_encryption")]
     pub parquet_encryption_factory_registry: Arc<EncryptionFactoryRegistry>,
+    /// Queues queries before they start executing, if set
+    pub admission_controller: Option<Arc<AdmissionController>>,
+    /// Receives the metrics of each query once it completes, if set
+    pub metrics_exporter: Option<Arc<dyn MetricsExporter>>,
 }
 
 impl Debug for RuntimeEnv {
@@ -198,6 +204,15 @@ pub struct RuntimeEnvBuilder {
     /// Parquet encryption factory registry
     #[cfg(feature = "parquet_encryption")]
     pub parquet_encryption_factory_registry: Arc<EncryptionFactoryRegistry>,
+    /// [`AdmissionController`] that queues queries before they start executing
+    ///
+    /// Defaults to admitting all queries immediately if `None`
+    pub admission_controller: Option<Arc<AdmissionController>>,
+    /// [`MetricsExporter`] that receives the metrics of each query once it
+    /// completes
+    ///
+    /// Defaults to not exporting metrics if `None`
+    pub metrics_exporter: Option<Arc<dyn MetricsExporter>>,
 }
 
 impl Default for RuntimeEnvBuilder {
@@ -217,6 +232,8 @@ impl RuntimeEnvBuilder {
             object_store_registry: Arc::new(DefaultObjectStoreRegistry::default()),
             #[cfg(feature = "parquet_encryption")]
             parquet_encryption_factory_registry: Default::default(),
+            admission_controller: None,
+            metrics_exporter: None,
         }
     }
 
@@ -246,6 +263,26 @@ impl RuntimeEnvBuilder {
         self
     }
 
+    /// Queue queries using the provided [`AdmissionController`] before they
+    /// start executing
+    pub fn with_admission_controller(
+        mut self,
+        admission_controller: Arc<AdmissionController>,
+    ) -> Self {
+        self.admission_controller = Some(admission_controller);
+        self
+    }
+
+    /// Send the metrics of each query to the provided [`MetricsExporter`]
+    /// once it completes
+    pub fn with_metrics_exporter(
+        mut self,
+        metrics_exporter: Arc<dyn MetricsExporter>,
+    ) -> Self {
+        self.metrics_exporter = Some(metrics_exporter);
+        self
+    }
+
     /// Customize object store registry
     pub fn with_object_store_registry(
         mut self,
@@ -290,6 +327,13 @@ impl RuntimeEnvBuilder {
         self
     }
 
+    /// Specify the limit of the cache of byte ranges read from files, such as
+    /// Parquet column chunks, in bytes.
+    pub fn with_file_range_cache_limit(mut self, limit: usize) -> Self {
+        self.cache_manager = self.cache_manager.with_file_range_cache_limit(limit);
+        self
+    }
+
     /// Build a RuntimeEnv
     pub fn build(self) -> Result<RuntimeEnv> {
         let Self {
@@ -300,6 +344,8 @@ impl RuntimeEnvBuilder {
             object_store_registry,
             #[cfg(feature = "parquet_encryption")]
             parquet_encryption_factory_registry,
+            admission_controller,
+            metrics_exporter,
         } = self;
         let memory_pool =
             memory_pool.unwrap_or_else(|| Arc::new(UnboundedMemoryPool::default()));
@@ -316,6 +362,8 @@ impl RuntimeEnvBuilder {
             object_store_registry,
             #[cfg(feature = "parquet_encryption")]
             parquet_encryption_factory_registry,
+            admission_controller,
+            metrics_exporter,
         })
     }
 
@@ -335,6 +383,14 @@ impl RuntimeEnvBuilder {
                 runtime_env.cache_manager.get_file_metadata_cache(),
             ),
             metadata_cache_limit: runtime_env.cache_manager.get_metadata_cache_limit(),
+            table_statistics_cache: Some(
+                runtime_env.cache_manager.get_table_statistics_cache(),
+            ),
+            file_range_cache: Some(runtime_env.cache_manager.get_file_range_cache()),
+            file_range_cache_limit: runtime_env
+                .cache_manager
+                .get_file_range_cache_limit(),
+            result_cache: runtime_env.cache_manager.get_result_cache(),
         };
 
         Self {
@@ -350,6 +406,8 @@ impl RuntimeEnvBuilder {
             parquet_encryption_factory_registry: Arc::clone(
                 &runtime_env.parquet_encryption_factory_registry,
             ),
+            admission_controller: runtime_env.admission_controller.clone(),
+            metrics_exporter: runtime_env.metrics_exporter.clone(),
         }
     }
 
@@ -375,6 +433,11 @@ impl RuntimeEnvBuilder {
                 key: "datafusion.runtime.metadata_cache_limit".to_string(),
                 value: Some("50M".to_owned()),
                 description: "Maximum memory to use for file metadata cache such as Parquet metadata. Supports suffixes K (kilobytes), M (megabytes), and G (gigabytes). Example: '2G' for 2 gigabytes.",
+            },
+            ConfigEntry {
+                key: "datafusion.runtime.file_range_cache_limit".to_string(),
+                value: Some("0K".to_owned()),
+                description: "Maximum memory to use for caching byte ranges read from files, such as Parquet column chunks. The cache is disabled when set to 0. Supports suffixes K (kilobytes), M (megabytes), and G (gigabytes). Example: '2G' for 2 gigabytes.",
             }
         ]
     }
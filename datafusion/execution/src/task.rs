@@ -19,6 +19,7 @@ use crate::{
     config::SessionConfig, memory_pool::MemoryPool, registry::FunctionRegistry,
     runtime_env::RuntimeEnv,
 };
+use datafusion_common::instant::Instant;
 use datafusion_common::{plan_datafusion_err, DataFusionError, Result};
 use datafusion_expr::planner::ExprPlanner;
 use datafusion_expr::{AggregateUDF, ScalarUDF, WindowUDF};
@@ -32,7 +33,7 @@ use std::{collections::HashMap, sync::Arc};
 /// information.
 ///
 /// [`SessionContext`]: https://docs.rs/datafusion/latest/datafusion/execution/context/struct.SessionContext.html
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TaskContext {
     /// Session Id
     session_id: String,
@@ -48,6 +49,8 @@ pub struct TaskContext {
     window_functions: HashMap<String, Arc<WindowUDF>>,
     /// Runtime environment associated with this task context
     runtime: Arc<RuntimeEnv>,
+    /// Point in time after which the execution of the task is cancelled
+    deadline: Option<Instant>,
 }
 
 impl Default for TaskContext {
@@ -63,6 +66,7 @@ impl Default for TaskContext {
             aggregate_functions: HashMap::new(),
             window_functions: HashMap::new(),
             runtime,
+            deadline: None,
         }
     }
 }
@@ -90,6 +94,7 @@ impl TaskContext {
             aggregate_functions,
             window_functions,
             runtime,
+            deadline: None,
         }
     }
 
@@ -130,12 +135,29 @@ impl TaskContext {
         &self.window_functions
     }
 
+    /// Return the point in time after which the streams of this task are
+    /// cancelled with a [`DataFusionError::Timeout`] error, if any.
+    ///
+    /// Not set by default. [`execute_stream`] starts the deadline of a query
+    /// from its `datafusion.execution.query_timeout`.
+    ///
+    /// [`execute_stream`]: https://docs.rs/datafusion/latest/datafusion/physical_plan/fn.execute_stream.html
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
     /// Update the [`SessionConfig`]
     pub fn with_session_config(mut self, session_config: SessionConfig) -> Self {
         self.session_config = session_config;
         self
     }
 
+    /// Update the deadline of the task, see [`Self::deadline`]
+    pub fn with_deadline(mut self, deadline: Option<Instant>) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
     /// Update the [`RuntimeEnv`]
     pub fn with_runtime(mut self, runtime: Arc<RuntimeEnv>) -> Self {
         self.runtime = runtime;
@@ -218,6 +240,7 @@ mod tests {
         config::{ConfigExtension, ConfigOptions, Extensions},
         extensions_options,
     };
+    use std::time::Duration;
 
     extensions_options! {
         struct TestExtension {
@@ -264,6 +287,27 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn task_context_deadline() -> Result<()> {
+        assert_eq!(TaskContext::default().deadline(), None);
+
+        // The timeout only starts once the query is executed
+        let mut config = ConfigOptions::new();
+        config.set("datafusion.execution.query_timeout", "60000")?;
+        let task_context =
+            TaskContext::default().with_session_config(SessionConfig::from(config));
+        assert_eq!(task_context.deadline(), None);
+
+        let deadline = Instant::now() + Duration::from_secs(60);
+        let task_context = task_context.with_deadline(Some(deadline));
+        assert_eq!(task_context.deadline(), Some(deadline));
+
+        let task_context = task_context.with_deadline(None);
+        assert_eq!(task_context.deadline(), None);
+
+        Ok(())
+    }
+
     #[test]
     fn task_context_extensions_default() -> Result<()> {
         let runtime = Arc::new(RuntimeEnv::default());
@@ -279,11 +279,16 @@ impl ExprSchemable for Expr {
             Expr::OuterReferenceColumn(field, _) => Ok(field.is_nullable()),
             Expr::Literal(value, _) => Ok(value.is_null()),
             Expr::Case(case) => {
-                // This expression is nullable if any of the input expressions are nullable
+                // This expression is nullable if any of the input expressions are nullable,
+                // except for `WHEN x IS NOT NULL THEN x`, as produced by simplifying `coalesce`
                 let then_nullable = case
                     .when_then_expr
                     .iter()
-                    .map(|(_, t)| t.nullable(input_schema))
+                    .map(|(w, t)| {
+                        let guarded = case.expr.is_none()
+                            && matches!(w.as_ref(), Expr::IsNotNull(e) if e == t);
+                        Ok(!guarded && t.nullable(input_schema)?)
+                    })
                     .collect::<Result<Vec<_>>>()?;
                 if then_nullable.contains(&true) {
                     Ok(true)
@@ -777,7 +782,7 @@ pub fn cast_subquery(subquery: Subquery, cast_to_type: &DataType) -> Result<Subq
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{col, lit, out_ref_col_with_metadata};
+    use crate::{col, lit, out_ref_col_with_metadata, when};
 
     use datafusion_common::{internal_err, DFSchema, HashMap, ScalarValue};
 
@@ -830,6 +835,32 @@ mod tests {
         assert!(expr.nullable(&get_schema(false)).unwrap());
     }
 
+    #[test]
+    fn test_case_nullability() {
+        let get_schema = |nullable| {
+            MockExprSchema::new()
+                .with_data_type(DataType::Int32)
+                .with_nullable(nullable)
+        };
+
+        // CASE WHEN foo IS NOT NULL THEN foo ELSE 0 END, as produced by coalesce(foo, 0)
+        let expr = when(col("foo").is_not_null(), col("foo"))
+            .otherwise(lit(0))
+            .unwrap();
+        assert!(!expr.nullable(&get_schema(true)).unwrap());
+
+        // CASE WHEN bar IS NOT NULL THEN foo ELSE 0 END
+        let expr = when(col("bar").is_not_null(), col("foo"))
+            .otherwise(lit(0))
+            .unwrap();
+        assert!(expr.nullable(&get_schema(true)).unwrap());
+
+        // CASE WHEN foo IS NOT NULL THEN foo END
+        let expr = when(col("foo").is_not_null(), col("foo")).end().unwrap();
+        assert!(expr.nullable(&get_schema(true)).unwrap());
+        assert!(expr.nullable(&get_schema(false)).unwrap());
+    }
+
     #[test]
     fn test_inlist_nullability() {
         let get_schema = |nullable| {
@@ -32,10 +32,11 @@ use crate::expr_rewriter::{
     rewrite_sort_cols_by_aggs,
 };
 use crate::logical_plan::{
-    Aggregate, Analyze, Distinct, DistinctOn, EmptyRelation, Explain, Filter, Join,
-    JoinConstraint, JoinType, Limit, LogicalPlan, Partitioning, PlanType, Prepare,
-    Projection, Repartition, Sort, SubqueryAlias, TableScan, Union, Unnest, Values,
-    Window,
+    AfterMatchSkip, Aggregate, Analyze, Distinct, DistinctOn, EmptyRelation, Explain,
+    Filter, Join, JoinConstraint, JoinType, Limit, LogicalPlan, MatchMeasure,
+    MatchPattern, MatchRecognize, MatchSymbol, Partitioning, PlanType, Prepare,
+    Projection, Repartition, RowsPerMatch, Sample, SampleMethod, Sort, SubqueryAlias,
+    TableScan, Union, Unnest, Values, Window,
 };
 use crate::select_expr::SelectExpr;
 use crate::utils::{
@@ -44,8 +45,9 @@ use crate::utils::{
     group_window_expr_by_sort_keys,
 };
 use crate::{
-    and, binary_expr, lit, DmlStatement, ExplainOption, Expr, ExprSchemable, Operator,
-    RecursiveQuery, Statement, TableProviderFilterPushDown, TableSource, WriteOp,
+    and, binary_expr, lit, when, DmlStatement, ExplainOption, Expr, ExprSchemable,
+    Operator, RecursiveQuery, Statement, TableProviderFilterPushDown, TableSource,
+    WriteOp,
 };
 
 use super::dml::InsertOp;
@@ -659,6 +661,46 @@ impl LogicalPlanBuilder {
         })))
     }
 
+    /// Return a random sample of the rows, keeping each row (or block of
+    /// rows for [`SampleMethod::System`]) with probability `fraction`
+    pub fn sample(
+        self,
+        method: SampleMethod,
+        fraction: f64,
+        seed: Option<u64>,
+    ) -> Result<Self> {
+        Sample::try_new(self.plan, method, fraction, seed)
+            .map(LogicalPlan::Sample)
+            .map(Self::new)
+    }
+
+    /// Find sequences of rows matching `pattern` within each partition, see
+    /// [`MatchRecognize`]
+    #[allow(clippy::too_many_arguments)]
+    pub fn match_recognize(
+        self,
+        partition_by: Vec<Expr>,
+        order_by: Vec<SortExpr>,
+        symbols: Vec<MatchSymbol>,
+        pattern: MatchPattern,
+        measures: Vec<MatchMeasure>,
+        rows_per_match: RowsPerMatch,
+        after_match_skip: AfterMatchSkip,
+    ) -> Result<Self> {
+        MatchRecognize::try_new(
+            self.plan,
+            partition_by,
+            order_by,
+            symbols,
+            pattern,
+            measures,
+            rows_per_match,
+            after_match_skip,
+        )
+        .map(LogicalPlan::MatchRecognize)
+        .map(Self::new)
+    }
+
     /// Apply an alias
     pub fn alias(self, alias: impl Into<TableReference>) -> Result<Self> {
         subquery_alias(Arc::unwrap_or_clone(self.plan), alias).map(Self::new)
@@ -1263,6 +1305,171 @@ impl LogicalPlanBuilder {
             .map(Self::new)
     }
 
+    /// Rotate the rows of `pivot_column` into columns: every value in
+    /// `pivot_values` becomes a column that holds the `aggr_expr` aggregates
+    /// of the rows where `pivot_column` equals that value, grouped by all
+    /// other input columns that are not referenced by the aggregates.
+    ///
+    /// A pivot column is named after its value (or the alias of the value),
+    /// suffixed with `_<aggregate alias>` if there are multiple aggregates or
+    /// the aggregate is aliased. Pivot columns without matching rows hold
+    /// `default_on_null` instead of a null if it is given.
+    pub fn pivot(
+        self,
+        aggr_expr: Vec<Expr>,
+        pivot_column: impl Into<Column>,
+        pivot_values: Vec<Expr>,
+        default_on_null: Option<Expr>,
+    ) -> Result<Self> {
+        if pivot_values.is_empty() {
+            return plan_err!("PIVOT requires at least one pivot value");
+        }
+        let pivot_column =
+            Expr::Column(Self::normalize(&self.plan, pivot_column.into())?);
+        let aggr_expr = normalize_cols(aggr_expr, &self.plan)?;
+
+        let mut referenced = HashSet::new();
+        expr_to_columns(&pivot_column, &mut referenced)?;
+        let aggregates = aggr_expr
+            .iter()
+            .map(|expr| {
+                expr_to_columns(expr, &mut referenced)?;
+                // The aggregate may carry more than one alias, e.g. `count(*) AS c`
+                match expr {
+                    Expr::Alias(Alias { expr, name, .. }) => {
+                        Ok((expr.as_ref().clone().unalias_nested().data, Some(name)))
+                    }
+                    expr => Ok((expr.clone(), None)),
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let group_expr = self
+            .plan
+            .schema()
+            .columns()
+            .into_iter()
+            .filter(|column| !referenced.contains(column))
+            .map(Expr::Column)
+            .collect::<Vec<_>>();
+
+        let mut pivot_exprs = vec![];
+        for value in pivot_values {
+            let (value, value_name) = match value {
+                Expr::Alias(Alias { expr, name, .. }) => {
+                    (expr.unalias_nested().data, name)
+                }
+                Expr::Literal(value, metadata) => {
+                    let name = match value.try_as_str() {
+                        Some(Some(value)) => value.to_string(),
+                        _ => value.to_string(),
+                    };
+                    (Expr::Literal(value, metadata), name)
+                }
+                value => {
+                    let name = value.schema_name().to_string();
+                    (value, name)
+                }
+            };
+            for (aggregate, alias) in &aggregates {
+                let Expr::AggregateFunction(mut function) = aggregate.clone() else {
+                    return plan_err!(
+                        "PIVOT requires aggregate functions, got {aggregate}"
+                    );
+                };
+                let name = match alias {
+                    None if aggregates.len() == 1 => value_name.clone(),
+                    None => format!("{value_name}_{}", aggregate.schema_name()),
+                    Some(alias) => format!("{value_name}_{alias}"),
+                };
+                let matches = pivot_column.clone().eq(value.clone());
+                function.params.filter =
+                    Some(Box::new(match function.params.filter.take() {
+                        Some(filter) => and(*filter, matches),
+                        None => matches,
+                    }));
+                pivot_exprs.push(Expr::AggregateFunction(function).alias(name));
+            }
+        }
+
+        let names = pivot_exprs
+            .iter()
+            .map(|expr| expr.schema_name().to_string())
+            .collect::<Vec<_>>();
+        let plan = self.aggregate(group_expr.clone(), pivot_exprs)?;
+        let Some(default_on_null) = default_on_null else {
+            return Ok(plan);
+        };
+        let project_exprs = group_expr
+            .into_iter()
+            .chain(
+                names
+                    .into_iter()
+                    .map(|name| {
+                        let column = Expr::Column(Column::from_name(&name));
+                        when(column.clone().is_null(), default_on_null.clone())
+                            .otherwise(column)
+                            .map(|expr| expr.alias(name))
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+            )
+            .collect::<Vec<_>>();
+        plan.project(project_exprs)
+    }
+
+    /// Rotate the `columns` into rows: every input row is turned into one row
+    /// per column in `columns`, holding the remaining input columns, the
+    /// column name in a `name_column` column and the column value in a
+    /// `value_column` column. Rows with a null value are skipped unless
+    /// `include_nulls` is true.
+    pub fn unpivot(
+        self,
+        value_column: impl Into<String>,
+        name_column: impl Into<String>,
+        columns: Vec<Column>,
+        include_nulls: bool,
+    ) -> Result<Self> {
+        let (value_column, name_column) = (value_column.into(), name_column.into());
+        let schema = self.plan.schema();
+        let columns = columns
+            .into_iter()
+            .map(|column| Self::normalize(&self.plan, column))
+            .collect::<Result<Vec<_>>>()?;
+        if columns.is_empty() {
+            return plan_err!("UNPIVOT requires at least one column");
+        }
+        let remaining = schema
+            .columns()
+            .into_iter()
+            .filter(|column| !columns.contains(column))
+            .map(Expr::Column)
+            .collect::<Vec<_>>();
+
+        let inputs = columns
+            .iter()
+            .map(|column| {
+                let exprs = remaining.iter().cloned().chain([
+                    lit(column.name.as_str()).alias(&name_column),
+                    Expr::Column(column.clone()).alias(&value_column),
+                ]);
+                let mut input =
+                    Self::new_from_arc(Arc::clone(&self.plan)).project(exprs)?;
+                if !include_nulls {
+                    input = input.filter(
+                        Expr::Column(Column::from_name(&value_column)).is_not_null(),
+                    )?;
+                }
+                input.build().map(Arc::new)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if inputs.len() == 1 {
+            return Ok(Self::new_from_arc(Arc::clone(&inputs[0])));
+        }
+        Union::try_new_with_loose_types(inputs)
+            .map(LogicalPlan::Union)
+            .map(Self::new)
+    }
+
     /// Create an expression to represent the explanation of the plan
     ///
     /// if `analyze` is true, runs the actual plan and produces
@@ -1641,7 +1848,7 @@ pub fn build_join_schema(
                 .collect::<Vec<_>>();
             left_fields.into_iter().chain(right_fields).collect()
         }
-        JoinType::Left => {
+        JoinType::Left | JoinType::AsOf => {
             // left then right, right set to nullable in case of not matched scenario
             let left_fields = left_fields
                 .map(|(q, f)| (q.cloned(), Arc::clone(f)))
@@ -22,9 +22,9 @@ use std::fmt;
 
 use crate::{
     expr_vec_fmt, Aggregate, DescribeTable, Distinct, DistinctOn, DmlStatement, Expr,
-    Filter, Join, Limit, LogicalPlan, Partitioning, Projection, RecursiveQuery,
-    Repartition, Sort, Subquery, SubqueryAlias, TableProviderFilterPushDown, TableScan,
-    Unnest, Values, Window,
+    Filter, Join, Limit, LogicalPlan, MatchRecognize, Partitioning, Projection,
+    RecursiveQuery, Repartition, Sample, Sort, Subquery, SubqueryAlias,
+    TableProviderFilterPushDown, TableScan, Unnest, Values, Window,
 };
 
 use crate::dml::CopyTo;
@@ -552,6 +552,43 @@ impl<'a, 'b> PgJsonVisitor<'a, 'b> {
                 };
                 object
             }
+            LogicalPlan::Sample(Sample {
+                method,
+                fraction,
+                seed,
+                ..
+            }) => {
+                let mut object = json!({
+                    "Node Type": "Sample",
+                    "Method": method.to_string(),
+                    "Fraction": fraction
+                });
+                if let Some(seed) = seed {
+                    object["Seed"] = (*seed).into()
+                };
+                object
+            }
+            LogicalPlan::MatchRecognize(MatchRecognize {
+                partition_by,
+                order_by,
+                symbols,
+                pattern,
+                measures,
+                rows_per_match,
+                after_match_skip,
+                ..
+            }) => {
+                json!({
+                    "Node Type": "MatchRecognize",
+                    "Partition By": expr_vec_fmt!(partition_by),
+                    "Order By": expr_vec_fmt!(order_by),
+                    "Measures": expr_vec_fmt!(measures),
+                    "Rows Per Match": rows_per_match.to_string(),
+                    "After Match Skip": after_match_skip.to_string(),
+                    "Pattern": pattern.to_string(),
+                    "Define": expr_vec_fmt!(symbols)
+                })
+            }
             LogicalPlan::Subquery(Subquery { .. }) => {
                 json!({
                     "Node Type": "Subquery"
@@ -15,17 +15,22 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::any::Any;
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::fmt::{self, Debug, Display, Formatter};
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 
-use arrow::datatypes::{DataType, Field, Schema};
+use arrow::array::{AsArray, BooleanArray};
+use arrow::datatypes::{DataType, Field, Int64Type, Schema};
 use datafusion_common::file_options::file_type::FileType;
-use datafusion_common::{DFSchemaRef, TableReference};
+use datafusion_common::{exec_err, DFSchemaRef, Result, ScalarValue, TableReference};
 
-use crate::{LogicalPlan, TableSource};
+use crate::{
+    ColumnarValue, LogicalPlan, ScalarFunctionArgs, ScalarUDF, ScalarUDFImpl, Signature,
+    TableSource, Volatility,
+};
 
 /// Operator that copies the contents of a database to file(s)
 #[derive(Clone)]
@@ -122,11 +127,13 @@ impl CopyTo {
 /// * `INSERT` - Appends new rows to the existing table. Calls
 ///   [`TableProvider::insert_into`]
 ///
-/// * `DELETE` - Removes rows from the table. Currently NOT supported by the
-///   [`TableProvider`] trait or builtin sources.
+/// * `DELETE` - Removes rows from the table. Calls [`TableProvider::delete_from`]
 ///
-/// * `UPDATE` - Modifies existing rows in the table. Currently NOT supported by
-///   the [`TableProvider`] trait or builtin sources.
+/// * `UPDATE` - Modifies existing rows in the table. Calls
+///   [`TableProvider::update`]
+///
+/// * `MERGE` - Inserts, updates and deletes rows of the table based on a join
+///   with a source relation. Calls [`TableProvider::merge_into`]
 ///
 /// * `CREATE TABLE AS SELECT` - Creates a new table and populates it with data
 ///   from a query. This is similar to the `INSERT` operation, but it creates a new
@@ -136,6 +143,9 @@ impl CopyTo {
 ///
 /// [`TableProvider`]: https://docs.rs/datafusion/latest/datafusion/datasource/trait.TableProvider.html
 /// [`TableProvider::insert_into`]: https://docs.rs/datafusion/latest/datafusion/datasource/trait.TableProvider.html#method.insert_into
+/// [`TableProvider::delete_from`]: https://docs.rs/datafusion/latest/datafusion/datasource/trait.TableProvider.html#method.delete_from
+/// [`TableProvider::update`]: https://docs.rs/datafusion/latest/datafusion/datasource/trait.TableProvider.html#method.update
+/// [`TableProvider::merge_into`]: https://docs.rs/datafusion/latest/datafusion/datasource/trait.TableProvider.html#method.merge_into
 #[derive(Clone)]
 pub struct DmlStatement {
     /// The table name
@@ -237,6 +247,12 @@ pub enum WriteOp {
     Update,
     /// `CREATE TABLE AS SELECT` operation
     Ctas,
+    /// `MERGE` operation
+    ///
+    /// The input of the statement produces the rows of the target table, with
+    /// the action applied to each of them in an additional
+    /// [`MERGE_ACTION_COLUMN`] column (see [`MergeRowAction`]).
+    Merge,
 }
 
 impl WriteOp {
@@ -247,6 +263,7 @@ impl WriteOp {
             WriteOp::Delete => "Delete",
             WriteOp::Update => "Update",
             WriteOp::Ctas => "Ctas",
+            WriteOp::Merge => "Merge",
         }
     }
 }
@@ -288,6 +305,136 @@ impl Display for InsertOp {
     }
 }
 
+/// Name of the column, appended to the columns of the target table, that
+/// holds the [`MergeRowAction`] of each row produced by the input of a
+/// [`WriteOp::Merge`] statement
+pub const MERGE_ACTION_COLUMN: &str = "__merge_action";
+
+/// The action that a `MERGE` statement applies to a row of its target table.
+///
+/// The input of a [`WriteOp::Merge`] statement produces every row of the
+/// target table after the merge, plus the deleted rows. It is typically
+/// computed by an outer join of the target table with the source relation,
+/// which makes it suitable both for table providers that rewrite the whole
+/// table and for the ones that only apply the changes.
+///
+/// The action is encoded as a `UInt8` in the [`MERGE_ACTION_COLUMN`] column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MergeRowAction {
+    /// An existing row that is not modified by the statement
+    Keep,
+    /// A new row, from a `WHEN NOT MATCHED THEN INSERT` clause
+    Insert,
+    /// The new values of an existing row, from a `THEN UPDATE` clause
+    Update,
+    /// The values of an existing row, that is removed by a `THEN DELETE` clause
+    Delete,
+}
+
+impl MergeRowAction {
+    /// Return the value that represents this action in the
+    /// [`MERGE_ACTION_COLUMN`] column
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            MergeRowAction::Keep => 0,
+            MergeRowAction::Insert => 1,
+            MergeRowAction::Update => 2,
+            MergeRowAction::Delete => 3,
+        }
+    }
+
+    /// Return the action represented by a value of the
+    /// [`MERGE_ACTION_COLUMN`] column, if valid
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(MergeRowAction::Keep),
+            1 => Some(MergeRowAction::Insert),
+            2 => Some(MergeRowAction::Update),
+            3 => Some(MergeRowAction::Delete),
+            _ => None,
+        }
+    }
+
+    /// Return `true` if this action modifies the target table
+    pub fn is_modification(&self) -> bool {
+        !matches!(self, MergeRowAction::Keep)
+    }
+}
+
+/// Name of the function returned by [`merge_cardinality_check`]
+pub const MERGE_CARDINALITY_CHECK: &str = "merge_cardinality_check";
+
+/// Returns the function that enforces the cardinality rule of `MERGE`: a
+/// row of the target table can not be updated or deleted by more than one
+/// row of the source relation.
+///
+/// Its argument is the number of source rows that apply a clause to the
+/// same target row. It returns `true` if that number is at most one, and
+/// fails otherwise, so it is meant to be used as a filter of the input of a
+/// [`WriteOp::Merge`] statement.
+pub fn merge_cardinality_check() -> Arc<ScalarUDF> {
+    Arc::new(ScalarUDF::from(MergeCardinalityCheck::new()))
+}
+
+/// See [`merge_cardinality_check`]
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct MergeCardinalityCheck {
+    signature: Signature,
+}
+
+impl MergeCardinalityCheck {
+    fn new() -> Self {
+        Self {
+            // Volatile, so that the check is never removed or moved by the optimizer
+            signature: Signature::exact(vec![DataType::Int64], Volatility::Volatile),
+        }
+    }
+}
+
+impl ScalarUDFImpl for MergeCardinalityCheck {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        MERGE_CARDINALITY_CHECK
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Boolean)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let violated = match &args.args[0] {
+            ColumnarValue::Array(array) => array
+                .as_primitive::<Int64Type>()
+                .iter()
+                .any(|count| count.is_some_and(|count| count > 1)),
+            ColumnarValue::Scalar(ScalarValue::Int64(count)) => {
+                count.is_some_and(|count| count > 1)
+            }
+            ColumnarValue::Scalar(other) => {
+                return exec_err!(
+                    "Unexpected argument to merge_cardinality_check: {other}"
+                )
+            }
+        };
+        if violated {
+            return exec_err!(
+                "MERGE can not update or delete a target row matched by more than one source row"
+            );
+        }
+        Ok(ColumnarValue::Array(Arc::new(BooleanArray::from(vec![
+            true;
+            args.number_rows
+        ]))))
+    }
+}
+
 fn make_count_schema() -> DFSchemaRef {
     Arc::new(
         Schema::new(vec![Field::new("count", DataType::UInt64, false)])
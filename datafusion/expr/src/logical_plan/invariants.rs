@@ -25,7 +25,7 @@ use crate::{
     expr::{Exists, InSubquery},
     expr_rewriter::strip_outer_reference,
     utils::{collect_subquery_cols, split_conjunction},
-    Aggregate, Expr, Filter, Join, JoinType, LogicalPlan, Window,
+    Aggregate, Expr, Filter, Join, JoinType, LogicalPlan,
 };
 
 use super::Extension;
@@ -201,7 +201,9 @@ pub fn check_subquery_expr(
             }?;
             match outer_plan {
                 LogicalPlan::Projection(_)
-                | LogicalPlan::Filter(_) => Ok(()),
+                | LogicalPlan::Filter(_)
+                | LogicalPlan::Window(_)
+                | LogicalPlan::Join(_) => Ok(()),
                 LogicalPlan::Aggregate(Aggregate { group_expr, aggr_expr, .. }) => {
                     if group_expr.contains(expr) && !aggr_expr.contains(expr) {
                         // TODO revisit this validation logic
@@ -213,7 +215,7 @@ pub fn check_subquery_expr(
                     }
                 }
                 _ => plan_err!(
-                    "Correlated scalar subquery can only be used in Projection, Filter, Aggregate plan nodes"
+                    "Correlated scalar subquery can only be used in Projection, Filter, Window, Aggregate, Join plan nodes"
                 )
             }?;
         }
@@ -265,15 +267,8 @@ fn check_inner_plan(inner_plan: &LogicalPlan) -> Result<()> {
             Ok(())
         }
         LogicalPlan::Filter(Filter { input, .. }) => check_inner_plan(input),
-        LogicalPlan::Window(window) => {
-            check_mixed_out_refer_in_window(window)?;
-            inner_plan.apply_children(|plan| {
-                check_inner_plan(plan)?;
-                Ok(TreeNodeRecursion::Continue)
-            })?;
-            Ok(())
-        }
         LogicalPlan::Projection(_)
+        | LogicalPlan::Window(_)
         | LogicalPlan::Distinct(_)
         | LogicalPlan::Sort(_)
         | LogicalPlan::Union(_)
@@ -291,35 +286,15 @@ fn check_inner_plan(inner_plan: &LogicalPlan) -> Result<()> {
             Ok(())
         }
         LogicalPlan::Join(Join {
-            left,
-            right,
-            join_type,
-            ..
+            left, join_type, ..
         }) => match join_type {
-            JoinType::Inner => {
-                inner_plan.apply_children(|plan| {
-                    check_inner_plan(plan)?;
-                    Ok(TreeNodeRecursion::Continue)
-                })?;
-                Ok(())
-            }
-            JoinType::Left
-            | JoinType::LeftSemi
-            | JoinType::LeftAnti
-            | JoinType::LeftMark => {
+            JoinType::AsOf => {
                 check_inner_plan(left)?;
-                check_no_outer_references(right)
-            }
-            JoinType::Right
-            | JoinType::RightSemi
-            | JoinType::RightAnti
-            | JoinType::RightMark => {
-                check_no_outer_references(left)?;
-                check_inner_plan(right)
+                check_no_outer_references(inner_plan.inputs()[1])
             }
-            JoinType::Full => {
+            _ => {
                 inner_plan.apply_children(|plan| {
-                    check_no_outer_references(plan)?;
+                    check_inner_plan(plan)?;
                     Ok(TreeNodeRecursion::Continue)
                 })?;
                 Ok(())
@@ -399,21 +374,6 @@ fn get_correlated_expressions(inner_plan: &LogicalPlan) -> Result<Vec<Expr>> {
     Ok(exprs)
 }
 
-/// Check whether the window expressions contain a mixture of out reference columns and inner columns
-fn check_mixed_out_refer_in_window(window: &Window) -> Result<()> {
-    let mixed = window
-        .window_expr
-        .iter()
-        .any(|win_expr| win_expr.contains_outer() && win_expr.any_column_refs());
-    if mixed {
-        plan_err!(
-            "Window expressions should not contain a mixed of outer references and inner columns"
-        )
-    } else {
-        Ok(())
-    }
-}
-
 #[cfg(test)]
 mod test {
     use std::cmp::Ordering;
@@ -0,0 +1,737 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Row pattern recognition, see [`MatchRecognize`]
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt::{self, Display, Formatter};
+use std::sync::Arc;
+
+use arrow::datatypes::{DataType, Field};
+use datafusion_common::tree_node::{Transformed, TreeNodeContainer, TreeNodeRecursion};
+use datafusion_common::{
+    internal_datafusion_err, plan_err, DFSchema, DFSchemaRef, Result,
+};
+
+use crate::expr::Sort;
+use crate::{Expr, ExprSchemable, LogicalPlan};
+
+/// Finds sequences of rows matching a regular expression over pattern
+/// symbols. This is used to implement SQL `MATCH_RECOGNIZE`.
+///
+/// The input is split by `partition_by` and ordered by `order_by`. A row
+/// can be mapped to a symbol if the symbol's predicate is true for it, and a
+/// match is a sequence of rows whose symbols match `pattern`. Matches are
+/// searched for from the first row of each partition, preferring the longest
+/// match of greedy quantifiers, and the search resumes after each match as
+/// specified by `after_match_skip`.
+///
+/// With [`RowsPerMatch::OneRow`] the output has the `partition_by` columns
+/// and one column per measure. With [`RowsPerMatch::AllRows`] the output has
+/// all input columns and one column per measure.
+///
+/// Symbol predicates only see the current row, navigation functions like
+/// `PREV` are planned as window functions below this node.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MatchRecognize {
+    /// The incoming logical plan
+    pub input: Arc<LogicalPlan>,
+    /// Columns that split the input into independently matched partitions
+    pub partition_by: Vec<Expr>,
+    /// Order of the rows within each partition
+    pub order_by: Vec<Sort>,
+    /// Every symbol of `pattern` with the predicate that defines it
+    pub symbols: Vec<MatchSymbol>,
+    /// The row pattern
+    pub pattern: MatchPattern,
+    /// Values computed for each match
+    pub measures: Vec<MatchMeasure>,
+    /// How many rows are produced for each match
+    pub rows_per_match: RowsPerMatch,
+    /// Where to resume searching after a match
+    pub after_match_skip: AfterMatchSkip,
+    /// The output schema
+    pub schema: DFSchemaRef,
+}
+
+impl MatchRecognize {
+    /// Create a new `MatchRecognize` node, validating the symbols used by
+    /// the pattern, measures and `after_match_skip`
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_new(
+        input: Arc<LogicalPlan>,
+        partition_by: Vec<Expr>,
+        order_by: Vec<Sort>,
+        symbols: Vec<MatchSymbol>,
+        pattern: MatchPattern,
+        measures: Vec<MatchMeasure>,
+        rows_per_match: RowsPerMatch,
+        after_match_skip: AfterMatchSkip,
+    ) -> Result<Self> {
+        let mut defined = HashSet::new();
+        for symbol in &symbols {
+            if !defined.insert(symbol.name.as_str()) {
+                return plan_err!("Symbol {} is defined more than once", symbol.name);
+            }
+        }
+        let pattern_symbols = pattern.symbols();
+        for symbol in &pattern_symbols {
+            if !defined.contains(symbol) {
+                return plan_err!("Pattern symbol {symbol} has no definition");
+            }
+        }
+        let check_symbol = |symbol: &str| {
+            if pattern_symbols.contains(&symbol) {
+                Ok(())
+            } else {
+                plan_err!("Symbol {symbol} does not appear in the pattern")
+            }
+        };
+        for symbol in &symbols {
+            check_symbol(&symbol.name)?;
+        }
+        for measure in &measures {
+            if let Some(symbol) = measure.kind.symbol() {
+                check_symbol(symbol)?;
+            }
+        }
+        if let AfterMatchSkip::ToFirst(symbol) | AfterMatchSkip::ToLast(symbol) =
+            &after_match_skip
+        {
+            check_symbol(symbol)?;
+        }
+
+        let input_schema = input.schema();
+        let mut fields = match rows_per_match {
+            RowsPerMatch::OneRow => partition_by
+                .iter()
+                .map(|expr| match expr {
+                    Expr::Column(column) => {
+                        let (qualifier, field) =
+                            input_schema.qualified_field_from_column(column)?;
+                        Ok((qualifier.cloned(), Arc::new(field.clone())))
+                    }
+                    _ => plan_err!(
+                        "MATCH_RECOGNIZE can only partition by columns, got {expr}"
+                    ),
+                })
+                .collect::<Result<Vec<_>>>()?,
+            RowsPerMatch::AllRows(_) => input_schema
+                .iter()
+                .map(|(qualifier, field)| (qualifier.cloned(), Arc::clone(field)))
+                .collect(),
+        };
+        for measure in &measures {
+            let data_type = match &measure.kind {
+                MatchMeasureKind::MatchNumber => DataType::UInt64,
+                MatchMeasureKind::Classifier => DataType::Utf8,
+                MatchMeasureKind::Count { .. } => DataType::Int64,
+                MatchMeasureKind::First { expr, .. }
+                | MatchMeasureKind::Last { expr, .. } => {
+                    expr.get_type(input_schema.as_ref())?
+                }
+            };
+            // Measures are null for empty matches and unmatched rows
+            fields.push((None, Arc::new(Field::new(&measure.name, data_type, true))));
+        }
+        let schema =
+            DFSchema::new_with_metadata(fields, input_schema.metadata().clone())?;
+
+        Ok(Self {
+            input,
+            partition_by,
+            order_by,
+            symbols,
+            pattern,
+            measures,
+            rows_per_match,
+            after_match_skip,
+            schema: Arc::new(schema),
+        })
+    }
+
+    /// Recreates this node with new expressions, in the order they are
+    /// visited by [`LogicalPlan::apply_expressions`], and a new input
+    pub(crate) fn with_new_exprs(
+        &self,
+        exprs: Vec<Expr>,
+        input: LogicalPlan,
+    ) -> Result<Self> {
+        let mut exprs = exprs.into_iter();
+        let mut next = || {
+            exprs.next().ok_or_else(|| {
+                internal_datafusion_err!("Missing MATCH_RECOGNIZE expression")
+            })
+        };
+        let partition_by = self
+            .partition_by
+            .iter()
+            .map(|_| next())
+            .collect::<Result<_>>()?;
+        let order_by = self
+            .order_by
+            .iter()
+            .map(|sort| Ok(sort.with_expr(next()?)))
+            .collect::<Result<_>>()?;
+        let symbols = self
+            .symbols
+            .iter()
+            .map(|symbol| Ok(MatchSymbol::new(&symbol.name, next()?)))
+            .collect::<Result<_>>()?;
+        let measures = self
+            .measures
+            .iter()
+            .map(|measure| {
+                let kind = match &measure.kind {
+                    MatchMeasureKind::First { symbol, .. } => MatchMeasureKind::First {
+                        expr: next()?,
+                        symbol: symbol.clone(),
+                    },
+                    MatchMeasureKind::Last { symbol, .. } => MatchMeasureKind::Last {
+                        expr: next()?,
+                        symbol: symbol.clone(),
+                    },
+                    kind => kind.clone(),
+                };
+                Ok(MatchMeasure::new(kind, &measure.name))
+            })
+            .collect::<Result<_>>()?;
+        Self::try_new(
+            Arc::new(input),
+            partition_by,
+            order_by,
+            symbols,
+            self.pattern.clone(),
+            measures,
+            self.rows_per_match,
+            self.after_match_skip.clone(),
+        )
+    }
+}
+
+impl PartialOrd for MatchRecognize {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.input.partial_cmp(&other.input)? {
+            Ordering::Equal => {} // continue
+            not_equal => return Some(not_equal),
+        }
+
+        match self.partition_by.partial_cmp(&other.partition_by)? {
+            Ordering::Equal => {} // continue
+            not_equal => return Some(not_equal),
+        }
+
+        match self.order_by.partial_cmp(&other.order_by)? {
+            Ordering::Equal => {} // continue
+            not_equal => return Some(not_equal),
+        }
+
+        // Contract for PartialOrd and PartialEq consistency requires that
+        // a == b if and only if partial_cmp(a, b) == Some(Equal).
+        if self == other {
+            Some(Ordering::Equal)
+        } else {
+            None
+        }
+    }
+}
+
+/// A pattern symbol and the predicate a row must satisfy to be mapped to it
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
+pub struct MatchSymbol {
+    /// The symbol name
+    pub name: String,
+    /// Predicate over the current row, `true` for symbols without a
+    /// `DEFINE` clause
+    pub predicate: Expr,
+}
+
+impl MatchSymbol {
+    /// Create a new symbol
+    pub fn new(name: impl Into<String>, predicate: Expr) -> Self {
+        Self {
+            name: name.into(),
+            predicate,
+        }
+    }
+}
+
+impl<'a> TreeNodeContainer<'a, Expr> for MatchSymbol {
+    fn apply_elements<F: FnMut(&'a Expr) -> Result<TreeNodeRecursion>>(
+        &'a self,
+        f: F,
+    ) -> Result<TreeNodeRecursion> {
+        self.predicate.apply_elements(f)
+    }
+
+    fn map_elements<F: FnMut(Expr) -> Result<Transformed<Expr>>>(
+        self,
+        f: F,
+    ) -> Result<Transformed<Self>> {
+        self.predicate
+            .map_elements(f)?
+            .map_data(|predicate| Ok(Self { predicate, ..self }))
+    }
+}
+
+impl Display for MatchSymbol {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{} AS {}", self.name, self.predicate)
+    }
+}
+
+/// A value computed for each match, named `name` in the output
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
+pub struct MatchMeasure {
+    /// How the value is computed
+    pub kind: MatchMeasureKind,
+    /// The output column name
+    pub name: String,
+}
+
+impl MatchMeasure {
+    /// Create a new measure
+    pub fn new(kind: MatchMeasureKind, name: impl Into<String>) -> Self {
+        Self {
+            kind,
+            name: name.into(),
+        }
+    }
+}
+
+impl<'a> TreeNodeContainer<'a, Expr> for MatchMeasure {
+    fn apply_elements<F: FnMut(&'a Expr) -> Result<TreeNodeRecursion>>(
+        &'a self,
+        mut f: F,
+    ) -> Result<TreeNodeRecursion> {
+        match &self.kind {
+            MatchMeasureKind::First { expr, .. }
+            | MatchMeasureKind::Last { expr, .. } => f(expr),
+            _ => Ok(TreeNodeRecursion::Continue),
+        }
+    }
+
+    fn map_elements<F: FnMut(Expr) -> Result<Transformed<Expr>>>(
+        self,
+        mut f: F,
+    ) -> Result<Transformed<Self>> {
+        let Self { kind, name } = self;
+        match kind {
+            MatchMeasureKind::First { expr, symbol } => {
+                f(expr)?.update_data(|expr| MatchMeasureKind::First { expr, symbol })
+            }
+            MatchMeasureKind::Last { expr, symbol } => {
+                f(expr)?.update_data(|expr| MatchMeasureKind::Last { expr, symbol })
+            }
+            kind => Transformed::no(kind),
+        }
+        .map_data(|kind| Ok(Self { kind, name }))
+    }
+}
+
+impl Display for MatchMeasure {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{} AS {}", self.kind, self.name)
+    }
+}
+
+/// How a [`MatchMeasure`] is computed from the rows of a match.
+///
+/// With [`RowsPerMatch::AllRows`] measures are computed over the rows of the
+/// match up to the current row.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
+pub enum MatchMeasureKind {
+    /// `MATCH_NUMBER()`: the sequential number of the match within its
+    /// partition, starting at 1
+    MatchNumber,
+    /// `CLASSIFIER()`: the symbol of the last row
+    Classifier,
+    /// `COUNT(*)` or `COUNT(symbol.*)`: the number of rows, optionally only
+    /// those mapped to `symbol`
+    Count { symbol: Option<String> },
+    /// `FIRST(expr)`: `expr` evaluated on the first row, optionally the
+    /// first row mapped to `symbol`
+    First { expr: Expr, symbol: Option<String> },
+    /// `LAST(expr)`: `expr` evaluated on the last row, optionally the last
+    /// row mapped to `symbol`
+    Last { expr: Expr, symbol: Option<String> },
+}
+
+impl MatchMeasureKind {
+    /// The symbol this measure is restricted to, if any
+    pub fn symbol(&self) -> Option<&str> {
+        match self {
+            MatchMeasureKind::MatchNumber | MatchMeasureKind::Classifier => None,
+            MatchMeasureKind::Count { symbol }
+            | MatchMeasureKind::First { symbol, .. }
+            | MatchMeasureKind::Last { symbol, .. } => symbol.as_deref(),
+        }
+    }
+}
+
+impl Display for MatchMeasureKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            MatchMeasureKind::MatchNumber => write!(f, "MATCH_NUMBER()"),
+            MatchMeasureKind::Classifier => write!(f, "CLASSIFIER()"),
+            MatchMeasureKind::Count { symbol: None } => write!(f, "COUNT(*)"),
+            MatchMeasureKind::Count {
+                symbol: Some(symbol),
+            } => write!(f, "COUNT({symbol}.*)"),
+            MatchMeasureKind::First { expr, symbol } => {
+                write!(f, "FIRST({expr})")?;
+                fmt_symbol(f, symbol)
+            }
+            MatchMeasureKind::Last { expr, symbol } => {
+                write!(f, "LAST({expr})")?;
+                fmt_symbol(f, symbol)
+            }
+        }
+    }
+}
+
+fn fmt_symbol(f: &mut Formatter, symbol: &Option<String>) -> fmt::Result {
+    match symbol {
+        Some(symbol) => write!(f, " FOR {symbol}"),
+        None => Ok(()),
+    }
+}
+
+/// A regular expression over pattern symbols
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
+pub enum MatchPattern {
+    /// A row mapped to the symbol
+    Symbol(String),
+    /// `^`: the start of the partition
+    PartitionStart,
+    /// `$`: the end of the partition
+    PartitionEnd,
+    /// `{- pattern -}`: rows matched by `pattern` are excluded from the
+    /// output of [`RowsPerMatch::AllRows`]
+    Exclude(Box<MatchPattern>),
+    /// `PERMUTE(pattern, ...)`: all patterns in any order, preferring the
+    /// order they are listed in
+    Permute(Vec<MatchPattern>),
+    /// `pattern pattern ...`
+    Concat(Vec<MatchPattern>),
+    /// `pattern | pattern | ...`: the first alternative that matches
+    Alternation(Vec<MatchPattern>),
+    /// `pattern{min,max}`: greedily matches `pattern` between `min` and
+    /// `max` (or unlimited) times
+    Repetition {
+        pattern: Box<MatchPattern>,
+        min: u32,
+        max: Option<u32>,
+    },
+}
+
+impl MatchPattern {
+    /// Returns the symbols used by this pattern, in order of appearance
+    pub fn symbols(&self) -> Vec<&str> {
+        let mut symbols = vec![];
+        self.collect_symbols(&mut symbols);
+        symbols
+    }
+
+    fn collect_symbols<'a>(&'a self, symbols: &mut Vec<&'a str>) {
+        match self {
+            MatchPattern::Symbol(symbol) => {
+                if !symbols.contains(&symbol.as_str()) {
+                    symbols.push(symbol);
+                }
+            }
+            MatchPattern::PartitionStart | MatchPattern::PartitionEnd => {}
+            MatchPattern::Exclude(pattern) | MatchPattern::Repetition { pattern, .. } => {
+                pattern.collect_symbols(symbols)
+            }
+            MatchPattern::Permute(patterns)
+            | MatchPattern::Concat(patterns)
+            | MatchPattern::Alternation(patterns) => {
+                for pattern in patterns {
+                    pattern.collect_symbols(symbols);
+                }
+            }
+        }
+    }
+
+    /// Writes `self`, in parentheses if it would otherwise bind to its
+    /// neighbours differently
+    fn fmt_nested(&self, f: &mut Formatter, parenthesize: bool) -> fmt::Result {
+        if parenthesize {
+            write!(f, "({self})")
+        } else {
+            write!(f, "{self}")
+        }
+    }
+}
+
+impl Display for MatchPattern {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            MatchPattern::Symbol(symbol) => write!(f, "{symbol}"),
+            MatchPattern::PartitionStart => write!(f, "^"),
+            MatchPattern::PartitionEnd => write!(f, "$"),
+            MatchPattern::Exclude(pattern) => write!(f, "{{- {pattern} -}}"),
+            MatchPattern::Permute(patterns) => {
+                write!(f, "PERMUTE(")?;
+                for (i, pattern) in patterns.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{pattern}")?;
+                }
+                write!(f, ")")
+            }
+            MatchPattern::Concat(patterns) => {
+                for (i, pattern) in patterns.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " ")?;
+                    }
+                    let parenthesize = matches!(pattern, MatchPattern::Alternation(_));
+                    pattern.fmt_nested(f, parenthesize)?;
+                }
+                Ok(())
+            }
+            MatchPattern::Alternation(patterns) => {
+                for (i, pattern) in patterns.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " | ")?;
+                    }
+                    write!(f, "{pattern}")?;
+                }
+                Ok(())
+            }
+            MatchPattern::Repetition { pattern, min, max } => {
+                let parenthesize = matches!(
+                    pattern.as_ref(),
+                    MatchPattern::Concat(_)
+                        | MatchPattern::Alternation(_)
+                        | MatchPattern::Repetition { .. }
+                );
+                pattern.fmt_nested(f, parenthesize)?;
+                match (min, max) {
+                    (0, None) => write!(f, "*"),
+                    (1, None) => write!(f, "+"),
+                    (0, Some(1)) => write!(f, "?"),
+                    (min, None) => write!(f, "{{{min},}}"),
+                    (min, Some(max)) if min == max => write!(f, "{{{min}}}"),
+                    (min, Some(max)) => write!(f, "{{{min},{max}}}"),
+                }
+            }
+        }
+    }
+}
+
+/// How many rows [`MatchRecognize`] produces for each match
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Hash)]
+pub enum RowsPerMatch {
+    /// `ONE ROW PER MATCH`: one summary row per match
+    OneRow,
+    /// `ALL ROWS PER MATCH`: every matched row that is not excluded
+    AllRows(EmptyMatchesMode),
+}
+
+impl Display for RowsPerMatch {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            RowsPerMatch::OneRow => write!(f, "one row per match"),
+            RowsPerMatch::AllRows(mode) => write!(f, "all rows per match {mode}"),
+        }
+    }
+}
+
+/// How [`RowsPerMatch::AllRows`] outputs empty matches and unmatched rows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Hash)]
+pub enum EmptyMatchesMode {
+    /// `SHOW EMPTY MATCHES`: an empty match produces its starting row with
+    /// null measures
+    #[default]
+    Show,
+    /// `OMIT EMPTY MATCHES`: empty matches produce no rows
+    Omit,
+    /// `WITH UNMATCHED ROWS`: like `Show`, and rows that are not part of any
+    /// match are produced with null measures
+    WithUnmatched,
+}
+
+impl Display for EmptyMatchesMode {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            EmptyMatchesMode::Show => write!(f, "show empty matches"),
+            EmptyMatchesMode::Omit => write!(f, "omit empty matches"),
+            EmptyMatchesMode::WithUnmatched => write!(f, "with unmatched rows"),
+        }
+    }
+}
+
+/// Where [`MatchRecognize`] resumes searching after a match
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Hash)]
+pub enum AfterMatchSkip {
+    /// `PAST LAST ROW`: after the last row of the match
+    #[default]
+    PastLastRow,
+    /// `TO NEXT ROW`: after the first row of the match
+    ToNextRow,
+    /// `TO FIRST symbol`: at the first row mapped to the symbol
+    ToFirst(String),
+    /// `TO LAST symbol`: at the last row mapped to the symbol
+    ToLast(String),
+}
+
+impl Display for AfterMatchSkip {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            AfterMatchSkip::PastLastRow => write!(f, "past last row"),
+            AfterMatchSkip::ToNextRow => write!(f, "to next row"),
+            AfterMatchSkip::ToFirst(symbol) => write!(f, "to first {symbol}"),
+            AfterMatchSkip::ToLast(symbol) => write!(f, "to last {symbol}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{col, lit, table_scan};
+    use arrow::datatypes::Schema;
+
+    fn symbol(name: &str) -> MatchPattern {
+        MatchPattern::Symbol(name.to_string())
+    }
+
+    fn repeat(pattern: MatchPattern, min: u32, max: Option<u32>) -> MatchPattern {
+        MatchPattern::Repetition {
+            pattern: Box::new(pattern),
+            min,
+            max,
+        }
+    }
+
+    fn input() -> Arc<LogicalPlan> {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("ts", DataType::Int64, false),
+        ]);
+        Arc::new(
+            table_scan(Some("t"), &schema, None)
+                .unwrap()
+                .build()
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn display_pattern() {
+        let pattern = MatchPattern::Concat(vec![
+            MatchPattern::PartitionStart,
+            symbol("a"),
+            repeat(symbol("b"), 1, None),
+            repeat(
+                MatchPattern::Alternation(vec![symbol("c"), symbol("d")]),
+                0,
+                Some(1),
+            ),
+            MatchPattern::Alternation(vec![symbol("e"), symbol("f")]),
+            repeat(
+                MatchPattern::Concat(vec![symbol("g"), symbol("h")]),
+                2,
+                Some(2),
+            ),
+            repeat(symbol("i"), 1, Some(3)),
+            MatchPattern::Exclude(Box::new(repeat(symbol("j"), 0, None))),
+            MatchPattern::Permute(vec![symbol("k"), symbol("l")]),
+        ]);
+        assert_eq!(
+            pattern.to_string(),
+            "^ a b+ (c | d)? (e | f) (g h){2} i{1,3} {- j* -} PERMUTE(k, l)"
+        );
+        assert_eq!(
+            pattern.symbols(),
+            vec!["a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l"]
+        );
+    }
+
+    #[test]
+    fn one_row_per_match_schema() -> Result<()> {
+        let plan = MatchRecognize::try_new(
+            input(),
+            vec![col("id")],
+            vec![col("ts").sort(true, false)],
+            vec![MatchSymbol::new("a", lit(true))],
+            repeat(symbol("a"), 1, None),
+            vec![
+                MatchMeasure::new(MatchMeasureKind::MatchNumber, "m"),
+                MatchMeasure::new(
+                    MatchMeasureKind::First {
+                        expr: col("ts"),
+                        symbol: Some("a".to_string()),
+                    },
+                    "start_ts",
+                ),
+            ],
+            RowsPerMatch::OneRow,
+            AfterMatchSkip::PastLastRow,
+        )?;
+        let fields = plan
+            .schema
+            .columns()
+            .iter()
+            .map(|column| column.flat_name())
+            .collect::<Vec<_>>();
+        assert_eq!(fields, vec!["t.id", "m", "start_ts"]);
+        assert_eq!(
+            plan.schema.field(2).data_type(),
+            &DataType::Int64,
+            "FIRST has the type of its argument"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn undefined_symbols() {
+        let err = MatchRecognize::try_new(
+            input(),
+            vec![],
+            vec![],
+            vec![MatchSymbol::new("a", lit(true))],
+            MatchPattern::Concat(vec![symbol("a"), symbol("b")]),
+            vec![],
+            RowsPerMatch::AllRows(EmptyMatchesMode::Show),
+            AfterMatchSkip::PastLastRow,
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Pattern symbol b has no definition"));
+
+        let err = MatchRecognize::try_new(
+            input(),
+            vec![],
+            vec![],
+            vec![MatchSymbol::new("a", lit(true))],
+            symbol("a"),
+            vec![],
+            RowsPerMatch::OneRow,
+            AfterMatchSkip::ToLast("c".to_string()),
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Symbol c does not appear in the pattern"));
+    }
+}
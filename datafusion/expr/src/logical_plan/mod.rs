@@ -22,6 +22,7 @@ pub mod dml;
 mod extension;
 pub(crate) mod invariants;
 pub use invariants::{assert_expected_schema, check_subquery_expr, InvariantLevel};
+mod match_recognize;
 mod plan;
 mod statement;
 pub mod tree_node;
@@ -37,16 +38,22 @@ pub use ddl::{
     DropCatalogSchema, DropFunction, DropTable, DropView, OperateFunctionArg,
 };
 pub use dml::{DmlStatement, WriteOp};
+pub use match_recognize::{
+    AfterMatchSkip, EmptyMatchesMode, MatchMeasure, MatchMeasureKind, MatchPattern,
+    MatchRecognize, MatchSymbol, RowsPerMatch,
+};
 pub use plan::{
-    projection_schema, Aggregate, Analyze, ColumnUnnestList, DescribeTable, Distinct,
-    DistinctOn, EmptyRelation, Explain, ExplainOption, Extension, FetchType, Filter,
-    Join, JoinConstraint, JoinType, Limit, LogicalPlan, Partitioning, PlanType,
-    Projection, RecursiveQuery, Repartition, SkipType, Sort, StringifiedPlan, Subquery,
-    SubqueryAlias, TableScan, ToStringifiedPlan, Union, Unnest, Values, Window,
+    projection_schema, Aggregate, Analyze, AsOfMatchCondition, ColumnUnnestList,
+    DescribeTable, Distinct, DistinctOn, EmptyRelation, Explain, ExplainOption,
+    Extension, FetchType, Filter, Join, JoinConstraint, JoinType, Limit, LogicalPlan,
+    Partitioning, PlanType, Projection, RecursiveQuery, Repartition, Sample,
+    SampleMethod, SkipType, Sort, StringifiedPlan, Subquery, SubqueryAlias, TableScan,
+    ToStringifiedPlan, Union, Unnest, Values, Window,
 };
 pub use statement::{
-    Deallocate, Execute, Prepare, SetVariable, Statement, TransactionAccessMode,
-    TransactionConclusion, TransactionEnd, TransactionIsolationLevel, TransactionStart,
+    AnalyzeTable, Deallocate, Execute, Prepare, SetVariable, Statement,
+    TransactionAccessMode, TransactionConclusion, TransactionEnd,
+    TransactionIsolationLevel, TransactionStart,
 };
 
 pub use datafusion_common::format::ExplainFormat;
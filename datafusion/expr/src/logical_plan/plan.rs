@@ -28,11 +28,12 @@ use super::invariants::{
     assert_always_invariants_at_current_node, assert_executable_invariants,
     InvariantLevel,
 };
+use super::match_recognize::{MatchRecognize, RowsPerMatch};
 use super::DdlStatement;
 use crate::builder::{unique_field_aliases, unnest_with_options};
 use crate::expr::{
-    intersect_metadata_for_union, Alias, Placeholder, Sort as SortExpr, WindowFunction,
-    WindowFunctionParams,
+    intersect_metadata_for_union, Alias, Cast, Placeholder, Sort as SortExpr, TryCast,
+    WindowFunction, WindowFunctionParams,
 };
 use crate::expr_rewriter::{
     create_col_from_scalar_expr, normalize_cols, normalize_sorts, NamePreserver,
@@ -42,7 +43,8 @@ use crate::logical_plan::extension::UserDefinedLogicalNode;
 use crate::logical_plan::{DmlStatement, Statement};
 use crate::utils::{
     enumerate_grouping_sets, exprlist_to_fields, find_out_reference_exprs,
-    grouping_set_expr_count, grouping_set_to_exprlist, split_conjunction,
+    find_valid_equijoin_key_pair, grouping_set_expr_count, grouping_set_to_exprlist,
+    split_conjunction,
 };
 use crate::{
     build_join_schema, expr_vec_fmt, requalify_sides_if_needed, BinaryExpr,
@@ -58,10 +60,10 @@ use datafusion_common::tree_node::{
     Transformed, TreeNode, TreeNodeContainer, TreeNodeRecursion,
 };
 use datafusion_common::{
-    aggregate_functional_dependencies, internal_err, plan_err, Column, Constraints,
-    DFSchema, DFSchemaRef, DataFusionError, Dependency, FunctionalDependence,
-    FunctionalDependencies, NullEquality, ParamValues, Result, ScalarValue, Spans,
-    TableReference, UnnestOptions,
+    aggregate_functional_dependencies, internal_err, plan_datafusion_err, plan_err,
+    Column, Constraints, DFSchema, DFSchemaRef, DataFusionError, Dependency,
+    FunctionalDependence, FunctionalDependencies, NullEquality, ParamValues, Result,
+    ScalarValue, Spans, TableReference, UnnestOptions,
 };
 use indexmap::IndexSet;
 
@@ -257,6 +259,14 @@ pub enum LogicalPlan {
     SubqueryAlias(SubqueryAlias),
     /// Skip some number of rows, and then fetch some number of rows.
     Limit(Limit),
+    /// Returns a random sample of the rows of its input. This is used to
+    /// implement SQL `TABLESAMPLE`.
+    Sample(Sample),
+    /// Finds sequences of rows that match a pattern. This is used to
+    /// implement SQL `MATCH_RECOGNIZE`.
+    ///
+    /// See [`MatchRecognize`] for more details
+    MatchRecognize(MatchRecognize),
     /// A DataFusion [`Statement`] such as `SET VARIABLE` or `START TRANSACTION`
     Statement(Statement),
     /// Values expression. See
@@ -337,6 +347,8 @@ impl LogicalPlan {
             LogicalPlan::Join(Join { schema, .. }) => schema,
             LogicalPlan::Repartition(Repartition { input, .. }) => input.schema(),
             LogicalPlan::Limit(Limit { input, .. }) => input.schema(),
+            LogicalPlan::Sample(Sample { input, .. }) => input.schema(),
+            LogicalPlan::MatchRecognize(MatchRecognize { schema, .. }) => schema,
             LogicalPlan::Statement(statement) => statement.schema(),
             LogicalPlan::Subquery(Subquery { subquery, .. }) => subquery.schema(),
             LogicalPlan::SubqueryAlias(SubqueryAlias { schema, .. }) => schema,
@@ -457,6 +469,8 @@ impl LogicalPlan {
             LogicalPlan::Sort(Sort { input, .. }) => vec![input],
             LogicalPlan::Join(Join { left, right, .. }) => vec![left, right],
             LogicalPlan::Limit(Limit { input, .. }) => vec![input],
+            LogicalPlan::Sample(Sample { input, .. }) => vec![input],
+            LogicalPlan::MatchRecognize(MatchRecognize { input, .. }) => vec![input],
             LogicalPlan::Subquery(Subquery { subquery, .. }) => vec![subquery],
             LogicalPlan::SubqueryAlias(SubqueryAlias { input, .. }) => vec![input],
             LogicalPlan::Extension(extension) => extension.node.inputs(),
@@ -542,6 +556,7 @@ impl LogicalPlan {
             | LogicalPlan::Distinct(Distinct::All(input))
             | LogicalPlan::Sort(Sort { input, .. })
             | LogicalPlan::Limit(Limit { input, .. })
+            | LogicalPlan::Sample(Sample { input, .. })
             | LogicalPlan::Repartition(Repartition { input, .. })
             | LogicalPlan::Window(Window { input, .. }) => input.head_output_expr(),
             LogicalPlan::Join(Join {
@@ -557,9 +572,10 @@ impl LogicalPlan {
                         left.head_output_expr()
                     }
                 }
-                JoinType::LeftSemi | JoinType::LeftAnti | JoinType::LeftMark => {
-                    left.head_output_expr()
-                }
+                JoinType::LeftSemi
+                | JoinType::LeftAnti
+                | JoinType::LeftMark
+                | JoinType::AsOf => left.head_output_expr(),
                 JoinType::RightSemi | JoinType::RightAnti | JoinType::RightMark => {
                     right.head_output_expr()
                 }
@@ -586,6 +602,7 @@ impl LogicalPlan {
             }
             LogicalPlan::Subquery(_) => Ok(None),
             LogicalPlan::EmptyRelation(_)
+            | LogicalPlan::MatchRecognize(_)
             | LogicalPlan::Statement(_)
             | LogicalPlan::Values(_)
             | LogicalPlan::Explain(_)
@@ -692,6 +709,8 @@ impl LogicalPlan {
                 schema: _,
             }) => SubqueryAlias::try_new(input, alias).map(LogicalPlan::SubqueryAlias),
             LogicalPlan::Limit(_) => Ok(self),
+            LogicalPlan::Sample(_) => Ok(self),
+            LogicalPlan::MatchRecognize(_) => Ok(self),
             LogicalPlan::Ddl(_) => Ok(self),
             LogicalPlan::Extension(Extension { node }) => {
                 // todo make an API that does not require cloning
@@ -982,6 +1001,27 @@ impl LogicalPlan {
                     input: Arc::new(input),
                 }))
             }
+            LogicalPlan::Sample(Sample {
+                method,
+                fraction,
+                seed,
+                ..
+            }) => {
+                self.assert_no_expressions(expr)?;
+                let input = self.only_input(inputs)?;
+                Ok(LogicalPlan::Sample(Sample {
+                    input: Arc::new(input),
+                    method: *method,
+                    fraction: *fraction,
+                    seed: *seed,
+                }))
+            }
+            LogicalPlan::MatchRecognize(match_recognize) => {
+                let input = self.only_input(inputs)?;
+                match_recognize
+                    .with_new_exprs(expr, input)
+                    .map(LogicalPlan::MatchRecognize)
+            }
             LogicalPlan::Ddl(DdlStatement::CreateMemoryTable(CreateMemoryTable {
                 name,
                 if_not_exists,
@@ -1344,14 +1384,26 @@ impl LogicalPlan {
                         (left_max, right_max, _) => Some(left_max * right_max),
                     }
                 }
-                JoinType::LeftSemi | JoinType::LeftAnti | JoinType::LeftMark => {
-                    left.max_rows()
-                }
+                JoinType::LeftSemi
+                | JoinType::LeftAnti
+                | JoinType::LeftMark
+                | JoinType::AsOf => left.max_rows(),
                 JoinType::RightSemi | JoinType::RightAnti | JoinType::RightMark => {
                     right.max_rows()
                 }
             },
             LogicalPlan::Repartition(Repartition { input, .. }) => input.max_rows(),
+            LogicalPlan::Sample(Sample { input, .. }) => input.max_rows(),
+            LogicalPlan::MatchRecognize(MatchRecognize {
+                input,
+                rows_per_match,
+                ..
+            }) => match rows_per_match {
+                // Every match starts at a different row
+                RowsPerMatch::OneRow => input.max_rows(),
+                // Rows can be part of several matches
+                RowsPerMatch::AllRows(_) => None,
+            },
             LogicalPlan::Union(Union { inputs, .. }) => {
                 inputs.iter().try_fold(0usize, |mut acc, plan| {
                     acc += plan.max_rows()?;
@@ -1976,6 +2028,32 @@ impl LogicalPlan {
                             "Limit: skip={skip_str}, fetch={fetch_str}",
                         )
                     }
+                    LogicalPlan::Sample(Sample { method, fraction, seed, .. }) => {
+                        write!(f, "Sample: method={method}, fraction={fraction}")?;
+                        if let Some(seed) = seed {
+                            write!(f, ", seed={seed}")?;
+                        }
+                        Ok(())
+                    }
+                    LogicalPlan::MatchRecognize(MatchRecognize {
+                        partition_by,
+                        order_by,
+                        symbols,
+                        pattern,
+                        measures,
+                        rows_per_match,
+                        after_match_skip,
+                        ..
+                    }) => {
+                        write!(
+                            f,
+                            "MatchRecognize: partition_by=[{}], order_by=[{}], measures=[{}], {rows_per_match}, after match skip {after_match_skip}, pattern=({pattern}), define=[{}]",
+                            expr_vec_fmt!(partition_by),
+                            expr_vec_fmt!(order_by),
+                            expr_vec_fmt!(measures),
+                            expr_vec_fmt!(symbols),
+                        )
+                    }
                     LogicalPlan::Subquery(Subquery { .. }) => {
                         write!(f, "Subquery:")
                     }
@@ -3246,6 +3324,75 @@ impl Limit {
     }
 }
 
+/// Returns a random sample of the rows of its input.
+///
+/// Every row ([`SampleMethod::Bernoulli`]) or block of rows
+/// ([`SampleMethod::System`]) of the input is kept with probability `fraction`.
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct Sample {
+    /// The incoming logical plan
+    pub input: Arc<LogicalPlan>,
+    /// How rows are selected
+    pub method: SampleMethod,
+    /// Probability of keeping a row or block, between 0 and 1
+    pub fraction: f64,
+    /// Seed for the random number generator. Samples with the same seed
+    /// select the same rows from the same data.
+    pub seed: Option<u64>,
+}
+
+impl Sample {
+    /// Create a new sample of `input`, checking that `fraction` is valid
+    pub fn try_new(
+        input: Arc<LogicalPlan>,
+        method: SampleMethod,
+        fraction: f64,
+        seed: Option<u64>,
+    ) -> Result<Self> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return plan_err!("Sample fraction must be between 0 and 1, got {fraction}");
+        }
+        Ok(Self {
+            input,
+            method,
+            fraction,
+            seed,
+        })
+    }
+}
+
+// `fraction` is never NaN, see `Sample::try_new`
+impl Eq for Sample {}
+
+impl Hash for Sample {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.input.hash(state);
+        self.method.hash(state);
+        self.fraction.to_bits().hash(state);
+        self.seed.hash(state);
+    }
+}
+
+/// How [`Sample`] selects rows from its input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Hash)]
+pub enum SampleMethod {
+    /// Keep every row independently, with the same probability
+    Bernoulli,
+    /// Keep or discard whole blocks of rows, such as Parquet row groups or
+    /// record batches. Faster than [`SampleMethod::Bernoulli`] as discarded
+    /// blocks do not need to be read, but the sample is less random.
+    System,
+}
+
+impl Display for SampleMethod {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            SampleMethod::Bernoulli => write!(f, "bernoulli"),
+            SampleMethod::System => write!(f, "system"),
+        }
+    }
+}
+
 /// Removes duplicate rows from the input
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
 pub enum Distinct {
@@ -3765,6 +3912,7 @@ impl Join {
             || original_join.join_type == JoinType::Left
             || original_join.join_type == JoinType::Right
             || original_join.join_type == JoinType::Full
+            || original_join.join_type == JoinType::AsOf
         {
             (left_sch, right_sch, requalified) =
                 requalify_sides_if_needed(left_sch.clone(), right_sch.clone())?;
@@ -3797,6 +3945,153 @@ impl Join {
             requalified,
         ))
     }
+
+    /// Returns the match condition of a [`JoinType::AsOf`] join, which is
+    /// stored as the join `filter`.
+    ///
+    /// The filter must compare an expression on the left input with an
+    /// expression on the right input using `>=`, `>`, `<=` or `<`. For example
+    /// `trades.ts >= quotes.ts` matches every trade with the quote that has the
+    /// latest `quotes.ts` at or before `trades.ts`.
+    ///
+    /// The comparison can be combined with a tolerance that bounds how far
+    /// away the match may be, written as `quotes.ts >= trades.ts - <literal>`
+    /// for `>=` and `>`, or as `quotes.ts <= trades.ts + <literal>` for `<=`
+    /// and `<`.
+    pub fn asof_match_condition(&self) -> Result<AsOfMatchCondition> {
+        let Some(filter) = &self.filter else {
+            return plan_err!("AsOf join requires a match condition");
+        };
+        let comparisons = split_conjunction(filter)
+            .into_iter()
+            .map(|expr| {
+                asof_comparison(expr, self.left.schema(), self.right.schema())?
+                    .ok_or_else(|| {
+                        plan_datafusion_err!(
+                            "Unsupported AsOf join match condition {expr}, expected a \
+                             comparison between the left and the right input"
+                        )
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        match comparisons.as_slice() {
+            [(left, op, right)] => Ok(AsOfMatchCondition {
+                left: left.clone(),
+                op: *op,
+                right: right.clone(),
+                tolerance: None,
+            }),
+            [first, second] => {
+                let tolerance = asof_tolerance(first, second)
+                    .map(|tolerance| (second, tolerance))
+                    .or_else(|| {
+                        asof_tolerance(second, first).map(|tolerance| (first, tolerance))
+                    });
+                match tolerance {
+                    Some(((left, op, right), tolerance)) => Ok(AsOfMatchCondition {
+                        left: left.clone(),
+                        op: *op,
+                        right: right.clone(),
+                        tolerance: Some(tolerance),
+                    }),
+                    None => plan_err!(
+                        "Unsupported AsOf join match condition {filter}, expected a \
+                         comparison with an optional tolerance"
+                    ),
+                }
+            }
+            _ => plan_err!(
+                "Unsupported AsOf join match condition {filter}, expected a \
+                 comparison with an optional tolerance"
+            ),
+        }
+    }
+}
+
+/// The match condition of a [`JoinType::AsOf`] join, see
+/// [`Join::asof_match_condition`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct AsOfMatchCondition {
+    /// Expression on the left input
+    pub left: Expr,
+    /// How `left` compares to the matching `right` value: one of `>=`, `>`,
+    /// `<=` or `<`
+    pub op: Operator,
+    /// Expression on the right input
+    pub right: Expr,
+    /// Maximum distance between `left` and the matching `right` value
+    pub tolerance: Option<ScalarValue>,
+}
+
+/// Splits `expr` into `(left, op, right)` if it is an inequality between an
+/// expression on the left input and an expression on the right input
+fn asof_comparison(
+    expr: &Expr,
+    left_schema: &DFSchema,
+    right_schema: &DFSchema,
+) -> Result<Option<(Expr, Operator, Expr)>> {
+    let Expr::BinaryExpr(BinaryExpr { left, op, right }) = expr else {
+        return Ok(None);
+    };
+    if !matches!(
+        op,
+        Operator::Gt | Operator::GtEq | Operator::Lt | Operator::LtEq
+    ) {
+        return Ok(None);
+    }
+    let Some((l, r)) =
+        find_valid_equijoin_key_pair(left, right, left_schema, right_schema)?
+    else {
+        return Ok(None);
+    };
+    let op = if &l == left.as_ref() {
+        *op
+    } else {
+        // `swap` is defined for all comparison operators
+        op.swap().unwrap()
+    };
+    Ok(Some((l, op, r)))
+}
+
+/// Returns the tolerance if `bound` limits how far the right value may be from
+/// the left value of `condition`
+fn asof_tolerance(
+    condition: &(Expr, Operator, Expr),
+    bound: &(Expr, Operator, Expr),
+) -> Option<ScalarValue> {
+    let (left, op, right) = condition;
+    let (bound_left, bound_op, bound_right) = bound;
+    let Expr::BinaryExpr(BinaryExpr {
+        left: base,
+        op: offset_op,
+        right: offset,
+    }) = bound_left
+    else {
+        return None;
+    };
+    let Expr::Literal(tolerance, _) = offset.as_ref() else {
+        return None;
+    };
+    let expected_ops = match op {
+        Operator::GtEq | Operator::Gt => (Operator::Minus, Operator::LtEq),
+        Operator::LtEq | Operator::Lt => (Operator::Plus, Operator::GtEq),
+        _ => return None,
+    };
+    let matches = (*offset_op, *bound_op) == expected_ops
+        && strip_casts(base) == strip_casts(left)
+        && strip_casts(bound_right) == strip_casts(right)
+        && !tolerance.is_null();
+    matches.then(|| tolerance.clone())
+}
+
+fn strip_casts(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Cast(Cast { expr, .. }) | Expr::TryCast(TryCast { expr, .. }) => {
+            strip_casts(expr)
+        }
+        _ => expr,
+    }
 }
 
 // Manual implementation needed because of `schema` field. Comparison excludes this field.
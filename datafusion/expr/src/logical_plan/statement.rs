@@ -16,7 +16,7 @@
 // under the License.
 
 use arrow::datatypes::DataType;
-use datafusion_common::{DFSchema, DFSchemaRef};
+use datafusion_common::{DFSchema, DFSchemaRef, TableReference};
 use itertools::Itertools as _;
 use std::fmt::{self, Display};
 use std::sync::{Arc, LazyLock};
@@ -46,6 +46,9 @@ pub enum Statement {
     /// Deallocate a prepared statement.
     /// This is used to implement SQL 'DEALLOCATE'.
     Deallocate(Deallocate),
+    /// Compute statistics of a table.
+    /// This is used to implement SQL 'ANALYZE TABLE'.
+    AnalyzeTable(AnalyzeTable),
 }
 
 impl Statement {
@@ -68,6 +71,7 @@ impl Statement {
             Statement::Prepare(_) => "Prepare",
             Statement::Execute(_) => "Execute",
             Statement::Deallocate(_) => "Deallocate",
+            Statement::AnalyzeTable(_) => "AnalyzeTable",
         }
     }
 
@@ -126,6 +130,16 @@ impl Statement {
                     Statement::Deallocate(Deallocate { name }) => {
                         write!(f, "Deallocate: {name}")
                     }
+                    Statement::AnalyzeTable(AnalyzeTable {
+                        table_name,
+                        columns,
+                    }) => {
+                        write!(f, "AnalyzeTable: {table_name}")?;
+                        if !columns.is_empty() {
+                            write!(f, " columns=[{}]", columns.join(", "))?;
+                        }
+                        Ok(())
+                    }
                 }
             }
         }
@@ -212,3 +226,16 @@ pub struct Deallocate {
     /// The name of the prepared statement to deallocate
     pub name: String,
 }
+
+/// Compute statistics of a table, and store them in the table statistics
+/// cache of the [`CacheManager`], so that they can be used to estimate the
+/// cardinality of filters and joins.
+///
+/// [`CacheManager`]: https://docs.rs/datafusion/latest/datafusion/execution/cache/cache_manager/struct.CacheManager.html
+#[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Hash)]
+pub struct AnalyzeTable {
+    /// The name of the table to analyze
+    pub table_name: TableReference,
+    /// The names of the columns to analyze, all columns if empty
+    pub columns: Vec<String>,
+}
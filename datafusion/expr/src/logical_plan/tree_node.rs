@@ -40,9 +40,9 @@
 use crate::{
     dml::CopyTo, Aggregate, Analyze, CreateMemoryTable, CreateView, DdlStatement,
     Distinct, DistinctOn, DmlStatement, Execute, Explain, Expr, Extension, Filter, Join,
-    Limit, LogicalPlan, Partitioning, Prepare, Projection, RecursiveQuery, Repartition,
-    Sort, Statement, Subquery, SubqueryAlias, TableScan, Union, Unnest,
-    UserDefinedLogicalNode, Values, Window,
+    Limit, LogicalPlan, MatchRecognize, Partitioning, Prepare, Projection,
+    RecursiveQuery, Repartition, Sample, Sort, Statement, Subquery, SubqueryAlias,
+    TableScan, Union, Unnest, UserDefinedLogicalNode, Values, Window,
 };
 use datafusion_common::tree_node::TreeNodeRefContainer;
 
@@ -148,6 +148,42 @@ impl TreeNode for LogicalPlan {
             LogicalPlan::Limit(Limit { skip, fetch, input }) => input
                 .map_elements(f)?
                 .update_data(|input| LogicalPlan::Limit(Limit { skip, fetch, input })),
+            LogicalPlan::Sample(Sample {
+                input,
+                method,
+                fraction,
+                seed,
+            }) => input.map_elements(f)?.update_data(|input| {
+                LogicalPlan::Sample(Sample {
+                    input,
+                    method,
+                    fraction,
+                    seed,
+                })
+            }),
+            LogicalPlan::MatchRecognize(MatchRecognize {
+                input,
+                partition_by,
+                order_by,
+                symbols,
+                pattern,
+                measures,
+                rows_per_match,
+                after_match_skip,
+                schema,
+            }) => input.map_elements(f)?.update_data(|input| {
+                LogicalPlan::MatchRecognize(MatchRecognize {
+                    input,
+                    partition_by,
+                    order_by,
+                    symbols,
+                    pattern,
+                    measures,
+                    rows_per_match,
+                    after_match_skip,
+                    schema,
+                })
+            }),
             LogicalPlan::Subquery(Subquery {
                 subquery,
                 outer_ref_columns,
@@ -429,6 +465,13 @@ impl LogicalPlan {
                 (on, filter).apply_ref_elements(f)
             }
             LogicalPlan::Sort(Sort { expr, .. }) => expr.apply_elements(f),
+            LogicalPlan::MatchRecognize(MatchRecognize {
+                partition_by,
+                order_by,
+                symbols,
+                measures,
+                ..
+            }) => (partition_by, order_by, symbols, measures).apply_ref_elements(f),
             LogicalPlan::Extension(extension) => {
                 // would be nice to avoid this copy -- maybe can
                 // update extension to just observer Exprs
@@ -464,6 +507,7 @@ impl LogicalPlan {
             // plans without expressions
             LogicalPlan::EmptyRelation(_)
             | LogicalPlan::RecursiveQuery(_)
+            | LogicalPlan::Sample(_)
             | LogicalPlan::Subquery(_)
             | LogicalPlan::SubqueryAlias(_)
             | LogicalPlan::Analyze(_)
@@ -579,6 +623,31 @@ impl LogicalPlan {
             LogicalPlan::Sort(Sort { expr, input, fetch }) => expr
                 .map_elements(f)?
                 .update_data(|expr| LogicalPlan::Sort(Sort { expr, input, fetch })),
+            LogicalPlan::MatchRecognize(MatchRecognize {
+                input,
+                partition_by,
+                order_by,
+                symbols,
+                pattern,
+                measures,
+                rows_per_match,
+                after_match_skip,
+                schema,
+            }) => (partition_by, order_by, symbols, measures)
+                .map_elements(f)?
+                .update_data(|(partition_by, order_by, symbols, measures)| {
+                    LogicalPlan::MatchRecognize(MatchRecognize {
+                        input,
+                        partition_by,
+                        order_by,
+                        symbols,
+                        pattern,
+                        measures,
+                        rows_per_match,
+                        after_match_skip,
+                        schema,
+                    })
+                }),
             LogicalPlan::Extension(Extension { node }) => {
                 // would be nice to avoid this copy -- maybe can
                 // update extension to just observer Exprs
@@ -644,6 +713,7 @@ impl LogicalPlan {
             LogicalPlan::EmptyRelation(_)
             | LogicalPlan::Unnest(_)
             | LogicalPlan::RecursiveQuery(_)
+            | LogicalPlan::Sample(_)
             | LogicalPlan::Subquery(_)
             | LogicalPlan::SubqueryAlias(_)
             | LogicalPlan::Analyze(_)
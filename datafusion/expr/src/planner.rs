@@ -22,8 +22,8 @@ use std::sync::Arc;
 
 use crate::expr::NullTreatment;
 use crate::{
-    AggregateUDF, Expr, GetFieldAccess, ScalarUDF, SortExpr, TableSource, WindowFrame,
-    WindowFunctionDefinition, WindowUDF,
+    AggregateUDF, Expr, GetFieldAccess, LogicalPlan, ScalarUDF, SortExpr, TableSource,
+    WindowFrame, WindowFunctionDefinition, WindowUDF,
 };
 use arrow::datatypes::{DataType, Field, SchemaRef};
 use datafusion_common::{
@@ -78,6 +78,23 @@ pub trait ContextProvider {
         not_impl_err!("Recursive CTE is not implemented")
     }
 
+    /// Provides a table that computes the non-recursive CTE `name` once and
+    /// shares its results between all the references to it, instead of
+    /// inlining `plan` at each reference
+    ///
+    /// Returns `None` if CTEs can not be materialized, in which case they
+    /// are inlined. See [`Self::create_cte_work_table`] for why this is not
+    /// implemented in [`SqlToRel`] directly.
+    ///
+    /// [`SqlToRel`]: https://docs.rs/datafusion/latest/datafusion/sql/planner/struct.SqlToRel.html
+    fn create_materialized_cte(
+        &self,
+        _name: &str,
+        _plan: Arc<LogicalPlan>,
+    ) -> Result<Option<Arc<dyn TableSource>>> {
+        Ok(None)
+    }
+
     /// Return [`ExprPlanner`] extensions for planning expressions
     fn get_expr_planners(&self) -> &[Arc<dyn ExprPlanner>] {
         &[]
@@ -294,6 +294,11 @@ impl AggregateUDF {
         self.inner.reverse_expr()
     }
 
+    /// See [`AggregateUDFImpl::decomposition`] for more details.
+    pub fn decomposition(&self) -> AggregateDecomposition {
+        self.inner.decomposition()
+    }
+
     /// Do the function rewrite
     ///
     /// See [`AggregateUDFImpl::simplify`] for more details.
@@ -680,6 +685,18 @@ pub trait AggregateUDFImpl: Debug + DynEq + DynHash + Send + Sync {
         ReversedUDAF::NotSupported
     }
 
+    /// Returns whether the aggregate function can be computed in two phases
+    /// of aggregate functions, first on disjoint subsets of the input and
+    /// then on the results of the first phase. See [`AggregateDecomposition`]
+    /// for possible options.
+    ///
+    /// This is used by the `EagerAggregation` optimizer rule to push
+    /// aggregations below joins. The default implementation returns
+    /// [`AggregateDecomposition::NotSupported`].
+    fn decomposition(&self) -> AggregateDecomposition {
+        AggregateDecomposition::NotSupported
+    }
+
     /// Coerce arguments of a function call to types that the function can evaluate.
     ///
     /// This function is only called if [`AggregateUDFImpl::signature`] returns [`crate::TypeSignature::UserDefined`]. Most
@@ -1068,6 +1085,39 @@ pub enum ReversedUDAF {
     Reversed(Arc<AggregateUDF>),
 }
 
+/// Describes how an aggregate function can be computed in two phases. See
+/// [`AggregateUDFImpl::decomposition`].
+///
+/// For example, `sum(x)` over the whole input is the `sum` of the `sum(x)`
+/// of disjoint subsets of the input, and `count(x)` is the `sum` of the
+/// `count(x)` of the subsets.
+pub enum AggregateDecomposition {
+    /// The aggregate can not be computed in two phases, like `avg` or `median`
+    NotSupported,
+    /// The aggregate is computed by applying the given aggregate function to
+    /// the results of the first phase, like `sum` for `sum` and `count`
+    Decomposable(Arc<AggregateUDF>),
+    /// Same as [`Self::Decomposable`], and in addition the result does not
+    /// change when input rows are duplicated, like `min` and `max`
+    DuplicateInsensitive(Arc<AggregateUDF>),
+}
+
+impl AggregateDecomposition {
+    /// Returns the aggregate function of the second phase, if any
+    pub fn merge_udaf(&self) -> Option<&Arc<AggregateUDF>> {
+        match self {
+            Self::NotSupported => None,
+            Self::Decomposable(udaf) | Self::DuplicateInsensitive(udaf) => Some(udaf),
+        }
+    }
+
+    /// Returns whether the result does not change when input rows are
+    /// duplicated
+    pub fn is_duplicate_insensitive(&self) -> bool {
+        matches!(self, Self::DuplicateInsensitive(_))
+    }
+}
+
 /// AggregateUDF that adds an alias to the underlying function. It is better to
 /// implement [`AggregateUDFImpl`], which supports aliases, directly if possible.
 #[derive(Debug, PartialEq, Eq, Hash)]
@@ -1193,6 +1243,10 @@ impl AggregateUDFImpl for AliasedAggregateUDFImpl {
         self.inner.reverse_expr()
     }
 
+    fn decomposition(&self) -> AggregateDecomposition {
+        self.inner.decomposition()
+    }
+
     fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
         self.inner.coerce_types(arg_types)
     }
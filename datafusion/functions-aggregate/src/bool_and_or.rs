@@ -33,8 +33,8 @@ use datafusion_common::{Result, ScalarValue};
 use datafusion_expr::function::{AccumulatorArgs, StateFieldsArgs};
 use datafusion_expr::utils::{format_state_name, AggregateOrderSensitivity};
 use datafusion_expr::{
-    Accumulator, AggregateUDFImpl, Documentation, GroupsAccumulator, ReversedUDAF,
-    Signature, Volatility,
+    Accumulator, AggregateDecomposition, AggregateUDFImpl, Documentation,
+    GroupsAccumulator, ReversedUDAF, Signature, Volatility,
 };
 
 use datafusion_functions_aggregate_common::aggregate::groups_accumulator::bool_op::BooleanGroupsAccumulator;
@@ -187,6 +187,10 @@ impl AggregateUDFImpl for BoolAnd {
         ReversedUDAF::Identical
     }
 
+    fn decomposition(&self) -> AggregateDecomposition {
+        AggregateDecomposition::DuplicateInsensitive(bool_and_udaf())
+    }
+
     fn documentation(&self) -> Option<&Documentation> {
         self.doc()
     }
@@ -323,6 +327,10 @@ impl AggregateUDFImpl for BoolOr {
         ReversedUDAF::Identical
     }
 
+    fn decomposition(&self) -> AggregateDecomposition {
+        AggregateDecomposition::DuplicateInsensitive(bool_or_udaf())
+    }
+
     fn documentation(&self) -> Option<&Documentation> {
         self.doc()
     }
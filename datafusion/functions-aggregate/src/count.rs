@@ -37,9 +37,9 @@ use datafusion_expr::{
     expr::WindowFunction,
     function::{AccumulatorArgs, StateFieldsArgs},
     utils::format_state_name,
-    Accumulator, AggregateUDFImpl, Documentation, EmitTo, Expr, GroupsAccumulator,
-    ReversedUDAF, SetMonotonicity, Signature, StatisticsArgs, TypeSignature, Volatility,
-    WindowFunctionDefinition,
+    Accumulator, AggregateDecomposition, AggregateUDFImpl, Documentation, EmitTo, Expr,
+    GroupsAccumulator, ReversedUDAF, SetMonotonicity, Signature, StatisticsArgs,
+    TypeSignature, Volatility, WindowFunctionDefinition,
 };
 use datafusion_functions_aggregate_common::aggregate::{
     count_distinct::BytesDistinctCountAccumulator,
@@ -365,6 +365,11 @@ impl AggregateUDFImpl for Count {
         ReversedUDAF::Identical
     }
 
+    fn decomposition(&self) -> AggregateDecomposition {
+        // The count of the whole input is the sum of the partial counts
+        AggregateDecomposition::Decomposable(crate::sum::sum_udaf())
+    }
+
     fn default_value(&self, _data_type: &DataType) -> Result<ScalarValue> {
         Ok(ScalarValue::Int64(Some(0)))
     }
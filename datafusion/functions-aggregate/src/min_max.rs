@@ -46,8 +46,8 @@ use crate::min_max::min_max_bytes::MinMaxBytesAccumulator;
 use crate::min_max::min_max_struct::MinMaxStructAccumulator;
 use datafusion_common::ScalarValue;
 use datafusion_expr::{
-    function::AccumulatorArgs, Accumulator, AggregateUDFImpl, Documentation,
-    SetMonotonicity, Signature, Volatility,
+    function::AccumulatorArgs, Accumulator, AggregateDecomposition, AggregateUDFImpl,
+    Documentation, SetMonotonicity, Signature, Volatility,
 };
 use datafusion_expr::{GroupsAccumulator, StatisticsArgs};
 use datafusion_macros::user_doc;
@@ -368,6 +368,9 @@ impl AggregateUDFImpl for Max {
     fn reverse_expr(&self) -> datafusion_expr::ReversedUDAF {
         datafusion_expr::ReversedUDAF::Identical
     }
+    fn decomposition(&self) -> AggregateDecomposition {
+        AggregateDecomposition::DuplicateInsensitive(max_udaf())
+    }
     fn value_from_stats(&self, statistics_args: &StatisticsArgs) -> Option<ScalarValue> {
         self.value_from_statistics(statistics_args)
     }
@@ -659,6 +662,9 @@ impl AggregateUDFImpl for Min {
     fn reverse_expr(&self) -> datafusion_expr::ReversedUDAF {
         datafusion_expr::ReversedUDAF::Identical
     }
+    fn decomposition(&self) -> AggregateDecomposition {
+        AggregateDecomposition::DuplicateInsensitive(min_udaf())
+    }
 
     fn documentation(&self) -> Option<&Documentation> {
         self.doc()
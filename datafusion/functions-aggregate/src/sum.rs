@@ -41,8 +41,8 @@ use datafusion_expr::function::AccumulatorArgs;
 use datafusion_expr::function::StateFieldsArgs;
 use datafusion_expr::utils::format_state_name;
 use datafusion_expr::{
-    Accumulator, AggregateUDFImpl, Documentation, GroupsAccumulator, ReversedUDAF,
-    SetMonotonicity, Signature, Volatility,
+    Accumulator, AggregateDecomposition, AggregateUDFImpl, Documentation,
+    GroupsAccumulator, ReversedUDAF, SetMonotonicity, Signature, Volatility,
 };
 use datafusion_functions_aggregate_common::aggregate::groups_accumulator::prim_op::PrimitiveGroupsAccumulator;
 use datafusion_functions_aggregate_common::aggregate::sum_distinct::DistinctSumAccumulator;
@@ -297,6 +297,10 @@ impl AggregateUDFImpl for Sum {
         ReversedUDAF::Identical
     }
 
+    fn decomposition(&self) -> AggregateDecomposition {
+        AggregateDecomposition::Decomposable(sum_udaf())
+    }
+
     fn order_sensitivity(&self) -> AggregateOrderSensitivity {
         AggregateOrderSensitivity::Insensitive
     }
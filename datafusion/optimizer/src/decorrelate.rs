@@ -26,7 +26,9 @@ use crate::simplify_expressions::ExprSimplifier;
 use datafusion_common::tree_node::{
     Transformed, TransformedResult, TreeNode, TreeNodeRecursion, TreeNodeRewriter,
 };
-use datafusion_common::{plan_err, Column, DFSchemaRef, HashMap, Result, ScalarValue};
+use datafusion_common::{
+    plan_err, Column, DFSchema, DFSchemaRef, HashMap, HashSet, Result, ScalarValue,
+};
 use datafusion_expr::expr::Alias;
 use datafusion_expr::simplify::SimplifyContext;
 use datafusion_expr::utils::{
@@ -125,6 +127,72 @@ impl PullUpCorrelatedExpr {
 /// [the Count bug]: https://github.com/apache/datafusion/issues/10553
 pub const UN_MATCHED_ROW_INDICATOR: &str = "__always_true";
 
+/// Returns whether `subquery` has outer references that [`PullUpCorrelatedExpr`]
+/// can not pull up into a join with an outer query with `outer_schema`: the
+/// references to the columns of the queries enclosing the outer query, and
+/// the references of the nested subqueries of `subquery` to the outer query.
+pub fn has_nested_outer_references(
+    subquery: &LogicalPlan,
+    outer_schema: &DFSchema,
+) -> Result<bool> {
+    let mut inner_columns = HashSet::new();
+    subquery.apply_with_subqueries(|plan| {
+        inner_columns.extend(plan.schema().columns());
+        Ok(TreeNodeRecursion::Continue)
+    })?;
+
+    let mut found = false;
+    subquery.apply(|plan| {
+        plan.apply_expressions(|expr| {
+            expr.apply(|expr| {
+                found = match expr {
+                    Expr::OuterReferenceColumn(_, column) => {
+                        !outer_schema.has_column(column)
+                    }
+                    Expr::ScalarSubquery(nested)
+                    | Expr::Exists(expr::Exists {
+                        subquery: nested, ..
+                    })
+                    | Expr::InSubquery(expr::InSubquery {
+                        subquery: nested, ..
+                    }) => nested_references_outside(&nested.subquery, &inner_columns)?,
+                    _ => false,
+                };
+                Ok(if found {
+                    TreeNodeRecursion::Stop
+                } else {
+                    TreeNodeRecursion::Continue
+                })
+            })
+        })
+    })?;
+    Ok(found)
+}
+
+/// Returns whether `plan` references columns that are not in `inner_columns`
+fn nested_references_outside(
+    plan: &LogicalPlan,
+    inner_columns: &HashSet<Column>,
+) -> Result<bool> {
+    let mut found = false;
+    plan.apply_with_subqueries(|plan| {
+        plan.apply_expressions(|expr| {
+            found = expr.exists(|expr| {
+                Ok(matches!(
+                    expr,
+                    Expr::OuterReferenceColumn(_, column) if !inner_columns.contains(column)
+                ))
+            })?;
+            Ok(if found {
+                TreeNodeRecursion::Stop
+            } else {
+                TreeNodeRecursion::Continue
+            })
+        })
+    })?;
+    Ok(found)
+}
+
 /// Mapping from expr display name to its evaluation result on empty record
 /// batch (for example: 'count(*)' is 'ScalarValue(0)', 'count(*) + 2' is
 /// 'ScalarValue(2)')
@@ -0,0 +1,1486 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`DecorrelateDependentJoin`] decorrelates arbitrary subqueries and lateral
+//! joins by unnesting dependent joins
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use crate::decorrelate::UN_MATCHED_ROW_INDICATOR;
+use crate::decorrelate_lateral_join::DecorrelateLateralJoin;
+use crate::decorrelate_predicate_subquery::DecorrelatePredicateSubquery;
+use crate::optimizer::ApplyOrder;
+use crate::scalar_subquery_to_join::ScalarSubqueryToJoin;
+use crate::{OptimizerConfig, OptimizerRule};
+
+use datafusion_common::tree_node::{
+    Transformed, TransformedResult, TreeNode, TreeNodeRecursion,
+};
+use datafusion_common::{Column, Result, ScalarValue, TableReference};
+use datafusion_expr::expr::{
+    AggregateFunction, Exists, InSubquery, Sort as SortExpr, WindowFunction,
+};
+use datafusion_expr::expr_rewriter::NamePreserver;
+use datafusion_expr::logical_plan::{
+    Aggregate, Distinct, DistinctOn, FetchType, Filter, Join, JoinType, Limit,
+    Projection, SkipType, Sort, SubqueryAlias, Union, Window,
+};
+use datafusion_expr::utils::{conjunction, split_conjunction_owned};
+use datafusion_expr::{
+    binary_expr, lit, not, when, Expr, ExprFunctionExt, ExprSchemable, LogicalPlan,
+    LogicalPlanBuilder, Operator, Subquery,
+};
+
+/// Optimizer rule that rewrites the subqueries and lateral joins that the
+/// other decorrelation rules can not rewrite into joins, following
+/// "Unnesting Arbitrary Queries" by Neumann and Kemper.
+///
+/// A correlated subquery is a dependent join between its outer plan and the
+/// subquery, which is evaluated once for each outer row. This rule computes
+/// the *domain* of the dependent join, i.e. the distinct values of the outer
+/// columns referenced by the subquery, and pushes the join with the domain
+/// down the subquery, until it reaches plans that do not reference outer
+/// columns. Along the way, the outer references are replaced by the columns
+/// of the domain. The subquery is then evaluated once for all the values of
+/// the domain, and joined with the outer plan on the referenced columns.
+///
+/// For example, the subquery of
+///
+/// ```text
+/// SELECT a, (SELECT sum(t2.b) FROM t2 WHERE t2.a < t1.a) FROM t1
+/// ```
+///
+/// is rewritten to
+///
+/// ```text
+/// SELECT t1.a, s.sum FROM t1 LEFT JOIN (
+///   SELECT d.a, sum(t2.b) AS sum FROM (SELECT DISTINCT a FROM t1) d, t2
+///   WHERE t2.a < d.a GROUP BY d.a
+/// ) s ON t1.a IS NOT DISTINCT FROM s.a
+/// ```
+///
+/// Scalar subqueries are left joined with the outer plan. `EXISTS` and `IN`
+/// subqueries that are conjuncts of a filter are rewritten to semi and anti
+/// joins, and the others to mark joins.
+#[derive(Default, Debug)]
+pub struct DecorrelateDependentJoin {}
+
+impl DecorrelateDependentJoin {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl OptimizerRule for DecorrelateDependentJoin {
+    fn supports_rewrite(&self) -> bool {
+        true
+    }
+
+    fn rewrite(
+        &self,
+        plan: LogicalPlan,
+        config: &dyn OptimizerConfig,
+    ) -> Result<Transformed<LogicalPlan>> {
+        if !has_subquery(&plan) {
+            return Ok(Transformed::no(plan));
+        }
+        // The joins of the other decorrelation rules are cheaper than the
+        // dependent joins, so they are preferred for the subqueries they support
+        if let Some(rewritten) = rewrite_with_other_rules(&plan, config) {
+            return Ok(Transformed::yes(rewritten));
+        }
+
+        match plan {
+            LogicalPlan::Join(join) if lateral_subquery(&join.right).is_some() => {
+                rewrite_lateral_join(join, config)
+            }
+            LogicalPlan::Join(join)
+                if join.join_type == JoinType::Inner
+                    && join.filter.as_ref().is_some_and(contains_subquery) =>
+            {
+                // Evaluate the filter of the inner join above a join without it
+                let mut filtered_join = join.clone();
+                let predicate = filtered_join.filter.take().unwrap();
+                let filter = LogicalPlan::Filter(Filter::try_new(
+                    predicate,
+                    Arc::new(LogicalPlan::Join(filtered_join)),
+                )?);
+                let rewritten = rewrite_filter(filter, config)?;
+                if rewritten.transformed {
+                    Ok(rewritten)
+                } else {
+                    Ok(Transformed::no(LogicalPlan::Join(join)))
+                }
+            }
+            LogicalPlan::Filter(_) if contains_subquery(&plan.expressions()[0]) => {
+                rewrite_filter(plan, config)
+            }
+            LogicalPlan::Projection(_)
+            | LogicalPlan::Aggregate(_)
+            | LogicalPlan::Window(_)
+                if plan.expressions().iter().any(contains_subquery) =>
+            {
+                rewrite_subquery_exprs(plan, config)
+            }
+            _ => Ok(Transformed::no(plan)),
+        }
+    }
+
+    fn name(&self) -> &str {
+        "decorrelate_dependent_join"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        Some(ApplyOrder::BottomUp)
+    }
+}
+
+/// Returns `plan` rewritten by the first of the other decorrelation rules that
+/// rewrites it, if any
+fn rewrite_with_other_rules(
+    plan: &LogicalPlan,
+    config: &dyn OptimizerConfig,
+) -> Option<LogicalPlan> {
+    let rules: [Box<dyn OptimizerRule>; 3] = [
+        Box::new(DecorrelatePredicateSubquery::new()),
+        Box::new(ScalarSubqueryToJoin::new()),
+        Box::new(DecorrelateLateralJoin::new()),
+    ];
+    rules.into_iter().find_map(|rule| {
+        // A failure of the other rules only means that this rule has to
+        // rewrite the subqueries. Some of them report unsupported subqueries
+        // as transformed, so the plans are compared instead.
+        rule.rewrite(plan.clone(), config)
+            .ok()
+            .map(|rewritten| rewritten.data)
+            .filter(|rewritten| rewritten != plan)
+    })
+}
+
+/// Rewrites the `EXISTS` and `IN` subqueries that are conjuncts of the
+/// predicate of `filter` to semi and anti joins, and its other subquery
+/// expressions to joins with its input
+fn rewrite_filter(
+    filter: LogicalPlan,
+    config: &dyn OptimizerConfig,
+) -> Result<Transformed<LogicalPlan>> {
+    let LogicalPlan::Filter(Filter {
+        predicate, input, ..
+    }) = &filter
+    else {
+        return Ok(Transformed::no(filter));
+    };
+    let mut outer = input.as_ref().clone();
+    let mut transformed = false;
+    let mut other_conjuncts = vec![];
+    for conjunct in split_conjunction_owned(predicate.clone()) {
+        let rewritten = match &conjunct {
+            Expr::Exists(Exists { subquery, negated }) => {
+                let join_type = if *negated {
+                    JoinType::LeftAnti
+                } else {
+                    JoinType::LeftSemi
+                };
+                semi_join(&outer, input, subquery, None, join_type, config)?
+            }
+            // `NOT IN` is `NULL` if the subquery returns `NULL`, which anti
+            // joins do not handle
+            Expr::InSubquery(InSubquery {
+                expr,
+                subquery,
+                negated: false,
+            }) if !contains_subquery(expr) => semi_join(
+                &outer,
+                input,
+                subquery,
+                Some(expr),
+                JoinType::LeftSemi,
+                config,
+            )?,
+            _ => None,
+        };
+        match rewritten {
+            Some(plan) => {
+                outer = plan;
+                transformed = true;
+            }
+            None => other_conjuncts.push(conjunct),
+        }
+    }
+    if !transformed {
+        return rewrite_subquery_exprs(filter, config);
+    }
+
+    let Some(predicate) = conjunction(other_conjuncts) else {
+        return Ok(Transformed::yes(outer));
+    };
+    let filter = LogicalPlan::Filter(Filter::try_new(predicate, Arc::new(outer))?);
+    let rewritten = rewrite_subquery_exprs(filter, config)?;
+    Ok(Transformed::yes(rewritten.data))
+}
+
+/// Rewrites the subquery expressions of `plan`, which has a single input, to
+/// joins of its input with the subqueries
+fn rewrite_subquery_exprs(
+    plan: LogicalPlan,
+    config: &dyn OptimizerConfig,
+) -> Result<Transformed<LogicalPlan>> {
+    // The subqueries only reference the columns of the input, so their
+    // domains are computed from it rather than from the previous joins
+    let input = plan.inputs()[0].clone();
+    let mut outer = input.clone();
+    let name_preserver = NamePreserver::new(&plan);
+    let mut new_exprs = vec![];
+    for expr in plan.expressions() {
+        let saved_name = name_preserver.save(&expr);
+        let mut unsupported = false;
+        let new_expr = expr
+            .transform_down(|expr| {
+                let rewritten = match &expr {
+                    Expr::ScalarSubquery(subquery) => {
+                        scalar_subquery_join(&outer, &input, subquery, config)?
+                    }
+                    Expr::Exists(Exists { subquery, negated }) => {
+                        exists_join(&outer, &input, subquery, *negated, config)?
+                    }
+                    Expr::InSubquery(in_subquery) => {
+                        in_subquery_join(&outer, &input, in_subquery, config)?
+                    }
+                    _ => return Ok(Transformed::no(expr)),
+                };
+                match rewritten {
+                    Some((new_outer, value)) => {
+                        outer = new_outer;
+                        Ok(Transformed::new(value, true, TreeNodeRecursion::Jump))
+                    }
+                    None => {
+                        unsupported = true;
+                        Ok(Transformed::new(expr, false, TreeNodeRecursion::Stop))
+                    }
+                }
+            })
+            .data()?;
+        if unsupported {
+            return Ok(Transformed::no(plan));
+        }
+        new_exprs.push(saved_name.restore(new_expr));
+    }
+
+    let new_plan = plan.with_new_exprs(new_exprs, vec![outer])?;
+    // Filters and windows also output the columns of their input, which now
+    // include the columns of the subqueries
+    if new_plan.schema().fields().len() == plan.schema().fields().len() {
+        Ok(Transformed::yes(new_plan))
+    } else {
+        let columns = plan.schema().columns().into_iter().map(Expr::Column);
+        LogicalPlanBuilder::from(new_plan)
+            .project(columns)?
+            .build()
+            .map(Transformed::yes)
+    }
+}
+
+/// Left joins `outer`, the plan `input` joined with the previous subqueries,
+/// with the values of the scalar subquery for each of its rows, and returns
+/// the join and the expression of the value
+fn scalar_subquery_join(
+    outer: &LogicalPlan,
+    input: &LogicalPlan,
+    subquery: &Subquery,
+    config: &dyn OptimizerConfig,
+) -> Result<Option<(LogicalPlan, Expr)>> {
+    let alias = config.alias_generator().next("__scalar_sq");
+    let Some((right, join_filter)) =
+        decorrelate_subquery(input, &subquery.subquery, &alias, config)?
+    else {
+        return Ok(None);
+    };
+    let value = Expr::Column(Column::from(right.schema().qualified_field(0)));
+    let plan = LogicalPlanBuilder::from(outer.clone())
+        .join_on(right, JoinType::Left, [join_filter])?
+        .build()?;
+    Ok(Some((plan, value)))
+}
+
+/// Semi or anti joins `outer`, the plan `input` joined with the previous
+/// subqueries, with the `EXISTS` subquery, or with the `IN` subquery of
+/// `expr` if any
+fn semi_join(
+    outer: &LogicalPlan,
+    input: &LogicalPlan,
+    subquery: &Subquery,
+    expr: Option<&Expr>,
+    join_type: JoinType,
+    config: &dyn OptimizerConfig,
+) -> Result<Option<LogicalPlan>> {
+    let alias = config.alias_generator().next("__correlated_sq");
+    let Some((right, join_filter)) =
+        decorrelate_subquery(input, &subquery.subquery, &alias, config)?
+    else {
+        return Ok(None);
+    };
+    let mut conditions = vec![join_filter];
+    if let Some(expr) = expr {
+        let (qualifier, field) = right.schema().qualified_field(0);
+        conditions.push(binary_expr(
+            expr.clone(),
+            Operator::Eq,
+            Expr::Column(Column::from((qualifier, field))),
+        ));
+    }
+    LogicalPlanBuilder::from(outer.clone())
+        .join_on(right, join_type, conditions)?
+        .build()
+        .map(Some)
+}
+
+/// Mark joins `outer`, the plan `input` joined with the previous subqueries,
+/// with the `EXISTS` subquery, and returns the join and the expression of
+/// the value of the subquery
+fn exists_join(
+    outer: &LogicalPlan,
+    input: &LogicalPlan,
+    subquery: &Subquery,
+    negated: bool,
+    config: &dyn OptimizerConfig,
+) -> Result<Option<(LogicalPlan, Expr)>> {
+    let alias = config.alias_generator().next("__correlated_sq");
+    let Some((right, join_filter)) =
+        decorrelate_subquery(input, &subquery.subquery, &alias, config)?
+    else {
+        return Ok(None);
+    };
+    let plan = LogicalPlanBuilder::from(outer.clone())
+        .join_on(right, JoinType::LeftMark, [join_filter])?
+        .build()?;
+    let mark = Expr::Column(Column::new(Some(alias), "mark"));
+    let value = if negated { not(mark) } else { mark };
+    Ok(Some((plan, value)))
+}
+
+/// Mark joins `outer`, the plan `input` joined with the previous subqueries,
+/// with the `IN` subquery, and returns the join and the expression of the
+/// value of the subquery.
+///
+/// The value is `true` if the subquery returns the value of the expression,
+/// `NULL` if it does not but comparing the expression with one of the
+/// values of the subquery is `NULL`, and `false` otherwise.
+fn in_subquery_join(
+    outer: &LogicalPlan,
+    input: &LogicalPlan,
+    in_subquery: &InSubquery,
+    config: &dyn OptimizerConfig,
+) -> Result<Option<(LogicalPlan, Expr)>> {
+    let InSubquery {
+        expr,
+        subquery,
+        negated,
+    } = in_subquery;
+    if contains_subquery(expr) {
+        return Ok(None);
+    }
+
+    let alias = config.alias_generator().next("__correlated_sq");
+    let Some((right, join_filter)) =
+        decorrelate_subquery(input, &subquery.subquery, &alias, config)?
+    else {
+        return Ok(None);
+    };
+    let (qualifier, field) = right.schema().qualified_field(0);
+    let nullable = field.is_nullable() || expr.nullable(outer.schema())?;
+    let equal = binary_expr(
+        expr.as_ref().clone(),
+        Operator::Eq,
+        Expr::Column(Column::from((qualifier, field))),
+    );
+    let mut plan = LogicalPlanBuilder::from(outer.clone()).join_on(
+        right,
+        JoinType::LeftMark,
+        [join_filter, equal],
+    )?;
+    let mut value = when(Expr::Column(Column::new(Some(alias), "mark")), lit(true));
+
+    if nullable {
+        let null_alias = config.alias_generator().next("__correlated_sq");
+        let Some((right, join_filter)) =
+            decorrelate_subquery(input, &subquery.subquery, &null_alias, config)?
+        else {
+            return Ok(None);
+        };
+        let (qualifier, field) = right.schema().qualified_field(0);
+        let unknown = binary_expr(
+            expr.as_ref().clone(),
+            Operator::Eq,
+            Expr::Column(Column::from((qualifier, field))),
+        )
+        .is_null();
+        plan = plan.join_on(right, JoinType::LeftMark, [join_filter, unknown])?;
+        value = value.when(
+            Expr::Column(Column::new(Some(null_alias), "mark")),
+            lit(ScalarValue::Boolean(None)),
+        );
+    }
+
+    let value = value.otherwise(lit(false))?;
+    let value = if *negated { not(value) } else { value };
+    Ok(Some((plan.build()?, value)))
+}
+
+/// Rewrites `subquery` to a plan with its first column and the columns of
+/// the domain of its outer references in `outer`, aliased as `alias`.
+///
+/// Returns the rewritten subquery and the condition to join it with `outer`.
+fn decorrelate_subquery(
+    outer: &LogicalPlan,
+    subquery: &LogicalPlan,
+    alias: &str,
+    config: &dyn OptimizerConfig,
+) -> Result<Option<(LogicalPlan, Expr)>> {
+    let dependent_join = DependentJoin::try_new(outer, subquery, config)?;
+    let Some(plan) = dependent_join.unnest(subquery)? else {
+        return Ok(None);
+    };
+    let columns = plan.schema().columns();
+    let domain_len = dependent_join.columns.len();
+    let plan = if columns.len() == domain_len + 1 {
+        LogicalPlanBuilder::from(plan)
+    } else {
+        let exprs = columns
+            .iter()
+            .take(1)
+            .chain(&columns[columns.len() - domain_len..])
+            .cloned()
+            .map(Expr::Column);
+        LogicalPlanBuilder::from(plan).project(exprs)?
+    };
+    let plan = plan.alias(alias)?.build()?;
+    let join_filter =
+        conjunction(dependent_join.join_conditions(alias)).unwrap_or_else(|| lit(true));
+    Ok(Some((plan, join_filter)))
+}
+
+/// Rewrites a lateral join, whose right side is a subquery that may reference
+/// the columns of its left side, to a join
+fn rewrite_lateral_join(
+    join: Join,
+    config: &dyn OptimizerConfig,
+) -> Result<Transformed<LogicalPlan>> {
+    let Some(subquery) = lateral_subquery(&join.right) else {
+        return Ok(Transformed::no(LogicalPlan::Join(join)));
+    };
+    if !matches!(join.join_type, JoinType::Inner | JoinType::Left) {
+        return Ok(Transformed::no(LogicalPlan::Join(join)));
+    }
+    let dependent_join = DependentJoin::try_new(&join.left, &subquery, config)?;
+    let Some(right) = dependent_join.unnest(&subquery)? else {
+        return Ok(Transformed::no(LogicalPlan::Join(join)));
+    };
+    let domain_alias = dependent_join.alias.clone();
+    let filter = conjunction(
+        join.filter
+            .clone()
+            .into_iter()
+            .chain(dependent_join.join_conditions(&domain_alias)),
+    );
+    let new_join = Join::try_new(
+        Arc::clone(&join.left),
+        Arc::new(right),
+        join.on.clone(),
+        filter,
+        join.join_type,
+        join.join_constraint,
+        join.null_equality,
+    )?;
+    let columns = join.schema.columns().into_iter().map(Expr::Column);
+    LogicalPlanBuilder::from(LogicalPlan::Join(new_join))
+        .project(columns)?
+        .build()
+        .map(Transformed::yes)
+}
+
+/// Returns the plan of the lateral subquery `plan`, with its alias if any
+fn lateral_subquery(plan: &LogicalPlan) -> Option<LogicalPlan> {
+    match plan {
+        LogicalPlan::Subquery(subquery) => Some(subquery.subquery.as_ref().clone()),
+        LogicalPlan::SubqueryAlias(SubqueryAlias { input, alias, .. }) => {
+            let LogicalPlan::Subquery(subquery) = input.as_ref() else {
+                return None;
+            };
+            SubqueryAlias::try_new(Arc::clone(&subquery.subquery), alias.clone())
+                .ok()
+                .map(LogicalPlan::SubqueryAlias)
+        }
+        _ => None,
+    }
+}
+
+/// The dependent join of an outer plan with a subquery that references some
+/// of its columns
+struct DependentJoin<'a> {
+    config: &'a dyn OptimizerConfig,
+    /// The alias of the domain
+    alias: String,
+    /// The outer columns referenced by the subquery, with the columns of the
+    /// domain that replace them
+    columns: Vec<(Column, Column)>,
+    /// The distinct values of the referenced outer columns, unused if there
+    /// are none
+    domain: LogicalPlan,
+}
+
+impl<'a> DependentJoin<'a> {
+    fn try_new(
+        outer: &LogicalPlan,
+        subquery: &LogicalPlan,
+        config: &'a dyn OptimizerConfig,
+    ) -> Result<Self> {
+        // References to the columns of plans above `outer` are left as is
+        let mut outer_columns = BTreeSet::new();
+        subquery.apply_with_subqueries(|plan| {
+            plan.apply_expressions(|expr| {
+                expr.apply(|expr| {
+                    if let Expr::OuterReferenceColumn(_, column) = expr {
+                        if outer.schema().has_column(column) {
+                            outer_columns.insert(column.clone());
+                        }
+                    }
+                    Ok(TreeNodeRecursion::Continue)
+                })
+            })
+        })?;
+
+        let alias = config.alias_generator().next("__domain");
+        let columns = outer_columns
+            .into_iter()
+            .map(|column| {
+                let domain_column = Column::new(Some(alias.clone()), column.flat_name());
+                (column, domain_column)
+            })
+            .collect::<Vec<_>>();
+        // The distinct values are computed by an aggregation, as this rule
+        // runs after `ReplaceDistinctWithAggregate`
+        let domain_columns = columns
+            .iter()
+            .map(|(_, domain_column)| Expr::Column(domain_column.clone()))
+            .collect::<Vec<_>>();
+        let domain = if columns.is_empty() {
+            outer.clone()
+        } else {
+            LogicalPlanBuilder::from(outer.clone())
+                .project(columns.iter().map(|(column, domain_column)| {
+                    Expr::Column(column.clone()).alias_qualified(
+                        domain_column.relation.clone(),
+                        &domain_column.name,
+                    )
+                }))?
+                .aggregate(domain_columns, Vec::<Expr>::new())?
+                .build()?
+        };
+        Ok(Self {
+            config,
+            alias,
+            columns,
+            domain,
+        })
+    }
+
+    /// The conditions to join the outer plan with the unnested subquery,
+    /// whose domain columns are qualified with `alias`
+    fn join_conditions(&self, alias: &str) -> Vec<Expr> {
+        self.columns
+            .iter()
+            .map(|(column, domain_column)| {
+                is_not_distinct_from(
+                    Expr::Column(column.clone()),
+                    Expr::Column(Column::new(Some(alias), &domain_column.name)),
+                )
+            })
+            .collect()
+    }
+
+    /// Rewrites `subquery` to a plan that computes its results for all the
+    /// values of the domain, with the columns of the domain after its own
+    /// columns. Returns `None` if the subquery can not be unnested.
+    fn unnest(&self, subquery: &LogicalPlan) -> Result<Option<LogicalPlan>> {
+        if self.columns.is_empty() {
+            Ok(Some(subquery.clone()))
+        } else {
+            self.push_down(subquery)
+        }
+    }
+
+    fn domain_exprs(&self) -> impl Iterator<Item = Expr> + '_ {
+        self.columns
+            .iter()
+            .map(|(_, domain_column)| Expr::Column(domain_column.clone()))
+    }
+
+    fn is_domain_column(&self, qualifier: Option<&TableReference>) -> bool {
+        qualifier.is_some_and(|qualifier| qualifier.table() == self.alias)
+    }
+
+    /// Returns whether `plan` references the outer columns of the domain
+    fn is_correlated(&self, plan: &LogicalPlan) -> Result<bool> {
+        let mut correlated = false;
+        plan.apply_with_subqueries(|plan| {
+            plan.apply_expressions(|expr| {
+                correlated = expr.exists(|expr| {
+                    Ok(matches!(
+                        expr,
+                        Expr::OuterReferenceColumn(_, column)
+                            if self.columns.iter().any(|(c, _)| c == column)
+                    ))
+                })?;
+                Ok(if correlated {
+                    TreeNodeRecursion::Stop
+                } else {
+                    TreeNodeRecursion::Continue
+                })
+            })
+        })?;
+        Ok(correlated)
+    }
+
+    /// Replaces the outer references of `expr` with the columns of the domain
+    fn replace(&self, expr: Expr) -> Result<Expr> {
+        expr.transform(|expr| {
+            if let Expr::OuterReferenceColumn(_, column) = &expr {
+                if let Some((_, domain_column)) =
+                    self.columns.iter().find(|(c, _)| c == column)
+                {
+                    return Ok(Transformed::yes(Expr::Column(domain_column.clone())));
+                }
+            }
+            Ok(Transformed::no(expr))
+        })
+        .data()
+    }
+
+    /// Replaces the outer references of `exprs`, preserving their names
+    fn replace_all(&self, exprs: &[Expr]) -> Result<Vec<Expr>> {
+        let name_preserver = NamePreserver::new_for_projection();
+        exprs
+            .iter()
+            .map(|expr| {
+                let saved_name = name_preserver.save(expr);
+                Ok(saved_name.restore(self.replace(expr.clone())?))
+            })
+            .collect()
+    }
+
+    fn replace_sort(&self, sort_exprs: &[SortExpr]) -> Result<Vec<SortExpr>> {
+        sort_exprs
+            .iter()
+            .map(|sort| Ok(sort.with_expr(self.replace(sort.expr.clone())?)))
+            .collect()
+    }
+
+    /// Pushes the join with the domain down `plan`
+    fn push_down(&self, plan: &LogicalPlan) -> Result<Option<LogicalPlan>> {
+        if !self.is_correlated(plan)? {
+            return LogicalPlanBuilder::from(plan.clone())
+                .cross_join(self.domain.clone())?
+                .build()
+                .map(Some);
+        }
+        // Nested subqueries are unnested before their outer plan
+        if plan.expressions().iter().any(contains_subquery) {
+            return Ok(None);
+        }
+
+        let new_plan = match plan {
+            LogicalPlan::Projection(projection) => {
+                let Some(input) = self.push_down(&projection.input)? else {
+                    return Ok(None);
+                };
+                let mut exprs = self.replace_all(&projection.expr)?;
+                exprs.extend(self.domain_exprs());
+                LogicalPlan::Projection(Projection::try_new(exprs, Arc::new(input))?)
+            }
+            LogicalPlan::Filter(filter) => {
+                let Some(input) = self.push_down(&filter.input)? else {
+                    return Ok(None);
+                };
+                LogicalPlan::Filter(Filter::try_new(
+                    self.replace(filter.predicate.clone())?,
+                    Arc::new(input),
+                )?)
+            }
+            LogicalPlan::Aggregate(aggregate) => {
+                let Some(new_plan) = self.push_down_aggregate(aggregate)? else {
+                    return Ok(None);
+                };
+                new_plan
+            }
+            LogicalPlan::Window(window) => {
+                let Some(new_plan) = self.push_down_window(window)? else {
+                    return Ok(None);
+                };
+                new_plan
+            }
+            LogicalPlan::Sort(sort) => match sort.fetch {
+                Some(fetch) => {
+                    let Some(new_plan) =
+                        self.push_down_limit(&sort.input, &sort.expr, 0, Some(fetch))?
+                    else {
+                        return Ok(None);
+                    };
+                    new_plan
+                }
+                None => {
+                    let Some(input) = self.push_down(&sort.input)? else {
+                        return Ok(None);
+                    };
+                    LogicalPlan::Sort(Sort {
+                        expr: self.replace_sort(&sort.expr)?,
+                        input: Arc::new(input),
+                        fetch: None,
+                    })
+                }
+            },
+            LogicalPlan::Limit(limit) => {
+                let Some(new_plan) = self.push_down_limit_plan(limit)? else {
+                    return Ok(None);
+                };
+                new_plan
+            }
+            LogicalPlan::Distinct(Distinct::All(input)) => {
+                let Some(input) = self.push_down(input)? else {
+                    return Ok(None);
+                };
+                LogicalPlan::Distinct(Distinct::All(Arc::new(input)))
+            }
+            LogicalPlan::Distinct(Distinct::On(distinct_on)) => {
+                let Some(input) = self.push_down(&distinct_on.input)? else {
+                    return Ok(None);
+                };
+                let on_expr = self
+                    .domain_exprs()
+                    .chain(self.replace_all(&distinct_on.on_expr)?)
+                    .collect();
+                let mut select_expr = self.replace_all(&distinct_on.select_expr)?;
+                select_expr.extend(self.domain_exprs());
+                let sort_expr = match &distinct_on.sort_expr {
+                    Some(sort_expr) => Some(
+                        self.domain_exprs()
+                            .map(|expr| expr.sort(true, false))
+                            .chain(self.replace_sort(sort_expr)?)
+                            .collect(),
+                    ),
+                    None => None,
+                };
+                LogicalPlan::Distinct(Distinct::On(DistinctOn::try_new(
+                    on_expr,
+                    select_expr,
+                    sort_expr,
+                    Arc::new(input),
+                )?))
+            }
+            LogicalPlan::Join(join) => {
+                let Some(new_plan) = self.push_down_join(join)? else {
+                    return Ok(None);
+                };
+                new_plan
+            }
+            LogicalPlan::Union(union) => {
+                let mut inputs = Vec::with_capacity(union.inputs.len());
+                for input in &union.inputs {
+                    let Some(input) = self.push_down(input)? else {
+                        return Ok(None);
+                    };
+                    inputs.push(Arc::new(input));
+                }
+                // The union takes the qualifiers of its first input
+                let union = LogicalPlan::Union(Union::try_new_with_loose_types(inputs)?);
+                self.restore_domain_qualifier(union)?
+            }
+            LogicalPlan::SubqueryAlias(subquery_alias) => {
+                let Some(input) = self.push_down(&subquery_alias.input)? else {
+                    return Ok(None);
+                };
+                let aliased = LogicalPlan::SubqueryAlias(SubqueryAlias::try_new(
+                    Arc::new(input),
+                    subquery_alias.alias.clone(),
+                )?);
+                self.restore_domain_qualifier(aliased)?
+            }
+            LogicalPlan::Subquery(subquery) => {
+                let Some(input) = self.push_down(&subquery.subquery)? else {
+                    return Ok(None);
+                };
+                input
+            }
+            LogicalPlan::Unnest(unnest) => {
+                let Some(input) = self.push_down(&unnest.input)? else {
+                    return Ok(None);
+                };
+                LogicalPlanBuilder::from(input)
+                    .unnest_columns_with_options(
+                        unnest.exec_columns.clone(),
+                        unnest.options.clone(),
+                    )?
+                    .build()?
+            }
+            _ => return Ok(None),
+        };
+        self.move_domain_to_end(new_plan).map(Some)
+    }
+
+    fn push_down_aggregate(&self, aggregate: &Aggregate) -> Result<Option<LogicalPlan>> {
+        if aggregate
+            .group_expr
+            .iter()
+            .any(|expr| matches!(expr, Expr::GroupingSet(_)))
+        {
+            return Ok(None);
+        }
+        let Some(input) = self.push_down(&aggregate.input)? else {
+            return Ok(None);
+        };
+        let mut group_expr = self.replace_all(&aggregate.group_expr)?;
+        let is_scalar = group_expr.is_empty();
+        group_expr.extend(self.domain_exprs());
+        let aggr_expr = self.replace_all(&aggregate.aggr_expr)?;
+        let new_plan = LogicalPlan::Aggregate(Aggregate::try_new(
+            Arc::new(input),
+            group_expr,
+            aggr_expr,
+        )?);
+        if is_scalar {
+            self.join_scalar_aggregate(new_plan, &aggregate.aggr_expr)
+                .map(Some)
+        } else {
+            Ok(Some(new_plan))
+        }
+    }
+
+    /// A scalar aggregation returns a row even if its input is empty, so
+    /// left joins the domain with the aggregation grouped by the domain
+    /// columns, and replaces the results for the values of the domain without
+    /// input rows with the results of the aggregate functions on empty input
+    /// (i.e. the "count bug")
+    fn join_scalar_aggregate(
+        &self,
+        aggregate: LogicalPlan,
+        aggr_expr: &[Expr],
+    ) -> Result<LogicalPlan> {
+        // The aggregation outputs the domain columns before the aggregates
+        let aggregate_columns = aggregate
+            .schema()
+            .iter()
+            .skip(self.columns.len())
+            .zip(aggr_expr)
+            .map(|((qualifier, field), expr)| {
+                let default = match expr.clone().unalias() {
+                    Expr::AggregateFunction(AggregateFunction { func, .. }) => {
+                        func.default_value(field.data_type())?
+                    }
+                    _ => ScalarValue::Null,
+                };
+                Ok((Column::from((qualifier, field)), default))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let needs_indicator = aggregate_columns
+            .iter()
+            .any(|(_, default)| !default.is_null());
+
+        // Rename the domain columns of the aggregation to join it with the
+        // domain
+        let aggregate_alias = self.config.alias_generator().next("__domain");
+        let mut exprs = aggregate_columns
+            .iter()
+            .map(|(column, _)| Expr::Column(column.clone()))
+            .collect::<Vec<_>>();
+        if needs_indicator {
+            exprs.push(lit(true).alias(UN_MATCHED_ROW_INDICATOR));
+        }
+        exprs.extend(self.columns.iter().map(|(_, domain_column)| {
+            Expr::Column(domain_column.clone())
+                .alias_qualified(Some(aggregate_alias.as_str()), &domain_column.name)
+        }));
+        let aggregate = LogicalPlanBuilder::from(aggregate)
+            .project(exprs)?
+            .build()?;
+
+        let mut exprs = aggregate_columns
+            .into_iter()
+            .map(|(column, default)| {
+                if default.is_null() {
+                    return Ok(Expr::Column(column));
+                }
+                let (qualifier, name) = (column.relation.clone(), column.name.clone());
+                Ok(when(
+                    Expr::Column(Column::from_name(UN_MATCHED_ROW_INDICATOR)).is_null(),
+                    lit(default),
+                )
+                .otherwise(Expr::Column(column))?
+                .alias_qualified(qualifier, name))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        exprs.extend(self.domain_exprs());
+        let join_conditions = self.columns.iter().map(|(_, domain_column)| {
+            is_not_distinct_from(
+                Expr::Column(domain_column.clone()),
+                Expr::Column(Column::new(
+                    Some(aggregate_alias.as_str()),
+                    &domain_column.name,
+                )),
+            )
+        });
+        LogicalPlanBuilder::from(self.domain.clone())
+            .join_on(aggregate, JoinType::Left, join_conditions)?
+            .project(exprs)?
+            .build()
+    }
+
+    fn push_down_window(&self, window: &Window) -> Result<Option<LogicalPlan>> {
+        let Some(input) = self.push_down(&window.input)? else {
+            return Ok(None);
+        };
+        // Each value of the domain is a partition of the window functions
+        let name_preserver = NamePreserver::new_for_projection();
+        let window_expr = window
+            .window_expr
+            .iter()
+            .map(|expr| {
+                let saved_name = name_preserver.save(expr);
+                let new_expr = self.replace(expr.clone())?;
+                new_expr
+                    .transform(|expr| match expr {
+                        Expr::WindowFunction(mut window_function) => {
+                            window_function
+                                .params
+                                .partition_by
+                                .extend(self.domain_exprs());
+                            Ok(Transformed::yes(Expr::WindowFunction(window_function)))
+                        }
+                        _ => Ok(Transformed::no(expr)),
+                    })
+                    .map(|new_expr| saved_name.restore(new_expr.data))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Some(LogicalPlan::Window(Window::try_new(
+            window_expr,
+            Arc::new(input),
+        )?)))
+    }
+
+    fn push_down_limit_plan(&self, limit: &Limit) -> Result<Option<LogicalPlan>> {
+        let (SkipType::Literal(skip), FetchType::Literal(fetch)) =
+            (limit.get_skip_type()?, limit.get_fetch_type()?)
+        else {
+            return Ok(None);
+        };
+        // The order of the input only matters to select the rows of the limit
+        match limit.input.as_ref() {
+            LogicalPlan::Sort(sort) if sort.fetch.is_none() => {
+                self.push_down_limit(&sort.input, &sort.expr, skip, fetch)
+            }
+            LogicalPlan::Sort(sort) => {
+                self.push_down_limit(&limit.input, &sort.expr, skip, fetch)
+            }
+            input => self.push_down_limit(input, &[], skip, fetch),
+        }
+    }
+
+    /// Limits the rows of `input` for each value of the domain, by numbering
+    /// them in the order of `sort_expr`
+    fn push_down_limit(
+        &self,
+        input: &LogicalPlan,
+        sort_expr: &[SortExpr],
+        skip: usize,
+        fetch: Option<usize>,
+    ) -> Result<Option<LogicalPlan>> {
+        let Some(row_number) = self
+            .config
+            .function_registry()
+            .and_then(|registry| registry.udwf("row_number").ok())
+        else {
+            return Ok(None);
+        };
+        let Some(input) = self.push_down(input)? else {
+            return Ok(None);
+        };
+        let columns = input.schema().columns().into_iter().map(Expr::Column);
+
+        let row_number_alias = self.config.alias_generator().next("__row_number");
+        let row_number_expr = Expr::from(WindowFunction::new(row_number, vec![]))
+            .partition_by(self.domain_exprs().collect())
+            .order_by(self.replace_sort(sort_expr)?)
+            .build()?
+            .alias(&row_number_alias);
+        let row_number = Expr::Column(Column::from_name(row_number_alias));
+        let predicate = conjunction(
+            (skip > 0)
+                .then(|| row_number.clone().gt(lit(skip as u64)))
+                .into_iter()
+                .chain(fetch.map(|fetch| {
+                    row_number
+                        .clone()
+                        .lt_eq(lit(skip.saturating_add(fetch) as u64))
+                })),
+        );
+
+        let mut builder =
+            LogicalPlanBuilder::from(input).window(vec![row_number_expr])?;
+        if let Some(predicate) = predicate {
+            builder = builder.filter(predicate)?;
+        }
+        builder.project(columns)?.build().map(Some)
+    }
+
+    fn push_down_join(&self, join: &Join) -> Result<Option<LogicalPlan>> {
+        let left_correlated = self.is_correlated(&join.left)?;
+        let right_correlated = self.is_correlated(&join.right)?;
+        // The domain must be joined with the preserved sides of outer joins,
+        // and with the sides that reference it
+        let (push_left, push_right) = match join.join_type {
+            JoinType::Inner => (left_correlated || !right_correlated, right_correlated),
+            JoinType::Left
+            | JoinType::LeftSemi
+            | JoinType::LeftAnti
+            | JoinType::LeftMark => (true, right_correlated),
+            JoinType::Right
+            | JoinType::RightSemi
+            | JoinType::RightAnti
+            | JoinType::RightMark => (left_correlated, true),
+            JoinType::Full => (true, true),
+            JoinType::AsOf => return Ok(None),
+        };
+
+        let push_side = |side: &LogicalPlan, push: bool| {
+            if push {
+                self.push_down(side)
+            } else {
+                Ok(Some(side.clone()))
+            }
+        };
+        let (Some(left), Some(mut right)) = (
+            push_side(&join.left, push_left)?,
+            push_side(&join.right, push_right)?,
+        ) else {
+            return Ok(None);
+        };
+        let on = join
+            .on
+            .iter()
+            .map(|(left, right)| {
+                Ok((self.replace(left.clone())?, self.replace(right.clone())?))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut filter = join
+            .filter
+            .clone()
+            .map(|filter| self.replace(filter))
+            .transpose()?;
+
+        // Both sides have the domain columns, which must be equal
+        let right_alias = if push_left && push_right {
+            let right_alias = self.config.alias_generator().next("__domain");
+            right = self.rename_domain(right, &right_alias)?;
+            filter = conjunction(filter.into_iter().chain(self.columns.iter().map(
+                |(_, domain_column)| {
+                    is_not_distinct_from(
+                        Expr::Column(domain_column.clone()),
+                        Expr::Column(Column::new(
+                            Some(right_alias.as_str()),
+                            &domain_column.name,
+                        )),
+                    )
+                },
+            )));
+            Some(right_alias)
+        } else {
+            None
+        };
+
+        let new_plan = LogicalPlan::Join(Join::try_new(
+            Arc::new(left),
+            Arc::new(right),
+            on,
+            filter,
+            join.join_type,
+            join.join_constraint,
+            join.null_equality,
+        )?);
+        let is_mark_join =
+            matches!(join.join_type, JoinType::LeftMark | JoinType::RightMark);
+        if right_alias.is_none() && !is_mark_join {
+            return Ok(Some(new_plan));
+        }
+
+        // The other columns are the columns of the original join. The mark
+        // column of a mark join takes the qualifier of the other side, which
+        // the domain columns may have changed.
+        let is_domain_column = |qualifier: Option<&TableReference>| {
+            self.is_domain_column(qualifier)
+                || qualifier.is_some_and(|q| Some(q.table()) == right_alias.as_deref())
+        };
+        let mut exprs = new_plan
+            .schema()
+            .iter()
+            .filter(|(qualifier, _)| !is_domain_column(*qualifier))
+            .zip(join.schema.iter())
+            .map(
+                |((qualifier, field), (original_qualifier, original_field))| {
+                    let expr = Expr::Column(Column::from((qualifier, field)));
+                    if qualifier == original_qualifier {
+                        expr
+                    } else {
+                        expr.alias_qualified(
+                            original_qualifier.cloned(),
+                            original_field.name(),
+                        )
+                    }
+                },
+            )
+            .collect::<Vec<_>>();
+
+        // Keep a single copy of the domain columns, from the preserved side
+        for (_, domain_column) in &self.columns {
+            let left = Expr::Column(domain_column.clone());
+            let Some(right_alias) = &right_alias else {
+                exprs.push(left);
+                continue;
+            };
+            let right = Expr::Column(Column::new(
+                Some(right_alias.as_str()),
+                &domain_column.name,
+            ));
+            let expr = match join.join_type {
+                JoinType::Full => {
+                    when(left.clone().is_not_null(), left).otherwise(right)?
+                }
+                JoinType::Right
+                | JoinType::RightSemi
+                | JoinType::RightAnti
+                | JoinType::RightMark => right,
+                _ => left,
+            };
+            exprs.push(
+                expr.alias_qualified(domain_column.relation.clone(), &domain_column.name),
+            );
+        }
+        LogicalPlanBuilder::from(new_plan)
+            .project(exprs)?
+            .build()
+            .map(Some)
+    }
+
+    /// Qualifies the domain columns of `plan` with `alias`
+    fn rename_domain(&self, plan: LogicalPlan, alias: &str) -> Result<LogicalPlan> {
+        let exprs = plan
+            .schema()
+            .iter()
+            .map(|(qualifier, field)| {
+                let column = Expr::Column(Column::from((qualifier, field)));
+                if self.is_domain_column(qualifier) {
+                    column.alias_qualified(Some(alias), field.name())
+                } else {
+                    column
+                }
+            })
+            .collect::<Vec<_>>();
+        LogicalPlanBuilder::from(plan).project(exprs)?.build()
+    }
+
+    /// Qualifies the last columns of `plan`, which are the domain columns,
+    /// with the alias of the domain
+    fn restore_domain_qualifier(&self, plan: LogicalPlan) -> Result<LogicalPlan> {
+        let columns = plan.schema().columns();
+        let num_columns = columns.len() - self.columns.len();
+        let exprs = columns[..num_columns]
+            .iter()
+            .cloned()
+            .map(Expr::Column)
+            .chain(columns[num_columns..].iter().zip(&self.columns).map(
+                |(column, (_, domain_column))| {
+                    Expr::Column(column.clone()).alias_qualified(
+                        domain_column.relation.clone(),
+                        &domain_column.name,
+                    )
+                },
+            ))
+            .collect::<Vec<_>>();
+        Ok(LogicalPlan::Projection(Projection::try_new(
+            exprs,
+            Arc::new(plan),
+        )?))
+    }
+
+    /// Projects the domain columns of `plan` after its other columns, if they
+    /// are not already
+    fn move_domain_to_end(&self, plan: LogicalPlan) -> Result<LogicalPlan> {
+        let columns = plan.schema().columns();
+        let num_columns = columns.len() - self.columns.len();
+        let at_end = columns[num_columns..]
+            .iter()
+            .zip(&self.columns)
+            .all(|(column, (_, domain_column))| column == domain_column);
+        if at_end {
+            return Ok(plan);
+        }
+        let exprs = plan
+            .schema()
+            .iter()
+            .filter(|(qualifier, _)| !self.is_domain_column(*qualifier))
+            .map(|(qualifier, field)| Expr::Column(Column::from((qualifier, field))))
+            .chain(self.domain_exprs())
+            .collect::<Vec<_>>();
+        LogicalPlanBuilder::from(plan).project(exprs)?.build()
+    }
+}
+
+fn is_not_distinct_from(left: Expr, right: Expr) -> Expr {
+    binary_expr(left, Operator::IsNotDistinctFrom, right)
+}
+
+/// Returns whether the expressions of `plan` contain a subquery, or `plan` is
+/// a lateral join
+fn has_subquery(plan: &LogicalPlan) -> bool {
+    match plan {
+        LogicalPlan::Join(join) if lateral_subquery(&join.right).is_some() => true,
+        _ => plan.expressions().iter().any(contains_subquery),
+    }
+}
+
+/// Returns whether `expr` contains a subquery
+fn contains_subquery(expr: &Expr) -> bool {
+    expr.exists(|expr| {
+        Ok(matches!(
+            expr,
+            Expr::ScalarSubquery(_) | Expr::Exists(_) | Expr::InSubquery(_)
+        ))
+    })
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::*;
+
+    use crate::assert_optimized_plan_eq_display_indent_snapshot;
+    use arrow::datatypes::DataType;
+    use datafusion_expr::test::function_stub::sum;
+    use datafusion_expr::{
+        col, exists, in_subquery, not_exists, out_ref_col, scalar_subquery,
+    };
+    use datafusion_functions_aggregate::count::count;
+
+    macro_rules! assert_optimized_plan_equal {
+        (
+            $plan:expr,
+            @ $expected:literal $(,)?
+        ) => {{
+            let rule: Arc<dyn crate::OptimizerRule + Send + Sync> = Arc::new(DecorrelateDependentJoin::new());
+            assert_optimized_plan_eq_display_indent_snapshot!(
+                rule,
+                $plan,
+                @ $expected,
+            )
+        }};
+    }
+
+    /// Test for a scalar subquery correlated with a non-equality predicate
+    #[test]
+    fn scalar_subquery_non_equality() -> Result<()> {
+        let subquery = Arc::new(
+            LogicalPlanBuilder::from(test_table_scan_with_name("sq")?)
+                .filter(col("sq.a").lt(out_ref_col(DataType::UInt32, "test.a")))?
+                .aggregate(Vec::<Expr>::new(), vec![sum(col("sq.b"))])?
+                .build()?,
+        );
+        let plan = LogicalPlanBuilder::from(test_table_scan()?)
+            .project(vec![col("test.a"), scalar_subquery(subquery)])?
+            .build()?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            @r"
+        Projection: test.a, __scalar_sq_2.sum(sq.b) AS sum(sq.b) [a:UInt32, sum(sq.b):UInt64;N]
+          Left Join:  Filter: test.a IS NOT DISTINCT FROM __scalar_sq_2.test.a [a:UInt32, b:UInt32, c:UInt32, sum(sq.b):UInt64;N, test.a:UInt32;N]
+            TableScan: test [a:UInt32, b:UInt32, c:UInt32]
+            SubqueryAlias: __scalar_sq_2 [sum(sq.b):UInt64;N, test.a:UInt32]
+              Projection: sum(sq.b), __domain_3.test.a [sum(sq.b):UInt64;N, test.a:UInt32]
+                Left Join:  Filter: __domain_3.test.a IS NOT DISTINCT FROM __domain_4.test.a [test.a:UInt32, sum(sq.b):UInt64;N, test.a:UInt32;N]
+                  Aggregate: groupBy=[[__domain_3.test.a]], aggr=[[]] [test.a:UInt32]
+                    Projection: test.a AS test.a [test.a:UInt32]
+                      TableScan: test [a:UInt32, b:UInt32, c:UInt32]
+                  Projection: sum(sq.b), __domain_3.test.a AS test.a [sum(sq.b):UInt64;N, test.a:UInt32]
+                    Aggregate: groupBy=[[__domain_3.test.a]], aggr=[[sum(sq.b)]] [test.a:UInt32, sum(sq.b):UInt64;N]
+                      Filter: sq.a < __domain_3.test.a [a:UInt32, b:UInt32, c:UInt32, test.a:UInt32]
+                        Cross Join:  [a:UInt32, b:UInt32, c:UInt32, test.a:UInt32]
+                          TableScan: sq [a:UInt32, b:UInt32, c:UInt32]
+                          Aggregate: groupBy=[[__domain_3.test.a]], aggr=[[]] [test.a:UInt32]
+                            Projection: test.a AS test.a [test.a:UInt32]
+                              TableScan: test [a:UInt32, b:UInt32, c:UInt32]
+        "
+        )
+    }
+
+    /// Test for a correlated count, which is 0 for outer rows without matches
+    #[test]
+    fn scalar_subquery_count() -> Result<()> {
+        let subquery = Arc::new(
+            LogicalPlanBuilder::from(test_table_scan_with_name("sq")?)
+                .filter(col("sq.a").gt(out_ref_col(DataType::UInt32, "test.a")))?
+                .aggregate(Vec::<Expr>::new(), vec![count(col("sq.b"))])?
+                .build()?,
+        );
+        let plan = LogicalPlanBuilder::from(test_table_scan()?)
+            .project(vec![col("test.a"), scalar_subquery(subquery)])?
+            .build()?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            @r"
+        Projection: test.a, __scalar_sq_2.count(sq.b) AS count(sq.b) [a:UInt32, count(sq.b):Int64;N]
+          Left Join:  Filter: test.a IS NOT DISTINCT FROM __scalar_sq_2.test.a [a:UInt32, b:UInt32, c:UInt32, count(sq.b):Int64;N, test.a:UInt32;N]
+            TableScan: test [a:UInt32, b:UInt32, c:UInt32]
+            SubqueryAlias: __scalar_sq_2 [count(sq.b):Int64;N, test.a:UInt32]
+              Projection: CASE WHEN __always_true IS NULL THEN Int64(0) ELSE count(sq.b) END AS count(sq.b), __domain_3.test.a [count(sq.b):Int64;N, test.a:UInt32]
+                Left Join:  Filter: __domain_3.test.a IS NOT DISTINCT FROM __domain_4.test.a [test.a:UInt32, count(sq.b):Int64;N, __always_true:Boolean;N, test.a:UInt32;N]
+                  Aggregate: groupBy=[[__domain_3.test.a]], aggr=[[]] [test.a:UInt32]
+                    Projection: test.a AS test.a [test.a:UInt32]
+                      TableScan: test [a:UInt32, b:UInt32, c:UInt32]
+                  Projection: count(sq.b), Boolean(true) AS __always_true, __domain_3.test.a AS test.a [count(sq.b):Int64, __always_true:Boolean, test.a:UInt32]
+                    Aggregate: groupBy=[[__domain_3.test.a]], aggr=[[count(sq.b)]] [test.a:UInt32, count(sq.b):Int64]
+                      Filter: sq.a > __domain_3.test.a [a:UInt32, b:UInt32, c:UInt32, test.a:UInt32]
+                        Cross Join:  [a:UInt32, b:UInt32, c:UInt32, test.a:UInt32]
+                          TableScan: sq [a:UInt32, b:UInt32, c:UInt32]
+                          Aggregate: groupBy=[[__domain_3.test.a]], aggr=[[]] [test.a:UInt32]
+                            Projection: test.a AS test.a [test.a:UInt32]
+                              TableScan: test [a:UInt32, b:UInt32, c:UInt32]
+        "
+        )
+    }
+
+    /// Test for an EXISTS subquery in a projection
+    #[test]
+    fn exists_in_projection() -> Result<()> {
+        let subquery = Arc::new(
+            LogicalPlanBuilder::from(test_table_scan_with_name("sq")?)
+                .filter(col("sq.a").gt(out_ref_col(DataType::UInt32, "test.a")))?
+                .project(vec![col("sq.c")])?
+                .build()?,
+        );
+        let plan = LogicalPlanBuilder::from(test_table_scan()?)
+            .project(vec![col("test.a"), exists(subquery)])?
+            .build()?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            @r"
+        Projection: test.a, __correlated_sq_1.mark AS EXISTS [a:UInt32, EXISTS:Boolean]
+          LeftMark Join:  Filter: test.a IS NOT DISTINCT FROM __correlated_sq_1.test.a [a:UInt32, b:UInt32, c:UInt32, mark:Boolean]
+            TableScan: test [a:UInt32, b:UInt32, c:UInt32]
+            SubqueryAlias: __correlated_sq_1 [c:UInt32, test.a:UInt32]
+              Projection: sq.c, __domain_2.test.a [c:UInt32, test.a:UInt32]
+                Filter: sq.a > __domain_2.test.a [a:UInt32, b:UInt32, c:UInt32, test.a:UInt32]
+                  Cross Join:  [a:UInt32, b:UInt32, c:UInt32, test.a:UInt32]
+                    TableScan: sq [a:UInt32, b:UInt32, c:UInt32]
+                    Aggregate: groupBy=[[__domain_2.test.a]], aggr=[[]] [test.a:UInt32]
+                      Projection: test.a AS test.a [test.a:UInt32]
+                        TableScan: test [a:UInt32, b:UInt32, c:UInt32]
+        "
+        )
+    }
+
+    /// Test for EXISTS and NOT EXISTS conjuncts of a filter
+    #[test]
+    fn exists_in_filter() -> Result<()> {
+        let subquery = |name| -> Result<Arc<LogicalPlan>> {
+            Ok(Arc::new(
+                LogicalPlanBuilder::from(test_table_scan_with_name(name)?)
+                    .filter(
+                        col(format!("{name}.a"))
+                            .gt(out_ref_col(DataType::UInt32, "test.a")),
+                    )?
+                    .project(vec![
+                        col(format!("{name}.b"))
+                            + out_ref_col(DataType::UInt32, "test.a"),
+                    ])?
+                    .build()?,
+            ))
+        };
+        let plan = LogicalPlanBuilder::from(test_table_scan()?)
+            .filter(exists(subquery("sq_1")?).and(not_exists(subquery("sq_2")?)))?
+            .project(vec![col("test.a")])?
+            .build()?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            @r"
+        Projection: test.a [a:UInt32]
+          LeftAnti Join:  Filter: test.a IS NOT DISTINCT FROM __correlated_sq_5.test.a [a:UInt32, b:UInt32, c:UInt32]
+            LeftSemi Join:  Filter: test.a IS NOT DISTINCT FROM __correlated_sq_3.test.a [a:UInt32, b:UInt32, c:UInt32]
+              TableScan: test [a:UInt32, b:UInt32, c:UInt32]
+              SubqueryAlias: __correlated_sq_3 [sq_1.b + outer_ref(test.a):UInt32, test.a:UInt32]
+                Projection: sq_1.b + __domain_4.test.a AS sq_1.b + outer_ref(test.a), __domain_4.test.a [sq_1.b + outer_ref(test.a):UInt32, test.a:UInt32]
+                  Filter: sq_1.a > __domain_4.test.a [a:UInt32, b:UInt32, c:UInt32, test.a:UInt32]
+                    Cross Join:  [a:UInt32, b:UInt32, c:UInt32, test.a:UInt32]
+                      TableScan: sq_1 [a:UInt32, b:UInt32, c:UInt32]
+                      Aggregate: groupBy=[[__domain_4.test.a]], aggr=[[]] [test.a:UInt32]
+                        Projection: test.a AS test.a [test.a:UInt32]
+                          TableScan: test [a:UInt32, b:UInt32, c:UInt32]
+            SubqueryAlias: __correlated_sq_5 [sq_2.b + outer_ref(test.a):UInt32, test.a:UInt32]
+              Projection: sq_2.b + __domain_6.test.a AS sq_2.b + outer_ref(test.a), __domain_6.test.a [sq_2.b + outer_ref(test.a):UInt32, test.a:UInt32]
+                Filter: sq_2.a > __domain_6.test.a [a:UInt32, b:UInt32, c:UInt32, test.a:UInt32]
+                  Cross Join:  [a:UInt32, b:UInt32, c:UInt32, test.a:UInt32]
+                    TableScan: sq_2 [a:UInt32, b:UInt32, c:UInt32]
+                    Aggregate: groupBy=[[__domain_6.test.a]], aggr=[[]] [test.a:UInt32]
+                      Projection: test.a AS test.a [test.a:UInt32]
+                        TableScan: test [a:UInt32, b:UInt32, c:UInt32]
+        "
+        )
+    }
+
+    /// Test for an IN subquery in a projection
+    #[test]
+    fn in_subquery_in_projection() -> Result<()> {
+        let subquery = Arc::new(
+            LogicalPlanBuilder::from(test_table_scan_with_name("sq")?)
+                .filter(col("sq.b").gt(out_ref_col(DataType::UInt32, "test.b")))?
+                .project(vec![col("sq.c")])?
+                .build()?,
+        );
+        let plan = LogicalPlanBuilder::from(test_table_scan()?)
+            .project(vec![col("test.a"), in_subquery(col("test.c"), subquery)])?
+            .build()?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            @r"
+        Projection: test.a, CASE WHEN __correlated_sq_1.mark THEN Boolean(true) ELSE Boolean(false) END AS IN [a:UInt32, IN:Boolean]
+          LeftMark Join:  Filter: test.b IS NOT DISTINCT FROM __correlated_sq_1.test.b AND test.c = __correlated_sq_1.c [a:UInt32, b:UInt32, c:UInt32, mark:Boolean]
+            TableScan: test [a:UInt32, b:UInt32, c:UInt32]
+            SubqueryAlias: __correlated_sq_1 [c:UInt32, test.b:UInt32]
+              Projection: sq.c, __domain_2.test.b [c:UInt32, test.b:UInt32]
+                Filter: sq.b > __domain_2.test.b [a:UInt32, b:UInt32, c:UInt32, test.b:UInt32]
+                  Cross Join:  [a:UInt32, b:UInt32, c:UInt32, test.b:UInt32]
+                    TableScan: sq [a:UInt32, b:UInt32, c:UInt32]
+                    Aggregate: groupBy=[[__domain_2.test.b]], aggr=[[]] [test.b:UInt32]
+                      Projection: test.b AS test.b [test.b:UInt32]
+                        TableScan: test [a:UInt32, b:UInt32, c:UInt32]
+        "
+        )
+    }
+}
@@ -19,7 +19,7 @@
 
 use std::collections::BTreeSet;
 
-use crate::decorrelate::PullUpCorrelatedExpr;
+use crate::decorrelate::{has_nested_outer_references, PullUpCorrelatedExpr};
 use crate::optimizer::ApplyOrder;
 use crate::{OptimizerConfig, OptimizerRule};
 use datafusion_expr::{lit, Join};
@@ -107,6 +107,9 @@ fn rewrite_internal(join: Join) -> Result<Transformed<LogicalPlan>> {
         return Ok(Transformed::no(LogicalPlan::Join(join)));
     }
     let subquery_plan = subquery.subquery.as_ref();
+    if has_nested_outer_references(subquery_plan, join.left.schema())? {
+        return Ok(Transformed::no(LogicalPlan::Join(join)));
+    }
     let mut pull_up = PullUpCorrelatedExpr::new().with_need_handle_count_bug(true);
     let rewritten_subquery = subquery_plan.clone().rewrite(&mut pull_up).data()?;
     if !pull_up.can_pull_up {
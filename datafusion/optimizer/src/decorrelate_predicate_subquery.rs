@@ -20,7 +20,7 @@ use std::collections::BTreeSet;
 use std::ops::Deref;
 use std::sync::Arc;
 
-use crate::decorrelate::PullUpCorrelatedExpr;
+use crate::decorrelate::{has_nested_outer_references, PullUpCorrelatedExpr};
 use crate::optimizer::ApplyOrder;
 use crate::utils::replace_qualified_name;
 use crate::{OptimizerConfig, OptimizerRule};
@@ -318,6 +318,9 @@ fn build_join(
     join_type: JoinType,
     alias: String,
 ) -> Result<Option<LogicalPlan>> {
+    if has_nested_outer_references(subquery, left.schema())? {
+        return Ok(None);
+    }
     let mut pull_up = PullUpCorrelatedExpr::new()
         .with_in_predicate_opt(in_predicate_opt.clone())
         .with_exists_sub_query(in_predicate_opt.is_none());
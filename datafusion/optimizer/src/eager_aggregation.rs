@@ -0,0 +1,543 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`EagerAggregation`] pushes aggregations below inner joins
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::optimizer::ApplyOrder;
+use crate::{OptimizerConfig, OptimizerRule};
+
+use datafusion_common::tree_node::Transformed;
+use datafusion_common::NullEquality;
+use datafusion_common::{Column, DFSchema, Dependency, JoinSide, JoinType, Result};
+use datafusion_expr::expr::AggregateFunction;
+use datafusion_expr::{cast, Aggregate, Expr, Join, LogicalPlan, Projection};
+
+/// Prefix of the names of the partial aggregates pushed below joins
+const EAGER_AGG_PREFIX: &str = "__eager_agg";
+
+/// Optimizer rule that computes an aggregation over an inner join in two
+/// phases, aggregating one input of the join before the join.
+///
+/// For example, if `d.id` is the primary key of `d`,
+///
+/// ```text
+/// Aggregate: groupBy=[[d.region]], aggr=[[sum(f.amount)]]
+///   Inner Join: f.dim_id = d.id
+///     TableScan: f
+///     TableScan: d
+/// ```
+///
+/// is rewritten to
+///
+/// ```text
+/// Projection: d.region, sum(__eager_agg_1) AS sum(f.amount)
+///   Aggregate: groupBy=[[d.region]], aggr=[[sum(__eager_agg_1)]]
+///     Inner Join: f.dim_id = d.id
+///       Aggregate: groupBy=[[f.dim_id]], aggr=[[sum(f.amount) AS __eager_agg_1]]
+///         TableScan: f
+///       TableScan: d
+/// ```
+///
+/// so that the join input `f` is reduced to one row per join key. The
+/// aggregation is pushed to the join input that the arguments of all
+/// aggregate functions refer to, grouped by the columns of that input that
+/// are used in the `GROUP BY` expressions and in the join condition. This is
+/// only done if:
+///
+/// * all aggregate functions are decomposable (see
+///   [`AggregateUDFImpl::decomposition`]), without `DISTINCT` or `ORDER BY`
+/// * the join keys of the other input are unique (according to its
+///   functional dependencies), or all aggregate functions are insensitive to
+///   duplicates, like `min` and `max`
+/// * the columns the pushed aggregation groups by are not empty and not
+///   already unique, so that it reduces the number of rows
+/// * the aggregation has a `GROUP BY` clause, as aggregate functions like
+///   `count` return a value for an empty input
+///
+/// [`AggregateUDFImpl::decomposition`]: datafusion_expr::AggregateUDFImpl::decomposition
+#[derive(Default, Debug)]
+pub struct EagerAggregation {}
+
+impl EagerAggregation {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl OptimizerRule for EagerAggregation {
+    fn supports_rewrite(&self) -> bool {
+        true
+    }
+
+    fn rewrite(
+        &self,
+        plan: LogicalPlan,
+        config: &dyn OptimizerConfig,
+    ) -> Result<Transformed<LogicalPlan>> {
+        if !config.options().optimizer.enable_eager_aggregation {
+            return Ok(Transformed::no(plan));
+        }
+        let LogicalPlan::Aggregate(aggregate) = plan else {
+            return Ok(Transformed::no(plan));
+        };
+        let LogicalPlan::Join(join) = aggregate.input.as_ref() else {
+            return Ok(Transformed::no(LogicalPlan::Aggregate(aggregate)));
+        };
+        match push_down_aggregate(&aggregate, join, config)? {
+            Some(plan) => Ok(Transformed::yes(plan)),
+            None => Ok(Transformed::no(LogicalPlan::Aggregate(aggregate))),
+        }
+    }
+
+    fn name(&self) -> &str {
+        "eager_aggregation"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        Some(ApplyOrder::BottomUp)
+    }
+}
+
+/// An aggregate function of the aggregation, with the aggregate function
+/// that combines its partial results
+struct DecomposedAggregate<'a> {
+    function: &'a AggregateFunction,
+    merge: Arc<datafusion_expr::AggregateUDF>,
+    duplicate_insensitive: bool,
+}
+
+/// Returns the plan with the aggregation pushed below the join, or `None` if
+/// the aggregation can not be pushed down
+fn push_down_aggregate(
+    aggregate: &Aggregate,
+    join: &Join,
+    config: &dyn OptimizerConfig,
+) -> Result<Option<LogicalPlan>> {
+    if join.join_type != JoinType::Inner
+        || aggregate.group_expr.is_empty()
+        || aggregate.aggr_expr.is_empty()
+        || aggregate
+            .group_expr
+            .iter()
+            .any(|expr| matches!(expr, Expr::GroupingSet(_)))
+    {
+        return Ok(None);
+    }
+
+    let mut aggregates = vec![];
+    let mut aggregate_columns = HashSet::new();
+    for expr in &aggregate.aggr_expr {
+        let expr = match expr {
+            Expr::Alias(alias) => alias.expr.as_ref(),
+            expr => expr,
+        };
+        let Expr::AggregateFunction(function) = expr else {
+            return Ok(None);
+        };
+        let params = &function.params;
+        if params.distinct
+            || !params.order_by.is_empty()
+            || params.null_treatment.is_some()
+        {
+            return Ok(None);
+        }
+        let decomposition = function.func.decomposition();
+        let Some(merge) = decomposition.merge_udaf() else {
+            return Ok(None);
+        };
+        aggregates.push(DecomposedAggregate {
+            function,
+            merge: Arc::clone(merge),
+            duplicate_insensitive: decomposition.is_duplicate_insensitive(),
+        });
+        expr.add_column_refs(&mut aggregate_columns);
+    }
+
+    for side in [JoinSide::Left, JoinSide::Right] {
+        let input = match side {
+            JoinSide::Left => &join.left,
+            _ => &join.right,
+        };
+        if !aggregate_columns
+            .iter()
+            .all(|column| input.schema().has_column(column))
+        {
+            continue;
+        }
+        if let Some(plan) = push_down_to_side(aggregate, join, side, &aggregates, config)?
+        {
+            return Ok(Some(plan));
+        }
+    }
+    Ok(None)
+}
+
+/// Returns the plan with the aggregation pushed to the `side` input of the
+/// join, or `None` if the aggregation can not be pushed to this input
+fn push_down_to_side(
+    aggregate: &Aggregate,
+    join: &Join,
+    side: JoinSide,
+    aggregates: &[DecomposedAggregate],
+    config: &dyn OptimizerConfig,
+) -> Result<Option<LogicalPlan>> {
+    let (input, other, input_keys, other_keys): (_, _, Vec<_>, Vec<_>) = match side {
+        JoinSide::Left => (
+            &join.left,
+            &join.right,
+            join.on.iter().map(|(l, _)| l).collect(),
+            join.on.iter().map(|(_, r)| r).collect(),
+        ),
+        _ => (
+            &join.right,
+            &join.left,
+            join.on.iter().map(|(_, r)| r).collect(),
+            join.on.iter().map(|(l, _)| l).collect(),
+        ),
+    };
+
+    // Each row of the input matches at most one row of the other input if
+    // the join keys of the other input are unique. Otherwise the partial
+    // results are duplicated by the join.
+    let other_keys_unique = join.null_equality == NullEquality::NullEqualsNothing
+        && other_keys
+            .iter()
+            .map(|key| match key {
+                Expr::Column(column) => other.schema().index_of_column(column).ok(),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()
+            .is_some_and(|indices| is_unique(other.schema(), &indices));
+    if !other_keys_unique && !aggregates.iter().all(|a| a.duplicate_insensitive) {
+        return Ok(None);
+    }
+
+    // Group by the columns of the input that are needed above the join
+    let mut required_columns = HashSet::new();
+    for expr in aggregate.group_expr.iter().chain(input_keys) {
+        expr.add_column_refs(&mut required_columns);
+    }
+    if let Some(filter) = &join.filter {
+        filter.add_column_refs(&mut required_columns);
+    }
+    let (group_indices, group_columns): (Vec<_>, Vec<_>) = input
+        .schema()
+        .columns()
+        .into_iter()
+        .enumerate()
+        .filter(|(_, column)| required_columns.contains(column))
+        .unzip();
+    if group_indices.is_empty() || is_unique(input.schema(), &group_indices) {
+        return Ok(None);
+    }
+
+    let names = aggregates
+        .iter()
+        .map(|_| config.alias_generator().next(EAGER_AGG_PREFIX))
+        .collect::<Vec<_>>();
+    let partial_aggr_expr = aggregates
+        .iter()
+        .zip(&names)
+        .map(|(a, name)| Expr::AggregateFunction(a.function.clone()).alias(name))
+        .collect();
+    let partial_aggregate = Arc::new(LogicalPlan::Aggregate(Aggregate::try_new(
+        Arc::clone(input),
+        group_columns.into_iter().map(Expr::Column).collect(),
+        partial_aggr_expr,
+    )?));
+
+    let (left, right) = match side {
+        JoinSide::Left => (partial_aggregate, Arc::clone(&join.right)),
+        _ => (Arc::clone(&join.left), partial_aggregate),
+    };
+    let join = Join::try_new(
+        left,
+        right,
+        join.on.clone(),
+        join.filter.clone(),
+        join.join_type,
+        join.join_constraint,
+        join.null_equality,
+    )?;
+
+    let merge_aggr_expr = aggregates
+        .iter()
+        .zip(names)
+        .map(|(a, name)| {
+            Expr::AggregateFunction(AggregateFunction::new_udf(
+                Arc::clone(&a.merge),
+                vec![Expr::Column(Column::from_name(name))],
+                false,
+                None,
+                vec![],
+                None,
+            ))
+        })
+        .collect();
+    let merge_aggregate = Aggregate::try_new(
+        Arc::new(LogicalPlan::Join(join)),
+        aggregate.group_expr.clone(),
+        merge_aggr_expr,
+    )?;
+
+    // Restore the names and types of the original aggregation
+    let group_len = aggregate.group_expr.len();
+    let expr = merge_aggregate
+        .schema
+        .iter()
+        .zip(aggregate.schema.iter())
+        .enumerate()
+        .map(
+            |(index, ((qualifier, field), (original_qualifier, original_field)))| {
+                let column = Expr::Column(Column::from((qualifier, field)));
+                if index < group_len {
+                    return column;
+                }
+                let column = if field.data_type() == original_field.data_type() {
+                    column
+                } else {
+                    cast(column, original_field.data_type().clone())
+                };
+                column.alias_qualified(original_qualifier.cloned(), original_field.name())
+            },
+        )
+        .collect();
+    let projection =
+        Projection::try_new(expr, Arc::new(LogicalPlan::Aggregate(merge_aggregate)))?;
+    Ok(Some(LogicalPlan::Projection(projection)))
+}
+
+/// Returns whether the columns at `indices` are a unique key of `schema`
+fn is_unique(schema: &DFSchema, indices: &[usize]) -> bool {
+    schema.functional_dependencies().iter().any(|dependency| {
+        dependency.mode == Dependency::Single
+            && dependency
+                .source_indices
+                .iter()
+                .all(|index| indices.contains(index))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_optimized_plan_eq_snapshot;
+    use crate::test::*;
+    use crate::{Optimizer, OptimizerContext};
+
+    use arrow::datatypes::{DataType, Field, Schema};
+    use datafusion_common::{Constraint, Constraints};
+    use datafusion_expr::{col, LogicalPlanBuilder, LogicalTableSource};
+    use datafusion_functions_aggregate::expr_fn::{avg, count, count_distinct, max, sum};
+
+    macro_rules! assert_optimized_plan_equal {
+        (
+            $plan:expr,
+            @ $expected:literal $(,)?
+        ) => {{
+            let optimizer_ctx = OptimizerContext::new().with_max_passes(1);
+            let rules: Vec<Arc<dyn crate::OptimizerRule + Send + Sync>> =
+                vec![Arc::new(EagerAggregation::new())];
+            assert_optimized_plan_eq_snapshot!(
+                optimizer_ctx,
+                rules,
+                $plan,
+                @ $expected,
+            )
+        }};
+    }
+
+    /// Scan of a dimension table `d` with the primary key `d.id`
+    fn dim_table_scan() -> Result<LogicalPlan> {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::UInt32, false),
+            Field::new("region", DataType::Utf8, false),
+        ]);
+        let source = LogicalTableSource::new(Arc::new(schema)).with_constraints(
+            Constraints::new_unverified(vec![Constraint::PrimaryKey(vec![0])]),
+        );
+        LogicalPlanBuilder::scan("d", Arc::new(source), None)?.build()
+    }
+
+    #[test]
+    fn push_down_to_unique_join_keys() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(test_table_scan()?)
+            .join(
+                dim_table_scan()?,
+                JoinType::Inner,
+                (vec!["a"], vec!["id"]),
+                None,
+            )?
+            .aggregate(
+                vec![col("d.region")],
+                vec![sum(col("test.b")), count(col("test.c"))],
+            )?
+            .build()?;
+
+        assert_optimized_plan_equal!(plan, @r"
+        Projection: d.region, sum(__eager_agg_1) AS sum(test.b), sum(__eager_agg_2) AS count(test.c)
+          Aggregate: groupBy=[[d.region]], aggr=[[sum(__eager_agg_1), sum(__eager_agg_2)]]
+            Inner Join: test.a = d.id
+              Aggregate: groupBy=[[test.a]], aggr=[[sum(test.b) AS __eager_agg_1, count(test.c) AS __eager_agg_2]]
+                TableScan: test
+              TableScan: d
+        ")
+    }
+
+    #[test]
+    fn push_down_to_right_input() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(dim_table_scan()?)
+            .join(
+                test_table_scan()?,
+                JoinType::Inner,
+                (vec!["id"], vec!["a"]),
+                None,
+            )?
+            .aggregate(
+                vec![col("d.region"), col("test.c")],
+                vec![sum(col("test.b"))],
+            )?
+            .build()?;
+
+        assert_optimized_plan_equal!(plan, @r"
+        Projection: d.region, test.c, sum(__eager_agg_1) AS sum(test.b)
+          Aggregate: groupBy=[[d.region, test.c]], aggr=[[sum(__eager_agg_1)]]
+            Inner Join: d.id = test.a
+              TableScan: d
+              Aggregate: groupBy=[[test.a, test.c]], aggr=[[sum(test.b) AS __eager_agg_1]]
+                TableScan: test
+        ")
+    }
+
+    #[test]
+    fn push_down_duplicate_insensitive() -> Result<()> {
+        // The join keys of `t2` are not unique, but `max` is insensitive to
+        // duplicates
+        let plan = LogicalPlanBuilder::from(test_table_scan_with_name("t1")?)
+            .join(
+                test_table_scan_with_name("t2")?,
+                JoinType::Inner,
+                (vec!["a"], vec!["a"]),
+                None,
+            )?
+            .aggregate(vec![col("t2.b")], vec![max(col("t1.c"))])?
+            .build()?;
+
+        assert_optimized_plan_equal!(plan, @r"
+        Projection: t2.b, max(__eager_agg_1) AS max(t1.c)
+          Aggregate: groupBy=[[t2.b]], aggr=[[max(__eager_agg_1)]]
+            Inner Join: t1.a = t2.a
+              Aggregate: groupBy=[[t1.a]], aggr=[[max(t1.c) AS __eager_agg_1]]
+                TableScan: t1
+              TableScan: t2
+        ")
+    }
+
+    #[test]
+    fn no_push_down_non_unique_join_keys() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(test_table_scan_with_name("t1")?)
+            .join(
+                test_table_scan_with_name("t2")?,
+                JoinType::Inner,
+                (vec!["a"], vec!["a"]),
+                None,
+            )?
+            .aggregate(vec![col("t2.b")], vec![sum(col("t1.c"))])?
+            .build()?;
+
+        assert_optimized_plan_equal!(plan, @r"
+        Aggregate: groupBy=[[t2.b]], aggr=[[sum(t1.c)]]
+          Inner Join: t1.a = t2.a
+            TableScan: t1
+            TableScan: t2
+        ")
+    }
+
+    #[test]
+    fn no_push_down_non_decomposable() -> Result<()> {
+        for aggr_expr in [avg(col("test.b")), count_distinct(col("test.b"))] {
+            let plan = LogicalPlanBuilder::from(test_table_scan()?)
+                .join(
+                    dim_table_scan()?,
+                    JoinType::Inner,
+                    (vec!["a"], vec!["id"]),
+                    None,
+                )?
+                .aggregate(vec![col("d.region")], vec![aggr_expr])?
+                .build()?;
+            let expected = plan.clone();
+
+            let optimized =
+                Optimizer::with_rules(vec![Arc::new(EagerAggregation::new())]).optimize(
+                    plan,
+                    &OptimizerContext::new(),
+                    |_, _| {},
+                )?;
+            assert_eq!(optimized, expected);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn no_push_down_aggregate_of_both_inputs() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(test_table_scan()?)
+            .join(
+                dim_table_scan()?,
+                JoinType::Inner,
+                (vec!["a"], vec!["id"]),
+                None,
+            )?
+            .aggregate(
+                vec![col("test.c")],
+                vec![max(col("test.b")), max(col("d.region"))],
+            )?
+            .build()?;
+
+        assert_optimized_plan_equal!(plan, @r"
+        Aggregate: groupBy=[[test.c]], aggr=[[max(test.b), max(d.region)]]
+          Inner Join: test.a = d.id
+            TableScan: test
+            TableScan: d
+        ")
+    }
+
+    #[test]
+    fn no_push_down_unique_group_keys() -> Result<()> {
+        // `d` is already unique on `d.id`, so aggregating it would not reduce
+        // its number of rows
+        let plan = LogicalPlanBuilder::from(test_table_scan()?)
+            .join(
+                dim_table_scan()?,
+                JoinType::Inner,
+                (vec!["a"], vec!["id"]),
+                None,
+            )?
+            .aggregate(vec![col("test.c")], vec![max(col("d.region"))])?
+            .build()?;
+
+        assert_optimized_plan_equal!(plan, @r"
+        Aggregate: groupBy=[[test.c]], aggr=[[max(d.region)]]
+          Inner Join: test.a = d.id
+            TableScan: test
+            TableScan: d
+        ")
+    }
+}
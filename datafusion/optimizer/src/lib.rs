@@ -40,8 +40,10 @@
 pub mod analyzer;
 pub mod common_subexpr_eliminate;
 pub mod decorrelate;
+pub mod decorrelate_dependent_join;
 pub mod decorrelate_lateral_join;
 pub mod decorrelate_predicate_subquery;
+pub mod eager_aggregation;
 pub mod eliminate_cross_join;
 pub mod eliminate_duplicated_expr;
 pub mod eliminate_filter;
@@ -73,7 +75,7 @@ pub use optimizer::{
 };
 
 pub(crate) mod join_key_set;
-mod plan_signature;
+pub mod plan_signature;
 
 #[cfg(test)]
 #[ctor::ctor]
@@ -30,8 +30,8 @@ use datafusion_common::{
 };
 use datafusion_expr::expr::Alias;
 use datafusion_expr::{
-    logical_plan::LogicalPlan, Aggregate, Distinct, EmptyRelation, Expr, Projection,
-    TableScan, Unnest, Window,
+    logical_plan::LogicalPlan, Aggregate, Distinct, EmptyRelation, Expr, MatchRecognize,
+    Projection, RowsPerMatch, TableScan, Unnest, Window,
 };
 
 use crate::optimize_projections::required_indices::RequiredIndices;
@@ -288,6 +288,7 @@ fn optimize_projections(
         LogicalPlan::Sort(_)
         | LogicalPlan::Filter(_)
         | LogicalPlan::Repartition(_)
+        | LogicalPlan::Sample(_)
         | LogicalPlan::Union(_)
         | LogicalPlan::SubqueryAlias(_)
         | LogicalPlan::Distinct(Distinct::On(_)) => {
@@ -305,6 +306,19 @@ fn optimize_projections(
                 })
                 .collect::<Result<_>>()?
         }
+        LogicalPlan::MatchRecognize(MatchRecognize {
+            input,
+            rows_per_match,
+            ..
+        }) => match rows_per_match {
+            // Only the columns used by the plan's expressions are needed, and
+            // the output does not depend on the input schema otherwise
+            RowsPerMatch::OneRow => vec![RequiredIndices::new()
+                .with_projection_beneficial()
+                .with_plan_exprs(&plan, input.schema())?],
+            // All input columns are part of the output
+            RowsPerMatch::AllRows(_) => vec![RequiredIndices::new_for_all_exprs(input)],
+        },
         LogicalPlan::Limit(_) => {
             // Pass index requirements from the parent as well as column indices
             // that appear in this plan's expressions to its child. These operators
@@ -740,7 +754,8 @@ fn split_join_requirements(
         | JoinType::Right
         | JoinType::Full
         | JoinType::LeftMark
-        | JoinType::RightMark => {
+        | JoinType::RightMark
+        | JoinType::AsOf => {
             // Decrease right side indices by `left_len` so that they point to valid
             // positions within the right child:
             indices.split_off(left_len)
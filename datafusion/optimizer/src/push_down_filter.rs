@@ -171,6 +171,8 @@ pub(crate) fn lr_is_preserved(join_type: JoinType) -> (bool, bool) {
         // No columns from the left side of the join can be referenced in output
         // predicates for semi/anti joins, so whether we specify t/f doesn't matter.
         JoinType::RightSemi | JoinType::RightAnti | JoinType::RightMark => (false, true),
+        // Like a left join, the right side of an AsOf join can be padded with nulls.
+        JoinType::AsOf => (true, false),
     }
 }
 
@@ -194,6 +196,9 @@ pub(crate) fn on_lr_is_preserved(join_type: JoinType) -> (bool, bool) {
         JoinType::RightAnti => (true, false),
         JoinType::LeftMark => (false, true),
         JoinType::RightMark => (true, false),
+        // The filter of an AsOf join selects the closest match, so filtering
+        // either input beforehand can change which row is the closest.
+        JoinType::AsOf => (false, false),
     }
 }
 
@@ -681,7 +686,9 @@ fn infer_join_predicates_from_on_filters(
     inferred_predicates: &mut InferredPredicates,
 ) -> Result<()> {
     match join_type {
-        JoinType::Full | JoinType::LeftAnti | JoinType::RightAnti => Ok(()),
+        JoinType::Full | JoinType::LeftAnti | JoinType::RightAnti | JoinType::AsOf => {
+            Ok(())
+        }
         JoinType::Inner => infer_join_predicates_impl::<true, true>(
             join_col_keys,
             on_filters,
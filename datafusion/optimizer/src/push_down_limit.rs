@@ -251,7 +251,7 @@ fn push_down_join(mut join: Join, limit: usize) -> Transformed<Join> {
         (Some(limit), Some(limit))
     } else {
         match join.join_type {
-            Left => (Some(limit), None),
+            Left | AsOf => (Some(limit), None),
             Right => (None, Some(limit)),
             _ => (None, None),
         }
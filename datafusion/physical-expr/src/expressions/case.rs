@@ -31,7 +31,7 @@ use datafusion_common::{
 };
 use datafusion_expr::ColumnarValue;
 
-use super::{Column, Literal};
+use super::{Column, IsNotNullExpr, Literal};
 use datafusion_physical_expr_common::datum::compare_with_eq;
 use itertools::Itertools;
 
@@ -190,6 +190,20 @@ impl CaseExpr {
 }
 
 impl CaseExpr {
+    /// Returns true if `then` is only evaluated when it is not null, that is
+    /// for `WHEN x IS NOT NULL THEN x`
+    fn is_not_null_guard(
+        &self,
+        when: &Arc<dyn PhysicalExpr>,
+        then: &Arc<dyn PhysicalExpr>,
+    ) -> bool {
+        self.expr.is_none()
+            && when
+                .as_any()
+                .downcast_ref::<IsNotNullExpr>()
+                .is_some_and(|is_not_null| is_not_null.arg() == then)
+    }
+
     /// This function evaluates the form of CASE that matches an expression to fixed values.
     ///
     /// CASE expression
@@ -477,11 +491,12 @@ impl PhysicalExpr for CaseExpr {
     }
 
     fn nullable(&self, input_schema: &Schema) -> Result<bool> {
-        // this expression is nullable if any of the input expressions are nullable
+        // this expression is nullable if any of the input expressions are nullable,
+        // except for `WHEN x IS NOT NULL THEN x`, as produced by simplifying `coalesce`
         let then_nullable = self
             .when_then_expr
             .iter()
-            .map(|(_, t)| t.nullable(input_schema))
+            .map(|(w, t)| Ok(t.nullable(input_schema)? && !self.is_not_null_guard(w, t)))
             .collect::<Result<Vec<_>>>()?;
         if then_nullable.contains(&true) {
             Ok(true)
@@ -1309,6 +1324,41 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn case_nullability() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Int32, true),
+        ]);
+        let a = make_col("a", 0);
+        let b = make_col("b", 1);
+        let is_not_null = |expr: &Arc<dyn PhysicalExpr>| -> Arc<dyn PhysicalExpr> {
+            Arc::new(IsNotNullExpr::new(Arc::clone(expr)))
+        };
+
+        // CASE WHEN a IS NOT NULL THEN a ELSE 0 END, as produced by coalesce(a, 0)
+        let expr = CaseExpr::try_new(
+            None,
+            vec![(is_not_null(&a), Arc::clone(&a))],
+            Some(make_lit_i32(0)),
+        )?;
+        assert!(!expr.nullable(&schema)?);
+
+        // CASE WHEN b IS NOT NULL THEN a ELSE 0 END
+        let expr = CaseExpr::try_new(
+            None,
+            vec![(is_not_null(&b), Arc::clone(&a))],
+            Some(make_lit_i32(0)),
+        )?;
+        assert!(expr.nullable(&schema)?);
+
+        // CASE WHEN a IS NOT NULL THEN a END
+        let expr =
+            CaseExpr::try_new(None, vec![(is_not_null(&a), Arc::clone(&a))], None)?;
+        assert!(expr.nullable(&schema)?);
+        Ok(())
+    }
+
     #[test]
     fn test_expr_or_expr_specialization() -> Result<()> {
         let batch = case_test_batch1()?;
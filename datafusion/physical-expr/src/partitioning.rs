@@ -22,6 +22,7 @@ use crate::{
     EquivalenceProperties, PhysicalExpr,
 };
 use datafusion_physical_expr_common::physical_expr::format_physical_expr_list;
+use datafusion_physical_expr_common::sort_expr::{LexOrdering, PhysicalSortExpr};
 use std::fmt;
 use std::fmt::Display;
 use std::sync::Arc;
@@ -117,6 +118,11 @@ pub enum Partitioning {
     /// Allocate rows based on a hash of one of more expressions and the specified number of
     /// partitions
     Hash(Vec<Arc<dyn PhysicalExpr>>, usize),
+    /// Allocate rows to the specified number of partitions based on ranges of
+    /// the values of the sort expressions, so that every row of partition `i`
+    /// sorts before (or equal to) every row of partition `i + 1`. The range
+    /// boundaries are determined at runtime by sampling the input.
+    Range(LexOrdering, usize),
     /// Unknown partitioning scheme with a known number of partitions
     UnknownPartitioning(usize),
 }
@@ -133,6 +139,9 @@ impl Display for Partitioning {
                     .join(", ");
                 write!(f, "Hash([{phy_exprs_str}], {size})")
             }
+            Partitioning::Range(ordering, size) => {
+                write!(f, "Range([{ordering}], {size})")
+            }
             Partitioning::UnknownPartitioning(size) => {
                 write!(f, "UnknownPartitioning({size})")
             }
@@ -144,7 +153,7 @@ impl Partitioning {
     pub fn partition_count(&self) -> usize {
         use Partitioning::*;
         match self {
-            RoundRobinBatch(n) | Hash(_, n) | UnknownPartitioning(n) => *n,
+            RoundRobinBatch(n) | Hash(_, n) | Range(_, n) | UnknownPartitioning(n) => *n,
         }
     }
 
@@ -202,19 +211,33 @@ impl Partitioning {
         mapping: &ProjectionMapping,
         input_eq_properties: &EquivalenceProperties,
     ) -> Self {
-        if let Partitioning::Hash(exprs, part) = self {
-            let normalized_exprs = input_eq_properties
-                .project_expressions(exprs, mapping)
-                .zip(exprs)
-                .map(|(proj_expr, expr)| {
-                    proj_expr.unwrap_or_else(|| {
-                        Arc::new(UnKnownColumn::new(&expr.to_string()))
+        match self {
+            Partitioning::Hash(exprs, part) => {
+                let normalized_exprs = input_eq_properties
+                    .project_expressions(exprs, mapping)
+                    .zip(exprs)
+                    .map(|(proj_expr, expr)| {
+                        proj_expr.unwrap_or_else(|| {
+                            Arc::new(UnKnownColumn::new(&expr.to_string()))
+                        })
                     })
-                })
-                .collect();
-            Partitioning::Hash(normalized_exprs, *part)
-        } else {
-            self.clone()
+                    .collect();
+                Partitioning::Hash(normalized_exprs, *part)
+            }
+            Partitioning::Range(ordering, part) => {
+                let sort_exprs = input_eq_properties
+                    .project_expressions(ordering.iter().map(|e| &e.expr), mapping)
+                    .zip(ordering.iter())
+                    .map(|(proj_expr, sort_expr)| {
+                        let expr = proj_expr.unwrap_or_else(|| {
+                            Arc::new(UnKnownColumn::new(&sort_expr.expr.to_string()))
+                        });
+                        PhysicalSortExpr::new(expr, sort_expr.options)
+                    });
+                // A non-empty ordering projects to a non-empty ordering
+                Partitioning::Range(LexOrdering::new(sort_exprs).unwrap(), *part)
+            }
+            _ => self.clone(),
         }
     }
 }
@@ -231,6 +254,10 @@ impl PartialEq for Partitioning {
             {
                 true
             }
+            (
+                Partitioning::Range(ordering1, count1),
+                Partitioning::Range(ordering2, count2),
+            ) => ordering1 == ordering2 && count1 == count2,
             _ => false,
         }
     }
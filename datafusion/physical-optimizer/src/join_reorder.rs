@@ -0,0 +1,648 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! The [`JoinReorder`] rule reorders trees of inner joins using the statistics
+//! of their inputs, so that the estimated sizes of the intermediate results
+//! are as small as possible.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::PhysicalOptimizerRule;
+
+use arrow::datatypes::{Schema, SchemaRef};
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::error::Result;
+use datafusion_common::stats::Precision;
+use datafusion_common::tree_node::{
+    Transformed, TransformedResult, TreeNode, TreeNodeRecursion,
+};
+use datafusion_common::{internal_datafusion_err, JoinType, NullEquality, Statistics};
+use datafusion_physical_expr::expressions::Column;
+use datafusion_physical_expr::utils::collect_columns;
+use datafusion_physical_expr::PhysicalExpr;
+use datafusion_physical_plan::joins::utils::{estimate_join_statistics, JoinOn};
+use datafusion_physical_plan::joins::{CrossJoinExec, HashJoinExec, PartitionMode};
+use datafusion_physical_plan::projection::ProjectionExec;
+use datafusion_physical_plan::{with_new_children_if_necessary, ExecutionPlan};
+
+/// The [`JoinReorder`] rule reorders trees of inner joins, based on the
+/// statistics of their inputs.
+///
+/// A join tree consists of [`HashJoinExec`]s with [`JoinType::Inner`] and no
+/// filter or projection, and of [`CrossJoinExec`]s. Every equijoin key of these
+/// joins must refer to a single input of the tree. For trees with up to
+/// `join_reordering_dp_threshold` inputs, all join orders are considered by
+/// dynamic programming, larger trees are ordered greedily by repeatedly
+/// joining the two subtrees with the smallest estimated join output.
+///
+/// The cost of a join order is the sum of the estimated number of output rows
+/// of its joins. These estimates use the row counts, distinct counts and
+/// min/max values of the join keys, see [`estimate_join_statistics`]. Trees
+/// with inputs without row count statistics are not reordered, and the
+/// written join order is kept unless another order has a lower cost.
+///
+/// Cross joins are only introduced where the inputs are not connected by
+/// equijoin keys. The build side of each join is selected later by the
+/// [`JoinSelection`] rule.
+///
+/// [`JoinSelection`]: crate::join_selection::JoinSelection
+#[derive(Default, Debug)]
+pub struct JoinReorder {}
+
+impl JoinReorder {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl PhysicalOptimizerRule for JoinReorder {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if !config.optimizer.enable_join_reordering {
+            return Ok(plan);
+        }
+        reorder_joins(plan, config.optimizer.join_reordering_dp_threshold)
+    }
+
+    fn name(&self) -> &str {
+        "join_reorder"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Reorders all join trees in `plan`, from the top down
+fn reorder_joins(
+    plan: Arc<dyn ExecutionPlan>,
+    dp_threshold: usize,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    plan.transform_down(|plan| {
+        let Some(graph) = JoinGraph::try_new(&plan) else {
+            return Ok(Transformed::no(plan));
+        };
+        // The inputs of the join tree may contain other join trees
+        let graph = graph.map_inputs(|input| reorder_joins(input, dp_threshold))?;
+        let new_plan = match graph.reorder(dp_threshold)? {
+            Some(new_plan) => new_plan,
+            None => graph.with_written_order(&plan)?,
+        };
+        // The inputs are already reordered, so skip the children
+        Ok(Transformed::new(new_plan, true, TreeNodeRecursion::Jump))
+    })
+    .data()
+}
+
+/// An equijoin key pair between two inputs of a join tree, the expressions
+/// refer to the columns of their input
+#[derive(Debug)]
+struct JoinEdge {
+    left_input: usize,
+    left: Arc<dyn PhysicalExpr>,
+    right_input: usize,
+    right: Arc<dyn PhysicalExpr>,
+}
+
+/// The shape of a join tree, the leaves are the indices of the inputs
+#[derive(Debug, Clone)]
+enum JoinTree {
+    Input(usize),
+    Join(Box<JoinTree>, Box<JoinTree>),
+}
+
+impl JoinTree {
+    /// Appends the inputs of this tree, in the order of its output columns
+    fn collect_inputs(&self, inputs: &mut Vec<usize>) {
+        match self {
+            JoinTree::Input(input) => inputs.push(*input),
+            JoinTree::Join(left, right) => {
+                left.collect_inputs(inputs);
+                right.collect_inputs(inputs);
+            }
+        }
+    }
+}
+
+/// A join tree with its estimated statistics and cost
+#[derive(Debug, Clone)]
+struct JoinPlan {
+    tree: JoinTree,
+    /// Inputs in the order of the output columns
+    inputs: Vec<usize>,
+    schema: SchemaRef,
+    statistics: Statistics,
+    /// Sum of the estimated output rows of all joins
+    cost: usize,
+}
+
+/// The inputs of a tree of inner joins, and the equijoin keys between them
+#[derive(Debug)]
+struct JoinGraph {
+    inputs: Vec<Arc<dyn ExecutionPlan>>,
+    edges: Vec<JoinEdge>,
+    /// The written join order
+    tree: JoinTree,
+    /// The partition mode of the hash joins of the tree, if they all have the
+    /// same one
+    partition_mode: Option<PartitionMode>,
+}
+
+impl JoinGraph {
+    /// Returns the join graph of the join tree rooted at `plan`, if `plan` is
+    /// the root of a join tree with at least three inputs
+    fn try_new(plan: &Arc<dyn ExecutionPlan>) -> Option<Self> {
+        if !is_reorderable_join(plan) {
+            return None;
+        }
+        let mut graph = Self {
+            inputs: vec![],
+            edges: vec![],
+            tree: JoinTree::Input(0),
+            partition_mode: None,
+        };
+        graph.tree = graph.add_join_tree(plan, &mut vec![])?;
+        (graph.inputs.len() >= 3).then_some(graph)
+    }
+
+    /// Adds the inputs and edges of the join tree rooted at `plan`. `offsets`
+    /// holds the index of the first output column of each input.
+    fn add_join_tree(
+        &mut self,
+        plan: &Arc<dyn ExecutionPlan>,
+        offsets: &mut Vec<usize>,
+    ) -> Option<JoinTree> {
+        if !is_reorderable_join(plan) {
+            let offset = offsets.last().map_or(0, |offset| {
+                offset + self.inputs.last().unwrap().schema().fields().len()
+            });
+            offsets.push(offset);
+            self.inputs.push(Arc::clone(plan));
+            return Some(JoinTree::Input(self.inputs.len() - 1));
+        }
+
+        let children = plan.children();
+        let left_start = self.inputs.len();
+        let left = self.add_join_tree(children[0], offsets)?;
+        let right_start = self.inputs.len();
+        let right = self.add_join_tree(children[1], offsets)?;
+
+        if let Some(hash_join) = plan.as_any().downcast_ref::<HashJoinExec>() {
+            let mode = *hash_join.partition_mode();
+            self.partition_mode = match self.partition_mode {
+                Some(current) if current != mode => Some(PartitionMode::Auto),
+                _ => Some(mode),
+            };
+            for (left_key, right_key) in hash_join.on() {
+                let (left_input, left) =
+                    key_input(left_key, &offsets[left_start..right_start])?;
+                let (right_input, right) = key_input(right_key, &offsets[right_start..])?;
+                self.edges.push(JoinEdge {
+                    left_input: left_start + left_input,
+                    left,
+                    right_input: right_start + right_input,
+                    right,
+                });
+            }
+        }
+        Some(JoinTree::Join(Box::new(left), Box::new(right)))
+    }
+
+    /// Replaces the inputs of the join graph
+    fn map_inputs(
+        mut self,
+        f: impl Fn(Arc<dyn ExecutionPlan>) -> Result<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Self> {
+        self.inputs = self.inputs.into_iter().map(f).collect::<Result<_>>()?;
+        Ok(self)
+    }
+
+    /// Returns the reordered join tree, or `None` if the written join order
+    /// has the lowest cost, or the inputs do not have the required statistics
+    fn reorder(&self, dp_threshold: usize) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+        let mut input_plans = Vec::with_capacity(self.inputs.len());
+        for (index, input) in self.inputs.iter().enumerate() {
+            let statistics = input.partition_statistics(None)?;
+            if statistics.num_rows.get_value().is_none() {
+                return Ok(None);
+            }
+            input_plans.push(JoinPlan {
+                tree: JoinTree::Input(index),
+                inputs: vec![index],
+                schema: input.schema(),
+                statistics,
+                cost: 0,
+            });
+        }
+
+        let written = self.estimate_tree(&self.tree, &input_plans)?;
+        let best = if self.inputs.len() <= dp_threshold {
+            self.dynamic_programming(input_plans)?
+        } else {
+            self.greedy(input_plans)?
+        };
+        if best.cost >= written.cost {
+            return Ok(None);
+        }
+        self.build_plan(&best).map(Some)
+    }
+
+    /// Returns the cost of the given join tree
+    fn estimate_tree(
+        &self,
+        tree: &JoinTree,
+        input_plans: &[JoinPlan],
+    ) -> Result<JoinPlan> {
+        match tree {
+            JoinTree::Input(input) => Ok(input_plans[*input].clone()),
+            JoinTree::Join(left, right) => {
+                let left = self.estimate_tree(left, input_plans)?;
+                let right = self.estimate_tree(right, input_plans)?;
+                self.join(&left, &right)
+            }
+        }
+    }
+
+    /// Finds the join tree with the lowest cost among all join trees, joining
+    /// subtrees without equijoin keys between them only if necessary
+    fn dynamic_programming(&self, input_plans: Vec<JoinPlan>) -> Result<JoinPlan> {
+        let num_inputs = input_plans.len();
+        let mut best: HashMap<usize, JoinPlan> = input_plans
+            .into_iter()
+            .enumerate()
+            .map(|(index, plan)| (1 << index, plan))
+            .collect();
+
+        // Subsets of a set are smaller than the set itself, so they are
+        // visited first
+        for set in 1_usize..(1 << num_inputs) {
+            if set.count_ones() < 2 {
+                continue;
+            }
+            let lowest = set & set.wrapping_neg();
+            let mut connected_best: Option<JoinPlan> = None;
+            let mut cross_best: Option<JoinPlan> = None;
+            // The left subset contains the lowest input, so that every split
+            // is visited once
+            let mut left_set = (set - 1) & set;
+            while left_set > 0 {
+                let right_set = set & !left_set;
+                if left_set & lowest != 0 {
+                    let (left, right) = (&best[&left_set], &best[&right_set]);
+                    let candidate = self.join(left, right)?;
+                    let slot = if self.is_connected(left_set, right_set) {
+                        &mut connected_best
+                    } else {
+                        &mut cross_best
+                    };
+                    if slot.as_ref().is_none_or(|plan| candidate.cost < plan.cost) {
+                        *slot = Some(candidate);
+                    }
+                }
+                left_set = (left_set - 1) & set;
+            }
+            if let Some(plan) = connected_best.or(cross_best) {
+                best.insert(set, plan);
+            }
+        }
+        Ok(best.remove(&((1 << num_inputs) - 1)).unwrap())
+    }
+
+    /// Repeatedly joins the two join trees with the smallest estimated join
+    /// output, preferring join trees connected by equijoin keys
+    fn greedy(&self, mut plans: Vec<JoinPlan>) -> Result<JoinPlan> {
+        while plans.len() > 1 {
+            let mut best: Option<(bool, usize, usize, JoinPlan)> = None;
+            for i in 0..plans.len() {
+                for j in i + 1..plans.len() {
+                    let connected = self.edges.iter().any(|edge| {
+                        let (left, right) = (&plans[i].inputs, &plans[j].inputs);
+                        (left.contains(&edge.left_input)
+                            && right.contains(&edge.right_input))
+                            || (left.contains(&edge.right_input)
+                                && right.contains(&edge.left_input))
+                    });
+                    let candidate = self.join(&plans[i], &plans[j])?;
+                    let rows = output_rows(&candidate.statistics);
+                    let better =
+                        best.as_ref().is_none_or(|(best_connected, _, _, plan)| {
+                            (connected, std::cmp::Reverse(rows))
+                                > (
+                                    *best_connected,
+                                    std::cmp::Reverse(output_rows(&plan.statistics)),
+                                )
+                        });
+                    if better {
+                        best = Some((connected, i, j, candidate));
+                    }
+                }
+            }
+            let (_, i, j, plan) = best.unwrap();
+            plans.remove(j);
+            plans[i] = plan;
+        }
+        Ok(plans.pop().unwrap())
+    }
+
+    /// Returns whether there are equijoin keys between the given sets of
+    /// inputs, which are bitmasks of the input indices
+    fn is_connected(&self, left_set: usize, right_set: usize) -> bool {
+        self.edges.iter().any(|edge| {
+            let (left, right) = (1 << edge.left_input, 1 << edge.right_input);
+            (left_set & left != 0 && right_set & right != 0)
+                || (left_set & right != 0 && right_set & left != 0)
+        })
+    }
+
+    /// Joins two join trees, and estimates the statistics of the join
+    fn join(&self, left: &JoinPlan, right: &JoinPlan) -> Result<JoinPlan> {
+        let on = self.join_on(left, right);
+        let schema = Arc::new(Schema::new(
+            left.schema
+                .fields()
+                .iter()
+                .chain(right.schema.fields().iter())
+                .cloned()
+                .collect::<Vec<_>>(),
+        ));
+        let statistics = if on.is_empty() {
+            let num_rows = left
+                .statistics
+                .num_rows
+                .multiply(&right.statistics.num_rows);
+            Statistics {
+                num_rows,
+                total_byte_size: Precision::Absent,
+                column_statistics: Statistics::unknown_column(&schema),
+            }
+        } else {
+            estimate_join_statistics(
+                left.statistics.clone(),
+                right.statistics.clone(),
+                on,
+                &JoinType::Inner,
+                &schema,
+            )?
+        };
+        let cost = left
+            .cost
+            .saturating_add(right.cost)
+            .saturating_add(output_rows(&statistics));
+        Ok(JoinPlan {
+            tree: JoinTree::Join(
+                Box::new(left.tree.clone()),
+                Box::new(right.tree.clone()),
+            ),
+            inputs: left.inputs.iter().chain(&right.inputs).copied().collect(),
+            schema,
+            statistics,
+            cost,
+        })
+    }
+
+    /// Returns the equijoin keys between two join trees, referring to the
+    /// output columns of the join trees
+    fn join_on(&self, left: &JoinPlan, right: &JoinPlan) -> JoinOn {
+        let left_offsets = self.input_offsets(&left.inputs);
+        let right_offsets = self.input_offsets(&right.inputs);
+        self.edges
+            .iter()
+            .filter_map(|edge| {
+                let (left_key, right_key) = match (
+                    left_offsets.get(&edge.left_input),
+                    right_offsets.get(&edge.right_input),
+                    left_offsets.get(&edge.right_input),
+                    right_offsets.get(&edge.left_input),
+                ) {
+                    (Some(left_offset), Some(right_offset), _, _) => (
+                        shift_columns(&edge.left, *left_offset),
+                        shift_columns(&edge.right, *right_offset),
+                    ),
+                    (_, _, Some(left_offset), Some(right_offset)) => (
+                        shift_columns(&edge.right, *left_offset),
+                        shift_columns(&edge.left, *right_offset),
+                    ),
+                    _ => return None,
+                };
+                Some((left_key, right_key))
+            })
+            .collect()
+    }
+
+    /// Returns the index of the first output column of each input, when the
+    /// inputs are joined in the given order
+    fn input_offsets(&self, inputs: &[usize]) -> HashMap<usize, usize> {
+        let mut offset = 0;
+        inputs
+            .iter()
+            .map(|input| {
+                let input_offset = offset;
+                offset += self.inputs[*input].schema().fields().len();
+                (*input, input_offset)
+            })
+            .collect()
+    }
+
+    /// Returns the written join tree `plan` with the (possibly rewritten)
+    /// inputs of this graph
+    fn with_written_order(
+        &self,
+        plan: &Arc<dyn ExecutionPlan>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        fn replace_inputs(
+            plan: &Arc<dyn ExecutionPlan>,
+            inputs: &mut impl Iterator<Item = Arc<dyn ExecutionPlan>>,
+        ) -> Result<Arc<dyn ExecutionPlan>> {
+            if !is_reorderable_join(plan) {
+                return inputs
+                    .next()
+                    .ok_or_else(|| internal_datafusion_err!("Missing join tree input"));
+            }
+            let children = plan
+                .children()
+                .into_iter()
+                .map(|child| replace_inputs(child, inputs))
+                .collect::<Result<Vec<_>>>()?;
+            with_new_children_if_necessary(Arc::clone(plan), children)
+        }
+        replace_inputs(plan, &mut self.inputs.iter().cloned())
+    }
+
+    /// Creates the joins of the given join tree, and restores the order of the
+    /// output columns of the written join tree
+    fn build_plan(&self, plan: &JoinPlan) -> Result<Arc<dyn ExecutionPlan>> {
+        let (join, _) = self.build_tree(&plan.tree)?;
+
+        let written_inputs = {
+            let mut inputs = vec![];
+            self.tree.collect_inputs(&mut inputs);
+            inputs
+        };
+        if written_inputs == plan.inputs {
+            return Ok(join);
+        }
+
+        let offsets = self.input_offsets(&plan.inputs);
+        let schema = join.schema();
+        let exprs = written_inputs
+            .iter()
+            .flat_map(|input| {
+                let offset = offsets[input];
+                let schema = Arc::clone(&schema);
+                (0..self.inputs[*input].schema().fields().len()).map(move |index| {
+                    let field = schema.field(offset + index);
+                    (
+                        Arc::new(Column::new(field.name(), offset + index))
+                            as Arc<dyn PhysicalExpr>,
+                        field.name().to_string(),
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+        Ok(Arc::new(ProjectionExec::try_new(exprs, join)?))
+    }
+
+    /// Creates the joins of the given join tree, returning the inputs in the
+    /// order of the output columns
+    fn build_tree(&self, tree: &JoinTree) -> Result<(Arc<dyn ExecutionPlan>, JoinPlan)> {
+        match tree {
+            JoinTree::Input(input) => {
+                let plan = Arc::clone(&self.inputs[*input]);
+                let join_plan = JoinPlan {
+                    tree: tree.clone(),
+                    inputs: vec![*input],
+                    schema: plan.schema(),
+                    statistics: Statistics::new_unknown(&plan.schema()),
+                    cost: 0,
+                };
+                Ok((plan, join_plan))
+            }
+            JoinTree::Join(left, right) => {
+                let (left, left_plan) = self.build_tree(left)?;
+                let (right, right_plan) = self.build_tree(right)?;
+                let on = self.join_on(&left_plan, &right_plan);
+                let join: Arc<dyn ExecutionPlan> = if on.is_empty() {
+                    Arc::new(CrossJoinExec::new(left, right))
+                } else {
+                    Arc::new(HashJoinExec::try_new(
+                        left,
+                        right,
+                        on,
+                        None,
+                        &JoinType::Inner,
+                        None,
+                        // Let `JoinSelection` pick the mode of each join if
+                        // the written joins do not agree on one
+                        self.partition_mode.unwrap_or(PartitionMode::Auto),
+                        NullEquality::NullEqualsNothing,
+                    )?)
+                };
+                let join_plan = JoinPlan {
+                    tree: tree.clone(),
+                    inputs: left_plan
+                        .inputs
+                        .into_iter()
+                        .chain(right_plan.inputs)
+                        .collect(),
+                    schema: join.schema(),
+                    statistics: Statistics::new_unknown(&join.schema()),
+                    cost: 0,
+                };
+                Ok((join, join_plan))
+            }
+        }
+    }
+}
+
+/// Returns whether `plan` is a join that can be reordered with the joins
+/// directly below it
+fn is_reorderable_join(plan: &Arc<dyn ExecutionPlan>) -> bool {
+    if let Some(hash_join) = plan.as_any().downcast_ref::<HashJoinExec>() {
+        hash_join.join_type() == &JoinType::Inner
+            && hash_join.filter().is_none()
+            && hash_join.projection.is_none()
+            && hash_join.null_equality() == NullEquality::NullEqualsNothing
+    } else {
+        plan.as_any().is::<CrossJoinExec>()
+    }
+}
+
+/// Returns the input a join key refers to, and the key referring to the
+/// columns of that input. `offsets` are the indices of the first column of the
+/// inputs of the join side. Returns `None` if the key refers to more than one
+/// input.
+fn key_input(
+    key: &Arc<dyn PhysicalExpr>,
+    offsets: &[usize],
+) -> Option<(usize, Arc<dyn PhysicalExpr>)> {
+    let base = offsets[0];
+    let input_of = |column: &Column| {
+        offsets.partition_point(|offset| *offset <= base + column.index()) - 1
+    };
+    let mut inputs = collect_columns(key)
+        .iter()
+        .map(input_of)
+        .collect::<Vec<_>>();
+    inputs.dedup();
+    let [input] = inputs[..] else {
+        return None;
+    };
+    let shift = offsets[input] - base;
+    let key = Arc::clone(key)
+        .transform(|expr| {
+            Ok(match expr.as_any().downcast_ref::<Column>() {
+                Some(column) => Transformed::yes(Arc::new(Column::new(
+                    column.name(),
+                    column.index() - shift,
+                )) as _),
+                None => Transformed::no(expr),
+            })
+        })
+        .data()
+        .ok()?;
+    Some((input, key))
+}
+
+/// Adds `offset` to the indices of all columns in `expr`
+fn shift_columns(expr: &Arc<dyn PhysicalExpr>, offset: usize) -> Arc<dyn PhysicalExpr> {
+    Arc::clone(expr)
+        .transform(|expr| {
+            Ok(match expr.as_any().downcast_ref::<Column>() {
+                Some(column) => Transformed::yes(Arc::new(Column::new(
+                    column.name(),
+                    column.index() + offset,
+                )) as _),
+                None => Transformed::no(expr),
+            })
+        })
+        .data()
+        .unwrap()
+}
+
+/// Returns the estimated number of output rows, saturating when unknown
+fn output_rows(statistics: &Statistics) -> usize {
+    statistics
+        .num_rows
+        .get_value()
+        .copied()
+        .unwrap_or(usize::MAX)
+}
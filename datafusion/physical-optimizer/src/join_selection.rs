@@ -34,7 +34,7 @@ use datafusion_physical_expr::LexOrdering;
 use datafusion_physical_plan::execution_plan::EmissionType;
 use datafusion_physical_plan::joins::utils::ColumnIndex;
 use datafusion_physical_plan::joins::{
-    CrossJoinExec, HashJoinExec, NestedLoopJoinExec, PartitionMode,
+    CrossJoinExec, HashJoinExec, IEJoinExec, NestedLoopJoinExec, PartitionMode,
     StreamJoinPartitionMode, SymmetricHashJoinExec,
 };
 use datafusion_physical_plan::{ExecutionPlan, ExecutionPlanProperties};
@@ -306,6 +306,16 @@ fn statistical_join_selection_subrule(
             } else {
                 None
             }
+        } else if let Some(ie_join) = plan.as_any().downcast_ref::<IEJoinExec>() {
+            let left = ie_join.left();
+            let right = ie_join.right();
+            if ie_join.join_type().supports_swap()
+                && should_swap_join_order(&**left, &**right)?
+            {
+                ie_join.swap_inputs().map(Some)?
+            } else {
+                None
+            }
         } else {
             None
         };
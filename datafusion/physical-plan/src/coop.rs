@@ -64,6 +64,12 @@
 //!
 //! The optimizer rule currently checks the plan for exchange-like operators and leave operators
 //! that report [`SchedulingType::NonCooperative`] in their [plan properties](ExecutionPlan::properties).
+//!
+//! ## Deadlines
+//!
+//! Since yield points are reached regularly, [`CooperativeStream`]s are also where long running
+//! queries observe their deadline, see [`TaskContext::deadline`] and
+//! [`CooperativeStream::with_deadline`].
 
 use datafusion_common::config::ConfigOptions;
 use datafusion_physical_expr::PhysicalExpr;
@@ -85,18 +91,25 @@ use crate::{
 };
 use arrow::record_batch::RecordBatch;
 use arrow_schema::Schema;
+use datafusion_common::instant::Instant;
 use datafusion_common::{internal_err, Result, Statistics};
 use datafusion_execution::TaskContext;
 
 use crate::execution_plan::SchedulingType;
-use crate::stream::RecordBatchStreamAdapter;
+use crate::stream::{deadline_exceeded_error, RecordBatchStreamAdapter};
 use futures::{Stream, StreamExt};
 
 /// A stream that passes record batches through unchanged while cooperating with the Tokio runtime.
 /// It consumes cooperative scheduling budget for each returned [`RecordBatch`],
 /// allowing other tasks to execute when the budget is exhausted.
 ///
+/// If the stream has a deadline (see [`Self::with_deadline`]), it also checks the deadline
+/// every time it is polled and returns a [`DataFusionError::Timeout`] error once it has
+/// passed, so that long running queries are cancelled at their next yield point.
+///
 /// See the [module level documentation](crate::coop) for an in-depth discussion.
+///
+/// [`DataFusionError::Timeout`]: datafusion_common::DataFusionError::Timeout
 pub struct CooperativeStream<T>
 where
     T: RecordBatchStream + Unpin,
@@ -104,6 +117,10 @@ where
     inner: T,
     #[cfg(datafusion_coop = "per_stream")]
     budget: u8,
+    /// Point in time after which the stream returns an error
+    deadline: Option<Instant>,
+    /// Set once the deadline error was returned
+    timed_out: bool,
 }
 
 #[cfg(datafusion_coop = "per_stream")]
@@ -122,8 +139,19 @@ where
             inner,
             #[cfg(datafusion_coop = "per_stream")]
             budget: YIELD_FREQUENCY,
+            deadline: None,
+            timed_out: false,
         }
     }
+
+    /// Cancels the stream with a [`DataFusionError::Timeout`] error once `deadline`
+    /// has passed, typically the [`TaskContext::deadline`] of the query
+    ///
+    /// [`DataFusionError::Timeout`]: datafusion_common::DataFusionError::Timeout
+    pub fn with_deadline(mut self, deadline: Option<Instant>) -> Self {
+        self.deadline = deadline;
+        self
+    }
 }
 
 impl<T> Stream for CooperativeStream<T>
@@ -136,6 +164,17 @@ where
         mut self: Pin<&mut Self>,
         cx: &mut Context<'_>,
     ) -> Poll<Option<Self::Item>> {
+        if self.timed_out {
+            return Poll::Ready(None);
+        }
+        if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            self.timed_out = true;
+            return Poll::Ready(Some(Err(deadline_exceeded_error())));
+        }
+
         #[cfg(any(
             datafusion_coop = "tokio",
             not(any(
@@ -280,8 +319,14 @@ impl ExecutionPlan for CooperativeExec {
         partition: usize,
         task_ctx: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
-        let child_stream = self.input.execute(partition, task_ctx)?;
-        Ok(make_cooperative(child_stream))
+        let child_stream = self.input.execute(partition, Arc::clone(&task_ctx))?;
+        Ok(Box::pin(
+            cooperative(RecordBatchStreamAdapter::new(
+                child_stream.schema(),
+                child_stream,
+            ))
+            .with_deadline(task_ctx.deadline()),
+        ))
     }
 
     fn partition_statistics(&self, partition: Option<usize>) -> Result<Statistics> {
@@ -344,6 +389,7 @@ mod tests {
     use crate::stream::RecordBatchStreamAdapter;
 
     use arrow_schema::SchemaRef;
+    use datafusion_common::DataFusionError;
 
     use futures::{stream, StreamExt};
 
@@ -381,6 +427,22 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn cancel_at_deadline() -> Result<()> {
+        let inner = RecordBatchStreamAdapter::new(
+            Arc::new(Schema::empty()),
+            make_empty_batches(10),
+        );
+        let deadline = Instant::now() - std::time::Duration::from_millis(1);
+        let out = cooperative(inner)
+            .with_deadline(Some(deadline))
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(out.len(), 1);
+        assert!(matches!(out[0], Err(DataFusionError::Timeout(_))));
+        Ok(())
+    }
+
     #[tokio::test]
     async fn yield_more_than_threshold() -> Result<()> {
         let count = TASK_BUDGET + 20;
@@ -37,16 +37,19 @@ pub use datafusion_physical_expr::{
 use std::any::Any;
 use std::fmt::Debug;
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::coalesce_partitions::CoalescePartitionsExec;
 use crate::display::DisplayableExecutionPlan;
+use crate::metrics::ExportMetricsOnDrop;
 use crate::metrics::MetricsSet;
 use crate::projection::ProjectionExec;
-use crate::stream::RecordBatchStreamAdapter;
+use crate::stream::{DeadlineStream, RecordBatchStreamAdapter};
 
 use arrow::array::{Array, RecordBatch};
 use arrow::datatypes::SchemaRef;
 use datafusion_common::config::ConfigOptions;
+use datafusion_common::instant::Instant;
 use datafusion_common::{exec_err, Constraints, DataFusionError, Result};
 use datafusion_common_runtime::JoinSet;
 use datafusion_execution::TaskContext;
@@ -486,6 +489,16 @@ pub trait ExecutionPlan: Debug + DisplayAs + Send + Sync {
         Ok(Statistics::new_unknown(&self.schema()))
     }
 
+    /// Returns the estimated total size, in bytes, of the input that this
+    /// `ExecutionPlan` node reads from outside of the plan, such as the files
+    /// it scans, or `None` (the default) if it is unknown.
+    ///
+    /// Used to report the progress of running queries, see
+    /// [`crate::progress`].
+    fn estimated_input_bytes(&self) -> Option<usize> {
+        None
+    }
+
     /// Returns `true` if a limit can be safely pushed down through this
     /// `ExecutionPlan` node.
     ///
@@ -1158,20 +1171,67 @@ pub async fn collect(
 ///
 /// Dropping the stream will abort the execution of the query, and free up
 /// any allocated resources
+///
+/// # Deadline
+///
+/// The `datafusion.execution.query_timeout` of the query starts when this
+/// function is called, unless the [`TaskContext`] already has a
+/// [deadline](TaskContext::deadline). The stream returns a
+/// [`DataFusionError::Timeout`] error once the deadline has passed, see
+/// [`DeadlineStream`]. Setting a timeout requires a Tokio runtime with the time
+/// driver enabled.
+///
+/// # Metrics
+///
+/// If the [`RuntimeEnv`] has a [`MetricsExporter`], the metrics of the plan
+/// are exported once the stream is dropped.
+///
+/// [`DataFusionError::Timeout`]: datafusion_common::DataFusionError::Timeout
+/// [`RuntimeEnv`]: datafusion_execution::runtime_env::RuntimeEnv
+/// [`MetricsExporter`]: datafusion_execution::metrics_exporter::MetricsExporter
 pub fn execute_stream(
     plan: Arc<dyn ExecutionPlan>,
     context: Arc<TaskContext>,
 ) -> Result<SendableRecordBatchStream> {
-    match plan.output_partitioning().partition_count() {
-        0 => Ok(Box::pin(EmptyRecordBatchStream::new(plan.schema()))),
-        1 => plan.execute(0, context),
+    let context = start_query_timeout(context);
+    let (plan, stream) = match plan.output_partitioning().partition_count() {
+        0 => {
+            let stream = Box::pin(EmptyRecordBatchStream::new(plan.schema())) as _;
+            (plan, stream)
+        }
+        1 => {
+            let stream = plan.execute(0, Arc::clone(&context))?;
+            (plan, stream)
+        }
         2.. => {
             // merge into a single partition
-            let plan = CoalescePartitionsExec::new(Arc::clone(&plan));
+            let plan: Arc<dyn ExecutionPlan> =
+                Arc::new(CoalescePartitionsExec::new(Arc::clone(&plan)));
             // CoalescePartitionsExec must produce a single partition
             assert_eq!(1, plan.properties().output_partitioning().partition_count());
-            plan.execute(0, context)
+            let stream = plan.execute(0, Arc::clone(&context))?;
+            (plan, stream)
         }
+    };
+    let stream = match ExportMetricsOnDrop::try_new(&plan, &context) {
+        Some(exporter) => exporter.attach(stream),
+        None => stream,
+    };
+    DeadlineStream::wrap(stream, &context)
+}
+
+/// Starts the `datafusion.execution.query_timeout` of a query that is about to
+/// be executed, unless `context` already has a deadline
+fn start_query_timeout(context: Arc<TaskContext>) -> Arc<TaskContext> {
+    if context.deadline().is_some() {
+        return context;
+    }
+    match context.session_config().options().execution.query_timeout {
+        Some(timeout) => {
+            let deadline = Instant::now().checked_add(Duration::from_millis(timeout));
+            Arc::new(TaskContext::clone(&context).with_deadline(deadline))
+        }
+        None => context,
     }
 }
 
@@ -1222,14 +1282,24 @@ pub async fn collect_partitioned(
 ///
 /// Dropping the stream will abort the execution of the query, and free up
 /// any allocated resources
+///
+/// Like [`execute_stream`], the streams observe the deadline of the
+/// [`TaskContext`], and the metrics of the plan are exported once all of them
+/// are dropped.
 pub fn execute_stream_partitioned(
     plan: Arc<dyn ExecutionPlan>,
     context: Arc<TaskContext>,
 ) -> Result<Vec<SendableRecordBatchStream>> {
+    let context = start_query_timeout(context);
     let num_partitions = plan.output_partitioning().partition_count();
+    let exporter = ExportMetricsOnDrop::try_new(&plan, &context);
     let mut streams = Vec::with_capacity(num_partitions);
     for i in 0..num_partitions {
-        streams.push(plan.execute(i, Arc::clone(&context))?);
+        let mut stream = plan.execute(i, Arc::clone(&context))?;
+        if let Some(exporter) = &exporter {
+            stream = exporter.attach(stream);
+        }
+        streams.push(DeadlineStream::wrap(stream, &context)?);
     }
     Ok(streams)
 }
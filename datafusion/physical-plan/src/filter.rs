@@ -51,6 +51,7 @@ use datafusion_common::{
     internal_err, plan_err, project_schema, DataFusionError, Result, ScalarValue,
 };
 use datafusion_execution::TaskContext;
+use datafusion_expr::interval_arithmetic::cardinality_ratio;
 use datafusion_expr::Operator;
 use datafusion_physical_expr::equivalence::ProjectionMapping;
 use datafusion_physical_expr::expressions::{lit, BinaryExpr, Column};
@@ -201,10 +202,17 @@ impl FilterExec {
             &input_stats.column_statistics,
         )?;
 
+        let initial_boundaries = input_analysis_ctx.boundaries.clone();
         let analysis_ctx = analyze(predicate, input_analysis_ctx, &schema)?;
 
         // Estimate (inexact) selectivity of predicate
-        let selectivity = analysis_ctx.selectivity.unwrap_or(1.0);
+        let selectivity = histogram_selectivity(
+            &initial_boundaries,
+            &analysis_ctx.boundaries,
+            &input_stats.column_statistics,
+        )
+        .or(analysis_ctx.selectivity)
+        .unwrap_or(1.0);
         let num_rows = num_rows.with_estimated_selectivity(selectivity);
         let total_byte_size = total_byte_size.with_estimated_selectivity(selectivity);
 
@@ -565,6 +573,39 @@ impl EmbeddedProjection for FilterExec {
     }
 }
 
+/// Estimates the selectivity of a predicate from the initial and pruned
+/// boundaries of the columns, using the histograms of the columns analyzed
+/// with `ANALYZE TABLE` instead of assuming that their values are uniformly
+/// distributed. Returns `None` if no constrained column has a histogram.
+fn histogram_selectivity(
+    initial_boundaries: &[ExprBoundaries],
+    target_boundaries: &[ExprBoundaries],
+    column_statistics: &[ColumnStatistics],
+) -> Option<f64> {
+    let mut uses_histogram = false;
+    let mut selectivity = 1.0;
+    for ((initial, target), stats) in initial_boundaries
+        .iter()
+        .zip(target_boundaries)
+        .zip(column_statistics)
+    {
+        let (Some(initial), Some(target)) = (&initial.interval, &target.interval) else {
+            return Some(0.0);
+        };
+        if initial == target {
+            continue;
+        }
+        selectivity *= match &stats.histogram {
+            Some(histogram) => {
+                uses_histogram = true;
+                histogram.estimate_range(target.lower(), target.upper())?
+            }
+            None => cardinality_ratio(initial, target),
+        };
+    }
+    uses_histogram.then_some(selectivity)
+}
+
 /// This function ensures that all bounds in the `ExprBoundaries` vector are
 /// converted to closed bounds. If a lower/upper bound is initially open, it
 /// is adjusted by using the next/previous value for its data type to convert
@@ -593,6 +634,7 @@ fn collect_new_statistics(
                         min_value: Precision::Exact(ScalarValue::Null),
                         sum_value: Precision::Exact(ScalarValue::Null),
                         distinct_count: Precision::Exact(0),
+                        histogram: None,
                     };
                 };
                 let (lower, upper) = interval.into_bounds();
@@ -607,6 +649,7 @@ fn collect_new_statistics(
                     min_value,
                     sum_value: Precision::Absent,
                     distinct_count: distinct_count.to_inexact(),
+                    histogram: None,
                 }
             },
         )
@@ -765,7 +808,7 @@ mod tests {
     use crate::test;
     use crate::test::exec::StatisticsExec;
     use arrow::datatypes::{Field, Schema, UnionFields, UnionMode};
-    use datafusion_common::ScalarValue;
+    use datafusion_common::{ColumnHistogram, HistogramBucket, ScalarValue};
 
     #[tokio::test]
     async fn collect_columns_predicates() -> Result<()> {
@@ -857,6 +900,47 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_filter_statistics_histogram() -> Result<()> {
+        // Table:
+        //      a: min=1, max=100, 90% of the values are at most 25
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, false)]);
+        let bucket = |lower, upper, num_values, distinct_count| HistogramBucket {
+            lower: ScalarValue::Int32(Some(lower)),
+            upper: ScalarValue::Int32(Some(upper)),
+            num_values,
+            distinct_count,
+        };
+        let histogram = ColumnHistogram {
+            num_rows: 100,
+            buckets: vec![bucket(1, 25, 90, 25), bucket(26, 100, 10, 75)],
+            most_common_values: vec![],
+        };
+        let input = Arc::new(StatisticsExec::new(
+            Statistics {
+                num_rows: Precision::Inexact(100),
+                total_byte_size: Precision::Absent,
+                column_statistics: vec![ColumnStatistics {
+                    min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
+                    max_value: Precision::Inexact(ScalarValue::Int32(Some(100))),
+                    ..Default::default()
+                }
+                .with_histogram(Some(Arc::new(histogram)))],
+            },
+            schema.clone(),
+        ));
+
+        // WHERE a <= 25
+        let predicate: Arc<dyn PhysicalExpr> =
+            binary(col("a", &schema)?, Operator::LtEq, lit(25i32), &schema)?;
+        let filter: Arc<dyn ExecutionPlan> =
+            Arc::new(FilterExec::try_new(predicate, input)?);
+
+        let statistics = filter.partition_statistics(None)?;
+        assert_eq!(statistics.num_rows, Precision::Inexact(90));
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_filter_statistics_column_level_nested() -> Result<()> {
         // Table:
@@ -1232,6 +1316,7 @@ mod tests {
                     sum_value: Precision::Exact(ScalarValue::Null),
                     distinct_count: Precision::Exact(0),
                     null_count: Precision::Exact(0),
+                    histogram: None,
                 },
                 ColumnStatistics {
                     min_value: Precision::Exact(ScalarValue::Null),
@@ -1239,6 +1324,7 @@ mod tests {
                     sum_value: Precision::Exact(ScalarValue::Null),
                     distinct_count: Precision::Exact(0),
                     null_count: Precision::Exact(0),
+                    histogram: None,
                 },
             ]
         );
@@ -1340,6 +1426,7 @@ mod tests {
                 max_value: Precision::Inexact(ScalarValue::Int32(Some(10))),
                 sum_value: Precision::Absent,
                 distinct_count: Precision::Absent,
+                histogram: None,
             }],
         };
 
@@ -0,0 +1,1139 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the AsOf join execution plan. An AsOf join consumes two sorted
+//! children plans and joins every left row with the closest matching right row.
+
+use std::any::Any;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Formatter;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use crate::execution_plan::{boundedness_from_children, EmissionType};
+use crate::expressions::PhysicalSortExpr;
+use crate::joins::utils::{
+    build_join_schema, check_join_is_valid, estimate_join_statistics,
+    symmetric_join_output_partitioning, JoinOn, JoinOnRef,
+};
+use crate::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use crate::spill::get_record_batch_memory_size;
+use crate::{
+    DisplayAs, DisplayFormatType, Distribution, ExecutionPlan, ExecutionPlanProperties,
+    PlanProperties, RecordBatchStream, SendableRecordBatchStream, Statistics,
+};
+
+use arrow::array::{new_empty_array, new_null_array, Array, ArrayRef, Scalar};
+use arrow::buffer::NullBuffer;
+use arrow::compute::kernels::cmp::{gt_eq, lt_eq};
+use arrow::compute::kernels::numeric::{add, sub};
+use arrow::compute::{interleave, SortOptions};
+use arrow::datatypes::{DataType, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use arrow::row::{Row, RowConverter, Rows, SortField};
+use datafusion_common::{
+    internal_err, plan_err, JoinSide, JoinType, Result, ScalarValue,
+};
+use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
+use datafusion_execution::TaskContext;
+use datafusion_expr::Operator;
+use datafusion_physical_expr::equivalence::join_equivalence_properties;
+use datafusion_physical_expr_common::physical_expr::{fmt_sql, PhysicalExprRef};
+use datafusion_physical_expr_common::sort_expr::{LexOrdering, OrderingRequirements};
+use futures::{ready, Stream, StreamExt};
+
+/// Join execution plan that matches every row of the left input with the
+/// closest row of the right input, e.g. every trade with the latest quote at
+/// or before the time of the trade.
+///
+/// # Join Expressions
+///
+/// The optional equi-join predicates (e.g. `<symbol> = <symbol>`) in
+/// [`Self::on`] partition both inputs: a left row is only matched with right
+/// rows that have the same join key values.
+///
+/// The match condition compares an expression on the left input with an
+/// expression on the right input, e.g. `<left ts> >= <right ts>`:
+///
+/// * For `>=` and `>`, a left row is matched with the right row that has the
+///   largest value that satisfies the condition.
+/// * For `<=` and `<`, a left row is matched with the right row that has the
+///   smallest value that satisfies the condition.
+///
+/// An optional tolerance limits how far the matched right value may be from
+/// the left value. Left rows without a match (including rows with null join
+/// keys or null match values) are returned with nulls for the right columns,
+/// so every left row is returned exactly once.
+///
+/// # Sorting
+///
+/// Both inputs must be sorted by the join keys followed by the match
+/// expression. Like [`SortMergeJoinExec`], it is not the responsibility of
+/// this execution plan to sort the inputs.
+///
+/// The left input is streamed and its order is maintained. Only the right
+/// batches that may still be matched are kept in memory.
+///
+/// [`SortMergeJoinExec`]: crate::joins::SortMergeJoinExec
+#[derive(Debug, Clone)]
+pub struct AsOfJoinExec {
+    /// Left sorted joining execution plan
+    left: Arc<dyn ExecutionPlan>,
+    /// Right sorted joining execution plan
+    right: Arc<dyn ExecutionPlan>,
+    /// Set of common columns used to join on
+    on: JoinOn,
+    /// Expression on the left input compared by the match condition
+    left_match: PhysicalExprRef,
+    /// Comparison of `left_match` with `right_match`
+    match_op: Operator,
+    /// Expression on the right input compared by the match condition
+    right_match: PhysicalExprRef,
+    /// Maximum distance between the left and the matched right value
+    tolerance: Option<ScalarValue>,
+    /// The schema once the join is applied
+    schema: SchemaRef,
+    /// Required ordering of the left input
+    left_sort_exprs: LexOrdering,
+    /// Required ordering of the right input
+    right_sort_exprs: LexOrdering,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+    /// Cache holding plan properties like equivalences, output partitioning etc.
+    cache: PlanProperties,
+}
+
+impl AsOfJoinExec {
+    /// Tries to create a new [`AsOfJoinExec`] that matches every left row with
+    /// the closest right row for which `left_match match_op right_match` holds.
+    ///
+    /// # Error
+    /// This function errors when `match_op` is not one of `>=`, `>`, `<=` or
+    /// `<`, when the types of the join keys or the match expressions of both
+    /// sides differ, or when the tolerance can not be applied to the left
+    /// match values.
+    pub fn try_new(
+        left: Arc<dyn ExecutionPlan>,
+        right: Arc<dyn ExecutionPlan>,
+        on: JoinOn,
+        left_match: PhysicalExprRef,
+        match_op: Operator,
+        right_match: PhysicalExprRef,
+        tolerance: Option<ScalarValue>,
+    ) -> Result<Self> {
+        let left_schema = left.schema();
+        let right_schema = right.schema();
+
+        check_join_is_valid(&left_schema, &right_schema, &on)?;
+        if !matches!(
+            match_op,
+            Operator::Gt | Operator::GtEq | Operator::Lt | Operator::LtEq
+        ) {
+            return plan_err!(
+                "AsOfJoinExec requires a >=, >, <= or < match condition, got {match_op}"
+            );
+        }
+        for (l, r) in &on {
+            let (left_type, right_type) =
+                (l.data_type(&left_schema)?, r.data_type(&right_schema)?);
+            if left_type != right_type {
+                return plan_err!(
+                    "AsOfJoinExec join keys {l} and {r} have different types: \
+                     {left_type} and {right_type}"
+                );
+            }
+        }
+        let left_match_type = left_match.data_type(&left_schema)?;
+        let right_match_type = right_match.data_type(&right_schema)?;
+        if left_match_type != right_match_type {
+            return plan_err!(
+                "AsOfJoinExec match expressions {left_match} and {right_match} have \
+                 different types: {left_match_type} and {right_match_type}"
+            );
+        }
+        let tolerance = tolerance
+            .map(|tolerance| validate_tolerance(tolerance, match_op, &left_match_type))
+            .transpose()?;
+
+        let (left_sort_exprs, right_sort_exprs): (Vec<_>, Vec<_>) = on
+            .iter()
+            .map(|(l, r)| (Arc::clone(l), Arc::clone(r)))
+            .chain([(Arc::clone(&left_match), Arc::clone(&right_match))])
+            .map(|(l, r)| {
+                (
+                    PhysicalSortExpr::new(l, SortOptions::default()),
+                    PhysicalSortExpr::new(r, SortOptions::default()),
+                )
+            })
+            .unzip();
+        let (Some(left_sort_exprs), Some(right_sort_exprs)) = (
+            LexOrdering::new(left_sort_exprs),
+            LexOrdering::new(right_sort_exprs),
+        ) else {
+            return plan_err!("AsOfJoinExec requires valid sort expressions");
+        };
+
+        let (schema, _) = build_join_schema(&left_schema, &right_schema, &JoinType::AsOf);
+        let schema = Arc::new(schema);
+        let cache = Self::compute_properties(&left, &right, Arc::clone(&schema), &on)?;
+
+        Ok(Self {
+            left,
+            right,
+            on,
+            left_match,
+            match_op,
+            right_match,
+            tolerance,
+            schema,
+            left_sort_exprs,
+            right_sort_exprs,
+            metrics: ExecutionPlanMetricsSet::new(),
+            cache,
+        })
+    }
+
+    /// Ref to left execution plan
+    pub fn left(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.left
+    }
+
+    /// Ref to right execution plan
+    pub fn right(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.right
+    }
+
+    /// Set of common columns used to join on
+    pub fn on(&self) -> &[(PhysicalExprRef, PhysicalExprRef)] {
+        &self.on
+    }
+
+    /// Expression on the left input compared by the match condition
+    pub fn left_match(&self) -> &PhysicalExprRef {
+        &self.left_match
+    }
+
+    /// Comparison operator of the match condition
+    pub fn match_op(&self) -> Operator {
+        self.match_op
+    }
+
+    /// Expression on the right input compared by the match condition
+    pub fn right_match(&self) -> &PhysicalExprRef {
+        &self.right_match
+    }
+
+    /// Maximum distance between the left and the matched right value
+    pub fn tolerance(&self) -> Option<&ScalarValue> {
+        self.tolerance.as_ref()
+    }
+
+    /// This function creates the cache object that stores the plan properties such as schema, equivalence properties, ordering, partitioning, etc.
+    fn compute_properties(
+        left: &Arc<dyn ExecutionPlan>,
+        right: &Arc<dyn ExecutionPlan>,
+        schema: SchemaRef,
+        join_on: JoinOnRef,
+    ) -> Result<PlanProperties> {
+        let eq_properties = join_equivalence_properties(
+            left.equivalence_properties().clone(),
+            right.equivalence_properties().clone(),
+            &JoinType::AsOf,
+            schema,
+            &[true, false],
+            Some(JoinSide::Left),
+            join_on,
+        )?;
+
+        let output_partitioning =
+            symmetric_join_output_partitioning(left, right, &JoinType::AsOf)?;
+
+        Ok(PlanProperties::new(
+            eq_properties,
+            output_partitioning,
+            EmissionType::Incremental,
+            boundedness_from_children([left, right]),
+        ))
+    }
+
+    fn match_condition_display(&self) -> String {
+        let tolerance = self
+            .tolerance
+            .as_ref()
+            .map_or_else(String::new, |t| format!(", tolerance={t}"));
+        format!(
+            "match_condition={} {} {}{tolerance}",
+            self.left_match, self.match_op, self.right_match
+        )
+    }
+}
+
+/// Casts the tolerance to the type of the left match values if needed, and
+/// checks that the bound for the right values can be computed from it
+fn validate_tolerance(
+    tolerance: ScalarValue,
+    match_op: Operator,
+    match_type: &DataType,
+) -> Result<ScalarValue> {
+    if tolerance.is_null() {
+        return plan_err!("AsOfJoinExec tolerance must not be null");
+    }
+    let tolerance = match tolerance.data_type() {
+        DataType::Interval(_) | DataType::Duration(_) => tolerance,
+        _ => tolerance.cast_to(match_type)?,
+    };
+    let values = new_empty_array(match_type);
+    match match_bound(&values, match_op, &tolerance) {
+        Ok(bound) if bound.data_type() == match_type => Ok(tolerance),
+        _ => plan_err!(
+            "AsOfJoinExec tolerance {tolerance} can not be applied to values of type \
+             {match_type}"
+        ),
+    }
+}
+
+/// Returns the furthest right values that are still within `tolerance` of the
+/// left `values`
+fn match_bound(
+    values: &ArrayRef,
+    match_op: Operator,
+    tolerance: &ScalarValue,
+) -> Result<ArrayRef> {
+    let tolerance = Scalar::new(tolerance.to_array()?);
+    let bound = match match_op {
+        Operator::Gt | Operator::GtEq => sub(values, &tolerance)?,
+        _ => add(values, &tolerance)?,
+    };
+    Ok(bound)
+}
+
+impl DisplayAs for AsOfJoinExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut Formatter) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                let on = self
+                    .on
+                    .iter()
+                    .map(|(c1, c2)| format!("({c1}, {c2})"))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                write!(
+                    f,
+                    "AsOfJoinExec: on=[{on}], {}",
+                    self.match_condition_display()
+                )
+            }
+            DisplayFormatType::TreeRender => {
+                let on = self
+                    .on
+                    .iter()
+                    .map(|(c1, c2)| {
+                        format!("({} = {})", fmt_sql(c1.as_ref()), fmt_sql(c2.as_ref()))
+                    })
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                if !self.on.is_empty() {
+                    writeln!(f, "on={on}")?;
+                }
+                writeln!(
+                    f,
+                    "match_condition={} {} {}",
+                    fmt_sql(self.left_match.as_ref()),
+                    self.match_op,
+                    fmt_sql(self.right_match.as_ref())
+                )?;
+                if let Some(tolerance) = &self.tolerance {
+                    writeln!(f, "tolerance={tolerance}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for AsOfJoinExec {
+    fn name(&self) -> &'static str {
+        "AsOfJoinExec"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        &self.cache
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        if self.on.is_empty() {
+            return vec![Distribution::SinglePartition, Distribution::SinglePartition];
+        }
+        let (left_expr, right_expr) = self
+            .on
+            .iter()
+            .map(|(l, r)| (Arc::clone(l), Arc::clone(r)))
+            .unzip();
+        vec![
+            Distribution::HashPartitioned(left_expr),
+            Distribution::HashPartitioned(right_expr),
+        ]
+    }
+
+    fn required_input_ordering(&self) -> Vec<Option<OrderingRequirements>> {
+        vec![
+            Some(OrderingRequirements::from(self.left_sort_exprs.clone())),
+            Some(OrderingRequirements::from(self.right_sort_exprs.clone())),
+        ]
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true, false]
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![&self.left, &self.right]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        match &children[..] {
+            [left, right] => Ok(Arc::new(AsOfJoinExec::try_new(
+                Arc::clone(left),
+                Arc::clone(right),
+                self.on.clone(),
+                Arc::clone(&self.left_match),
+                self.match_op,
+                Arc::clone(&self.right_match),
+                self.tolerance.clone(),
+            )?)),
+            _ => internal_err!("AsOfJoinExec wrong number of children"),
+        }
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let left_partitions = self.left.output_partitioning().partition_count();
+        let right_partitions = self.right.output_partitioning().partition_count();
+        if left_partitions != right_partitions {
+            return internal_err!(
+                "Invalid AsOfJoinExec, partition count mismatch {left_partitions}!={right_partitions},\
+                 consider using RepartitionExec"
+            );
+        }
+
+        let left_schema = self.left.schema();
+        let right_schema = self.right.schema();
+        let key_fields = self
+            .on
+            .iter()
+            .map(|(l, _)| Ok(SortField::new(l.data_type(&left_schema)?)))
+            .collect::<Result<Vec<_>>>()?;
+        let match_type = self.left_match.data_type(&left_schema)?;
+        let null_right_row = right_schema
+            .fields()
+            .iter()
+            .map(|field| new_null_array(field.data_type(), 1))
+            .collect();
+
+        let reservation = MemoryConsumer::new(format!("AsOfJoinStream[{partition}]"))
+            .register(context.memory_pool());
+
+        Ok(Box::pin(AsOfJoinStream {
+            schema: Arc::clone(&self.schema),
+            left: self.left.execute(partition, Arc::clone(&context))?,
+            right: self.right.execute(partition, context)?,
+            on_left: self.on.iter().map(|(l, _)| Arc::clone(l)).collect(),
+            on_right: self.on.iter().map(|(_, r)| Arc::clone(r)).collect(),
+            left_match: Arc::clone(&self.left_match),
+            match_op: self.match_op,
+            right_match: Arc::clone(&self.right_match),
+            tolerance: self.tolerance.clone(),
+            key_converter: RowConverter::new(key_fields)?,
+            match_converter: RowConverter::new(vec![SortField::new(match_type.clone())])?,
+            null_right_row,
+            null_match_value: new_null_array(&match_type, 1),
+            left_batch: None,
+            right_batches: VecDeque::new(),
+            right_row: 0,
+            right_done: false,
+            next_right_batch_id: 0,
+            candidate: None,
+            reservation,
+            baseline_metrics: BaselineMetrics::new(&self.metrics, partition),
+        }))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Result<Statistics> {
+        self.partition_statistics(None)
+    }
+
+    fn partition_statistics(&self, partition: Option<usize>) -> Result<Statistics> {
+        if partition.is_some() {
+            return Ok(Statistics::new_unknown(&self.schema()));
+        }
+        estimate_join_statistics(
+            self.left.partition_statistics(None)?,
+            self.right.partition_statistics(None)?,
+            self.on.clone(),
+            &JoinType::AsOf,
+            &self.schema,
+        )
+    }
+}
+
+/// A batch of one of the inputs, with its join keys and match values
+/// converted to rows so that they can be compared across the inputs
+#[derive(Debug)]
+struct JoinBatch {
+    /// Identifies the batch among the right batches of a stream
+    id: usize,
+    batch: RecordBatch,
+    /// `None` if there are no join keys
+    keys: Option<Rows>,
+    match_rows: Rows,
+    match_values: ArrayRef,
+    /// Rows with null join keys or a null match value never match
+    nulls: Option<NullBuffer>,
+}
+
+impl JoinBatch {
+    fn key(&self, row: usize) -> Option<Row<'_>> {
+        self.keys.as_ref().map(|keys| keys.row(row))
+    }
+
+    fn is_valid(&self, row: usize) -> bool {
+        self.nulls.as_ref().is_none_or(|nulls| nulls.is_valid(row))
+    }
+
+    fn size(&self) -> usize {
+        get_record_batch_memory_size(&self.batch)
+            + self.keys.as_ref().map_or(0, Rows::size)
+            + self.match_rows.size()
+    }
+}
+
+/// A row of a right batch
+type BatchRow = (Arc<JoinBatch>, usize);
+
+/// The left batch that is being joined, and the matches found so far
+#[derive(Debug)]
+struct LeftBatch {
+    batch: JoinBatch,
+    /// The right batch and row matched with every processed left row
+    matches: Vec<Option<BatchRow>>,
+}
+
+/// A stream that joins every left row with the closest right row. See
+/// [`AsOfJoinExec`] for details.
+struct AsOfJoinStream {
+    /// Output schema
+    schema: SchemaRef,
+    /// Left (streamed) input
+    left: SendableRecordBatchStream,
+    /// Right input
+    right: SendableRecordBatchStream,
+    /// Join key expressions of the left input
+    on_left: Vec<PhysicalExprRef>,
+    /// Join key expressions of the right input
+    on_right: Vec<PhysicalExprRef>,
+    left_match: PhysicalExprRef,
+    match_op: Operator,
+    right_match: PhysicalExprRef,
+    tolerance: Option<ScalarValue>,
+    /// Converts join keys of both inputs into comparable rows
+    key_converter: RowConverter,
+    /// Converts match values of both inputs into comparable rows
+    match_converter: RowConverter,
+    /// A row of nulls for the right columns of unmatched left rows
+    null_right_row: Vec<ArrayRef>,
+    /// A null match value for unmatched left rows
+    null_match_value: ArrayRef,
+    /// The left batch that is being joined
+    left_batch: Option<LeftBatch>,
+    /// The right batches that have not been consumed yet. The next right row
+    /// is `right_row` of the first batch.
+    right_batches: VecDeque<Arc<JoinBatch>>,
+    right_row: usize,
+    /// Whether the right input is exhausted
+    right_done: bool,
+    next_right_batch_id: usize,
+    /// The last consumed right row that satisfies the match condition for a
+    /// `>=` or `>` match condition
+    candidate: Option<BatchRow>,
+    /// Memory used by the buffered right batches
+    reservation: MemoryReservation,
+    baseline_metrics: BaselineMetrics,
+}
+
+impl AsOfJoinStream {
+    fn convert_batch(
+        &self,
+        batch: RecordBatch,
+        on: &[PhysicalExprRef],
+        match_expr: &PhysicalExprRef,
+        id: usize,
+    ) -> Result<JoinBatch> {
+        let num_rows = batch.num_rows();
+        let key_values = on
+            .iter()
+            .map(|expr| expr.evaluate(&batch)?.into_array(num_rows))
+            .collect::<Result<Vec<_>>>()?;
+        let match_values = match_expr.evaluate(&batch)?.into_array(num_rows)?;
+        let nulls =
+            key_values
+                .iter()
+                .chain([&match_values])
+                .fold(None, |nulls, values| {
+                    NullBuffer::union(nulls.as_ref(), values.logical_nulls().as_ref())
+                });
+        Ok(JoinBatch {
+            id,
+            keys: (!key_values.is_empty())
+                .then(|| self.key_converter.convert_columns(&key_values))
+                .transpose()?,
+            match_rows: self
+                .match_converter
+                .convert_columns(&[Arc::clone(&match_values)])?,
+            batch,
+            match_values,
+            nulls,
+        })
+    }
+
+    /// Returns the next right row, polling the right input if needed
+    fn poll_right_row(&mut self, cx: &mut Context<'_>) -> Poll<Result<Option<BatchRow>>> {
+        loop {
+            if let Some(batch) = self.right_batches.front() {
+                return Poll::Ready(Ok(Some((Arc::clone(batch), self.right_row))));
+            }
+            if self.right_done {
+                return Poll::Ready(Ok(None));
+            }
+            match ready!(self.right.poll_next_unpin(cx)) {
+                Some(Ok(batch)) if batch.num_rows() == 0 => {}
+                Some(Ok(batch)) => {
+                    let id = self.next_right_batch_id;
+                    self.next_right_batch_id += 1;
+                    let batch =
+                        self.convert_batch(batch, &self.on_right, &self.right_match, id)?;
+                    self.reservation.try_grow(batch.size())?;
+                    self.right_batches.push_back(Arc::new(batch));
+                    self.right_row = 0;
+                }
+                Some(Err(e)) => return Poll::Ready(Err(e)),
+                None => self.right_done = true,
+            }
+        }
+    }
+
+    /// Moves past the next right row
+    fn advance_right(&mut self) {
+        self.right_row += 1;
+        if self
+            .right_batches
+            .front()
+            .is_some_and(|batch| self.right_row == batch.batch.num_rows())
+        {
+            if let Some(batch) = self.right_batches.pop_front() {
+                self.reservation.shrink(batch.size());
+            }
+            self.right_row = 0;
+        }
+    }
+
+    /// Finds the matches of the remaining rows of `left`
+    fn poll_matches(
+        &mut self,
+        cx: &mut Context<'_>,
+        left: &mut LeftBatch,
+    ) -> Poll<Result<()>> {
+        let backward = matches!(self.match_op, Operator::Gt | Operator::GtEq);
+        let inclusive = matches!(self.match_op, Operator::GtEq | Operator::LtEq);
+        while left.matches.len() < left.batch.batch.num_rows() {
+            let row = left.matches.len();
+            if !left.batch.is_valid(row) {
+                left.matches.push(None);
+                continue;
+            }
+            let key = left.batch.key(row);
+            let value = left.batch.match_rows.row(row);
+
+            // Consume the right rows that come before the left row, keeping
+            // track of the last one that satisfies a `>=` or `>` condition
+            let next = loop {
+                let Some((batch, right_row)) = ready!(self.poll_right_row(cx))? else {
+                    break None;
+                };
+                let before = match batch.key(right_row).cmp(&key) {
+                    std::cmp::Ordering::Less => true,
+                    std::cmp::Ordering::Greater => false,
+                    std::cmp::Ordering::Equal => {
+                        match batch.match_rows.row(right_row).cmp(&value) {
+                            std::cmp::Ordering::Less => true,
+                            std::cmp::Ordering::Greater => false,
+                            std::cmp::Ordering::Equal => backward == inclusive,
+                        }
+                    }
+                };
+                if !before {
+                    break Some((batch, right_row));
+                }
+                if backward && batch.is_valid(right_row) {
+                    self.candidate = Some((Arc::clone(&batch), right_row));
+                }
+                self.advance_right();
+            };
+
+            let matched = if backward {
+                self.candidate.clone()
+            } else {
+                next
+            };
+            left.matches.push(matched.filter(|(batch, right_row)| {
+                batch.is_valid(*right_row) && batch.key(*right_row) == key
+            }));
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    /// Builds the output batch of a left batch whose matches are all known
+    fn build_output(&self, left: LeftBatch) -> Result<RecordBatch> {
+        let LeftBatch { batch, matches } = left;
+
+        // Source 0 is the null row, followed by the matched right batches
+        let mut sources: Vec<&JoinBatch> = vec![];
+        let mut source_by_id = HashMap::new();
+        let mut indices = matches
+            .iter()
+            .map(|matched| match matched {
+                Some((right, row)) => {
+                    let source = *source_by_id.entry(right.id).or_insert_with(|| {
+                        sources.push(right.as_ref());
+                        sources.len()
+                    });
+                    (source, *row)
+                }
+                None => (0, 0),
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(tolerance) = &self.tolerance {
+            let match_values = std::iter::once(self.null_match_value.as_ref())
+                .chain(sources.iter().map(|source| source.match_values.as_ref()))
+                .collect::<Vec<_>>();
+            let matched_values = interleave(&match_values, &indices)?;
+            let bound = match_bound(&batch.match_values, self.match_op, tolerance)?;
+            let within = match self.match_op {
+                Operator::Gt | Operator::GtEq => gt_eq(&matched_values, &bound)?,
+                _ => lt_eq(&matched_values, &bound)?,
+            };
+            for (row, index) in indices.iter_mut().enumerate() {
+                if within.is_valid(row) && !within.value(row) {
+                    *index = (0, 0);
+                }
+            }
+        }
+
+        let right_columns = (0..self.null_right_row.len())
+            .map(|column| {
+                let values = std::iter::once(self.null_right_row[column].as_ref())
+                    .chain(
+                        sources
+                            .iter()
+                            .map(|source| source.batch.column(column).as_ref()),
+                    )
+                    .collect::<Vec<_>>();
+                Ok(interleave(&values, &indices)?)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let columns = batch
+            .batch
+            .columns()
+            .iter()
+            .cloned()
+            .chain(right_columns)
+            .collect();
+        Ok(RecordBatch::try_new(Arc::clone(&self.schema), columns)?)
+    }
+
+    fn poll_next_impl(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<RecordBatch>>> {
+        loop {
+            let Some(mut left) = self.left_batch.take() else {
+                match ready!(self.left.poll_next_unpin(cx)) {
+                    Some(Ok(batch)) if batch.num_rows() == 0 => continue,
+                    Some(Ok(batch)) => {
+                        let batch = self.convert_batch(
+                            batch,
+                            &self.on_left,
+                            &self.left_match,
+                            0,
+                        )?;
+                        self.left_batch = Some(LeftBatch {
+                            matches: Vec::with_capacity(batch.batch.num_rows()),
+                            batch,
+                        });
+                        continue;
+                    }
+                    Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                    None => return Poll::Ready(None),
+                }
+            };
+
+            let elapsed_compute = self.baseline_metrics.elapsed_compute().clone();
+            let poll = {
+                let _timer = elapsed_compute.timer();
+                self.poll_matches(cx, &mut left)
+            };
+            match poll {
+                Poll::Pending => {
+                    self.left_batch = Some(left);
+                    return Poll::Pending;
+                }
+                Poll::Ready(Err(e)) => return Poll::Ready(Some(Err(e))),
+                Poll::Ready(Ok(())) => {
+                    let _timer = elapsed_compute.timer();
+                    return Poll::Ready(Some(self.build_output(left)));
+                }
+            }
+        }
+    }
+}
+
+impl Stream for AsOfJoinStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let poll = self.poll_next_impl(cx);
+        self.baseline_metrics.record_poll(poll)
+    }
+}
+
+impl RecordBatchStream for AsOfJoinStream {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::collect;
+    use crate::expressions::Column;
+    use crate::test::TestMemoryExec;
+
+    use arrow::array::{Int32Array, StringArray};
+    use arrow::datatypes::{Field, Schema};
+    use arrow::util::pretty::pretty_format_batches;
+    use datafusion_common::assert_contains;
+    use insta::{allow_duplicates, assert_snapshot};
+
+    fn build_input(
+        key: &str,
+        ts: &str,
+        value: &str,
+        rows: &[(&str, i32, i32)],
+        batch_rows: usize,
+    ) -> Arc<dyn ExecutionPlan> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(key, DataType::Utf8, true),
+            Field::new(ts, DataType::Int32, true),
+            Field::new(value, DataType::Int32, false),
+        ]));
+        let batches = rows
+            .chunks(batch_rows)
+            .map(|chunk| {
+                RecordBatch::try_new(
+                    Arc::clone(&schema),
+                    vec![
+                        Arc::new(StringArray::from_iter(
+                            chunk.iter().map(|(k, _, _)| (!k.is_empty()).then_some(*k)),
+                        )),
+                        Arc::new(Int32Array::from_iter(
+                            chunk.iter().map(|(_, t, _)| (*t >= 0).then_some(*t)),
+                        )),
+                        Arc::new(Int32Array::from_iter_values(
+                            chunk.iter().map(|(_, _, v)| *v),
+                        )),
+                    ],
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        TestMemoryExec::try_new_exec(&[batches], schema, None).unwrap()
+    }
+
+    /// Trades and quotes sorted by symbol and time. Empty symbols and negative
+    /// times are nulls.
+    fn trades(batch_rows: usize) -> Arc<dyn ExecutionPlan> {
+        build_input(
+            "t_sym",
+            "t_ts",
+            "t_id",
+            &[
+                ("", 5, 0),
+                ("a", -1, 1),
+                ("a", 1, 2),
+                ("a", 5, 3),
+                ("a", 7, 4),
+                ("a", 20, 5),
+                ("b", 3, 6),
+                ("c", 4, 7),
+                ("d", 9, 8),
+            ],
+            batch_rows,
+        )
+    }
+
+    fn quotes(batch_rows: usize) -> Arc<dyn ExecutionPlan> {
+        build_input(
+            "q_sym",
+            "q_ts",
+            "q_price",
+            &[
+                ("", 1, 100),
+                ("a", -1, 101),
+                ("a", 2, 102),
+                ("a", 5, 103),
+                ("a", 6, 104),
+                ("b", 3, 105),
+                ("b", 8, 106),
+                ("d", 1, 107),
+                ("d", 9, 108),
+                ("e", 1, 109),
+            ],
+            batch_rows,
+        )
+    }
+
+    async fn join_collect(
+        op: Operator,
+        tolerance: Option<ScalarValue>,
+        batch_rows: usize,
+    ) -> Result<String> {
+        let left = trades(batch_rows);
+        let right = quotes(batch_rows);
+        let on = vec![(
+            Arc::new(Column::new_with_schema("t_sym", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("q_sym", &right.schema())?) as _,
+        )];
+        let left_match = Arc::new(Column::new_with_schema("t_ts", &left.schema())?);
+        let right_match = Arc::new(Column::new_with_schema("q_ts", &right.schema())?);
+        let join = AsOfJoinExec::try_new(
+            left,
+            right,
+            on,
+            left_match,
+            op,
+            right_match,
+            tolerance,
+        )?;
+        let batches = collect(join.execute(0, Arc::new(TaskContext::default()))?).await?;
+        Ok(pretty_format_batches(&batches)?.to_string())
+    }
+
+    #[tokio::test]
+    async fn asof_join_backward() -> Result<()> {
+        for batch_rows in [1, 2, 100] {
+            let actual = join_collect(Operator::GtEq, None, batch_rows).await?;
+            allow_duplicates! {
+                assert_snapshot!(actual, @r"
+                +-------+------+------+-------+------+---------+
+                | t_sym | t_ts | t_id | q_sym | q_ts | q_price |
+                +-------+------+------+-------+------+---------+
+                |       | 5    | 0    |       |      |         |
+                | a     |      | 1    |       |      |         |
+                | a     | 1    | 2    |       |      |         |
+                | a     | 5    | 3    | a     | 5    | 103     |
+                | a     | 7    | 4    | a     | 6    | 104     |
+                | a     | 20   | 5    | a     | 6    | 104     |
+                | b     | 3    | 6    | b     | 3    | 105     |
+                | c     | 4    | 7    |       |      |         |
+                | d     | 9    | 8    | d     | 9    | 108     |
+                +-------+------+------+-------+------+---------+
+                ");
+            }
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn asof_join_backward_exclusive() -> Result<()> {
+        let actual = join_collect(Operator::Gt, None, 2).await?;
+        assert_snapshot!(actual, @r"
+        +-------+------+------+-------+------+---------+
+        | t_sym | t_ts | t_id | q_sym | q_ts | q_price |
+        +-------+------+------+-------+------+---------+
+        |       | 5    | 0    |       |      |         |
+        | a     |      | 1    |       |      |         |
+        | a     | 1    | 2    |       |      |         |
+        | a     | 5    | 3    | a     | 2    | 102     |
+        | a     | 7    | 4    | a     | 6    | 104     |
+        | a     | 20   | 5    | a     | 6    | 104     |
+        | b     | 3    | 6    |       |      |         |
+        | c     | 4    | 7    |       |      |         |
+        | d     | 9    | 8    | d     | 1    | 107     |
+        +-------+------+------+-------+------+---------+
+        ");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn asof_join_forward() -> Result<()> {
+        for batch_rows in [1, 3, 100] {
+            let actual = join_collect(Operator::LtEq, None, batch_rows).await?;
+            allow_duplicates! {
+                assert_snapshot!(actual, @r"
+                +-------+------+------+-------+------+---------+
+                | t_sym | t_ts | t_id | q_sym | q_ts | q_price |
+                +-------+------+------+-------+------+---------+
+                |       | 5    | 0    |       |      |         |
+                | a     |      | 1    |       |      |         |
+                | a     | 1    | 2    | a     | 2    | 102     |
+                | a     | 5    | 3    | a     | 5    | 103     |
+                | a     | 7    | 4    |       |      |         |
+                | a     | 20   | 5    |       |      |         |
+                | b     | 3    | 6    | b     | 3    | 105     |
+                | c     | 4    | 7    |       |      |         |
+                | d     | 9    | 8    | d     | 9    | 108     |
+                +-------+------+------+-------+------+---------+
+                ");
+            }
+        }
+
+        let actual = join_collect(Operator::Lt, None, 2).await?;
+        assert_snapshot!(actual, @r"
+        +-------+------+------+-------+------+---------+
+        | t_sym | t_ts | t_id | q_sym | q_ts | q_price |
+        +-------+------+------+-------+------+---------+
+        |       | 5    | 0    |       |      |         |
+        | a     |      | 1    |       |      |         |
+        | a     | 1    | 2    | a     | 2    | 102     |
+        | a     | 5    | 3    | a     | 6    | 104     |
+        | a     | 7    | 4    |       |      |         |
+        | a     | 20   | 5    |       |      |         |
+        | b     | 3    | 6    | b     | 8    | 106     |
+        | c     | 4    | 7    |       |      |         |
+        | d     | 9    | 8    |       |      |         |
+        +-------+------+------+-------+------+---------+
+        ");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn asof_join_tolerance() -> Result<()> {
+        let actual =
+            join_collect(Operator::GtEq, Some(ScalarValue::Int64(Some(2))), 2).await?;
+        assert_snapshot!(actual, @r"
+        +-------+------+------+-------+------+---------+
+        | t_sym | t_ts | t_id | q_sym | q_ts | q_price |
+        +-------+------+------+-------+------+---------+
+        |       | 5    | 0    |       |      |         |
+        | a     |      | 1    |       |      |         |
+        | a     | 1    | 2    |       |      |         |
+        | a     | 5    | 3    | a     | 5    | 103     |
+        | a     | 7    | 4    | a     | 6    | 104     |
+        | a     | 20   | 5    |       |      |         |
+        | b     | 3    | 6    | b     | 3    | 105     |
+        | c     | 4    | 7    |       |      |         |
+        | d     | 9    | 8    | d     | 9    | 108     |
+        +-------+------+------+-------+------+---------+
+        ");
+
+        let actual =
+            join_collect(Operator::Lt, Some(ScalarValue::Int32(Some(3))), 2).await?;
+        assert_snapshot!(actual, @r"
+        +-------+------+------+-------+------+---------+
+        | t_sym | t_ts | t_id | q_sym | q_ts | q_price |
+        +-------+------+------+-------+------+---------+
+        |       | 5    | 0    |       |      |         |
+        | a     |      | 1    |       |      |         |
+        | a     | 1    | 2    | a     | 2    | 102     |
+        | a     | 5    | 3    | a     | 6    | 104     |
+        | a     | 7    | 4    |       |      |         |
+        | a     | 20   | 5    |       |      |         |
+        | b     | 3    | 6    |       |      |         |
+        | c     | 4    | 7    |       |      |         |
+        | d     | 9    | 8    |       |      |         |
+        +-------+------+------+-------+------+---------+
+        ");
+        Ok(())
+    }
+
+    #[test]
+    fn asof_join_invalid_match_condition() -> Result<()> {
+        let left = trades(2);
+        let right = quotes(2);
+        let left_match: PhysicalExprRef =
+            Arc::new(Column::new_with_schema("t_ts", &left.schema())?);
+        let right_match: PhysicalExprRef =
+            Arc::new(Column::new_with_schema("q_sym", &right.schema())?);
+
+        let err = AsOfJoinExec::try_new(
+            Arc::clone(&left),
+            Arc::clone(&right),
+            vec![],
+            Arc::clone(&left_match),
+            Operator::Eq,
+            Arc::clone(&left_match),
+            None,
+        )
+        .unwrap_err();
+        assert_contains!(err.to_string(), "requires a >=, >, <= or < match condition");
+
+        let err = AsOfJoinExec::try_new(
+            Arc::clone(&left),
+            Arc::clone(&right),
+            vec![],
+            Arc::clone(&left_match),
+            Operator::GtEq,
+            right_match,
+            None,
+        )
+        .unwrap_err();
+        assert_contains!(err.to_string(), "have different types");
+
+        let err = AsOfJoinExec::try_new(
+            left,
+            right,
+            vec![],
+            Arc::clone(&left_match),
+            Operator::GtEq,
+            left_match,
+            Some(ScalarValue::Utf8(Some("x".to_string()))),
+        )
+        .unwrap_err();
+        assert_contains!(err.to_string(), "Cast error");
+        Ok(())
+    }
+}
@@ -0,0 +1,266 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Bloom filter of the join keys of the build side of a hash join. Used in
+//! dynamic filter pushdown in Hash Joins, where min/max bounds are not
+//! selective for high-cardinality keys.
+
+use std::any::Any;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::mem::size_of;
+use std::sync::Arc;
+
+use ahash::RandomState;
+use arrow::array::BooleanArray;
+use arrow::buffer::BooleanBuffer;
+use arrow::datatypes::{DataType, Schema};
+use arrow::record_batch::RecordBatch;
+use datafusion_common::hash_utils::create_hashes;
+use datafusion_common::Result;
+use datafusion_expr::ColumnarValue;
+use datafusion_physical_expr::{PhysicalExpr, PhysicalExprRef};
+
+/// Number of bits per inserted key. With [`NUM_HASH_FUNCTIONS`] hash
+/// functions, this results in a false positive rate of about 1%.
+const BITS_PER_KEY: usize = 10;
+
+/// Number of bits set for each inserted key
+const NUM_HASH_FUNCTIONS: u64 = 7;
+
+/// Maximum number of bits of a bloom filter (16 MB)
+const MAX_BITS: usize = 1 << 27;
+
+/// A bloom filter of the hashes of the join keys of the build side.
+///
+/// The hashes are the ones computed to build the hash table, so the bloom
+/// filter is created without hashing the build side again. Probe-side keys
+/// are hashed with the same [`RandomState`] to check for membership.
+#[derive(Debug)]
+pub(crate) struct BloomFilter {
+    bits: Vec<u64>,
+    /// Mask to map a hash to the index of a bit
+    mask: u64,
+    /// The random state used to hash the join keys
+    random_state: RandomState,
+}
+
+impl BloomFilter {
+    /// Creates an empty bloom filter sized for `num_keys` keys, hashed
+    /// with `random_state`
+    pub(crate) fn with_capacity(num_keys: usize, random_state: RandomState) -> Self {
+        let num_bits = num_keys
+            .saturating_mul(BITS_PER_KEY)
+            .next_power_of_two()
+            .clamp(64, MAX_BITS);
+        Self {
+            bits: vec![0; num_bits / 64],
+            mask: num_bits as u64 - 1,
+            random_state,
+        }
+    }
+
+    /// Returns the memory size of the bloom filter, in bytes
+    pub(crate) fn size(&self) -> usize {
+        self.bits.len() * size_of::<u64>()
+    }
+
+    /// Inserts the hashes of the join keys of some build-side rows
+    pub(crate) fn insert_hashes(&mut self, hashes: &[u64]) {
+        for hash in hashes {
+            for bit in self.bit_indices(*hash) {
+                self.bits[(bit / 64) as usize] |= 1 << (bit % 64);
+            }
+        }
+    }
+
+    /// Returns whether a key with this hash may have been inserted
+    fn contains(&self, hash: u64) -> bool {
+        self.bit_indices(hash)
+            .all(|bit| self.bits[(bit / 64) as usize] & (1 << (bit % 64)) != 0)
+    }
+
+    /// Returns the indices of the bits of a hash, using double hashing on
+    /// the two halves of the hash
+    fn bit_indices(&self, hash: u64) -> impl Iterator<Item = u64> {
+        let mask = self.mask;
+        let low = hash & 0xFFFF_FFFF;
+        let high = (hash >> 32) | 1;
+        (0..NUM_HASH_FUNCTIONS)
+            .map(move |i| low.wrapping_add(i.wrapping_mul(high)) & mask)
+    }
+}
+
+/// Physical expression that evaluates to `true` for the rows whose join keys
+/// may be in a [`BloomFilter`], and to `false` for the rows whose join keys
+/// are definitely not.
+#[derive(Debug)]
+pub(crate) struct BloomFilterExpr {
+    /// The probe-side join key expressions
+    keys: Vec<PhysicalExprRef>,
+    bloom_filter: Arc<BloomFilter>,
+}
+
+impl BloomFilterExpr {
+    pub(crate) fn new(
+        keys: Vec<PhysicalExprRef>,
+        bloom_filter: Arc<BloomFilter>,
+    ) -> Self {
+        Self { keys, bloom_filter }
+    }
+}
+
+// The bloom filter is compared by identity, as comparing its bits is expensive
+impl PartialEq for BloomFilterExpr {
+    fn eq(&self, other: &Self) -> bool {
+        self.keys == other.keys && Arc::ptr_eq(&self.bloom_filter, &other.bloom_filter)
+    }
+}
+
+impl Eq for BloomFilterExpr {}
+
+impl Hash for BloomFilterExpr {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.keys.hash(state);
+        Arc::as_ptr(&self.bloom_filter).hash(state);
+    }
+}
+
+impl fmt::Display for BloomFilterExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "bloom_filter_contains(")?;
+        for (i, key) in self.keys.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{key}")?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl PhysicalExpr for BloomFilterExpr {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn data_type(&self, _input_schema: &Schema) -> Result<DataType> {
+        Ok(DataType::Boolean)
+    }
+
+    fn nullable(&self, _input_schema: &Schema) -> Result<bool> {
+        Ok(false)
+    }
+
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ColumnarValue> {
+        let num_rows = batch.num_rows();
+        let keys = self
+            .keys
+            .iter()
+            .map(|key| key.evaluate(batch)?.into_array(num_rows))
+            .collect::<Result<Vec<_>>>()?;
+        let mut hashes = vec![0; num_rows];
+        create_hashes(&keys, &self.bloom_filter.random_state, &mut hashes)?;
+        let contains = BooleanBuffer::collect_bool(num_rows, |row| {
+            self.bloom_filter.contains(hashes[row])
+        });
+        Ok(ColumnarValue::Array(Arc::new(BooleanArray::new(
+            contains, None,
+        ))))
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn PhysicalExpr>> {
+        self.keys.iter().collect()
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn PhysicalExpr>>,
+    ) -> Result<Arc<dyn PhysicalExpr>> {
+        Ok(Arc::new(Self::new(
+            children,
+            Arc::clone(&self.bloom_filter),
+        )))
+    }
+
+    fn fmt_sql(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "bloom_filter_contains(")?;
+        for (i, key) in self.keys.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            key.fmt_sql(f)?;
+        }
+        write!(f, ")")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{ArrayRef, Int32Array, StringArray};
+    use arrow::datatypes::Field;
+    use datafusion_physical_expr::expressions::col;
+
+    #[test]
+    fn test_bloom_filter_expr() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, true),
+        ]));
+        let random_state = RandomState::with_seeds(1, 2, 3, 4);
+
+        // Build side keys: (i, "i") for i in 0..1000
+        let build: Vec<ArrayRef> = vec![
+            Arc::new(Int32Array::from_iter_values(0..1000)),
+            Arc::new(StringArray::from_iter_values(
+                (0..1000).map(|i| i.to_string()),
+            )),
+        ];
+        let mut hashes = vec![0; 1000];
+        create_hashes(&build, &random_state, &mut hashes)?;
+        let mut bloom_filter = BloomFilter::with_capacity(1000, random_state);
+        bloom_filter.insert_hashes(&hashes);
+
+        let expr = BloomFilterExpr::new(
+            vec![col("a", &schema)?, col("b", &schema)?],
+            Arc::new(bloom_filter),
+        );
+        assert_eq!(expr.to_string(), "bloom_filter_contains(a@0, b@1)");
+
+        // Probe side keys: (i, "i") for i in 0..2000, so only the first half
+        // of the rows match
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![
+                Arc::new(Int32Array::from_iter_values(0..2000)),
+                Arc::new(StringArray::from_iter_values(
+                    (0..2000).map(|i| i.to_string()),
+                )),
+            ],
+        )?;
+        let result = expr.evaluate(&batch)?.into_array(batch.num_rows())?;
+        let result = result.as_any().downcast_ref::<BooleanArray>().unwrap();
+
+        // No false negatives
+        assert!((0..1000).all(|row| result.value(row)));
+        // Few false positives
+        let false_positives = (1000..2000).filter(|row| result.value(*row)).count();
+        assert!(false_positives < 50, "{false_positives} false positives");
+        Ok(())
+    }
+}
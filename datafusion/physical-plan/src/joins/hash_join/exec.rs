@@ -26,7 +26,11 @@ use crate::filter_pushdown::{
     ChildPushdownResult, FilterDescription, FilterPushdownPhase,
     FilterPushdownPropagation,
 };
-use crate::joins::hash_join::shared_bounds::{ColumnBounds, SharedBoundsAccumulator};
+use crate::joins::hash_join::bloom_filter::BloomFilter;
+use crate::joins::hash_join::shared_bounds::{
+    ColumnBounds, MembershipFilter, MembershipFilterOptions, SharedBoundsAccumulator,
+};
+use crate::joins::hash_join::spill::SpillableHashJoin;
 use crate::joins::hash_join::stream::{
     BuildSide, BuildSideInitialState, HashJoinStream, HashJoinStreamState,
 };
@@ -54,7 +58,7 @@ use crate::{
     PlanProperties, SendableRecordBatchStream, Statistics,
 };
 
-use arrow::array::{ArrayRef, BooleanBufferBuilder};
+use arrow::array::{Array, ArrayRef, BooleanBufferBuilder};
 use arrow::compute::concat_batches;
 use arrow::datatypes::SchemaRef;
 use arrow::record_batch::RecordBatch;
@@ -63,7 +67,8 @@ use arrow_schema::DataType;
 use datafusion_common::config::ConfigOptions;
 use datafusion_common::utils::memory::estimate_memory_size;
 use datafusion_common::{
-    internal_err, plan_err, project_schema, JoinSide, JoinType, NullEquality, Result,
+    internal_err, plan_err, project_schema, HashSet, JoinSide, JoinType, NullEquality,
+    Result, ScalarValue,
 };
 use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
 use datafusion_execution::TaskContext;
@@ -104,10 +109,14 @@ pub(super) struct JoinLeftData {
     _reservation: MemoryReservation,
     /// Bounds computed from the build side for dynamic filter pushdown
     pub(super) bounds: Option<Vec<ColumnBounds>>,
+    /// Filter on the join keys computed from the build side for dynamic
+    /// filter pushdown
+    pub(super) membership: Option<MembershipFilter>,
 }
 
 impl JoinLeftData {
     /// Create a new `JoinLeftData` from its parts
+    #[allow(clippy::too_many_arguments)]
     pub(super) fn new(
         hash_map: Box<dyn JoinHashMapType>,
         batch: RecordBatch,
@@ -116,6 +125,7 @@ impl JoinLeftData {
         probe_threads_counter: AtomicUsize,
         reservation: MemoryReservation,
         bounds: Option<Vec<ColumnBounds>>,
+        membership: Option<MembershipFilter>,
     ) -> Self {
         Self {
             hash_map,
@@ -125,6 +135,7 @@ impl JoinLeftData {
             probe_threads_counter,
             _reservation: reservation,
             bounds,
+            membership,
         }
     }
 
@@ -314,6 +325,20 @@ impl JoinLeftData {
 ///                       └───────────────┘     └───────────────┘
 /// ```
 ///
+/// # Spilling
+///
+/// When the disk manager allows temporary files and the build side of an
+/// output partition is not shared with other partitions
+/// ([`PartitionMode::Partitioned`], or [`PartitionMode::CollectLeft`] with a
+/// single probe partition), a build side that does not fit into the memory
+/// reservation is not an error. Instead, both inputs are hash partitioned
+/// into spill files (a "grace" hash join) and each pair of partitions is
+/// joined on its own, partitioning again with a different hash seed if a
+/// build partition is still too large.
+///
+/// Spilling does not preserve the order of the probe side, so it is disabled
+/// when this join is expected to maintain that order.
+///
 /// # Clone / Shared State
 ///
 /// Note this structure includes a [`OnceAsync`] that is used to coordinate the
@@ -420,6 +445,11 @@ impl HashJoinExec {
         }
 
         check_join_is_valid(&left_schema, &right_schema, &on)?;
+        if *join_type == JoinType::AsOf {
+            return plan_err!(
+                "HashJoinExec does not support AsOf joins, use AsOfJoinExec"
+            );
+        }
 
         let (join_schema, column_indices) =
             build_join_schema(&left_schema, &right_schema, join_type);
@@ -611,7 +641,8 @@ impl HashJoinExec {
                 JoinType::Left
                 | JoinType::LeftAnti
                 | JoinType::LeftMark
-                | JoinType::Full => EmissionType::Both,
+                | JoinType::Full
+                | JoinType::AsOf => EmissionType::Both,
             }
         } else {
             right.pipeline_behavior()
@@ -673,7 +704,7 @@ impl HashJoinExec {
                 .map(|(l, r)| (Arc::clone(r), Arc::clone(l)))
                 .collect(),
             self.filter().map(JoinFilter::swap),
-            &self.join_type().swap(),
+            &self.join_type().swap()?,
             swap_join_projection(
                 left.schema().fields().len(),
                 right.schema().fields().len(),
@@ -697,6 +728,67 @@ impl HashJoinExec {
             reorder_output_after_swap(Arc::new(new_join), &left.schema(), &right.schema())
         }
     }
+
+    /// Returns the future collecting the build side into an in-memory hash
+    /// table, shared by all output streams in [`PartitionMode::CollectLeft`].
+    fn collect_left_future(
+        &self,
+        partition: usize,
+        context: &Arc<TaskContext>,
+        on_left: &[PhysicalExprRef],
+        join_metrics: &BuildProbeJoinMetrics,
+        enable_dynamic_filter_pushdown: bool,
+    ) -> Result<OnceFut<JoinLeftData>> {
+        let membership_options =
+            MembershipFilterOptions::from_config(context.session_config().options());
+        let left_fut = match self.mode {
+            PartitionMode::CollectLeft => self.left_fut.try_once(|| {
+                let left_stream = self.left.execute(0, Arc::clone(context))?;
+
+                let reservation =
+                    MemoryConsumer::new("HashJoinInput").register(context.memory_pool());
+
+                Ok(collect_left_input(
+                    self.random_state.clone(),
+                    left_stream,
+                    on_left.to_vec(),
+                    join_metrics.clone(),
+                    reservation,
+                    need_produce_result_in_final(self.join_type),
+                    self.right().output_partitioning().partition_count(),
+                    enable_dynamic_filter_pushdown,
+                    membership_options,
+                ))
+            })?,
+            PartitionMode::Partitioned => {
+                let left_stream = self.left.execute(partition, Arc::clone(context))?;
+
+                let reservation =
+                    MemoryConsumer::new(format!("HashJoinInput[{partition}]"))
+                        .register(context.memory_pool());
+
+                OnceFut::new(collect_left_input(
+                    self.random_state.clone(),
+                    left_stream,
+                    on_left.to_vec(),
+                    join_metrics.clone(),
+                    reservation,
+                    need_produce_result_in_final(self.join_type),
+                    1,
+                    enable_dynamic_filter_pushdown,
+                    membership_options,
+                ))
+            }
+            PartitionMode::Auto => {
+                return plan_err!(
+                    "Invalid HashJoinExec, unsupported PartitionMode {:?} in execute()",
+                    PartitionMode::Auto
+                );
+            }
+        };
+
+        Ok(left_fut)
+    }
 }
 
 impl DisplayAs for HashJoinExec {
@@ -929,48 +1021,34 @@ impl ExecutionPlan for HashJoinExec {
         let enable_dynamic_filter_pushdown = self.dynamic_filter.is_some();
 
         let join_metrics = BuildProbeJoinMetrics::new(partition, &self.metrics);
-        let left_fut = match self.mode {
-            PartitionMode::CollectLeft => self.left_fut.try_once(|| {
-                let left_stream = self.left.execute(0, Arc::clone(&context))?;
-
-                let reservation =
-                    MemoryConsumer::new("HashJoinInput").register(context.memory_pool());
 
-                Ok(collect_left_input(
-                    self.random_state.clone(),
-                    left_stream,
-                    on_left.clone(),
-                    join_metrics.clone(),
-                    reservation,
-                    need_produce_result_in_final(self.join_type),
-                    self.right().output_partitioning().partition_count(),
-                    enable_dynamic_filter_pushdown,
-                ))
-            })?,
-            PartitionMode::Partitioned => {
-                let left_stream = self.left.execute(partition, Arc::clone(&context))?;
-
-                let reservation =
-                    MemoryConsumer::new(format!("HashJoinInput[{partition}]"))
-                        .register(context.memory_pool());
-
-                OnceFut::new(collect_left_input(
-                    self.random_state.clone(),
-                    left_stream,
-                    on_left.clone(),
-                    join_metrics.clone(),
-                    reservation,
-                    need_produce_result_in_final(self.join_type),
-                    1,
-                    enable_dynamic_filter_pushdown,
-                ))
-            }
-            PartitionMode::Auto => {
-                return plan_err!(
-                    "Invalid HashJoinExec, unsupported PartitionMode {:?} in execute()",
-                    PartitionMode::Auto
-                );
+        // The build side can be spilled when it is owned by a single output
+        // stream, and spilling would not break an ordering of the probe side
+        // this join is expected to maintain.
+        let spill_enabled = context
+            .session_config()
+            .options()
+            .execution
+            .enable_hash_join_spill
+            && context.runtime_env().disk_manager.tmp_files_enabled()
+            && match self.mode {
+                PartitionMode::Partitioned => true,
+                PartitionMode::CollectLeft => right_partitions == 1,
+                PartitionMode::Auto => false,
             }
+            && !(self.right.output_ordering().is_some()
+                && Self::maintains_input_order(self.join_type)[1]);
+
+        let left_fut = if spill_enabled {
+            None
+        } else {
+            Some(self.collect_left_future(
+                partition,
+                &context,
+                &on_left,
+                &join_metrics,
+                enable_dynamic_filter_pushdown,
+            )?)
         };
 
         let batch_size = context.session_config().batch_size();
@@ -1001,7 +1079,7 @@ impl ExecutionPlan for HashJoinExec {
 
         // we have the batches and the hash map with their keys. We can how create a stream
         // over the right that uses this information to issue new batches.
-        let right_stream = self.right.execute(partition, context)?;
+        let right_stream = self.right.execute(partition, Arc::clone(&context))?;
 
         // update column indices to reflect the projection
         let column_indices_after_projection = match &self.projection {
@@ -1018,6 +1096,42 @@ impl ExecutionPlan for HashJoinExec {
             .map(|(_, right_expr)| Arc::clone(right_expr))
             .collect::<Vec<_>>();
 
+        let Some(left_fut) = left_fut else {
+            let left_partition = match self.mode {
+                PartitionMode::Partitioned => partition,
+                _ => 0,
+            };
+            let left_stream = self.left.execute(left_partition, Arc::clone(&context))?;
+            let consumer_name = match self.mode {
+                PartitionMode::Partitioned => format!("HashJoinInput[{partition}]"),
+                _ => "HashJoinInput".to_string(),
+            };
+            let join = Arc::new(SpillableHashJoin {
+                partition,
+                schema: self.schema(),
+                on_left,
+                on_right,
+                filter: self.filter.clone(),
+                join_type: self.join_type,
+                random_state: self.random_state.clone(),
+                column_indices: column_indices_after_projection,
+                null_equality: self.null_equality,
+                batch_size,
+                right_side_ordered: self.right.output_ordering().is_some(),
+                mode: self.mode,
+                consumer_name,
+                metrics: self.metrics.clone(),
+                spill_metrics: OnceLock::new(),
+                context,
+            });
+            return Ok(join.execute(
+                left_stream,
+                right_stream,
+                join_metrics,
+                bounds_accumulator,
+            ));
+        };
+
         Ok(Box::pin(HashJoinStream::new(
             partition,
             self.schema(),
@@ -1214,7 +1328,7 @@ impl ExecutionPlan for HashJoinExec {
 /// The bounds are used for dynamic filter pushdown optimization, where filters
 /// based on the actual data ranges can be pushed down to the probe side to
 /// eliminate unnecessary data early.
-struct CollectLeftAccumulator {
+pub(super) struct CollectLeftAccumulator {
     /// The physical expression to evaluate for each batch
     expr: Arc<dyn PhysicalExpr>,
     /// Accumulator for tracking the minimum value across all batches
@@ -1232,7 +1346,10 @@ impl CollectLeftAccumulator {
     ///
     /// # Returns
     /// A new `CollectLeftAccumulator` instance configured for the expression's data type
-    fn try_new(expr: Arc<dyn PhysicalExpr>, schema: &SchemaRef) -> Result<Self> {
+    pub(super) fn try_new(
+        expr: Arc<dyn PhysicalExpr>,
+        schema: &SchemaRef,
+    ) -> Result<Self> {
         /// Recursively unwraps dictionary types to get the underlying value type.
         fn dictionary_value_type(data_type: &DataType) -> DataType {
             match data_type {
@@ -1264,7 +1381,7 @@ impl CollectLeftAccumulator {
     ///
     /// # Returns
     /// Ok(()) if the update succeeds, or an error if expression evaluation fails
-    fn update_batch(&mut self, batch: &RecordBatch) -> Result<()> {
+    pub(super) fn update_batch(&mut self, batch: &RecordBatch) -> Result<()> {
         let array = self.expr.evaluate(batch)?.into_array(batch.num_rows())?;
         self.min.update_batch(std::slice::from_ref(&array))?;
         self.max.update_batch(std::slice::from_ref(&array))?;
@@ -1277,7 +1394,7 @@ impl CollectLeftAccumulator {
     ///
     /// # Returns
     /// The `ColumnBounds` containing the minimum and maximum values observed
-    fn evaluate(mut self) -> Result<ColumnBounds> {
+    pub(super) fn evaluate(mut self) -> Result<ColumnBounds> {
         Ok(ColumnBounds::new(
             self.min.evaluate()?,
             self.max.evaluate()?,
@@ -1339,10 +1456,13 @@ impl BuildSideState {
 /// * `with_visited_indices_bitmap` - Whether to track visited indices (for outer joins)
 /// * `probe_threads_count` - Number of threads that will probe this hash table
 /// * `should_compute_bounds` - Whether to compute min/max bounds for dynamic filtering
+/// * `membership_options` - How to filter the join keys in dynamic filtering, in
+///   addition to their bounds
 ///
 /// # Dynamic Filter Coordination
 /// When `should_compute_bounds` is true, this function computes the min/max bounds
-/// for each join key column but does NOT update the dynamic filter. Instead, the
+/// for each join key column, as well as `IN` lists of the join keys of small build
+/// sides or a bloom filter of their hashes, but does NOT update the dynamic filter. Instead, the
 /// bounds are stored in the returned `JoinLeftData` and later coordinated by
 /// `SharedBoundsAccumulator` to ensure all partitions contribute their bounds
 /// before updating the filter exactly once.
@@ -1351,7 +1471,7 @@ impl BuildSideState {
 /// `JoinLeftData` containing the hash map, consolidated batch, join key values,
 /// visited indices bitmap, and computed bounds (if requested).
 #[allow(clippy::too_many_arguments)]
-async fn collect_left_input(
+pub(super) async fn collect_left_input(
     random_state: RandomState,
     left_stream: SendableRecordBatchStream,
     on_left: Vec<PhysicalExprRef>,
@@ -1360,6 +1480,7 @@ async fn collect_left_input(
     with_visited_indices_bitmap: bool,
     probe_threads_count: usize,
     should_compute_bounds: bool,
+    membership_options: MembershipFilterOptions,
 ) -> Result<JoinLeftData> {
     let schema = left_stream.schema();
 
@@ -1429,6 +1550,18 @@ async fn collect_left_input(
         Box::new(JoinHashMapU32::with_capacity(num_rows))
     };
 
+    // The bloom filter is built from the hashes computed for the hash table.
+    // It is only an optimization, so if there is not enough memory for it the
+    // dynamic filter falls back to the min/max bounds instead of failing the join
+    let mut bloom_filter = (should_compute_bounds
+        && membership_options.enable_bloom_filter
+        && num_rows > membership_options.max_inlist_rows)
+        .then(|| BloomFilter::with_capacity(num_rows, random_state.clone()))
+        .filter(|bloom_filter| reservation.try_grow(bloom_filter.size()).is_ok());
+    if let Some(bloom_filter) = &bloom_filter {
+        metrics.build_mem_used.add(bloom_filter.size());
+    }
+
     let mut hashes_buffer = Vec::new();
     let mut offset = 0;
 
@@ -1447,6 +1580,9 @@ async fn collect_left_input(
             0,
             true,
         )?;
+        if let Some(bloom_filter) = bloom_filter.as_mut() {
+            bloom_filter.insert_hashes(&hashes_buffer);
+        }
         offset += batch.num_rows();
     }
     // Merge all batches into a single batch, so we can directly index into the arrays
@@ -1485,6 +1621,20 @@ async fn collect_left_input(
         _ => None,
     };
 
+    let membership = if !should_compute_bounds || num_rows == 0 {
+        None
+    } else if num_rows <= membership_options.max_inlist_rows {
+        Some(MembershipFilter::InList(
+            left_values
+                .iter()
+                .map(distinct_values)
+                .collect::<Result<Vec<_>>>()?,
+        ))
+    } else {
+        bloom_filter
+            .map(|bloom_filter| MembershipFilter::BloomFilter(Arc::new(bloom_filter)))
+    };
+
     let data = JoinLeftData::new(
         hashmap,
         single_batch,
@@ -1493,11 +1643,25 @@ async fn collect_left_input(
         AtomicUsize::new(probe_threads_count),
         reservation,
         bounds,
+        membership,
     );
 
     Ok(data)
 }
 
+/// Returns the distinct non-null values of a join key column, in ascending order
+fn distinct_values(values: &ArrayRef) -> Result<Vec<ScalarValue>> {
+    let mut distinct = HashSet::new();
+    for row in 0..values.len() {
+        if values.is_valid(row) {
+            distinct.insert(ScalarValue::try_from_array(values, row)?);
+        }
+    }
+    let mut distinct = distinct.into_iter().collect::<Vec<_>>();
+    distinct.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    Ok(distinct)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1520,6 +1684,10 @@ mod tests {
         ScalarValue,
     };
     use datafusion_execution::config::SessionConfig;
+    use datafusion_execution::disk_manager::{DiskManagerBuilder, DiskManagerMode};
+    use datafusion_execution::memory_pool::{
+        GreedyMemoryPool, MemoryPool, UnboundedMemoryPool,
+    };
     use datafusion_execution::runtime_env::RuntimeEnvBuilder;
     use datafusion_expr::Operator;
     use datafusion_physical_expr::expressions::{BinaryExpr, Literal};
@@ -4278,8 +4446,12 @@ mod tests {
         ];
 
         for join_type in join_types {
+            // Disable spilling so the build side has to fit in memory
             let runtime = RuntimeEnvBuilder::new()
                 .with_memory_limit(100, 1.0)
+                .with_disk_manager_builder(
+                    DiskManagerBuilder::default().with_mode(DiskManagerMode::Disabled),
+                )
                 .build_arc()?;
             let task_ctx = TaskContext::default().with_runtime(runtime);
             let task_ctx = Arc::new(task_ctx);
@@ -4353,8 +4525,12 @@ mod tests {
         ];
 
         for join_type in join_types {
+            // Disable spilling so the build side has to fit in memory
             let runtime = RuntimeEnvBuilder::new()
                 .with_memory_limit(100, 1.0)
+                .with_disk_manager_builder(
+                    DiskManagerBuilder::default().with_mode(DiskManagerMode::Disabled),
+                )
                 .build_arc()?;
             let session_config = SessionConfig::default().with_batch_size(50);
             let task_ctx = TaskContext::default()
@@ -4512,6 +4688,53 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn collect_left_input_without_memory_for_bloom_filter() -> Result<()> {
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 6]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let on_left = vec![Arc::new(Column::new_with_schema("a1", &left.schema())?) as _];
+        let membership_options = MembershipFilterOptions {
+            max_inlist_rows: 0,
+            enable_bloom_filter: true,
+        };
+
+        let collect = |pool: Arc<dyn MemoryPool>| {
+            let task_ctx = Arc::new(TaskContext::default());
+            let reservation = MemoryConsumer::new("HashJoinInput").register(&pool);
+            let metrics = BuildProbeJoinMetrics::new(0, &ExecutionPlanMetricsSet::new());
+            collect_left_input(
+                RandomState::with_seeds(0, 0, 0, 0),
+                left.execute(0, task_ctx).unwrap(),
+                on_left.clone(),
+                metrics,
+                reservation,
+                false,
+                1,
+                true,
+                membership_options,
+            )
+        };
+
+        // With enough memory the build side keys are pushed down as a bloom filter
+        let pool: Arc<dyn MemoryPool> = Arc::new(UnboundedMemoryPool::default());
+        let data = collect(Arc::clone(&pool)).await?;
+        assert!(matches!(
+            data.membership,
+            Some(MembershipFilter::BloomFilter(_))
+        ));
+
+        // Without memory for the bloom filter only the bounds are pushed down
+        let pool = Arc::new(GreedyMemoryPool::new(pool.reserved() - 1));
+        let data = collect(pool).await?;
+        assert!(data.membership.is_none());
+        assert!(data.bounds.is_some());
+
+        Ok(())
+    }
+
     /// Returns the column names on the schema
     fn columns(schema: &Schema) -> Vec<String> {
         schema.fields().iter().map(|f| f.name().clone()).collect()
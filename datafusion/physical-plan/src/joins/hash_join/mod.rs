@@ -19,6 +19,8 @@
 
 pub use exec::HashJoinExec;
 
+mod bloom_filter;
 mod exec;
 mod shared_bounds;
+mod spill;
 mod stream;
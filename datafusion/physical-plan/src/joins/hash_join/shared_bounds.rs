@@ -21,13 +21,18 @@
 use std::fmt;
 use std::sync::Arc;
 
+use crate::joins::hash_join::bloom_filter::{BloomFilter, BloomFilterExpr};
 use crate::joins::PartitionMode;
 use crate::ExecutionPlan;
 use crate::ExecutionPlanProperties;
 
+use arrow::datatypes::SchemaRef;
+use datafusion_common::config::ConfigOptions;
 use datafusion_common::{Result, ScalarValue};
 use datafusion_expr::Operator;
-use datafusion_physical_expr::expressions::{lit, BinaryExpr, DynamicFilterPhysicalExpr};
+use datafusion_physical_expr::expressions::{
+    in_list, lit, BinaryExpr, DynamicFilterPhysicalExpr,
+};
 use datafusion_physical_expr::{PhysicalExpr, PhysicalExprRef};
 
 use itertools::Itertools;
@@ -50,6 +55,37 @@ impl ColumnBounds {
     }
 }
 
+/// Represents the join keys of the build side of a single partition, pushed
+/// down to the probe side in addition to the min/max bounds, which are not
+/// selective for high-cardinality keys.
+#[derive(Debug, Clone)]
+pub(crate) enum MembershipFilter {
+    /// The distinct non-null values of each join key column, for small build
+    /// sides. Index corresponds to the join key expression index.
+    InList(Vec<Vec<ScalarValue>>),
+    /// A bloom filter of the hashes of the join keys, for larger build sides
+    BloomFilter(Arc<BloomFilter>),
+}
+
+/// Options for the [`MembershipFilter`] of the join keys of the build side
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct MembershipFilterOptions {
+    /// Maximum number of build-side rows for which the join keys are pushed
+    /// down as `IN` lists
+    pub(crate) max_inlist_rows: usize,
+    /// Whether to push down a bloom filter for larger build sides
+    pub(crate) enable_bloom_filter: bool,
+}
+
+impl MembershipFilterOptions {
+    pub(crate) fn from_config(config: &ConfigOptions) -> Self {
+        Self {
+            max_inlist_rows: config.optimizer.hash_join_inlist_pushdown_max_rows,
+            enable_bloom_filter: config.optimizer.enable_hash_join_bloom_filter_pushdown,
+        }
+    }
+}
+
 /// Represents the bounds for all join key columns from a single partition.
 /// This contains the min/max values computed from one partition's build-side data.
 #[derive(Debug, Clone)]
@@ -59,13 +95,20 @@ pub(crate) struct PartitionBounds {
     /// Min/max bounds for each join key column in this partition.
     /// Index corresponds to the join key expression index.
     column_bounds: Vec<ColumnBounds>,
+    /// Filter on the join keys of this partition, if any
+    membership: Option<MembershipFilter>,
 }
 
 impl PartitionBounds {
-    pub(crate) fn new(partition: usize, column_bounds: Vec<ColumnBounds>) -> Self {
+    pub(crate) fn new(
+        partition: usize,
+        column_bounds: Vec<ColumnBounds>,
+        membership: Option<MembershipFilter>,
+    ) -> Self {
         Self {
             partition,
             column_bounds,
+            membership,
         }
     }
 
@@ -109,6 +152,8 @@ pub(crate) struct SharedBoundsAccumulator {
     dynamic_filter: Arc<DynamicFilterPhysicalExpr>,
     /// Right side join expressions needed for creating filter bounds
     on_right: Vec<PhysicalExprRef>,
+    /// Schema of the right side, needed for creating `IN` lists
+    right_schema: SchemaRef,
 }
 
 /// State protected by SharedBoundsAccumulator's mutex
@@ -172,6 +217,7 @@ impl SharedBoundsAccumulator {
             barrier: Barrier::new(expected_calls),
             dynamic_filter,
             on_right,
+            right_schema: right_child.schema(),
         }
     }
 
@@ -184,6 +230,10 @@ impl SharedBoundsAccumulator {
     /// ((col0 >= p0_min0 AND col0 <= p0_max0 AND col1 >= p0_min1 AND col1 <= p0_max1)
     ///  OR
     ///  (col0 >= p1_min0 AND col0 <= p1_max0 AND col1 >= p1_min1 AND col1 <= p1_max1))
+    ///
+    /// Each partition's predicate also checks that the join keys are among the
+    /// join keys of the partition, with `IN` lists or a bloom filter, if these
+    /// were computed.
     pub(crate) fn create_filter_from_partition_bounds(
         &self,
         bounds: &[PartitionBounds],
@@ -218,6 +268,9 @@ impl SharedBoundsAccumulator {
                     column_predicates.push(range_expr);
                 }
             }
+            if let Some(membership) = &partition_bounds.membership {
+                column_predicates.extend(self.create_membership_predicates(membership)?);
+            }
 
             // Combine all column predicates for this partition with AND
             if !column_predicates.is_empty() {
@@ -244,6 +297,32 @@ impl SharedBoundsAccumulator {
         Ok(combined_predicate)
     }
 
+    /// Create the predicates checking that the right side join keys are among
+    /// the join keys of a partition of the left side
+    fn create_membership_predicates(
+        &self,
+        membership: &MembershipFilter,
+    ) -> Result<Vec<Arc<dyn PhysicalExpr>>> {
+        match membership {
+            MembershipFilter::InList(values) => self
+                .on_right
+                .iter()
+                .zip(values)
+                .map(|(right_expr, values)| {
+                    // Null keys never match
+                    if values.is_empty() {
+                        return Ok(lit(false));
+                    }
+                    let list = values.iter().map(|value| lit(value.clone())).collect();
+                    in_list(Arc::clone(right_expr), list, &false, &self.right_schema)
+                })
+                .collect(),
+            MembershipFilter::BloomFilter(bloom_filter) => Ok(vec![Arc::new(
+                BloomFilterExpr::new(self.on_right.clone(), Arc::clone(bloom_filter)),
+            )]),
+        }
+    }
+
     /// Report bounds from a completed partition and update dynamic filter if all partitions are done
     ///
     /// This method coordinates the dynamic filter updates across all partitions. It stores the
@@ -264,6 +343,7 @@ impl SharedBoundsAccumulator {
     /// # Arguments
     /// * `left_side_partition_id` - The identifier for the **left-side** partition reporting its bounds
     /// * `partition_bounds` - The bounds computed by this partition (if any)
+    /// * `membership` - The filter on the join keys of this partition (if any)
     ///
     /// # Returns
     /// * `Result<()>` - Ok if successful, Err if filter update failed
@@ -271,6 +351,7 @@ impl SharedBoundsAccumulator {
         &self,
         left_side_partition_id: usize,
         partition_bounds: Option<Vec<ColumnBounds>>,
+        membership: Option<MembershipFilter>,
     ) -> Result<()> {
         // Store bounds in the accumulator - this runs once per partition
         if let Some(bounds) = partition_bounds {
@@ -286,9 +367,11 @@ impl SharedBoundsAccumulator {
             };
 
             if should_push {
-                guard
-                    .bounds
-                    .push(PartitionBounds::new(left_side_partition_id, bounds));
+                guard.bounds.push(PartitionBounds::new(
+                    left_side_partition_id,
+                    bounds,
+                    membership,
+                ));
             }
         }
 
@@ -0,0 +1,606 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Spilling ("grace") execution of [`super::HashJoinExec`]
+//!
+//! When the build side of a hash join does not fit in memory, both inputs are
+//! split into [`SPILL_FANOUT`] partitions by hashing their join keys, and the
+//! partitions are written to disk using a [`SpillManager`]. Rows with equal
+//! join keys always end up in the same partition, so each pair of build and
+//! probe partitions can then be joined independently, using the regular
+//! in-memory [`HashJoinStream`]. Partitions that still do not fit are split
+//! again with a different hash seed, up to [`MAX_SPILL_DEPTH`] times.
+//!
+//! ```text
+//!   build input ──► buffer in memory ──(reservation exhausted)──► hash partition ──► spill files B0..Bn
+//!   probe input ─────────────────────────────────────────────────► hash partition ──► spill files P0..Pn
+//!
+//!   for each i: join(Bi, Pi) (recursively, with the next hash seed)
+//! ```
+
+use std::sync::{Arc, OnceLock};
+
+use crate::joins::hash_join::exec::{collect_left_input, CollectLeftAccumulator};
+use crate::joins::hash_join::shared_bounds::{
+    MembershipFilterOptions, SharedBoundsAccumulator,
+};
+use crate::joins::hash_join::stream::{
+    BuildSide, BuildSideInitialState, HashJoinStream, HashJoinStreamState,
+};
+use crate::joins::utils::{
+    need_produce_result_in_final, BuildProbeJoinMetrics, ColumnIndex, JoinFilter, OnceFut,
+};
+use crate::joins::PartitionMode;
+use crate::memory::MemoryStream;
+use crate::metrics::{ExecutionPlanMetricsSet, SpillMetrics};
+use crate::spill::get_record_batch_memory_size;
+use crate::spill::in_progress_spill_file::InProgressSpillFile;
+use crate::spill::spill_manager::SpillManager;
+use crate::stream::RecordBatchStreamAdapter;
+use crate::{hash_utils::create_hashes, EmptyRecordBatchStream};
+use crate::{PhysicalExpr, SendableRecordBatchStream};
+
+use arrow::array::UInt32Array;
+use arrow::compute::take_record_batch;
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use datafusion_common::{internal_err, DataFusionError, JoinType, NullEquality, Result};
+use datafusion_execution::disk_manager::RefCountedTempFile;
+use datafusion_execution::memory_pool::MemoryConsumer;
+use datafusion_execution::TaskContext;
+use datafusion_physical_expr::PhysicalExprRef;
+
+use ahash::RandomState;
+use futures::future::BoxFuture;
+use futures::{stream, FutureExt, StreamExt, TryStreamExt};
+
+/// Number of partitions each input is split into when the build side spills
+const SPILL_FANOUT: usize = 16;
+
+/// Maximum number of times a spilled partition is split again before giving up
+const MAX_SPILL_DEPTH: usize = 4;
+
+/// Returns the random state used to assign rows to spill partitions at `depth`.
+///
+/// The seeds differ from both the hash join and `RepartitionExec` seeds, and
+/// between levels, so that a partition that is split again actually spreads
+/// its rows over the new partitions.
+fn spill_random_state(depth: usize) -> RandomState {
+    RandomState::with_seeds('G' as u64, 'R' as u64, 'A' as u64, depth as u64)
+}
+
+/// Everything needed to run one (possibly spilling) hash join over a single
+/// pair of build and probe streams.
+pub(super) struct SpillableHashJoin {
+    /// Output partition this join produces
+    pub(super) partition: usize,
+    /// Output schema of the join
+    pub(super) schema: SchemaRef,
+    /// Build side join key expressions
+    pub(super) on_left: Vec<PhysicalExprRef>,
+    /// Probe side join key expressions
+    pub(super) on_right: Vec<PhysicalExprRef>,
+    /// Optional join filter
+    pub(super) filter: Option<JoinFilter>,
+    /// Type of the join
+    pub(super) join_type: JoinType,
+    /// Random state used to build the hash tables
+    pub(super) random_state: RandomState,
+    /// Output columns of the join
+    pub(super) column_indices: Vec<ColumnIndex>,
+    /// Null equality of the join keys
+    pub(super) null_equality: NullEquality,
+    /// Maximum output batch size
+    pub(super) batch_size: usize,
+    /// Whether the probe side ordering must be preserved (only while nothing is spilled)
+    pub(super) right_side_ordered: bool,
+    /// Partition mode of the join, used to report dynamic filter bounds
+    pub(super) mode: PartitionMode,
+    /// Name of the memory consumer holding the build side
+    pub(super) consumer_name: String,
+    /// Metrics of the [`super::HashJoinExec`]
+    pub(super) metrics: ExecutionPlanMetricsSet,
+    /// Spill metrics, registered the first time this join spills
+    pub(super) spill_metrics: OnceLock<SpillMetrics>,
+    /// Task context of the execution
+    pub(super) context: Arc<TaskContext>,
+}
+
+impl SpillableHashJoin {
+    /// Executes the join of `left` (build) and `right` (probe), spilling both
+    /// inputs to disk if the build side does not fit in memory.
+    pub(super) fn execute(
+        self: Arc<Self>,
+        left: SendableRecordBatchStream,
+        right: SendableRecordBatchStream,
+        join_metrics: BuildProbeJoinMetrics,
+        bounds_accumulator: Option<Arc<SharedBoundsAccumulator>>,
+    ) -> SendableRecordBatchStream {
+        let schema = Arc::clone(&self.schema);
+        let stream =
+            stream::once(self.join(left, right, join_metrics, bounds_accumulator, 0))
+                .try_flatten();
+        Box::pin(RecordBatchStreamAdapter::new(schema, stream))
+    }
+
+    /// Joins a pair of build and probe inputs at the given spill `depth`.
+    ///
+    /// The build side is first buffered in memory. If it fits, the join is
+    /// performed by a regular [`HashJoinStream`]. Otherwise both sides are
+    /// partitioned to disk and each partition pair is joined recursively.
+    fn join(
+        self: Arc<Self>,
+        mut left: SendableRecordBatchStream,
+        right: SendableRecordBatchStream,
+        join_metrics: BuildProbeJoinMetrics,
+        bounds_accumulator: Option<Arc<SharedBoundsAccumulator>>,
+        depth: usize,
+    ) -> BoxFuture<'static, Result<SendableRecordBatchStream>> {
+        async move {
+            let left_schema = left.schema();
+            let mut reservation = MemoryConsumer::new(&self.consumer_name)
+                .with_can_spill(true)
+                .register(self.context.memory_pool());
+
+            // Dynamic filter bounds must be reported for the whole build side,
+            // even if it ends up being spilled
+            let mut bounds_accumulators = bounds_accumulator
+                .is_some()
+                .then(|| {
+                    self.on_left
+                        .iter()
+                        .map(|expr| {
+                            CollectLeftAccumulator::try_new(Arc::clone(expr), &left_schema)
+                        })
+                        .collect::<Result<Vec<_>>>()
+                })
+                .transpose()?;
+            let mut num_rows = 0;
+
+            let mut batches = vec![];
+            let mut spill_error = None;
+            while let Some(batch) = left.next().await.transpose()? {
+                if let Some(accumulators) = bounds_accumulators.as_mut() {
+                    for accumulator in accumulators {
+                        accumulator.update_batch(&batch)?;
+                    }
+                }
+                num_rows += batch.num_rows();
+
+                let batch_size = get_record_batch_memory_size(&batch);
+                batches.push(batch);
+                if let Err(e) = reservation.try_grow(batch_size) {
+                    spill_error = Some(e);
+                    break;
+                }
+            }
+
+            if spill_error.is_none() {
+                // The build side fits, try to create the hash table in memory
+                reservation.free();
+                let build_stream = Box::pin(MemoryStream::try_new(
+                    batches.clone(),
+                    Arc::clone(&left_schema),
+                    None,
+                )?);
+                match collect_left_input(
+                    self.random_state.clone(),
+                    build_stream,
+                    self.on_left.clone(),
+                    join_metrics.clone(),
+                    reservation,
+                    need_produce_result_in_final(self.join_type),
+                    1,
+                    bounds_accumulator.is_some(),
+                    MembershipFilterOptions::from_config(
+                        self.context.session_config().options(),
+                    ),
+                )
+                .await
+                {
+                    Ok(left_data) => {
+                        let left_fut = OnceFut::new(async move { Ok(left_data) });
+                        return Ok(Box::pin(HashJoinStream::new(
+                            self.partition,
+                            Arc::clone(&self.schema),
+                            self.on_right.clone(),
+                            self.filter.clone(),
+                            self.join_type,
+                            right,
+                            self.random_state.clone(),
+                            join_metrics,
+                            self.column_indices.clone(),
+                            self.null_equality,
+                            HashJoinStreamState::WaitBuildSide,
+                            BuildSide::Initial(BuildSideInitialState { left_fut }),
+                            self.batch_size,
+                            vec![],
+                            self.right_side_ordered && depth == 0,
+                            bounds_accumulator,
+                            self.mode,
+                        )) as SendableRecordBatchStream);
+                    }
+                    Err(e) if is_resources_exhausted(&e) => spill_error = Some(e),
+                    Err(e) => return Err(e),
+                }
+                reservation = MemoryConsumer::new(&self.consumer_name)
+                    .with_can_spill(true)
+                    .register(self.context.memory_pool());
+            }
+
+            let Some(spill_error) = spill_error else {
+                return internal_err!("Hash join build side was expected to spill");
+            };
+            if depth >= MAX_SPILL_DEPTH {
+                return Err(spill_error.context(format!(
+                    "Hash join build side still does not fit in memory after spilling {MAX_SPILL_DEPTH} times"
+                )));
+            }
+
+            // Partition the buffered and remaining build side to disk
+            let build_spill_manager = self.spill_manager(Arc::clone(&left_schema));
+            let mut build_files =
+                self.create_spill_files(&build_spill_manager, "HashJoinBuildSpill")?;
+            let random_state = spill_random_state(depth);
+            for batch in batches.drain(..) {
+                self.spill_partitioned(&batch, &self.on_left, &random_state, &mut build_files)?;
+            }
+            reservation.free();
+            while let Some(batch) = left.next().await.transpose()? {
+                if let Some(accumulators) = bounds_accumulators.as_mut() {
+                    for accumulator in accumulators {
+                        accumulator.update_batch(&batch)?;
+                    }
+                }
+                num_rows += batch.num_rows();
+                self.spill_partitioned(&batch, &self.on_left, &random_state, &mut build_files)?;
+            }
+
+            if let Some(bounds_accumulator) = bounds_accumulator {
+                let bounds = match bounds_accumulators {
+                    Some(accumulators) if num_rows > 0 => Some(
+                        accumulators
+                            .into_iter()
+                            .map(CollectLeftAccumulator::evaluate)
+                            .collect::<Result<Vec<_>>>()?,
+                    ),
+                    _ => None,
+                };
+                let left_side_partition_id = match self.mode {
+                    PartitionMode::Partitioned => self.partition,
+                    _ => 0,
+                };
+                // Only the bounds of a spilled build side are pushed down, as
+                // its join keys do not fit in memory
+                bounds_accumulator
+                    .report_partition_bounds(left_side_partition_id, bounds, None)
+                    .await?;
+            }
+
+            // Partition the probe side to disk
+            let right_schema = right.schema();
+            let probe_spill_manager = self.spill_manager(Arc::clone(&right_schema));
+            let mut probe_files =
+                self.create_spill_files(&probe_spill_manager, "HashJoinProbeSpill")?;
+            let mut right = right;
+            while let Some(batch) = right.next().await.transpose()? {
+                self.spill_partitioned(&batch, &self.on_right, &random_state, &mut probe_files)?;
+            }
+
+            let build_files = finish_spill_files(build_files)?;
+            let probe_files = finish_spill_files(probe_files)?;
+
+            let this = Arc::clone(&self);
+            let output = stream::iter(build_files.into_iter().zip(probe_files))
+                .filter(|(build_file, probe_file)| {
+                    futures::future::ready(build_file.is_some() || probe_file.is_some())
+                })
+                .then(move |(build_file, probe_file)| {
+                    let this = Arc::clone(&this);
+                    let left = read_spill_file(
+                        &build_spill_manager,
+                        build_file,
+                        Arc::clone(&left_schema),
+                    );
+                    let right = read_spill_file(
+                        &probe_spill_manager,
+                        probe_file,
+                        Arc::clone(&right_schema),
+                    );
+                    let join_metrics = join_metrics.clone();
+                    async move {
+                        Arc::clone(&this)
+                            .join(left?, right?, join_metrics, None, depth + 1)
+                            .await
+                    }
+                })
+                .try_flatten();
+
+            Ok(Box::pin(RecordBatchStreamAdapter::new(
+                Arc::clone(&self.schema),
+                output,
+            )) as SendableRecordBatchStream)
+        }
+        .boxed()
+    }
+
+    /// Creates a [`SpillManager`] for batches of the given schema
+    fn spill_manager(&self, schema: SchemaRef) -> SpillManager {
+        let spill_metrics = self
+            .spill_metrics
+            .get_or_init(|| SpillMetrics::new(&self.metrics, self.partition));
+        SpillManager::new(self.context.runtime_env(), spill_metrics.clone(), schema)
+            .with_compression_type(self.context.session_config().spill_compression())
+    }
+
+    /// Creates one in-progress spill file per spill partition
+    fn create_spill_files(
+        &self,
+        spill_manager: &SpillManager,
+        request_description: &str,
+    ) -> Result<Vec<InProgressSpillFile>> {
+        (0..SPILL_FANOUT)
+            .map(|_| spill_manager.create_in_progress_file(request_description))
+            .collect()
+    }
+
+    /// Splits `batch` by the hash of its join keys and appends each part to
+    /// the corresponding spill file
+    fn spill_partitioned(
+        &self,
+        batch: &RecordBatch,
+        on: &[Arc<dyn PhysicalExpr>],
+        random_state: &RandomState,
+        files: &mut [InProgressSpillFile],
+    ) -> Result<()> {
+        if batch.num_rows() == 0 {
+            return Ok(());
+        }
+        let keys = on
+            .iter()
+            .map(|expr| expr.evaluate(batch)?.into_array(batch.num_rows()))
+            .collect::<Result<Vec<_>>>()?;
+        let mut hashes = vec![0; batch.num_rows()];
+        create_hashes(&keys, random_state, &mut hashes)?;
+
+        let mut indices = vec![vec![]; files.len()];
+        for (row, hash) in hashes.iter().enumerate() {
+            indices[(*hash % files.len() as u64) as usize].push(row as u32);
+        }
+        for (file, indices) in files.iter_mut().zip(indices) {
+            if indices.is_empty() {
+                continue;
+            }
+            let part = take_record_batch(batch, &UInt32Array::from(indices))?;
+            file.append_batch(&part)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns true if `e` was caused by a failed memory reservation
+fn is_resources_exhausted(e: &DataFusionError) -> bool {
+    matches!(e.find_root(), DataFusionError::ResourcesExhausted(_))
+}
+
+/// Finalizes all spill files, returning `None` for partitions without rows
+fn finish_spill_files(
+    files: Vec<InProgressSpillFile>,
+) -> Result<Vec<Option<RefCountedTempFile>>> {
+    files.into_iter().map(|mut file| file.finish()).collect()
+}
+
+/// Reads back a spill partition, or returns an empty stream if it has no rows
+fn read_spill_file(
+    spill_manager: &SpillManager,
+    file: Option<RefCountedTempFile>,
+    schema: SchemaRef,
+) -> Result<SendableRecordBatchStream> {
+    match file {
+        Some(file) => spill_manager.read_spill_as_stream(file, None),
+        None => Ok(Box::pin(EmptyRecordBatchStream::new(schema))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::common::collect;
+    use crate::joins::HashJoinExec;
+    use crate::test::TestMemoryExec;
+    use crate::ExecutionPlan;
+
+    use arrow::array::{Int32Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use datafusion_common::NullEquality;
+    use datafusion_execution::config::SessionConfig;
+    use datafusion_execution::disk_manager::{DiskManagerBuilder, DiskManagerMode};
+    use datafusion_execution::runtime_env::RuntimeEnvBuilder;
+    use datafusion_physical_expr::expressions::Column;
+
+    fn spill_config() -> SessionConfig {
+        SessionConfig::new().set_bool("datafusion.execution.enable_hash_join_spill", true)
+    }
+
+    fn build_input(
+        name: &str,
+        num_rows: i32,
+        partitions: usize,
+    ) -> Arc<dyn ExecutionPlan> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(format!("{name}_key"), DataType::Int32, true),
+            Field::new(format!("{name}_value"), DataType::Utf8, false),
+        ]));
+        let batches = (0..partitions)
+            .map(|partition| {
+                (0..num_rows)
+                    .step_by(100)
+                    .map(|start| {
+                        let keys = (start..(start + 100).min(num_rows))
+                            .filter(|key| *key as usize % partitions == partition)
+                            .collect::<Vec<_>>();
+                        let values = keys
+                            .iter()
+                            .map(|key| format!("{name}-{key:05}"))
+                            .collect::<Vec<_>>();
+                        RecordBatch::try_new(
+                            Arc::clone(&schema),
+                            vec![
+                                Arc::new(Int32Array::from(
+                                    keys.into_iter()
+                                        .map(|key| (key % 7 != 0).then_some(key))
+                                        .collect::<Vec<_>>(),
+                                )),
+                                Arc::new(StringArray::from(values)),
+                            ],
+                        )
+                        .unwrap()
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        TestMemoryExec::try_new_exec(&batches, schema, None).unwrap()
+    }
+
+    async fn run_join(
+        join_type: JoinType,
+        memory_limit: Option<usize>,
+    ) -> Result<(Vec<String>, usize)> {
+        let left = build_input("l", 3000, 2);
+        let right = build_input("r", 4000, 2);
+        let on = vec![(
+            Arc::new(Column::new_with_schema("l_key", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("r_key", &right.schema())?) as _,
+        )];
+        let join = HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            None,
+            &join_type,
+            None,
+            PartitionMode::Partitioned,
+            NullEquality::NullEqualsNothing,
+        )?;
+
+        let mut runtime = RuntimeEnvBuilder::new().with_disk_manager_builder(
+            DiskManagerBuilder::default().with_mode(DiskManagerMode::OsTmpDirectory),
+        );
+        if let Some(memory_limit) = memory_limit {
+            runtime = runtime.with_memory_limit(memory_limit, 1.0);
+        }
+        let task_ctx = Arc::new(
+            TaskContext::default()
+                .with_session_config(spill_config())
+                .with_runtime(runtime.build_arc()?),
+        );
+
+        let mut rows = vec![];
+        for partition in 0..join.properties().output_partitioning().partition_count() {
+            let batches =
+                collect(join.execute(partition, Arc::clone(&task_ctx))?).await?;
+            for batch in batches {
+                let formatted =
+                    arrow::util::pretty::pretty_format_batches(&[batch])?.to_string();
+                rows.extend(
+                    formatted
+                        .lines()
+                        .filter(|line| line.starts_with("| ") && !line.contains("_key"))
+                        .map(|line| {
+                            line.split('|').map(str::trim).collect::<Vec<_>>().join("|")
+                        }),
+                );
+            }
+        }
+        rows.sort();
+        let spill_count = join
+            .metrics()
+            .and_then(|metrics| metrics.spill_count())
+            .unwrap_or(0);
+        Ok((rows, spill_count))
+    }
+
+    #[tokio::test]
+    async fn spilling_hash_join_matches_in_memory_join() -> Result<()> {
+        for join_type in [
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::Full,
+            JoinType::LeftSemi,
+            JoinType::LeftAnti,
+            JoinType::RightSemi,
+            JoinType::RightAnti,
+            JoinType::LeftMark,
+            JoinType::RightMark,
+        ] {
+            let (expected, spill_count) = run_join(join_type, None).await?;
+            assert_eq!(spill_count, 0, "{join_type}");
+
+            let (actual, spill_count) = run_join(join_type, Some(20_000)).await?;
+            assert!(spill_count > 0, "{join_type} did not spill");
+            assert_eq!(expected, actual, "{join_type}");
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn spilling_hash_join_gives_up_on_skewed_keys() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("k", DataType::Int32, false)]));
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![Arc::new(Int32Array::from(vec![1; 1000]))],
+        )?;
+        let input = TestMemoryExec::try_new_exec(
+            &[vec![batch.clone(); 50]],
+            Arc::clone(&schema),
+            None,
+        )?;
+        let on = vec![(
+            Arc::new(Column::new("k", 0)) as _,
+            Arc::new(Column::new("k", 0)) as _,
+        )];
+        let join = HashJoinExec::try_new(
+            Arc::clone(&input) as _,
+            input,
+            on,
+            None,
+            &JoinType::Inner,
+            None,
+            PartitionMode::Partitioned,
+            NullEquality::NullEqualsNothing,
+        )?;
+        let runtime = RuntimeEnvBuilder::new()
+            .with_memory_limit(50_000, 1.0)
+            .build_arc()?;
+        let task_ctx = Arc::new(
+            TaskContext::default()
+                .with_session_config(spill_config())
+                .with_runtime(runtime),
+        );
+
+        let err = collect(join.execute(0, task_ctx)?).await.unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("still does not fit in memory after spilling"),
+            "{err}"
+        );
+        assert!(is_resources_exhausted(&err), "{err}");
+        Ok(())
+    }
+}
@@ -420,9 +420,14 @@ impl HashJoinStream {
             };
 
             let left_data_bounds = left_data.bounds.clone();
+            let left_data_membership = left_data.membership.clone();
             self.bounds_waiter = Some(OnceFut::new(async move {
                 bounds_accumulator
-                    .report_partition_bounds(left_side_partition_id, left_data_bounds)
+                    .report_partition_bounds(
+                        left_side_partition_id,
+                        left_data_bounds,
+                        left_data_membership,
+                    )
                     .await
             }));
             self.state = HashJoinStreamState::WaitPartitionBoundsReport;
@@ -0,0 +1,1353 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`IEJoinExec`]: joins on inequality (range) predicates without equijoin keys.
+
+use std::any::Any;
+use std::cmp::Ordering;
+use std::fmt::Formatter;
+use std::pin::Pin;
+use std::sync::atomic::{self, AtomicUsize};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use crate::execution_plan::{boundedness_from_children, EmissionType};
+use crate::joins::utils::{
+    adjust_indices_by_join_type, apply_join_filter_to_indices,
+    asymmetric_join_output_partitioning, build_batch_from_indices, build_join_schema,
+    check_join_is_valid, estimate_join_statistics, get_final_indices_from_shared_bitmap,
+    need_produce_result_in_final, reorder_output_after_swap, BatchSplitter,
+    BatchTransformer, BuildProbeJoinMetrics, ColumnIndex, JoinFilter, OnceAsync, OnceFut,
+};
+use crate::joins::SharedBitmapBuilder;
+use crate::metrics::{ExecutionPlanMetricsSet, MetricsSet};
+use crate::{
+    DisplayAs, DisplayFormatType, Distribution, ExecutionPlan, ExecutionPlanProperties,
+    PlanProperties, RecordBatchStream, SendableRecordBatchStream,
+};
+
+use arrow::array::{Array, ArrayRef, BooleanBufferBuilder, UInt32Array, UInt64Array};
+use arrow::compute::concat_batches;
+use arrow::datatypes::{Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use arrow::row::{Row, RowConverter, Rows, SortField};
+use datafusion_common::tree_node::{Transformed, TransformedResult, TreeNode};
+use datafusion_common::{internal_err, plan_err, JoinSide, JoinType, Result, Statistics};
+use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
+use datafusion_execution::TaskContext;
+use datafusion_expr::Operator;
+use datafusion_physical_expr::equivalence::join_equivalence_properties;
+use datafusion_physical_expr::expressions::{BinaryExpr, Column};
+use datafusion_physical_expr::utils::{collect_columns, split_conjunction};
+use datafusion_physical_expr_common::physical_expr::{fmt_sql, PhysicalExprRef};
+
+use futures::{ready, Stream, StreamExt, TryStreamExt};
+use parking_lot::Mutex;
+
+/// A range predicate `left op right` of a join filter, where `left` only
+/// references columns of the left input and `right` only references columns
+/// of the right input.
+#[derive(Debug, Clone)]
+pub struct RangePredicate {
+    /// Expression on the left input
+    pub left: PhysicalExprRef,
+    /// One of `<`, `<=`, `>` or `>=`
+    pub op: Operator,
+    /// Expression on the right input
+    pub right: PhysicalExprRef,
+}
+
+impl RangePredicate {
+    /// Returns whether the predicate holds when the left value compares to
+    /// the right value as `ordering`
+    fn holds(&self, ordering: Ordering) -> bool {
+        match self.op {
+            Operator::Lt => ordering == Ordering::Less,
+            Operator::LtEq => ordering != Ordering::Greater,
+            Operator::Gt => ordering == Ordering::Greater,
+            _ => ordering != Ordering::Less,
+        }
+    }
+}
+
+/// Join execution plan for joins whose filter contains range predicates
+/// between the inputs, such as the band join
+/// `a.start <= b.ts AND b.ts < a.end`, but no equijoin keys.
+///
+/// Unlike [`NestedLoopJoinExec`], which evaluates the filter for every pair
+/// of rows, this operator sorts the build side once and uses up to two range
+/// predicates (chosen from the conjuncts of the filter) to enumerate only the
+/// pairs of rows that satisfy them, following the IEJoin algorithm:
+///
+/// * The left (build) input is collected into a single batch, and its rows
+///   are sorted by the left expression `x1` of the first predicate.
+/// * For each right (probe) batch, the rows satisfying the first predicate
+///   form a contiguous range of the sorted build rows, found with a binary
+///   search on the right value `y1`.
+/// * If there is a second predicate, the probe rows are visited in the order
+///   of their right value `y2`. Build rows are marked in a bit array
+///   (indexed by their position in the `x1` order) as soon as they satisfy
+///   the second predicate `x2 op2 y2`, which remains true for all
+///   subsequent probe rows. The matches of a probe row are then the marked
+///   bits within its range of the first predicate.
+///
+/// Rows with a null value for any of the used predicates never match. Other
+/// conjuncts of the filter are evaluated on the candidate pairs. Every join
+/// type except AsOf joins is supported; unmatched build rows are emitted
+/// by the last probe partition to finish, like in [`NestedLoopJoinExec`].
+///
+/// [`NestedLoopJoinExec`]: crate::joins::NestedLoopJoinExec
+#[derive(Debug)]
+pub struct IEJoinExec {
+    /// Left (build) side, collected into a single batch
+    left: Arc<dyn ExecutionPlan>,
+    /// Right (probe) side
+    right: Arc<dyn ExecutionPlan>,
+    /// The complete join filter
+    filter: JoinFilter,
+    /// Range predicates of the filter used to find candidate pairs
+    predicates: Vec<RangePredicate>,
+    /// Whether the filter contains conjuncts besides `predicates`
+    residual: bool,
+    /// How the join is performed
+    join_type: JoinType,
+    /// Future that consumes and sorts the left input
+    build_side_data: OnceAsync<IEJoinBuildData>,
+    /// Information of index and left / right placement of columns
+    column_indices: Vec<ColumnIndex>,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+    /// Cache holding plan properties like equivalences, output partitioning etc.
+    cache: PlanProperties,
+}
+
+impl IEJoinExec {
+    /// Tries to create a new [`IEJoinExec`].
+    ///
+    /// # Error
+    /// This function errors when `join_type` is an AsOf join, or when the
+    /// filter has no range predicate usable by this operator (see
+    /// [`Self::range_predicates`]).
+    pub fn try_new(
+        left: Arc<dyn ExecutionPlan>,
+        right: Arc<dyn ExecutionPlan>,
+        filter: JoinFilter,
+        join_type: &JoinType,
+    ) -> Result<Self> {
+        let left_schema = left.schema();
+        let right_schema = right.schema();
+        check_join_is_valid(&left_schema, &right_schema, &[])?;
+        if *join_type == JoinType::AsOf {
+            return plan_err!("IEJoinExec does not support AsOf joins, use AsOfJoinExec");
+        }
+        let mut predicates =
+            Self::range_predicates(&filter, &left_schema, &right_schema)?;
+        if predicates.is_empty() {
+            return plan_err!(
+                "IEJoinExec requires a range predicate between the inputs in the \
+                 join filter, got {}",
+                filter.expression()
+            );
+        }
+        predicates.truncate(2);
+        let residual = split_conjunction(filter.expression()).len() > predicates.len();
+
+        let (join_schema, column_indices) =
+            build_join_schema(&left_schema, &right_schema, join_type);
+        let cache =
+            Self::compute_properties(&left, &right, Arc::new(join_schema), *join_type)?;
+
+        Ok(Self {
+            left,
+            right,
+            filter,
+            predicates,
+            residual,
+            join_type: *join_type,
+            build_side_data: Default::default(),
+            column_indices,
+            metrics: Default::default(),
+            cache,
+        })
+    }
+
+    /// Returns the conjuncts of `filter` that compare an expression on the
+    /// left input with an expression on the right input using `<`, `<=`,
+    /// `>` or `>=`, rewritten against the input schemas and normalized so
+    /// that the left expression comes first.
+    ///
+    /// Only comparisons of expressions with the same, orderable data type
+    /// are returned. [`IEJoinExec`] can be used for a filter if this returns
+    /// at least one predicate.
+    pub fn range_predicates(
+        filter: &JoinFilter,
+        left_schema: &Schema,
+        right_schema: &Schema,
+    ) -> Result<Vec<RangePredicate>> {
+        let mut predicates = vec![];
+        for conjunct in split_conjunction(filter.expression()) {
+            let Some(binary) = conjunct.as_any().downcast_ref::<BinaryExpr>() else {
+                continue;
+            };
+            if !matches!(
+                binary.op(),
+                Operator::Lt | Operator::LtEq | Operator::Gt | Operator::GtEq
+            ) {
+                continue;
+            }
+            let (left, op, right) = match (
+                filter_side(binary.left(), filter),
+                filter_side(binary.right(), filter),
+            ) {
+                (Some(JoinSide::Left), Some(JoinSide::Right)) => {
+                    (binary.left(), *binary.op(), binary.right())
+                }
+                (Some(JoinSide::Right), Some(JoinSide::Left)) => {
+                    (binary.right(), binary.op().swap().unwrap(), binary.left())
+                }
+                _ => continue,
+            };
+            let left = to_input_expr(left, filter, left_schema)?;
+            let right = to_input_expr(right, filter, right_schema)?;
+            let left_type = left.data_type(left_schema)?;
+            if left_type != right.data_type(right_schema)?
+                || !RowConverter::supports_fields(&[SortField::new(left_type)])
+            {
+                continue;
+            }
+            predicates.push(RangePredicate { left, op, right });
+        }
+        Ok(predicates)
+    }
+
+    /// left (build) side
+    pub fn left(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.left
+    }
+
+    /// right (probe) side
+    pub fn right(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.right
+    }
+
+    /// The join filter
+    pub fn filter(&self) -> &JoinFilter {
+        &self.filter
+    }
+
+    /// The range predicates of the filter used to find matching rows
+    pub fn predicates(&self) -> &[RangePredicate] {
+        &self.predicates
+    }
+
+    /// How the join is performed
+    pub fn join_type(&self) -> &JoinType {
+        &self.join_type
+    }
+
+    /// This function creates the cache object that stores the plan properties such as schema, equivalence properties, ordering, partitioning, etc.
+    fn compute_properties(
+        left: &Arc<dyn ExecutionPlan>,
+        right: &Arc<dyn ExecutionPlan>,
+        schema: SchemaRef,
+        join_type: JoinType,
+    ) -> Result<PlanProperties> {
+        let eq_properties = join_equivalence_properties(
+            left.equivalence_properties().clone(),
+            right.equivalence_properties().clone(),
+            &join_type,
+            schema,
+            &[false, false],
+            None,
+            &[],
+        )?;
+
+        let output_partitioning =
+            asymmetric_join_output_partitioning(left, right, &join_type)?;
+
+        let emission_type = if left.boundedness().is_unbounded() {
+            EmissionType::Final
+        } else if right.pipeline_behavior() == EmissionType::Incremental {
+            if need_produce_result_in_final(join_type) {
+                EmissionType::Both
+            } else {
+                EmissionType::Incremental
+            }
+        } else {
+            right.pipeline_behavior()
+        };
+
+        Ok(PlanProperties::new(
+            eq_properties,
+            output_partitioning,
+            emission_type,
+            boundedness_from_children([left, right]),
+        ))
+    }
+
+    /// Returns a new `ExecutionPlan` that runs the join with the left and
+    /// right inputs swapped.
+    ///
+    /// # Notes:
+    ///
+    /// This function should be called BEFORE inserting any repartitioning
+    /// operators on the join's children. Check [`super::HashJoinExec::swap_inputs`]
+    /// for more details.
+    pub fn swap_inputs(&self) -> Result<Arc<dyn ExecutionPlan>> {
+        let new_join = IEJoinExec::try_new(
+            Arc::clone(&self.right),
+            Arc::clone(&self.left),
+            self.filter.swap(),
+            &self.join_type.swap()?,
+        )?;
+
+        // For Semi/Anti joins, swap result will produce same output schema,
+        // no need to wrap them into additional projection
+        if matches!(
+            self.join_type,
+            JoinType::LeftSemi
+                | JoinType::RightSemi
+                | JoinType::LeftAnti
+                | JoinType::RightAnti
+        ) {
+            Ok(Arc::new(new_join))
+        } else {
+            reorder_output_after_swap(
+                Arc::new(new_join),
+                &self.left.schema(),
+                &self.right.schema(),
+            )
+        }
+    }
+}
+
+/// Returns the side of the join input referenced by all columns of `expr`, an
+/// expression on the intermediate schema of `filter`
+fn filter_side(expr: &PhysicalExprRef, filter: &JoinFilter) -> Option<JoinSide> {
+    let mut sides = collect_columns(expr)
+        .into_iter()
+        .map(|column| filter.column_indices()[column.index()].side);
+    let side = sides.next()?;
+    sides.all(|s| s == side).then_some(side)
+}
+
+/// Rewrites `expr` from the intermediate schema of `filter` to the schema of
+/// the join input that it references
+fn to_input_expr(
+    expr: &PhysicalExprRef,
+    filter: &JoinFilter,
+    input_schema: &Schema,
+) -> Result<PhysicalExprRef> {
+    Arc::clone(expr)
+        .transform(|expr| {
+            let Some(column) = expr.as_any().downcast_ref::<Column>() else {
+                return Ok(Transformed::no(expr));
+            };
+            let index = filter.column_indices()[column.index()].index;
+            let name = input_schema.field(index).name();
+            Ok(Transformed::yes(Arc::new(Column::new(name, index)) as _))
+        })
+        .data()
+}
+
+/// Qualifies the displayed expression of one side of a predicate with the
+/// input it is evaluated against, e.g. `left.a@0` or `right.(b@1 + 1)`
+fn display_side(side: &str, expr: &PhysicalExprRef, displayed: String) -> String {
+    if expr.as_any().is::<Column>() {
+        format!("{side}.{displayed}")
+    } else {
+        format!("{side}.({displayed})")
+    }
+}
+
+impl DisplayAs for IEJoinExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut Formatter) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                let predicates = self
+                    .predicates
+                    .iter()
+                    .map(|p| {
+                        format!(
+                            "{} {} {}",
+                            display_side("left", &p.left, p.left.to_string()),
+                            p.op,
+                            display_side("right", &p.right, p.right.to_string())
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let display_filter = if self.residual {
+                    format!(", filter={}", self.filter.expression())
+                } else {
+                    String::new()
+                };
+                write!(
+                    f,
+                    "IEJoinExec: join_type={:?}, predicates=[{predicates}]{display_filter}",
+                    self.join_type
+                )
+            }
+            DisplayFormatType::TreeRender => {
+                if self.join_type != JoinType::Inner {
+                    writeln!(f, "join_type={:?}", self.join_type)?;
+                }
+                let predicates = self
+                    .predicates
+                    .iter()
+                    .map(|p| {
+                        format!(
+                            "{} {} {}",
+                            display_side(
+                                "left",
+                                &p.left,
+                                fmt_sql(p.left.as_ref()).to_string()
+                            ),
+                            p.op,
+                            display_side(
+                                "right",
+                                &p.right,
+                                fmt_sql(p.right.as_ref()).to_string()
+                            )
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                writeln!(f, "predicates={predicates}")
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for IEJoinExec {
+    fn name(&self) -> &'static str {
+        "IEJoinExec"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        &self.cache
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        vec![
+            Distribution::SinglePartition,
+            Distribution::UnspecifiedDistribution,
+        ]
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![false, false]
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![&self.left, &self.right]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(IEJoinExec::try_new(
+            Arc::clone(&children[0]),
+            Arc::clone(&children[1]),
+            self.filter.clone(),
+            &self.join_type,
+        )?))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        if self.left.output_partitioning().partition_count() != 1 {
+            return internal_err!(
+                "Invalid IEJoinExec, the output partition count of the left child must be 1,\
+                 consider using CoalescePartitionsExec or the EnforceDistribution rule"
+            );
+        }
+
+        let join_metrics = BuildProbeJoinMetrics::new(partition, &self.metrics);
+        let reservation = MemoryConsumer::new(format!("IEJoinLoad[{partition}]"))
+            .register(context.memory_pool());
+
+        let build_side_data = self.build_side_data.try_once(|| {
+            let stream = self.left.execute(0, Arc::clone(&context))?;
+            Ok(collect_left_input(
+                stream,
+                self.predicates.clone(),
+                join_metrics.clone(),
+                reservation,
+                need_produce_result_in_final(self.join_type),
+                self.right.output_partitioning().partition_count(),
+            ))
+        })?;
+
+        let batch_size = context.session_config().batch_size();
+        let right = self.right.execute(partition, context)?;
+
+        Ok(Box::pin(IEJoinStream {
+            schema: self.schema(),
+            right_schema: self.right.schema(),
+            predicates: self.predicates.clone(),
+            filter: self.residual.then(|| self.filter.clone()),
+            join_type: self.join_type,
+            right,
+            build_side_data,
+            build_side: None,
+            column_indices: self.column_indices.clone(),
+            join_metrics,
+            batch_size,
+            batch_transformer: BatchSplitter::new(batch_size),
+            state: IEJoinStreamState::WaitBuild,
+        }))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Result<Statistics> {
+        self.partition_statistics(None)
+    }
+
+    fn partition_statistics(&self, partition: Option<usize>) -> Result<Statistics> {
+        if partition.is_some() {
+            return Ok(Statistics::new_unknown(&self.schema()));
+        }
+        estimate_join_statistics(
+            self.left.partition_statistics(None)?,
+            self.right.partition_statistics(None)?,
+            vec![],
+            &self.join_type,
+            &self.schema(),
+        )
+    }
+}
+
+/// The sorted left (build) side of an [`IEJoinExec`]
+struct IEJoinBuildData {
+    /// Build-side data collected to single batch
+    batch: RecordBatch,
+    /// Converters of the predicate expressions to comparable rows
+    converters: Vec<RowConverter>,
+    /// Values of the left expression of every predicate for all build rows
+    values: Vec<Rows>,
+    /// Build rows without null predicate values, sorted by the left
+    /// expression of the first predicate
+    sorted: Vec<u32>,
+    /// Position of every build row in `sorted`, only used with two predicates
+    positions: Vec<u32>,
+    /// Build rows of `sorted` in the order in which they start to satisfy the
+    /// second predicate when the probe rows are visited in order, i.e.
+    /// ascending for `<` and `<=`, descending for `>` and `>=`
+    activation_order: Vec<u32>,
+    /// Shared bitmap builder for visited left indices
+    bitmap: SharedBitmapBuilder,
+    /// Counter of running probe-threads, potentially able to update `bitmap`
+    probe_threads_counter: AtomicUsize,
+    /// Memory reservation for the batch, the sorted values and the bitmap,
+    /// released on drop
+    _reservation: MemoryReservation,
+}
+
+impl IEJoinBuildData {
+    /// Returns the range of `sorted` whose rows satisfy the first predicate
+    /// for the right value `value`
+    fn first_predicate_range(
+        &self,
+        op: Operator,
+        value: Row<'_>,
+    ) -> std::ops::Range<usize> {
+        let x = &self.values[0];
+        let lower = || self.sorted.partition_point(|&i| x.row(i as usize) < value);
+        let upper = || self.sorted.partition_point(|&i| x.row(i as usize) <= value);
+        match op {
+            Operator::Lt => 0..lower(),
+            Operator::LtEq => 0..upper(),
+            Operator::Gt => upper()..self.sorted.len(),
+            _ => lower()..self.sorted.len(),
+        }
+    }
+
+    /// Decrements counter of running threads, and returns `true`
+    /// if caller is the last running thread
+    fn report_probe_completed(&self) -> bool {
+        self.probe_threads_counter
+            .fetch_sub(1, atomic::Ordering::Relaxed)
+            == 1
+    }
+}
+
+/// Evaluates `exprs` on `batch` and converts the results to rows
+fn convert_values(
+    converters: &[RowConverter],
+    exprs: impl Iterator<Item = PhysicalExprRef>,
+    batch: &RecordBatch,
+) -> Result<(Vec<Rows>, Vec<ArrayRef>)> {
+    let arrays = exprs
+        .map(|expr| expr.evaluate(batch)?.into_array(batch.num_rows()))
+        .collect::<Result<Vec<_>>>()?;
+    let rows = converters
+        .iter()
+        .zip(&arrays)
+        .map(|(converter, array)| Ok(converter.convert_columns(&[Arc::clone(array)])?))
+        .collect::<Result<Vec<_>>>()?;
+    Ok((rows, arrays))
+}
+
+/// Returns the rows of `arrays` without nulls
+fn valid_rows(arrays: &[ArrayRef], num_rows: usize) -> Vec<u32> {
+    (0..num_rows)
+        .filter(|&i| arrays.iter().all(|array| array.is_valid(i)))
+        .map(|i| i as u32)
+        .collect()
+}
+
+/// Asynchronously collects the left input into a single batch and sorts it
+async fn collect_left_input(
+    stream: SendableRecordBatchStream,
+    predicates: Vec<RangePredicate>,
+    join_metrics: BuildProbeJoinMetrics,
+    reservation: MemoryReservation,
+    with_visited_left_side: bool,
+    probe_threads_count: usize,
+) -> Result<IEJoinBuildData> {
+    let schema = stream.schema();
+
+    let (batches, metrics, mut reservation) = stream
+        .try_fold(
+            (Vec::new(), join_metrics, reservation),
+            |(mut batches, metrics, mut reservation), batch| async {
+                let batch_size = batch.get_array_memory_size();
+                reservation.try_grow(batch_size)?;
+                metrics.build_mem_used.add(batch_size);
+                metrics.build_input_batches.add(1);
+                metrics.build_input_rows.add(batch.num_rows());
+                batches.push(batch);
+                Ok((batches, metrics, reservation))
+            },
+        )
+        .await?;
+    let timer = metrics.build_time.timer();
+    let batch = concat_batches(&schema, &batches)?;
+    let num_rows = batch.num_rows();
+
+    let converters = predicates
+        .iter()
+        .map(|p| {
+            Ok(RowConverter::new(vec![SortField::new(
+                p.left.data_type(&schema)?,
+            )])?)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let (values, arrays) = convert_values(
+        &converters,
+        predicates.iter().map(|p| Arc::clone(&p.left)),
+        &batch,
+    )?;
+
+    let mut sorted = valid_rows(&arrays, num_rows);
+    sorted.sort_unstable_by(|&a, &b| {
+        values[0].row(a as usize).cmp(&values[0].row(b as usize))
+    });
+
+    let (positions, activation_order) = match predicates.get(1) {
+        Some(second) => {
+            let mut positions = vec![0; num_rows];
+            for (position, &row) in sorted.iter().enumerate() {
+                positions[row as usize] = position as u32;
+            }
+            let mut activation_order = sorted.clone();
+            let descending = matches!(second.op, Operator::Gt | Operator::GtEq);
+            activation_order.sort_unstable_by(|&a, &b| {
+                let ordering = values[1].row(a as usize).cmp(&values[1].row(b as usize));
+                if descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            });
+            (positions, activation_order)
+        }
+        None => (vec![], vec![]),
+    };
+
+    let sorted_size = values.iter().map(Rows::size).sum::<usize>()
+        + (sorted.len() + positions.len() + activation_order.len()) * size_of::<u32>();
+    timer.done();
+    reservation.try_grow(sorted_size)?;
+    metrics.build_mem_used.add(sorted_size);
+
+    let visited_left_side = if with_visited_left_side {
+        let buffer_size = num_rows.div_ceil(8);
+        reservation.try_grow(buffer_size)?;
+        metrics.build_mem_used.add(buffer_size);
+
+        let mut buffer = BooleanBufferBuilder::new(num_rows);
+        buffer.append_n(num_rows, false);
+        buffer
+    } else {
+        BooleanBufferBuilder::new(0)
+    };
+
+    Ok(IEJoinBuildData {
+        batch,
+        converters,
+        values,
+        sorted,
+        positions,
+        activation_order,
+        bitmap: Mutex::new(visited_left_side),
+        probe_threads_counter: AtomicUsize::new(probe_threads_count),
+        _reservation: reservation,
+    })
+}
+
+/// Sets bits of build rows that satisfy the second predicate, indexed by
+/// their position in the order of the first predicate
+struct ActiveRows {
+    words: Vec<u64>,
+}
+
+impl ActiveRows {
+    fn new(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(64)],
+        }
+    }
+
+    fn insert(&mut self, position: usize) {
+        self.words[position / 64] |= 1 << (position % 64);
+    }
+
+    /// Calls `f` with every set position in `range`, in ascending order
+    fn for_each_in(&self, range: std::ops::Range<usize>, mut f: impl FnMut(usize)) {
+        if range.is_empty() {
+            return;
+        }
+        let last_word = (range.end - 1) / 64;
+        for word_index in range.start / 64..=last_word {
+            let mut word = self.words[word_index];
+            if word_index == range.start / 64 {
+                word &= u64::MAX << (range.start % 64);
+            }
+            if word_index == last_word && !range.end.is_multiple_of(64) {
+                word &= u64::MAX >> (64 - range.end % 64);
+            }
+            while word != 0 {
+                f(word_index * 64 + word.trailing_zeros() as usize);
+                word &= word - 1;
+            }
+        }
+    }
+}
+
+/// Represents the state of an [`IEJoinStream`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IEJoinStreamState {
+    /// Waiting for the build side to be collected and sorted
+    WaitBuild,
+    /// Joining probe side batches
+    FetchProbe,
+    /// The probe side is exhausted, unmatched build rows may be emitted
+    ExhaustedProbe,
+    /// The stream is finished
+    Completed,
+}
+
+/// A stream that issues [`RecordBatch`]es as they arrive from the right side
+/// of the join.
+struct IEJoinStream {
+    /// Output schema
+    schema: SchemaRef,
+    /// Schema of the right input
+    right_schema: SchemaRef,
+    /// Range predicates used to find candidate pairs
+    predicates: Vec<RangePredicate>,
+    /// The complete join filter, if it has conjuncts besides `predicates`
+    filter: Option<JoinFilter>,
+    /// type of the join
+    join_type: JoinType,
+    /// the probe-side(right) input
+    right: SendableRecordBatchStream,
+    /// Future for the sorted build side
+    build_side_data: OnceFut<IEJoinBuildData>,
+    /// The sorted build side, once collected
+    build_side: Option<Arc<IEJoinBuildData>>,
+    /// Information of index and left / right placement of columns
+    column_indices: Vec<ColumnIndex>,
+    /// Join execution metrics
+    join_metrics: BuildProbeJoinMetrics,
+    /// `batch_size` from configuration
+    batch_size: usize,
+    /// Splits the output of a probe batch into batches of `batch_size`
+    batch_transformer: BatchSplitter,
+    /// State of the stream
+    state: IEJoinStreamState,
+}
+
+impl IEJoinStream {
+    /// Returns the candidate pairs of build and probe rows that satisfy the
+    /// range predicates
+    fn candidates(
+        &self,
+        build: &IEJoinBuildData,
+        probe: &RecordBatch,
+    ) -> Result<(UInt64Array, UInt32Array)> {
+        let (values, arrays) = convert_values(
+            &build.converters,
+            self.predicates.iter().map(|p| Arc::clone(&p.right)),
+            probe,
+        )?;
+        let mut probe_rows = valid_rows(&arrays, probe.num_rows());
+        let first = &self.predicates[0];
+        let mut build_indices = vec![];
+        let mut probe_indices = vec![];
+
+        let Some(second) = self.predicates.get(1) else {
+            for row in probe_rows {
+                let range =
+                    build.first_predicate_range(first.op, values[0].row(row as usize));
+                build_indices.extend(build.sorted[range].iter().map(|&i| i as u64));
+                probe_indices.resize(build_indices.len(), row);
+            }
+            return Ok((build_indices.into(), probe_indices.into()));
+        };
+
+        // Visit the probe rows so that the set of build rows satisfying the
+        // second predicate only grows
+        let descending = matches!(second.op, Operator::Gt | Operator::GtEq);
+        probe_rows.sort_unstable_by(|&a, &b| {
+            let ordering = values[1].row(a as usize).cmp(&values[1].row(b as usize));
+            if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+        let mut active = ActiveRows::new(build.sorted.len());
+        let mut activated = 0;
+        for row in probe_rows {
+            let value = values[1].row(row as usize);
+            while let Some(&next) = build.activation_order.get(activated) {
+                if !second.holds(build.values[1].row(next as usize).cmp(&value)) {
+                    break;
+                }
+                active.insert(build.positions[next as usize] as usize);
+                activated += 1;
+            }
+            let range =
+                build.first_predicate_range(first.op, values[0].row(row as usize));
+            active.for_each_in(range, |position| {
+                build_indices.push(build.sorted[position] as u64);
+                probe_indices.push(row);
+            });
+        }
+        Ok((build_indices.into(), probe_indices.into()))
+    }
+
+    /// Joins a probe batch with the build side
+    fn join_probe_batch(
+        &self,
+        build: &IEJoinBuildData,
+        probe: &RecordBatch,
+    ) -> Result<RecordBatch> {
+        let (build_indices, probe_indices) = self.candidates(build, probe)?;
+        let (build_indices, probe_indices) = match &self.filter {
+            Some(filter) => apply_join_filter_to_indices(
+                &build.batch,
+                probe,
+                build_indices,
+                probe_indices,
+                filter,
+                JoinSide::Left,
+                Some(self.batch_size),
+            )?,
+            None => (build_indices, probe_indices),
+        };
+
+        if need_produce_result_in_final(self.join_type) {
+            let mut bitmap = build.bitmap.lock();
+            build_indices.iter().flatten().for_each(|x| {
+                bitmap.set_bit(x as usize, true);
+            });
+        }
+
+        let (build_indices, probe_indices) = adjust_indices_by_join_type(
+            build_indices,
+            probe_indices,
+            0..probe.num_rows(),
+            self.join_type,
+            false,
+        )?;
+        build_batch_from_indices(
+            &self.schema,
+            &build.batch,
+            probe,
+            &build_indices,
+            &probe_indices,
+            &self.column_indices,
+            JoinSide::Left,
+        )
+    }
+
+    /// Returns the build rows that are emitted after all probe batches, if
+    /// this is the last probe thread to finish
+    fn final_batch(&self, build: &IEJoinBuildData) -> Result<Option<RecordBatch>> {
+        if !need_produce_result_in_final(self.join_type)
+            || !build.report_probe_completed()
+        {
+            return Ok(None);
+        }
+        let (build_indices, probe_indices) =
+            get_final_indices_from_shared_bitmap(&build.bitmap, self.join_type);
+        let batch = build_batch_from_indices(
+            &self.schema,
+            &build.batch,
+            &RecordBatch::new_empty(Arc::clone(&self.right_schema)),
+            &build_indices,
+            &probe_indices,
+            &self.column_indices,
+            JoinSide::Left,
+        )?;
+        Ok(Some(batch))
+    }
+
+    fn poll_next_impl(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<RecordBatch>>> {
+        loop {
+            if let Some((batch, _)) = self.batch_transformer.next() {
+                self.join_metrics.output_batches.add(1);
+                self.join_metrics.baseline.record_output(batch.num_rows());
+                return Poll::Ready(Some(Ok(batch)));
+            }
+            match self.state {
+                IEJoinStreamState::WaitBuild => {
+                    let build = ready!(self.build_side_data.get_shared(cx))?;
+                    self.build_side = Some(build);
+                    self.state = IEJoinStreamState::FetchProbe;
+                }
+                IEJoinStreamState::FetchProbe => {
+                    match ready!(self.right.poll_next_unpin(cx)) {
+                        Some(Ok(probe)) => {
+                            self.join_metrics.input_batches.add(1);
+                            self.join_metrics.input_rows.add(probe.num_rows());
+                            let timer = self.join_metrics.join_time.timer();
+                            let build = Arc::clone(self.build_side.as_ref().unwrap());
+                            let result = self.join_probe_batch(&build, &probe)?;
+                            timer.done();
+                            if result.num_rows() > 0 {
+                                self.batch_transformer.set_batch(result);
+                            }
+                        }
+                        Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                        None => self.state = IEJoinStreamState::ExhaustedProbe,
+                    }
+                }
+                IEJoinStreamState::ExhaustedProbe => {
+                    self.state = IEJoinStreamState::Completed;
+                    let timer = self.join_metrics.join_time.timer();
+                    let build = Arc::clone(self.build_side.as_ref().unwrap());
+                    let result = self.final_batch(&build)?;
+                    timer.done();
+                    if let Some(batch) = result.filter(|b| b.num_rows() > 0) {
+                        self.batch_transformer.set_batch(batch);
+                    }
+                }
+                IEJoinStreamState::Completed => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+impl Stream for IEJoinStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        self.poll_next_impl(cx)
+    }
+}
+
+impl RecordBatchStream for IEJoinStream {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::collect;
+    use crate::test::{assert_join_metrics, TestMemoryExec};
+
+    use arrow::array::Int32Array;
+    use arrow::datatypes::{DataType, Field};
+    use datafusion_common::assert_contains;
+    use datafusion_common::test_util::batches_to_sort_string;
+    use datafusion_common::ScalarValue;
+    use datafusion_physical_expr::expressions::Literal;
+    use insta::{allow_duplicates, assert_snapshot};
+    use rstest::rstest;
+
+    /// Builds an input of nullable Int32 columns, with negative values as
+    /// nulls, split into partitions of batches of `batch_rows` rows
+    fn build_input(
+        columns: &[&str],
+        partitions: &[&[&[i32]]],
+        batch_rows: usize,
+    ) -> Arc<dyn ExecutionPlan> {
+        let schema = Arc::new(Schema::new(
+            columns
+                .iter()
+                .map(|c| Field::new(*c, DataType::Int32, true))
+                .collect::<Vec<_>>(),
+        ));
+        let partitions = partitions
+            .iter()
+            .map(|rows| {
+                rows.chunks(batch_rows)
+                    .map(|chunk| {
+                        let arrays = (0..columns.len())
+                            .map(|i| {
+                                Arc::new(Int32Array::from_iter(
+                                    chunk
+                                        .iter()
+                                        .map(|row| (row[i] >= 0).then_some(row[i])),
+                                )) as ArrayRef
+                            })
+                            .collect();
+                        RecordBatch::try_new(Arc::clone(&schema), arrays).unwrap()
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        TestMemoryExec::try_new_exec(&partitions, schema, None).unwrap()
+    }
+
+    /// Intervals `[start, end)`
+    fn intervals(batch_rows: usize) -> Arc<dyn ExecutionPlan> {
+        build_input(
+            &["id", "start", "end"],
+            &[&[
+                &[1, 0, 10],
+                &[2, 5, 15],
+                &[3, -1, 20],
+                &[4, 20, 30],
+                &[5, 12, 13],
+            ]],
+            batch_rows,
+        )
+    }
+
+    /// Events in two partitions
+    fn events(batch_rows: usize) -> Arc<dyn ExecutionPlan> {
+        build_input(
+            &["ts", "v"],
+            &[
+                &[&[3, 30], &[12, 120], &[-1, 0]],
+                &[&[5, 50], &[25, 250], &[40, 400]],
+            ],
+            batch_rows,
+        )
+    }
+
+    fn column(name: &str, index: usize) -> PhysicalExprRef {
+        Arc::new(Column::new(name, index))
+    }
+
+    fn binary(
+        left: PhysicalExprRef,
+        op: Operator,
+        right: PhysicalExprRef,
+    ) -> PhysicalExprRef {
+        Arc::new(BinaryExpr::new(left, op, right))
+    }
+
+    /// `start <= ts AND ts < end`, optionally `AND end <> <residual>`
+    fn band_filter(residual: Option<i32>) -> JoinFilter {
+        let schema = Schema::new(vec![
+            Field::new("start", DataType::Int32, true),
+            Field::new("end", DataType::Int32, true),
+            Field::new("ts", DataType::Int32, true),
+        ]);
+        let column_indices = vec![
+            ColumnIndex {
+                index: 1,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 2,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 0,
+                side: JoinSide::Right,
+            },
+        ];
+        let mut expression = binary(
+            binary(column("start", 0), Operator::LtEq, column("ts", 2)),
+            Operator::And,
+            binary(column("ts", 2), Operator::Lt, column("end", 1)),
+        );
+        if let Some(residual) = residual {
+            let literal = Arc::new(Literal::new(ScalarValue::Int32(Some(residual))));
+            expression = binary(
+                expression,
+                Operator::And,
+                binary(column("end", 1), Operator::NotEq, literal),
+            );
+        }
+        JoinFilter::new(expression, column_indices, Arc::new(schema))
+    }
+
+    async fn join_collect(
+        join_type: JoinType,
+        filter: JoinFilter,
+        batch_rows: usize,
+    ) -> Result<String> {
+        let join = IEJoinExec::try_new(
+            intervals(batch_rows),
+            events(batch_rows),
+            filter,
+            &join_type,
+        )?;
+        let task_ctx = Arc::new(TaskContext::default());
+        let mut batches = vec![];
+        for partition in 0..2 {
+            let stream = join.execute(partition, Arc::clone(&task_ctx))?;
+            batches.extend(collect(stream).await?);
+        }
+        let output_rows = batches.iter().map(|b| b.num_rows()).sum::<usize>();
+        assert_join_metrics!(join.metrics().unwrap(), output_rows);
+        Ok(batches_to_sort_string(&batches))
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn band_join_inner(#[values(1, 2, 16)] batch_rows: usize) -> Result<()> {
+        let result = join_collect(JoinType::Inner, band_filter(None), batch_rows).await?;
+        allow_duplicates!(assert_snapshot!(result, @r"
+        +----+-------+-----+----+-----+
+        | id | start | end | ts | v   |
+        +----+-------+-----+----+-----+
+        | 1  | 0     | 10  | 3  | 30  |
+        | 1  | 0     | 10  | 5  | 50  |
+        | 2  | 5     | 15  | 12 | 120 |
+        | 2  | 5     | 15  | 5  | 50  |
+        | 4  | 20    | 30  | 25 | 250 |
+        | 5  | 12    | 13  | 12 | 120 |
+        +----+-------+-----+----+-----+
+        "));
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn band_join_full(#[values(1, 2, 16)] batch_rows: usize) -> Result<()> {
+        let result = join_collect(JoinType::Full, band_filter(None), batch_rows).await?;
+        allow_duplicates!(assert_snapshot!(result, @r"
+        +----+-------+-----+----+-----+
+        | id | start | end | ts | v   |
+        +----+-------+-----+----+-----+
+        |    |       |     |    | 0   |
+        |    |       |     | 40 | 400 |
+        | 1  | 0     | 10  | 3  | 30  |
+        | 1  | 0     | 10  | 5  | 50  |
+        | 2  | 5     | 15  | 12 | 120 |
+        | 2  | 5     | 15  | 5  | 50  |
+        | 3  |       | 20  |    |     |
+        | 4  | 20    | 30  | 25 | 250 |
+        | 5  | 12    | 13  | 12 | 120 |
+        +----+-------+-----+----+-----+
+        "));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn band_join_semi_anti() -> Result<()> {
+        let left_semi = join_collect(JoinType::LeftSemi, band_filter(None), 2).await?;
+        assert_snapshot!(left_semi, @r"
+        +----+-------+-----+
+        | id | start | end |
+        +----+-------+-----+
+        | 1  | 0     | 10  |
+        | 2  | 5     | 15  |
+        | 4  | 20    | 30  |
+        | 5  | 12    | 13  |
+        +----+-------+-----+
+        ");
+        let left_anti = join_collect(JoinType::LeftAnti, band_filter(None), 2).await?;
+        assert_snapshot!(left_anti, @r"
+        +----+-------+-----+
+        | id | start | end |
+        +----+-------+-----+
+        | 3  |       | 20  |
+        +----+-------+-----+
+        ");
+        let right_semi = join_collect(JoinType::RightSemi, band_filter(None), 2).await?;
+        assert_snapshot!(right_semi, @r"
+        +----+-----+
+        | ts | v   |
+        +----+-----+
+        | 12 | 120 |
+        | 25 | 250 |
+        | 3  | 30  |
+        | 5  | 50  |
+        +----+-----+
+        ");
+        let right_anti = join_collect(JoinType::RightAnti, band_filter(None), 2).await?;
+        assert_snapshot!(right_anti, @r"
+        +----+-----+
+        | ts | v   |
+        +----+-----+
+        |    | 0   |
+        | 40 | 400 |
+        +----+-----+
+        ");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn band_join_with_residual_filter() -> Result<()> {
+        let result = join_collect(JoinType::Left, band_filter(Some(15)), 2).await?;
+        assert_snapshot!(result, @r"
+        +----+-------+-----+----+-----+
+        | id | start | end | ts | v   |
+        +----+-------+-----+----+-----+
+        | 1  | 0     | 10  | 3  | 30  |
+        | 1  | 0     | 10  | 5  | 50  |
+        | 2  | 5     | 15  |    |     |
+        | 3  |       | 20  |    |     |
+        | 4  | 20    | 30  | 25 | 250 |
+        | 5  | 12    | 13  | 12 | 120 |
+        +----+-------+-----+----+-----+
+        ");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn single_range_predicate() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("end", DataType::Int32, true),
+            Field::new("ts", DataType::Int32, true),
+        ]);
+        let column_indices = vec![
+            ColumnIndex {
+                index: 2,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 0,
+                side: JoinSide::Right,
+            },
+        ];
+        // ts >= end
+        let filter = JoinFilter::new(
+            binary(column("ts", 1), Operator::GtEq, column("end", 0)),
+            column_indices,
+            Arc::new(schema),
+        );
+        let join = IEJoinExec::try_new(
+            intervals(2),
+            events(2),
+            filter.clone(),
+            &JoinType::Inner,
+        )?;
+        let predicate = &join.predicates()[0];
+        assert_eq!(
+            format!("{} {} {}", predicate.left, predicate.op, predicate.right),
+            "end@2 <= ts@0"
+        );
+        let result = join_collect(JoinType::Inner, filter, 2).await?;
+        assert_snapshot!(result, @r"
+        +----+-------+-----+----+-----+
+        | id | start | end | ts | v   |
+        +----+-------+-----+----+-----+
+        | 1  | 0     | 10  | 12 | 120 |
+        | 1  | 0     | 10  | 25 | 250 |
+        | 1  | 0     | 10  | 40 | 400 |
+        | 2  | 5     | 15  | 25 | 250 |
+        | 2  | 5     | 15  | 40 | 400 |
+        | 3  |       | 20  | 25 | 250 |
+        | 3  |       | 20  | 40 | 400 |
+        | 4  | 20    | 30  | 40 | 400 |
+        | 5  | 12    | 13  | 25 | 250 |
+        | 5  | 12    | 13  | 40 | 400 |
+        +----+-------+-----+----+-----+
+        ");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn swap_inputs() -> Result<()> {
+        let join = IEJoinExec::try_new(
+            intervals(2),
+            events(2),
+            band_filter(None),
+            &JoinType::Left,
+        )?;
+        let swapped = join.swap_inputs()?;
+        let swapped_join = swapped.children()[0]
+            .as_any()
+            .downcast_ref::<IEJoinExec>()
+            .expect("projection over swapped join");
+        assert_eq!(*swapped_join.join_type(), JoinType::Right);
+        let predicates = swapped_join
+            .predicates()
+            .iter()
+            .map(|p| format!("{} {} {}", p.left, p.op, p.right))
+            .collect::<Vec<_>>();
+        assert_eq!(predicates, ["ts@0 >= start@1", "ts@0 < end@2"]);
+        Ok(())
+    }
+
+    #[test]
+    fn requires_range_predicate() {
+        let schema = Schema::new(vec![
+            Field::new("start", DataType::Int32, true),
+            Field::new("ts", DataType::Int32, true),
+        ]);
+        let column_indices = vec![
+            ColumnIndex {
+                index: 1,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 0,
+                side: JoinSide::Right,
+            },
+        ];
+        let filter = JoinFilter::new(
+            binary(column("start", 0), Operator::NotEq, column("ts", 1)),
+            column_indices,
+            Arc::new(schema),
+        );
+        let err = IEJoinExec::try_new(intervals(2), events(2), filter, &JoinType::Inner)
+            .unwrap_err();
+        assert_contains!(err.to_string(), "IEJoinExec requires a range predicate");
+    }
+}
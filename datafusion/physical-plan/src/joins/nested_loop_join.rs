@@ -36,14 +36,17 @@ use crate::joins::utils::{
     OnceAsync, OnceFut,
 };
 use crate::joins::SharedBitmapBuilder;
-use crate::metrics::{Count, ExecutionPlanMetricsSet, MetricsSet};
+use crate::metrics::{Count, ExecutionPlanMetricsSet, MetricsSet, SpillMetrics};
 use crate::projection::{
     try_embed_projection, try_pushdown_through_join, EmbeddedProjection, JoinData,
     ProjectionExec,
 };
+use crate::spill::in_progress_spill_file::InProgressSpillFile;
+use crate::spill::spill_manager::SpillManager;
 use crate::{
-    DisplayAs, DisplayFormatType, Distribution, ExecutionPlan, ExecutionPlanProperties,
-    PlanProperties, RecordBatchStream, SendableRecordBatchStream,
+    DisplayAs, DisplayFormatType, Distribution, EmptyRecordBatchStream, ExecutionPlan,
+    ExecutionPlanProperties, PlanProperties, RecordBatchStream,
+    SendableRecordBatchStream,
 };
 
 use arrow::array::{
@@ -55,9 +58,10 @@ use arrow::datatypes::{Schema, SchemaRef};
 use arrow::record_batch::RecordBatch;
 use datafusion_common::cast::as_boolean_array;
 use datafusion_common::{
-    arrow_err, internal_datafusion_err, internal_err, project_schema,
+    arrow_err, internal_datafusion_err, internal_err, plan_err, project_schema,
     unwrap_or_internal_err, DataFusionError, JoinSide, Result, ScalarValue, Statistics,
 };
+use datafusion_execution::disk_manager::RefCountedTempFile;
 use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
 use datafusion_execution::TaskContext;
 use datafusion_expr::JoinType;
@@ -65,7 +69,7 @@ use datafusion_physical_expr::equivalence::{
     join_equivalence_properties, ProjectionMapping,
 };
 
-use futures::{Stream, StreamExt, TryStreamExt};
+use futures::{ready, Stream, StreamExt};
 use log::debug;
 use parking_lot::Mutex;
 
@@ -112,10 +116,8 @@ use parking_lot::Mutex;
 ///
 /// ## 1. Buffering Left Input
 /// - The operator eagerly buffers all left-side input batches into memory,
-///   util a memory limit is reached.
-///   Currently, an out-of-memory error will be thrown if all the left-side input batches
-///   cannot fit into memory at once.
-///   In the future, it's possible to make this case finish execution. (see
+///   util a memory limit is reached. If all the left-side input batches
+///   cannot fit into memory at once, they are spilled to disk (see
 ///   'Memory-limited Execution' section)
 /// - The rationale for buffering the left side is that scanning the right side
 ///   can be expensive (e.g., decoding Parquet files), so buffering more left
@@ -152,10 +154,29 @@ use parking_lot::Mutex;
 /// - The design try to minimize the intermediate data size to approximately
 ///   1 batch, for better cache locality and memory efficiency.
 ///
-/// # TODO: Memory-limited Execution
-/// If the memory budget is exceeded during left-side buffering, fallback
-/// strategies such as streaming left batches and re-scanning the right side
-/// may be implemented in the future.
+/// # Memory-limited Execution
+/// If the memory budget is exceeded during left-side buffering and the disk
+/// manager allows spilling, all left-side batches are written to a spill file
+/// instead. Each output stream then joins the left side in chunks:
+///
+/// ```text
+/// for chunk in left_spill_file (as many rows as fit in memory):
+///     for batch in right_side (read from a spill file after the first pass):
+///         join(chunk, batch)
+/// ```
+///
+/// - The right-side batches are spilled during the first pass, so that the
+///   right input is only executed once.
+/// - The left bitmap covers all left rows, and the matched bitmaps of the
+///   right batches are kept across passes; unmatched right rows are emitted
+///   in the last pass, and unmatched left rows by the last stream to finish,
+///   re-reading the left spill file once more.
+/// - The memory of a chunk is limited to the memory that was reserved before
+///   spilling, divided by the number of output streams, but a chunk always
+///   contains at least one batch.
+///
+/// The number of spills and the spilled bytes and rows are reported in the
+/// metrics of the operator.
 ///
 /// Tracking issue: <https://github.com/apache/datafusion/issues/15760>
 ///
@@ -206,6 +227,11 @@ impl NestedLoopJoinExec {
         let left_schema = left.schema();
         let right_schema = right.schema();
         check_join_is_valid(&left_schema, &right_schema, &[])?;
+        if *join_type == JoinType::AsOf {
+            return plan_err!(
+                "NestedLoopJoinExec does not support AsOf joins, use AsOfJoinExec"
+            );
+        }
         let (join_schema, column_indices) =
             build_join_schema(&left_schema, &right_schema, join_type);
         let join_schema = Arc::new(join_schema);
@@ -295,7 +321,8 @@ impl NestedLoopJoinExec {
                 JoinType::Left
                 | JoinType::LeftAnti
                 | JoinType::LeftMark
-                | JoinType::Full => EmissionType::Both,
+                | JoinType::Full
+                | JoinType::AsOf => EmissionType::Both,
             }
         } else {
             right.pipeline_behavior()
@@ -362,7 +389,7 @@ impl NestedLoopJoinExec {
             Arc::clone(right),
             Arc::clone(left),
             self.filter().map(JoinFilter::swap),
-            &self.join_type().swap(),
+            &self.join_type().swap()?,
             swap_join_projection(
                 left.schema().fields().len(),
                 right.schema().fields().len(),
@@ -497,6 +524,31 @@ impl ExecutionPlan for NestedLoopJoinExec {
             MemoryConsumer::new(format!("NestedLoopJoinLoad[{partition}]"))
                 .register(context.memory_pool());
 
+        // Spill managers for the build and probe side, if spilling is possible
+        let spill_managers =
+            context
+                .runtime_env()
+                .disk_manager
+                .tmp_files_enabled()
+                .then(|| {
+                    let spill_metrics = SpillMetrics::new(&self.metrics, partition);
+                    let spill_manager = |schema| {
+                        SpillManager::new(
+                            context.runtime_env(),
+                            spill_metrics.clone(),
+                            schema,
+                        )
+                        .with_compression_type(
+                            context.session_config().spill_compression(),
+                        )
+                    };
+                    (
+                        spill_manager(self.left.schema()),
+                        spill_manager(self.right.schema()),
+                    )
+                });
+        let (left_spill_manager, right_spill_manager) = spill_managers.unzip();
+
         let build_side_data = self.build_side_data.try_once(|| {
             let stream = self.left.execute(0, Arc::clone(&context))?;
 
@@ -506,11 +558,18 @@ impl ExecutionPlan for NestedLoopJoinExec {
                 load_reservation,
                 need_produce_result_in_final(self.join_type),
                 self.right().output_partitioning().partition_count(),
+                left_spill_manager,
             ))
         })?;
 
         let batch_size = context.session_config().batch_size();
 
+        // Reservation for the chunks of the left side, when it was spilled
+        let chunk_reservation = right_spill_manager.is_some().then(|| {
+            MemoryConsumer::new(format!("NestedLoopJoinChunk[{partition}]"))
+                .register(context.memory_pool())
+        });
+
         let probe_side_data = self.right.execute(partition, context)?;
 
         // update column indices to reflect the projection
@@ -522,16 +581,19 @@ impl ExecutionPlan for NestedLoopJoinExec {
             None => self.column_indices.clone(),
         };
 
-        Ok(Box::pin(NestedLoopJoinStream::new(
-            self.schema(),
-            self.filter.clone(),
-            self.join_type,
-            probe_side_data,
-            build_side_data,
-            column_indices_after_projection,
-            join_metrics,
-            batch_size,
-        )))
+        Ok(Box::pin(
+            NestedLoopJoinStream::new(
+                self.schema(),
+                self.filter.clone(),
+                self.join_type,
+                probe_side_data,
+                build_side_data,
+                column_indices_after_projection,
+                join_metrics,
+                batch_size,
+            )
+            .with_spilling(chunk_reservation, right_spill_manager),
+        ))
     }
 
     fn metrics(&self) -> Option<MetricsSet> {
@@ -602,8 +664,10 @@ impl EmbeddedProjection for NestedLoopJoinExec {
 
 /// Left (build-side) data
 pub(crate) struct JoinLeftData {
-    /// Build-side data collected to single batch
+    /// Build-side data collected to single batch, empty if it was spilled
     batch: RecordBatch,
+    /// Build-side data spilled to disk because it did not fit in memory
+    spilled: Option<SpilledLeftData>,
     /// Shared bitmap builder for visited left indices
     bitmap: SharedBitmapBuilder,
     /// Counter of running probe-threads, potentially able to update `bitmap`
@@ -615,15 +679,35 @@ pub(crate) struct JoinLeftData {
     reservation: MemoryReservation,
 }
 
+/// Left (build-side) data that was spilled to disk
+pub(crate) struct SpilledLeftData {
+    /// Spill file with all build-side batches
+    file: Arc<RefCountedTempFile>,
+    /// Spill manager used to read back `file`
+    spill_manager: SpillManager,
+    /// Maximum memory of a chunk of the build side joined by an output stream
+    chunk_memory: usize,
+}
+
+impl SpilledLeftData {
+    /// Returns a new stream of the spilled build-side batches
+    fn read(&self) -> Result<SendableRecordBatchStream> {
+        self.spill_manager
+            .read_shared_spill_as_stream(Arc::clone(&self.file), None)
+    }
+}
+
 impl JoinLeftData {
     pub(crate) fn new(
         batch: RecordBatch,
+        spilled: Option<SpilledLeftData>,
         bitmap: SharedBitmapBuilder,
         probe_threads_counter: AtomicUsize,
         reservation: MemoryReservation,
     ) -> Self {
         Self {
             batch,
+            spilled,
             bitmap,
             probe_threads_counter,
             reservation,
@@ -646,39 +730,73 @@ impl JoinLeftData {
 }
 
 /// Asynchronously collect input into a single batch, and creates `JoinLeftData` from it
+///
+/// If the batches do not fit in memory and `spill_manager` is set, all batches
+/// are spilled to disk instead.
 async fn collect_left_input(
-    stream: SendableRecordBatchStream,
-    join_metrics: BuildProbeJoinMetrics,
-    reservation: MemoryReservation,
+    mut stream: SendableRecordBatchStream,
+    metrics: BuildProbeJoinMetrics,
+    mut reservation: MemoryReservation,
     with_visited_left_side: bool,
     probe_threads_count: usize,
+    spill_manager: Option<SpillManager>,
 ) -> Result<JoinLeftData> {
     let schema = stream.schema();
 
     // Load all batches and count the rows
-    let (batches, metrics, mut reservation) = stream
-        .try_fold(
-            (Vec::new(), join_metrics, reservation),
-            |(mut batches, metrics, mut reservation), batch| async {
-                let batch_size = batch.get_array_memory_size();
-                // Reserve memory for incoming batch
-                reservation.try_grow(batch_size)?;
-                // Update metrics
+    let mut batches = vec![];
+    let mut spill: Option<(InProgressSpillFile, usize)> = None;
+    let mut n_rows = 0;
+    while let Some(batch) = stream.next().await {
+        let batch = batch?;
+        metrics.build_input_batches.add(1);
+        metrics.build_input_rows.add(batch.num_rows());
+        n_rows += batch.num_rows();
+        if let Some((spill_file, _)) = spill.as_mut() {
+            spill_file.append_batch(&batch)?;
+            continue;
+        }
+
+        let batch_size = batch.get_array_memory_size();
+        // Reserve memory for incoming batch
+        match (reservation.try_grow(batch_size), &spill_manager) {
+            (Ok(()), _) => {
                 metrics.build_mem_used.add(batch_size);
-                metrics.build_input_batches.add(1);
-                metrics.build_input_rows.add(batch.num_rows());
-                // Push batch to output
                 batches.push(batch);
-                Ok((batches, metrics, reservation))
-            },
-        )
-        .await?;
+            }
+            (Err(_), Some(spill_manager)) => {
+                // Spill all batches, the memory reserved so far is shared by
+                // the chunks of all output streams
+                let mut spill_file =
+                    spill_manager.create_in_progress_file("NestedLoopJoin build side")?;
+                for batch in batches.drain(..).chain([batch]) {
+                    spill_file.append_batch(&batch)?;
+                }
+                let chunk_memory = reservation.free() / probe_threads_count.max(1);
+                metrics.build_mem_used.set(0);
+                spill = Some((spill_file, chunk_memory));
+            }
+            (Err(e), None) => return Err(e),
+        }
+    }
 
-    let merged_batch = concat_batches(&schema, &batches)?;
+    let (merged_batch, spilled) = match spill {
+        Some((mut spill_file, chunk_memory)) => {
+            let file = spill_file.finish()?.ok_or_else(|| {
+                internal_datafusion_err!("Spilled build side should not be empty")
+            })?;
+            let spilled = SpilledLeftData {
+                file: Arc::new(file),
+                spill_manager: spill_manager.unwrap(),
+                chunk_memory,
+            };
+            (RecordBatch::new_empty(schema), Some(spilled))
+        }
+        None => (concat_batches(&schema, &batches)?, None),
+    };
 
     // Reserve memory for visited_left_side bitmap if required by join type
     let visited_left_side = if with_visited_left_side {
-        let n_rows = merged_batch.num_rows();
         let buffer_size = n_rows.div_ceil(8);
         reservation.try_grow(buffer_size)?;
         metrics.build_mem_used.add(buffer_size);
@@ -692,6 +810,7 @@ async fn collect_left_input(
 
     Ok(JoinLeftData::new(
         merged_batch,
+        spilled,
         Mutex::new(visited_left_side),
         AtomicUsize::new(probe_threads_count),
         reservation,
@@ -767,17 +886,33 @@ pub(crate) struct NestedLoopJoinStream {
     // -----------------
     /// The current buffered left data to join
     buffered_left_data: Option<Arc<JoinLeftData>>,
+    /// The left rows to join in the current pass: all left rows, or a chunk
+    /// of them if the left side was spilled
+    left_batch: RecordBatch,
+    /// Index of the first row of `left_batch` in all left rows (and the left
+    /// bitmap)
+    left_offset: usize,
     /// Index into the left buffered batch. Used in `ProbeRight` state
     left_probe_idx: usize,
     /// Index into the left buffered batch. Used in `EmitLeftUnmatched` state
     left_emit_idx: usize,
-    /// Should we go back to `BufferingLeft` state again after `EmitLeftUnmatched`
+    /// Should we go back to `BufferingLeft` state again after `FetchingRight`
     /// state is over.
     left_exhausted: bool,
     /// If we can buffer all left data in one pass
-    /// TODO(now): this is for the (unimplemented) memory-limited execution
-    #[allow(dead_code)]
     left_buffered_in_one_pass: bool,
+    /// Reader of the spilled left side, used to load the chunks to join, and
+    /// to emit unmatched left rows
+    left_spill_reader: Option<SendableRecordBatchStream>,
+    /// Spilled left batch that did not fit into the previous chunk
+    left_pending_batch: Option<RecordBatch>,
+    /// Batches of the chunk being loaded
+    left_chunk_batches: Vec<RecordBatch>,
+    /// Memory reservation for the chunk of the spilled left side
+    left_chunk_reservation: Option<MemoryReservation>,
+    /// Whether this stream emits the unmatched left rows, decided once all
+    /// right batches are processed
+    left_unmatched_owner: Option<bool>,
 
     // Probe(right) side
     // -----------------
@@ -786,6 +921,18 @@ pub(crate) struct NestedLoopJoinStream {
     // For right join, keep track of matched rows in `current_right_batch`
     // Constructed when fetching each new incoming right batch in `FetchingRight` state.
     current_right_batch_matched: Option<BooleanArray>,
+    /// Spill manager for the right batches, if spilling is possible
+    right_spill_manager: Option<SpillManager>,
+    /// Spill file the right batches are written to during the first pass
+    right_spill_in_progress: Option<InProgressSpillFile>,
+    /// Right batches of the first pass, read again by the later passes
+    right_spill_file: Option<Arc<RefCountedTempFile>>,
+    /// Whether the right batches are read from `right_spill_file`
+    rescanning_right: bool,
+    /// Matched bitmaps of the right batches, kept across passes
+    right_matched_bitmaps: Vec<BooleanArray>,
+    /// Number of right batches fetched in the current pass
+    right_batch_idx: usize,
 }
 
 impl Stream for NestedLoopJoinStream {
@@ -808,11 +955,14 @@ impl Stream for NestedLoopJoinStream {
     /// BufferingLeft → FetchingRight
     ///
     /// FetchingRight → ProbeRight (if right batch available)
+    /// FetchingRight → BufferingLeft (if right exhausted, and there are more
+    /// spilled left chunks)
     /// FetchingRight → EmitLeftUnmatched (if right exhausted)
     ///
     /// ProbeRight → ProbeRight (next left row or after yielding output)
     /// ProbeRight → EmitRightUnmatched (for special join types like right join)
-    /// ProbeRight → FetchingRight (done with the current right batch)
+    /// ProbeRight → FetchingRight (done with the current right batch, or with
+    /// the current left chunk)
     ///
     /// EmitRightUnmatched → FetchingRight
     ///
@@ -832,6 +982,8 @@ impl Stream for NestedLoopJoinStream {
                 // This state will prepare the left side batches, next state
                 // `FetchingRight` is responsible for preparing a single probe
                 // side batch, before start joining.
+                // If the left side was spilled, it loads the next chunk of
+                // the left side, and rewinds the right side.
                 NLJState::BufferingLeft => {
                     debug!("[NLJState] Entering: {:?}", self.state);
                     // inside `collect_left_input` (the routine to buffer build
@@ -851,7 +1003,9 @@ impl Stream for NestedLoopJoinStream {
                 // 1. --> ProbeRight
                 //    Start processing the join for the newly fetched right
                 //    batch.
-                // 2. --> EmitLeftUnmatched: When the right side input is exhausted, (maybe) emit
+                // 2. --> BufferingLeft: When the right side input is
+                //    exhausted, and the left side has more spilled chunks.
+                // 3. --> EmitLeftUnmatched: When the right side input is exhausted, (maybe) emit
                 //    unmatched left side rows.
                 //
                 // After fetching a new batch from the right side, it will
@@ -943,9 +1097,6 @@ impl Stream for NestedLoopJoinStream {
                 // 3. --> Done
                 //    It has processed all data, go to the final state and ready
                 //    to exit.
-                //
-                // TODO: For memory-limited case, go back to `BufferingLeft`
-                // state again.
                 NLJState::EmitLeftUnmatched => {
                     debug!("[NLJState] Entering: {:?}", self.state);
 
@@ -953,7 +1104,7 @@ impl Stream for NestedLoopJoinStream {
                     let join_metric = self.join_metrics.join_time.clone();
                     let _join_timer = join_metric.timer();
 
-                    match self.handle_emit_left_unmatched() {
+                    match self.handle_emit_left_unmatched(cx) {
                         ControlFlow::Continue(()) => continue,
                         ControlFlow::Break(poll) => {
                             return self.join_metrics.baseline.record_poll(poll)
@@ -1010,16 +1161,41 @@ impl NestedLoopJoinStream {
             batch_size,
             current_right_batch: None,
             current_right_batch_matched: None,
+            right_spill_manager: None,
+            right_spill_in_progress: None,
+            right_spill_file: None,
+            rescanning_right: false,
+            right_matched_bitmaps: vec![],
+            right_batch_idx: 0,
             state: NLJState::BufferingLeft,
+            left_batch: RecordBatch::new_empty(Arc::new(Schema::empty())),
+            left_offset: 0,
             left_probe_idx: 0,
             left_emit_idx: 0,
             left_exhausted: false,
             left_buffered_in_one_pass: true,
+            left_spill_reader: None,
+            left_pending_batch: None,
+            left_chunk_batches: vec![],
+            left_chunk_reservation: None,
+            left_unmatched_owner: None,
             handled_empty_output: false,
             should_track_unmatched_right: need_produce_right_in_final(join_type),
         }
     }
 
+    /// Sets the memory reservation for the chunks of a spilled left side, and
+    /// the spill manager for the right batches
+    pub(crate) fn with_spilling(
+        mut self,
+        chunk_reservation: Option<MemoryReservation>,
+        right_spill_manager: Option<SpillManager>,
+    ) -> Self {
+        self.left_chunk_reservation = chunk_reservation;
+        self.right_spill_manager = right_spill_manager;
+        self
+    }
+
     // ==== State handler functions ====
 
     /// Handle BufferingLeft state - prepare left side batches
@@ -1027,18 +1203,62 @@ impl NestedLoopJoinStream {
         &mut self,
         cx: &mut std::task::Context<'_>,
     ) -> ControlFlow<Poll<Option<Result<RecordBatch>>>> {
-        match self.left_data.get_shared(cx) {
-            Poll::Ready(Ok(left_data)) => {
-                self.buffered_left_data = Some(left_data);
-                // TODO: implement memory-limited case
-                self.left_exhausted = true;
-                self.state = NLJState::FetchingRight;
-                // Continue to next state immediately
-                ControlFlow::Continue(())
+        if self.buffered_left_data.is_none() {
+            match self.left_data.get_shared(cx) {
+                Poll::Ready(Ok(left_data)) => {
+                    self.left_batch = left_data.batch().clone();
+                    self.left_buffered_in_one_pass = left_data.spilled.is_none();
+                    if let Some(spilled) = &left_data.spilled {
+                        match spilled.read() {
+                            Ok(reader) => self.left_spill_reader = Some(reader),
+                            Err(e) => {
+                                return ControlFlow::Break(Poll::Ready(Some(Err(e))))
+                            }
+                        }
+                    }
+                    self.buffered_left_data = Some(left_data);
+                }
+                Poll::Ready(Err(e)) => {
+                    return ControlFlow::Break(Poll::Ready(Some(Err(e))))
+                }
+                Poll::Pending => return ControlFlow::Break(Poll::Pending),
             }
-            Poll::Ready(Err(e)) => ControlFlow::Break(Poll::Ready(Some(Err(e)))),
-            Poll::Pending => ControlFlow::Break(Poll::Pending),
         }
+
+        if self.left_buffered_in_one_pass {
+            self.left_exhausted = true;
+            self.state = NLJState::FetchingRight;
+            // Continue to next state immediately
+            return ControlFlow::Continue(());
+        }
+
+        // The left side was spilled, load its next chunk
+        match self.poll_next_left_chunk(cx) {
+            Poll::Ready(Ok(())) => {}
+            Poll::Ready(Err(e)) => return ControlFlow::Break(Poll::Ready(Some(Err(e)))),
+            Poll::Pending => return ControlFlow::Break(Poll::Pending),
+        }
+
+        // Rewind the right side, which was spilled during the first pass
+        if self.rescanning_right {
+            let right_data = match (&self.right_spill_file, &self.right_spill_manager) {
+                (Some(file), Some(spill_manager)) => {
+                    spill_manager.read_shared_spill_as_stream(Arc::clone(file), None)
+                }
+                _ => Ok(
+                    Box::pin(EmptyRecordBatchStream::new(self.right_data.schema()))
+                        as SendableRecordBatchStream,
+                ),
+            };
+            match right_data {
+                Ok(right_data) => self.right_data = right_data,
+                Err(e) => return ControlFlow::Break(Poll::Ready(Some(Err(e)))),
+            }
+            self.right_batch_idx = 0;
+        }
+
+        self.state = NLJState::FetchingRight;
+        ControlFlow::Continue(())
     }
 
     /// Handle FetchingRight state - fetch next right batch and prepare for processing
@@ -1049,24 +1269,41 @@ impl NestedLoopJoinStream {
         match self.right_data.poll_next_unpin(cx) {
             Poll::Ready(result) => match result {
                 Some(Ok(right_batch)) => {
-                    // Update metrics
+                    // Update metrics, the right input is only read once
                     let right_batch_size = right_batch.num_rows();
-                    self.join_metrics.input_rows.add(right_batch_size);
-                    self.join_metrics.input_batches.add(1);
+                    if !self.rescanning_right {
+                        self.join_metrics.input_rows.add(right_batch_size);
+                        self.join_metrics.input_batches.add(1);
+                    }
 
                     // Skip the empty batch
                     if right_batch_size == 0 {
                         return ControlFlow::Continue(());
                     }
 
+                    // Spill the right batch for the next passes
+                    if !self.rescanning_right && !self.left_exhausted {
+                        if let Err(e) = self.spill_right_batch(&right_batch) {
+                            return ControlFlow::Break(Poll::Ready(Some(Err(e))));
+                        }
+                    }
+
                     self.current_right_batch = Some(right_batch);
 
                     // Prepare right bitmap
                     if self.should_track_unmatched_right {
-                        let zeroed_buf = BooleanBuffer::new_unset(right_batch_size);
-                        self.current_right_batch_matched =
-                            Some(BooleanArray::new(zeroed_buf, None));
+                        let bitmap = if self.rescanning_right {
+                            std::mem::replace(
+                                &mut self.right_matched_bitmaps[self.right_batch_idx],
+                                BooleanArray::new_null(0),
+                            )
+                        } else {
+                            let zeroed_buf = BooleanBuffer::new_unset(right_batch_size);
+                            BooleanArray::new(zeroed_buf, None)
+                        };
+                        self.current_right_batch_matched = Some(bitmap);
                     }
+                    self.right_batch_idx += 1;
 
                     self.left_probe_idx = 0;
                     self.state = NLJState::ProbeRight;
@@ -1075,7 +1312,27 @@ impl NestedLoopJoinStream {
                 Some(Err(e)) => ControlFlow::Break(Poll::Ready(Some(Err(e)))),
                 None => {
                     // Right stream exhausted
-                    self.state = NLJState::EmitLeftUnmatched;
+                    if let Some(mut spill_file) = self.right_spill_in_progress.take() {
+                        match spill_file.finish() {
+                            Ok(file) => self.right_spill_file = file.map(Arc::new),
+                            Err(e) => {
+                                return ControlFlow::Break(Poll::Ready(Some(Err(e))))
+                            }
+                        }
+                    }
+
+                    if self.left_exhausted {
+                        self.state = NLJState::EmitLeftUnmatched;
+                    } else {
+                        // Join the next chunk of the left side
+                        self.rescanning_right = true;
+                        self.left_offset += self.left_batch.num_rows();
+                        self.left_batch = self.left_batch.slice(0, 0);
+                        if let Some(reservation) = self.left_chunk_reservation.as_mut() {
+                            reservation.free();
+                        }
+                        self.state = NLJState::BufferingLeft;
+                    }
                     ControlFlow::Continue(())
                 }
             },
@@ -1102,7 +1359,21 @@ impl NestedLoopJoinStream {
             Ok(false) => {
                 // Left exhausted, transition to FetchingRight
                 self.left_probe_idx = 0;
-                if self.should_track_unmatched_right {
+                if self.should_track_unmatched_right && !self.left_exhausted {
+                    // Keep the right bitmap for the next chunk of the left side
+                    let Some(bitmap) = self.current_right_batch_matched.take() else {
+                        return ControlFlow::Break(Poll::Ready(Some(internal_err!(
+                            "right bitmap should be available"
+                        ))));
+                    };
+                    if self.rescanning_right {
+                        self.right_matched_bitmaps[self.right_batch_idx - 1] = bitmap;
+                    } else {
+                        self.right_matched_bitmaps.push(bitmap);
+                    }
+                    self.current_right_batch = None;
+                    self.state = NLJState::FetchingRight;
+                } else if self.should_track_unmatched_right {
                     debug_assert!(
                         self.current_right_batch_matched.is_some(),
                         "If it's required to track matched rows in the right input, the right bitmap must be present"
@@ -1161,12 +1432,39 @@ impl NestedLoopJoinStream {
     /// Handle EmitLeftUnmatched state - emit unmatched left rows
     fn handle_emit_left_unmatched(
         &mut self,
+        cx: &mut std::task::Context<'_>,
     ) -> ControlFlow<Poll<Option<Result<RecordBatch>>>> {
         // Return any completed batches first
         if let Some(poll) = self.maybe_flush_ready_batch() {
             return ControlFlow::Break(poll);
         }
 
+        // Only the last stream to finish probing emits the unmatched rows
+        if self.left_unmatched_owner.is_none() {
+            if let Err(e) = self.claim_left_unmatched() {
+                return ControlFlow::Break(Poll::Ready(Some(Err(e))));
+            }
+        }
+
+        // Load the next spilled left batch to emit unmatched rows from
+        while self.left_emit_idx >= self.left_batch.num_rows() {
+            let Some(reader) = self.left_spill_reader.as_mut() else {
+                break;
+            };
+            match reader.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(batch))) => {
+                    self.left_offset += self.left_batch.num_rows();
+                    self.left_batch = batch;
+                    self.left_emit_idx = 0;
+                }
+                Poll::Ready(Some(Err(e))) => {
+                    return ControlFlow::Break(Poll::Ready(Some(Err(e))))
+                }
+                Poll::Ready(None) => self.left_spill_reader = None,
+                Poll::Pending => return ControlFlow::Break(Poll::Pending),
+            }
+        }
+
         // Process current unmatched state
         match self.process_left_unmatched() {
             // State unchanged (EmitLeftUnmatched)
@@ -1216,7 +1514,7 @@ impl NestedLoopJoinStream {
     /// false -> It has done with the (buffered_left x cur_right_batch), go to
     /// next state (ProbeRight)
     fn process_probe_batch(&mut self) -> Result<bool> {
-        let left_data = Arc::clone(self.get_left_data()?);
+        let left_batch = self.left_batch.clone();
         let right_batch = self
             .current_right_batch
             .as_ref()
@@ -1224,7 +1522,7 @@ impl NestedLoopJoinStream {
             .clone();
 
         // stop probing, the caller will go to the next state
-        if self.left_probe_idx >= left_data.batch().num_rows() {
+        if self.left_probe_idx >= left_batch.num_rows() {
             return Ok(false);
         }
 
@@ -1235,7 +1533,7 @@ impl NestedLoopJoinStream {
 
         let l_idx = self.left_probe_idx;
         let join_batch =
-            self.process_single_left_row_join(&left_data, &right_batch, l_idx)?;
+            self.process_single_left_row_join(&left_batch, &right_batch, l_idx)?;
 
         if let Some(batch) = join_batch {
             self.output_buffer.push_batch(batch)?;
@@ -1254,7 +1552,7 @@ impl NestedLoopJoinStream {
     /// Returns a RecordBatch containing the join results (None if empty)
     fn process_single_left_row_join(
         &mut self,
-        left_data: &JoinLeftData,
+        left_batch: &RecordBatch,
         right_batch: &RecordBatch,
         l_index: usize,
     ) -> Result<Option<RecordBatch>> {
@@ -1264,12 +1562,7 @@ impl NestedLoopJoinStream {
         }
 
         let cur_right_bitmap = if let Some(filter) = &self.join_filter {
-            apply_filter_to_row_join_batch(
-                left_data.batch(),
-                l_index,
-                right_batch,
-                filter,
-            )?
+            apply_filter_to_row_join_batch(left_batch, l_index, right_batch, filter)?
         } else {
             BooleanArray::from(vec![true; right_row_count])
         };
@@ -1297,7 +1590,7 @@ impl NestedLoopJoinStream {
             // Use the optimized approach similar to build_intermediate_batch_for_single_left_row
             let join_batch = build_row_join_batch(
                 &self.output_schema,
-                left_data.batch(),
+                left_batch,
                 l_index,
                 right_batch,
                 Some(cur_right_bitmap),
@@ -1313,7 +1606,7 @@ impl NestedLoopJoinStream {
     /// false -> next state (Done)
     fn process_left_unmatched(&mut self) -> Result<bool> {
         let left_data = self.get_left_data()?;
-        let left_batch = left_data.batch();
+        let left_batch = &self.left_batch;
 
         // ========
         // Check early return conditions
@@ -1322,8 +1615,7 @@ impl NestedLoopJoinStream {
         // Early return if join type can't have unmatched rows
         let join_type_no_produce_left = !need_produce_result_in_final(self.join_type);
         // Early return if another thread is already processing unmatched rows
-        let handled_by_other_partition =
-            self.left_emit_idx == 0 && !left_data.report_probe_completed();
+        let handled_by_other_partition = self.left_unmatched_owner == Some(false);
         // Stop processing unmatched rows, the caller will go to the next state
         let finished = self.left_emit_idx >= left_batch.num_rows();
 
@@ -1375,8 +1667,7 @@ impl NestedLoopJoinStream {
 
         // Slice both left batch, and bitmap to range [start_idx, end_idx)
         // The range is bit index (not byte)
-        let left_batch = left_data.batch();
-        let left_batch_sliced = left_batch.slice(start_idx, end_idx - start_idx);
+        let left_batch_sliced = self.left_batch.slice(start_idx, end_idx - start_idx);
 
         // Can this be more efficient?
         let mut bitmap_sliced = BooleanBufferBuilder::new(end_idx - start_idx);
@@ -1387,7 +1678,7 @@ impl NestedLoopJoinStream {
                 i - start_idx < bitmap_sliced.capacity(),
                 "DBG: {start_idx}, {end_idx}"
             );
-            bitmap_sliced.set_bit(i - start_idx, bitmap.get_bit(i));
+            bitmap_sliced.set_bit(i - start_idx, bitmap.get_bit(self.left_offset + i));
         }
         let bitmap_sliced = BooleanArray::new(bitmap_sliced.finish(), None);
 
@@ -1414,8 +1705,7 @@ impl NestedLoopJoinStream {
         let right_batch = self.current_right_batch.take();
         let cur_right_batch = unwrap_or_internal_err!(right_batch);
 
-        let left_data = self.get_left_data()?;
-        let left_schema = left_data.batch().schema();
+        let left_schema = self.left_batch.schema();
 
         let res = build_unmatched_batch(
             Arc::clone(&self.output_schema),
@@ -1435,6 +1725,92 @@ impl NestedLoopJoinStream {
 
     // ==== Utilities ====
 
+    /// Loads the next chunk of the spilled left side into `left_batch`.
+    ///
+    /// Batches are added to the chunk until the chunk memory is exceeded,
+    /// but a chunk always contains at least one batch.
+    fn poll_next_left_chunk(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Result<()>> {
+        let chunk_memory = match &self.get_left_data()?.spilled {
+            Some(spilled) => spilled.chunk_memory,
+            None => return Poll::Ready(internal_err!("left side should be spilled")),
+        };
+        let (Some(reader), Some(reservation)) = (
+            self.left_spill_reader.as_mut(),
+            self.left_chunk_reservation.as_mut(),
+        ) else {
+            return Poll::Ready(internal_err!("left spill reader should be available"));
+        };
+
+        loop {
+            let batch = match self.left_pending_batch.take() {
+                Some(batch) => batch,
+                None => match ready!(reader.poll_next_unpin(cx)) {
+                    Some(batch) => batch?,
+                    None => {
+                        self.left_exhausted = true;
+                        self.left_spill_reader = None;
+                        break;
+                    }
+                },
+            };
+
+            let batch_size = batch.get_array_memory_size();
+            let fits = reservation.size() + batch_size <= chunk_memory
+                && reservation.try_grow(batch_size).is_ok();
+            if !fits {
+                if !self.left_chunk_batches.is_empty() {
+                    self.left_pending_batch = Some(batch);
+                    break;
+                }
+                reservation.try_grow(batch_size)?;
+            }
+            self.left_chunk_batches.push(batch);
+        }
+
+        self.left_batch =
+            concat_batches(&self.left_batch.schema(), &self.left_chunk_batches)?;
+        self.left_chunk_batches.clear();
+        Poll::Ready(Ok(()))
+    }
+
+    /// Appends a right batch to the spill file read by the later passes
+    fn spill_right_batch(&mut self, batch: &RecordBatch) -> Result<()> {
+        if self.right_spill_in_progress.is_none() {
+            let Some(spill_manager) = &self.right_spill_manager else {
+                return internal_err!("right spill manager should be available");
+            };
+            self.right_spill_in_progress =
+                Some(spill_manager.create_in_progress_file("NestedLoopJoin probe side")?);
+        }
+        self.right_spill_in_progress
+            .as_mut()
+            .map_or(Ok(()), |spill_file| spill_file.append_batch(batch))
+    }
+
+    /// Decides whether this stream emits the unmatched left rows, and if so
+    /// rewinds the spilled left side
+    fn claim_left_unmatched(&mut self) -> Result<()> {
+        let left_data = Arc::clone(self.get_left_data()?);
+        let owner = left_data.report_probe_completed();
+        self.left_unmatched_owner = Some(owner);
+
+        if owner && need_produce_result_in_final(self.join_type) {
+            if let Some(spilled) = &left_data.spilled {
+                self.left_spill_reader = Some(spilled.read()?);
+                self.left_offset = 0;
+                self.left_batch = self.left_batch.slice(0, 0);
+                self.left_emit_idx = 0;
+                if let Some(reservation) = self.left_chunk_reservation.as_mut() {
+                    reservation.free();
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Get the build-side data of the left input, errors if it's None
     fn get_left_data(&self) -> Result<&Arc<JoinLeftData>> {
         self.buffered_left_data
@@ -1486,7 +1862,7 @@ impl NestedLoopJoinStream {
         // 1. Maybe update the left bitmap
         if need_produce_result_in_final(self.join_type) && (joined_len > 0) {
             let mut bitmap = left_data.bitmap().lock();
-            bitmap.set_bit(l_index, true);
+            bitmap.set_bit(self.left_offset + l_index, true);
         }
 
         // 2. Maybe updateh the right bitmap
@@ -1911,6 +2287,8 @@ pub(crate) mod tests {
     use arrow::datatypes::{DataType, Field};
     use datafusion_common::test_util::batches_to_sort_string;
     use datafusion_common::{assert_contains, ScalarValue};
+    use datafusion_execution::config::SessionConfig;
+    use datafusion_execution::disk_manager::{DiskManagerBuilder, DiskManagerMode};
     use datafusion_execution::runtime_env::RuntimeEnvBuilder;
     use datafusion_expr::Operator;
     use datafusion_physical_expr::expressions::{BinaryExpr, Literal};
@@ -2457,6 +2835,9 @@ pub(crate) mod tests {
         for join_type in join_types {
             let runtime = RuntimeEnvBuilder::new()
                 .with_memory_limit(100, 1.0)
+                .with_disk_manager_builder(
+                    DiskManagerBuilder::default().with_mode(DiskManagerMode::Disabled),
+                )
                 .build_arc()?;
             let task_ctx = TaskContext::default().with_runtime(runtime);
             let task_ctx = Arc::new(task_ctx);
@@ -2480,6 +2861,104 @@ pub(crate) mod tests {
         Ok(())
     }
 
+    /// Builds a table with `num_partitions` partitions of `num_batches`
+    /// separately allocated batches each
+    fn build_batched_table(
+        names: (&str, &str, &str),
+        num_partitions: i32,
+        num_batches: i32,
+        batch_rows: i32,
+    ) -> Arc<dyn ExecutionPlan> {
+        let partitions = (0..num_partitions)
+            .map(|p| {
+                (0..num_batches)
+                    .map(|i| {
+                        let start = (p * num_batches + i) * batch_rows;
+                        let values = (start..start + batch_rows).collect::<Vec<_>>();
+                        let b = values.iter().map(|v| v % 11).collect::<Vec<_>>();
+                        build_table_i32(
+                            (names.0, &values),
+                            (names.1, &b),
+                            (names.2, &values),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let schema = partitions[0][0].schema();
+        TestMemoryExec::try_new_exec(&partitions, schema, None).unwrap()
+    }
+
+    /// Joins all partitions, returning the sorted output and the metrics
+    async fn partitioned_join_collect(
+        left: Arc<dyn ExecutionPlan>,
+        right: Arc<dyn ExecutionPlan>,
+        join_type: JoinType,
+        filter: JoinFilter,
+        context: Arc<TaskContext>,
+    ) -> Result<(String, MetricsSet)> {
+        let join =
+            NestedLoopJoinExec::try_new(left, right, Some(filter), &join_type, None)?;
+        let mut batches = vec![];
+        for i in 0..join.properties().partitioning.partition_count() {
+            batches
+                .extend(common::collect(join.execute(i, Arc::clone(&context))?).await?);
+        }
+        Ok((batches_to_sort_string(&batches), join.metrics().unwrap()))
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn join_with_spilled_left(
+        #[values(
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::Full,
+            JoinType::LeftSemi,
+            JoinType::LeftAnti,
+            JoinType::LeftMark,
+            JoinType::RightSemi,
+            JoinType::RightAnti,
+            JoinType::RightMark
+        )]
+        join_type: JoinType,
+    ) -> Result<()> {
+        let left = build_batched_table(("a1", "b1", "c1"), 1, 8, 4);
+        let right = build_batched_table(("a2", "b2", "c2"), 3, 2, 3);
+        let filter = prepare_join_filter();
+
+        let (expected, metrics) = partitioned_join_collect(
+            Arc::clone(&left),
+            Arc::clone(&right),
+            join_type,
+            filter.clone(),
+            new_task_ctx(16),
+        )
+        .await?;
+        assert_eq!(metrics.spill_count(), Some(0));
+
+        let runtime = RuntimeEnvBuilder::new()
+            .with_memory_limit(2000, 1.0)
+            .with_disk_manager_builder(
+                DiskManagerBuilder::default().with_mode(DiskManagerMode::OsTmpDirectory),
+            )
+            .build_arc()?;
+        let task_ctx = TaskContext::default()
+            .with_session_config(SessionConfig::new().with_batch_size(16))
+            .with_runtime(runtime);
+        let (actual, metrics) =
+            partitioned_join_collect(left, right, join_type, filter, Arc::new(task_ctx))
+                .await?;
+
+        assert_eq!(actual, expected);
+        assert!(metrics.spill_count().unwrap() > 0);
+        assert!(metrics.spilled_bytes().unwrap() > 0);
+        assert!(metrics.spilled_rows().unwrap() > 0);
+
+        Ok(())
+    }
+
     /// Returns the column names on the schema
     fn columns(schema: &Schema) -> Vec<String> {
         schema.fields().iter().map(|f| f.name().clone()).collect()
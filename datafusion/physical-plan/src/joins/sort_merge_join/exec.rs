@@ -147,6 +147,11 @@ impl SortMergeJoinExec {
         let right_schema = right.schema();
 
         check_join_is_valid(&left_schema, &right_schema, &on)?;
+        if join_type == JoinType::AsOf {
+            return plan_err!(
+                "SortMergeJoinExec does not support AsOf joins, use AsOfJoinExec"
+            );
+        }
         if sort_options.len() != on.len() {
             return plan_err!(
                 "Expected number of sort options: {}, actual: {}",
@@ -217,7 +222,8 @@ impl SortMergeJoinExec {
             | JoinType::Full
             | JoinType::LeftAnti
             | JoinType::LeftSemi
-            | JoinType::LeftMark => JoinSide::Left,
+            | JoinType::LeftMark
+            | JoinType::AsOf => JoinSide::Left,
         }
     }
 
@@ -320,7 +326,7 @@ impl SortMergeJoinExec {
                 .map(|(l, r)| (Arc::clone(r), Arc::clone(l)))
                 .collect::<Vec<_>>(),
             self.filter().as_ref().map(JoinFilter::swap),
-            self.join_type().swap(),
+            self.join_type().swap()?,
             self.sort_options.clone(),
             self.null_equality,
         )?;
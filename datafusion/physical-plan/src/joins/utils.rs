@@ -56,7 +56,8 @@ use datafusion_common::cast::as_boolean_array;
 use datafusion_common::hash_utils::create_hashes;
 use datafusion_common::stats::Precision;
 use datafusion_common::{
-    plan_err, DataFusionError, JoinSide, JoinType, NullEquality, Result, SharedResult,
+    internal_err, plan_err, DataFusionError, JoinSide, JoinType, NullEquality, Result,
+    SharedResult,
 };
 use datafusion_expr::interval_arithmetic::Interval;
 use datafusion_expr::Operator;
@@ -219,6 +220,7 @@ fn output_join_field(old_field: &Field, join_type: &JoinType, is_left: bool) ->
     let force_nullable = match join_type {
         JoinType::Inner => false,
         JoinType::Left => !is_left, // right input is padded with nulls
+        JoinType::AsOf => !is_left, // right input is padded with nulls
         JoinType::Right => is_left, // left input is padded with nulls
         JoinType::Full => true,     // both inputs can be padded with nulls
         JoinType::LeftSemi => false, // doesn't introduce nulls
@@ -277,7 +279,11 @@ pub fn build_join_schema(
     };
 
     let (fields, column_indices): (SchemaBuilder, Vec<ColumnIndex>) = match join_type {
-        JoinType::Inner | JoinType::Left | JoinType::Full | JoinType::Right => {
+        JoinType::Inner
+        | JoinType::Left
+        | JoinType::Full
+        | JoinType::Right
+        | JoinType::AsOf => {
             // left then right
             left_fields().chain(right_fields()).unzip()
         }
@@ -399,7 +405,7 @@ struct PartialJoinStatistics {
 }
 
 /// Estimate the statistics for the given join's output.
-pub(crate) fn estimate_join_statistics(
+pub fn estimate_join_statistics(
     left_stats: Statistics,
     right_stats: Statistics,
     on: JoinOn,
@@ -539,6 +545,26 @@ fn estimate_join_cardinality(
                 column_statistics,
             })
         }
+
+        // AsOf joins return exactly one row for every left row, and the right
+        // columns of these rows may repeat or be null
+        JoinType::AsOf => {
+            let num_rows = *left_stats.num_rows.get_value()?;
+            let column_statistics = left_stats
+                .column_statistics
+                .into_iter()
+                .chain(
+                    right_stats
+                        .column_statistics
+                        .iter()
+                        .map(|_| ColumnStatistics::new_unknown()),
+                )
+                .collect();
+            Some(PartialJoinStatistics {
+                num_rows,
+                column_statistics,
+            })
+        }
     }
 }
 
@@ -555,6 +581,10 @@ fn estimate_inner_join_cardinality(
         return Some(estimation);
     };
 
+    if let Some(estimation) = estimate_histogram_join(&left_stats, &right_stats) {
+        return Some(estimation);
+    }
+
     // The algorithm here is partly based on the non-histogram selectivity estimation
     // from Spark's Catalyst optimizer.
     let mut join_selectivity = Precision::Absent;
@@ -594,6 +624,34 @@ fn estimate_inner_join_cardinality(
     }
 }
 
+/// Estimates the cardinality of an inner join from the histograms of the join
+/// columns computed by `ANALYZE TABLE`, which also accounts for skewed values.
+/// Uses the most selective pair of join columns that both have a histogram,
+/// and returns `None` if there is no such pair.
+fn estimate_histogram_join(
+    left_stats: &Statistics,
+    right_stats: &Statistics,
+) -> Option<Precision<usize>> {
+    let left_num_rows = *left_stats.num_rows.get_value()?;
+    let right_num_rows = *right_stats.num_rows.get_value()?;
+    let selectivity = left_stats
+        .column_statistics
+        .iter()
+        .zip(right_stats.column_statistics.iter())
+        .filter_map(|(left_stat, right_stat)| {
+            let left = left_stat.histogram.as_ref()?;
+            let right = right_stat.histogram.as_ref()?;
+            if left.num_rows == 0 || right.num_rows == 0 {
+                return Some(0.0);
+            }
+            let rows = left.estimate_join(right)?;
+            Some(rows / (left.num_rows as f64 * right.num_rows as f64))
+        })
+        .min_by(f64::total_cmp)?;
+    let rows = left_num_rows as f64 * right_num_rows as f64 * selectivity;
+    Some(Precision::Inexact(rows.round() as usize))
+}
+
 /// Estimates if inputs are non-overlapping, using input statistics.
 /// If inputs are disjoint, returns zero estimation, otherwise returns None
 fn estimate_disjoint_inputs(
@@ -996,7 +1054,8 @@ pub(crate) fn build_batch_empty_build_side(
         | JoinType::LeftSemi
         | JoinType::RightSemi
         | JoinType::LeftAnti
-        | JoinType::LeftMark => Ok(RecordBatch::new_empty(Arc::new(schema.clone()))),
+        | JoinType::LeftMark
+        | JoinType::AsOf => Ok(RecordBatch::new_empty(Arc::new(schema.clone()))),
 
         // the remaining joins will return data for the right columns and null for the left ones
         JoinType::Right | JoinType::Full | JoinType::RightAnti | JoinType::RightMark => {
@@ -1086,6 +1145,9 @@ pub(crate) fn adjust_indices_by_join_type(
                 UInt32Array::from_iter_values(vec![]),
             ))
         }
+        JoinType::AsOf => {
+            internal_err!("AsOf joins are only supported by AsOfJoinExec")
+        }
     }
 }
 
@@ -1429,9 +1491,11 @@ pub(crate) fn symmetric_join_output_partitioning(
     let left_partitioning = left.output_partitioning();
     let right_partitioning = right.output_partitioning();
     let result = match join_type {
-        JoinType::Left | JoinType::LeftSemi | JoinType::LeftAnti | JoinType::LeftMark => {
-            left_partitioning.clone()
-        }
+        JoinType::Left
+        | JoinType::LeftSemi
+        | JoinType::LeftAnti
+        | JoinType::LeftMark
+        | JoinType::AsOf => left_partitioning.clone(),
         JoinType::RightSemi | JoinType::RightAnti | JoinType::RightMark => {
             right_partitioning.clone()
         }
@@ -1463,7 +1527,8 @@ pub(crate) fn asymmetric_join_output_partitioning(
         | JoinType::LeftSemi
         | JoinType::LeftAnti
         | JoinType::Full
-        | JoinType::LeftMark => Partitioning::UnknownPartitioning(
+        | JoinType::LeftMark
+        | JoinType::AsOf => Partitioning::UnknownPartitioning(
             right.output_partitioning().partition_count(),
         ),
     };
@@ -1756,7 +1821,9 @@ mod tests {
     use arrow::datatypes::{DataType, Fields};
     use arrow::error::{ArrowError, Result as ArrowResult};
     use datafusion_common::stats::Precision::{Absent, Exact, Inexact};
-    use datafusion_common::{arrow_datafusion_err, arrow_err, ScalarValue};
+    use datafusion_common::{
+        arrow_datafusion_err, arrow_err, ColumnHistogram, HistogramBucket, ScalarValue,
+    };
     use datafusion_physical_expr::PhysicalSortExpr;
 
     use rstest::rstest;
@@ -1956,6 +2023,7 @@ mod tests {
             max_value: max.map(ScalarValue::from),
             sum_value: Absent,
             null_count,
+            histogram: None,
         }
     }
 
@@ -2209,6 +2277,69 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_inner_join_cardinality_histogram() -> Result<()> {
+        let bucket =
+            |lower: i64, upper: i64, num_values, distinct_count| HistogramBucket {
+                lower: ScalarValue::from(lower),
+                upper: ScalarValue::from(upper),
+                num_values,
+                distinct_count,
+            };
+        let histogram = |num_rows, buckets| ColumnHistogram {
+            num_rows,
+            buckets,
+            most_common_values: vec![],
+        };
+        // 90% of the left rows have one of the 10 values of the right side
+        let left_col_stats =
+            vec![
+                create_column_stats(Inexact(0), Inexact(99), Inexact(100), Absent)
+                    .with_histogram(Some(Arc::new(histogram(
+                        1000,
+                        vec![bucket(0, 9, 900, 10), bucket(10, 99, 100, 90)],
+                    )))),
+            ];
+        let right_col_stats =
+            vec![
+                create_column_stats(Inexact(0), Inexact(9), Inexact(10), Absent)
+                    .with_histogram(Some(Arc::new(histogram(
+                        10,
+                        vec![bucket(0, 9, 10, 10)],
+                    )))),
+            ];
+
+        let estimate = |left_col_stats, right_col_stats| {
+            estimate_inner_join_cardinality(
+                create_stats(Some(1000), left_col_stats, false),
+                create_stats(Some(10), right_col_stats, false),
+            )
+        };
+        assert_eq!(
+            estimate(left_col_stats.clone(), right_col_stats.clone()),
+            Some(Inexact(900))
+        );
+
+        // Without histograms, the values are assumed to be uniformly distributed
+        let without_histogram = |stats: Vec<ColumnStatistics>| {
+            stats
+                .into_iter()
+                .map(|stats| ColumnStatistics {
+                    histogram: None,
+                    ..stats
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            estimate(
+                without_histogram(left_col_stats),
+                without_histogram(right_col_stats)
+            ),
+            Some(Inexact(100))
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_inner_join_cardinality_decimal_range() -> Result<()> {
         let left_col_stats = vec![ColumnStatistics {
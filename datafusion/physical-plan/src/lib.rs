@@ -74,12 +74,16 @@ pub mod filter;
 pub mod filter_pushdown;
 pub mod joins;
 pub mod limit;
+pub mod match_recognize;
+pub mod materialized_cte;
 pub mod memory;
 pub mod metrics;
 pub mod placeholder_row;
+pub mod progress;
 pub mod projection;
 pub mod recursive_query;
 pub mod repartition;
+pub mod sample;
 pub mod sorts;
 pub mod spill;
 pub mod stream;
@@ -0,0 +1,997 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the row pattern recognition plan, see [`MatchRecognizeExec`]
+
+mod pattern;
+
+use std::any::Any;
+use std::fmt::{self, Display, Formatter};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{ready, Context, Poll};
+
+use crate::execution_plan::{CardinalityEffect, EmissionType};
+use crate::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use crate::windows::get_ordered_partition_by_indices;
+use crate::{
+    DisplayAs, DisplayFormatType, Distribution, ExecutionPlan, ExecutionPlanProperties,
+    PlanProperties, RecordBatchStream, SendableRecordBatchStream, Statistics,
+};
+use pattern::{MatchResult, MatchedRow, PatternInput, PatternMatcher};
+
+use arrow::array::{Array, ArrayRef, Int64Array, StringArray, UInt32Array, UInt64Array};
+use arrow::compute::{concat_batches, take, SortColumn};
+use arrow::datatypes::{DataType, Field, FieldRef, Schema, SchemaRef};
+use arrow::record_batch::{RecordBatch, RecordBatchOptions};
+use datafusion_common::cast::as_boolean_array;
+use datafusion_common::utils::evaluate_partition_ranges;
+use datafusion_common::{exec_err, internal_err, plan_err, Result, ScalarValue};
+use datafusion_execution::TaskContext;
+use datafusion_expr::{AfterMatchSkip, EmptyMatchesMode, MatchPattern, RowsPerMatch};
+use datafusion_physical_expr::equivalence::ProjectionMapping;
+use datafusion_physical_expr::{
+    EquivalenceProperties, LexRequirement, OrderingRequirements, PhysicalExpr,
+    PhysicalSortExpr, PhysicalSortRequirement,
+};
+use datafusion_physical_expr_common::physical_expr::format_physical_expr_list;
+
+use futures::stream::{Stream, StreamExt};
+
+/// A pattern symbol and the predicate a row must satisfy to be mapped to it
+#[derive(Debug, Clone)]
+pub struct MatchSymbolExpr {
+    /// The symbol name
+    pub name: String,
+    /// Predicate over the current row
+    pub predicate: Arc<dyn PhysicalExpr>,
+}
+
+impl Display for MatchSymbolExpr {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{} AS {}", self.name, self.predicate)
+    }
+}
+
+/// A value computed for each match, named `name` in the output
+#[derive(Debug, Clone)]
+pub struct MatchMeasureExpr {
+    /// How the value is computed
+    pub function: MatchMeasureFunction,
+    /// The output column name
+    pub name: String,
+}
+
+impl Display for MatchMeasureExpr {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{} AS {}", self.function, self.name)
+    }
+}
+
+/// How a [`MatchMeasureExpr`] is computed from the rows of a match, see
+/// [`datafusion_expr::MatchMeasureKind`]
+#[derive(Debug, Clone)]
+pub enum MatchMeasureFunction {
+    /// The sequential number of the match within its partition
+    MatchNumber,
+    /// The symbol of the last row
+    Classifier,
+    /// The number of rows, optionally only those mapped to `symbol`
+    Count { symbol: Option<String> },
+    /// `expr` evaluated on the first row, optionally the first row mapped
+    /// to `symbol`
+    First {
+        expr: Arc<dyn PhysicalExpr>,
+        symbol: Option<String>,
+    },
+    /// `expr` evaluated on the last row, optionally the last row mapped to
+    /// `symbol`
+    Last {
+        expr: Arc<dyn PhysicalExpr>,
+        symbol: Option<String>,
+    },
+}
+
+impl MatchMeasureFunction {
+    fn symbol(&self) -> Option<&str> {
+        match self {
+            MatchMeasureFunction::MatchNumber | MatchMeasureFunction::Classifier => None,
+            MatchMeasureFunction::Count { symbol }
+            | MatchMeasureFunction::First { symbol, .. }
+            | MatchMeasureFunction::Last { symbol, .. } => symbol.as_deref(),
+        }
+    }
+}
+
+impl Display for MatchMeasureFunction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let (name, expr, symbol) = match self {
+            MatchMeasureFunction::MatchNumber => return write!(f, "MATCH_NUMBER()"),
+            MatchMeasureFunction::Classifier => return write!(f, "CLASSIFIER()"),
+            MatchMeasureFunction::Count { symbol: None } => return write!(f, "COUNT(*)"),
+            MatchMeasureFunction::Count {
+                symbol: Some(symbol),
+            } => return write!(f, "COUNT({symbol}.*)"),
+            MatchMeasureFunction::First { expr, symbol } => ("FIRST", expr, symbol),
+            MatchMeasureFunction::Last { expr, symbol } => ("LAST", expr, symbol),
+        };
+        write!(f, "{name}({expr})")?;
+        match symbol {
+            Some(symbol) => write!(f, " FOR {symbol}"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Finds sequences of rows matching a pattern, see
+/// [`datafusion_expr::MatchRecognize`].
+///
+/// The input must be hash partitioned by `partition_by` and sorted by
+/// `partition_by` and `order_by`. Each partition is matched as it streams
+/// through, only buffering the rows that may still be part of a match: a
+/// match is emitted as soon as no later row can change it.
+#[derive(Debug, Clone)]
+pub struct MatchRecognizeExec {
+    /// Input execution plan
+    input: Arc<dyn ExecutionPlan>,
+    /// Expressions that split the input into partitions
+    partition_by: Vec<Arc<dyn PhysicalExpr>>,
+    /// Order of the rows within each partition
+    order_by: Vec<PhysicalSortExpr>,
+    /// Every symbol of `pattern` with the predicate that defines it
+    symbols: Vec<MatchSymbolExpr>,
+    /// The row pattern
+    pattern: MatchPattern,
+    /// Values computed for each match
+    measures: Vec<MatchMeasureExpr>,
+    /// How many rows are produced for each match
+    rows_per_match: RowsPerMatch,
+    /// Where to resume searching after a match
+    after_match_skip: AfterMatchSkip,
+    /// The output schema
+    schema: SchemaRef,
+    /// `pattern` compiled for matching
+    matcher: Arc<PatternMatcher>,
+    /// Partition by indices that define the ordering of the input
+    ordered_partition_by_indices: Vec<usize>,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+    cache: PlanProperties,
+}
+
+impl MatchRecognizeExec {
+    /// Create a new MatchRecognizeExec
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_new(
+        input: Arc<dyn ExecutionPlan>,
+        partition_by: Vec<Arc<dyn PhysicalExpr>>,
+        order_by: Vec<PhysicalSortExpr>,
+        symbols: Vec<MatchSymbolExpr>,
+        pattern: MatchPattern,
+        measures: Vec<MatchMeasureExpr>,
+        rows_per_match: RowsPerMatch,
+        after_match_skip: AfterMatchSkip,
+    ) -> Result<Self> {
+        let symbol_names = symbols
+            .iter()
+            .map(|symbol| symbol.name.clone())
+            .collect::<Vec<_>>();
+        let matcher = PatternMatcher::try_new(&pattern, &symbol_names)?;
+        for measure in &measures {
+            if let Some(symbol) = measure.function.symbol() {
+                symbol_index(&symbol_names, symbol)?;
+            }
+        }
+        if let AfterMatchSkip::ToFirst(symbol) | AfterMatchSkip::ToLast(symbol) =
+            &after_match_skip
+        {
+            symbol_index(&symbol_names, symbol)?;
+        }
+
+        let input_schema = input.schema();
+        let mut fields: Vec<FieldRef> = match rows_per_match {
+            RowsPerMatch::OneRow => partition_by
+                .iter()
+                .map(|expr| expr.return_field(&input_schema))
+                .collect::<Result<_>>()?,
+            RowsPerMatch::AllRows(_) => input_schema.fields().iter().cloned().collect(),
+        };
+        for measure in &measures {
+            let data_type = match &measure.function {
+                MatchMeasureFunction::MatchNumber => DataType::UInt64,
+                MatchMeasureFunction::Classifier => DataType::Utf8,
+                MatchMeasureFunction::Count { .. } => DataType::Int64,
+                MatchMeasureFunction::First { expr, .. }
+                | MatchMeasureFunction::Last { expr, .. } => {
+                    expr.data_type(&input_schema)?
+                }
+            };
+            fields.push(Arc::new(Field::new(&measure.name, data_type, true)));
+        }
+        let schema = Arc::new(Schema::new_with_metadata(
+            fields,
+            input_schema.metadata().clone(),
+        ));
+
+        let ordered_partition_by_indices =
+            get_ordered_partition_by_indices(&partition_by, &input)?;
+        let cache =
+            Self::compute_properties(&input, &schema, &partition_by, rows_per_match)?;
+        Ok(Self {
+            input,
+            partition_by,
+            order_by,
+            symbols,
+            pattern,
+            measures,
+            rows_per_match,
+            after_match_skip,
+            schema,
+            matcher: Arc::new(matcher),
+            ordered_partition_by_indices,
+            metrics: ExecutionPlanMetricsSet::new(),
+            cache,
+        })
+    }
+
+    /// This function creates the cache object that stores the plan properties such as schema, equivalence properties, ordering, partitioning, etc.
+    fn compute_properties(
+        input: &Arc<dyn ExecutionPlan>,
+        schema: &SchemaRef,
+        partition_by: &[Arc<dyn PhysicalExpr>],
+        rows_per_match: RowsPerMatch,
+    ) -> Result<PlanProperties> {
+        let input_eq_properties = input.equivalence_properties();
+        let (eq_properties, output_partitioning) = match rows_per_match {
+            // The output starts with the partition by columns
+            RowsPerMatch::OneRow => {
+                let projection_mapping = ProjectionMapping::try_new(
+                    partition_by
+                        .iter()
+                        .zip(schema.fields())
+                        .map(|(expr, field)| (Arc::clone(expr), field.name().clone())),
+                    &input.schema(),
+                )?;
+                (
+                    input_eq_properties.project(&projection_mapping, Arc::clone(schema)),
+                    input
+                        .output_partitioning()
+                        .project(&projection_mapping, input_eq_properties),
+                )
+            }
+            // The output starts with the input columns, in input order
+            RowsPerMatch::AllRows(_) => (
+                EquivalenceProperties::new(Arc::clone(schema))
+                    .extend(input_eq_properties.clone())?,
+                input.output_partitioning().clone(),
+            ),
+        };
+        Ok(PlanProperties::new(
+            eq_properties,
+            output_partitioning,
+            EmissionType::Incremental,
+            input.boundedness(),
+        ))
+    }
+
+    /// Input execution plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// Expressions that split the input into partitions
+    pub fn partition_by(&self) -> &[Arc<dyn PhysicalExpr>] {
+        &self.partition_by
+    }
+
+    /// Order of the rows within each partition
+    pub fn order_by(&self) -> &[PhysicalSortExpr] {
+        &self.order_by
+    }
+
+    /// Every symbol of the pattern with the predicate that defines it
+    pub fn symbols(&self) -> &[MatchSymbolExpr] {
+        &self.symbols
+    }
+
+    /// The row pattern
+    pub fn pattern(&self) -> &MatchPattern {
+        &self.pattern
+    }
+
+    /// Values computed for each match
+    pub fn measures(&self) -> &[MatchMeasureExpr] {
+        &self.measures
+    }
+
+    /// How many rows are produced for each match
+    pub fn rows_per_match(&self) -> RowsPerMatch {
+        self.rows_per_match
+    }
+
+    /// Where to resume searching after a match
+    pub fn after_match_skip(&self) -> &AfterMatchSkip {
+        &self.after_match_skip
+    }
+
+    fn symbol_names(&self) -> Vec<String> {
+        self.symbols
+            .iter()
+            .map(|symbol| symbol.name.clone())
+            .collect()
+    }
+}
+
+/// Returns the index of `symbol` in `symbols`
+fn symbol_index(symbols: &[String], symbol: &str) -> Result<usize> {
+    match symbols.iter().position(|s| s == symbol) {
+        Some(index) => Ok(index),
+        None => plan_err!("Symbol {symbol} does not appear in the pattern"),
+    }
+}
+
+impl DisplayAs for MatchRecognizeExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut Formatter) -> fmt::Result {
+        let list = |items: &[String]| items.join(", ");
+        let measures = self
+            .measures
+            .iter()
+            .map(|measure| measure.to_string())
+            .collect::<Vec<_>>();
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                let order_by = self
+                    .order_by
+                    .iter()
+                    .map(|sort| sort.to_string())
+                    .collect::<Vec<_>>();
+                let symbols = self
+                    .symbols
+                    .iter()
+                    .map(|symbol| symbol.to_string())
+                    .collect::<Vec<_>>();
+                write!(
+                    f,
+                    "MatchRecognizeExec: partition_by=[{}], order_by=[{}], measures=[{}], {}, after match skip {}, pattern=({}), define=[{}]",
+                    format_physical_expr_list(&self.partition_by),
+                    list(&order_by),
+                    list(&measures),
+                    self.rows_per_match,
+                    self.after_match_skip,
+                    self.pattern,
+                    list(&symbols),
+                )
+            }
+            DisplayFormatType::TreeRender => {
+                writeln!(f, "pattern={}", self.pattern)?;
+                writeln!(f, "measures={}", list(&measures))?;
+                write!(f, "rows_per_match={}", self.rows_per_match)
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for MatchRecognizeExec {
+    fn name(&self) -> &'static str {
+        "MatchRecognizeExec"
+    }
+
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        &self.cache
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![&self.input]
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        if self.partition_by.is_empty() {
+            vec![Distribution::SinglePartition]
+        } else {
+            vec![Distribution::HashPartitioned(self.partition_by.clone())]
+        }
+    }
+
+    /// Rows of each partition must be adjacent and sorted, the partition
+    /// by expressions are ordered to reuse an existing input ordering
+    fn required_input_ordering(&self) -> Vec<Option<OrderingRequirements>> {
+        let ordered = self
+            .ordered_partition_by_indices
+            .iter()
+            .map(|idx| &self.partition_by[*idx]);
+        let unordered = self
+            .partition_by
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| !self.ordered_partition_by_indices.contains(idx))
+            .map(|(_, expr)| expr);
+        let mut requirements = ordered
+            .chain(unordered)
+            .map(|expr| PhysicalSortRequirement::new(Arc::clone(expr), None))
+            .collect::<Vec<_>>();
+        for sort_expr in &self.order_by {
+            if !requirements.iter().any(|req| req.expr.eq(&sort_expr.expr)) {
+                requirements.push(sort_expr.clone().into());
+            }
+        }
+        vec![LexRequirement::new(requirements).map(OrderingRequirements::new)]
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![matches!(self.rows_per_match, RowsPerMatch::AllRows(_))]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        match children.len() {
+            1 => Ok(Arc::new(MatchRecognizeExec::try_new(
+                Arc::clone(&children[0]),
+                self.partition_by.clone(),
+                self.order_by.clone(),
+                self.symbols.clone(),
+                self.pattern.clone(),
+                self.measures.clone(),
+                self.rows_per_match,
+                self.after_match_skip.clone(),
+            )?)),
+            _ => internal_err!("MatchRecognizeExec wrong number of children"),
+        }
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let symbol_names = self.symbol_names();
+        let measures = self
+            .measures
+            .iter()
+            .map(|measure| {
+                let symbol = measure
+                    .function
+                    .symbol()
+                    .map(|symbol| symbol_index(&symbol_names, symbol))
+                    .transpose()?;
+                Ok(match &measure.function {
+                    MatchMeasureFunction::MatchNumber => Measure::MatchNumber,
+                    MatchMeasureFunction::Classifier => Measure::Classifier,
+                    MatchMeasureFunction::Count { .. } => Measure::Count(symbol),
+                    MatchMeasureFunction::First { expr, .. } => {
+                        Measure::First(Arc::clone(expr), symbol)
+                    }
+                    MatchMeasureFunction::Last { expr, .. } => {
+                        Measure::Last(Arc::clone(expr), symbol)
+                    }
+                })
+            })
+            .collect::<Result<_>>()?;
+        let after_match_skip = match &self.after_match_skip {
+            AfterMatchSkip::PastLastRow => Skip::PastLastRow,
+            AfterMatchSkip::ToNextRow => Skip::ToNextRow,
+            AfterMatchSkip::ToFirst(symbol) => {
+                Skip::ToFirst(symbol_index(&symbol_names, symbol)?)
+            }
+            AfterMatchSkip::ToLast(symbol) => {
+                Skip::ToLast(symbol_index(&symbol_names, symbol)?)
+            }
+        };
+        let input_schema = self.input.schema();
+        Ok(Box::pin(MatchRecognizeStream {
+            schema: Arc::clone(&self.schema),
+            input: self.input.execute(partition, context)?,
+            partition_by: self.partition_by.clone(),
+            predicates: self
+                .symbols
+                .iter()
+                .map(|symbol| Arc::clone(&symbol.predicate))
+                .collect(),
+            symbol_names,
+            matcher: Arc::clone(&self.matcher),
+            measures,
+            rows_per_match: self.rows_per_match,
+            after_match_skip,
+            partition: PartitionState::new(RecordBatch::new_empty(input_schema)),
+            finished: false,
+            baseline_metrics: BaselineMetrics::new(&self.metrics, partition),
+        }))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Result<Statistics> {
+        self.partition_statistics(None)
+    }
+
+    fn partition_statistics(&self, _partition: Option<usize>) -> Result<Statistics> {
+        Ok(Statistics::new_unknown(&self.schema))
+    }
+
+    fn cardinality_effect(&self) -> CardinalityEffect {
+        match self.rows_per_match {
+            RowsPerMatch::OneRow => CardinalityEffect::LowerEqual,
+            RowsPerMatch::AllRows(_) => CardinalityEffect::Unknown,
+        }
+    }
+}
+
+/// A measure with symbols resolved to their index
+enum Measure {
+    MatchNumber,
+    Classifier,
+    Count(Option<usize>),
+    First(Arc<dyn PhysicalExpr>, Option<usize>),
+    Last(Arc<dyn PhysicalExpr>, Option<usize>),
+}
+
+impl Measure {
+    fn symbol(&self) -> Option<usize> {
+        match self {
+            Measure::MatchNumber | Measure::Classifier => None,
+            Measure::Count(symbol)
+            | Measure::First(_, symbol)
+            | Measure::Last(_, symbol) => *symbol,
+        }
+    }
+}
+
+/// `AFTER MATCH SKIP` with symbols resolved to their index
+enum Skip {
+    PastLastRow,
+    ToNextRow,
+    ToFirst(usize),
+    ToLast(usize),
+}
+
+/// The buffered rows of the partition being matched
+struct PartitionState {
+    /// The partition by values, `None` before the first row
+    key: Option<Vec<ScalarValue>>,
+    /// Rows that may still be part of a match
+    rows: RecordBatch,
+    /// Whether row `i` can be mapped to symbol `j`, at `i * num_symbols + j`
+    symbol_matches: Vec<bool>,
+    /// Position of the first buffered row within the partition
+    offset: usize,
+    /// The buffered row to search the next match from
+    search_start: usize,
+    /// Buffered rows before this one are part of a match
+    matched_until: usize,
+    /// The number of the last match
+    match_number: u64,
+}
+
+impl PartitionState {
+    fn new(rows: RecordBatch) -> Self {
+        Self {
+            key: None,
+            rows,
+            symbol_matches: vec![],
+            offset: 0,
+            search_start: 0,
+            matched_until: 0,
+            match_number: 0,
+        }
+    }
+
+    /// Discards the rows before `search_start`, which can not be part of
+    /// any later match
+    fn discard_searched_rows(&mut self, num_symbols: usize) {
+        let consumed = self.search_start;
+        self.rows = self.rows.slice(consumed, self.rows.num_rows() - consumed);
+        self.symbol_matches.drain(..consumed * num_symbols);
+        self.offset += consumed;
+        self.search_start = 0;
+        self.matched_until = self.matched_until.saturating_sub(consumed);
+    }
+}
+
+/// The value of a measure for one output row
+#[derive(Debug, Clone, Copy)]
+enum MeasureValue {
+    Null,
+    MatchNumber(u64),
+    Classifier(usize),
+    Count(i64),
+    /// The buffered row to evaluate the measure expression on
+    Row(u32),
+}
+
+/// Running state of a measure over the rows of a match
+#[derive(Debug, Clone, Copy, Default)]
+struct MeasureState {
+    first: Option<u32>,
+    last: Option<u32>,
+    count: i64,
+}
+
+/// Rows produced for the matches of a partition
+struct MatchOutput {
+    /// The buffered row each output row is built from
+    rows: Vec<u32>,
+    /// The values of every measure for each output row
+    measures: Vec<Vec<MeasureValue>>,
+}
+
+impl MatchOutput {
+    fn new(num_measures: usize) -> Self {
+        Self {
+            rows: vec![],
+            measures: vec![vec![]; num_measures],
+        }
+    }
+
+    fn push_unmatched(&mut self, row: usize) {
+        self.rows.push(row as u32);
+        for values in &mut self.measures {
+            values.push(MeasureValue::Null);
+        }
+    }
+
+    fn push(
+        &mut self,
+        row: usize,
+        measures: &[Measure],
+        states: &[MeasureState],
+        classifier: Option<usize>,
+        match_number: u64,
+    ) {
+        self.rows.push(row as u32);
+        for ((measure, state), values) in
+            measures.iter().zip(states).zip(&mut self.measures)
+        {
+            let row =
+                |row: Option<u32>| row.map_or(MeasureValue::Null, MeasureValue::Row);
+            values.push(match measure {
+                Measure::MatchNumber => MeasureValue::MatchNumber(match_number),
+                Measure::Classifier => {
+                    classifier.map_or(MeasureValue::Null, MeasureValue::Classifier)
+                }
+                Measure::Count(_) => MeasureValue::Count(state.count),
+                Measure::First(..) => row(state.first),
+                Measure::Last(..) => row(state.last),
+            });
+        }
+    }
+
+    /// Adds the output rows of a match of `rows` starting at buffered row
+    /// `start`
+    fn push_match(
+        &mut self,
+        start: usize,
+        rows: &[MatchedRow],
+        measures: &[Measure],
+        rows_per_match: RowsPerMatch,
+        match_number: u64,
+    ) {
+        let mut states = vec![MeasureState::default(); measures.len()];
+        let mut classifier = None;
+        if rows.is_empty() {
+            if rows_per_match != RowsPerMatch::AllRows(EmptyMatchesMode::Omit) {
+                self.push(start, measures, &states, classifier, match_number);
+            }
+            return;
+        }
+        for (i, row) in rows.iter().enumerate() {
+            let index = (start + i) as u32;
+            for (measure, state) in measures.iter().zip(&mut states) {
+                if measure.symbol().is_none() || measure.symbol() == Some(row.symbol) {
+                    state.count += 1;
+                    state.first.get_or_insert(index);
+                    state.last = Some(index);
+                }
+            }
+            classifier = Some(row.symbol);
+            // Measures of all rows per match are computed up to the current row
+            if matches!(rows_per_match, RowsPerMatch::AllRows(_)) && !row.excluded {
+                self.push(start + i, measures, &states, classifier, match_number);
+            }
+        }
+        if rows_per_match == RowsPerMatch::OneRow {
+            self.push(start, measures, &states, classifier, match_number);
+        }
+    }
+}
+
+/// Returns the buffered row to search the next match from after a match of
+/// `rows` starting at `start`
+fn next_search_start(
+    skip: &Skip,
+    symbol_names: &[String],
+    start: usize,
+    rows: &[MatchedRow],
+) -> Result<usize> {
+    let (position, target, symbol) = match skip {
+        // Always advance past empty matches
+        Skip::PastLastRow => return Ok(start + rows.len().max(1)),
+        Skip::ToNextRow => return Ok(start + 1),
+        Skip::ToFirst(symbol) => (
+            rows.iter().position(|row| row.symbol == *symbol),
+            "FIRST",
+            *symbol,
+        ),
+        Skip::ToLast(symbol) => (
+            rows.iter().rposition(|row| row.symbol == *symbol),
+            "LAST",
+            *symbol,
+        ),
+    };
+    let symbol = &symbol_names[symbol];
+    match position {
+        None => exec_err!(
+            "AFTER MATCH SKIP TO {target} {symbol} failed, no row of the match is mapped to {symbol}"
+        ),
+        Some(0) => exec_err!(
+            "AFTER MATCH SKIP TO {target} {symbol} failed, it would resume at the first row of the match"
+        ),
+        Some(position) => Ok(start + position),
+    }
+}
+
+/// Matches the partitions of a sorted input stream
+struct MatchRecognizeStream {
+    schema: SchemaRef,
+    input: SendableRecordBatchStream,
+    partition_by: Vec<Arc<dyn PhysicalExpr>>,
+    /// The predicate of every symbol
+    predicates: Vec<Arc<dyn PhysicalExpr>>,
+    symbol_names: Vec<String>,
+    matcher: Arc<PatternMatcher>,
+    measures: Vec<Measure>,
+    rows_per_match: RowsPerMatch,
+    after_match_skip: Skip,
+    partition: PartitionState,
+    /// Whether the input is exhausted
+    finished: bool,
+    /// Execution time metrics
+    baseline_metrics: BaselineMetrics,
+}
+
+impl MatchRecognizeStream {
+    /// Adds `batch` to the buffered partitions and returns the output of
+    /// all matches that are known so far
+    fn process_batch(&mut self, batch: RecordBatch) -> Result<RecordBatch> {
+        let elapsed_compute = self.baseline_metrics.elapsed_compute().clone();
+        let _timer = elapsed_compute.timer();
+
+        let num_rows = batch.num_rows();
+        let partition_columns = self
+            .partition_by
+            .iter()
+            .map(|expr| {
+                Ok(SortColumn {
+                    values: expr.evaluate(&batch)?.into_array(num_rows)?,
+                    options: None,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut output = vec![];
+        for range in evaluate_partition_ranges(num_rows, &partition_columns)? {
+            let key = partition_columns
+                .iter()
+                .map(|column| ScalarValue::try_from_array(&column.values, range.start))
+                .collect::<Result<Vec<_>>>()?;
+            if self.partition.key.as_ref().is_some_and(|k| k != &key) {
+                output.push(self.finish_partition()?);
+            }
+            self.partition.key = Some(key);
+            self.append_rows(batch.slice(range.start, range.len()))?;
+            output.push(self.find_matches(false)?);
+        }
+        Ok(concat_batches(&self.schema, &output)?)
+    }
+
+    /// Buffers rows of the current partition
+    fn append_rows(&mut self, rows: RecordBatch) -> Result<()> {
+        let num_rows = rows.num_rows();
+        let matches = self
+            .predicates
+            .iter()
+            .map(|predicate| predicate.evaluate(&rows)?.into_array(num_rows))
+            .collect::<Result<Vec<ArrayRef>>>()?;
+        let matches = matches
+            .iter()
+            .map(|array| as_boolean_array(array))
+            .collect::<Result<Vec<_>>>()?;
+        for row in 0..num_rows {
+            for array in &matches {
+                self.partition
+                    .symbol_matches
+                    .push(array.is_valid(row) && array.value(row));
+            }
+        }
+        self.partition.rows =
+            concat_batches(&rows.schema(), [&self.partition.rows, &rows])?;
+        Ok(())
+    }
+
+    /// Matches the rest of the current partition and starts a new one
+    fn finish_partition(&mut self) -> Result<RecordBatch> {
+        let output = self.find_matches(true)?;
+        let rows = self.partition.rows.slice(0, 0);
+        self.partition = PartitionState::new(rows);
+        Ok(output)
+    }
+
+    /// Finds all matches of the current partition that can not change with
+    /// later rows, returning their output rows
+    fn find_matches(&mut self, partition_finished: bool) -> Result<RecordBatch> {
+        let mut output = MatchOutput::new(self.measures.len());
+        let num_symbols = self.predicates.len();
+        let partition = &mut self.partition;
+        let num_rows = partition.rows.num_rows();
+        while partition.search_start < num_rows {
+            let start = partition.search_start;
+            let input = PatternInput {
+                symbol_matches: &partition.symbol_matches,
+                num_symbols,
+                num_rows,
+                partition_offset: partition.offset,
+                partition_finished,
+            };
+            match self.matcher.find_match(start, &input) {
+                MatchResult::NeedMoreRows => break,
+                MatchResult::NoMatch => {
+                    if self.rows_per_match
+                        == RowsPerMatch::AllRows(EmptyMatchesMode::WithUnmatched)
+                        && start >= partition.matched_until
+                    {
+                        output.push_unmatched(start);
+                    }
+                    partition.search_start += 1;
+                }
+                MatchResult::Match(rows) => {
+                    partition.match_number += 1;
+                    output.push_match(
+                        start,
+                        &rows,
+                        &self.measures,
+                        self.rows_per_match,
+                        partition.match_number,
+                    );
+                    partition.matched_until =
+                        partition.matched_until.max(start + rows.len());
+                    partition.search_start = next_search_start(
+                        &self.after_match_skip,
+                        &self.symbol_names,
+                        start,
+                        &rows,
+                    )?;
+                }
+            }
+        }
+        let batch = self.build_output(output)?;
+        self.partition.discard_searched_rows(num_symbols);
+        Ok(batch)
+    }
+
+    /// Builds the output rows from the buffered rows of the partition
+    fn build_output(&self, output: MatchOutput) -> Result<RecordBatch> {
+        let rows = &self.partition.rows;
+        let num_rows = output.rows.len();
+        if num_rows == 0 {
+            return Ok(RecordBatch::new_empty(Arc::clone(&self.schema)));
+        }
+        let indices = UInt32Array::from(output.rows);
+        let mut columns = match self.rows_per_match {
+            RowsPerMatch::OneRow => self
+                .partition_by
+                .iter()
+                .map(|expr| {
+                    let values = expr.evaluate(rows)?.into_array(rows.num_rows())?;
+                    Ok(take(&values, &indices, None)?)
+                })
+                .collect::<Result<Vec<_>>>()?,
+            RowsPerMatch::AllRows(_) => rows
+                .columns()
+                .iter()
+                .map(|column| Ok(take(column, &indices, None)?))
+                .collect::<Result<Vec<_>>>()?,
+        };
+        for (measure, values) in self.measures.iter().zip(output.measures) {
+            let column: ArrayRef = match measure {
+                Measure::MatchNumber => {
+                    Arc::new(UInt64Array::from_iter(values.iter().map(|v| match v {
+                        MeasureValue::MatchNumber(n) => Some(*n),
+                        _ => None,
+                    })))
+                }
+                Measure::Classifier => {
+                    Arc::new(StringArray::from_iter(values.iter().map(|v| match v {
+                        MeasureValue::Classifier(symbol) => {
+                            Some(self.symbol_names[*symbol].as_str())
+                        }
+                        _ => None,
+                    })))
+                }
+                Measure::Count(_) => {
+                    Arc::new(Int64Array::from_iter(values.iter().map(|v| match v {
+                        MeasureValue::Count(count) => Some(*count),
+                        _ => None,
+                    })))
+                }
+                Measure::First(expr, _) | Measure::Last(expr, _) => {
+                    let indices =
+                        UInt32Array::from_iter(values.iter().map(|v| match v {
+                            MeasureValue::Row(row) => Some(*row),
+                            _ => None,
+                        }));
+                    let values = expr.evaluate(rows)?.into_array(rows.num_rows())?;
+                    take(&values, &indices, None)?
+                }
+            };
+            columns.push(column);
+        }
+        let options = RecordBatchOptions::new().with_row_count(Some(num_rows));
+        Ok(RecordBatch::try_new_with_options(
+            Arc::clone(&self.schema),
+            columns,
+            &options,
+        )?)
+    }
+
+    fn poll_next_inner(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<RecordBatch>>> {
+        loop {
+            if self.finished {
+                return Poll::Ready(None);
+            }
+            let output = match ready!(self.input.poll_next_unpin(cx)) {
+                Some(Ok(batch)) => self.process_batch(batch),
+                Some(Err(e)) => Err(e),
+                None => {
+                    self.finished = true;
+                    self.finish_partition()
+                }
+            };
+            match output {
+                Ok(batch) if batch.num_rows() == 0 => continue,
+                output => return Poll::Ready(Some(output)),
+            }
+        }
+    }
+}
+
+impl Stream for MatchRecognizeStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let poll = self.poll_next_inner(cx);
+        self.baseline_metrics.record_poll(poll)
+    }
+}
+
+impl RecordBatchStream for MatchRecognizeStream {
+    /// Get the schema
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+}
@@ -0,0 +1,489 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Matches [`MatchPattern`]s against sequences of rows
+
+use std::collections::HashSet;
+
+use datafusion_common::{plan_err, Result};
+use datafusion_expr::MatchPattern;
+
+/// The maximum number of patterns in `PERMUTE`, as every order of the
+/// patterns becomes an alternative
+const MAX_PERMUTE_PATTERNS: usize = 6;
+
+/// A [`MatchPattern`] compiled to a program for a backtracking matcher.
+///
+/// Alternatives are tried in order of preference (the first alternative, or
+/// one more repetition for greedy quantifiers), so the first successful
+/// thread is the preferred match. Since symbol predicates only depend on
+/// the row, a thread reaching an instruction at a row that was already
+/// visited can not succeed and is dropped, which bounds the work per match
+/// attempt by the number of instructions times the number of rows.
+#[derive(Debug)]
+pub(crate) struct PatternMatcher {
+    program: Vec<Instruction>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Instruction {
+    /// Consume a row mapped to the symbol
+    Symbol(usize),
+    /// Continue at the first target, then backtrack to the second one
+    Split(usize, usize),
+    Jump(usize),
+    PartitionStart,
+    PartitionEnd,
+    ExcludeStart,
+    ExcludeEnd,
+    Match,
+}
+
+/// A row of a match
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct MatchedRow {
+    /// The index of the symbol the row is mapped to
+    pub symbol: usize,
+    /// Whether the row is excluded from `ALL ROWS PER MATCH` output
+    pub excluded: bool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum MatchResult {
+    /// The rows of the preferred match, starting at the searched row
+    Match(Vec<MatchedRow>),
+    /// No match starts at the searched row
+    NoMatch,
+    /// The result depends on rows of the partition that are not available yet
+    NeedMoreRows,
+}
+
+/// The rows of a partition a pattern is matched against
+pub(crate) struct PatternInput<'a> {
+    /// Whether row `i` can be mapped to symbol `j`, at `i * num_symbols + j`
+    pub symbol_matches: &'a [bool],
+    pub num_symbols: usize,
+    pub num_rows: usize,
+    /// The position of the first row within the partition
+    pub partition_offset: usize,
+    /// Whether the last row is the last row of the partition
+    pub partition_finished: bool,
+}
+
+impl PatternInput<'_> {
+    fn is_symbol(&self, row: usize, symbol: usize) -> bool {
+        self.symbol_matches[row * self.num_symbols + symbol]
+    }
+}
+
+impl PatternMatcher {
+    /// Compiles `pattern`, resolving symbols to their index in `symbols`
+    pub fn try_new(pattern: &MatchPattern, symbols: &[String]) -> Result<Self> {
+        let mut compiler = Compiler {
+            symbols,
+            program: vec![],
+        };
+        compiler.compile(pattern)?;
+        compiler.program.push(Instruction::Match);
+        Ok(Self {
+            program: compiler.program,
+        })
+    }
+
+    /// Returns the preferred match starting at row `start` of `input`
+    pub fn find_match(&self, start: usize, input: &PatternInput) -> MatchResult {
+        let num_rows = input.num_rows;
+        let mut visited = HashSet::new();
+        let mut rows = vec![];
+        // (instruction, row, number of matched rows, exclusion depth)
+        let mut threads = vec![(0, start, 0, 0)];
+        while let Some((mut pc, mut row, matched, mut excluded)) = threads.pop() {
+            rows.truncate(matched);
+            loop {
+                if !visited.insert((pc, row)) {
+                    break;
+                }
+                match self.program[pc] {
+                    Instruction::Symbol(symbol) => {
+                        if row == num_rows {
+                            if input.partition_finished {
+                                break;
+                            }
+                            return MatchResult::NeedMoreRows;
+                        }
+                        if !input.is_symbol(row, symbol) {
+                            break;
+                        }
+                        rows.push(MatchedRow {
+                            symbol,
+                            excluded: excluded > 0,
+                        });
+                        row += 1;
+                        pc += 1;
+                    }
+                    Instruction::Split(first, second) => {
+                        threads.push((second, row, rows.len(), excluded));
+                        pc = first;
+                    }
+                    Instruction::Jump(target) => pc = target,
+                    Instruction::PartitionStart => {
+                        if input.partition_offset + row != 0 {
+                            break;
+                        }
+                        pc += 1;
+                    }
+                    Instruction::PartitionEnd => {
+                        if row < num_rows {
+                            break;
+                        }
+                        if !input.partition_finished {
+                            return MatchResult::NeedMoreRows;
+                        }
+                        pc += 1;
+                    }
+                    Instruction::ExcludeStart => {
+                        excluded += 1;
+                        pc += 1;
+                    }
+                    Instruction::ExcludeEnd => {
+                        excluded -= 1;
+                        pc += 1;
+                    }
+                    Instruction::Match => return MatchResult::Match(rows),
+                }
+            }
+        }
+        MatchResult::NoMatch
+    }
+}
+
+struct Compiler<'a> {
+    symbols: &'a [String],
+    program: Vec<Instruction>,
+}
+
+impl Compiler<'_> {
+    fn push(&mut self, instruction: Instruction) -> usize {
+        self.program.push(instruction);
+        self.program.len() - 1
+    }
+
+    /// Points the unresolved targets of the `Split` or `Jump` at `pc` to
+    /// the end of the program
+    fn patch(&mut self, pc: usize) {
+        let end = self.program.len();
+        match &mut self.program[pc] {
+            Instruction::Split(_, second) => *second = end,
+            Instruction::Jump(target) => *target = end,
+            _ => unreachable!("only splits and jumps are patched"),
+        }
+    }
+
+    fn compile(&mut self, pattern: &MatchPattern) -> Result<()> {
+        match pattern {
+            MatchPattern::Symbol(name) => {
+                let Some(symbol) = self.symbols.iter().position(|s| s == name) else {
+                    return plan_err!("Pattern symbol {name} has no definition");
+                };
+                self.push(Instruction::Symbol(symbol));
+            }
+            MatchPattern::PartitionStart => {
+                self.push(Instruction::PartitionStart);
+            }
+            MatchPattern::PartitionEnd => {
+                self.push(Instruction::PartitionEnd);
+            }
+            MatchPattern::Exclude(pattern) => {
+                self.push(Instruction::ExcludeStart);
+                self.compile(pattern)?;
+                self.push(Instruction::ExcludeEnd);
+            }
+            MatchPattern::Concat(patterns) => {
+                for pattern in patterns {
+                    self.compile(pattern)?;
+                }
+            }
+            MatchPattern::Alternation(patterns) => {
+                let mut jumps = vec![];
+                for (i, pattern) in patterns.iter().enumerate() {
+                    if i + 1 == patterns.len() {
+                        self.compile(pattern)?;
+                    } else {
+                        let split = self.push(Instruction::Split(0, 0));
+                        self.program[split] = Instruction::Split(split + 1, 0);
+                        self.compile(pattern)?;
+                        jumps.push(self.push(Instruction::Jump(0)));
+                        self.patch(split);
+                    }
+                }
+                for jump in jumps {
+                    self.patch(jump);
+                }
+            }
+            MatchPattern::Permute(patterns) => {
+                if patterns.len() > MAX_PERMUTE_PATTERNS {
+                    return plan_err!(
+                        "PERMUTE supports at most {MAX_PERMUTE_PATTERNS} patterns, got {}",
+                        patterns.len()
+                    );
+                }
+                let alternatives = permutations(patterns.len())
+                    .into_iter()
+                    .map(|order| {
+                        MatchPattern::Concat(
+                            order.into_iter().map(|i| patterns[i].clone()).collect(),
+                        )
+                    })
+                    .collect();
+                self.compile(&MatchPattern::Alternation(alternatives))?;
+            }
+            MatchPattern::Repetition { pattern, min, max } => {
+                for _ in 0..*min {
+                    self.compile(pattern)?;
+                }
+                match max {
+                    None => {
+                        let split = self.push(Instruction::Split(0, 0));
+                        self.program[split] = Instruction::Split(split + 1, 0);
+                        self.compile(pattern)?;
+                        self.push(Instruction::Jump(split));
+                        self.patch(split);
+                    }
+                    Some(max) if max < min => {
+                        return plan_err!(
+                            "Invalid pattern quantifier {{{min},{max}}}, the maximum is less than the minimum"
+                        );
+                    }
+                    Some(max) => {
+                        let mut splits = vec![];
+                        for _ in *min..*max {
+                            let split = self.push(Instruction::Split(0, 0));
+                            self.program[split] = Instruction::Split(split + 1, 0);
+                            self.compile(pattern)?;
+                            splits.push(split);
+                        }
+                        for split in splits {
+                            self.patch(split);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns all orders of `n` items in lexicographic order
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+    if n == 0 {
+        return vec![vec![]];
+    }
+    let mut result = vec![];
+    for first in 0..n {
+        for rest in permutations(n - 1) {
+            let mut order = vec![first];
+            order.extend(rest.into_iter().map(|i| if i >= first { i + 1 } else { i }));
+            result.push(order);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbol(name: &str) -> MatchPattern {
+        MatchPattern::Symbol(name.to_string())
+    }
+
+    fn repeat(pattern: MatchPattern, min: u32, max: Option<u32>) -> MatchPattern {
+        MatchPattern::Repetition {
+            pattern: Box::new(pattern),
+            min,
+            max,
+        }
+    }
+
+    /// Matches `pattern` against rows given as the set of symbols (one
+    /// character each) they can be mapped to, returning the matched symbols
+    fn find(
+        pattern: &MatchPattern,
+        rows: &[&str],
+        start: usize,
+        partition_finished: bool,
+    ) -> Option<String> {
+        let symbols = ["a", "b", "c"].map(String::from);
+        let matcher = PatternMatcher::try_new(pattern, &symbols).unwrap();
+        let symbol_matches = rows
+            .iter()
+            .flat_map(|row| symbols.iter().map(|s| row.contains(s.as_str())))
+            .collect::<Vec<_>>();
+        let input = PatternInput {
+            symbol_matches: &symbol_matches,
+            num_symbols: symbols.len(),
+            num_rows: rows.len(),
+            partition_offset: 0,
+            partition_finished,
+        };
+        match matcher.find_match(start, &input) {
+            MatchResult::Match(rows) => Some(
+                rows.iter()
+                    .map(|row| {
+                        let symbol = symbols[row.symbol].clone();
+                        if row.excluded {
+                            symbol.to_uppercase()
+                        } else {
+                            symbol
+                        }
+                    })
+                    .collect(),
+            ),
+            MatchResult::NoMatch => None,
+            MatchResult::NeedMoreRows => Some("need more rows".to_string()),
+        }
+    }
+
+    #[test]
+    fn greedy_repetition() {
+        let pattern =
+            MatchPattern::Concat(vec![symbol("a"), repeat(symbol("b"), 0, None)]);
+        assert_eq!(
+            find(&pattern, &["a", "b", "b", "c"], 0, true).unwrap(),
+            "abb"
+        );
+        assert_eq!(find(&pattern, &["a", "c"], 0, true).unwrap(), "a");
+        assert_eq!(find(&pattern, &["c", "a"], 0, true), None);
+        assert_eq!(find(&pattern, &["c", "a"], 1, true).unwrap(), "a");
+    }
+
+    #[test]
+    fn backtracking() {
+        // a+ must give up rows that can also be mapped to b
+        let pattern = MatchPattern::Concat(vec![
+            repeat(symbol("a"), 1, None),
+            symbol("b"),
+            symbol("c"),
+        ]);
+        assert_eq!(
+            find(&pattern, &["a", "ab", "ab", "c"], 0, true).unwrap(),
+            "aabc"
+        );
+    }
+
+    #[test]
+    fn bounded_repetition() {
+        let pattern = repeat(symbol("a"), 2, Some(3));
+        assert_eq!(find(&pattern, &["a"], 0, true), None);
+        assert_eq!(find(&pattern, &["a", "a"], 0, true).unwrap(), "aa");
+        assert_eq!(
+            find(&pattern, &["a", "a", "a", "a"], 0, true).unwrap(),
+            "aaa"
+        );
+    }
+
+    #[test]
+    fn alternation_prefers_first() {
+        let pattern = MatchPattern::Alternation(vec![
+            symbol("a"),
+            MatchPattern::Concat(vec![symbol("a"), symbol("b")]),
+        ]);
+        assert_eq!(find(&pattern, &["a", "b"], 0, true).unwrap(), "a");
+        let pattern = MatchPattern::Concat(vec![
+            MatchPattern::Alternation(vec![symbol("a"), symbol("b")]),
+            symbol("c"),
+        ]);
+        assert_eq!(find(&pattern, &["ab", "c"], 0, true).unwrap(), "ac");
+        assert_eq!(find(&pattern, &["b", "c"], 0, true).unwrap(), "bc");
+    }
+
+    #[test]
+    fn permute() {
+        let pattern = MatchPattern::Permute(vec![symbol("a"), symbol("b"), symbol("c")]);
+        assert_eq!(find(&pattern, &["c", "a", "b"], 0, true).unwrap(), "cab");
+        assert_eq!(
+            find(&pattern, &["abc", "abc", "abc"], 0, true).unwrap(),
+            "abc"
+        );
+        assert_eq!(find(&pattern, &["a", "a", "b"], 0, true), None);
+    }
+
+    #[test]
+    fn exclusion() {
+        let pattern = MatchPattern::Concat(vec![
+            symbol("a"),
+            MatchPattern::Exclude(Box::new(repeat(symbol("b"), 1, None))),
+            symbol("c"),
+        ]);
+        assert_eq!(
+            find(&pattern, &["a", "b", "b", "c"], 0, true).unwrap(),
+            "aBBc"
+        );
+    }
+
+    #[test]
+    fn anchors() {
+        let pattern =
+            MatchPattern::Concat(vec![MatchPattern::PartitionStart, symbol("a")]);
+        assert_eq!(find(&pattern, &["a", "a"], 0, true).unwrap(), "a");
+        assert_eq!(find(&pattern, &["a", "a"], 1, true), None);
+
+        let pattern = MatchPattern::Concat(vec![symbol("a"), MatchPattern::PartitionEnd]);
+        assert_eq!(find(&pattern, &["a", "a"], 0, true), None);
+        assert_eq!(find(&pattern, &["a", "a"], 1, true).unwrap(), "a");
+        assert_eq!(
+            find(&pattern, &["a", "a"], 1, false).unwrap(),
+            "need more rows"
+        );
+    }
+
+    #[test]
+    fn empty_loops_terminate() {
+        let pattern = repeat(repeat(symbol("a"), 0, None), 0, None);
+        assert_eq!(find(&pattern, &["a", "a", "b"], 0, true).unwrap(), "aa");
+        assert_eq!(find(&pattern, &["b"], 0, true).unwrap(), "");
+    }
+
+    #[test]
+    fn need_more_rows() {
+        let pattern =
+            MatchPattern::Concat(vec![repeat(symbol("a"), 1, None), symbol("b")]);
+        // the a+ could continue past the available rows
+        assert_eq!(
+            find(&pattern, &["a", "a"], 0, false).unwrap(),
+            "need more rows"
+        );
+        // a match is final once a row ends the repetition
+        assert_eq!(find(&pattern, &["a", "b", "a"], 0, false).unwrap(), "ab");
+        // and so is a failure
+        assert_eq!(find(&pattern, &["a", "c"], 0, false), None);
+    }
+
+    #[test]
+    fn invalid_patterns() {
+        let err = PatternMatcher::try_new(&symbol("d"), &["a".to_string()]).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Pattern symbol d has no definition"));
+        let err =
+            PatternMatcher::try_new(&repeat(symbol("a"), 3, Some(2)), &["a".to_string()])
+                .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("the maximum is less than the minimum"));
+    }
+}
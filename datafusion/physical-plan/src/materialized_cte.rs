@@ -0,0 +1,408 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the materialized CTE query plan, which computes a common table
+//! expression once and shares its results with all references to it
+
+use std::any::Any;
+use std::sync::Arc;
+
+use crate::coop::cooperative;
+use crate::execution_plan::{Boundedness, EmissionType, SchedulingType};
+use crate::joins::utils::{OnceAsync, OnceFut};
+use crate::metrics::{
+    BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet, SpillMetrics,
+};
+use crate::spill::get_record_batch_memory_size;
+use crate::spill::in_progress_spill_file::InProgressSpillFile;
+use crate::spill::spill_manager::SpillManager;
+use crate::stream::{ObservedStream, RecordBatchStreamAdapter};
+use crate::{
+    DisplayAs, DisplayFormatType, ExecutionPlan, ExecutionPlanProperties, PlanProperties,
+    SendableRecordBatchStream, Statistics,
+};
+
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use datafusion_common::{internal_err, Result};
+use datafusion_execution::disk_manager::RefCountedTempFile;
+use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
+use datafusion_execution::TaskContext;
+use datafusion_physical_expr::{EquivalenceProperties, Partitioning};
+use futures::{future, stream, StreamExt, TryStreamExt};
+
+/// The results of a common table expression (CTE) that is referenced several
+/// times in a query, shared by all [`MaterializedCteExec`]s that reference it.
+///
+/// Each partition of the CTE is executed once, when it is first read by any
+/// of the references, and buffered in memory, or spilled to disk under
+/// memory pressure. The results are kept until all references are dropped.
+#[derive(Debug)]
+pub struct MaterializedCte {
+    /// Name of the CTE
+    name: String,
+    /// The physical plan of the CTE
+    input: Arc<dyn ExecutionPlan>,
+    /// The results of each partition of `input`, computed on first use
+    partitions: Vec<OnceAsync<MaterializedPartition>>,
+    /// Spill metrics, shared by all references
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl MaterializedCte {
+    /// Create the shared results of the CTE named `name` with the physical
+    /// plan `input`
+    pub fn new(name: impl Into<String>, input: Arc<dyn ExecutionPlan>) -> Self {
+        let partitions = (0..input.output_partitioning().partition_count())
+            .map(|_| OnceAsync::default())
+            .collect();
+        Self {
+            name: name.into(),
+            input,
+            partitions,
+            metrics: ExecutionPlanMetricsSet::new(),
+        }
+    }
+
+    /// Name of the CTE
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The physical plan of the CTE
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// Returns the results of `partition`, executing it if this is the first
+    /// time it is read
+    fn partition(
+        &self,
+        partition: usize,
+        context: &Arc<TaskContext>,
+    ) -> Result<OnceFut<MaterializedPartition>> {
+        let Some(once) = self.partitions.get(partition) else {
+            return internal_err!(
+                "MaterializedCteExec got an invalid partition {partition} (expected less than {})",
+                self.partitions.len()
+            );
+        };
+        once.try_once(|| {
+            let stream = self.input.execute(partition, Arc::clone(context))?;
+            let reservation =
+                MemoryConsumer::new(format!("MaterializedCteExec[{partition}]"))
+                    .with_can_spill(true)
+                    .register(context.memory_pool());
+            let spill_manager = SpillManager::new(
+                context.runtime_env(),
+                SpillMetrics::new(&self.metrics, partition),
+                self.input.schema(),
+            )
+            .with_compression_type(context.session_config().spill_compression());
+            Ok(materialize_partition(stream, reservation, spill_manager))
+        })
+    }
+}
+
+/// The results of a partition of a materialized CTE: the batches that fit in
+/// memory, followed by the batches spilled to disk
+#[derive(Debug)]
+struct MaterializedPartition {
+    batches: Vec<RecordBatch>,
+    spill_file: Option<Arc<RefCountedTempFile>>,
+    spill_manager: SpillManager,
+    /// Memory reserved for `batches`
+    _reservation: MemoryReservation,
+}
+
+/// Reads all batches of `input`, buffering them in memory until the memory
+/// reservation can not grow anymore, and spilling the remaining batches to
+/// disk
+async fn materialize_partition(
+    mut input: SendableRecordBatchStream,
+    mut reservation: MemoryReservation,
+    spill_manager: SpillManager,
+) -> Result<MaterializedPartition> {
+    let mut batches = vec![];
+    let mut in_progress_file: Option<InProgressSpillFile> = None;
+    while let Some(batch) = input.next().await {
+        let batch = batch?;
+        if let Some(file) = &mut in_progress_file {
+            file.append_batch(&batch)?;
+            continue;
+        }
+        if let Err(e) = reservation.try_grow(get_record_batch_memory_size(&batch)) {
+            let mut file = spill_manager
+                .create_in_progress_file("MaterializedCteExec")
+                .map_err(|_| e)?;
+            file.append_batch(&batch)?;
+            in_progress_file = Some(file);
+        } else {
+            batches.push(batch);
+        }
+    }
+    let spill_file = match in_progress_file {
+        Some(mut file) => file.finish()?.map(Arc::new),
+        None => None,
+    };
+    Ok(MaterializedPartition {
+        batches,
+        spill_file,
+        spill_manager,
+        _reservation: reservation,
+    })
+}
+
+/// Execution plan that reads the results of a [`MaterializedCte`].
+///
+/// Each reference to a materialized CTE in a query is planned as a
+/// `MaterializedCteExec` reading from the same [`MaterializedCte`], so that
+/// the CTE is computed once instead of once per reference. The physical plan
+/// of the CTE is not a child of this node, as it is shared with the other
+/// references.
+#[derive(Debug, Clone)]
+pub struct MaterializedCteExec {
+    /// The shared results of the CTE
+    cte: Arc<MaterializedCte>,
+    /// Optional projection of the columns of the CTE
+    projection: Option<Vec<usize>>,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+    /// Cache holding plan properties like equivalences, output partitioning etc.
+    cache: PlanProperties,
+}
+
+impl MaterializedCteExec {
+    /// Create a new execution plan reading the `projection` of the columns
+    /// of `cte`
+    pub fn try_new(
+        cte: Arc<MaterializedCte>,
+        projection: Option<Vec<usize>>,
+    ) -> Result<Self> {
+        let schema = match &projection {
+            Some(projection) => Arc::new(cte.input.schema().project(projection)?),
+            None => cte.input.schema(),
+        };
+        let cache = Self::compute_properties(
+            schema,
+            cte.input.output_partitioning().partition_count(),
+        );
+        Ok(Self {
+            cte,
+            projection,
+            metrics: ExecutionPlanMetricsSet::new(),
+            cache,
+        })
+    }
+
+    /// The shared results of the CTE
+    pub fn cte(&self) -> &Arc<MaterializedCte> {
+        &self.cte
+    }
+
+    /// Optional projection of the columns of the CTE
+    pub fn projection(&self) -> Option<&Vec<usize>> {
+        self.projection.as_ref()
+    }
+
+    /// This function creates the cache object that stores the plan properties such as schema, equivalence properties, ordering, partitioning, etc.
+    fn compute_properties(schema: SchemaRef, partition_count: usize) -> PlanProperties {
+        PlanProperties::new(
+            EquivalenceProperties::new(schema),
+            Partitioning::UnknownPartitioning(partition_count),
+            EmissionType::Final,
+            Boundedness::Bounded,
+        )
+        .with_scheduling_type(SchedulingType::Cooperative)
+    }
+}
+
+impl DisplayAs for MaterializedCteExec {
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(f, "MaterializedCteExec: name={}", self.cte.name)?;
+                if let Some(projection) = &self.projection {
+                    let columns = projection
+                        .iter()
+                        .map(|i| self.cte.input.schema().field(*i).name().to_string())
+                        .collect::<Vec<_>>();
+                    write!(f, ", projection=[{}]", columns.join(", "))?;
+                }
+                Ok(())
+            }
+            DisplayFormatType::TreeRender => {
+                write!(f, "name={}", self.cte.name)
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for MaterializedCteExec {
+    fn name(&self) -> &'static str {
+        "MaterializedCteExec"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        &self.cache
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::clone(&self) as Arc<dyn ExecutionPlan>)
+    }
+
+    /// Stream the results of the CTE, waiting for them to be computed
+    /// (by this or another reference) first
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let mut results = self.cte.partition(partition, &context)?;
+        let projection = self.projection.clone();
+        let stream = stream::once(async move {
+            let results = future::poll_fn(|cx| results.get_shared(cx)).await?;
+            let batches = stream::iter(results.batches.clone()).map(Ok);
+            let stream = match &results.spill_file {
+                Some(spill_file) => batches
+                    .chain(
+                        results
+                            .spill_manager
+                            .read_shared_spill_as_stream(Arc::clone(spill_file), None)?,
+                    )
+                    .boxed(),
+                None => batches.boxed(),
+            };
+            Ok::<_, datafusion_common::DataFusionError>(stream)
+        })
+        .try_flatten()
+        .map(move |batch| match &projection {
+            Some(projection) => Ok(batch?.project(projection)?),
+            None => batch,
+        });
+
+        let stream = Box::pin(RecordBatchStreamAdapter::new(self.schema(), stream));
+        let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+        Ok(Box::pin(
+            cooperative(ObservedStream::new(stream, baseline_metrics, None))
+                .with_deadline(context.deadline()),
+        ))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        let mut metrics = self.metrics.clone_inner();
+        for metric in self.cte.metrics.clone_inner().iter() {
+            metrics.push(Arc::clone(metric));
+        }
+        Some(metrics)
+    }
+
+    fn statistics(&self) -> Result<Statistics> {
+        self.partition_statistics(None)
+    }
+
+    fn partition_statistics(&self, partition: Option<usize>) -> Result<Statistics> {
+        let statistics = self.cte.input.partition_statistics(partition)?;
+        match &self.projection {
+            Some(projection) => Ok(statistics.project(Some(projection))),
+            None => Ok(statistics),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collect;
+    use crate::test::{build_table_i32, TestMemoryExec};
+
+    use datafusion_execution::memory_pool::FairSpillPool;
+    use datafusion_execution::runtime_env::RuntimeEnvBuilder;
+
+    fn test_cte() -> Result<Arc<MaterializedCte>> {
+        let batch = build_table_i32(
+            ("a", &vec![1, 2, 3]),
+            ("b", &vec![4, 5, 6]),
+            ("c", &vec![7, 8, 9]),
+        );
+        let schema = batch.schema();
+        let input = TestMemoryExec::try_new_exec(
+            &[vec![batch.clone(), batch.clone()], vec![batch]],
+            schema,
+            None,
+        )?;
+        Ok(Arc::new(MaterializedCte::new("t", input)))
+    }
+
+    #[tokio::test]
+    async fn test_materialized_cte() -> Result<()> {
+        let cte = test_cte()?;
+        let first = Arc::new(MaterializedCteExec::try_new(Arc::clone(&cte), None)?);
+        let second = Arc::new(MaterializedCteExec::try_new(
+            Arc::clone(&cte),
+            Some(vec![2]),
+        )?);
+        assert_eq!(
+            first.properties().output_partitioning().partition_count(),
+            2
+        );
+
+        let context = Arc::new(TaskContext::default());
+        let first_batches = collect(first, Arc::clone(&context)).await?;
+        let second_batches = collect(second, context).await?;
+        assert_eq!(first_batches.iter().map(|b| b.num_rows()).sum::<usize>(), 9);
+        assert_eq!(second_batches.len(), 3);
+        assert_eq!(second_batches[0].num_columns(), 1);
+        assert_eq!(second_batches[0].schema().field(0).name(), "c");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_materialized_cte_spill() -> Result<()> {
+        let cte = test_cte()?;
+        let runtime = RuntimeEnvBuilder::new()
+            .with_memory_pool(Arc::new(FairSpillPool::new(0)))
+            .build_arc()?;
+        let context = Arc::new(TaskContext::default().with_runtime(runtime));
+
+        for _ in 0..2 {
+            let exec = Arc::new(MaterializedCteExec::try_new(Arc::clone(&cte), None)?);
+            let batches = collect(Arc::clone(&exec) as _, Arc::clone(&context)).await?;
+            assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 9);
+
+            let metrics = exec.metrics().unwrap();
+            assert_eq!(metrics.spill_count(), Some(2));
+            assert_eq!(metrics.spilled_rows(), Some(9));
+        }
+        Ok(())
+    }
+}
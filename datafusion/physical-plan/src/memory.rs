@@ -306,7 +306,7 @@ impl ExecutionPlan for LazyMemoryExec {
     fn execute(
         &self,
         partition: usize,
-        _context: Arc<TaskContext>,
+        context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
         if partition >= self.batch_generators.len() {
             return internal_err!(
@@ -323,7 +323,9 @@ impl ExecutionPlan for LazyMemoryExec {
             generator: Arc::clone(&self.batch_generators[partition]),
             baseline_metrics,
         };
-        Ok(Box::pin(cooperative(stream)))
+        Ok(Box::pin(
+            cooperative(stream).with_deadline(context.deadline()),
+        ))
     }
 
     fn metrics(&self) -> Option<MetricsSet> {
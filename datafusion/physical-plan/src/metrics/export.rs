@@ -0,0 +1,331 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Conversion of plan metrics to [`QueryMetrics`] for a [`MetricsExporter`]
+
+use std::collections::BTreeMap;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime};
+
+use super::{MetricValue, MetricsSet};
+use crate::{ExecutionPlan, RecordBatchStream, SendableRecordBatchStream};
+
+use arrow::array::RecordBatch;
+use arrow::datatypes::SchemaRef;
+use datafusion_common::Result;
+use datafusion_common_runtime::{
+    is_join_set_tracer_set, record_operator_span, OperatorSpan,
+};
+use datafusion_execution::metrics_exporter::{
+    MetricSample, MetricSampleValue, MetricsExporter, OperatorMetrics, QueryMetrics,
+};
+use datafusion_execution::TaskContext;
+use futures::Stream;
+
+/// Returns the current metrics of every operator in `plan`, in pre-order
+pub fn query_metrics(plan: &dyn ExecutionPlan, context: &TaskContext) -> QueryMetrics {
+    let mut operators = vec![];
+    collect_operator_metrics(plan, None, &mut operators);
+    QueryMetrics {
+        session_id: context.session_id(),
+        task_id: context.task_id(),
+        operators,
+    }
+}
+
+fn collect_operator_metrics(
+    plan: &dyn ExecutionPlan,
+    parent_id: Option<usize>,
+    operators: &mut Vec<OperatorMetrics>,
+) {
+    let id = operators.len();
+    operators.push(OperatorMetrics {
+        id,
+        parent_id,
+        name: plan.name().to_string(),
+        metrics: plan.metrics().as_ref().map(samples).unwrap_or_default(),
+    });
+    for child in plan.children() {
+        collect_operator_metrics(child.as_ref(), Some(id), operators);
+    }
+}
+
+/// Converts a [`MetricsSet`] to [`MetricSample`]s, skipping unset timestamps
+fn samples(metrics: &MetricsSet) -> Vec<MetricSample> {
+    metrics
+        .iter()
+        .filter_map(|metric| {
+            let value = match metric.value() {
+                MetricValue::OutputRows(_)
+                | MetricValue::SpillCount(_)
+                | MetricValue::SpilledBytes(_)
+                | MetricValue::SpilledRows(_)
+                | MetricValue::Count { .. } => {
+                    MetricSampleValue::Counter(metric.value().as_usize() as u64)
+                }
+                MetricValue::CurrentMemoryUsage(_)
+                | MetricValue::Gauge { .. }
+                | MetricValue::Custom { .. } => {
+                    MetricSampleValue::Gauge(metric.value().as_usize() as u64)
+                }
+                MetricValue::ElapsedCompute(_) | MetricValue::Time { .. } => {
+                    MetricSampleValue::Time(Duration::from_nanos(
+                        metric.value().as_usize() as u64,
+                    ))
+                }
+                MetricValue::StartTimestamp(timestamp)
+                | MetricValue::EndTimestamp(timestamp) => {
+                    MetricSampleValue::Timestamp(timestamp.value()?.into())
+                }
+            };
+            Some(MetricSample {
+                name: metric.value().name().to_string(),
+                partition: metric.partition(),
+                labels: metric
+                    .labels()
+                    .iter()
+                    .map(|label| (label.name().to_string(), label.value().to_string()))
+                    .collect(),
+                value,
+            })
+        })
+        .collect()
+}
+
+/// Records an [`OperatorSpan`] for each partition of each operator that
+/// reported both a start and an end timestamp
+fn record_operator_spans(metrics: &QueryMetrics) {
+    for operator in &metrics.operators {
+        let mut partitions: BTreeMap<usize, (Option<SystemTime>, Option<SystemTime>)> =
+            BTreeMap::new();
+        let mut attributes: BTreeMap<usize, Vec<(String, String)>> = BTreeMap::new();
+        for sample in &operator.metrics {
+            let Some(partition) = sample.partition else {
+                continue;
+            };
+            match (sample.name.as_str(), sample.value) {
+                ("start_timestamp", MetricSampleValue::Timestamp(start)) => {
+                    partitions.entry(partition).or_default().0 = Some(start);
+                }
+                ("end_timestamp", MetricSampleValue::Timestamp(end)) => {
+                    partitions.entry(partition).or_default().1 = Some(end);
+                }
+                (name, value) => {
+                    let value = match value {
+                        MetricSampleValue::Counter(v) | MetricSampleValue::Gauge(v) => {
+                            v.to_string()
+                        }
+                        MetricSampleValue::Time(time) => time.as_nanos().to_string(),
+                        MetricSampleValue::Timestamp(_) => continue,
+                    };
+                    attributes
+                        .entry(partition)
+                        .or_default()
+                        .push((name.to_string(), value));
+                }
+            }
+        }
+
+        for (partition, times) in partitions {
+            let (Some(start), Some(end)) = times else {
+                continue;
+            };
+            record_operator_span(&OperatorSpan {
+                session_id: metrics.session_id.clone(),
+                operator: operator.name.clone(),
+                operator_id: operator.id,
+                parent_operator_id: operator.parent_id,
+                partition,
+                start,
+                end,
+                attributes: attributes.remove(&partition).unwrap_or_default(),
+            });
+        }
+    }
+}
+
+/// Exports the metrics of a plan once it is dropped, which happens after all
+/// the streams it was [attached](Self::attach) to are dropped.
+pub(crate) struct ExportMetricsOnDrop {
+    plan: Arc<dyn ExecutionPlan>,
+    context: Arc<TaskContext>,
+    exporter: Option<Arc<dyn MetricsExporter>>,
+}
+
+impl ExportMetricsOnDrop {
+    /// Returns `None` if there is neither a [`MetricsExporter`] nor a tracer
+    /// to export the metrics of `plan` to
+    pub(crate) fn try_new(
+        plan: &Arc<dyn ExecutionPlan>,
+        context: &Arc<TaskContext>,
+    ) -> Option<Arc<Self>> {
+        let exporter = context.runtime_env().metrics_exporter.clone();
+        if exporter.is_none() && !is_join_set_tracer_set() {
+            return None;
+        }
+        Some(Arc::new(Self {
+            plan: Arc::clone(plan),
+            context: Arc::clone(context),
+            exporter,
+        }))
+    }
+
+    /// Wraps `stream` so that it keeps `self` alive
+    pub(crate) fn attach(
+        self: &Arc<Self>,
+        stream: SendableRecordBatchStream,
+    ) -> SendableRecordBatchStream {
+        Box::pin(ExportingStream {
+            inner: stream,
+            _exporter: Arc::clone(self),
+        })
+    }
+}
+
+impl Drop for ExportMetricsOnDrop {
+    fn drop(&mut self) {
+        let metrics = query_metrics(self.plan.as_ref(), &self.context);
+        if let Some(exporter) = &self.exporter {
+            exporter.export(&metrics);
+        }
+        if is_join_set_tracer_set() {
+            record_operator_spans(&metrics);
+        }
+    }
+}
+
+/// A stream that exports the metrics of the query it belongs to once it and
+/// the other streams of the query are dropped
+struct ExportingStream {
+    inner: SendableRecordBatchStream,
+    _exporter: Arc<ExportMetricsOnDrop>,
+}
+
+impl Stream for ExportingStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl RecordBatchStream for ExportingStream {
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::coalesce_batches::CoalesceBatchesExec;
+    use crate::placeholder_row::PlaceholderRowExec;
+    use crate::{collect, execute_stream_partitioned};
+
+    use arrow::datatypes::Schema;
+    use datafusion_execution::runtime_env::RuntimeEnvBuilder;
+    use futures::StreamExt;
+    use parking_lot::Mutex;
+
+    #[derive(Debug, Default)]
+    struct TestExporter {
+        exported: Mutex<Vec<QueryMetrics>>,
+    }
+
+    impl MetricsExporter for TestExporter {
+        fn export(&self, metrics: &QueryMetrics) {
+            self.exported.lock().push(metrics.clone());
+        }
+    }
+
+    fn test_plan(partitions: usize) -> Arc<dyn ExecutionPlan> {
+        let input = PlaceholderRowExec::new(Arc::new(Schema::empty()))
+            .with_partitions(partitions);
+        Arc::new(CoalesceBatchesExec::new(Arc::new(input), 1024))
+    }
+
+    fn test_context(exporter: &Arc<TestExporter>) -> Arc<TaskContext> {
+        let runtime = RuntimeEnvBuilder::new()
+            .with_metrics_exporter(Arc::clone(exporter) as _)
+            .build_arc()
+            .unwrap();
+        Arc::new(TaskContext::default().with_runtime(runtime))
+    }
+
+    #[tokio::test]
+    async fn export_metrics_after_collect() -> Result<()> {
+        let exporter = Arc::new(TestExporter::default());
+        collect(test_plan(1), test_context(&exporter)).await?;
+
+        let exported = exporter.exported.lock();
+        assert_eq!(exported.len(), 1);
+        let operators = &exported[0].operators;
+        assert_eq!(operators.len(), 2);
+        assert_eq!(
+            (
+                operators[0].id,
+                operators[0].parent_id,
+                operators[0].name.as_str()
+            ),
+            (0, None, "CoalesceBatchesExec")
+        );
+        assert_eq!(
+            (
+                operators[1].id,
+                operators[1].parent_id,
+                operators[1].name.as_str()
+            ),
+            (1, Some(0), "PlaceholderRowExec")
+        );
+        let output_rows = operators[0]
+            .metrics
+            .iter()
+            .find(|sample| sample.name == "output_rows")
+            .unwrap();
+        assert_eq!(output_rows.partition, Some(0));
+        assert_eq!(output_rows.value, MetricSampleValue::Counter(1));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn export_metrics_once_all_partitions_dropped() -> Result<()> {
+        let exporter = Arc::new(TestExporter::default());
+        let mut streams =
+            execute_stream_partitioned(test_plan(2), test_context(&exporter))?;
+
+        let mut stream = streams.pop().unwrap();
+        while let Some(batch) = stream.next().await {
+            batch?;
+        }
+        drop(stream);
+        assert!(exporter.exported.lock().is_empty());
+
+        drop(streams);
+        assert_eq!(exporter.exported.lock().len(), 1);
+        Ok(())
+    }
+}
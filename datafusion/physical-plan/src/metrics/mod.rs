@@ -20,6 +20,7 @@
 mod baseline;
 mod builder;
 mod custom;
+mod export;
 mod value;
 
 use parking_lot::Mutex;
@@ -35,8 +36,11 @@ use datafusion_common::HashMap;
 pub use baseline::{BaselineMetrics, RecordOutput, SpillMetrics, SplitMetrics};
 pub use builder::MetricBuilder;
 pub use custom::CustomMetricValue;
+pub use export::query_metrics;
 pub use value::{Count, Gauge, MetricValue, ScopedTimerGuard, Time, Timestamp};
 
+pub(crate) use export::ExportMetricsOnDrop;
+
 /// Something that tracks a value of interest (metric) of a DataFusion
 /// [`ExecutionPlan`] execution.
 ///
@@ -163,7 +163,7 @@ impl ExecutionPlan for PlaceholderRowExec {
         }
 
         let ms = MemoryStream::try_new(self.data()?, Arc::clone(&self.schema), None)?;
-        Ok(Box::pin(cooperative(ms)))
+        Ok(Box::pin(cooperative(ms).with_deadline(context.deadline())))
     }
 
     fn statistics(&self) -> Result<Statistics> {
@@ -0,0 +1,510 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Progress reporting for running queries
+//!
+//! A [`ProgressTracker`] combines the metrics that operators record while
+//! they execute (see [`BaselineMetrics`]) with the estimates of their input
+//! that are known before execution starts (see [`Statistics`] and
+//! [`ExecutionPlan::estimated_input_bytes`]) into a [`QueryProgress`]
+//! snapshot.
+//!
+//! The progress of a query is approximated by how much of its input the leaf
+//! operators (typically scans) have read so far. Operators that buffer their
+//! input, such as sorts, may therefore still do significant work once all of
+//! the input has been read.
+//!
+//! [`BaselineMetrics`]: crate::metrics::BaselineMetrics
+//! [`Statistics`]: datafusion_common::Statistics
+
+use std::fmt::{self, Display, Formatter};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::metrics::MetricValue;
+use crate::{ExecutionPlan, ExecutionPlanProperties};
+
+use datafusion_common::instant::Instant;
+
+/// Upper bound of the estimated progress of an operator that is still running
+const MAX_UNFINISHED_FRACTION: f64 = 0.99;
+
+/// Tracks the progress of the execution of an [`ExecutionPlan`]
+///
+/// Create the tracker when the plan starts executing, and call
+/// [`Self::snapshot`] periodically, for example from another task, to report
+/// the progress of the query.
+///
+/// # Example
+/// ```
+/// # use std::sync::Arc;
+/// # use arrow::datatypes::Schema;
+/// # use datafusion_execution::TaskContext;
+/// # use datafusion_physical_plan::coalesce_batches::CoalesceBatchesExec;
+/// # use datafusion_physical_plan::collect;
+/// # use datafusion_physical_plan::placeholder_row::PlaceholderRowExec;
+/// # use datafusion_physical_plan::progress::ProgressTracker;
+/// # use datafusion_physical_plan::ExecutionPlan;
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> datafusion_common::Result<()> {
+/// # let input = Arc::new(PlaceholderRowExec::new(Arc::new(Schema::empty())));
+/// # let plan: Arc<dyn ExecutionPlan> = Arc::new(CoalesceBatchesExec::new(input, 8192));
+/// let tracker = ProgressTracker::new(Arc::clone(&plan));
+/// collect(plan, Arc::new(TaskContext::default())).await?;
+///
+/// let progress = tracker.snapshot();
+/// assert_eq!(progress.percent_complete(), Some(100.0));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ProgressTracker {
+    /// The operators of the plan, in depth-first pre-order
+    operators: Vec<TrackedOperator>,
+    start: Instant,
+}
+
+/// An operator of the plan tracked by a [`ProgressTracker`]
+#[derive(Debug)]
+struct TrackedOperator {
+    plan: Arc<dyn ExecutionPlan>,
+    depth: usize,
+    is_leaf: bool,
+    partitions: usize,
+    estimated_output_rows: Option<usize>,
+    estimated_input_bytes: Option<usize>,
+}
+
+impl ProgressTracker {
+    /// Creates a tracker for `plan`, measuring throughput from now on
+    pub fn new(plan: Arc<dyn ExecutionPlan>) -> Self {
+        let mut operators = vec![];
+        track(plan, 0, &mut operators);
+        Self {
+            operators,
+            start: Instant::now(),
+        }
+    }
+
+    /// Returns the current progress of the query
+    pub fn snapshot(&self) -> QueryProgress {
+        let elapsed = self.start.elapsed();
+        let operators = self
+            .operators
+            .iter()
+            .map(|operator| operator.snapshot(elapsed))
+            .collect();
+        QueryProgress { elapsed, operators }
+    }
+}
+
+/// Collects the operators of `plan` in depth-first pre-order
+fn track(
+    plan: Arc<dyn ExecutionPlan>,
+    depth: usize,
+    operators: &mut Vec<TrackedOperator>,
+) {
+    let children = plan.children().into_iter().cloned().collect::<Vec<_>>();
+    let estimated_output_rows = plan
+        .partition_statistics(None)
+        .ok()
+        .and_then(|statistics| statistics.num_rows.get_value().copied());
+    operators.push(TrackedOperator {
+        depth,
+        is_leaf: children.is_empty(),
+        partitions: plan.output_partitioning().partition_count(),
+        estimated_output_rows,
+        estimated_input_bytes: plan.estimated_input_bytes(),
+        plan,
+    });
+    for child in children {
+        track(child, depth + 1, operators);
+    }
+}
+
+impl TrackedOperator {
+    fn snapshot(&self, elapsed: Duration) -> OperatorProgress {
+        let mut progress = OperatorProgress {
+            name: self.plan.name().to_string(),
+            depth: self.depth,
+            is_leaf: self.is_leaf,
+            partitions: self.partitions,
+            finished_partitions: 0,
+            output_rows: 0,
+            estimated_output_rows: self.estimated_output_rows,
+            bytes_scanned: None,
+            estimated_input_bytes: self.estimated_input_bytes,
+            elapsed_compute: Duration::ZERO,
+            elapsed,
+        };
+        let Some(metrics) = self.plan.metrics() else {
+            return progress;
+        };
+
+        progress.output_rows = metrics.output_rows().unwrap_or_default();
+        progress.elapsed_compute =
+            Duration::from_nanos(metrics.elapsed_compute().unwrap_or_default() as u64);
+        progress.bytes_scanned = metrics
+            .sum_by_name("bytes_scanned")
+            .map(|value| value.as_usize());
+        progress.finished_partitions = metrics
+            .iter()
+            .filter(|metric| {
+                matches!(
+                    metric.value(),
+                    MetricValue::EndTimestamp(timestamp) if timestamp.value().is_some()
+                )
+            })
+            .count();
+        progress
+    }
+}
+
+/// Snapshot of the progress of a query, see [`ProgressTracker`]
+#[derive(Debug, Clone)]
+pub struct QueryProgress {
+    elapsed: Duration,
+    operators: Vec<OperatorProgress>,
+}
+
+impl QueryProgress {
+    /// Wall clock time since the query started
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// The progress of every operator of the plan, in depth-first pre-order
+    pub fn operators(&self) -> &[OperatorProgress] {
+        &self.operators
+    }
+
+    /// Returns the estimated percentage, between 0 and 100, of the query
+    /// that is complete, or `None` if the size of its input is unknown.
+    ///
+    /// This is the progress of the leaf operators of the plan, weighted by the
+    /// estimated size of their input if it is known for all of them.
+    pub fn percent_complete(&self) -> Option<f64> {
+        if let Some(root) = self.operators.first() {
+            if root.is_finished() {
+                return Some(100.0);
+            }
+        }
+
+        let leaves = self
+            .operators
+            .iter()
+            .filter(|operator| operator.is_leaf)
+            .filter_map(|operator| {
+                let fraction = operator.fraction_complete()?;
+                Some((fraction, operator.estimated_input_bytes))
+            })
+            .collect::<Vec<_>>();
+        if leaves.is_empty() {
+            return None;
+        }
+
+        let weighted = leaves.iter().all(|(_, bytes)| bytes.is_some_and(|b| b > 0));
+        let (done, total) =
+            leaves
+                .iter()
+                .fold((0.0, 0.0), |(done, total), (fraction, bytes)| {
+                    let weight = if weighted {
+                        bytes.unwrap_or_default() as f64
+                    } else {
+                        1.0
+                    };
+                    (done + fraction * weight, total + weight)
+                });
+        Some(100.0 * done / total)
+    }
+}
+
+impl Display for QueryProgress {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.percent_complete() {
+            Some(percent) => write!(f, "{percent:.1}% complete")?,
+            None => write!(f, "progress unknown")?,
+        }
+        writeln!(f, ", elapsed {:.3}s", self.elapsed.as_secs_f64())?;
+        for operator in &self.operators {
+            writeln!(f, "{:indent$}{operator}", "", indent = operator.depth * 2)?;
+        }
+        Ok(())
+    }
+}
+
+/// Progress of a single operator of the plan, see [`QueryProgress`]
+#[derive(Debug, Clone)]
+pub struct OperatorProgress {
+    name: String,
+    depth: usize,
+    is_leaf: bool,
+    partitions: usize,
+    finished_partitions: usize,
+    output_rows: usize,
+    estimated_output_rows: Option<usize>,
+    bytes_scanned: Option<usize>,
+    estimated_input_bytes: Option<usize>,
+    elapsed_compute: Duration,
+    elapsed: Duration,
+}
+
+impl OperatorProgress {
+    /// The name of the operator, see [`ExecutionPlan::name`]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Depth of the operator in the plan, `0` for the root
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns `true` if the operator has no children
+    pub fn is_leaf(&self) -> bool {
+        self.is_leaf
+    }
+
+    /// Number of output partitions of the operator
+    pub fn partitions(&self) -> usize {
+        self.partitions
+    }
+
+    /// Number of output partitions that have been completely produced
+    pub fn finished_partitions(&self) -> usize {
+        self.finished_partitions
+    }
+
+    /// Returns `true` if all output partitions have been produced
+    pub fn is_finished(&self) -> bool {
+        self.partitions > 0 && self.finished_partitions >= self.partitions
+    }
+
+    /// Number of rows produced so far
+    pub fn output_rows(&self) -> usize {
+        self.output_rows
+    }
+
+    /// Estimated total number of output rows, from the statistics of the plan
+    pub fn estimated_output_rows(&self) -> Option<usize> {
+        self.estimated_output_rows
+    }
+
+    /// Number of bytes read so far, for operators that report them (for
+    /// example Parquet scans)
+    pub fn bytes_scanned(&self) -> Option<usize> {
+        self.bytes_scanned
+    }
+
+    /// Estimated total number of bytes of input, see
+    /// [`ExecutionPlan::estimated_input_bytes`]
+    pub fn estimated_input_bytes(&self) -> Option<usize> {
+        self.estimated_input_bytes
+    }
+
+    /// CPU time spent in the operator so far
+    pub fn elapsed_compute(&self) -> Duration {
+        self.elapsed_compute
+    }
+
+    /// Output rows per second of wall clock time since the query started
+    pub fn rows_per_second(&self) -> f64 {
+        per_second(self.output_rows, self.elapsed)
+    }
+
+    /// Bytes read per second of wall clock time since the query started, if
+    /// the operator reports the bytes it reads
+    pub fn bytes_per_second(&self) -> Option<f64> {
+        Some(per_second(self.bytes_scanned?, self.elapsed))
+    }
+
+    /// Returns the estimated fraction, between 0 and 1, of the output of the
+    /// operator that has been produced, or `None` if it is unknown.
+    ///
+    /// Uses the bytes read compared to the size of the input if both are
+    /// known, and the rows produced compared to the estimated number of
+    /// output rows otherwise.
+    pub fn fraction_complete(&self) -> Option<f64> {
+        if self.is_finished() {
+            return Some(1.0);
+        }
+        let fraction = match (self.bytes_scanned, self.estimated_input_bytes) {
+            (Some(scanned), Some(total)) if total > 0 => scanned as f64 / total as f64,
+            _ => match self.estimated_output_rows? {
+                0 => 0.0,
+                total => self.output_rows as f64 / total as f64,
+            },
+        };
+        Some(fraction.min(MAX_UNFINISHED_FRACTION))
+    }
+}
+
+impl Display for OperatorProgress {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} rows ({:.0} rows/s)",
+            self.name,
+            self.output_rows,
+            self.rows_per_second()
+        )?;
+        if let Some(fraction) = self.fraction_complete() {
+            write!(f, ", {:.1}%", fraction * 100.0)?;
+        }
+        write!(
+            f,
+            ", {}/{} partitions done",
+            self.finished_partitions, self.partitions
+        )
+    }
+}
+
+fn per_second(value: usize, elapsed: Duration) -> f64 {
+    let seconds = elapsed.as_secs_f64();
+    if seconds > 0.0 {
+        value as f64 / seconds
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coalesce_batches::CoalesceBatchesExec;
+    use crate::collect;
+    use crate::placeholder_row::PlaceholderRowExec;
+
+    use arrow::datatypes::Schema;
+    use datafusion_common::Result;
+    use datafusion_execution::TaskContext;
+
+    fn leaf(
+        output_rows: usize,
+        estimated_output_rows: Option<usize>,
+        bytes: Option<(usize, usize)>,
+    ) -> OperatorProgress {
+        OperatorProgress {
+            name: "TestExec".to_string(),
+            depth: 1,
+            is_leaf: true,
+            partitions: 1,
+            finished_partitions: 0,
+            output_rows,
+            estimated_output_rows,
+            bytes_scanned: bytes.map(|(scanned, _)| scanned),
+            estimated_input_bytes: bytes.map(|(_, total)| total),
+            elapsed_compute: Duration::ZERO,
+            elapsed: Duration::from_secs(2),
+        }
+    }
+
+    fn query(leaves: Vec<OperatorProgress>) -> QueryProgress {
+        let mut root = leaf(0, None, None);
+        root.depth = 0;
+        root.is_leaf = false;
+        let mut operators = vec![root];
+        operators.extend(leaves);
+        QueryProgress {
+            elapsed: Duration::from_secs(2),
+            operators,
+        }
+    }
+
+    fn assert_percent(progress: &QueryProgress, expected: f64) {
+        let percent = progress.percent_complete().unwrap();
+        assert!((percent - expected).abs() < 1e-9, "{percent} != {expected}");
+    }
+
+    #[test]
+    fn operator_fraction_complete() {
+        assert_eq!(leaf(10, None, None).fraction_complete(), None);
+        assert_eq!(leaf(10, Some(40), None).fraction_complete(), Some(0.25));
+        // Bytes take precedence over rows
+        assert_eq!(
+            leaf(10, Some(40), Some((50, 100))).fraction_complete(),
+            Some(0.5)
+        );
+        // Estimates may be too low, but the operator is not done until it finishes
+        assert_eq!(
+            leaf(80, Some(40), None).fraction_complete(),
+            Some(MAX_UNFINISHED_FRACTION)
+        );
+
+        let mut finished = leaf(80, Some(40), None);
+        finished.finished_partitions = 1;
+        assert_eq!(finished.fraction_complete(), Some(1.0));
+
+        assert_eq!(leaf(10, None, Some((1, 2))).rows_per_second(), 5.0);
+        assert_eq!(leaf(10, None, Some((1, 2))).bytes_per_second(), Some(0.5));
+    }
+
+    #[test]
+    fn query_percent_complete() {
+        assert_eq!(query(vec![leaf(10, None, None)]).percent_complete(), None);
+
+        // Leaves with unknown progress are ignored
+        let progress = query(vec![leaf(10, Some(40), None), leaf(10, None, None)]);
+        assert_percent(&progress, 25.0);
+
+        // Weighted by input size if it is known for all leaves
+        let progress = query(vec![
+            leaf(0, None, Some((100, 100))),
+            leaf(0, None, Some((0, 300))),
+        ]);
+        assert_percent(&progress, 24.75);
+
+        // Otherwise all leaves have the same weight
+        let progress = query(vec![
+            leaf(0, None, Some((10, 100))),
+            leaf(30, Some(100), None),
+        ]);
+        assert_percent(&progress, 20.0);
+    }
+
+    #[tokio::test]
+    async fn track_plan_execution() -> Result<()> {
+        let input = Arc::new(PlaceholderRowExec::new(Arc::new(Schema::empty())));
+        let plan: Arc<dyn ExecutionPlan> =
+            Arc::new(CoalesceBatchesExec::new(input, 8192));
+        let tracker = ProgressTracker::new(Arc::clone(&plan));
+
+        let progress = tracker.snapshot();
+        let operators = progress
+            .operators()
+            .iter()
+            .map(|operator| (operator.name(), operator.depth(), operator.is_leaf()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            operators,
+            vec![
+                ("CoalesceBatchesExec", 0, false),
+                ("PlaceholderRowExec", 1, true)
+            ]
+        );
+        assert_eq!(progress.operators()[1].estimated_output_rows(), Some(1));
+        assert_eq!(progress.percent_complete(), Some(0.0));
+
+        collect(plan, Arc::new(TaskContext::default())).await?;
+        let progress = tracker.snapshot();
+        assert!(progress.operators()[0].is_finished());
+        assert_eq!(progress.operators()[0].output_rows(), 1);
+        assert_eq!(progress.percent_complete(), Some(100.0));
+        assert!(progress.to_string().starts_with("100.0% complete"));
+        Ok(())
+    }
+}
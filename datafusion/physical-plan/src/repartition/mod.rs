@@ -19,6 +19,7 @@
 //! partitions to M output partitions based on a partitioning scheme, optionally
 //! maintaining the order of the input rows in the output.
 
+use std::collections::VecDeque;
 use std::fmt::{Debug, Formatter};
 use std::pin::Pin;
 use std::sync::Arc;
@@ -37,6 +38,7 @@ use crate::projection::{all_columns, make_with_child, update_expr, ProjectionExe
 use crate::repartition::distributor_channels::{
     channels, partition_aware_channels, DistributionReceiver, DistributionSender,
 };
+use crate::repartition::range::{evaluate_keys, RangeBounds, RangeSampler};
 use crate::sorts::streaming_merge::StreamingMergeBuilder;
 use crate::stream::RecordBatchStreamAdapter;
 use crate::{DisplayFormatType, ExecutionPlan, Partitioning, PlanProperties, Statistics};
@@ -53,7 +55,7 @@ use datafusion_common_runtime::SpawnedTask;
 use datafusion_execution::memory_pool::MemoryConsumer;
 use datafusion_execution::TaskContext;
 use datafusion_physical_expr::{EquivalenceProperties, PhysicalExpr};
-use datafusion_physical_expr_common::sort_expr::LexOrdering;
+use datafusion_physical_expr_common::sort_expr::{LexOrdering, PhysicalSortExpr};
 
 use crate::filter_pushdown::{
     ChildPushdownResult, FilterDescription, FilterPushdownPhase,
@@ -65,6 +67,7 @@ use log::trace;
 use parking_lot::Mutex;
 
 mod distributor_channels;
+mod range;
 
 type MaybeBatch = Option<Result<RecordBatch>>;
 type InputPartitionsToCurrentPartitionSender = Vec<DistributionSender<MaybeBatch>>;
@@ -161,7 +164,7 @@ impl RepartitionExecState {
         let streams_and_metrics = match self {
             RepartitionExecState::NotInitialized => {
                 self.ensure_input_streams_initialized(
-                    input,
+                    Arc::clone(&input),
                     metrics,
                     partitioning.partition_count(),
                     Arc::clone(&context),
@@ -210,6 +213,22 @@ impl RepartitionExecState {
             channels.insert(partition, (tx, rx, reservation));
         }
 
+        // range partitioning samples all inputs to agree on the partition boundaries
+        let range_sampler = match &partitioning {
+            Partitioning::Range(ordering, _) => Some(Arc::new(RangeSampler::try_new(
+                ordering.clone(),
+                &input.schema(),
+                num_input_partitions,
+                num_output_partitions,
+                context
+                    .session_config()
+                    .options()
+                    .execution
+                    .range_partitioning_sample_size,
+            )?)),
+            _ => None,
+        };
+
         // launch one async task per *input* partition
         let mut spawned_tasks = Vec::with_capacity(num_input_partitions);
         for (i, (stream, metrics)) in
@@ -226,6 +245,7 @@ impl RepartitionExecState {
                 stream,
                 txs.clone(),
                 partitioning.clone(),
+                range_sampler.clone(),
                 metrics,
             ));
 
@@ -267,6 +287,11 @@ enum BatchPartitionerState {
         num_partitions: usize,
         next_idx: usize,
     },
+    Range {
+        ordering: LexOrdering,
+        bounds: Arc<RangeBounds>,
+        num_partitions: usize,
+    },
 }
 
 impl BatchPartitioner {
@@ -288,12 +313,33 @@ impl BatchPartitioner {
                 random_state: ahash::RandomState::with_seeds(0, 0, 0, 0),
                 hash_buffer: vec![],
             },
+            Partitioning::Range(..) => {
+                return not_impl_err!(
+                "Range partitioning requires the boundaries sampled by RepartitionExec"
+            )
+            }
             other => return not_impl_err!("Unsupported repartitioning scheme {other:?}"),
         };
 
         Ok(Self { state, timer })
     }
 
+    /// Create a new [`BatchPartitioner`] for [`Partitioning::Range`], routing rows
+    /// based on the boundaries computed by a [`RangeSampler`]
+    fn try_new_range(
+        ordering: LexOrdering,
+        bounds: Arc<RangeBounds>,
+        num_partitions: usize,
+        timer: metrics::Time,
+    ) -> Result<Self> {
+        let state = BatchPartitionerState::Range {
+            ordering,
+            bounds,
+            num_partitions,
+        };
+        Ok(Self { state, timer })
+    }
+
     /// Partition the provided [`RecordBatch`] into one or more partitioned [`RecordBatch`]
     /// based on the [`Partitioning`] specified on construction
     ///
@@ -362,46 +408,74 @@ impl BatchPartitioner {
                     // Finished building index-arrays for output partitions
                     timer.done();
 
-                    // Borrowing partitioner timer to prevent moving `self` to closure
-                    let partitioner_timer = &self.timer;
-                    let it = indices
-                        .into_iter()
-                        .enumerate()
-                        .filter_map(|(partition, indices)| {
-                            let indices: PrimitiveArray<UInt32Type> = indices.into();
-                            (!indices.is_empty()).then_some((partition, indices))
-                        })
-                        .map(move |(partition, indices)| {
-                            // Tracking time required for repartitioned batches construction
-                            let _timer = partitioner_timer.timer();
-
-                            // Produce batches based on indices
-                            let columns = take_arrays(batch.columns(), &indices, None)?;
-
-                            let mut options = RecordBatchOptions::new();
-                            options = options.with_row_count(Some(indices.len()));
-                            let batch = RecordBatch::try_new_with_options(
-                                batch.schema(),
-                                columns,
-                                &options,
-                            )
-                            .unwrap();
-
-                            Ok((partition, batch))
-                        });
-
-                    Box::new(it)
+                    Box::new(Self::take_partitions(&self.timer, batch, indices))
+                }
+                BatchPartitionerState::Range {
+                    ordering,
+                    bounds,
+                    num_partitions: partitions,
+                } => {
+                    // Tracking time required for distributing indexes across output partitions
+                    let timer = self.timer.timer();
+
+                    let arrays = evaluate_keys(ordering, &batch)?;
+                    let rows = bounds.convert(&arrays)?;
+
+                    let mut indices: Vec<_> = (0..*partitions)
+                        .map(|_| Vec::with_capacity(batch.num_rows()))
+                        .collect();
+
+                    for (index, row) in rows.iter().enumerate() {
+                        indices[bounds.partition(row)].push(index as u32);
+                    }
+
+                    // Finished building index-arrays for output partitions
+                    timer.done();
+
+                    Box::new(Self::take_partitions(&self.timer, batch, indices))
                 }
             };
 
         Ok(it)
     }
 
+    /// Splits `batch` into one batch per output partition, where `indices[i]`
+    /// holds the rows of partition `i`. Partitions without rows are skipped.
+    fn take_partitions(
+        partitioner_timer: &metrics::Time,
+        batch: RecordBatch,
+        indices: Vec<Vec<u32>>,
+    ) -> impl Iterator<Item = Result<(usize, RecordBatch)>> + Send + '_ {
+        indices
+            .into_iter()
+            .enumerate()
+            .filter_map(|(partition, indices)| {
+                let indices: PrimitiveArray<UInt32Type> = indices.into();
+                (!indices.is_empty()).then_some((partition, indices))
+            })
+            .map(move |(partition, indices)| {
+                // Tracking time required for repartitioned batches construction
+                let _timer = partitioner_timer.timer();
+
+                // Produce batches based on indices
+                let columns = take_arrays(batch.columns(), &indices, None)?;
+
+                let mut options = RecordBatchOptions::new();
+                options = options.with_row_count(Some(indices.len()));
+                let batch =
+                    RecordBatch::try_new_with_options(batch.schema(), columns, &options)
+                        .unwrap();
+
+                Ok((partition, batch))
+            })
+    }
+
     // return the number of output partitions
     fn num_partitions(&self) -> usize {
         match self.state {
             BatchPartitionerState::RoundRobin { num_partitions, .. } => num_partitions,
             BatchPartitionerState::Hash { num_partitions, .. } => num_partitions,
+            BatchPartitionerState::Range { num_partitions, .. } => num_partitions,
         }
     }
 }
@@ -632,7 +706,10 @@ impl ExecutionPlan for RepartitionExec {
     }
 
     fn benefits_from_input_partitioning(&self) -> Vec<bool> {
-        vec![matches!(self.partitioning(), Partitioning::Hash(_, _))]
+        vec![matches!(
+            self.partitioning(),
+            Partitioning::Hash(_, _) | Partitioning::Range(_, _)
+        )]
     }
 
     fn maintains_input_order(&self) -> Vec<bool> {
@@ -832,6 +909,19 @@ impl ExecutionPlan for RepartitionExec {
                 }
                 Partitioning::Hash(new_partitions, *size)
             }
+            Partitioning::Range(ordering, size) => {
+                let mut new_ordering = vec![];
+                for sort_expr in ordering.iter() {
+                    let Some(new_expr) =
+                        update_expr(&sort_expr.expr, projection.expr(), false)?
+                    else {
+                        return Ok(None);
+                    };
+                    new_ordering.push(PhysicalSortExpr::new(new_expr, sort_expr.options));
+                }
+                // A non-empty ordering remains non-empty after the projection
+                Partitioning::Range(LexOrdering::new(new_ordering).unwrap(), *size)
+            }
             others => others.clone(),
         };
 
@@ -961,23 +1051,46 @@ impl RepartitionExec {
             (DistributionSender<MaybeBatch>, SharedMemoryReservation),
         >,
         partitioning: Partitioning,
+        range_sampler: Option<Arc<RangeSampler>>,
         metrics: RepartitionMetrics,
     ) -> Result<()> {
-        let mut partitioner =
-            BatchPartitioner::try_new(partitioning, metrics.repartition_time.clone())?;
+        // Batches already pulled from the input but not yet partitioned
+        let mut buffered = VecDeque::new();
+        let mut partitioner = match range_sampler {
+            Some(sampler) => {
+                let ordering = sampler.ordering().clone();
+                let (batches, bounds) =
+                    sampler.sample(&mut stream, &metrics.fetch_time).await?;
+                buffered.extend(batches);
+                BatchPartitioner::try_new_range(
+                    ordering,
+                    bounds,
+                    partitioning.partition_count(),
+                    metrics.repartition_time.clone(),
+                )?
+            }
+            None => {
+                BatchPartitioner::try_new(partitioning, metrics.repartition_time.clone())?
+            }
+        };
 
         // While there are still outputs to send to, keep pulling inputs
         let mut batches_until_yield = partitioner.num_partitions();
         while !output_channels.is_empty() {
-            // fetch the next batch
-            let timer = metrics.fetch_time.timer();
-            let result = stream.next().await;
-            timer.done();
+            let batch = match buffered.pop_front() {
+                Some(batch) => batch,
+                None => {
+                    // fetch the next batch
+                    let timer = metrics.fetch_time.timer();
+                    let result = stream.next().await;
+                    timer.done();
 
-            // Input is done
-            let batch = match result {
-                Some(result) => result?,
-                None => break,
+                    // Input is done
+                    match result {
+                        Some(result) => result?,
+                        None => break,
+                    }
+                }
             };
 
             for res in partitioner.partition_iter(batch)? {
@@ -1206,11 +1319,13 @@ mod tests {
     };
 
     use arrow::array::{ArrayRef, StringArray, UInt32Array};
+    use arrow::compute::SortOptions;
     use arrow::datatypes::{DataType, Field, Schema};
     use datafusion_common::cast::as_string_array;
     use datafusion_common::test_util::batches_to_sort_string;
     use datafusion_common::{arrow_datafusion_err, exec_err};
     use datafusion_common_runtime::JoinSet;
+    use datafusion_execution::config::SessionConfig;
     use datafusion_execution::runtime_env::RuntimeEnvBuilder;
     use insta::assert_snapshot;
     use itertools::Itertools;
@@ -1298,6 +1413,175 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn many_to_many_range_partition() -> Result<()> {
+        let schema = test_schema();
+        // Three inputs holding the interleaved values 0..3000 in descending order
+        let partitions = (0..3)
+            .map(|input| {
+                let values = (0..1000).rev().map(|v| v * 3 + input).collect();
+                uint32_batches(&schema, values, 100)
+            })
+            .collect();
+
+        let ordering = [PhysicalSortExpr::new_default(col("c0", &schema)?)].into();
+        let output_partitions =
+            repartition(&schema, partitions, Partitioning::Range(ordering, 4)).await?;
+
+        let values = partition_values(&output_partitions);
+        assert_eq!(values.len(), 4);
+        assert_eq!(values.iter().map(Vec::len).sum::<usize>(), 3000);
+        assert_ranges_ordered(&values);
+        // The sample covers the whole input, so the ranges are evenly sized
+        for partition in &values {
+            assert_eq!(partition.len(), 750);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn range_partition_descending_nulls_first() -> Result<()> {
+        let schema =
+            Arc::new(Schema::new(vec![Field::new("c0", DataType::UInt32, true)]));
+        let batch = |values: Vec<Option<u32>>| {
+            RecordBatch::try_new(
+                Arc::clone(&schema),
+                vec![Arc::new(UInt32Array::from(values))],
+            )
+            .unwrap()
+        };
+        let partitions = vec![
+            vec![batch(vec![Some(1), None, Some(5), Some(3)])],
+            vec![batch(vec![Some(2), Some(6), None, Some(4)])],
+            // An empty input does not prevent the others from being partitioned
+            vec![],
+        ];
+
+        let ordering = [PhysicalSortExpr::new(
+            col("c0", &schema)?,
+            SortOptions::new(true, true),
+        )]
+        .into();
+        let output_partitions =
+            repartition(&schema, partitions, Partitioning::Range(ordering, 2)).await?;
+
+        let values = partition_values(&output_partitions);
+        assert_eq!(
+            values,
+            vec![
+                vec![None, None, Some(6), Some(5)],
+                vec![Some(4), Some(3), Some(2), Some(1)],
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn range_partition_sample_smaller_than_input() -> Result<()> {
+        let schema = test_schema();
+        let partitions = vec![
+            uint32_batches(&schema, (0..500).collect(), 10),
+            uint32_batches(&schema, (0..500).rev().collect(), 10),
+        ];
+        let ordering = [PhysicalSortExpr::new_default(col("c0", &schema)?)].into();
+
+        // Only the leading 20 rows of each input are sampled
+        let mut config = SessionConfig::new();
+        config
+            .options_mut()
+            .execution
+            .range_partitioning_sample_size = 20;
+        let task_ctx = Arc::new(TaskContext::default().with_session_config(config));
+
+        let exec = TestMemoryExec::try_new_exec(&partitions, Arc::clone(&schema), None)?;
+        let exec = RepartitionExec::try_new(exec, Partitioning::Range(ordering, 3))?;
+        let mut output_partitions = vec![];
+        for i in 0..3 {
+            let stream = exec.execute(i, Arc::clone(&task_ctx))?;
+            output_partitions.push(crate::common::collect(stream).await?);
+        }
+
+        let values = partition_values(&output_partitions);
+        assert_eq!(values.iter().map(Vec::len).sum::<usize>(), 1000);
+        assert_ranges_ordered(&values);
+
+        Ok(())
+    }
+
+    #[test]
+    fn range_partitioning_requires_sampling() -> Result<()> {
+        let schema = test_schema();
+        let ordering = [PhysicalSortExpr::new_default(col("c0", &schema)?)].into();
+        let result = BatchPartitioner::try_new(
+            Partitioning::Range(ordering, 2),
+            metrics::Time::new(),
+        );
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    /// Splits `values` into batches of `batch_size` rows of [`test_schema`]
+    fn uint32_batches(
+        schema: &SchemaRef,
+        values: Vec<u32>,
+        batch_size: usize,
+    ) -> Vec<RecordBatch> {
+        values
+            .chunks(batch_size)
+            .map(|chunk| {
+                RecordBatch::try_new(
+                    Arc::clone(schema),
+                    vec![Arc::new(UInt32Array::from(chunk.to_vec()))],
+                )
+                .unwrap()
+            })
+            .collect()
+    }
+
+    /// Returns the values of the first column of each output partition, in
+    /// descending order with nulls first
+    fn partition_values(output_partitions: &[Vec<RecordBatch>]) -> Vec<Vec<Option<u32>>> {
+        output_partitions
+            .iter()
+            .map(|batches| {
+                let mut values = batches
+                    .iter()
+                    .flat_map(|batch| {
+                        batch
+                            .column(0)
+                            .as_any()
+                            .downcast_ref::<UInt32Array>()
+                            .unwrap()
+                            .iter()
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>();
+                values.sort_unstable_by(|a, b| match (a, b) {
+                    (None, None) => std::cmp::Ordering::Equal,
+                    (None, _) => std::cmp::Ordering::Less,
+                    (_, None) => std::cmp::Ordering::Greater,
+                    (Some(a), Some(b)) => b.cmp(a),
+                });
+                values
+            })
+            .collect()
+    }
+
+    /// Asserts that every value of a partition is less than or equal to
+    /// every value of the next (non-empty) partition
+    fn assert_ranges_ordered(values: &[Vec<Option<u32>>]) {
+        let ranges = values
+            .iter()
+            .filter(|values| !values.is_empty())
+            .map(|values| (values.iter().min().unwrap(), values.iter().max().unwrap()))
+            .collect::<Vec<_>>();
+        for window in ranges.windows(2) {
+            assert!(window[0].1 <= window[1].0, "overlapping ranges: {ranges:?}");
+        }
+    }
+
     fn test_schema() -> Arc<Schema> {
         Arc::new(Schema::new(vec![Field::new("c0", DataType::UInt32, false)]))
     }
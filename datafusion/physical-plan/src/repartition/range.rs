@@ -0,0 +1,276 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Sampling of the range boundaries used by [`Partitioning::Range`]
+//!
+//! # Design
+//!
+//! Every input partition of the [`RepartitionExec`] buffers its first
+//! `sample_size` rows and contributes (a subset of) their sort keys to a
+//! shared [`RangeSampler`]. Once all inputs have contributed, the sample is
+//! sorted and the keys at the quantiles `1/n, 2/n, ..., (n-1)/n` become the
+//! boundaries between the `n` output partitions. Every input then routes its
+//! buffered batches, and all subsequent ones, using the same boundaries.
+//!
+//! Keys are compared in the [`Rows`] format, whose byte-wise ordering matches
+//! the sort order (including direction and null placement) of the
+//! [`LexOrdering`].
+//!
+//! Only the leading rows of each input are sampled, so inputs whose key
+//! distribution drifts over time (for example inputs that are already
+//! sorted) may produce unevenly sized output partitions. The output is
+//! nevertheless always correctly range partitioned.
+//!
+//! [`Partitioning::Range`]: crate::Partitioning::Range
+//! [`RepartitionExec`]: super::RepartitionExec
+
+use std::sync::Arc;
+
+use crate::metrics;
+use crate::SendableRecordBatchStream;
+
+use arrow::array::{ArrayRef, RecordBatch};
+use arrow::datatypes::Schema;
+use arrow::row::{Row, RowConverter, Rows, SortField};
+use datafusion_common::{internal_datafusion_err, Result};
+use datafusion_physical_expr_common::sort_expr::LexOrdering;
+
+use futures::StreamExt;
+use parking_lot::Mutex;
+use tokio::sync::watch;
+
+/// Boundaries between the output partitions of a range partitioning
+#[derive(Debug)]
+pub(super) struct RangeBounds {
+    /// Converts the sort keys of a batch into comparable rows
+    converter: Arc<RowConverter>,
+    /// The `n - 1` boundaries between `n` output partitions, in ascending order
+    bounds: Rows,
+}
+
+impl RangeBounds {
+    /// Converts the sort key columns of a batch into [`Rows`]
+    pub(super) fn convert(&self, keys: &[ArrayRef]) -> Result<Rows> {
+        Ok(self.converter.convert_columns(keys)?)
+    }
+
+    /// Returns the output partition of a row: the number of boundaries that
+    /// sort before or equal to it. Rows equal to a boundary therefore always
+    /// end up in the same partition.
+    pub(super) fn partition(&self, row: Row<'_>) -> usize {
+        let (mut low, mut high) = (0, self.bounds.num_rows());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.bounds.row(mid) <= row {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        low
+    }
+}
+
+/// State shared by all input partitions while they sample their input
+#[derive(Debug)]
+struct SamplerState {
+    /// Number of input partitions that have not contributed a sample yet
+    pending_inputs: usize,
+    /// Rows sampled so far
+    samples: Rows,
+}
+
+/// Computes the [`RangeBounds`] of a range partitioning from a sample of the
+/// sort keys of every input partition
+#[derive(Debug)]
+pub(super) struct RangeSampler {
+    ordering: LexOrdering,
+    converter: Arc<RowConverter>,
+    num_partitions: usize,
+    sample_size: usize,
+    state: Mutex<SamplerState>,
+    bounds: watch::Sender<Option<Arc<RangeBounds>>>,
+}
+
+impl RangeSampler {
+    /// Creates a sampler that splits the rows of `num_inputs` input
+    /// partitions into `num_partitions` ranges of `ordering`, sampling up to
+    /// `sample_size` rows from each input
+    pub(super) fn try_new(
+        ordering: LexOrdering,
+        schema: &Schema,
+        num_inputs: usize,
+        num_partitions: usize,
+        sample_size: usize,
+    ) -> Result<Self> {
+        let fields = ordering
+            .iter()
+            .map(|sort_expr| {
+                Ok(SortField::new_with_options(
+                    sort_expr.expr.data_type(schema)?,
+                    sort_expr.options,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let converter = Arc::new(RowConverter::new(fields)?);
+        let samples = converter.empty_rows(0, 0);
+        let (bounds, _) = watch::channel(None);
+
+        Ok(Self {
+            ordering,
+            converter,
+            num_partitions,
+            sample_size: sample_size.max(1),
+            state: Mutex::new(SamplerState {
+                pending_inputs: num_inputs,
+                samples,
+            }),
+            bounds,
+        })
+    }
+
+    /// The sort expressions the rows are range partitioned by
+    pub(super) fn ordering(&self) -> &LexOrdering {
+        &self.ordering
+    }
+
+    /// Buffers the leading batches of `stream` until `sample_size` rows (or
+    /// the end of the input) are reached, contributes them to the sample and
+    /// waits until all other inputs have done the same.
+    ///
+    /// Returns the buffered batches, which still need to be partitioned, and
+    /// the boundaries computed from the samples of all inputs.
+    pub(super) async fn sample(
+        self: Arc<Self>,
+        stream: &mut SendableRecordBatchStream,
+        fetch_time: &metrics::Time,
+    ) -> Result<(Vec<RecordBatch>, Arc<RangeBounds>)> {
+        let mut contribution = Contribution {
+            sampler: Arc::clone(&self),
+            submitted: false,
+        };
+
+        let mut batches = vec![];
+        let mut num_rows = 0;
+        while num_rows < self.sample_size {
+            let timer = fetch_time.timer();
+            let result = stream.next().await;
+            timer.done();
+
+            let Some(batch) = result.transpose()? else {
+                break;
+            };
+            num_rows += batch.num_rows();
+            batches.push(batch);
+        }
+
+        // Take an evenly spaced subset of at most `sample_size` rows
+        let step = num_rows.div_ceil(self.sample_size).max(1);
+        let mut rows = self.converter.empty_rows(num_rows / step + 1, 0);
+        let mut offset = 0;
+        for batch in &batches {
+            let keys = evaluate_keys(&self.ordering, batch)?;
+            let batch_rows = self.converter.convert_columns(&keys)?;
+            // Index of the first row of this batch that is part of the subset
+            let first = (step - offset % step) % step;
+            for row in (first..batch_rows.num_rows()).step_by(step) {
+                rows.push(batch_rows.row(row));
+            }
+            offset += batch_rows.num_rows();
+        }
+        contribution.submit(rows);
+
+        let mut receiver = self.bounds.subscribe();
+        let bounds = receiver
+            .wait_for(Option::is_some)
+            .await
+            .map_err(|e| internal_datafusion_err!("Range sampler closed: {e}"))?
+            .as_ref()
+            .map(Arc::clone)
+            .unwrap();
+
+        Ok((batches, bounds))
+    }
+
+    /// Adds the sampled `rows` of one input, computing the boundaries once
+    /// every input has contributed
+    fn contribute(&self, rows: Option<Rows>) {
+        let mut state = self.state.lock();
+        if let Some(rows) = rows {
+            for row in rows.iter() {
+                state.samples.push(row);
+            }
+        }
+        state.pending_inputs = state.pending_inputs.saturating_sub(1);
+        if state.pending_inputs > 0 {
+            return;
+        }
+
+        let mut samples = state.samples.iter().collect::<Vec<_>>();
+        samples.sort_unstable();
+
+        let mut bounds = self
+            .converter
+            .empty_rows(self.num_partitions.saturating_sub(1), 0);
+        if !samples.is_empty() {
+            for partition in 1..self.num_partitions {
+                bounds.push(samples[partition * samples.len() / self.num_partitions]);
+            }
+        }
+
+        self.bounds.send_replace(Some(Arc::new(RangeBounds {
+            converter: Arc::clone(&self.converter),
+            bounds,
+        })));
+    }
+}
+
+/// Evaluates the sort expressions of `ordering` against `batch`
+pub(super) fn evaluate_keys(
+    ordering: &LexOrdering,
+    batch: &RecordBatch,
+) -> Result<Vec<ArrayRef>> {
+    ordering
+        .iter()
+        .map(|sort_expr| sort_expr.expr.evaluate(batch)?.into_array(batch.num_rows()))
+        .collect()
+}
+
+/// The sample of one input partition.
+///
+/// Dropping it without submitting it (for example, because the input
+/// returned an error or the task was cancelled) still counts the input as
+/// contributed, so that the other inputs are never blocked forever.
+struct Contribution {
+    sampler: Arc<RangeSampler>,
+    submitted: bool,
+}
+
+impl Contribution {
+    fn submit(&mut self, rows: Rows) {
+        self.submitted = true;
+        self.sampler.contribute(Some(rows));
+    }
+}
+
+impl Drop for Contribution {
+    fn drop(&mut self) {
+        if !self.submitted {
+            self.sampler.contribute(None);
+        }
+    }
+}
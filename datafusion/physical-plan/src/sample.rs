@@ -0,0 +1,368 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the TABLESAMPLE plan
+
+use std::any::Any;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use super::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use super::{
+    DisplayAs, PlanProperties, RecordBatchStream, SendableRecordBatchStream, Statistics,
+};
+use crate::execution_plan::CardinalityEffect;
+use crate::{DisplayFormatType, ExecutionPlan};
+
+use arrow::array::BooleanArray;
+use arrow::compute::filter_record_batch;
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use datafusion_common::{internal_err, plan_err, Result};
+use datafusion_execution::TaskContext;
+use datafusion_expr::SampleMethod;
+
+use futures::stream::{Stream, StreamExt};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Returns a random sample of the rows of its input.
+///
+/// With [`SampleMethod::Bernoulli`] every row is kept with probability
+/// `fraction`, with [`SampleMethod::System`] every record batch is.
+///
+/// When a `seed` is set every partition uses its own deterministic random
+/// number generator, so the same input batches always produce the same sample.
+#[derive(Debug, Clone)]
+pub struct SampleExec {
+    /// Input execution plan
+    input: Arc<dyn ExecutionPlan>,
+    /// How rows are selected
+    method: SampleMethod,
+    /// Probability of keeping a row or batch
+    fraction: f64,
+    /// Seed for the random number generator
+    seed: Option<u64>,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+    cache: PlanProperties,
+}
+
+impl SampleExec {
+    /// Create a new SampleExec
+    pub fn try_new(
+        input: Arc<dyn ExecutionPlan>,
+        method: SampleMethod,
+        fraction: f64,
+        seed: Option<u64>,
+    ) -> Result<Self> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return plan_err!("Sample fraction must be between 0 and 1, got {fraction}");
+        }
+        let cache = input.properties().clone();
+        Ok(Self {
+            input,
+            method,
+            fraction,
+            seed,
+            metrics: ExecutionPlanMetricsSet::new(),
+            cache,
+        })
+    }
+
+    /// Input execution plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// How rows are selected
+    pub fn method(&self) -> SampleMethod {
+        self.method
+    }
+
+    /// Probability of keeping a row or batch
+    pub fn fraction(&self) -> f64 {
+        self.fraction
+    }
+
+    /// Seed for the random number generator
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+}
+
+impl DisplayAs for SampleExec {
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(
+                    f,
+                    "SampleExec: method={}, fraction={}",
+                    self.method, self.fraction
+                )?;
+                if let Some(seed) = self.seed {
+                    write!(f, ", seed={seed}")?;
+                }
+                Ok(())
+            }
+            DisplayFormatType::TreeRender => {
+                writeln!(f, "method={}", self.method)?;
+                write!(f, "fraction={}", self.fraction)
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for SampleExec {
+    fn name(&self) -> &'static str {
+        "SampleExec"
+    }
+
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        &self.cache
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![&self.input]
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        match children.len() {
+            1 => Ok(Arc::new(SampleExec::try_new(
+                Arc::clone(&children[0]),
+                self.method,
+                self.fraction,
+                self.seed,
+            )?)),
+            _ => internal_err!("SampleExec wrong number of children"),
+        }
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed.wrapping_add(partition as u64)),
+            None => StdRng::from_os_rng(),
+        };
+        Ok(Box::pin(SampleStream {
+            schema: self.schema(),
+            input: self.input.execute(partition, context)?,
+            method: self.method,
+            fraction: self.fraction,
+            rng,
+            baseline_metrics: BaselineMetrics::new(&self.metrics, partition),
+        }))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Result<Statistics> {
+        self.partition_statistics(None)
+    }
+
+    fn partition_statistics(&self, partition: Option<usize>) -> Result<Statistics> {
+        let mut stats = self.input.partition_statistics(partition)?.to_inexact();
+        stats.num_rows = stats.num_rows.with_estimated_selectivity(self.fraction);
+        stats.total_byte_size = stats
+            .total_byte_size
+            .with_estimated_selectivity(self.fraction);
+        Ok(stats)
+    }
+
+    fn cardinality_effect(&self) -> CardinalityEffect {
+        CardinalityEffect::LowerEqual
+    }
+}
+
+/// Samples the batches of a single input partition
+struct SampleStream {
+    schema: SchemaRef,
+    input: SendableRecordBatchStream,
+    method: SampleMethod,
+    fraction: f64,
+    rng: StdRng,
+    /// Execution time metrics
+    baseline_metrics: BaselineMetrics,
+}
+
+impl SampleStream {
+    /// Returns the sampled rows of `batch`, or `None` if none are kept
+    fn sample(&mut self, batch: RecordBatch) -> Result<Option<RecordBatch>> {
+        let _timer = self.baseline_metrics.elapsed_compute().timer();
+        match self.method {
+            SampleMethod::Bernoulli => {
+                let mask = (0..batch.num_rows())
+                    .map(|_| Some(self.rng.random_bool(self.fraction)))
+                    .collect::<BooleanArray>();
+                let batch = filter_record_batch(&batch, &mask)?;
+                Ok((batch.num_rows() > 0).then_some(batch))
+            }
+            SampleMethod::System => {
+                Ok(self.rng.random_bool(self.fraction).then_some(batch))
+            }
+        }
+    }
+}
+
+impl Stream for SampleStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let poll = loop {
+            match self.input.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(batch))) => match self.sample(batch) {
+                    Ok(Some(batch)) => break Poll::Ready(Some(Ok(batch))),
+                    // Continue to poll input stream
+                    Ok(None) => {}
+                    Err(e) => break Poll::Ready(Some(Err(e))),
+                },
+                other => break other,
+            }
+        };
+        self.baseline_metrics.record_poll(poll)
+    }
+}
+
+impl RecordBatchStream for SampleStream {
+    /// Get the schema
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::collect;
+    use crate::test::{self, TestMemoryExec};
+
+    use arrow::array::Int32Array;
+    use datafusion_common::assert_contains;
+    use datafusion_common::stats::Precision;
+
+    /// Scans 2 partitions of 10 batches with 100 rows each
+    fn input() -> Arc<dyn ExecutionPlan> {
+        let partition: Vec<_> = (0..10).map(|_| test::make_partition(100)).collect();
+        let partitions = vec![partition.clone(), partition];
+        let schema = partitions[0][0].schema();
+        TestMemoryExec::try_new_exec(&partitions, schema, None).unwrap()
+    }
+
+    async fn sample(
+        method: SampleMethod,
+        fraction: f64,
+        seed: Option<u64>,
+        partition: usize,
+    ) -> Result<Vec<RecordBatch>> {
+        let sample = SampleExec::try_new(input(), method, fraction, seed)?;
+        collect(sample.execute(partition, Arc::new(TaskContext::default()))?).await
+    }
+
+    fn num_rows(batches: &[RecordBatch]) -> usize {
+        batches.iter().map(|batch| batch.num_rows()).sum()
+    }
+
+    #[tokio::test]
+    async fn sample_all_or_nothing() -> Result<()> {
+        for method in [SampleMethod::Bernoulli, SampleMethod::System] {
+            assert_eq!(num_rows(&sample(method, 0.0, None, 0).await?), 0);
+            assert_eq!(num_rows(&sample(method, 1.0, None, 0).await?), 1000);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sample_bernoulli() -> Result<()> {
+        let batches = sample(SampleMethod::Bernoulli, 0.5, None, 0).await?;
+        let rows = num_rows(&batches);
+        assert!((350..=650).contains(&rows), "unexpected sample size {rows}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sample_system_keeps_whole_batches() -> Result<()> {
+        let batches = sample(SampleMethod::System, 0.5, Some(7), 0).await?;
+        assert!(!batches.is_empty() && batches.len() < 10);
+        assert!(batches.iter().all(|batch| batch.num_rows() == 100));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sample_repeatable() -> Result<()> {
+        let values = |batches: Vec<RecordBatch>| {
+            batches
+                .iter()
+                .flat_map(|batch| {
+                    let array = batch.column(0).as_any().downcast_ref::<Int32Array>();
+                    array.unwrap().values().to_vec()
+                })
+                .collect::<Vec<_>>()
+        };
+        let first = values(sample(SampleMethod::Bernoulli, 0.3, Some(42), 0).await?);
+        let second = values(sample(SampleMethod::Bernoulli, 0.3, Some(42), 0).await?);
+        assert_eq!(first, second);
+
+        // other partitions and seeds produce a different sample
+        let other_partition =
+            values(sample(SampleMethod::Bernoulli, 0.3, Some(42), 1).await?);
+        let other_seed = values(sample(SampleMethod::Bernoulli, 0.3, Some(43), 0).await?);
+        assert_ne!(first, other_partition);
+        assert_ne!(first, other_seed);
+        Ok(())
+    }
+
+    #[test]
+    fn sample_statistics() -> Result<()> {
+        let sample = SampleExec::try_new(input(), SampleMethod::Bernoulli, 0.1, None)?;
+        let stats = sample.partition_statistics(None)?;
+        assert_eq!(stats.num_rows, Precision::Inexact(200));
+        Ok(())
+    }
+
+    #[test]
+    fn sample_invalid_fraction() {
+        let err = SampleExec::try_new(input(), SampleMethod::System, 1.5, None)
+            .unwrap_err()
+            .to_string();
+        assert_contains!(err, "Sample fraction must be between 0 and 1, got 1.5");
+    }
+}
@@ -1466,6 +1466,7 @@ mod tests {
         let &partition_count = match properties.output_partitioning() {
             Partitioning::RoundRobinBatch(partitions) => partitions,
             Partitioning::Hash(_, partitions) => partitions,
+            Partitioning::Range(_, partitions) => partitions,
             Partitioning::UnknownPartitioning(partitions) => partitions,
         };
         let source = CongestedExec {
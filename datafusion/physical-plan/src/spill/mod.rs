@@ -67,18 +67,19 @@ const SPILL_BATCH_MEMORY_MARGIN: usize = 4096;
 
 /// When we poll for the next batch, we will get back both the batch and the reader,
 /// so we can call `next` again.
-type NextRecordBatchResult = Result<(StreamReader<BufReader<File>>, Option<RecordBatch>)>;
+type NextRecordBatchResult =
+    Result<(Box<StreamReader<BufReader<File>>>, Option<RecordBatch>)>;
 
 enum SpillReaderStreamState {
     /// Initial state: the stream was not initialized yet
     /// and the file was not opened
-    Uninitialized(RefCountedTempFile),
+    Uninitialized(Arc<RefCountedTempFile>),
 
     /// A read is in progress in a spawned blocking task for which we hold the handle.
     ReadInProgress(SpawnedTask<NextRecordBatchResult>),
 
     /// A read has finished and we wait for being polled again in order to start reading the next batch.
-    Waiting(StreamReader<BufReader<File>>),
+    Waiting(Box<StreamReader<BufReader<File>>>),
 
     /// The stream has finished, successfully or not.
     Done,
@@ -87,7 +88,7 @@ enum SpillReaderStreamState {
 impl SpillReaderStream {
     fn new(
         schema: SchemaRef,
-        spill_file: RefCountedTempFile,
+        spill_file: Arc<RefCountedTempFile>,
         max_record_batch_memory: Option<usize>,
     ) -> Self {
         Self {
@@ -121,7 +122,7 @@ impl SpillReaderStream {
 
                     let next_batch = reader.next().transpose()?;
 
-                    Ok((reader, next_batch))
+                    Ok((Box::new(reader), next_batch))
                 });
 
                 self.state = SpillReaderStreamState::ReadInProgress(task);
@@ -175,10 +175,24 @@ impl SpillManager {
         &self,
         spill_file_path: RefCountedTempFile,
         max_record_batch_memory: Option<usize>,
+    ) -> Result<SendableRecordBatchStream> {
+        self.read_shared_spill_as_stream(
+            Arc::new(spill_file_path),
+            max_record_batch_memory,
+        )
+    }
+
+    /// Reads a spill file that may be read more than once as a stream, see
+    /// [`Self::read_spill_as_stream`]. The file is deleted once the last
+    /// reference to it is dropped.
+    pub fn read_shared_spill_as_stream(
+        &self,
+        spill_file: Arc<RefCountedTempFile>,
+        max_record_batch_memory: Option<usize>,
     ) -> Result<SendableRecordBatchStream> {
         let stream = Box::pin(cooperative(SpillReaderStream::new(
             Arc::clone(&self.schema),
-            spill_file_path,
+            spill_file,
             max_record_batch_memory,
         )));
 
@@ -21,6 +21,7 @@ use std::pin::Pin;
 use std::sync::Arc;
 use std::task::Context;
 use std::task::Poll;
+use std::time::Duration;
 
 #[cfg(test)]
 use super::metrics::ExecutionPlanMetricsSet;
@@ -29,7 +30,10 @@ use super::{ExecutionPlan, RecordBatchStream, SendableRecordBatchStream};
 use crate::displayable;
 
 use arrow::{datatypes::SchemaRef, record_batch::RecordBatch};
-use datafusion_common::{exec_err, Result};
+use datafusion_common::instant::Instant;
+use datafusion_common::{
+    exec_datafusion_err, exec_err, timeout_datafusion_err, DataFusionError, Result,
+};
 use datafusion_common_runtime::JoinSet;
 use datafusion_execution::TaskContext;
 
@@ -40,6 +44,7 @@ use log::debug;
 use pin_project_lite::pin_project;
 use tokio::runtime::Handle;
 use tokio::sync::mpsc::{Receiver, Sender};
+use tokio::time::Sleep;
 
 /// Creates a stream from a collection of producing tasks, routing panics to the stream.
 ///
@@ -496,6 +501,104 @@ impl Stream for EmptyRecordBatchStream {
     }
 }
 
+/// Returns the error reported by the streams of a query whose deadline,
+/// see [`TaskContext::deadline`], has passed
+pub(crate) fn deadline_exceeded_error() -> DataFusionError {
+    timeout_datafusion_err!("Query exceeded its deadline and was cancelled")
+}
+
+/// Stream wrapper that cancels the wrapped stream once a deadline passes.
+///
+/// In contrast to the deadline checks of [`CooperativeStream`], which only
+/// happen when the stream is polled, the deadline also wakes up the consumer
+/// of a stream that is waiting for its input, for example on I/O.
+///
+/// Once the deadline has passed, the wrapped stream is dropped, which aborts
+/// any work it started, and a [`DataFusionError::Timeout`] error is returned.
+///
+/// Must be created within a Tokio runtime with the time driver enabled.
+///
+/// [`CooperativeStream`]: crate::coop::CooperativeStream
+pub struct DeadlineStream {
+    schema: SchemaRef,
+    /// The wrapped stream, `None` once it was cancelled
+    inner: Option<SendableRecordBatchStream>,
+    /// Timer that fires at the deadline
+    timer: Pin<Box<Sleep>>,
+}
+
+impl DeadlineStream {
+    /// Wraps `inner` so that it is cancelled at `deadline`
+    ///
+    /// Returns an error if not called within a Tokio runtime with the time
+    /// driver enabled.
+    pub fn try_new(inner: SendableRecordBatchStream, deadline: Instant) -> Result<Self> {
+        let timer = deadline_timer(deadline.saturating_duration_since(Instant::now()))?;
+        Ok(Self {
+            schema: inner.schema(),
+            inner: Some(inner),
+            timer,
+        })
+    }
+
+    /// Wraps `stream` in a [`DeadlineStream`] if `context` has a deadline
+    pub fn wrap(
+        stream: SendableRecordBatchStream,
+        context: &TaskContext,
+    ) -> Result<SendableRecordBatchStream> {
+        Ok(match context.deadline() {
+            Some(deadline) => Box::pin(Self::try_new(stream, deadline)?),
+            None => stream,
+        })
+    }
+}
+
+/// Returns a timer that fires after `duration`, or an error if the current
+/// thread is not within a Tokio runtime with the time driver enabled
+fn deadline_timer(duration: Duration) -> Result<Pin<Box<Sleep>>> {
+    if Handle::try_current().is_err() {
+        return exec_err!("Query deadlines must be started within a Tokio runtime");
+    }
+    // Tokio panics when creating a timer without the time driver, and offers no
+    // way to check for the driver upfront
+    std::panic::catch_unwind(|| Box::pin(tokio::time::sleep(duration))).map_err(|_| {
+        exec_datafusion_err!(
+            "Query deadlines require a Tokio runtime with the time driver enabled, \
+            see tokio::runtime::Builder::enable_time"
+        )
+    })
+}
+
+impl Stream for DeadlineStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        if self.inner.is_none() {
+            return Poll::Ready(None);
+        }
+
+        if self.timer.as_mut().poll(cx).is_ready() {
+            self.inner = None;
+            return Poll::Ready(Some(Err(deadline_exceeded_error())));
+        }
+
+        let poll = self.inner.as_mut().unwrap().poll_next_unpin(cx);
+        if matches!(poll, Poll::Ready(None)) {
+            self.inner = None;
+        }
+        poll
+    }
+}
+
+impl RecordBatchStream for DeadlineStream {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+}
+
 /// Stream wrapper that records `BaselineMetrics` for a particular
 /// `[SendableRecordBatchStream]` (likely a partition)
 pub(crate) struct ObservedStream {
@@ -697,12 +800,15 @@ impl RecordBatchStream for BatchSplitStream {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::empty::EmptyExec;
     use crate::test::exec::{
         assert_strong_count_converges_to_zero, BlockingExec, MockExec, PanicExec,
     };
 
     use arrow::datatypes::{DataType, Field, Schema};
-    use datafusion_common::exec_err;
+    use datafusion_common::config::ConfigOptions;
+    use datafusion_common::{assert_contains, exec_err};
+    use datafusion_execution::config::SessionConfig;
 
     fn schema() -> SchemaRef {
         Arc::new(Schema::new(vec![Field::new("a", DataType::Float32, true)]))
@@ -760,6 +866,62 @@ mod test {
         assert_strong_count_converges_to_zero(refs).await;
     }
 
+    #[tokio::test]
+    async fn deadline_stream_cancels_blocked_input() {
+        let deadline = Instant::now() + Duration::from_millis(10);
+        let task_ctx = Arc::new(TaskContext::default().with_deadline(Some(deadline)));
+        let schema = schema();
+
+        // Make an input that never proceeds
+        let input = BlockingExec::new(Arc::clone(&schema), 1);
+        let refs = input.refs();
+
+        let mut stream = crate::execute_stream(Arc::new(input), task_ctx).unwrap();
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert!(matches!(err, DataFusionError::Timeout(_)), "{err}");
+        assert!(stream.next().await.is_none());
+
+        // The input is dropped once the deadline passes
+        assert_strong_count_converges_to_zero(refs).await;
+    }
+
+    #[tokio::test]
+    async fn query_timeout_starts_at_execution() {
+        let mut config = ConfigOptions::new();
+        config.execution.query_timeout = Some(50);
+        let task_ctx = Arc::new(
+            TaskContext::default().with_session_config(SessionConfig::from(config)),
+        );
+        let schema = schema();
+
+        // The timeout does not start when the task context is created
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let exec = Arc::new(EmptyExec::new(Arc::clone(&schema)));
+        let batches = crate::collect(exec, Arc::clone(&task_ctx)).await.unwrap();
+        assert!(batches.is_empty());
+
+        let input = BlockingExec::new(schema, 1);
+        let mut stream = crate::execute_stream(Arc::new(input), task_ctx).unwrap();
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert!(matches!(err, DataFusionError::Timeout(_)), "{err}");
+    }
+
+    #[test]
+    fn deadline_stream_requires_time_driver() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let deadline = Instant::now() + Duration::from_secs(60);
+        let task_ctx = Arc::new(TaskContext::default().with_deadline(Some(deadline)));
+        let input = Arc::new(BlockingExec::new(schema(), 1));
+
+        let err = runtime
+            .block_on(async { crate::execute_stream(input, task_ctx) })
+            .map(|_| ())
+            .unwrap_err();
+        assert_contains!(err.to_string(), "time driver enabled");
+    }
+
     #[tokio::test]
     /// Ensure that if an error is received in one stream, the
     /// `RecordBatchReceiverStream` stops early and does not drive
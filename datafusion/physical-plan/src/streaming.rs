@@ -22,7 +22,7 @@ use std::fmt::Debug;
 use std::sync::Arc;
 
 use super::{DisplayAs, DisplayFormatType, PlanProperties};
-use crate::coop::make_cooperative;
+use crate::coop::cooperative;
 use crate::display::{display_orderings, ProjectSchemaDisplay};
 use crate::execution_plan::{Boundedness, EmissionType, SchedulingType};
 use crate::limit::LimitStream;
@@ -274,7 +274,13 @@ impl ExecutionPlan for StreamingTableExec {
             )),
             None => stream,
         };
-        let stream = make_cooperative(projected_stream);
+        let stream = Box::pin(
+            cooperative(RecordBatchStreamAdapter::new(
+                projected_stream.schema(),
+                projected_stream,
+            ))
+            .with_deadline(ctx.deadline()),
+        );
 
         Ok(match self.limit {
             None => stream,
@@ -735,11 +735,14 @@ mod tests {
     use crate::collect;
     use crate::expressions::col;
     use crate::streaming::StreamingTableExec;
-    use crate::test::assert_is_pending;
     use crate::test::exec::{assert_strong_count_converges_to_zero, BlockingExec};
+    use crate::test::{assert_is_pending, TestMemoryExec};
 
+    use arrow::array::{Int32Array, RecordBatch};
     use arrow::compute::SortOptions;
     use arrow_schema::{DataType, Field};
+    use datafusion_common::test_util::batches_to_string;
+    use datafusion_execution::runtime_env::RuntimeEnvBuilder;
     use datafusion_execution::TaskContext;
     use datafusion_functions_aggregate::count::count_udaf;
     use InputOrderMode::{Linear, PartiallySorted, Sorted};
@@ -921,6 +924,69 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_window_agg_spill() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, false),
+        ]));
+        // 10 batches of 100 rows, with 30 rows in each window partition
+        let batches = (0..10)
+            .map(|i| {
+                let b = (i * 100..(i + 1) * 100).collect::<Vec<_>>();
+                let a = b.iter().map(|v| v / 30).collect::<Vec<_>>();
+                RecordBatch::try_new(
+                    Arc::clone(&schema),
+                    vec![Arc::new(Int32Array::from(a)), Arc::new(Int32Array::from(b))],
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let ordering = [PhysicalSortExpr::new_default(col("a", &schema)?)].into();
+        let input = TestMemoryExec::try_new(&[batches], Arc::clone(&schema), None)?
+            .try_with_sort_information(vec![ordering])?;
+        let input = Arc::new(TestMemoryExec::update_cache(Arc::new(input)));
+
+        let window_agg_exec = Arc::new(WindowAggExec::try_new(
+            vec![create_window_expr(
+                &WindowFunctionDefinition::AggregateUDF(count_udaf()),
+                "count".to_owned(),
+                &[col("b", &schema)?],
+                &[col("a", &schema)?],
+                &[],
+                Arc::new(WindowFrame::new(None)),
+                Arc::clone(&schema),
+                false,
+                false,
+                None,
+            )?],
+            input,
+            false,
+        )?);
+
+        let expected = collect(
+            Arc::clone(&window_agg_exec) as _,
+            Arc::new(TaskContext::default()),
+        )
+        .await?;
+        let metrics = window_agg_exec.metrics().unwrap();
+        assert_eq!(metrics.spill_count(), Some(0));
+
+        let runtime = RuntimeEnvBuilder::new()
+            .with_memory_limit(4000, 1.0)
+            .build_arc()?;
+        let task_ctx = TaskContext::default().with_runtime(runtime);
+        let actual =
+            collect(Arc::clone(&window_agg_exec) as _, Arc::new(task_ctx)).await?;
+
+        assert_eq!(batches_to_string(&actual), batches_to_string(&expected));
+        let metrics = window_agg_exec.metrics().unwrap();
+        assert!(metrics.spill_count().unwrap() > 0);
+        assert!(metrics.spilled_bytes().unwrap() > 0);
+        assert!(metrics.spilled_rows().unwrap() > 0);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_satisfy_nullable() -> Result<()> {
         let schema = create_test_schema()?;
@@ -18,13 +18,18 @@
 //! Stream and channel implementations for window function expressions.
 
 use std::any::Any;
+use std::ops::Range;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
 use super::utils::create_schema;
 use crate::execution_plan::EmissionType;
-use crate::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use crate::metrics::{
+    BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet, SpillMetrics,
+};
+use crate::spill::in_progress_spill_file::InProgressSpillFile;
+use crate::spill::spill_manager::SpillManager;
 use crate::windows::{
     calc_requirements, get_ordered_partition_by_indices, get_partition_by_sort_exprs,
     window_equivalence_properties,
@@ -35,14 +40,15 @@ use crate::{
     SendableRecordBatchStream, Statistics, WindowExpr,
 };
 
-use arrow::array::ArrayRef;
-use arrow::compute::{concat, concat_batches};
+use arrow::array::{ArrayRef, UInt32Array};
+use arrow::compute::{concat, concat_batches, take_record_batch};
 use arrow::datatypes::SchemaRef;
 use arrow::error::ArrowError;
 use arrow::record_batch::RecordBatch;
 use datafusion_common::stats::Precision;
 use datafusion_common::utils::{evaluate_partition_ranges, transpose};
 use datafusion_common::{internal_err, Result};
+use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
 use datafusion_execution::TaskContext;
 use datafusion_physical_expr_common::sort_expr::{
     OrderingRequirements, PhysicalSortExpr,
@@ -274,7 +280,23 @@ impl ExecutionPlan for WindowAggExec {
         partition: usize,
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
-        let input = self.input.execute(partition, context)?;
+        let input = self.input.execute(partition, Arc::clone(&context))?;
+        let spill_manager =
+            context
+                .runtime_env()
+                .disk_manager
+                .tmp_files_enabled()
+                .then(|| {
+                    SpillManager::new(
+                        context.runtime_env(),
+                        SpillMetrics::new(&self.metrics, partition),
+                        self.input.schema(),
+                    )
+                    .with_compression_type(context.session_config().spill_compression())
+                });
+        let reservation = MemoryConsumer::new(format!("WindowAggStream[{partition}]"))
+            .with_can_spill(spill_manager.is_some())
+            .register(context.memory_pool());
         let stream = Box::pin(WindowAggStream::new(
             Arc::clone(&self.schema),
             self.window_expr.clone(),
@@ -282,6 +304,8 @@ impl ExecutionPlan for WindowAggExec {
             BaselineMetrics::new(&self.metrics, partition),
             self.partition_by_sort_keys()?,
             self.ordered_partition_by_indices.clone(),
+            reservation,
+            spill_manager,
         )?);
         Ok(stream)
     }
@@ -315,6 +339,12 @@ fn compute_window_aggregates(
 }
 
 /// stream for window aggregation plan
+///
+/// The input is buffered in memory until it is exhausted. If the buffered
+/// batches exceed the memory limit, they are spilled to disk, and read back
+/// once the input is exhausted. While reading back, the complete window
+/// partitions are emitted whenever memory is tight, so only the largest
+/// window partition has to fit in memory.
 pub struct WindowAggStream {
     schema: SchemaRef,
     input: SendableRecordBatchStream,
@@ -324,10 +354,19 @@ pub struct WindowAggStream {
     partition_by_sort_keys: Vec<PhysicalSortExpr>,
     baseline_metrics: BaselineMetrics,
     ordered_partition_by_indices: Vec<usize>,
+    /// Memory reservation for `batches`
+    reservation: MemoryReservation,
+    /// Spill manager, if spilling is possible
+    spill_manager: Option<SpillManager>,
+    /// Spill file the input is written to once memory is exhausted
+    in_progress_spill_file: Option<InProgressSpillFile>,
+    /// Reader of the spilled input, once the input is exhausted
+    spill_reader: Option<SendableRecordBatchStream>,
 }
 
 impl WindowAggStream {
     /// Create a new WindowAggStream
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         schema: SchemaRef,
         window_expr: Vec<Arc<dyn WindowExpr>>,
@@ -335,6 +374,8 @@ impl WindowAggStream {
         baseline_metrics: BaselineMetrics,
         partition_by_sort_keys: Vec<PhysicalSortExpr>,
         ordered_partition_by_indices: Vec<usize>,
+        reservation: MemoryReservation,
+        spill_manager: Option<SpillManager>,
     ) -> Result<Self> {
         // In WindowAggExec all partition by columns should be ordered.
         if window_expr[0].partition_by().len() != ordered_partition_by_indices.len() {
@@ -349,25 +390,22 @@ impl WindowAggStream {
             baseline_metrics,
             partition_by_sort_keys,
             ordered_partition_by_indices,
+            reservation,
+            spill_manager,
+            in_progress_spill_file: None,
+            spill_reader: None,
         })
     }
 
-    fn compute_aggregates(&self) -> Result<Option<RecordBatch>> {
+    fn compute_aggregates(&self, batch: &RecordBatch) -> Result<Option<RecordBatch>> {
         // record compute time on drop
         let _timer = self.baseline_metrics.elapsed_compute().timer();
 
-        let batch = concat_batches(&self.input.schema(), &self.batches)?;
         if batch.num_rows() == 0 {
             return Ok(None);
         }
 
-        let partition_by_sort_keys = self
-            .ordered_partition_by_indices
-            .iter()
-            .map(|idx| self.partition_by_sort_keys[*idx].evaluate_to_sort_column(&batch))
-            .collect::<Result<Vec<_>>>()?;
-        let partition_points =
-            evaluate_partition_ranges(batch.num_rows(), &partition_by_sort_keys)?;
+        let partition_points = self.partition_ranges(batch)?;
 
         let mut partition_results = vec![];
         // Calculate window cols
@@ -396,6 +434,88 @@ impl WindowAggStream {
             batch_columns,
         )?))
     }
+
+    /// Returns the ranges of the window partitions in `batch`
+    fn partition_ranges(&self, batch: &RecordBatch) -> Result<Vec<Range<usize>>> {
+        let partition_by_sort_keys = self
+            .ordered_partition_by_indices
+            .iter()
+            .map(|idx| self.partition_by_sort_keys[*idx].evaluate_to_sort_column(batch))
+            .collect::<Result<Vec<_>>>()?;
+        evaluate_partition_ranges(batch.num_rows(), &partition_by_sort_keys)
+    }
+
+    /// Buffers an input batch, spilling all buffered batches if they do not
+    /// fit in memory
+    fn buffer_input_batch(&mut self, batch: RecordBatch) -> Result<()> {
+        if let Some(spill_file) = self.in_progress_spill_file.as_mut() {
+            return spill_file.append_batch(&batch);
+        }
+
+        match self.reservation.try_grow(batch.get_array_memory_size()) {
+            Ok(()) => self.batches.push(batch),
+            Err(e) => {
+                let Some(spill_manager) = &self.spill_manager else {
+                    return Err(e);
+                };
+                let mut spill_file =
+                    spill_manager.create_in_progress_file("WindowAggExec")?;
+                for batch in self.batches.drain(..).chain([batch]) {
+                    spill_file.append_batch(&batch)?;
+                }
+                self.reservation.free();
+                self.in_progress_spill_file = Some(spill_file);
+            }
+        }
+        Ok(())
+    }
+
+    /// Buffers a batch read back from the spill file. If memory is tight,
+    /// returns the window aggregates of the complete window partitions, and
+    /// only keeps the last (possibly incomplete) partition buffered.
+    fn buffer_spilled_batch(
+        &mut self,
+        batch: RecordBatch,
+    ) -> Result<Option<RecordBatch>> {
+        let tight = self
+            .reservation
+            .try_grow(batch.get_array_memory_size())
+            .is_err();
+        self.batches.push(batch);
+        if !tight {
+            return Ok(None);
+        }
+
+        let batch = concat_batches(&self.input.schema(), &self.batches)?;
+        self.batches.clear();
+        self.reservation.free();
+
+        let last_partition_start = self
+            .partition_ranges(&batch)?
+            .last()
+            .map_or(0, |range| range.start);
+        // Copy the last partition, so that the memory of the complete
+        // partitions is released once they are emitted
+        let indices = UInt32Array::from_iter_values(
+            last_partition_start as u32..batch.num_rows() as u32,
+        );
+        let last_partition = take_record_batch(&batch, &indices)?;
+        self.reservation
+            .try_grow(last_partition.get_array_memory_size())?;
+        self.batches.push(last_partition);
+
+        self.compute_aggregates(&batch.slice(0, last_partition_start))
+    }
+
+    /// Computes the window aggregates of all buffered batches
+    fn finish(&mut self) -> Result<Option<RecordBatch>> {
+        self.finished = true;
+        let batch = concat_batches(&self.input.schema(), &self.batches)?;
+        self.batches.clear();
+        let result = self.compute_aggregates(&batch);
+        self.reservation.free();
+        result
+    }
 }
 
 impl Stream for WindowAggStream {
@@ -421,23 +541,40 @@ impl WindowAggStream {
         }
 
         loop {
-            return Poll::Ready(Some(match ready!(self.input.poll_next_unpin(cx)) {
-                Some(Ok(batch)) => {
-                    self.batches.push(batch);
-                    continue;
+            // Read back the spilled input
+            if let Some(spill_reader) = self.spill_reader.as_mut() {
+                match ready!(spill_reader.poll_next_unpin(cx)) {
+                    Some(Ok(batch)) => match self.buffer_spilled_batch(batch)? {
+                        Some(result) => return Poll::Ready(Some(Ok(result))),
+                        None => continue,
+                    },
+                    Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                    None => self.spill_reader = None,
                 }
-                Some(Err(e)) => Err(e),
-                None => {
-                    let Some(result) = self.compute_aggregates()? else {
-                        return Poll::Ready(None);
-                    };
-                    self.finished = true;
-                    // Empty record batches should not be emitted.
-                    // They need to be treated as  [`Option<RecordBatch>`]es and handled separately
-                    debug_assert!(result.num_rows() > 0);
-                    Ok(result)
+            } else {
+                match ready!(self.input.poll_next_unpin(cx)) {
+                    Some(Ok(batch)) => {
+                        self.buffer_input_batch(batch)?;
+                        continue;
+                    }
+                    Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                    None => {
+                        if let (Some(mut spill_file), Some(spill_manager)) =
+                            (self.in_progress_spill_file.take(), &self.spill_manager)
+                        {
+                            if let Some(file) = spill_file.finish()? {
+                                self.spill_reader =
+                                    Some(spill_manager.read_spill_as_stream(file, None)?);
+                                continue;
+                            }
+                        }
+                    }
                 }
-            }));
+            }
+
+            // Empty record batches should not be emitted.
+            // They need to be treated as  [`Option<RecordBatch>`]es and handled separately
+            return Poll::Ready(self.finish()?.map(Ok));
         }
     }
 }
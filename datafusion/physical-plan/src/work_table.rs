@@ -189,7 +189,7 @@ impl ExecutionPlan for WorkTableExec {
     fn execute(
         &self,
         partition: usize,
-        _context: Arc<TaskContext>,
+        context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
         // WorkTable streams must be the plan base.
         if partition != 0 {
@@ -202,7 +202,9 @@ impl ExecutionPlan for WorkTableExec {
         let stream =
             MemoryStream::try_new(batch.batches, Arc::clone(&self.schema), None)?
                 .with_reservation(batch.reservation);
-        Ok(Box::pin(cooperative(stream)))
+        Ok(Box::pin(
+            cooperative(stream).with_deadline(context.deadline()),
+        ))
     }
 
     fn metrics(&self) -> Option<MetricsSet> {
@@ -696,6 +696,7 @@ impl From<&protobuf::ColumnStats> for ColumnStatistics {
             } else {
                 Precision::Absent
             },
+            histogram: None,
         }
     }
 }
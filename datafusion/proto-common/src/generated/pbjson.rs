@@ -4456,6 +4456,7 @@ impl serde::Serialize for JoinType {
             Self::Rightanti => "RIGHTANTI",
             Self::Leftmark => "LEFTMARK",
             Self::Rightmark => "RIGHTMARK",
+            Self::Asof => "ASOF",
         };
         serializer.serialize_str(variant)
     }
@@ -4477,6 +4478,7 @@ impl<'de> serde::Deserialize<'de> for JoinType {
             "RIGHTANTI",
             "LEFTMARK",
             "RIGHTMARK",
+            "ASOF",
         ];
 
         struct GeneratedVisitor;
@@ -4527,6 +4529,7 @@ impl<'de> serde::Deserialize<'de> for JoinType {
                     "RIGHTANTI" => Ok(JoinType::Rightanti),
                     "LEFTMARK" => Ok(JoinType::Leftmark),
                     "RIGHTMARK" => Ok(JoinType::Rightmark),
+                    "ASOF" => Ok(JoinType::Asof),
                     _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
                 }
             }
@@ -467,6 +467,23 @@ pub fn parse_protobuf_partitioning(
                     codec,
                 )
             }
+            Some(protobuf::partitioning::PartitionMethod::Range(range_repartition)) => {
+                let sort_exprs = parse_physical_sort_exprs(
+                    &range_repartition.sort_expr,
+                    ctx,
+                    input_schema,
+                    codec,
+                )?;
+                let Some(ordering) = LexOrdering::new(sort_exprs) else {
+                    return Err(proto_error(
+                        "Range partitioning requires at least one sort expression",
+                    ));
+                };
+                Ok(Some(Partitioning::Range(
+                    ordering,
+                    range_repartition.partition_count as usize,
+                )))
+            }
             Some(protobuf::partitioning::PartitionMethod::Unknown(partition_count)) => {
                 Ok(Some(Partitioning::UnknownPartitioning(
                     *partition_count as usize,
@@ -427,6 +427,18 @@ pub fn serialize_partitioning(
                 )),
             }
         }
+        Partitioning::Range(ordering, partition_count) => {
+            let serialized_sort_exprs =
+                serialize_physical_sort_exprs(ordering.iter().cloned(), codec)?;
+            protobuf::Partitioning {
+                partition_method: Some(protobuf::partitioning::PartitionMethod::Range(
+                    protobuf::PhysicalRangeRepartition {
+                        sort_expr: serialized_sort_exprs,
+                        partition_count: *partition_count as u64,
+                    },
+                )),
+            }
+        }
         Partitioning::UnknownPartitioning(partition_count) => protobuf::Partitioning {
             partition_method: Some(protobuf::partitioning::PartitionMethod::Unknown(
                 *partition_count as u64,
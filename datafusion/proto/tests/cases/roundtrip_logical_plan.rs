@@ -407,6 +407,7 @@ async fn roundtrip_logical_plan_dml() -> Result<()> {
         "INSERT OR REPLACE INTO T1 VALUES (1, null)",
         "DELETE FROM T1",
         "UPDATE T1 SET a = 1",
+        "MERGE INTO T1 USING (SELECT 1 AS a) s ON T1.a = s.a WHEN MATCHED THEN DELETE",
         "CREATE TABLE T2 AS SELECT * FROM T1",
     ];
     for query in queries {
@@ -797,6 +797,31 @@ fn roundtrip_sort() -> Result<()> {
     )))
 }
 
+#[test]
+fn roundtrip_range_repartition() -> Result<()> {
+    let field_a = Field::new("a", DataType::Boolean, false);
+    let field_b = Field::new("b", DataType::Int64, false);
+    let schema = Arc::new(Schema::new(vec![field_a, field_b]));
+    let ordering = [
+        PhysicalSortExpr {
+            expr: col("b", &schema)?,
+            options: SortOptions {
+                descending: true,
+                nulls_first: false,
+            },
+        },
+        PhysicalSortExpr {
+            expr: col("a", &schema)?,
+            options: SortOptions::default(),
+        },
+    ]
+    .into();
+    roundtrip_test(Arc::new(RepartitionExec::try_new(
+        Arc::new(EmptyExec::new(schema)),
+        Partitioning::Range(ordering, 4),
+    )?))
+}
+
 #[test]
 fn roundtrip_sort_preserve_partitioning() -> Result<()> {
     let field_a = Field::new("a", DataType::Boolean, false);
@@ -15,6 +15,8 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::collections::HashMap;
+use std::ops::ControlFlow;
 use std::sync::Arc;
 
 use crate::planner::{ContextProvider, PlannerContext, SqlToRel};
@@ -24,16 +26,26 @@ use datafusion_common::{
     tree_node::{TreeNode, TreeNodeRecursion},
     Result,
 };
-use datafusion_expr::{LogicalPlan, LogicalPlanBuilder, TableSource};
-use sqlparser::ast::{Query, SetExpr, SetOperator, With};
+use datafusion_expr::{Expr, LogicalPlan, LogicalPlanBuilder, TableSource};
+use sqlparser::ast::{
+    visit_relations, CteAsMaterialized, ObjectName, Query, SetExpr, SetOperator, Visit,
+    With,
+};
 
 impl<S: ContextProvider> SqlToRel<'_, S> {
+    /// Plans the CTEs of the `WITH` clause of `query`, which has already been
+    /// taken out of `query`
     pub(super) fn plan_with_clause(
         &self,
         with: With,
+        query: &Query,
         planner_context: &mut PlannerContext,
     ) -> Result<()> {
         let is_recursive = with.recursive;
+        // CTEs are referenced by the query and by the CTEs declared after them
+        let mut references = HashMap::new();
+        self.count_relation_references(query, &mut references);
+        self.count_relation_references(&with, &mut references);
         // Process CTEs from top to bottom
         for cte in with.cte_tables {
             // A `WITH` block can't use the same name more than once
@@ -54,12 +66,80 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
             // Each `WITH` block can change the column names in the last
             // projection (e.g. "WITH table(t1, t2) AS SELECT 1, 2").
             let final_plan = self.apply_table_alias(cte_plan, cte.alias)?;
+            let num_references = references.get(&cte_name).copied().unwrap_or(0);
+            let final_plan = if !is_recursive
+                && self.should_materialize_cte(
+                    &final_plan,
+                    cte.materialized.as_ref(),
+                    num_references,
+                )? {
+                self.materialize_cte(&cte_name, final_plan)?
+            } else {
+                final_plan
+            };
             // Export the CTE to the outer query
             planner_context.insert_cte(cte_name, final_plan);
         }
         Ok(())
     }
 
+    /// Counts the references to each relation with an unqualified name in
+    /// `node`, by normalized name
+    fn count_relation_references<V: Visit>(
+        &self,
+        node: &V,
+        references: &mut HashMap<String, usize>,
+    ) {
+        let _ = visit_relations(node, |relation: &ObjectName| {
+            if let [part] = relation.0.as_slice() {
+                if let Some(ident) = part.as_ident() {
+                    let name = self.ident_normalizer.normalize(ident.clone());
+                    *references.entry(name).or_default() += 1;
+                }
+            }
+            ControlFlow::<()>::Continue(())
+        });
+    }
+
+    /// Returns whether the non-recursive CTE with the logical plan `plan`
+    /// should be computed once for all its references, rather than inlined
+    /// at each of them
+    fn should_materialize_cte(
+        &self,
+        plan: &LogicalPlan,
+        materialized: Option<&CteAsMaterialized>,
+        num_references: usize,
+    ) -> Result<bool> {
+        let should_materialize = match materialized {
+            Some(CteAsMaterialized::Materialized) => true,
+            Some(CteAsMaterialized::NotMaterialized) => false,
+            None => {
+                self.context_provider
+                    .options()
+                    .execution
+                    .enable_cte_materialization
+                    && num_references > 1
+                    && is_expensive_plan(plan)?
+            }
+        };
+        // The placeholders and outer references of a materialized CTE could
+        // not be replaced by values, as its plan is not part of the query plan
+        Ok(should_materialize && !has_parameters(plan)?)
+    }
+
+    /// Returns the plan that reads the results of the materialized CTE
+    /// `cte_name`, or `plan` if the CTE can not be materialized
+    fn materialize_cte(&self, cte_name: &str, plan: LogicalPlan) -> Result<LogicalPlan> {
+        let plan = Arc::new(plan);
+        match self
+            .context_provider
+            .create_materialized_cte(cte_name, Arc::clone(&plan))?
+        {
+            Some(source) => LogicalPlanBuilder::scan(cte_name, source, None)?.build(),
+            None => Ok(Arc::unwrap_or_clone(plan)),
+        }
+    }
+
     fn non_recursive_cte(
         &self,
         cte_query: Query,
@@ -203,3 +283,37 @@ fn has_work_table_reference(
     .unwrap();
     has_reference
 }
+
+/// Returns whether computing `plan` is expensive enough for a CTE with this
+/// plan to be materialized rather than computed once per reference
+fn is_expensive_plan(plan: &LogicalPlan) -> Result<bool> {
+    plan.exists(|node| {
+        Ok(matches!(
+            node,
+            LogicalPlan::Aggregate(_)
+                | LogicalPlan::Join(_)
+                | LogicalPlan::Window(_)
+                | LogicalPlan::Sort(_)
+                | LogicalPlan::Distinct(_)
+                | LogicalPlan::RecursiveQuery(_)
+        ))
+    })
+}
+
+/// Returns whether `plan` or its subqueries contain placeholders or outer
+/// references
+fn has_parameters(plan: &LogicalPlan) -> Result<bool> {
+    let mut has_parameters = false;
+    plan.apply_with_subqueries(|node| {
+        node.apply_expressions(|expr| {
+            has_parameters = expr.contains_outer()
+                || expr.exists(|expr| Ok(matches!(expr, Expr::Placeholder(_))))?;
+            Ok(if has_parameters {
+                TreeNodeRecursion::Stop
+            } else {
+                TreeNodeRecursion::Continue
+            })
+        })
+    })?;
+    Ok(has_parameters)
+}
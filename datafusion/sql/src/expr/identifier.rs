@@ -69,8 +69,14 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 return Ok(Expr::Column(column));
             }
 
-            // Check the outer query schema
-            if let Some(outer) = planner_context.outer_query_schema() {
+            // Check the outer query schema, then the schema of the queries enclosing it
+            for outer in [
+                planner_context.outer_query_schema(),
+                planner_context.enclosing_query_schema(),
+            ]
+            .into_iter()
+            .flatten()
+            {
                 if let Ok((qualifier, field)) =
                     outer.qualified_field_with_unqualified_name(normalize_ident.as_str())
                 {
@@ -167,7 +173,12 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                     } else {
                         // Check the outer_query_schema and try to find a match
                         if let Some(outer) = planner_context.outer_query_schema() {
-                            let search_result = search_dfschema(&ids, outer);
+                            let search_result =
+                                search_dfschema(&ids, outer).or_else(|| {
+                                    planner_context.enclosing_query_schema().and_then(
+                                        |enclosing| search_dfschema(&ids, enclosing),
+                                    )
+                                });
                             match search_result {
                                 // Found matching field with spare identifier(s) for nested field(s) in structure
                                 Some((field, qualifier, nested_names))
@@ -510,6 +510,10 @@ impl<'a> DFParser<'a> {
                         self.parser.next_token(); // EXPLAIN
                         self.parse_explain()
                     }
+                    Keyword::ANALYZE => {
+                        self.parser.next_token(); // ANALYZE
+                        self.parse_analyze()
+                    }
                     _ => {
                         // use sqlparser-rs parser
                         self.parse_and_handle_statement()
@@ -563,6 +567,41 @@ impl<'a> DFParser<'a> {
             })
     }
 
+    /// Parse a SQL `ANALYZE TABLE` statement, for example:
+    /// ```sql
+    /// ANALYZE TABLE t [[FOR] COLUMNS [(]a, b[)]]
+    /// ```
+    pub fn parse_analyze(&mut self) -> Result<Statement, DataFusionError> {
+        self.parser.expect_keyword(Keyword::TABLE)?;
+        let table_name = self.parser.parse_object_name(true)?;
+        let for_columns = self.parser.parse_keyword(Keyword::FOR);
+        let columns = if self.parser.parse_keyword(Keyword::COLUMNS) {
+            let parenthesized = self.parser.consume_token(&Token::LParen);
+            let columns = self
+                .parser
+                .parse_comma_separated(|parser| parser.parse_identifier())?;
+            if parenthesized {
+                self.parser.expect_token(&Token::RParen)?;
+            }
+            columns
+        } else if for_columns {
+            return self.expected("COLUMNS", self.parser.peek_token());
+        } else {
+            vec![]
+        };
+
+        Ok(Statement::Statement(Box::new(SQLStatement::Analyze {
+            table_name,
+            partitions: None,
+            for_columns: !columns.is_empty(),
+            columns,
+            cache_metadata: false,
+            noscan: false,
+            compute_statistics: true,
+            has_table_keyword: true,
+        })))
+    }
+
     /// Parse a SQL `COPY TO` statement
     pub fn parse_copy(&mut self) -> Result<Statement, DataFusionError> {
         // parse as a query
@@ -1917,6 +1956,30 @@ mod tests {
         one_statement_parses_to(sql, sql)
     }
 
+    #[test]
+    fn analyze_table() -> Result<(), DataFusionError> {
+        let analyze = |columns: Vec<&str>| {
+            Statement::Statement(Box::new(SQLStatement::Analyze {
+                table_name: ObjectName::from(vec![Ident::new("t")]),
+                partitions: None,
+                for_columns: !columns.is_empty(),
+                columns: columns.into_iter().map(Ident::new).collect(),
+                cache_metadata: false,
+                noscan: false,
+                compute_statistics: true,
+                has_table_keyword: true,
+            }))
+        };
+        expect_parse_ok("ANALYZE TABLE t", analyze(vec![]))?;
+        expect_parse_ok("ANALYZE TABLE t COLUMNS a, b", analyze(vec!["a", "b"]))?;
+        expect_parse_ok("ANALYZE TABLE t COLUMNS (a, b)", analyze(vec!["a", "b"]))?;
+        expect_parse_ok("ANALYZE TABLE t FOR COLUMNS (a)", analyze(vec!["a"]))?;
+
+        expect_parse_error("ANALYZE t", "Expected: TABLE, found: t");
+        expect_parse_error("ANALYZE TABLE t FOR a", "Expected: COLUMNS, found: a");
+        Ok(())
+    }
+
     #[test]
     /// Checks the recursion limit works for sql queries
     /// Recursion can happen easily with binary exprs (i.e, AND or OR)
@@ -262,6 +262,9 @@ pub struct PlannerContext {
     ctes: HashMap<String, Arc<LogicalPlan>>,
     /// The query schema of the outer query plan, used to resolve the columns in subquery
     outer_query_schema: Option<DFSchemaRef>,
+    /// The merged schemas of the queries enclosing the outer query plan, used to
+    /// resolve the columns in nested subqueries that are not in the outer query
+    enclosing_query_schema: Option<DFSchemaRef>,
     /// The joined schemas of all FROM clauses planned so far. When planning LATERAL
     /// FROM clauses, this should become a suffix of the `outer_query_schema`.
     outer_from_schema: Option<DFSchemaRef>,
@@ -282,6 +285,7 @@ impl PlannerContext {
             prepare_param_data_types: Arc::new(vec![]),
             ctes: HashMap::new(),
             outer_query_schema: None,
+            enclosing_query_schema: None,
             outer_from_schema: None,
             create_table_schema: None,
         }
@@ -311,6 +315,49 @@ impl PlannerContext {
         schema
     }
 
+    // Return a reference to the schema of the queries enclosing the outer query
+    pub fn enclosing_query_schema(&self) -> Option<&DFSchema> {
+        self.enclosing_query_schema.as_ref().map(|s| s.as_ref())
+    }
+
+    /// Sets the outer query schemas for planning a subquery of a query with
+    /// `schema`, returning the existing ones, to be restored with
+    /// [`Self::exit_subquery`]
+    pub fn enter_subquery(
+        &mut self,
+        schema: &DFSchema,
+    ) -> (Option<DFSchemaRef>, Option<DFSchemaRef>) {
+        // The columns of the closest queries hide the ones of enclosing queries
+        let enclosing_query_schema = match (
+            self.outer_query_schema.as_ref(),
+            self.enclosing_query_schema.as_ref(),
+        ) {
+            (Some(outer), Some(enclosing)) => {
+                let mut merged = outer.as_ref().clone();
+                merged.merge(enclosing);
+                Some(Arc::new(merged))
+            }
+            (outer, enclosing) => outer.or(enclosing).cloned(),
+        };
+        let old_enclosing_query_schema =
+            std::mem::replace(&mut self.enclosing_query_schema, enclosing_query_schema);
+        let old_outer_query_schema =
+            self.set_outer_query_schema(Some(Arc::new(schema.clone())));
+        (old_outer_query_schema, old_enclosing_query_schema)
+    }
+
+    /// Restores the outer query schemas returned by [`Self::enter_subquery`]
+    pub fn exit_subquery(
+        &mut self,
+        (outer_query_schema, enclosing_query_schema): (
+            Option<DFSchemaRef>,
+            Option<DFSchemaRef>,
+        ),
+    ) {
+        self.outer_query_schema = outer_query_schema;
+        self.enclosing_query_schema = enclosing_query_schema;
+    }
+
     pub fn set_table_schema(
         &mut self,
         mut schema: Option<DFSchemaRef>,
@@ -37,7 +37,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
     /// Generate a logical plan from an SQL query/subquery
     pub(crate) fn query_to_plan(
         &self,
-        query: Query,
+        mut query: Query,
         outer_planner_context: &mut PlannerContext,
     ) -> Result<LogicalPlan> {
         // Each query has its own planner context, including CTEs that are visible within that query.
@@ -45,8 +45,8 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         let mut query_plan_context = outer_planner_context.clone();
         let planner_context = &mut query_plan_context;
 
-        if let Some(with) = query.with {
-            self.plan_with_clause(with, planner_context)?;
+        if let Some(with) = query.with.take() {
+            self.plan_with_clause(with, &query, planner_context)?;
         }
 
         let set_expr = *query.body;
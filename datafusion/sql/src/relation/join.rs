@@ -17,9 +17,14 @@
 
 use crate::planner::{ContextProvider, PlannerContext, SqlToRel};
 use datafusion_common::{not_impl_err, plan_datafusion_err, Column, Result};
-use datafusion_expr::{JoinType, LogicalPlan, LogicalPlanBuilder};
+use datafusion_expr::expr_rewriter::normalize_col_with_schemas_and_ambiguity_check;
+use datafusion_expr::utils::{find_valid_equijoin_key_pair, split_conjunction};
+use datafusion_expr::{
+    BinaryExpr, Expr, JoinType, LogicalPlan, LogicalPlanBuilder, Operator,
+};
 use sqlparser::ast::{
-    Join, JoinConstraint, JoinOperator, ObjectName, TableFactor, TableWithJoins,
+    Expr as SQLExpr, Join, JoinConstraint, JoinOperator, ObjectName, TableFactor,
+    TableWithJoins,
 };
 use std::collections::HashSet;
 
@@ -96,6 +101,16 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 self.parse_join(left, right, constraint, JoinType::Full, planner_context)
             }
             JoinOperator::CrossJoin => self.parse_cross_join(left, right),
+            JoinOperator::AsOf {
+                match_condition,
+                constraint,
+            } => self.parse_asof_join(
+                left,
+                right,
+                match_condition,
+                constraint,
+                planner_context,
+            ),
             other => not_impl_err!("Unsupported JOIN operator {other:?}"),
         }
     }
@@ -108,6 +123,112 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         LogicalPlanBuilder::from(left).cross_join(right)?.build()
     }
 
+    /// Plans `left ASOF JOIN right MATCH_CONDITION (...) [ON ... | USING (...)]`
+    ///
+    /// The `ON` clause may only contain equality predicates between the two
+    /// inputs, which are used as the join keys. The match condition becomes the
+    /// join filter, see [`datafusion_expr::Join::asof_match_condition`] for the
+    /// supported forms.
+    fn parse_asof_join(
+        &self,
+        left: LogicalPlan,
+        right: LogicalPlan,
+        match_condition: SQLExpr,
+        constraint: JoinConstraint,
+        planner_context: &mut PlannerContext,
+    ) -> Result<LogicalPlan> {
+        let join_schema = left.schema().join(right.schema())?;
+        let match_condition =
+            self.sql_to_expr(match_condition, &join_schema, planner_context)?;
+        let match_condition = normalize_col_with_schemas_and_ambiguity_check(
+            match_condition,
+            &[&[left.schema(), right.schema()]],
+            &[],
+        )?;
+
+        let is_using = matches!(constraint, JoinConstraint::Using(_));
+        let (left_keys, right_keys): (Vec<Expr>, Vec<Expr>) = match constraint {
+            JoinConstraint::On(sql_expr) => {
+                let expr = self.sql_to_expr(sql_expr, &join_schema, planner_context)?;
+                let expr = normalize_col_with_schemas_and_ambiguity_check(
+                    expr,
+                    &[&[left.schema(), right.schema()]],
+                    &[],
+                )?;
+                split_conjunction(&expr)
+                    .into_iter()
+                    .map(|predicate| {
+                        let key_pair = match predicate {
+                            Expr::BinaryExpr(BinaryExpr {
+                                left: l,
+                                op: Operator::Eq,
+                                right: r,
+                            }) => find_valid_equijoin_key_pair(
+                                l,
+                                r,
+                                left.schema(),
+                                right.schema(),
+                            )?,
+                            _ => None,
+                        };
+                        key_pair.ok_or_else(|| {
+                            plan_datafusion_err!(
+                                "ASOF JOIN only supports equality predicates between \
+                                 both inputs in the ON clause, got {predicate}"
+                            )
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?
+                    .into_iter()
+                    .unzip()
+            }
+            JoinConstraint::Using(object_names) => object_names
+                .into_iter()
+                .map(|object_name| {
+                    let ObjectName(mut parts) = object_name;
+                    let ident = (parts.len() == 1)
+                        .then(|| parts.swap_remove(0))
+                        .and_then(|part| part.as_ident().cloned())
+                        .ok_or_else(|| {
+                            plan_datafusion_err!("Expected identifier in USING clause")
+                        })?;
+                    let name = self.ident_normalizer.normalize(ident);
+                    let left_key =
+                        left.schema().qualified_field_with_unqualified_name(&name)?;
+                    let right_key = right
+                        .schema()
+                        .qualified_field_with_unqualified_name(&name)?;
+                    Ok((
+                        Expr::Column(Column::from(left_key)),
+                        Expr::Column(Column::from(right_key)),
+                    ))
+                })
+                .collect::<Result<Vec<_>>>()?
+                .into_iter()
+                .unzip(),
+            JoinConstraint::None => (vec![], vec![]),
+            JoinConstraint::Natural => {
+                return not_impl_err!("NATURAL is not supported for ASOF JOIN")
+            }
+        };
+
+        let mut plan = LogicalPlanBuilder::from(left)
+            .join_with_expr_keys(
+                right,
+                JoinType::AsOf,
+                (left_keys, right_keys),
+                Some(match_condition),
+            )?
+            .build()?;
+        if let LogicalPlan::Join(join) = &mut plan {
+            join.asof_match_condition()?;
+            if is_using {
+                join.join_constraint = datafusion_expr::JoinConstraint::Using;
+            }
+        }
+        Ok(plan)
+    }
+
     fn parse_join(
         &self,
         left: LogicalPlan,
@@ -0,0 +1,555 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::ops::ControlFlow;
+
+use crate::planner::{ContextProvider, PlannerContext, SqlToRel};
+use datafusion_common::tree_node::TreeNode;
+use datafusion_common::{
+    not_impl_err, plan_datafusion_err, plan_err, Column, DFSchema, Result,
+};
+use datafusion_expr::expr::WindowFunction;
+use datafusion_expr::{
+    lit, AfterMatchSkip, EmptyMatchesMode, Expr, ExprFunctionExt, LogicalPlan,
+    LogicalPlanBuilder, MatchMeasure, MatchMeasureKind, MatchPattern, MatchSymbol,
+    RowsPerMatch,
+};
+use sqlparser::ast::{
+    self, visit_expressions, visit_expressions_mut, Expr as SQLExpr, FunctionArg,
+    FunctionArgExpr, FunctionArguments, Ident, MatchRecognizePattern,
+    MatchRecognizeSymbol, Measure, OrderByExpr, RepetitionQuantifier, SymbolDefinition,
+};
+
+/// Prefix of the columns holding the values of `PREV` and `NEXT` calls
+const NAVIGATION_COLUMN_PREFIX: &str = "__match_recognize_navigation_";
+
+/// A `PREV` or `NEXT` call of a symbol definition
+#[derive(PartialEq)]
+struct Navigation {
+    /// `lag` or `lead`
+    function: &'static str,
+    argument: SQLExpr,
+    offset: i64,
+}
+
+impl<S: ContextProvider> SqlToRel<'_, S> {
+    /// Plans `input MATCH_RECOGNIZE (...)`
+    ///
+    /// Symbol definitions may only reference the current row. `PREV` and
+    /// `NEXT` are planned as `lag` and `lead` window functions below the
+    /// match, and symbols without a definition match every row.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn plan_match_recognize(
+        &self,
+        input: LogicalPlan,
+        partition_by: Vec<SQLExpr>,
+        order_by: Vec<OrderByExpr>,
+        measures: Vec<Measure>,
+        rows_per_match: Option<ast::RowsPerMatch>,
+        after_match_skip: Option<ast::AfterMatchSkip>,
+        pattern: MatchRecognizePattern,
+        definitions: Vec<SymbolDefinition>,
+        planner_context: &mut PlannerContext,
+    ) -> Result<LogicalPlan> {
+        let input_schema = input.schema().as_ref().clone();
+        let partition_by = partition_by
+            .into_iter()
+            .map(|expr| self.sql_to_expr(expr, &input_schema, planner_context))
+            .collect::<Result<Vec<_>>>()?;
+        let order_by = self.order_by_to_sort_expr(
+            order_by,
+            &input_schema,
+            planner_context,
+            false,
+            None,
+        )?;
+        let pattern = self.match_pattern(pattern)?;
+
+        let mut symbol_names = definitions
+            .iter()
+            .map(|definition| self.ident_normalizer.normalize(definition.symbol.clone()))
+            .collect::<Vec<_>>();
+        let num_defined = symbol_names.len();
+        for symbol in pattern.symbols() {
+            if !symbol_names.iter().any(|name| name == symbol) {
+                symbol_names.push(symbol.to_string());
+            }
+        }
+
+        let mut navigation = vec![];
+        let definitions = definitions
+            .into_iter()
+            .zip(&symbol_names)
+            .map(|(SymbolDefinition { mut definition, .. }, symbol)| {
+                self.rewrite_definition(
+                    symbol,
+                    &symbol_names,
+                    &mut definition,
+                    &mut navigation,
+                )?;
+                Ok(definition)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let plan = if navigation.is_empty() {
+            input
+        } else {
+            let window_exprs = navigation
+                .into_iter()
+                .map(|navigation| {
+                    let Navigation {
+                        function,
+                        argument,
+                        offset,
+                    } = navigation;
+                    let udwf = self
+                        .context_provider
+                        .get_window_meta(function)
+                        .ok_or_else(|| {
+                            plan_datafusion_err!(
+                                "There is no window function named {function}"
+                            )
+                        })?;
+                    let argument =
+                        self.sql_to_expr(argument, &input_schema, planner_context)?;
+                    Expr::from(WindowFunction::new(udwf, vec![argument, lit(offset)]))
+                        .partition_by(partition_by.clone())
+                        .order_by(order_by.clone())
+                        .build()
+                })
+                .collect::<Result<Vec<_>>>()?;
+            // Name the window function results as referenced by the definitions
+            let columns = input_schema
+                .columns()
+                .into_iter()
+                .map(Expr::Column)
+                .chain(window_exprs.iter().enumerate().map(|(i, expr)| {
+                    Expr::Column(Column::from_name(expr.schema_name().to_string()))
+                        .alias(format!("{NAVIGATION_COLUMN_PREFIX}{i}"))
+                }))
+                .collect::<Vec<_>>();
+            LogicalPlanBuilder::window_plan(input, window_exprs)
+                .and_then(|plan| LogicalPlanBuilder::from(plan).project(columns))?
+                .build()?
+        };
+        let schema = plan.schema().as_ref().clone();
+
+        let mut symbols = definitions
+            .into_iter()
+            .zip(&symbol_names)
+            .map(|(definition, symbol)| {
+                let predicate = self.sql_to_expr(definition, &schema, planner_context)?;
+                if contains_aggregate_or_window(&predicate) {
+                    return plan_err!(
+                        "Definition of symbol {symbol} can not contain aggregate or window functions, got {predicate}"
+                    );
+                }
+                Ok(MatchSymbol::new(symbol, predicate))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        symbols.extend(
+            symbol_names[num_defined..]
+                .iter()
+                .map(|symbol| MatchSymbol::new(symbol, lit(true))),
+        );
+
+        let measures = measures
+            .into_iter()
+            .map(|measure| {
+                self.match_measure(measure, &symbol_names, &schema, planner_context)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let rows_per_match = match rows_per_match {
+            None | Some(ast::RowsPerMatch::OneRow) => RowsPerMatch::OneRow,
+            Some(ast::RowsPerMatch::AllRows(mode)) => RowsPerMatch::AllRows(match mode {
+                None | Some(ast::EmptyMatchesMode::Show) => EmptyMatchesMode::Show,
+                Some(ast::EmptyMatchesMode::Omit) => EmptyMatchesMode::Omit,
+                Some(ast::EmptyMatchesMode::WithUnmatched) => {
+                    EmptyMatchesMode::WithUnmatched
+                }
+            }),
+        };
+        let after_match_skip = match after_match_skip {
+            None | Some(ast::AfterMatchSkip::PastLastRow) => AfterMatchSkip::PastLastRow,
+            Some(ast::AfterMatchSkip::ToNextRow) => AfterMatchSkip::ToNextRow,
+            Some(ast::AfterMatchSkip::ToFirst(symbol)) => {
+                AfterMatchSkip::ToFirst(self.ident_normalizer.normalize(symbol))
+            }
+            Some(ast::AfterMatchSkip::ToLast(symbol)) => {
+                AfterMatchSkip::ToLast(self.ident_normalizer.normalize(symbol))
+            }
+        };
+
+        let builder = LogicalPlanBuilder::from(plan).match_recognize(
+            partition_by,
+            order_by,
+            symbols,
+            pattern,
+            measures,
+            rows_per_match,
+            after_match_skip,
+        )?;
+        // Remove the navigation columns from all rows per match output
+        let has_navigation_columns = builder
+            .schema()
+            .columns()
+            .iter()
+            .any(|column| column.name.starts_with(NAVIGATION_COLUMN_PREFIX));
+        if !has_navigation_columns {
+            return builder.build();
+        }
+        let columns = builder
+            .schema()
+            .columns()
+            .into_iter()
+            .filter(|column| !column.name.starts_with(NAVIGATION_COLUMN_PREFIX))
+            .map(Expr::Column)
+            .collect::<Vec<_>>();
+        builder.project(columns)?.build()
+    }
+
+    /// Converts a row pattern, normalizing symbol names
+    fn match_pattern(&self, pattern: MatchRecognizePattern) -> Result<MatchPattern> {
+        let patterns = |patterns: Vec<MatchRecognizePattern>| {
+            patterns
+                .into_iter()
+                .map(|pattern| self.match_pattern(pattern))
+                .collect::<Result<Vec<_>>>()
+        };
+        Ok(match pattern {
+            MatchRecognizePattern::Symbol(symbol) => self.match_pattern_symbol(symbol),
+            MatchRecognizePattern::Exclude(symbol) => {
+                MatchPattern::Exclude(Box::new(self.match_pattern_symbol(symbol)))
+            }
+            MatchRecognizePattern::Permute(symbols) => MatchPattern::Permute(
+                symbols
+                    .into_iter()
+                    .map(|symbol| self.match_pattern_symbol(symbol))
+                    .collect(),
+            ),
+            MatchRecognizePattern::Concat(concat) => {
+                MatchPattern::Concat(patterns(concat)?)
+            }
+            MatchRecognizePattern::Group(pattern) => self.match_pattern(*pattern)?,
+            MatchRecognizePattern::Alternation(alternatives) => {
+                MatchPattern::Alternation(patterns(alternatives)?)
+            }
+            // A reluctant quantifier such as `f+?` is parsed as a quantifier
+            // applied to a quantified pattern, which would otherwise be planned
+            // as the greedy `(f+)?`
+            MatchRecognizePattern::Repetition(pattern, quantifier)
+                if matches!(*pattern, MatchRecognizePattern::Repetition(..)) =>
+            {
+                return not_impl_err!(
+                    "Reluctant and nested quantifiers are not supported in MATCH_RECOGNIZE patterns, got {pattern}{quantifier}"
+                );
+            }
+            MatchRecognizePattern::Repetition(pattern, quantifier) => {
+                let (min, max) = match quantifier {
+                    RepetitionQuantifier::ZeroOrMore => (0, None),
+                    RepetitionQuantifier::OneOrMore => (1, None),
+                    RepetitionQuantifier::AtMostOne => (0, Some(1)),
+                    RepetitionQuantifier::Exactly(n) => (n, Some(n)),
+                    RepetitionQuantifier::AtLeast(n) => (n, None),
+                    RepetitionQuantifier::AtMost(n) => (0, Some(n)),
+                    RepetitionQuantifier::Range(min, max) => (min, Some(max)),
+                };
+                MatchPattern::Repetition {
+                    pattern: Box::new(self.match_pattern(*pattern)?),
+                    min,
+                    max,
+                }
+            }
+        })
+    }
+
+    fn match_pattern_symbol(&self, symbol: MatchRecognizeSymbol) -> MatchPattern {
+        match symbol {
+            MatchRecognizeSymbol::Named(ident) => {
+                MatchPattern::Symbol(self.ident_normalizer.normalize(ident))
+            }
+            MatchRecognizeSymbol::Start => MatchPattern::PartitionStart,
+            MatchRecognizeSymbol::End => MatchPattern::PartitionEnd,
+        }
+    }
+
+    /// Rewrites the definition of `symbol` to only reference the current
+    /// row, replacing `PREV` and `NEXT` calls with columns that are
+    /// computed by the window functions added to `navigation`
+    fn rewrite_definition(
+        &self,
+        symbol: &str,
+        symbols: &[String],
+        definition: &mut SQLExpr,
+        navigation: &mut Vec<Navigation>,
+    ) -> Result<()> {
+        let mut result = Ok(());
+        let _ = visit_expressions_mut(definition, |expr| {
+            match self.rewrite_definition_expr(symbol, symbols, expr, navigation) {
+                Ok(()) => ControlFlow::Continue(()),
+                Err(e) => {
+                    result = Err(e);
+                    ControlFlow::Break(())
+                }
+            }
+        });
+        result
+    }
+
+    fn rewrite_definition_expr(
+        &self,
+        symbol: &str,
+        symbols: &[String],
+        expr: &mut SQLExpr,
+        navigation: &mut Vec<Navigation>,
+    ) -> Result<()> {
+        match expr {
+            SQLExpr::CompoundIdentifier(idents) if idents.len() == 2 => {
+                let qualifier = self.ident_normalizer.normalize(idents[0].clone());
+                if qualifier == symbol {
+                    *expr = SQLExpr::Identifier(idents[1].clone());
+                } else if symbols.contains(&qualifier) {
+                    return not_impl_err!(
+                        "Definition of symbol {symbol} can not reference rows mapped to {qualifier}"
+                    );
+                }
+            }
+            SQLExpr::Function(function) if function.over.is_none() => {
+                let function_name = match self.function_name(function).as_deref() {
+                    Some("prev") => "lag",
+                    Some("next") => "lead",
+                    _ => return Ok(()),
+                };
+                let (argument, offset) = match function_args(function).as_deref() {
+                    Some([argument]) => (argument.clone(), 1),
+                    Some([argument, SQLExpr::Value(value)]) => {
+                        let offset = match &value.value {
+                            ast::Value::Number(n, _) => n.parse::<i64>().ok(),
+                            _ => None,
+                        };
+                        match offset {
+                            Some(offset) if offset >= 0 => (argument.clone(), offset),
+                            _ => {
+                                return plan_err!(
+                                "{} offset must be a non-negative integer, got {value}",
+                                function.name
+                            )
+                            }
+                        }
+                    }
+                    _ => {
+                        return plan_err!(
+                            "{} requires an expression and an optional offset",
+                            function.name
+                        )
+                    }
+                };
+                let nested = visit_expressions(&argument, |expr| match expr {
+                    SQLExpr::Identifier(ident)
+                        if ident.value.starts_with(NAVIGATION_COLUMN_PREFIX) =>
+                    {
+                        ControlFlow::Break(())
+                    }
+                    _ => ControlFlow::Continue(()),
+                });
+                if nested.is_break() {
+                    return not_impl_err!("Nested PREV and NEXT calls are not supported");
+                }
+                let call = Navigation {
+                    function: function_name,
+                    argument,
+                    offset,
+                };
+                let index = match navigation.iter().position(|n| n == &call) {
+                    Some(index) => index,
+                    None => {
+                        navigation.push(call);
+                        navigation.len() - 1
+                    }
+                };
+                *expr = SQLExpr::Identifier(Ident::with_quote(
+                    '"',
+                    format!("{NAVIGATION_COLUMN_PREFIX}{index}"),
+                ));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Plans `expr AS alias` of `MEASURES`
+    fn match_measure(
+        &self,
+        measure: Measure,
+        symbols: &[String],
+        schema: &DFSchema,
+        planner_context: &mut PlannerContext,
+    ) -> Result<MatchMeasure> {
+        let Measure { expr, alias } = measure;
+        let unsupported = || {
+            not_impl_err!(
+                "MATCH_RECOGNIZE measures must be MATCH_NUMBER(), CLASSIFIER(), COUNT, FIRST, LAST or a column expression, got {expr}"
+            )
+        };
+        let function = match &expr {
+            SQLExpr::Function(function) if function.over.is_none() => {
+                self.function_name(function).map(|name| (name, function))
+            }
+            _ => None,
+        };
+        let kind = match function {
+            Some((name, function)) if name == "match_number" || name == "classifier" => {
+                if !function_args(function).is_some_and(|args| args.is_empty()) {
+                    return plan_err!("{} does not take arguments", function.name);
+                }
+                if name == "match_number" {
+                    MatchMeasureKind::MatchNumber
+                } else {
+                    MatchMeasureKind::Classifier
+                }
+            }
+            Some((name, function)) if name == "count" => {
+                let args = match &function.args {
+                    FunctionArguments::List(list) => list.args.as_slice(),
+                    _ => &[],
+                };
+                let symbol = match args {
+                    [FunctionArg::Unnamed(FunctionArgExpr::Wildcard)] => None,
+                    [FunctionArg::Unnamed(FunctionArgExpr::QualifiedWildcard(name))]
+                        if name.0.len() == 1 =>
+                    {
+                        let Some(ident) = name.0[0].as_ident() else {
+                            return unsupported();
+                        };
+                        let symbol = self.ident_normalizer.normalize(ident.clone());
+                        if !symbols.contains(&symbol) {
+                            return plan_err!(
+                                "Symbol {symbol} does not appear in the pattern"
+                            );
+                        }
+                        Some(symbol)
+                    }
+                    _ => return unsupported(),
+                };
+                MatchMeasureKind::Count { symbol }
+            }
+            Some((name, function)) if name == "first" || name == "last" => {
+                let Some([argument]) = function_args(function)
+                    .and_then(|args| <[SQLExpr; 1]>::try_from(args).ok())
+                else {
+                    return plan_err!("{} requires a single argument", function.name);
+                };
+                let (expr, symbol) =
+                    self.measure_expr(argument, symbols, schema, planner_context)?;
+                if name == "first" {
+                    MatchMeasureKind::First { expr, symbol }
+                } else {
+                    MatchMeasureKind::Last { expr, symbol }
+                }
+            }
+            _ => {
+                let (expr, symbol) =
+                    self.measure_expr(expr.clone(), symbols, schema, planner_context)?;
+                if contains_aggregate_or_window(&expr) {
+                    return unsupported();
+                }
+                MatchMeasureKind::Last { expr, symbol }
+            }
+        };
+        Ok(MatchMeasure::new(
+            kind,
+            self.ident_normalizer.normalize(alias),
+        ))
+    }
+
+    /// Plans the argument of a measure, returning the symbol its columns
+    /// are qualified with
+    fn measure_expr(
+        &self,
+        mut expr: SQLExpr,
+        symbols: &[String],
+        schema: &DFSchema,
+        planner_context: &mut PlannerContext,
+    ) -> Result<(Expr, Option<String>)> {
+        let mut symbol: Option<String> = None;
+        let mut result = Ok(());
+        let _ = visit_expressions_mut(&mut expr, |expr| {
+            let SQLExpr::CompoundIdentifier(idents) = expr else {
+                return ControlFlow::Continue(());
+            };
+            if idents.len() != 2 {
+                return ControlFlow::Continue(());
+            }
+            let qualifier = self.ident_normalizer.normalize(idents[0].clone());
+            if !symbols.contains(&qualifier) {
+                return ControlFlow::Continue(());
+            }
+            match &symbol {
+                Some(symbol) if symbol != &qualifier => {
+                    result = not_impl_err!(
+                        "A measure can only reference rows mapped to one symbol, got {symbol} and {qualifier}"
+                    );
+                    return ControlFlow::Break(());
+                }
+                _ => symbol = Some(qualifier),
+            }
+            *expr = SQLExpr::Identifier(idents[1].clone());
+            ControlFlow::Continue(())
+        });
+        result?;
+        let expr = self.sql_to_expr(expr, schema, planner_context)?;
+        Ok((expr, symbol))
+    }
+
+    /// Returns the normalized name of a function with an unqualified name
+    fn function_name(&self, function: &ast::Function) -> Option<String> {
+        match function.name.0.as_slice() {
+            [part] => part
+                .as_ident()
+                .map(|ident| self.ident_normalizer.normalize(ident.clone())),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the plain arguments of a function call, or `None` if it has
+/// named or wildcard arguments
+fn function_args(function: &ast::Function) -> Option<Vec<SQLExpr>> {
+    match &function.args {
+        FunctionArguments::None => Some(vec![]),
+        FunctionArguments::List(list) => list
+            .args
+            .iter()
+            .map(|arg| match arg {
+                FunctionArg::Unnamed(FunctionArgExpr::Expr(expr)) => Some(expr.clone()),
+                _ => None,
+            })
+            .collect(),
+        FunctionArguments::Subquery(_) => None,
+    }
+}
+
+fn contains_aggregate_or_window(expr: &Expr) -> bool {
+    expr.exists(|expr| {
+        Ok(matches!(
+            expr,
+            Expr::AggregateFunction(_) | Expr::WindowFunction(_)
+        ))
+    })
+    .unwrap_or(false)
+}
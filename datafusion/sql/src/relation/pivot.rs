@@ -0,0 +1,110 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::planner::{ContextProvider, PlannerContext, SqlToRel};
+use datafusion_common::{not_impl_err, plan_err, Column, DFSchema, Result};
+use datafusion_expr::{Expr, LogicalPlan, LogicalPlanBuilder};
+use sqlparser::ast::{
+    Expr as SQLExpr, ExprWithAlias, Ident, NullInclusion, PivotValueSource,
+};
+
+impl<S: ContextProvider> SqlToRel<'_, S> {
+    /// Plans `input PIVOT (aggregate_functions FOR value_column IN (value_source))`
+    ///
+    /// Dynamic value sources (`ANY` and subqueries) must be replaced with the
+    /// list of values before planning, as the planner can not run queries.
+    pub(crate) fn plan_pivot(
+        &self,
+        input: LogicalPlan,
+        aggregate_functions: Vec<ExprWithAlias>,
+        value_column: Vec<Ident>,
+        value_source: PivotValueSource,
+        default_on_null: Option<SQLExpr>,
+        planner_context: &mut PlannerContext,
+    ) -> Result<LogicalPlan> {
+        let schema = input.schema().as_ref().clone();
+        let aggr_expr = aggregate_functions
+            .into_iter()
+            .map(|ExprWithAlias { expr, alias }| {
+                let expr = self.sql_to_expr(expr, &schema, planner_context)?;
+                Ok(match alias {
+                    Some(alias) => expr.alias(self.ident_normalizer.normalize(alias)),
+                    None => expr,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let value_column = match <[Ident; 1]>::try_from(value_column) {
+            Ok([ident]) => SQLExpr::Identifier(ident),
+            Err(idents) => SQLExpr::CompoundIdentifier(idents),
+        };
+        let pivot_column =
+            match self.sql_to_expr(value_column, &schema, planner_context)? {
+                Expr::Column(column) => column,
+                expr => return plan_err!("PIVOT requires a column, got {expr}"),
+            };
+
+        let empty_schema = DFSchema::empty();
+        let pivot_values = match value_source {
+            PivotValueSource::List(values) => values
+                .into_iter()
+                .map(|ExprWithAlias { expr, alias }| {
+                    let expr = self.sql_to_expr(expr, &empty_schema, planner_context)?;
+                    Ok(match alias {
+                        Some(alias) => expr.alias(self.ident_normalizer.normalize(alias)),
+                        None => expr,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?,
+            PivotValueSource::Any(_) | PivotValueSource::Subquery(_) => {
+                return not_impl_err!(
+                    "PIVOT with a dynamic value list must be resolved before planning"
+                );
+            }
+        };
+        let default_on_null = default_on_null
+            .map(|expr| self.sql_to_expr(expr, &empty_schema, planner_context))
+            .transpose()?;
+
+        LogicalPlanBuilder::from(input)
+            .pivot(aggr_expr, pivot_column, pivot_values, default_on_null)?
+            .build()
+    }
+
+    /// Plans `input UNPIVOT [ { INCLUDE | EXCLUDE } NULLS ] (value FOR name IN (columns))`
+    pub(crate) fn plan_unpivot(
+        &self,
+        input: LogicalPlan,
+        value: Ident,
+        name: Ident,
+        columns: Vec<Ident>,
+        null_inclusion: Option<NullInclusion>,
+    ) -> Result<LogicalPlan> {
+        let columns = columns
+            .into_iter()
+            .map(|column| Column::from_name(self.ident_normalizer.normalize(column)))
+            .collect();
+        LogicalPlanBuilder::from(input)
+            .unpivot(
+                self.ident_normalizer.normalize(value),
+                self.ident_normalizer.normalize(name),
+                columns,
+                matches!(null_inclusion, Some(NullInclusion::IncludeNulls)),
+            )?
+            .build()
+    }
+}
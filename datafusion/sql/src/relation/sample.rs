@@ -0,0 +1,100 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::planner::{ContextProvider, PlannerContext, SqlToRel};
+use arrow::datatypes::DataType;
+use datafusion_common::{not_impl_err, plan_err, DFSchema, Result};
+use datafusion_expr::{Expr, LogicalPlan, LogicalPlanBuilder, SampleMethod};
+use sqlparser::ast::{
+    TableSample, TableSampleKind, TableSampleMethod, TableSampleQuantity,
+    TableSampleSeed, TableSampleUnit, Value,
+};
+
+impl<S: ContextProvider> SqlToRel<'_, S> {
+    /// Plans `input TABLESAMPLE method (percent) [REPEATABLE (seed)]`
+    ///
+    /// `BERNOULLI` and `ROW` sample individual rows, `SYSTEM` and `BLOCK`
+    /// sample blocks of rows, such as record batches or Parquet row groups.
+    pub(crate) fn plan_table_sample(
+        &self,
+        input: LogicalPlan,
+        sample: TableSampleKind,
+        planner_context: &mut PlannerContext,
+    ) -> Result<LogicalPlan> {
+        let (TableSampleKind::BeforeTableAlias(sample)
+        | TableSampleKind::AfterTableAlias(sample)) = sample;
+        let TableSample {
+            name,
+            quantity,
+            seed,
+            bucket,
+            offset,
+            ..
+        } = *sample;
+        if bucket.is_some() || offset.is_some() {
+            return not_impl_err!("TABLESAMPLE with BUCKET or OFFSET is not supported");
+        }
+
+        let method = match name {
+            None | Some(TableSampleMethod::Bernoulli | TableSampleMethod::Row) => {
+                SampleMethod::Bernoulli
+            }
+            Some(TableSampleMethod::System | TableSampleMethod::Block) => {
+                SampleMethod::System
+            }
+        };
+        let Some(TableSampleQuantity { value, unit, .. }) = quantity else {
+            return plan_err!("TABLESAMPLE requires a sample percentage");
+        };
+        if unit == Some(TableSampleUnit::Rows) {
+            return not_impl_err!("TABLESAMPLE with a number of rows is not supported");
+        }
+        let percent =
+            match self.sql_to_expr(value, &DFSchema::empty(), planner_context)? {
+                Expr::Literal(value, _) => value.cast_to(&DataType::Float64)?,
+                expr => {
+                    return plan_err!(
+                        "TABLESAMPLE percentage must be a literal, got {expr}"
+                    )
+                }
+            };
+        let fraction = match f64::try_from(percent.clone()) {
+            Ok(percent) if (0.0..=100.0).contains(&percent) => percent / 100.0,
+            _ => {
+                return plan_err!(
+                    "TABLESAMPLE percentage must be between 0 and 100, got {percent}"
+                )
+            }
+        };
+        let seed = seed
+            .map(|TableSampleSeed { value, .. }| match value {
+                Value::Number(n, _) => n.parse::<u64>().or_else(|_| {
+                    plan_err!("REPEATABLE seed must be a non-negative integer, got {n}")
+                }),
+                value => {
+                    plan_err!(
+                        "REPEATABLE seed must be a non-negative integer, got {value}"
+                    )
+                }
+            })
+            .transpose()?;
+
+        LogicalPlanBuilder::from(input)
+            .sample(method, fraction, seed)?
+            .build()
+    }
+}
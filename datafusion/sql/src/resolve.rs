@@ -209,6 +209,148 @@ pub fn resolve_table_references(
     Ok((table_refs, ctes))
 }
 
+/// Finds the first `PIVOT` with a dynamic value list, innermost first, and
+/// returns the query that computes its values
+#[derive(Default)]
+struct DynamicPivotVisitor {
+    ctes_in_scope: Vec<Cte>,
+    recursive: bool,
+}
+
+impl Visitor for DynamicPivotVisitor {
+    type Break = String;
+
+    fn pre_visit_query(&mut self, q: &Query) -> ControlFlow<String> {
+        if let Some(with) = &q.with {
+            self.recursive |= with.recursive;
+            self.ctes_in_scope.extend(with.cte_tables.iter().cloned());
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_query(&mut self, q: &Query) -> ControlFlow<String> {
+        if let Some(with) = &q.with {
+            let len = self.ctes_in_scope.len() - with.cte_tables.len();
+            self.ctes_in_scope.truncate(len);
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_table_factor(
+        &mut self,
+        table_factor: &TableFactor,
+    ) -> ControlFlow<String> {
+        let TableFactor::Pivot {
+            table,
+            value_column,
+            value_source,
+            ..
+        } = table_factor
+        else {
+            return ControlFlow::Continue(());
+        };
+        let query = match value_source {
+            PivotValueSource::List(_) => return ControlFlow::Continue(()),
+            PivotValueSource::Any(order_by) => {
+                let column = display_separated(value_column, ".");
+                let order_by = if order_by.is_empty() {
+                    column.to_string()
+                } else {
+                    display_comma_separated(order_by).to_string()
+                };
+                format!("SELECT DISTINCT {column} FROM {table} ORDER BY {order_by}")
+            }
+            PivotValueSource::Subquery(query) => query.to_string(),
+        };
+        if self.ctes_in_scope.is_empty() {
+            return ControlFlow::Break(query);
+        }
+        let recursive = if self.recursive { "RECURSIVE " } else { "" };
+        ControlFlow::Break(format!(
+            "WITH {recursive}{} {query}",
+            display_comma_separated(&self.ctes_in_scope)
+        ))
+    }
+}
+
+/// Replaces the value list of the first `PIVOT` found by [`DynamicPivotVisitor`]
+struct PivotValuesVisitor {
+    values: Vec<ExprWithAlias>,
+}
+
+impl VisitorMut for PivotValuesVisitor {
+    type Break = ();
+
+    fn post_visit_table_factor(
+        &mut self,
+        table_factor: &mut TableFactor,
+    ) -> ControlFlow<()> {
+        match table_factor {
+            TableFactor::Pivot {
+                value_source:
+                    value_source @ (PivotValueSource::Any(_) | PivotValueSource::Subquery(_)),
+                ..
+            } => {
+                *value_source = PivotValueSource::List(std::mem::take(&mut self.values));
+                ControlFlow::Break(())
+            }
+            _ => ControlFlow::Continue(()),
+        }
+    }
+}
+
+/// Returns the SQL query that computes the values of the first `PIVOT` in the
+/// statement with a dynamic value list (`IN (ANY [ORDER BY ...])` or
+/// `IN (<subquery>)`), or `None` if all `PIVOT`s have a static value list.
+///
+/// Inner `PIVOT`s are returned before the `PIVOT`s that contain them. The
+/// query returns a single column and must be run to replace the dynamic value
+/// list with [`resolve_pivot_values`] before the statement can be planned.
+///
+/// ## Example
+///
+/// ```
+/// # use datafusion_sql::parser::DFParser;
+/// # use datafusion_sql::resolve::dynamic_pivot_values_query;
+/// let query = "SELECT * FROM sales PIVOT (sum(amount) FOR month IN (ANY))";
+/// let statement = DFParser::parse_sql(query).unwrap().pop_back().unwrap();
+/// let values_query = dynamic_pivot_values_query(&statement).unwrap();
+/// assert_eq!(values_query, "SELECT DISTINCT month FROM sales ORDER BY month");
+/// ```
+pub fn dynamic_pivot_values_query(statement: &DFStatement) -> Option<String> {
+    let mut visitor = DynamicPivotVisitor::default();
+    match statement {
+        DFStatement::Statement(s) => s.visit(&mut visitor).break_value(),
+        DFStatement::CopyTo(CopyToStatement {
+            source: CopyToSource::Query(query),
+            ..
+        }) => query.visit(&mut visitor).break_value(),
+        DFStatement::Explain(explain) => dynamic_pivot_values_query(&explain.statement),
+        DFStatement::CopyTo(_) | DFStatement::CreateExternalTable(_) => None,
+    }
+}
+
+/// Replaces the dynamic value list of the `PIVOT` returned by
+/// [`dynamic_pivot_values_query`] with `values`.
+pub fn resolve_pivot_values(statement: &mut DFStatement, values: Vec<ExprWithAlias>) {
+    let mut visitor = PivotValuesVisitor { values };
+    match statement {
+        DFStatement::Statement(s) => {
+            let _ = s.visit(&mut visitor);
+        }
+        DFStatement::CopyTo(CopyToStatement {
+            source: CopyToSource::Query(query),
+            ..
+        }) => {
+            let _ = query.visit(&mut visitor);
+        }
+        DFStatement::Explain(explain) => {
+            resolve_pivot_values(&mut explain.statement, visitor.values)
+        }
+        DFStatement::CopyTo(_) | DFStatement::CreateExternalTable(_) => {}
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
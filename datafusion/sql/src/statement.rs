@@ -29,7 +29,7 @@ use crate::planner::{
 };
 use crate::utils::normalize_ident;
 
-use arrow::datatypes::{DataType, Fields};
+use arrow::datatypes::{DataType, Fields, Schema};
 use datafusion_common::error::_plan_err;
 use datafusion_common::parsers::CompressionTypeVariant;
 use datafusion_common::{
@@ -38,21 +38,25 @@ use datafusion_common::{
     DataFusionError, Result, ScalarValue, SchemaError, SchemaReference, TableReference,
     ToDFSchema,
 };
-use datafusion_expr::dml::{CopyTo, InsertOp};
+use datafusion_expr::dml::{
+    merge_cardinality_check, CopyTo, InsertOp, MergeRowAction, MERGE_ACTION_COLUMN,
+};
+use datafusion_expr::expr::{Case, WindowFunction};
 use datafusion_expr::expr_rewriter::normalize_col_with_schemas_and_ambiguity_check;
 use datafusion_expr::logical_plan::builder::project;
 use datafusion_expr::logical_plan::DdlStatement;
 use datafusion_expr::utils::expr_to_columns;
 use datafusion_expr::{
-    cast, col, Analyze, CreateCatalog, CreateCatalogSchema,
+    cast, col, lit, when, Analyze, AnalyzeTable, CreateCatalog, CreateCatalogSchema,
     CreateExternalTable as PlanCreateExternalTable, CreateFunction, CreateFunctionBody,
     CreateIndex as PlanCreateIndex, CreateMemoryTable, CreateView, Deallocate,
     DescribeTable, DmlStatement, DropCatalogSchema, DropFunction, DropTable, DropView,
-    EmptyRelation, Execute, Explain, ExplainFormat, Expr, ExprSchemable, Filter,
-    LogicalPlan, LogicalPlanBuilder, OperateFunctionArg, PlanType, Prepare, SetVariable,
-    SortExpr, Statement as PlanStatement, ToStringifiedPlan, TransactionAccessMode,
-    TransactionConclusion, TransactionEnd, TransactionIsolationLevel, TransactionStart,
-    Volatility, WriteOp,
+    EmptyRelation, Execute, Explain, ExplainFormat, Expr, ExprFunctionExt, ExprSchemable,
+    Filter, JoinType, LogicalPlan, LogicalPlanBuilder, OperateFunctionArg, PlanType,
+    Prepare, SetVariable, SortExpr, Statement as PlanStatement, TableSource,
+    ToStringifiedPlan, TransactionAccessMode, TransactionConclusion, TransactionEnd,
+    TransactionIsolationLevel, TransactionStart, Volatility, WindowFrame,
+    WindowFrameBound, WindowFrameUnits, WriteOp,
 };
 use sqlparser::ast::{
     self, BeginTransactionKind, IndexColumn, IndexType, NullsDistinctOption, OrderByExpr,
@@ -62,6 +66,7 @@ use sqlparser::ast::{
 use sqlparser::ast::{
     Assignment, AssignmentTarget, ColumnDef, CreateIndex, CreateTable,
     CreateTableOptions, Delete, DescribeAlias, Expr as SQLExpr, FromTable, Ident, Insert,
+    MergeAction, MergeClause, MergeClauseKind, MergeInsertExpr, MergeInsertKind,
     ObjectName, ObjectType, Query, SchemaName, SetExpr, ShowCreateObject,
     ShowStatementFilter, Statement, TableConstraint, TableFactor, TableWithJoins,
     TransactionMode, UnaryOperator, Value,
@@ -788,6 +793,29 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                     name: ident_to_string(&name),
                 },
             ))),
+            Statement::Analyze {
+                table_name,
+                partitions,
+                columns,
+                cache_metadata,
+                noscan,
+                ..
+            } => {
+                if partitions.is_some() || cache_metadata || noscan {
+                    return not_impl_err!(
+                        "ANALYZE with PARTITION, CACHE METADATA or NOSCAN is not supported"
+                    );
+                }
+                Ok(LogicalPlan::Statement(PlanStatement::AnalyzeTable(
+                    AnalyzeTable {
+                        table_name: self.object_name_to_table_reference(table_name)?,
+                        columns: columns
+                            .into_iter()
+                            .map(|ident| self.ident_normalizer.normalize(ident))
+                            .collect(),
+                    },
+                )))
+            }
 
             Statement::ShowTables {
                 extended,
@@ -1028,6 +1056,22 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 self.delete_to_plan(table_name, selection)
             }
 
+            Statement::Merge {
+                into,
+                table,
+                source,
+                on,
+                clauses,
+                output,
+            } => {
+                if output.is_some() {
+                    plan_err!("Merge-output clause not supported")?;
+                }
+                // optional keywords don't change behavior
+                let _ = into;
+                self.merge_to_plan(table, source, *on, clauses)
+            }
+
             Statement::StartTransaction {
                 modes,
                 begin: false,
@@ -1998,6 +2042,435 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         Ok(plan)
     }
 
+    /// Plans a `MERGE` statement as an outer join of the target table with the
+    /// source relation, that computes the new contents of the target table
+    /// along with the [`MergeRowAction`] applied to each row.
+    ///
+    /// ```text
+    /// Dml: op=[Merge]
+    ///   Projection: <new value of each target column>, <action> AS __merge_action
+    ///     Filter: __merge_clause IS NOT NULL
+    ///       Projection: <all columns>, <index of the applied clause> AS __merge_clause
+    ///         Left/Full Join: <ON condition>
+    ///           Projection: <target columns>, true AS __merge_target_row
+    ///             TableScan: <target>
+    ///           Projection: <source columns>, true AS __merge_source_row
+    ///             <source>
+    /// ```
+    ///
+    /// Every row of the target table is kept by the join. Unmatched source rows
+    /// are only kept if there is a `WHEN NOT MATCHED` clause, in which case the
+    /// filter discards the ones that are not inserted.
+    fn merge_to_plan(
+        &self,
+        table: TableFactor,
+        source: TableFactor,
+        on: SQLExpr,
+        clauses: Vec<MergeClause>,
+    ) -> Result<LogicalPlan> {
+        const TARGET_ROW: &str = "__merge_target_row";
+        const SOURCE_ROW: &str = "__merge_source_row";
+        const CLAUSE: &str = "__merge_clause";
+
+        let (table_name, table_alias) = match table {
+            TableFactor::Table { name, alias, .. } => (name, alias),
+            _ => plan_err!("Cannot merge into non-table relation!")?,
+        };
+
+        // Do a table lookup to verify the table exists
+        let table_name = self.object_name_to_table_reference(table_name)?;
+        let table_source = self.context_provider.get_table_source(table_name.clone())?;
+        let table_schema = table_source.schema();
+
+        let mut planner_context = PlannerContext::new();
+        let mut target = LogicalPlanBuilder::scan(
+            table_name.clone(),
+            Arc::clone(&table_source),
+            None,
+        )?
+        .build()?;
+        if let Some(alias) = table_alias {
+            target = self.apply_table_alias(target, alias)?;
+        }
+        let source = self.plan_table_with_joins(
+            TableWithJoins {
+                relation: source,
+                joins: vec![],
+            },
+            &mut planner_context,
+        )?;
+
+        // Mark the rows of both sides, so that matched and unmatched rows can be
+        // told apart after the join
+        let mark_rows = |plan: LogicalPlan, name: &str| {
+            let exprs = plan
+                .schema()
+                .columns()
+                .into_iter()
+                .map(Expr::Column)
+                .chain(std::iter::once(lit(true).alias(name)));
+            project(plan, exprs)
+        };
+        let target_columns = target.schema().columns();
+        let source_columns = source.schema().columns();
+        let source = mark_rows(source, SOURCE_ROW)?;
+
+        // The target rows are numbered, so that the rows of the join matching
+        // the same target row can be grouped together
+        let row_number = self
+            .context_provider
+            .get_window_meta("row_number")
+            .ok_or_else(|| plan_datafusion_err!("MERGE requires row_number"))?;
+        let count = self
+            .context_provider
+            .get_aggregate_meta("count")
+            .ok_or_else(|| plan_datafusion_err!("MERGE requires count"))?;
+        let target_row_number =
+            Expr::from(WindowFunction::new(Arc::clone(&row_number), vec![]));
+        let target_row_number_column = Expr::Column(Column::from_name(
+            target_row_number.schema_name().to_string(),
+        ));
+        let target = LogicalPlanBuilder::window_plan(target, vec![target_row_number])?;
+        let target = project(
+            target,
+            target_columns
+                .iter()
+                .cloned()
+                .map(Expr::Column)
+                .chain(std::iter::once(target_row_number_column.alias(TARGET_ROW))),
+        )?;
+
+        let inserts = clauses.iter().any(|clause| {
+            matches!(
+                clause.clause_kind,
+                MergeClauseKind::NotMatched | MergeClauseKind::NotMatchedByTarget
+            )
+        });
+        let join_type = if inserts {
+            JoinType::Full
+        } else {
+            JoinType::Left
+        };
+        let join_schema = target.schema().join(source.schema())?;
+        let on = self.sql_to_expr(on, &join_schema, &mut planner_context)?;
+        let join = LogicalPlanBuilder::from(target)
+            .join_on(source, join_type, Some(on))?
+            .build()?;
+        let join_schema = Arc::clone(join.schema());
+
+        let target_row = col(TARGET_ROW);
+        let source_row = col(SOURCE_ROW);
+
+        // The clause applied to each row of the join, and the new values that
+        // it assigns to the columns of the target table
+        let mut clause_when_then = vec![];
+        let mut actions = vec![];
+        let mut values: Vec<HashMap<usize, Expr>> = vec![];
+        for (index, clause) in clauses.into_iter().enumerate() {
+            let MergeClause {
+                clause_kind,
+                predicate,
+                action,
+            } = clause;
+            let mut condition = match clause_kind {
+                MergeClauseKind::Matched => target_row
+                    .clone()
+                    .is_not_null()
+                    .and(source_row.clone().is_not_null()),
+                MergeClauseKind::NotMatched | MergeClauseKind::NotMatchedByTarget => {
+                    target_row.clone().is_null()
+                }
+                MergeClauseKind::NotMatchedBySource => source_row.clone().is_null(),
+            };
+            if let Some(predicate) = predicate {
+                let predicate =
+                    self.sql_to_expr(predicate, &join_schema, &mut planner_context)?;
+                condition = condition.and(predicate);
+            }
+            clause_when_then.push((Box::new(condition), Box::new(lit(index as u32))));
+
+            let mut clause_values = HashMap::new();
+            let row_action = match (clause_kind, action) {
+                (
+                    MergeClauseKind::Matched | MergeClauseKind::NotMatchedBySource,
+                    MergeAction::Update { assignments },
+                ) => {
+                    for assignment in assignments {
+                        let (column_index, expr) = self.merge_assignment(
+                            assignment,
+                            &table_schema,
+                            &join_schema,
+                            &mut planner_context,
+                        )?;
+                        if clause_values.insert(column_index, expr).is_some() {
+                            plan_err!(
+                                "Multiple assignments to column {} in MERGE clause",
+                                table_schema.field(column_index).name()
+                            )?;
+                        }
+                    }
+                    MergeRowAction::Update
+                }
+                (
+                    MergeClauseKind::Matched | MergeClauseKind::NotMatchedBySource,
+                    MergeAction::Delete,
+                ) => MergeRowAction::Delete,
+                (
+                    MergeClauseKind::NotMatched | MergeClauseKind::NotMatchedByTarget,
+                    MergeAction::Insert(insert),
+                ) => {
+                    clause_values = self.merge_insert_values(
+                        insert,
+                        &table_schema,
+                        &table_source,
+                        &source_columns,
+                        &join_schema,
+                        &mut planner_context,
+                    )?;
+                    MergeRowAction::Insert
+                }
+                (clause_kind, action) => {
+                    let action = match action {
+                        MergeAction::Insert(_) => "INSERT",
+                        MergeAction::Update { .. } => "UPDATE",
+                        MergeAction::Delete => "DELETE",
+                    };
+                    return plan_err!(
+                        "{action} is not allowed in a WHEN {clause_kind} clause of MERGE"
+                    );
+                }
+            };
+            actions.push(row_action);
+            values.push(clause_values);
+        }
+        // The rows of the target table that no clause applies to are kept as they are
+        let keep_index = actions.len() as u32;
+        clause_when_then.push((
+            Box::new(target_row.clone().is_not_null()),
+            Box::new(lit(keep_index)),
+        ));
+        actions.push(MergeRowAction::Keep);
+        values.push(HashMap::new());
+
+        let clause_expr = Expr::Case(Case::new(None, clause_when_then, None));
+        let mut builder = LogicalPlanBuilder::from(join).project(
+            join_schema
+                .columns()
+                .into_iter()
+                .map(Expr::Column)
+                .chain(std::iter::once(clause_expr.alias(CLAUSE))),
+        )?;
+        if join_type == JoinType::Full {
+            // Discard the unmatched source rows that are not inserted
+            builder = builder.filter(col(CLAUSE).is_not_null())?;
+        }
+
+        // A target row matched by several source rows is returned once per
+        // match. Keep a single one, preferring a row that a clause applies to,
+        // and fail if clauses apply to several of them.
+        // Both functions share the same ordering, so that they are computed
+        // by a single window operator
+        let match_order = vec![col(CLAUSE).sort(true, true)];
+        let applied_clauses = Expr::from(WindowFunction::new(
+            count,
+            vec![when(col(CLAUSE).not_eq(lit(keep_index)), lit(1)).end()?],
+        ))
+        .partition_by(vec![target_row.clone()])
+        .order_by(match_order.clone())
+        .window_frame(WindowFrame::new_bounds(
+            WindowFrameUnits::Rows,
+            WindowFrameBound::Preceding(ScalarValue::UInt64(None)),
+            WindowFrameBound::Following(ScalarValue::UInt64(None)),
+        ))
+        .build()?;
+        let match_number = Expr::from(WindowFunction::new(row_number, vec![]))
+            .partition_by(vec![target_row.clone()])
+            .order_by(match_order)
+            .build()?;
+        let column_of =
+            |expr: &Expr| Expr::Column(Column::from_name(expr.schema_name().to_string()));
+        let cardinality_check = merge_cardinality_check().call(vec![when(
+            target_row.clone().is_null(),
+            lit(0i64),
+        )
+        .otherwise(column_of(&applied_clauses))?]);
+        let first_match = target_row
+            .is_null()
+            .or(column_of(&match_number).eq(lit(1u64)));
+        let plan = LogicalPlanBuilder::window_plan(
+            builder.build()?,
+            vec![applied_clauses, match_number],
+        )?;
+        let plan = LogicalPlanBuilder::from(plan)
+            .filter(cardinality_check.and(first_match))?
+            .build()?;
+
+        let mut exprs = table_schema
+            .fields()
+            .iter()
+            .zip(target_columns)
+            .enumerate()
+            .map(|(column_index, (field, column))| {
+                let when_then = values
+                    .iter_mut()
+                    .enumerate()
+                    .filter_map(|(index, clause_values)| {
+                        let value = clause_values.remove(&column_index)?;
+                        Some((Box::new(lit(index as u32)), Box::new(value)))
+                    })
+                    .collect::<Vec<_>>();
+                let expr = if when_then.is_empty() {
+                    Expr::Column(column)
+                } else {
+                    Expr::Case(Case::new(
+                        Some(Box::new(col(CLAUSE))),
+                        when_then,
+                        Some(Box::new(Expr::Column(column))),
+                    ))
+                };
+                expr.alias(field.name())
+            })
+            .collect::<Vec<_>>();
+        let action_when_then = actions
+            .iter()
+            .enumerate()
+            .map(|(index, action)| {
+                (Box::new(lit(index as u32)), Box::new(lit(action.as_u8())))
+            })
+            .collect();
+        exprs.push(
+            Expr::Case(Case::new(
+                Some(Box::new(col(CLAUSE))),
+                action_when_then,
+                None,
+            ))
+            .alias(MERGE_ACTION_COLUMN),
+        );
+        let input = project(plan, exprs)?;
+
+        Ok(LogicalPlan::Dml(DmlStatement::new(
+            table_name,
+            table_source,
+            WriteOp::Merge,
+            Arc::new(input),
+        )))
+    }
+
+    /// Returns the index of the target column of a `SET` assignment in a
+    /// `MERGE` statement, and the value assigned to it
+    fn merge_assignment(
+        &self,
+        assignment: Assignment,
+        table_schema: &Schema,
+        join_schema: &DFSchema,
+        planner_context: &mut PlannerContext,
+    ) -> Result<(usize, Expr)> {
+        let cols = match &assignment.target {
+            AssignmentTarget::ColumnName(cols) => cols,
+            _ => plan_err!("Tuples are not supported")?,
+        };
+        let col_name = cols
+            .0
+            .iter()
+            .last()
+            .and_then(|part| part.as_ident())
+            .ok_or_else(|| plan_datafusion_err!("Empty column id"))?;
+        let col_name = self.ident_normalizer.normalize(col_name.clone());
+        let column_index = table_schema.index_of(&col_name)?;
+        let field = table_schema.field(column_index);
+
+        let mut expr =
+            self.sql_to_expr(assignment.value, join_schema, planner_context)?;
+        // Update placeholder's datatype to the type of the target column
+        if let Expr::Placeholder(placeholder) = &mut expr {
+            placeholder.data_type = placeholder
+                .data_type
+                .take()
+                .or_else(|| Some(field.data_type().clone()));
+        }
+        Ok((column_index, expr.cast_to(field.data_type(), join_schema)?))
+    }
+
+    /// Returns the values inserted into each column of the target table by
+    /// the `INSERT` action of a `WHEN NOT MATCHED` clause of a `MERGE` statement
+    fn merge_insert_values(
+        &self,
+        insert: MergeInsertExpr,
+        table_schema: &Schema,
+        table_source: &Arc<dyn TableSource>,
+        source_columns: &[Column],
+        join_schema: &DFSchema,
+        planner_context: &mut PlannerContext,
+    ) -> Result<HashMap<usize, Expr>> {
+        let column_indices = if insert.columns.is_empty() {
+            (0..table_schema.fields().len()).collect::<Vec<_>>()
+        } else {
+            insert
+                .columns
+                .into_iter()
+                .map(|ident| {
+                    let name = self.ident_normalizer.normalize(ident);
+                    Ok(table_schema.index_of(&name)?)
+                })
+                .collect::<Result<Vec<_>>>()?
+        };
+
+        let row = match insert.kind {
+            MergeInsertKind::Values(ast::Values { mut rows, .. }) => {
+                if rows.len() != 1 {
+                    return plan_err!(
+                        "MERGE INSERT must specify exactly one row of values"
+                    );
+                }
+                rows.swap_remove(0)
+                    .into_iter()
+                    .map(|value| self.sql_to_expr(value, join_schema, planner_context))
+                    .collect::<Result<Vec<_>>>()?
+            }
+            // Inserts the columns of the source, in order
+            MergeInsertKind::Row => source_columns
+                .iter()
+                .map(|column| Expr::Column(column.clone()))
+                .collect(),
+        };
+        if row.len() != column_indices.len() {
+            return plan_err!("Column count doesn't match insert query!");
+        }
+
+        let mut values = HashMap::new();
+        for (column_index, mut expr) in column_indices.into_iter().zip(row) {
+            let field = table_schema.field(column_index);
+            if let Expr::Placeholder(placeholder) = &mut expr {
+                placeholder.data_type = placeholder
+                    .data_type
+                    .take()
+                    .or_else(|| Some(field.data_type().clone()));
+            }
+            let expr = expr.cast_to(field.data_type(), join_schema)?;
+            if values.insert(column_index, expr).is_some() {
+                return schema_err!(SchemaError::DuplicateUnqualifiedField {
+                    name: field.name().clone(),
+                });
+            }
+        }
+        // The value is not specified. Fill in the default value for the column.
+        for (column_index, field) in table_schema.fields().iter().enumerate() {
+            if values.contains_key(&column_index) {
+                continue;
+            }
+            let default = table_source
+                .get_column_default(field.name())
+                .cloned()
+                .unwrap_or_else(|| {
+                    // If there is no default for the column, then the default is NULL
+                    Expr::Literal(ScalarValue::Null, None)
+                })
+                .cast_to(field.data_type(), &DFSchema::empty())?;
+            values.insert(column_index, default);
+        }
+        Ok(values)
+    }
+
     fn insert_to_plan(
         &self,
         table_name: ObjectName,
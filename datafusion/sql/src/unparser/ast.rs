@@ -452,6 +452,12 @@ impl RelationBuilder {
         }
         new
     }
+    pub fn sample(&mut self, value: Option<ast::TableSampleKind>) -> &mut Self {
+        if let Some(TableFactorBuilder::Table(ref mut rel_builder)) = self.relation {
+            rel_builder.sample(value);
+        }
+        self
+    }
     pub fn build(&self) -> Result<Option<ast::TableFactor>, BuilderError> {
         Ok(match self.relation {
             Some(TableFactorBuilder::Table(ref value)) => Some(value.build()?),
@@ -482,6 +488,7 @@ pub struct TableRelationBuilder {
     version: Option<ast::TableVersion>,
     partitions: Vec<ast::Ident>,
     index_hints: Vec<ast::TableIndexHints>,
+    sample: Option<ast::TableSampleKind>,
 }
 
 #[allow(dead_code)]
@@ -514,6 +521,10 @@ impl TableRelationBuilder {
         self.index_hints = value;
         self
     }
+    pub fn sample(&mut self, value: Option<ast::TableSampleKind>) -> &mut Self {
+        self.sample = value;
+        self
+    }
     pub fn build(&self) -> Result<ast::TableFactor, BuilderError> {
         Ok(ast::TableFactor::Table {
             name: match self.name {
@@ -530,7 +541,7 @@ impl TableRelationBuilder {
             partitions: self.partitions.clone(),
             with_ordinality: false,
             json_path: None,
-            sample: None,
+            sample: self.sample.clone(),
             index_hints: self.index_hints.clone(),
         })
     }
@@ -543,6 +554,7 @@ impl TableRelationBuilder {
             version: Default::default(),
             partitions: Default::default(),
             index_hints: Default::default(),
+            sample: Default::default(),
         }
     }
 }
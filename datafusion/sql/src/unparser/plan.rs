@@ -46,8 +46,8 @@ use datafusion_common::{
 use datafusion_expr::expr::OUTER_REFERENCE_COLUMN_PREFIX;
 use datafusion_expr::{
     expr::Alias, BinaryExpr, Distinct, Expr, JoinConstraint, JoinType, LogicalPlan,
-    LogicalPlanBuilder, Operator, Projection, SortExpr, TableScan, Unnest,
-    UserDefinedLogicalNode,
+    LogicalPlanBuilder, Operator, Projection, Sample, SampleMethod, SortExpr, TableScan,
+    Unnest, UserDefinedLogicalNode,
 };
 use sqlparser::ast::{self, Ident, OrderByKind, SetExpr, TableAliasColumnDef};
 use std::{sync::Arc, vec};
@@ -111,6 +111,7 @@ impl Unparser<'_> {
             | LogicalPlan::Subquery(_)
             | LogicalPlan::SubqueryAlias(_)
             | LogicalPlan::Limit(_)
+            | LogicalPlan::Sample(_)
             | LogicalPlan::Statement(_)
             | LogicalPlan::Values(_)
             | LogicalPlan::Distinct(_) => self.select_to_sql_statement(&plan),
@@ -124,6 +125,7 @@ impl Unparser<'_> {
             | LogicalPlan::Copy(_)
             | LogicalPlan::DescribeTable(_)
             | LogicalPlan::RecursiveQuery(_)
+            | LogicalPlan::MatchRecognize(_)
             | LogicalPlan::Unnest(_) => not_impl_err!("Unsupported plan: {plan:?}"),
         }
     }
@@ -323,6 +325,35 @@ impl Unparser<'_> {
         }
     }
 
+    fn sample_to_sql(&self, sample: &Sample) -> ast::TableSample {
+        let method = match sample.method {
+            SampleMethod::Bernoulli => ast::TableSampleMethod::Bernoulli,
+            SampleMethod::System => ast::TableSampleMethod::System,
+        };
+        // Use the shortest percentage that is planned back into the same
+        // fraction, e.g. `10` rather than `10.000000000000002` for 0.1
+        let percent = sample.fraction * 100.0;
+        let percent = (0..17)
+            .map(|precision| format!("{percent:.precision$}"))
+            .find(|s| s.parse::<f64>().ok().map(|p| p / 100.0) == Some(sample.fraction))
+            .unwrap_or_else(|| percent.to_string());
+        ast::TableSample {
+            modifier: ast::TableSampleModifier::TableSample,
+            name: Some(method),
+            quantity: Some(ast::TableSampleQuantity {
+                parenthesized: true,
+                value: ast::Expr::value(ast::Value::Number(percent, false)),
+                unit: None,
+            }),
+            seed: sample.seed.map(|seed| ast::TableSampleSeed {
+                modifier: ast::TableSampleSeedModifier::Repeatable,
+                value: ast::Value::Number(seed.to_string(), false),
+            }),
+            bucket: None,
+            offset: None,
+        }
+    }
+
     #[cfg_attr(feature = "recursive_protection", recursive::recursive)]
     fn select_to_sql_recursively(
         &self,
@@ -663,8 +694,14 @@ impl Unparser<'_> {
                     &mut right_relation,
                 )?;
 
+                // The filter of an AsOf join is its match condition, which is
+                // unparsed separately from the join constraint
+                let join_filter = match join.join_type {
+                    JoinType::AsOf => None,
+                    _ => join.filter.clone(),
+                };
                 let join_filters = if table_scan_filters.is_empty() {
-                    join.filter.clone()
+                    join_filter
                 } else {
                     // Combine `table_scan_filters` into a single filter using `AND`
                     let Some(combined_filters) =
@@ -680,7 +717,7 @@ impl Unparser<'_> {
                     };
 
                     // Combine `join.filter` with `combined_filters` using `AND`
-                    match &join.filter {
+                    match &join_filter {
                         Some(filter) => Some(Expr::BinaryExpr(BinaryExpr {
                             left: Box::new(filter.clone()),
                             op: Operator::And,
@@ -769,15 +806,30 @@ impl Unparser<'_> {
                     JoinType::Inner
                     | JoinType::Left
                     | JoinType::Right
-                    | JoinType::Full => {
+                    | JoinType::Full
+                    | JoinType::AsOf => {
                         let Ok(Some(relation)) = right_relation.build() else {
                             return internal_err!("Failed to build right relation");
                         };
+                        let join_operator = match (join.join_type, &join.filter) {
+                            (JoinType::AsOf, Some(match_condition)) => {
+                                ast::JoinOperator::AsOf {
+                                    match_condition: self.expr_to_sql(match_condition)?,
+                                    constraint: join_constraint,
+                                }
+                            }
+                            (JoinType::AsOf, None) => {
+                                return internal_err!(
+                                    "AsOf join is missing its match condition"
+                                );
+                            }
+                            _ => self
+                                .join_operator_to_sql(join.join_type, join_constraint)?,
+                        };
                         let ast_join = ast::Join {
                             relation,
                             global: false,
-                            join_operator: self
-                                .join_operator_to_sql(join.join_type, join_constraint)?,
+                            join_operator,
                         };
                         let mut from = select.pop_from().unwrap();
                         from.push_join(ast_join);
@@ -802,6 +854,21 @@ impl Unparser<'_> {
 
                 Ok(())
             }
+            LogicalPlan::Sample(sample) => {
+                // TABLESAMPLE can only be attached to a table in SQL
+                if !matches!(sample.input.as_ref(), LogicalPlan::TableScan(_)) {
+                    return not_impl_err!(
+                        "Unsupported sample input: {}",
+                        sample.input.display()
+                    );
+                }
+                self.select_to_sql_recursively(&sample.input, query, select, relation)?;
+                relation.sample(Some(ast::TableSampleKind::AfterTableAlias(Box::new(
+                    self.sample_to_sql(sample),
+                ))));
+
+                Ok(())
+            }
             LogicalPlan::SubqueryAlias(plan_alias) => {
                 let (plan, mut columns) =
                     subquery_alias_inner_query_and_columns(plan_alias);
@@ -1264,6 +1331,11 @@ impl Unparser<'_> {
             JoinType::LeftMark | JoinType::RightMark => {
                 unimplemented!("Unparsing of Mark join type")
             }
+            JoinType::AsOf => {
+                return internal_err!(
+                    "AsOf joins are unparsed with their match condition"
+                )
+            }
         })
     }
 
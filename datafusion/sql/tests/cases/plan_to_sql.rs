@@ -119,9 +119,13 @@ fn roundtrip_statement() -> Result<()> {
             "select ta.j1_id from j1 ta order by ta.j1_id;",
             "select * from j1 ta order by ta.j1_id, ta.j1_string desc;",
             "select * from j1 limit 10;",
+            "select * from j1 tablesample bernoulli (10);",
+            "select ta.j1_id from j1 ta tablesample system (2.5) repeatable (42) where ta.j1_id > 1;",
             "select ta.j1_id from j1 ta where ta.j1_id > 1;",
             "select ta.j1_id, tb.j2_string from j1 ta join j2 tb on (ta.j1_id = tb.j2_id);",
             "select ta.j1_id, tb.j2_string, tc.j3_string from j1 ta join j2 tb on (ta.j1_id = tb.j2_id) join j3 tc on (ta.j1_id = tc.j3_id);",
+            "select ta.j1_id, tb.j2_string from j1 ta asof join j2 tb match_condition (ta.j1_id >= tb.j2_id);",
+            "select ta.j1_id, tb.j2_string from j1 ta asof join j2 tb match_condition (ta.j1_id <= tb.j2_id) on ta.j1_string = tb.j2_string;",
             "select * from (select id, first_name from person)",
             "select * from (select id, first_name from (select * from person))",
             "select id, count(*) as cnt from (select id from person) group by id",
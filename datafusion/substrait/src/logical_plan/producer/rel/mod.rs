@@ -58,6 +58,10 @@ pub fn to_substrait_rel(
         LogicalPlan::Subquery(plan) => not_impl_err!("Unsupported plan type: {plan:?}")?,
         LogicalPlan::SubqueryAlias(plan) => producer.handle_subquery_alias(plan),
         LogicalPlan::Limit(plan) => producer.handle_limit(plan),
+        LogicalPlan::Sample(plan) => not_impl_err!("Unsupported plan type: {plan:?}")?,
+        LogicalPlan::MatchRecognize(plan) => {
+            not_impl_err!("Unsupported plan type: {plan:?}")?
+        }
         LogicalPlan::Statement(plan) => not_impl_err!("Unsupported plan type: {plan:?}")?,
         LogicalPlan::Values(plan) => producer.handle_values(plan),
         LogicalPlan::Explain(plan) => not_impl_err!("Unsupported plan type: {plan:?}")?,